@@ -0,0 +1,94 @@
+//! Benchmarks for prefix lookups in the installed indexes.
+//!
+//! These require the indexes to be installed, like through `jpv build`, and
+//! are skipped otherwise.
+
+use anyhow::Result;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use jpv_lib::config::Config;
+use jpv_lib::data;
+use jpv_lib::database::Database;
+use jpv_lib::Dirs;
+
+/// Inputs of increasing length.
+const INPUTS: &[(&str, &str)] = &[
+    ("short", "日本語"),
+    (
+        "sentence",
+        "私は毎朝早く起きて、公園を散歩してから仕事に行きます。",
+    ),
+    (
+        "paragraph",
+        "吾輩は猫である。名前はまだ無い。どこで生れたかとんと見当がつかぬ。\
+         何でも薄暗いじめじめした所でニャーニャー泣いていた事だけは記憶している。\
+         吾輩はここで始めて人間というものを見た。しかもあとで聞くとそれは書生という\
+         人間中で一番獰悪な種族であったそうだ。",
+    ),
+];
+
+fn open() -> Result<Option<Database>> {
+    let dirs = Dirs::open()?;
+    let config = Config::load(&dirs)?;
+    let indexes = data::open_from_args(&[], &dirs)?;
+
+    if indexes.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(Database::open(indexes, &config)?))
+}
+
+/// Probe every prefix of the input from the longest, which is how analysis
+/// used to look up candidates.
+fn exhaustive(db: &Database, q: &str) -> Result<usize> {
+    let mut count = 0;
+    let mut it = q.chars();
+
+    while !it.as_str().is_empty() {
+        count += db.lookup(it.as_str())?.len();
+        it.next_back();
+    }
+
+    Ok(count)
+}
+
+fn lookup(c: &mut Criterion) {
+    let db = match open() {
+        Ok(Some(db)) => db,
+        Ok(None) => {
+            eprintln!("No indexes are installed, skipping benchmarks");
+            return;
+        }
+        Err(error) => {
+            eprintln!("Failed to open database, skipping benchmarks: {error}");
+            return;
+        }
+    };
+
+    let mut group = c.benchmark_group("analyze");
+
+    for &(name, q) in INPUTS {
+        group.bench_with_input(BenchmarkId::new("prefixes", name), q, |b, q| {
+            b.iter(|| db.analyze(black_box(q), 0).unwrap())
+        });
+
+        group.bench_with_input(BenchmarkId::new("exhaustive", name), q, |b, q| {
+            b.iter(|| exhaustive(&db, black_box(q)).unwrap())
+        });
+    }
+
+    group.finish();
+
+    let mut group = c.benchmark_group("phrase_spans");
+
+    for &(name, q) in INPUTS {
+        group.bench_with_input(BenchmarkId::from_parameter(name), q, |b, q| {
+            b.iter(|| db.phrase_spans(black_box(q)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, lookup);
+criterion_main!(benches);
@@ -0,0 +1,180 @@
+//! Notes added to Anki through AnkiConnect.
+//!
+//! What goes into the fields of a note is decided by the [`Anki`]
+//! configuration, which maps each part of a card to a field of the note type.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::config::{Anki, AnkiPart};
+use crate::export::Escape;
+use crate::mining::{self, Card, NewCard};
+
+/// What a note is made from, either a mined card or a dictionary entry.
+#[derive(Debug, Clone, Copy)]
+pub struct Content<'a> {
+    pub word: &'a str,
+    pub reading: Option<&'a str>,
+    pub glosses: &'a [String],
+    /// The sentence the word was mined from, which is empty for entries.
+    pub sentence: &'a str,
+}
+
+impl<'a> Content<'a> {
+    /// The content of a mined card.
+    pub fn card(card: &'a Card) -> Self {
+        Self {
+            word: &card.word,
+            reading: card.reading.as_deref(),
+            glosses: &card.glosses,
+            sentence: &card.sentence,
+        }
+    }
+
+    /// The content of a card which hasn't been mined, like one assembled for
+    /// a dictionary entry.
+    pub fn new_card(card: &'a NewCard) -> Self {
+        Self {
+            word: &card.word,
+            reading: card.reading.as_deref(),
+            glosses: &card.glosses,
+            sentence: &card.sentence,
+        }
+    }
+
+    /// A part of the content as HTML. Images are attached separately, so
+    /// they have no content of their own.
+    fn part(&self, part: AnkiPart) -> Option<String> {
+        let mut html = String::new();
+
+        match part {
+            AnkiPart::Word => {
+                _ = write!(html, "{}", Escape(self.word));
+            }
+            AnkiPart::Reading => {
+                _ = write!(html, "{}", Escape(self.reading?));
+            }
+            AnkiPart::Meaning => {
+                if self.glosses.is_empty() {
+                    return None;
+                }
+
+                html.push_str("<ol>");
+
+                for gloss in self.glosses {
+                    _ = write!(html, "<li>{}</li>", Escape(gloss));
+                }
+
+                html.push_str("</ol>");
+            }
+            AnkiPart::Sentence => {
+                if self.sentence.is_empty() {
+                    return None;
+                }
+
+                let sentence = self.sentence;
+
+                match mining::highlight(sentence, self.word, self.reading) {
+                    Some(range) => {
+                        _ = write!(
+                            html,
+                            "{}<b>{}</b>{}",
+                            Escape(&sentence[..range.start]),
+                            Escape(&sentence[range.clone()]),
+                            Escape(&sentence[range.end..])
+                        );
+                    }
+                    None => {
+                        _ = write!(html, "{}", Escape(sentence));
+                    }
+                }
+            }
+            AnkiPart::Image => return None,
+        }
+
+        Some(html)
+    }
+}
+
+/// The fields of a note.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Note {
+    /// The contents of each field.
+    pub fields: BTreeMap<String, String>,
+    /// The field an image is attached to, if any.
+    pub image: Option<String>,
+}
+
+/// Build the fields of a note from the given content.
+pub fn note(config: &Anki, content: &Content<'_>) -> Note {
+    let mut note = Note::default();
+
+    for part in AnkiPart::ALL {
+        let Some(field) = config.field(part) else {
+            continue;
+        };
+
+        if part == AnkiPart::Image {
+            note.image = Some(field.to_owned());
+            continue;
+        }
+
+        let Some(html) = content.part(part) else {
+            continue;
+        };
+
+        let value = note.fields.entry(field.to_owned()).or_default();
+
+        if !value.is_empty() {
+            value.push_str("<br>");
+        }
+
+        value.push_str(&html);
+    }
+
+    note
+}
+
+/// A search for notes in the configured deck which are for the same word as
+/// the given content.
+///
+/// The field the word is written to is compared exactly if the word is the
+/// only thing written to it, otherwise notes where it contains the word are
+/// found. Returns `None` if the word isn't written to any field, in which
+/// case duplicates can't be detected.
+pub fn duplicates_query(config: &Anki, content: &Content<'_>) -> Option<String> {
+    let field = config.field(AnkiPart::Word)?;
+
+    let shared = AnkiPart::ALL
+        .into_iter()
+        .filter(|&part| part != AnkiPart::Word)
+        .any(|part| config.field(part) == Some(field));
+
+    let word = quote(&Escape(content.word).to_string());
+
+    let value = if shared { format!("*{word}*") } else { word };
+
+    Some(format!(
+        "\"deck:{}\" \"{}:{value}\"",
+        quote(&config.deck),
+        quote(field)
+    ))
+}
+
+/// Quote text for use in a search, so that it's matched literally.
+fn quote(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if matches!(c, '\\' | '"' | '*' | '_' | ':') {
+            output.push('\\');
+        }
+
+        output.push(c);
+    }
+
+    output
+}
@@ -0,0 +1,68 @@
+use crate::config::{Anki, AnkiPart};
+
+use super::{duplicates_query, note, Content};
+
+fn content<'a>(sentence: &'a str, glosses: &'a [String]) -> Content<'a> {
+    Content {
+        word: "食べる",
+        reading: Some("たべる"),
+        glosses,
+        sentence,
+    }
+}
+
+#[test]
+fn default_note() {
+    let glosses = ["to eat".to_owned(), "<to live on> & more".to_owned()];
+    let note = note(&Anki::default(), &content("ご飯を食べる<時>", &glosses));
+
+    assert_eq!(note.fields.len(), 2);
+    assert_eq!(note.fields["Front"], "食べる");
+    assert_eq!(
+        note.fields["Back"],
+        "たべる<br><ol><li>to eat</li><li>&lt;to live on&gt; &amp; more</li></ol><br>ご飯を<b>食べる</b>&lt;時&gt;"
+    );
+    assert_eq!(note.image.as_deref(), Some("Back"));
+}
+
+#[test]
+fn mapped_note() {
+    let mut config = Anki::default();
+    config.fields.clear();
+    config
+        .fields
+        .insert(AnkiPart::Sentence, "Sentence".to_owned());
+    config.fields.insert(AnkiPart::Word, "Word".to_owned());
+    config.fields.insert(AnkiPart::Reading, " ".to_owned());
+
+    let note = note(&config, &content("ご飯をたべる", &[]));
+    assert_eq!(note.fields.len(), 2);
+    assert_eq!(note.fields["Word"], "食べる");
+    assert_eq!(note.fields["Sentence"], "ご飯を<b>たべる</b>");
+    assert_eq!(note.image, None);
+
+    // Entries have no sentence to write.
+    let note = super::note(&config, &content("", &[]));
+    assert_eq!(note.fields.len(), 1);
+}
+
+#[test]
+fn duplicates() {
+    let mut config = Anki::default();
+
+    assert_eq!(
+        duplicates_query(&config, &content("", &[])).as_deref(),
+        Some(r#""deck:Default" "Front:食べる""#)
+    );
+
+    config.deck = "日本語::Vocab_1".to_owned();
+    config.fields.insert(AnkiPart::Reading, "Front".to_owned());
+
+    assert_eq!(
+        duplicates_query(&config, &content("", &[])).as_deref(),
+        Some(r#""deck:日本語\:\:Vocab\_1" "Front:*食べる*""#)
+    );
+
+    config.fields.remove(&AnkiPart::Word);
+    assert_eq!(duplicates_query(&config, &content("", &[])), None);
+}
@@ -1,130 +1,1216 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::ops::Range;
 
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
-use crate::config::Config;
-use crate::database::EntryResultKey;
+use crate::config::{CaptureFilter, Config, IndexFormat};
+use crate::database::{BuildError, EntryResultKey, NameWeighting, SearchError};
+use crate::export::pdf;
+use crate::graph::Graph;
+use crate::ids;
 use crate::jmdict;
 use crate::jmnedict;
 use crate::kanjidic2;
-use crate::Weight;
+use crate::mining::Card;
+use crate::profile::{self, Annotation, Density, HistoryEntry, Readings, WordStatus};
+use crate::reader::epub::Ruby;
+use crate::reader::{DocumentInfo, Line, TocEntry};
+use crate::recordings::Recording;
+use crate::stats;
+use crate::{Difficulty, Inflection, PartOfSpeechGroup, Priority, Score};
+
+pub mod compat;
 
 pub trait Request: Serialize {
     /// The kind of the request.
     const KIND: &'static str;
 
-    /// The expected response.
-    type Response: 'static + DeserializeOwned;
+    /// The expected response.
+    type Response: 'static + DeserializeOwned;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalyzeRequest {
+    pub q: String,
+    pub start: usize,
+    /// Byte offset where analysis stops, so that only candidates inside of
+    /// `start..end` are considered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<usize>,
+}
+
+impl Request for AnalyzeRequest {
+    const KIND: &'static str = "analyze";
+    type Response = OwnedAnalyzeResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchRequest {
+    pub q: String,
+    /// Group phrases which share kanji into variants of a single phrase.
+    #[serde(default)]
+    pub group: bool,
+    /// Stream phrases in responses of at most this many phrases each, which
+    /// is only supported over the websocket.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chunk: Option<usize>,
+}
+
+impl Request for SearchRequest {
+    const KIND: &'static str = "search";
+    type Response = OwnedSearchResponse;
+}
+
+/// Search for lightweight summaries of matching entries, which are suitable
+/// for rendering long lists of results.
+///
+/// Full entries can be requested separately with [`EntryRequest`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchSummariesRequest {
+    pub q: String,
+}
+
+impl Request for SearchSummariesRequest {
+    const KIND: &'static str = "search-summaries";
+    type Response = OwnedSearchSummariesResponse;
+}
+
+/// Request a single phrase by its sequence number.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntryRequest {
+    pub sequence: u32,
+}
+
+impl Request for EntryRequest {
+    const KIND: &'static str = "entry";
+    type Response = OwnedEntryResponse;
+}
+
+/// The maximum number of phrases which can be requested with an
+/// [`EntriesRequest`].
+pub const MAX_ENTRIES: usize = 16;
+
+/// Request several phrases by their sequence numbers at once, like when they
+/// are compared side by side.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntriesRequest {
+    pub sequences: Vec<u32>,
+}
+
+impl Request for EntriesRequest {
+    const KIND: &'static str = "entries";
+    type Response = OwnedEntriesResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstallAllRequest;
+
+impl Request for InstallAllRequest {
+    const KIND: &'static str = "rebuild";
+    type Response = Empty;
+}
+
+/// Claim or release capture focus for the calling client.
+///
+/// While a client holds capture focus, it is the only client which will
+/// receive captured clipboard events.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CaptureFocusRequest {
+    pub claim: bool,
+}
+
+impl Request for CaptureFocusRequest {
+    const KIND: &'static str = "capture-focus";
+    type Response = Empty;
+}
+
+/// Capture data as if it was copied to the clipboard. The body of the request
+/// is the data, and its content type is the type of the data.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CaptureRequest {
+    /// The application the data comes from, which captures can be filtered
+    /// by.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchExamplesRequest {
+    pub q: String,
+}
+
+impl Request for SearchExamplesRequest {
+    const KIND: &'static str = "search-examples";
+    type Response = OwnedSearchExamplesResponse;
+}
+
+/// Request corpus examples for a single sense of a phrase.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SenseExamplesRequest {
+    pub sequence: u32,
+    /// The 0-based index of the sense.
+    pub sense: usize,
+    /// Number of examples to skip.
+    #[serde(default)]
+    pub offset: usize,
+    /// The maximum number of examples to return.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl Request for SenseExamplesRequest {
+    const KIND: &'static str = "sense-examples";
+    type Response = OwnedSenseExamplesResponse;
+}
+
+/// Request phrases related to the phrase with the given sequence.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelatedRequest {
+    pub sequence: u32,
+}
+
+impl Request for RelatedRequest {
+    const KIND: &'static str = "related";
+    type Response = OwnedRelatedResponse;
+}
+
+/// Request the difficulty of the phrases found in a text.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DifficultyRequest {
+    pub q: String,
+}
+
+impl Request for DifficultyRequest {
+    const KIND: &'static str = "difficulty";
+    type Response = DifficultyResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DifficultyResponse {
+    pub words: Vec<WordDifficulty>,
+}
+
+/// The difficulty of a phrase found in a text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordDifficulty {
+    /// Byte range of the phrase in the text.
+    pub range: Range<usize>,
+    /// Sequence of the phrase.
+    pub sequence: u64,
+    pub difficulty: Difficulty,
+}
+
+/// Request the status of the words found in a text in the current profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordStatusRequest {
+    pub q: String,
+}
+
+impl Request for WordStatusRequest {
+    const KIND: &'static str = "word-status";
+    type Response = WordStatusResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordStatusResponse {
+    pub words: Vec<WordStatusSpan>,
+}
+
+/// The status of a word found in a text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordStatusSpan {
+    /// Byte range of the word in the text.
+    pub range: Range<usize>,
+    /// Sequence of the phrase.
+    pub sequence: u64,
+    pub status: WordStatus,
+}
+
+/// Change the status of a word in the current profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetWordStatusRequest {
+    pub sequence: u64,
+    pub status: WordStatus,
+}
+
+impl Request for SetWordStatusRequest {
+    const KIND: &'static str = "set-word-status";
+    type Response = Empty;
+}
+
+/// Set the personal note and tags of a phrase in the current profile.
+///
+/// The annotation is removed if both the note and the tags are empty.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetAnnotationRequest {
+    pub sequence: u64,
+    #[serde(default)]
+    pub note: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Request for SetAnnotationRequest {
+    const KIND: &'static str = "set-annotation";
+    type Response = SetAnnotationResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetAnnotationResponse {
+    /// The annotation as it was stored, if any.
+    pub annotation: Option<Annotation>,
+}
+
+/// Request a conjugation practice question about a word which is being
+/// learned in the current profile.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct QuizRequest {
+    /// Only ask about words in the given word list.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list: Option<String>,
+}
+
+impl Request for QuizRequest {
+    const KIND: &'static str = "quiz";
+    type Response = QuizResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizResponse {
+    /// The question, or `None` if there are no words to practice.
+    pub question: Option<QuizQuestion>,
+}
+
+/// A question asking for a word to be put into a given inflection.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuizQuestion {
+    /// Sequence of the phrase.
+    pub sequence: u64,
+    /// The dictionary form of the word.
+    pub text: String,
+    /// The reading of the dictionary form.
+    pub reading: String,
+    /// The inflection being asked for.
+    pub inflection: Inflection,
+    /// How the inflection is formed, like `negative → past`.
+    pub explanation: String,
+    /// The first gloss of the word.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub gloss: String,
+}
+
+/// Answer a conjugation practice question.
+///
+/// Incorrect answers schedule the word for review in the current profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizAnswerRequest {
+    pub sequence: u64,
+    /// The dictionary form the question was asked about.
+    pub text: String,
+    pub inflection: Inflection,
+    pub answer: String,
+}
+
+impl Request for QuizAnswerRequest {
+    const KIND: &'static str = "quiz-answer";
+    type Response = QuizAnswerResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuizAnswerResponse {
+    pub correct: bool,
+    /// The accepted answers.
+    pub expected: Vec<String>,
+}
+
+/// Request the audio recorded by the user for a phrase.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordingsRequest {
+    pub sequence: u64,
+}
+
+impl Request for RecordingsRequest {
+    const KIND: &'static str = "recordings";
+    type Response = RecordingsResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordingsResponse {
+    pub recordings: Vec<Recording>,
+}
+
+/// Attach a recording to a phrase. The body of the request is the recorded
+/// audio, and its content type is used as the type of the recording.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddRecordingRequest {
+    pub sequence: u64,
+}
+
+/// Delete a recording.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteRecordingRequest {
+    pub id: u64,
+}
+
+impl Request for DeleteRecordingRequest {
+    const KIND: &'static str = "delete-recording";
+    type Response = Empty;
+}
+
+/// Mine a card for the phrase with the given sequence from a captured
+/// sentence.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MineRequest {
+    pub sentence: String,
+    pub sequence: u64,
+    /// Attach the image most recently captured from the clipboard.
+    #[serde(default)]
+    pub screenshot: bool,
+}
+
+impl Request for MineRequest {
+    const KIND: &'static str = "mine";
+    type Response = Card;
+}
+
+/// List mined cards.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MinedRequest;
+
+impl Request for MinedRequest {
+    const KIND: &'static str = "mined";
+    type Response = MinedResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MinedResponse {
+    pub cards: Vec<Card>,
+}
+
+/// Delete a mined card.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteMinedRequest {
+    pub id: u64,
+}
+
+impl Request for DeleteMinedRequest {
+    const KIND: &'static str = "delete-mined";
+    type Response = Empty;
+}
+
+/// Export mined cards to Anki through AnkiConnect.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportMinedRequest {
+    pub ids: Vec<u64>,
+    /// Add notes even if notes for the same word already exist.
+    #[serde(default)]
+    pub allow_duplicates: bool,
+}
+
+impl Request for ExportMinedRequest {
+    const KIND: &'static str = "export-mined";
+    type Response = ExportMinedResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportMinedResponse {
+    /// All mined cards, including the notes the exported cards were added as.
+    pub cards: Vec<Card>,
+    /// Cards which weren't exported since notes for the same word already
+    /// exist.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<u64>,
+}
+
+/// Find the notes in Anki for the phrase with the given sequence.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnkiNotesRequest {
+    pub sequence: u64,
+}
+
+impl Request for AnkiNotesRequest {
+    const KIND: &'static str = "anki-notes";
+    type Response = AnkiNotesResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnkiNotesResponse {
+    pub notes: Vec<u64>,
+}
+
+/// Add a note to Anki for the phrase with the given sequence, unless notes
+/// for it already exist. The response lists the notes for the phrase.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddAnkiNoteRequest {
+    pub sequence: u64,
+    /// Add a note even if notes for the phrase already exist.
+    #[serde(default)]
+    pub allow_duplicate: bool,
+}
+
+impl Request for AddAnkiNoteRequest {
+    const KIND: &'static str = "add-anki-note";
+    type Response = AnkiNotesResponse;
+}
+
+/// Read the given text out loud using the configured text-to-speech engine.
+/// The response is the synthesized audio.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TtsRequest {
+    pub text: String,
+}
+
+/// Request a graph of phrases related to the phrase with the given sequence.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GraphRequest {
+    pub seq: u32,
+    /// How many relations to follow away from the phrase.
+    #[serde(default = "default_graph_depth")]
+    pub depth: usize,
+}
+
+fn default_graph_depth() -> usize {
+    2
+}
+
+impl Request for GraphRequest {
+    const KIND: &'static str = "graph";
+    type Response = Graph;
+}
+
+/// The maximum number of days statistics can be requested for.
+pub const MAX_STATS_DAYS: u32 = 366;
+
+/// Request usage statistics for the given number of days, counting back from
+/// today.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsRequest {
+    #[serde(default = "default_stats_days")]
+    pub days: u32,
+}
+
+fn default_stats_days() -> u32 {
+    30
+}
+
+impl Request for StatsRequest {
+    const KIND: &'static str = "stats";
+    type Response = StatsResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsResponse {
+    /// Activity during each requested day, oldest first.
+    pub days: Vec<StatsDay>,
+    /// Activity during every day statistics are kept for.
+    pub total: stats::Day,
+    /// The number of consecutive days with activity up until today.
+    pub streak: u32,
+    /// The longest number of consecutive days with activity.
+    pub longest_streak: u32,
+    /// Text read through captures during every day statistics are kept for.
+    #[serde(default)]
+    pub immersion: stats::Immersion,
+    /// Text read from each source during every day statistics are kept for.
+    #[serde(default)]
+    pub sources: Vec<StatsSource>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsDay {
+    /// The date, formatted as `YYYY-MM-DD`.
+    pub date: String,
+    pub activity: stats::Day,
+    /// Text read through captures during the day.
+    #[serde(default)]
+    pub immersion: stats::Immersion,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatsSource {
+    /// The source captures came from.
+    pub source: String,
+    pub immersion: stats::Immersion,
+}
+
+/// Remove all collected usage statistics.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClearStatsRequest;
+
+impl Request for ClearStatsRequest {
+    const KIND: &'static str = "clear-stats";
+    type Response = Empty;
+}
+
+/// Request the component tree of a kanji.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KanjiComponentsRequest {
+    pub literal: String,
+}
+
+impl Request for KanjiComponentsRequest {
+    const KIND: &'static str = "kanji-components";
+    type Response = KanjiComponentsResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KanjiComponentsResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<ids::Component>,
+}
+
+/// The most phrases which can be requested at once for a kanji.
+pub const MAX_KANJI_WORDS: usize = 100;
+
+/// Request phrases written using a kanji, the most frequently used first.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KanjiWordsRequest {
+    /// The kanji, which is taken from the path over HTTP.
+    #[serde(default)]
+    pub literal: String,
+    #[serde(default = "default_kanji_words_limit")]
+    pub limit: usize,
+    /// The number of phrases to skip, used to page through them.
+    #[serde(default)]
+    pub offset: usize,
+}
+
+fn default_kanji_words_limit() -> usize {
+    10
+}
+
+impl Request for KanjiWordsRequest {
+    const KIND: &'static str = "kanji-words";
+    type Response = OwnedKanjiWordsResponse;
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KanjiWordsResponse<'a> {
+    #[borrowed_attr(serde(borrow))]
+    pub words: Vec<jmdict::Entry<'a>>,
+    /// Whether there are more phrases after these.
+    #[serde(default)]
+    pub more: bool,
+}
+
+/// Search kanji by reading, meaning or both.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct KanjiSearchRequest {
+    /// An onyomi or kunyomi reading, like `こう` or `kou`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading: Option<String>,
+    /// An English meaning, like `light`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meaning: Option<String>,
+}
+
+impl Request for KanjiSearchRequest {
+    const KIND: &'static str = "kanji-search";
+    type Response = OwnedKanjiSearchResponse;
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KanjiSearchResponse<'a> {
+    /// Matching kanji, the most frequently used first.
+    #[borrowed_attr(serde(borrow))]
+    pub characters: Vec<kanjidic2::Character<'a>>,
+}
+
+/// Request counters suggested for the phrase with the given sequence.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountersRequest {
+    pub sequence: u32,
+}
+
+impl Request for CountersRequest {
+    const KIND: &'static str = "counters";
+    type Response = OwnedCountersResponse;
+}
+
+/// Request the reading of a number together with a counter.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountRequest {
+    /// The counter, either as written or as read.
+    pub counter: String,
+    pub number: u32,
+}
+
+impl Request for CountRequest {
+    const KIND: &'static str = "count";
+    type Response = CountResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountResponse {
+    /// The reading of the number together with the counter, if the counter is
+    /// known and can be used with the number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetState;
+
+impl Request for GetState {
+    const KIND: &'static str = "get-config";
+    type Response = GetStateResult;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetStateResult {
+    /// Installed dictionaries.
+    pub installed: HashSet<String>,
+}
+
+/// Request the status of installed dictionaries.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusRequest;
+
+impl Request for StatusRequest {
+    const KIND: &'static str = "status";
+    type Response = StatusResponse;
+}
+
+/// A dictionary which is enabled but not installed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingIndex {
+    /// The identifier of the dictionary.
+    pub id: String,
+    /// The format of the dictionary, which indicates what is missing.
+    pub format: IndexFormat,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusResponse {
+    /// Installed and enabled dictionaries.
+    pub installed: Vec<String>,
+    /// Dictionaries which are enabled but not installed, so searches are
+    /// carried out without them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<MissingIndex>,
+    /// Whether the first-run setup has been completed or skipped.
+    #[serde(default)]
+    pub onboarded: bool,
+    /// The port the service is listening on, which is a free one picked when
+    /// starting if the default port was used by another application.
+    #[serde(default)]
+    pub port: u16,
+}
+
+/// Complete the first-run setup.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OnboardRequest {
+    /// Install the enabled dictionaries.
+    pub install: bool,
+    /// The license of the dictionaries has been accepted, which is required
+    /// to install dictionaries which come with one.
+    #[serde(default)]
+    pub accept_license: bool,
+    /// Recognize text in captured images.
+    pub ocr: bool,
+}
+
+impl Request for OnboardRequest {
+    const KIND: &'static str = "onboard";
+    type Response = Empty;
+}
+
+/// Request whether a newer release of the service is available, which is only
+/// checked if enabled in the configuration.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateRequest;
+
+impl Request for UpdateRequest {
+    const KIND: &'static str = "update";
+    type Response = UpdateResponse;
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateResponse {
+    /// The version of the running service.
+    pub version: String,
+    /// A newer release, if there is one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available: Option<Release>,
+}
+
+/// A published release of the service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Release {
+    pub version: String,
+    /// The page describing the release, where it can be downloaded from.
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetConfig;
+
+impl Request for GetConfig {
+    const KIND: &'static str = "get-config";
+    type Response = GetConfigResult;
+}
+
+/// Missing OCR support.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstallUrl {
+    /// Title of the URL.
+    pub text: String,
+    /// Hover title.
+    pub title: String,
+    /// The URL where to install it from.
+    pub url: String,
+}
+
+/// Missing OCR support.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MissingOcr {
+    /// The URL where to install it from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub install_url: Option<InstallUrl>,
+}
+
+impl MissingOcr {
+    #[cfg(unix)]
+    pub fn for_platform() -> Self {
+        Self { install_url: None }
+    }
+
+    #[cfg(windows)]
+    pub fn for_platform() -> Self {
+        Self {
+            install_url: Some(InstallUrl {
+                text: "Install Tesseract-OCR".to_string(),
+                title: "Download and install Tesseract-OCR from UB-Mannheim.\nDon't forget to add Japanese as additional script!".to_string(),
+                url: "https://github.com/UB-Mannheim/tesseract/wiki".to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetConfigResult {
+    /// System configuration.
+    pub config: Config,
+    /// Installed dictionaries.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub installed: HashSet<String>,
+    /// Indicates that OCR support is missing, and some indications of how to install it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub missing_ocr: Option<MissingOcr>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct UpdateConfigRequest(pub Config);
+
+impl Request for UpdateConfigRequest {
+    const KIND: &'static str = "update-config";
+    type Response = Empty;
+}
+
+/// Preview what capture filters do to a sample of captured text, without
+/// saving them.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewCaptureFiltersRequest {
+    pub filters: Vec<CaptureFilter>,
+    pub text: String,
+}
+
+impl Request for PreviewCaptureFiltersRequest {
+    const KIND: &'static str = "preview-capture-filters";
+    type Response = PreviewCaptureFiltersResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreviewCaptureFiltersResponse {
+    /// The filtered text, or `None` if the capture would be dropped.
+    pub text: Option<String>,
+    /// Filters which couldn't be compiled, and are skipped.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<CaptureFilterError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureFilterError {
+    /// The index of the filter.
+    pub index: usize,
+    pub message: String,
+}
+
+/// Correct the text recognized in a captured image, so that the correction is
+/// used the next time the same image is captured.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CorrectOcrRequest {
+    /// The key of the recognized text, as sent in [`RecognizedText`].
+    pub key: u64,
+    pub text: String,
+}
+
+impl Request for CorrectOcrRequest {
+    const KIND: &'static str = "correct-ocr";
+    type Response = Empty;
+}
+
+/// Request the available profiles.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfilesRequest;
+
+impl Request for ProfilesRequest {
+    const KIND: &'static str = "profiles";
+    type Response = ProfilesResponse;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfilesResponse {
+    /// The currently selected profile.
+    pub current: String,
+    /// All available profiles.
+    pub profiles: Vec<String>,
+    /// Whether profiles can be synchronized.
+    #[serde(default)]
+    pub sync: bool,
+    /// How names are weighed when analyzing text in the current profile.
+    #[serde(default)]
+    pub names: NameWeighting,
+    /// How readings are shown above kanji in the current profile.
+    #[serde(default)]
+    pub readings: Readings,
+    /// How densely entries and characters are shown in the current profile.
+    #[serde(default)]
+    pub density: Density,
+    /// How much Japanese text is scaled in the current profile, in percent.
+    #[serde(default = "default_text_scale")]
+    pub text_scale: u32,
+}
+
+fn default_text_scale() -> u32 {
+    profile::DEFAULT_TEXT_SCALE
+}
+
+/// Select a profile, creating it if it doesn't exist.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SelectProfileRequest {
+    pub name: String,
+}
+
+impl Request for SelectProfileRequest {
+    const KIND: &'static str = "select-profile";
+    type Response = ProfilesResponse;
+}
+
+/// Delete a profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteProfileRequest {
+    pub name: String,
+}
+
+impl Request for DeleteProfileRequest {
+    const KIND: &'static str = "delete-profile";
+    type Response = ProfilesResponse;
+}
+
+/// Change how names are weighed when analyzing text in the current profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetNameWeightingRequest {
+    pub names: NameWeighting,
+}
+
+impl Request for SetNameWeightingRequest {
+    const KIND: &'static str = "set-name-weighting";
+    type Response = ProfilesResponse;
+}
+
+/// Change how readings are shown above kanji in the current profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetReadingsRequest {
+    pub readings: Readings,
+}
+
+impl Request for SetReadingsRequest {
+    const KIND: &'static str = "set-readings";
+    type Response = ProfilesResponse;
+}
+
+/// Change how densely entries and characters are shown in the current
+/// profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetDensityRequest {
+    pub density: Density,
+    /// How much Japanese text is scaled, in percent.
+    pub text_scale: u32,
+}
+
+impl Request for SetDensityRequest {
+    const KIND: &'static str = "set-density";
+    type Response = ProfilesResponse;
+}
+
+/// Synchronize the current profile with other installs.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncRequest;
+
+impl Request for SyncRequest {
+    const KIND: &'static str = "sync";
+    type Response = Empty;
+}
+
+/// Add an entry to the history of the current profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AddHistoryRequest {
+    pub text: String,
+}
+
+impl Request for AddHistoryRequest {
+    const KIND: &'static str = "add-history";
+    type Response = Empty;
+}
+
+/// Request the history of the current profile.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryRequest;
+
+impl Request for HistoryRequest {
+    const KIND: &'static str = "history";
+    type Response = HistoryResponse;
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AnalyzeRequest {
-    pub q: String,
-    pub start: usize,
+pub struct HistoryResponse {
+    /// History entries, most recent first.
+    pub entries: Vec<HistoryEntry>,
 }
 
-impl Request for AnalyzeRequest {
-    const KIND: &'static str = "analyze";
-    type Response = OwnedAnalyzeResponse;
+/// Import known words into the current profile from an Anki plain text
+/// export or a CSV file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportKnownWordsRequest {
+    pub text: String,
+}
+
+impl Request for ImportKnownWordsRequest {
+    const KIND: &'static str = "import-known-words";
+    type Response = ImportKnownWordsResponse;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportKnownWordsResponse {
+    /// The number of words found in the import.
+    pub words: usize,
+    /// The number of words which were newly marked as known.
+    pub added: usize,
+    /// Words which couldn't be found in the dictionary.
+    pub missing: Vec<String>,
+    /// The number of known words in the profile after the import.
+    pub known: usize,
+}
+
+/// The maximum number of phrases in an exported PDF document.
+pub const MAX_EXPORT_ENTRIES: usize = 2000;
+
+/// Export phrases as a printable PDF document.
+///
+/// This is only served over HTTP, since the response is the document itself
+/// rather than JSON.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExportPdfRequest {
+    /// The title of the document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Export the given phrases in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sequences: Vec<u32>,
+    /// Export the words in the given word list. If neither phrases nor a list
+    /// is specified, every word which is being learned is exported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list: Option<String>,
+    /// Only include glossary entries in the given language.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    #[serde(default)]
+    pub layout: pdf::Layout,
 }
 
+/// Request the documents stored in the reader.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct SearchRequest {
-    pub q: String,
+pub struct DocumentsRequest;
+
+impl Request for DocumentsRequest {
+    const KIND: &'static str = "documents";
+    type Response = DocumentsResponse;
 }
 
-impl Request for SearchRequest {
-    const KIND: &'static str = "search";
-    type Response = OwnedSearchResponse;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentsResponse {
+    /// Stored documents, in the order they were added.
+    pub documents: Vec<DocumentInfo>,
 }
 
+/// Add a document to the reader.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct InstallAllRequest;
+pub struct AddDocumentRequest {
+    pub title: String,
+    pub text: String,
+}
 
-impl Request for InstallAllRequest {
-    const KIND: &'static str = "rebuild";
-    type Response = Empty;
+impl Request for AddDocumentRequest {
+    const KIND: &'static str = "add-document";
+    type Response = DocumentInfo;
+}
+
+/// Add a book to the reader. The body of the request is an EPUB archive.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AddEpubRequest {
+    /// How ruby annotations in the book are handled.
+    #[serde(default)]
+    pub ruby: Ruby,
 }
 
+/// Add subtitles to the reader, with one timed line per subtitle.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GetState;
+pub struct AddSubtitlesRequest {
+    /// The name of the subtitle file, which determines its format.
+    pub name: String,
+    pub text: String,
+}
 
-impl Request for GetState {
-    const KIND: &'static str = "get-config";
-    type Response = GetStateResult;
+impl Request for AddSubtitlesRequest {
+    const KIND: &'static str = "add-subtitles";
+    type Response = DocumentInfo;
 }
 
+/// Delete a document from the reader.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GetStateResult {
-    /// Installed dictionaries.
-    pub installed: HashSet<String>,
+pub struct DeleteDocumentRequest {
+    pub id: u64,
 }
 
+impl Request for DeleteDocumentRequest {
+    const KIND: &'static str = "delete-document";
+    type Response = DocumentsResponse;
+}
+
+/// Request a page of a document, which also stores it as the reading position
+/// of the document.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GetConfig;
+pub struct PageRequest {
+    pub id: u64,
+    /// The 0-based page to read. Defaults to the stored reading position.
+    #[serde(default)]
+    pub page: Option<usize>,
+    /// Read the page with the timed line shown at the given number of
+    /// milliseconds, which takes precedence over `page`.
+    #[serde(default)]
+    pub time: Option<u64>,
+}
 
-impl Request for GetConfig {
-    const KIND: &'static str = "get-config";
-    type Response = GetConfigResult;
+impl Request for PageRequest {
+    const KIND: &'static str = "page";
+    type Response = PageResponse;
 }
 
-/// Missing OCR support.
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct InstallUrl {
-    /// Title of the URL.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PageResponse {
+    pub document: DocumentInfo,
+    /// The 0-based page which was read.
+    pub page: usize,
+    /// The text of the page.
     pub text: String,
-    /// Hover title.
-    pub title: String,
-    /// The URL where to install it from.
-    pub url: String,
+    /// The table of contents of the document.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub toc: Vec<TocEntry>,
+    /// Timed lines on the page.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<Line>,
+    /// The index of the line on the page which was found by time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
 }
 
-/// Missing OCR support.
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct MissingOcr {
-    /// The URL where to install it from.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub install_url: Option<InstallUrl>,
+/// Search the timed lines of a document.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchLinesRequest {
+    pub id: u64,
+    pub q: String,
 }
 
-impl MissingOcr {
-    #[cfg(unix)]
-    pub fn for_platform() -> Self {
-        Self { install_url: None }
-    }
+impl Request for SearchLinesRequest {
+    const KIND: &'static str = "search-lines";
+    type Response = SearchLinesResponse;
+}
 
-    #[cfg(windows)]
-    pub fn for_platform() -> Self {
-        Self {
-            install_url: Some(InstallUrl {
-                text: "Install Tesseract-OCR".to_string(),
-                title: "Download and install Tesseract-OCR from UB-Mannheim.\nDon't forget to add Japanese as additional script!".to_string(),
-                url: "https://github.com/UB-Mannheim/tesseract/wiki".to_string(),
-            }),
-        }
-    }
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchLinesResponse {
+    pub lines: Vec<LineMatch>,
+}
+
+/// A timed line matching a search.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineMatch {
+    /// Milliseconds from the start when the line is shown.
+    pub start: u64,
+    /// The 0-based page the line is on.
+    pub page: usize,
+    pub text: String,
 }
 
+/// Request statistics about the words in a document which are unknown to the
+/// current profile.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GetConfigResult {
-    /// System configuration.
-    pub config: Config,
-    /// Installed dictionaries.
-    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
-    pub installed: HashSet<String>,
-    /// Indicates that OCR support is missing, and some indications of how to install it.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub missing_ocr: Option<MissingOcr>,
+pub struct DocumentStatsRequest {
+    pub id: u64,
+}
+
+impl Request for DocumentStatsRequest {
+    const KIND: &'static str = "document-stats";
+    type Response = CoverageResponse;
 }
 
+/// Request how much of a text is covered by the words known in the current
+/// profile.
 #[derive(Debug, Serialize, Deserialize)]
-#[serde(transparent)]
-pub struct UpdateConfigRequest(pub Config);
+pub struct CoverageRequest {
+    pub q: String,
+}
 
-impl Request for UpdateConfigRequest {
-    const KIND: &'static str = "update-config";
-    type Response = Empty;
+impl Request for CoverageRequest {
+    const KIND: &'static str = "coverage";
+    type Response = CoverageResponse;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoverageResponse {
+    /// The number of words found in the text.
+    pub words: usize,
+    /// The number of words found in the text which are known.
+    #[serde(default)]
+    pub known: usize,
+    /// The percentage of words in the text which are known.
+    #[serde(default)]
+    pub coverage: f32,
+    /// The number of distinct words found in the text.
+    pub unique: usize,
+    /// The number of distinct words which are unknown.
+    pub unknown: usize,
+    /// Unknown words, most frequent first.
+    pub unknown_words: Vec<UnknownWord>,
+}
+
+/// A word in a text which is unknown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnknownWord {
+    /// The dictionary form of the word.
+    pub text: String,
+    /// Sequence of the phrase.
+    pub sequence: u64,
+    /// The number of times the word appears.
+    pub count: usize,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -137,14 +1223,80 @@ pub struct SendClipboard<'a> {
     pub ty: Option<&'a str>,
     #[borrowme(owned = Box<[u8]>, to_owned_with = Box::from)]
     pub data: &'a [u8],
+    /// The application the data was captured from, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[borrowed_attr(serde(borrow))]
+    pub source: Option<&'a str>,
 }
 
-/// Json payload when sending the clipboard.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SendClipboardJson {
-    pub primary: String,
+/// A line captured as JSON with the `application/json` type, which lets tools
+/// like text extractors for games send more than the text of a line:
+///
+/// ```json
+/// {"text": "吾輩は猫である。", "translation": "I am a cat.", "source": "game"}
+/// ```
+///
+/// The `primary` and `secondary` names of the text and the translation are
+/// also accepted, which older tools use.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureJson {
+    /// The text of the line, which is looked up.
+    #[serde(alias = "primary")]
+    pub text: String,
+    /// A translation of the line, shown along with it.
+    #[serde(default, alias = "secondary", skip_serializing_if = "Option::is_none")]
+    pub translation: Option<String>,
+    /// The application the line comes from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// A captured line of text.
+#[borrowme::borrowme]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapturedLine<'a> {
+    #[borrowed_attr(serde(borrow))]
+    pub text: &'a str,
+    /// A translation of the line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[borrowed_attr(serde(borrow))]
+    pub translation: Option<&'a str>,
+    /// The application the line was captured from, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[borrowed_attr(serde(borrow))]
+    pub source: Option<&'a str>,
+}
+
+/// A line of text recognized in a captured image.
+#[borrowme::borrowme]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecognizedLine<'a> {
+    #[borrowed_attr(serde(borrow))]
+    pub text: &'a str,
+    /// How confident the OCR engine is in the line, from `0.0` to `1.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[copy]
+    pub confidence: Option<f32>,
+}
+
+/// Text recognized in a captured image.
+#[borrowme::borrowme]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecognizedText<'a> {
+    /// Identifies the image the text was recognized in, which is used to
+    /// correct it with a [`CorrectOcrRequest`].
+    #[copy]
+    pub key: u64,
+    /// The recognized text, after capture filters have been applied.
+    #[borrowed_attr(serde(borrow))]
+    pub text: &'a str,
+    /// The lines the text was recognized as.
+    #[borrowed_attr(serde(borrow))]
+    pub lines: Vec<RecognizedLine<'a>>,
+    /// The application the image was captured from, if known.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub secondary: Option<String>,
+    #[borrowed_attr(serde(borrow))]
+    pub source: Option<&'a str>,
 }
 
 #[borrowme::borrowme]
@@ -161,6 +1313,10 @@ pub enum BroadcastKind<'a> {
     #[borrowed_attr(serde(borrow))]
     SendClipboardData(SendClipboard<'a>),
     #[borrowed_attr(serde(borrow))]
+    CapturedLine(CapturedLine<'a>),
+    #[borrowed_attr(serde(borrow))]
+    RecognizedText(RecognizedText<'a>),
+    #[borrowed_attr(serde(borrow))]
     LogBackFill(LogBackFill<'a>),
     #[borrowed_attr(serde(borrow))]
     LogEntry(LogEntry<'a>),
@@ -168,6 +1324,9 @@ pub enum BroadcastKind<'a> {
     TaskProgress(TaskProgress<'a>),
     #[borrowed_attr(serde(borrow))]
     TaskCompleted(TaskCompleted<'a>),
+    CaptureFocus(CaptureFocus),
+    /// The selected profile has changed.
+    ProfileChanged,
     Refresh,
 }
 
@@ -178,6 +1337,99 @@ pub struct Broadcast<'a> {
     pub kind: BroadcastKind<'a>,
 }
 
+/// The kind of an error returned by the API, which lets clients tell errors
+/// apart without inspecting their messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The request is malformed or has invalid parameters.
+    BadRequest,
+    /// The requested resource doesn't exist.
+    NotFound,
+    /// The request isn't permitted, like changes to a shared dictionary.
+    Forbidden,
+    /// There are no dictionaries installed or enabled to serve the request.
+    IndexMissing,
+    /// A feature the request needs isn't configured, like text to speech.
+    Unavailable,
+    /// Too many requests have been made.
+    RateLimited,
+    /// The client speaks a version of the protocol which isn't supported.
+    Incompatible,
+    /// An unexpected error in the service.
+    Internal,
+}
+
+impl ErrorKind {
+    /// The HTTP status code the error is returned with.
+    pub fn status(self) -> u16 {
+        match self {
+            ErrorKind::BadRequest => 400,
+            ErrorKind::Forbidden => 403,
+            ErrorKind::NotFound => 404,
+            ErrorKind::Incompatible => 426,
+            ErrorKind::RateLimited => 429,
+            ErrorKind::Internal => 500,
+            ErrorKind::IndexMissing | ErrorKind::Unavailable => 503,
+        }
+    }
+}
+
+/// An error returned by the API, both as the body of failed HTTP requests and
+/// in websocket responses.
+#[derive(Debug, Clone, Error, Serialize, Deserialize)]
+#[error("{message}")]
+pub struct ApiError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Identifies the request in the logs of the service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ApiError {
+    /// Construct a new error of the given kind.
+    pub fn new<M>(kind: ErrorKind, message: M) -> Self
+    where
+        M: fmt::Display,
+    {
+        Self {
+            kind,
+            message: message.to_string(),
+            request_id: None,
+        }
+    }
+
+    /// Attach the identifier of the request which failed.
+    pub fn with_request_id(self, request_id: &str) -> Self {
+        Self {
+            request_id: Some(request_id.to_owned()),
+            ..self
+        }
+    }
+
+    /// Classify an arbitrary error, where errors which aren't recognized are
+    /// internal.
+    pub fn from_anyhow(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if let Some(error) = cause.downcast_ref::<ApiError>() {
+                return error.clone();
+            }
+
+            if let Some(SearchError::IndexMissing) = cause.downcast_ref::<SearchError>() {
+                return Self::new(ErrorKind::IndexMissing, error);
+            }
+
+            if let Some(BuildError::Shutdown) = cause.downcast_ref::<BuildError>() {
+                return Self::new(ErrorKind::Unavailable, error);
+            }
+        }
+
+        Self::new(ErrorKind::Internal, error)
+    }
+}
+
 #[borrowme::borrowme]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
@@ -194,6 +1446,9 @@ pub enum ClientResponse<'a> {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ClientRequestEnvelope {
+    /// The version of the protocol the client speaks.
+    #[serde(default)]
+    pub version: compat::Version,
     pub index: usize,
     pub serial: u32,
     pub kind: String,
@@ -207,7 +1462,7 @@ pub struct ClientResponseEnvelope {
     #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
     pub body: serde_json::Value,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+    pub error: Option<ApiError>,
 }
 
 #[borrowme::borrowme]
@@ -223,14 +1478,35 @@ pub enum ClientEvent<'a> {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchPhrase<'a> {
     pub key: EntryResultKey,
+    /// Identifier of the dictionary the phrase is from.
+    #[serde(default)]
+    pub dictionary: String,
     #[borrowed_attr(serde(borrow))]
     pub phrase: jmdict::Entry<'a>,
+    /// Lower ranked phrases sharing kanji with this one, only populated if
+    /// grouping was requested.
+    #[borrowed_attr(serde(borrow))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<SearchPhrase<'a>>,
+    /// The best corpus examples for each sense which has any.
+    #[borrowed_attr(serde(borrow))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sense_examples: Vec<SenseExamples<'a>>,
+    /// Estimated difficulty of the phrase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+    /// The personal note and tags of the phrase in the current profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<Annotation>,
 }
 
 #[borrowme::borrowme]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SearchName<'a> {
     pub key: EntryResultKey,
+    /// Identifier of the dictionary the name is from.
+    #[serde(default)]
+    pub dictionary: String,
     #[borrowed_attr(serde(borrow))]
     pub name: jmnedict::Entry<'a>,
 }
@@ -244,13 +1520,149 @@ pub struct SearchResponse<'a> {
     pub names: Vec<SearchName<'a>>,
     #[borrowed_attr(serde(borrow))]
     pub characters: Vec<kanjidic2::Character<'a>>,
+    /// Number of phrases belonging to each part of speech group.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub facets: BTreeMap<PartOfSpeechGroup, usize>,
+    /// The dictionaries which results are from.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dictionaries: Vec<Dictionary>,
+    /// The index of the first phrase in this response, when the search is
+    /// streamed. Only the first response holds names, characters, facets and
+    /// dictionaries.
+    #[serde(default)]
+    pub offset: usize,
+    /// Whether more phrases follow in later responses.
+    #[serde(default)]
+    pub more: bool,
+}
+
+/// The kind of entry being summarized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SummaryKind {
+    Phrase,
+    Name,
+}
+
+/// A lightweight summary of an entry.
+#[borrowme::borrowme]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntrySummary<'a> {
+    #[copy]
+    pub kind: SummaryKind,
+    pub sequence: u64,
+    /// The primary way the entry is written.
+    pub term: &'a str,
+    /// The primary reading of the entry, if it differs from the term.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading: Option<&'a str>,
+    /// The first gloss or translation of the entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gloss: Option<&'a str>,
+    /// Priority tags of the term and the reading, like `ichi1` or `nf05`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub priority: Vec<Priority>,
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchSummariesResponse<'a> {
+    /// Summaries of matching phrases followed by matching names, each
+    /// ordered from best to worst.
+    #[borrowed_attr(serde(borrow))]
+    pub entries: Vec<EntrySummary<'a>>,
+}
+
+/// A dictionary which results are from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dictionary {
+    /// The identifier of the index the dictionary is installed as.
+    pub id: String,
+    /// Human readable name of the dictionary.
+    pub title: String,
+    /// The format of the dictionary.
+    pub format: IndexFormat,
+    /// Whether the dictionary was added by the user.
+    #[serde(default)]
+    pub user: bool,
+    /// Where the dictionary is downloaded from.
+    pub url: String,
+    /// Page with more information about the dictionary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// Attribution required by the license of the dictionary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<String>,
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchExample<'a> {
+    pub text: &'a str,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<&'a str>,
+    /// Byte ranges in `text` matching the query.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub text_highlights: Vec<Range<usize>>,
+    /// Byte ranges in `translation` matching the query.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub translation_highlights: Vec<Range<usize>>,
+    /// Sequence of the phrase the example belongs to.
+    pub sequence: u64,
+    /// Kanji of the phrase the example belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kanji: Option<&'a str>,
+    /// Reading of the phrase the example belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading: Option<&'a str>,
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchExamplesResponse<'a> {
+    #[borrowed_attr(serde(borrow))]
+    pub examples: Vec<SearchExample<'a>>,
+    /// Total number of matching examples, which might be more than the number
+    /// returned.
+    pub total: usize,
+}
+
+#[borrowme::borrowme]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SenseExample<'a> {
+    pub text: &'a str,
+    pub translation: &'a str,
+    pub checked: bool,
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SenseExamplesResponse<'a> {
+    #[borrowed_attr(serde(borrow))]
+    pub examples: Vec<SenseExample<'a>>,
+    /// Total number of examples for the sense.
+    pub total: usize,
+}
+
+/// The best examples for a single sense of a phrase.
+#[borrowme::borrowme]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SenseExamples<'a> {
+    /// The 0-based index of the sense.
+    pub sense: usize,
+    /// Total number of examples for the sense.
+    pub total: usize,
+    #[borrowed_attr(serde(borrow))]
+    pub examples: Vec<SenseExample<'a>>,
 }
 
 #[borrowme::borrowme]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnalyzeEntry<'a> {
-    pub key: Weight,
     pub string: &'a str,
+    /// The score of the candidate, explaining how it was ranked.
+    #[copy]
+    pub score: Score,
 }
 
 #[borrowme::borrowme]
@@ -265,6 +1677,60 @@ pub struct AnalyzeResponse<'a> {
 pub struct EntryResponse<'a> {
     #[borrowed_attr(serde(borrow))]
     pub entry: jmdict::Entry<'a>,
+    #[borrowed_attr(serde(borrow))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<jmdict::Entry<'a>>,
+    /// Estimated difficulty of the phrase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntriesResponse<'a> {
+    /// Phrases in the order they were requested, where phrases which couldn't
+    /// be found are left out.
+    #[borrowed_attr(serde(borrow))]
+    pub entries: Vec<ComparedEntry<'a>>,
+}
+
+/// A phrase with the details used to tell it apart from other phrases.
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComparedEntry<'a> {
+    #[borrowed_attr(serde(borrow))]
+    pub entry: jmdict::Entry<'a>,
+    /// The best corpus examples for each sense which has any.
+    #[borrowed_attr(serde(borrow))]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sense_examples: Vec<SenseExamples<'a>>,
+    /// Estimated difficulty of the phrase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RelatedResponse<'a> {
+    #[borrowed_attr(serde(borrow))]
+    pub related: Vec<jmdict::Entry<'a>>,
+}
+
+#[borrowme::borrowme]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Counter<'a> {
+    pub text: &'a str,
+    pub reading: &'a str,
+    pub description: &'a str,
+    /// Readings of the numbers one through ten together with the counter.
+    pub readings: Vec<String>,
+}
+
+#[borrowme::borrowme]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountersResponse<'a> {
+    #[borrowed_attr(serde(borrow))]
+    pub counters: Vec<Counter<'a>>,
 }
 
 #[borrowme::borrowme]
@@ -272,6 +1738,8 @@ pub struct EntryResponse<'a> {
 pub struct KanjiResponse<'a> {
     #[borrowed_attr(serde(borrow))]
     pub entry: kanjidic2::Character<'a>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<ids::Component>,
 }
 
 #[borrowme::borrowme]
@@ -305,3 +1773,12 @@ pub struct TaskProgress<'a> {
 pub struct TaskCompleted<'a> {
     pub name: &'a str,
 }
+
+/// Indicates which client currently holds capture focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureFocus {
+    /// If the receiving client holds capture focus.
+    pub focused: bool,
+    /// If any client holds capture focus.
+    pub claimed: bool,
+}
@@ -0,0 +1,136 @@
+//! Compatibility between versions of the protocol spoken by the service and
+//! its clients.
+//!
+//! The protocol has a major and a minor version. A client is served as long as
+//! it speaks the same major version and is at most one minor version behind,
+//! which covers a web application cached by the browser from before the
+//! service was upgraded. Differences to such clients are bridged here, so that
+//! the rest of the service only deals with the current version.
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::{ApiError, ClientResponseEnvelope, ErrorKind, OwnedClientEvent};
+
+/// The header HTTP clients declare the version of the protocol they speak in.
+pub const HEADER: &str = "x-jpv-protocol";
+
+/// The version of the protocol spoken by this build.
+pub const PROTOCOL_VERSION: Version = Version::new(1, 1);
+
+/// The version of clients which don't declare one, since they predate
+/// versioning.
+///
+/// These receive errors as plain messages rather than as [`ApiError`].
+pub const LEGACY_VERSION: Version = Version::new(1, 0);
+
+/// A version of the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl Version {
+    /// Construct a new version.
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl Default for Version {
+    #[inline]
+    fn default() -> Self {
+        LEGACY_VERSION
+    }
+}
+
+impl fmt::Display for Version {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for Version {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parsed = s
+            .split_once('.')
+            .and_then(|(major, minor)| Some(Self::new(major.parse().ok()?, minor.parse().ok()?)));
+
+        match parsed {
+            Some(version) => Ok(version),
+            None => Err(ApiError::new(
+                ErrorKind::BadRequest,
+                format_args!("Invalid protocol version `{s}`"),
+            )),
+        }
+    }
+}
+
+/// Check that a client speaking the given version can be served.
+pub fn check(version: Version) -> Result<(), ApiError> {
+    if version > PROTOCOL_VERSION {
+        return Err(ApiError::new(
+            ErrorKind::Incompatible,
+            format_args!(
+                "Client speaks protocol {version} which is newer than {PROTOCOL_VERSION} spoken by the service, update the service"
+            ),
+        ));
+    }
+
+    if version.major != PROTOCOL_VERSION.major || version.minor + 1 < PROTOCOL_VERSION.minor {
+        return Err(ApiError::new(
+            ErrorKind::Incompatible,
+            format_args!(
+                "Client speaks protocol {version} which is too old for {PROTOCOL_VERSION} spoken by the service, reload the page"
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Encode a websocket response in the form understood by a client speaking
+/// the given version.
+pub fn encode_response(
+    version: Version,
+    response: ClientResponseEnvelope,
+) -> serde_json::Result<Vec<u8>> {
+    if version >= Version::new(1, 1) {
+        return serde_json::to_vec(&OwnedClientEvent::ClientResponse(response));
+    }
+
+    serde_json::to_vec(&LegacyClientEvent::ClientResponse(LegacyResponseEnvelope {
+        index: response.index,
+        serial: response.serial,
+        body: response.body,
+        error: response.error.map(|error| error.message),
+    }))
+}
+
+/// A websocket event as understood by clients speaking 1.0.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+enum LegacyClientEvent {
+    ClientResponse(LegacyResponseEnvelope),
+}
+
+/// A response envelope as understood by clients speaking 1.0, where errors
+/// are plain messages.
+#[derive(Serialize)]
+struct LegacyResponseEnvelope {
+    index: usize,
+    serial: u32,
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    body: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
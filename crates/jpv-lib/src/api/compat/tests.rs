@@ -0,0 +1,69 @@
+use super::{check, encode_response, Version, LEGACY_VERSION, PROTOCOL_VERSION};
+use crate::api::{ApiError, ClientRequestEnvelope, ClientResponseEnvelope, ErrorKind};
+
+#[test]
+fn parse_version() {
+    assert_eq!("1.0".parse::<Version>().unwrap(), Version::new(1, 0));
+    assert_eq!("2.13".parse::<Version>().unwrap(), Version::new(2, 13));
+    assert!("1".parse::<Version>().is_err());
+    assert!("1.x".parse::<Version>().is_err());
+    assert_eq!(
+        PROTOCOL_VERSION.to_string().parse::<Version>().unwrap(),
+        PROTOCOL_VERSION
+    );
+}
+
+#[test]
+fn supported_versions() {
+    let Version { major, minor } = PROTOCOL_VERSION;
+
+    assert!(check(PROTOCOL_VERSION).is_ok());
+    assert!(check(LEGACY_VERSION).is_ok());
+    assert!(check(Version::new(major, minor - 1)).is_ok());
+
+    let error = check(Version::new(major, minor + 1)).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Incompatible);
+
+    let error = check(Version::new(major + 1, 0)).unwrap_err();
+    assert_eq!(error.kind, ErrorKind::Incompatible);
+
+    if minor >= 2 {
+        assert!(check(Version::new(major, minor - 2)).is_err());
+    }
+}
+
+#[test]
+fn unversioned_request() {
+    let request: ClientRequestEnvelope =
+        serde_json::from_str(r#"{"index":1,"serial":2,"kind":"search","body":null}"#).unwrap();
+    assert_eq!(request.version, LEGACY_VERSION);
+}
+
+#[test]
+fn legacy_error_response() {
+    let response = || ClientResponseEnvelope {
+        index: 1,
+        serial: 2,
+        body: serde_json::Value::Null,
+        error: Some(ApiError::new(ErrorKind::NotFound, "Missing entry")),
+    };
+
+    let legacy: serde_json::Value =
+        serde_json::from_slice(&encode_response(LEGACY_VERSION, response()).unwrap()).unwrap();
+
+    assert_eq!(
+        legacy,
+        serde_json::json!({
+            "type": "client-response",
+            "index": 1,
+            "serial": 2,
+            "error": "Missing entry",
+        })
+    );
+
+    let current: serde_json::Value =
+        serde_json::from_slice(&encode_response(PROTOCOL_VERSION, response()).unwrap()).unwrap();
+
+    assert_eq!(current["error"]["kind"], "not-found");
+    assert_eq!(current["error"]["message"], "Missing entry");
+}
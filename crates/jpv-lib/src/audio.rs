@@ -0,0 +1,97 @@
+//! Captured audio prepared for transcription.
+//!
+//! Speech recognition models like whisper expect mono samples at
+//! [`SAMPLE_RATE`], so captured audio is mixed down and resampled to it. Only
+//! WAV is decoded, which is what audio is usually captured as.
+
+#[cfg(test)]
+mod tests;
+
+use std::io::Cursor;
+
+use anyhow::{bail, ensure, Context, Result};
+use hound::{SampleFormat, WavReader};
+
+/// The sample rate audio is transcribed at.
+pub const SAMPLE_RATE: u32 = 16_000;
+
+/// The longest audio which is transcribed, in seconds.
+pub const MAX_SECONDS: u32 = 120;
+
+/// Decode captured audio of the given mime type into mono samples at
+/// [`SAMPLE_RATE`].
+pub fn decode(mimetype: &str, data: &[u8]) -> Result<Vec<f32>> {
+    let essence = mimetype.split(';').next().unwrap_or_default().trim();
+
+    match essence {
+        "audio/wav" | "audio/wave" | "audio/x-wav" | "audio/vnd.wave" => decode_wav(data),
+        _ => bail!("Audio of type `{essence}` can't be decoded, only WAV is supported"),
+    }
+}
+
+/// Decode WAV data into mono samples at [`SAMPLE_RATE`].
+pub fn decode_wav(data: &[u8]) -> Result<Vec<f32>> {
+    let reader = WavReader::new(Cursor::new(data)).context("Reading WAV header")?;
+    let spec = reader.spec();
+
+    ensure!(
+        spec.channels > 0 && spec.sample_rate > 0,
+        "WAV data has no channels or sample rate"
+    );
+
+    ensure!(
+        reader.duration() / spec.sample_rate <= MAX_SECONDS,
+        "Audio is longer than {MAX_SECONDS} seconds"
+    );
+
+    let samples = match spec.sample_format {
+        SampleFormat::Float => reader
+            .into_samples::<f32>()
+            .collect::<Result<Vec<_>, _>>()?,
+        SampleFormat::Int => {
+            let scale = 1.0 / (1u64 << (spec.bits_per_sample.clamp(1, 32) - 1)) as f32;
+
+            reader
+                .into_samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 * scale))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+    };
+
+    let samples = mix_down(samples, usize::from(spec.channels));
+    Ok(resample(samples, spec.sample_rate, SAMPLE_RATE))
+}
+
+/// Mix interleaved samples down to a single channel.
+fn mix_down(samples: Vec<f32>, channels: usize) -> Vec<f32> {
+    if channels == 1 {
+        return samples;
+    }
+
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Resample by interpolating linearly between samples, which is good enough
+/// for speech.
+fn resample(samples: Vec<f32>, from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples;
+    }
+
+    let len = (samples.len() as u64 * u64::from(to) / u64::from(from)) as usize;
+    let step = f64::from(from) / f64::from(to);
+
+    (0..len)
+        .map(|n| {
+            let at = n as f64 * step;
+            let index = at as usize;
+            let fraction = (at - index as f64) as f32;
+            let a = samples[index];
+            let b = samples.get(index + 1).copied().unwrap_or(a);
+            a + (b - a) * fraction
+        })
+        .collect()
+}
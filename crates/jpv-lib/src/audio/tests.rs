@@ -0,0 +1,57 @@
+use std::io::Cursor;
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+
+use super::{decode, decode_wav, SAMPLE_RATE};
+
+fn wav(spec: WavSpec, frames: usize, sample: impl Fn(usize, u16) -> i16) -> Vec<u8> {
+    let mut data = Cursor::new(Vec::new());
+    let mut writer = WavWriter::new(&mut data, spec).unwrap();
+
+    for n in 0..frames {
+        for channel in 0..spec.channels {
+            writer.write_sample(sample(n, channel)).unwrap();
+        }
+    }
+
+    writer.finalize().unwrap();
+    data.into_inner()
+}
+
+#[test]
+fn mono_at_sample_rate() {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+
+    let data = wav(spec, 4, |n, _| [0, 16384, -16384, i16::MIN][n]);
+    assert_eq!(decode_wav(&data).unwrap(), [0.0, 0.5, -0.5, -1.0]);
+}
+
+#[test]
+fn stereo_is_mixed_and_resampled() {
+    let spec = WavSpec {
+        channels: 2,
+        sample_rate: SAMPLE_RATE * 2,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+
+    // The left channel is silent, so the mix is half of the right one.
+    let data = wav(spec, 8, |n, channel| match channel {
+        0 => 0,
+        _ => n as i16 * 4096,
+    });
+
+    let samples = decode("audio/wav", &data).unwrap();
+    assert_eq!(samples, [0.0, 0.125, 0.25, 0.375]);
+}
+
+#[test]
+fn unsupported() {
+    assert!(decode("audio/ogg", b"OggS").is_err());
+    assert!(decode("audio/x-wav", b"not a wav file").is_err());
+}
@@ -0,0 +1,198 @@
+//! Handling of captured text before it's delivered.
+//!
+//! Captured text is passed through filters, where each rule is a regular
+//! expression which either replaces what it matches, or drops captures which
+//! match it entirely. Rules are applied in order, so later rules see the text
+//! produced by earlier ones.
+//!
+//! Lines captured as JSON are validated here as well, see [`parse_json`].
+
+#[cfg(test)]
+mod tests;
+
+use std::borrow::Cow;
+
+use anyhow::{ensure, Context, Result};
+use regex::{Regex, RegexBuilder};
+
+use crate::api::CaptureJson;
+use crate::config::CaptureFilter;
+
+/// The largest compiled size of a pattern.
+const SIZE_LIMIT: usize = 1 << 20;
+
+/// The maximum number of bytes in the text or the translation of a line
+/// captured as JSON.
+pub const MAX_LINE: usize = 16 * 1024;
+
+/// What a rule does with the text it matches.
+#[derive(Debug)]
+enum Action {
+    Replace(String),
+    Drop,
+}
+
+#[derive(Debug)]
+struct Rule {
+    regex: Regex,
+    action: Action,
+}
+
+/// Compiled capture filters.
+#[derive(Debug, Default)]
+pub struct Filters {
+    rules: Vec<Rule>,
+}
+
+impl Filters {
+    /// Compile the enabled rules among the given filters.
+    pub fn new(filters: &[CaptureFilter]) -> Result<Self> {
+        let mut rules = Vec::new();
+
+        for (index, filter) in filters.iter().enumerate() {
+            if !filter.enabled {
+                continue;
+            }
+
+            let rule = compile(filter).with_context(|| describe(index, filter))?;
+            rules.push(rule);
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Test if there are no rules to apply.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Apply the rules to captured text, returning `None` if the capture
+    /// should be dropped.
+    ///
+    /// The filtered text is trimmed, and captures which are left empty are
+    /// dropped too.
+    pub fn apply<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
+        if self.rules.is_empty() {
+            return Some(Cow::Borrowed(text));
+        }
+
+        let mut text = Cow::Borrowed(text);
+
+        for rule in &self.rules {
+            text = match &rule.action {
+                Action::Drop if rule.regex.is_match(&text) => return None,
+                Action::Drop => text,
+                Action::Replace(replace) => match rule.regex.replace_all(&text, replace.as_str()) {
+                    Cow::Borrowed(..) => text,
+                    Cow::Owned(replaced) => Cow::Owned(replaced),
+                },
+            };
+        }
+
+        let trimmed = text.trim();
+
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        Some(match text {
+            Cow::Borrowed(text) => Cow::Borrowed(text.trim()),
+            Cow::Owned(text) if text.len() == trimmed.len() => Cow::Owned(text),
+            Cow::Owned(text) => Cow::Owned(text.trim().to_owned()),
+        })
+    }
+}
+
+/// The outcome of previewing filters on a sample of text.
+#[derive(Debug, Default)]
+pub struct Preview {
+    /// The filtered text, or `None` if the capture would be dropped.
+    pub text: Option<String>,
+    /// Rules which couldn't be compiled, by index and error, which are
+    /// skipped.
+    pub errors: Vec<(usize, String)>,
+}
+
+/// Preview what the given filters do to a sample of captured text.
+///
+/// Unlike [`Filters::new`], rules which can't be compiled are reported
+/// rather than failing, so that the others can still be tried out while
+/// one is being written.
+pub fn preview(filters: &[CaptureFilter], text: &str) -> Preview {
+    let mut rules = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, filter) in filters.iter().enumerate() {
+        if !filter.enabled {
+            continue;
+        }
+
+        match compile(filter) {
+            Ok(rule) => rules.push(rule),
+            Err(error) => errors.push((index, error.to_string())),
+        }
+    }
+
+    let filters = Filters { rules };
+
+    Preview {
+        text: filters.apply(text).map(Cow::into_owned),
+        errors,
+    }
+}
+
+/// Parse and validate a line captured as JSON.
+///
+/// The text and the translation are trimmed, and empty translations and
+/// sources are treated as missing. Lines without text are rejected.
+pub fn parse_json(data: &[u8]) -> Result<CaptureJson> {
+    let mut json =
+        serde_json::from_slice::<CaptureJson>(data).context("Malformed captured line")?;
+
+    json.text = json.text.trim().to_owned();
+    json.translation = non_empty(json.translation);
+    json.source = non_empty(json.source);
+
+    ensure!(!json.text.is_empty(), "Captured line has no text");
+
+    ensure!(
+        json.text.len() <= MAX_LINE,
+        "Captured line is longer than {MAX_LINE} bytes"
+    );
+
+    if let Some(translation) = &json.translation {
+        ensure!(
+            translation.len() <= MAX_LINE,
+            "Translation is longer than {MAX_LINE} bytes"
+        );
+    }
+
+    Ok(json)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    let value = value?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+fn compile(filter: &CaptureFilter) -> Result<Rule> {
+    let regex = RegexBuilder::new(&filter.pattern)
+        .size_limit(SIZE_LIMIT)
+        .build()?;
+
+    let action = match &filter.replace {
+        Some(replace) => Action::Replace(replace.clone()),
+        None => Action::Drop,
+    };
+
+    Ok(Rule { regex, action })
+}
+
+fn describe(index: usize, filter: &CaptureFilter) -> String {
+    if filter.name.is_empty() {
+        format!("Capture filter #{}", index + 1)
+    } else {
+        format!("Capture filter `{}`", filter.name)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::api::CaptureJson;
+use crate::config::CaptureFilter;
+
+use super::{parse_json, preview, Filters, MAX_LINE};
+
+fn filter(pattern: &str, replace: Option<&str>) -> CaptureFilter {
+    CaptureFilter {
+        pattern: pattern.to_owned(),
+        replace: replace.map(str::to_owned),
+        ..CaptureFilter::default()
+    }
+}
+
+#[test]
+fn apply() {
+    let filters = Filters::new(&[
+        filter(r"^【[^】]*】", Some("")),
+        filter(r"\\[a-z]\d*", Some("")),
+        filter(r"^SYSTEM:", None),
+        filter(r"(?<name>\S+)「(?<line>.*)」", Some("「$line」")),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        filters.apply("【太郎】こんにちは\\n1").as_deref(),
+        Some("こんにちは")
+    );
+    assert_eq!(
+        filters.apply("花子「元気？」").as_deref(),
+        Some("「元気？」")
+    );
+    assert_eq!(filters.apply(" そのまま ").as_deref(), Some("そのまま"));
+    assert_eq!(filters.apply("SYSTEM: saved"), None);
+    assert_eq!(filters.apply("【太郎】 "), None);
+
+    let empty = Filters::new(&[]).unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.apply(" as is ").as_deref(), Some(" as is "));
+}
+
+#[test]
+fn disabled() {
+    let mut rule = filter("[", None);
+    assert!(Filters::new(&[rule.clone()]).is_err());
+
+    rule.enabled = false;
+    let filters = Filters::new(&[rule]).unwrap();
+    assert!(filters.is_empty());
+}
+
+#[test]
+fn previews() {
+    let preview = preview(&[filter("(", Some("")), filter("あ", Some("い"))], "ああ");
+    assert_eq!(preview.text.as_deref(), Some("いい"));
+    assert_eq!(preview.errors.len(), 1);
+    assert_eq!(preview.errors[0].0, 0);
+}
+
+#[test]
+fn json_lines() {
+    let json = parse_json(
+        r#"{"text": " 吾輩は猫である。\n", "translation": "I am a cat.", "source": " game "}"#
+            .as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        json,
+        CaptureJson {
+            text: "吾輩は猫である。".to_owned(),
+            translation: Some("I am a cat.".to_owned()),
+            source: Some("game".to_owned()),
+        }
+    );
+
+    let json = parse_json(r#"{"primary": "猫", "secondary": " "}"#.as_bytes()).unwrap();
+    assert_eq!(json.text, "猫");
+    assert_eq!(json.translation, None);
+
+    assert!(parse_json(b"{}").is_err());
+    assert!(parse_json(br#"{"text": " "}"#).is_err());
+    assert!(parse_json(b"not json").is_err());
+
+    let long = serde_json::json!({ "text": "あ".repeat(MAX_LINE) });
+    assert!(parse_json(long.to_string().as_bytes()).is_err());
+}
@@ -5,11 +5,20 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::Dirs;
+use crate::{Dirs, PartOfSpeechGroup};
 
 const JMDICT_URL: &str = "http://ftp.edrdg.org/pub/Nihongo/JMdict_e_examp.gz";
 const KANJIDIC2_URL: &str = "http://ftp.edrdg.org/pub/Nihongo/kanjidic2.xml.gz";
 const JMNEDICT_URL: &str = "http://ftp.edrdg.org/pub/Nihongo/JMnedict.xml.gz";
+const TANAKA_URL: &str = "http://ftp.edrdg.org/pub/Nihongo/examples.utf.gz";
+const IDS_URL: &str = "https://raw.githubusercontent.com/cjkvi/cjkvi-ids/master/ids.txt";
+
+/// Attribution required for dictionaries from the Electronic Dictionary
+/// Research and Development Group.
+/// The licence of the dictionary files from the EDRDG.
+pub const EDRDG_LICENSE_URL: &str = "https://www.edrdg.org/edrdg/licence.html";
+
+const EDRDG_ATTRIBUTION: &str = "This publication has included material from the dictionary files of the Electronic Dictionary Research and Development Group, used in conformance with the Group's licence. See https://www.edrdg.org/edrdg/licence.html";
 
 #[derive(Debug, Error)]
 #[error("Invalid index format")]
@@ -24,6 +33,42 @@ pub enum IndexFormat {
     Jmdict,
     Jmnedict,
     Kanjidic2,
+    Tanaka,
+    Ids,
+}
+
+impl IndexFormat {
+    /// Identifier of the format, as it's parsed by [`FromStr`].
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Jmdict => "jmdict",
+            Self::Jmnedict => "jmnedict",
+            Self::Kanjidic2 => "kanjidic2",
+            Self::Tanaka => "tanaka",
+            Self::Ids => "ids",
+        }
+    }
+
+    /// Human readable name of the format.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Jmdict => "JMdict",
+            Self::Jmnedict => "JMnedict",
+            Self::Kanjidic2 => "KANJIDIC2",
+            Self::Tanaka => "Tanaka Corpus",
+            Self::Ids => "CJKVI IDS",
+        }
+    }
+
+    /// Attribution required by the license of dictionaries in this format.
+    pub fn attribution(&self) -> Option<&'static str> {
+        match self {
+            Self::Jmdict | Self::Jmnedict | Self::Kanjidic2 | Self::Tanaka => {
+                Some(EDRDG_ATTRIBUTION)
+            }
+            Self::Ids => None,
+        }
+    }
 }
 
 impl FromStr for IndexFormat {
@@ -34,6 +79,8 @@ impl FromStr for IndexFormat {
             "jmdict" => Ok(Self::Jmdict),
             "jmnedict" => Ok(Self::Jmnedict),
             "kanjidic2" => Ok(Self::Kanjidic2),
+            "tanaka" => Ok(Self::Tanaka),
+            "ids" => Ok(Self::Ids),
             _ => Err(IndexFormatError),
         }
     }
@@ -49,10 +96,375 @@ pub struct ConfigIndex {
     pub description: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub help: Option<String>,
+    /// Store entries in compressed blocks, which makes the index smaller at
+    /// the cost of decompressing entries as they are loaded.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub compressed: bool,
 }
 
-/// A configuration used for the application.
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Weights used when ranking search results.
+///
+/// Boosts multiply the weight of an entry when it applies, while scales
+/// adjust how strongly the corresponding boost is applied where `0.0`
+/// disables it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ranking {
+    /// Scale of the boost from priority tags, like `ichi1` or `news1`,
+    /// including the bonus for words which appear in several ranking lists.
+    pub priority: f32,
+    /// Scale of the boost from word frequency tags, like `nf01`.
+    pub frequency: f32,
+    /// Boost when the input matches a kanji element.
+    pub exact_kanji: f32,
+    /// Boost when the input matches the reading of a word which is usually
+    /// written in kana.
+    pub exact_kana: f32,
+    /// Boost when the input matches the reading of a word which is usually
+    /// written in kanji.
+    pub exact_reading: f32,
+    /// Boost when the input matches a gloss.
+    pub exact_gloss: f32,
+    /// Boost for entries which were found through an inflection.
+    pub inflection: f32,
+    /// Boost for entries with a sense in the given part of speech group.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub parts_of_speech: BTreeMap<PartOfSpeechGroup, f32>,
+}
+
+impl Ranking {
+    /// Test if this is the default ranking.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The boost for an entry with senses in the given part of speech
+    /// groups, which is the largest boost among them.
+    pub fn part_of_speech_boost<I>(&self, groups: I) -> f32
+    where
+        I: IntoIterator<Item = PartOfSpeechGroup>,
+    {
+        if self.parts_of_speech.is_empty() {
+            return 1.0;
+        }
+
+        groups
+            .into_iter()
+            .map(|group| self.parts_of_speech.get(&group).copied().unwrap_or(1.0))
+            .reduce(f32::max)
+            .unwrap_or(1.0)
+    }
+}
+
+impl Default for Ranking {
+    fn default() -> Self {
+        Self {
+            priority: 1.0,
+            frequency: 1.0,
+            exact_kanji: 3.0,
+            exact_kana: 3.0,
+            exact_reading: 2.0,
+            exact_gloss: 1.5,
+            inflection: 1.2,
+            parts_of_speech: BTreeMap::new(),
+        }
+    }
+}
+
+/// An external program which is run by the service, like a text-to-speech
+/// engine.
+///
+/// These are only ever taken from the configuration file, see
+/// [`Config::keep_commands`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalCommand {
+    /// The program to run.
+    pub command: String,
+    /// Arguments to the program.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
+impl ExternalCommand {
+    /// Parse a command line, like `mecab -d /usr/lib/unidic`.
+    ///
+    /// Returns `None` if the command line is empty.
+    pub fn parse(command_line: &str) -> Option<Self> {
+        let mut it = command_line.split_whitespace();
+        let command = it.next()?.to_owned();
+
+        Some(Self {
+            command,
+            args: it.map(str::to_owned).collect(),
+        })
+    }
+
+    /// Format the command as a command line.
+    pub fn command_line(&self) -> String {
+        let mut out = self.command.clone();
+
+        for arg in &self.args {
+            out.push(' ');
+            out.push_str(arg);
+        }
+
+        out
+    }
+}
+
+/// An external text-to-speech engine.
+///
+/// The engine is a program which is passed the text to read through its
+/// arguments and writes the synthesized audio to its standard output, like
+/// `espeak-ng -v ja --stdout {text}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tts {
+    /// The program to run, where [`Tts::TEXT`] in its arguments is replaced
+    /// with the text to read.
+    #[serde(flatten)]
+    pub external: ExternalCommand,
+    /// The content type of the produced audio.
+    #[serde(default = "default_tts_mime")]
+    pub mime: String,
+}
+
+impl Tts {
+    /// Placeholder for the text to read in arguments.
+    pub const TEXT: &'static str = "{text}";
+
+    /// Parse an engine from a command line, like `espeak-ng --stdout {text}`.
+    ///
+    /// Returns `None` if the command line is empty.
+    pub fn parse(command_line: &str, mime: Option<&str>) -> Option<Self> {
+        Some(Self {
+            external: ExternalCommand::parse(command_line)?,
+            mime: mime.map_or_else(default_tts_mime, str::to_owned),
+        })
+    }
+
+    /// Format the engine as a command line.
+    pub fn command_line(&self) -> String {
+        self.external.command_line()
+    }
+
+    /// The arguments to pass to the program to read the given text.
+    ///
+    /// If no argument contains [`Tts::TEXT`], the text is passed as the last
+    /// argument.
+    pub fn args_for(&self, text: &str) -> Vec<String> {
+        let mut args = self
+            .external
+            .args
+            .iter()
+            .map(|arg| arg.replace(Self::TEXT, text))
+            .collect::<Vec<_>>();
+
+        if !self
+            .external
+            .args
+            .iter()
+            .any(|arg| arg.contains(Self::TEXT))
+        {
+            args.push(text.to_owned());
+        }
+
+        args
+    }
+}
+
+/// An external morphological analyzer used to split text into words, like
+/// `mecab` with the UniDic dictionary.
+///
+/// Text is written to the standard input of the program, which writes the
+/// words in MeCab's default format. See [`crate::tokenizer`].
+pub type Tokenizer = ExternalCommand;
+
+/// An external OCR engine used instead of tesseract, like a wrapper around
+/// [manga-ocr](https://github.com/kha-white/manga-ocr).
+///
+/// The image to recognize is written to the standard input of the program as
+/// PNG, and it writes what it recognized to its standard output. See
+/// [`crate::ocr`] for how engines can report how confident they are.
+pub type OcrEngine = ExternalCommand;
+
+/// How captured audio is transcribed, which requires the service to be built
+/// with the `whisper` feature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transcription {
+    /// Path to a whisper.cpp model in the ggml format, like
+    /// `ggml-small.bin`.
+    pub model: String,
+}
+
+/// How usage statistics are collected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    /// Whether activity is counted.
+    pub enabled: bool,
+    /// The number of days statistics are kept for, where `0` keeps them
+    /// forever.
+    pub retention: u32,
+}
+
+impl Statistics {
+    /// Test if these are the default settings.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention: 365,
+        }
+    }
+}
+
+/// A rule applied to captured text before it's delivered, like removing the
+/// speaker names and control codes which game hooks include.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureFilter {
+    /// Whether the rule is applied.
+    pub enabled: bool,
+    /// What the rule is for.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// The regular expression matched against captured text.
+    pub pattern: String,
+    /// What matches are replaced with, where `$1` or `${name}` refer to
+    /// groups in the pattern. Without a replacement, captures which match
+    /// are dropped entirely.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replace: Option<String>,
+}
+
+impl Default for CaptureFilter {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            name: String::new(),
+            pattern: String::new(),
+            replace: None,
+        }
+    }
+}
+
+/// Where AnkiConnect listens by default.
+pub const ANKI_CONNECT_URL: &str = "http://127.0.0.1:8765";
+
+/// A part of a card which is written to a field of an Anki note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnkiPart {
+    /// The word as it's written in the dictionary.
+    Word,
+    /// The reading of the word.
+    Reading,
+    /// Meanings of the word.
+    Meaning,
+    /// The sentence the word was mined from, with the word in bold.
+    Sentence,
+    /// The image the sentence was captured from.
+    Image,
+}
+
+impl AnkiPart {
+    /// All parts, in the order they're written when several are written to
+    /// the same field.
+    pub const ALL: [AnkiPart; 5] = [
+        AnkiPart::Word,
+        AnkiPart::Reading,
+        AnkiPart::Meaning,
+        AnkiPart::Sentence,
+        AnkiPart::Image,
+    ];
+
+    /// A stable identifier for the part.
+    pub fn ident(&self) -> &'static str {
+        match self {
+            AnkiPart::Word => "word",
+            AnkiPart::Reading => "reading",
+            AnkiPart::Meaning => "meaning",
+            AnkiPart::Sentence => "sentence",
+            AnkiPart::Image => "image",
+        }
+    }
+}
+
+/// How notes are added to Anki through AnkiConnect.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Anki {
+    /// Whether entries and mined sentences can be added to Anki.
+    pub enabled: bool,
+    /// The address AnkiConnect listens on.
+    pub url: String,
+    /// The deck notes are added to.
+    pub deck: String,
+    /// The note type notes are added as.
+    pub model: String,
+    /// The field of the note type each part of a card is written to. Parts
+    /// which aren't mapped are left out, and parts mapped to the same field
+    /// are separated by line breaks.
+    pub fields: BTreeMap<AnkiPart, String>,
+    /// Tags added to every note.
+    pub tags: Vec<String>,
+}
+
+impl Anki {
+    /// Test if these are the default settings.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The field the given part is written to.
+    pub fn field(&self, part: AnkiPart) -> Option<&str> {
+        let field = self.fields.get(&part)?.trim();
+        (!field.is_empty()).then_some(field)
+    }
+}
+
+impl Default for Anki {
+    /// Notes of the `Basic` type, which every Anki collection has, with the
+    /// word on the front and everything else on the back.
+    fn default() -> Self {
+        let fields = AnkiPart::ALL
+            .into_iter()
+            .map(|part| {
+                let field = match part {
+                    AnkiPart::Word => "Front",
+                    _ => "Back",
+                };
+
+                (part, field.to_owned())
+            })
+            .collect();
+
+        Self {
+            enabled: false,
+            url: ANKI_CONNECT_URL.to_owned(),
+            deck: String::from("Default"),
+            model: String::from("Basic"),
+            fields,
+            tags: vec![String::from("jpv")],
+        }
+    }
+}
+
+fn default_tts_mime() -> String {
+    String::from("audio/wav")
+}
+
+/// A configuration used for the application.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Config {
     /// Enabled indexes.
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
@@ -60,12 +472,58 @@ pub struct Config {
     /// Whether OCR support is enabled or not.
     #[serde(default = "default_ocr")]
     pub ocr: bool,
+    /// The engine used for OCR instead of tesseract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_engine: Option<OcrEngine>,
+    /// Directory through which profiles are synchronized with other
+    /// installs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_dir: Option<String>,
+    /// Weights used when ranking search results.
+    #[serde(default, skip_serializing_if = "Ranking::is_default")]
+    pub ranking: Ranking,
+    /// Engine used to read text out loud.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tts: Option<Tts>,
+    /// Morphological analyzer used to split text into words, instead of only
+    /// matching the longest phrases found in the dictionary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokenizer: Option<Tokenizer>,
+    /// Speech recognition used to transcribe captured audio.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcription: Option<Transcription>,
+    /// How usage statistics are collected.
+    #[serde(default, skip_serializing_if = "Statistics::is_default")]
+    pub stats: Statistics,
+    /// Open the interface in the default browser when the service starts.
+    #[serde(default = "default_open_browser")]
+    pub open_browser: bool,
+    /// Periodically check for new releases of the service.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub check_updates: bool,
+    /// Whether the first-run setup has been completed or skipped.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub onboarded: bool,
+    /// How notes are added to Anki.
+    #[serde(default, skip_serializing_if = "Anki::is_default")]
+    pub anki: Anki,
+    /// Rules applied in order to captured text.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capture_filters: Vec<CaptureFilter>,
+    /// Applications whose captures are ignored, like the browser the
+    /// dictionary is open in. Compared without regard to case.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored_sources: Vec<String>,
 }
 
 fn default_ocr() -> bool {
     true
 }
 
+fn default_open_browser() -> bool {
+    true
+}
+
 impl Config {
     pub fn load(dirs: &Dirs) -> Result<Self> {
         let config_path = dirs.config_path();
@@ -95,6 +553,25 @@ impl Config {
 
         index.enabled
     }
+
+    /// Test if captures from the given source are ignored.
+    pub fn is_ignored_source(&self, source: &str) -> bool {
+        self.ignored_sources
+            .iter()
+            .any(|ignored| ignored.trim().eq_ignore_ascii_case(source.trim()))
+    }
+
+    /// Keep the external programs of the `current` configuration, replacing
+    /// any in this one.
+    ///
+    /// Configuration updated through the service can come from any web page
+    /// the browser visits, so the programs it runs are only ever set in the
+    /// configuration file.
+    pub fn keep_commands(&mut self, current: &Config) {
+        self.tts.clone_from(&current.tts);
+        self.tokenizer.clone_from(&current.tokenizer);
+        self.ocr_engine.clone_from(&current.ocr_engine);
+    }
 }
 
 impl Default for Config {
@@ -112,6 +589,7 @@ impl Default for Config {
                     "https://www.edrdg.org/wiki/index.php/JMdict-EDICT_Dictionary_Project"
                         .to_owned(),
                 ),
+                compressed: false,
             },
         );
 
@@ -126,6 +604,7 @@ impl Default for Config {
                     "https://www.edrdg.org/wiki/index.php/Main_Page#The_ENAMDICT/JMnedict_Project"
                         .to_owned(),
                 ),
+                compressed: false,
             },
         );
 
@@ -137,9 +616,50 @@ impl Default for Config {
                 enabled: true,
                 description: Some("Kanji from Kanjidic2".to_owned()),
                 help: Some("https://www.edrdg.org/wiki/index.php/KANJIDIC_Project".to_owned()),
+                compressed: false,
+            },
+        );
+
+        indexes.insert(
+            "tanaka".to_owned(),
+            ConfigIndex {
+                format: IndexFormat::Tanaka,
+                url: TANAKA_URL.to_owned(),
+                enabled: false,
+                description: Some("Sense-tagged examples from the Tanaka corpus".to_owned()),
+                help: Some("https://www.edrdg.org/wiki/index.php/Tanaka_Corpus".to_owned()),
+                compressed: false,
+            },
+        );
+
+        indexes.insert(
+            "ids".to_owned(),
+            ConfigIndex {
+                format: IndexFormat::Ids,
+                url: IDS_URL.to_owned(),
+                enabled: true,
+                description: Some("Kanji components from the CJKVI IDS database".to_owned()),
+                help: Some("https://github.com/cjkvi/cjkvi-ids".to_owned()),
+                compressed: false,
             },
         );
 
-        Self { indexes, ocr: true }
+        Self {
+            indexes,
+            ocr: true,
+            ocr_engine: None,
+            sync_dir: None,
+            ranking: Ranking::default(),
+            tts: None,
+            tokenizer: None,
+            transcription: None,
+            stats: Statistics::default(),
+            open_browser: true,
+            check_updates: false,
+            onboarded: false,
+            anki: Anki::default(),
+            capture_filters: Vec::new(),
+            ignored_sources: Vec::new(),
+        }
     }
 }
@@ -0,0 +1,654 @@
+//! Counter words (助数詞) and how numbers are read together with them.
+//!
+//! Counters are suggested for nouns based on the words used in their
+//! glossary. Numbers are read together with a counter by applying the
+//! euphonic changes which happen where the two meet, such as gemination in
+//! いっぽん or voicing in さんぼん.
+
+#[cfg(test)]
+mod tests;
+
+use std::cmp::Reverse;
+
+use crate::jmdict;
+use crate::PartOfSpeechGroup;
+
+/// The largest number which can be read.
+const MAX_NUMBER: u32 = 99_999_999;
+
+/// How the first sound of a counter changes after a number.
+#[derive(Debug, Clone, Copy)]
+enum Sound {
+    /// The counter never changes.
+    Plain,
+    /// Counters starting with a k-sound, like こ.
+    K,
+    /// Counters starting with an s-sound, like さつ.
+    S,
+    /// Counters starting with a t-sound, like とう.
+    T,
+    /// Counters starting with an h-sound, like ほん, which turn into a p-sound
+    /// after a geminated number.
+    H,
+}
+
+/// How the first sound of a counter changes after a number ending in ん.
+#[derive(Debug, Clone, Copy)]
+enum AfterN {
+    Keep,
+    Voiced,
+    SemiVoiced,
+}
+
+/// A counter word.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Counter {
+    /// How the counter is written.
+    pub text: &'static str,
+    /// The reading of the counter on its own.
+    pub reading: &'static str,
+    /// What the counter is used to count.
+    pub description: &'static str,
+    sound: Sound,
+    after_n: AfterN,
+    /// Irregular readings of whole numbers.
+    exact: &'static [(u32, &'static str)],
+    /// Irregular readings of the last unit of a number together with the
+    /// counter.
+    tails: &'static [(u32, &'static str)],
+    /// The largest number the counter is used with.
+    limit: u32,
+    /// The head words of glossary entries for nouns counted with this
+    /// counter.
+    keywords: &'static [&'static str],
+}
+
+impl Counter {
+    const fn new(text: &'static str, reading: &'static str, description: &'static str) -> Self {
+        Self {
+            text,
+            reading,
+            description,
+            sound: Sound::Plain,
+            after_n: AfterN::Keep,
+            exact: &[],
+            tails: &[],
+            limit: MAX_NUMBER,
+            keywords: &[],
+        }
+    }
+
+    const fn sound(mut self, sound: Sound, after_n: AfterN) -> Self {
+        self.sound = sound;
+        self.after_n = after_n;
+        self
+    }
+
+    const fn exact(mut self, exact: &'static [(u32, &'static str)]) -> Self {
+        self.exact = exact;
+        self
+    }
+
+    const fn tails(mut self, tails: &'static [(u32, &'static str)]) -> Self {
+        self.tails = tails;
+        self
+    }
+
+    const fn limit(mut self, limit: u32) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    const fn keywords(mut self, keywords: &'static [&'static str]) -> Self {
+        self.keywords = keywords;
+        self
+    }
+
+    /// Read the given number together with the counter, or `None` if the
+    /// counter isn't used with the number.
+    pub fn reading_of(&self, number: u32) -> Option<String> {
+        if number == 0 || number > self.limit {
+            return None;
+        }
+
+        if let Some(reading) = lookup(self.exact, number) {
+            return Some(reading.to_owned());
+        }
+
+        let mut units = units(number)?;
+        let last = units.pop()?;
+
+        let mut output = String::new();
+
+        for unit in &units {
+            output.push_str(unit.lead);
+            output.push_str(unit.word);
+        }
+
+        output.push_str(last.lead);
+
+        if let Some(tail) = lookup(self.tails, last.value) {
+            output.push_str(tail);
+            return Some(output);
+        }
+
+        let mut counter = self.reading.chars();
+
+        let Some(first) = counter.next() else {
+            output.push_str(last.word);
+            return Some(output);
+        };
+
+        let first = if self.is_geminated(last.value) {
+            output.push_str(geminate(last.word));
+
+            match self.sound {
+                Sound::H => semi_voiced(first),
+                _ => first,
+            }
+        } else {
+            output.push_str(last.word);
+
+            // よん doesn't cause voicing, unlike other numbers ending in ん.
+            if last.word.ends_with('ん') && last.value != 4 {
+                match self.after_n {
+                    AfterN::Keep => first,
+                    AfterN::Voiced => voiced(first),
+                    AfterN::SemiVoiced => semi_voiced(first),
+                }
+            } else {
+                first
+            }
+        };
+
+        output.push(first);
+        output.push_str(counter.as_str());
+        Some(output)
+    }
+
+    fn is_geminated(&self, value: u32) -> bool {
+        match (self.sound, value) {
+            (Sound::Plain, _) => false,
+            (_, 1 | 8 | 10) => true,
+            (Sound::K | Sound::H, 6 | 100) => true,
+            _ => false,
+        }
+    }
+}
+
+static COUNTERS: &[Counter] = &[
+    Counter::new("本", "ほん", "long, thin objects")
+        .sound(Sound::H, AfterN::Voiced)
+        .keywords(&[
+            "pencil",
+            "pen",
+            "bottle",
+            "umbrella",
+            "tree",
+            "stick",
+            "rope",
+            "string",
+            "road",
+            "river",
+            "necktie",
+            "banana",
+            "carrot",
+            "cigarette",
+            "candle",
+            "needle",
+            "pole",
+            "cable",
+            "hair",
+            "tail",
+            "film",
+            "movie",
+            "sword",
+            "knife",
+        ]),
+    Counter::new("匹", "ひき", "small animals, fish and insects")
+        .sound(Sound::H, AfterN::Voiced)
+        .keywords(&[
+            "dog",
+            "cat",
+            "fish",
+            "insect",
+            "mouse",
+            "rat",
+            "frog",
+            "snake",
+            "bug",
+            "monkey",
+            "fox",
+            "puppy",
+            "kitten",
+            "ant",
+            "bee",
+            "butterfly",
+            "mosquito",
+            "fly",
+            "spider",
+            "squirrel",
+            "carp",
+            "goldfish",
+            "worm",
+            "crab",
+            "lizard",
+        ]),
+    Counter::new("頭", "とう", "large animals")
+        .sound(Sound::T, AfterN::Keep)
+        .keywords(&[
+            "horse", "cow", "cattle", "elephant", "whale", "bear", "lion", "tiger", "giraffe",
+            "ox", "bull", "camel", "deer", "pig", "sheep", "goat", "dolphin",
+        ]),
+    Counter::new("羽", "わ", "birds and rabbits")
+        .tails(&[
+            (3, "さんば"),
+            (6, "ろっぱ"),
+            (8, "はっぱ"),
+            (10, "じゅっぱ"),
+        ])
+        .keywords(&[
+            "bird", "chicken", "rabbit", "hen", "crow", "sparrow", "pigeon", "dove", "duck",
+            "swallow", "owl", "eagle", "hawk", "crane", "parrot",
+        ]),
+    Counter::new("枚", "まい", "flat objects").keywords(&[
+        "paper",
+        "sheet",
+        "shirt",
+        "plate",
+        "dish",
+        "photo",
+        "photograph",
+        "picture",
+        "ticket",
+        "stamp",
+        "card",
+        "postcard",
+        "leaf",
+        "coin",
+        "towel",
+        "blanket",
+        "board",
+        "handkerchief",
+        "napkin",
+        "envelope",
+        "page",
+    ]),
+    Counter::new("台", "だい", "machines and vehicles").keywords(&[
+        "car",
+        "automobile",
+        "machine",
+        "computer",
+        "television",
+        "bicycle",
+        "vehicle",
+        "truck",
+        "piano",
+        "camera",
+        "printer",
+        "telephone",
+        "bed",
+        "refrigerator",
+        "motorcycle",
+    ]),
+    Counter::new("冊", "さつ", "books and bound volumes")
+        .sound(Sound::S, AfterN::Keep)
+        .keywords(&[
+            "book",
+            "notebook",
+            "magazine",
+            "dictionary",
+            "volume",
+            "textbook",
+            "novel",
+            "album",
+            "diary",
+            "manga",
+        ]),
+    Counter::new("軒", "けん", "houses and shops")
+        .sound(Sound::K, AfterN::Voiced)
+        .keywords(&["house", "shop", "store", "restaurant", "inn", "home", "bar"]),
+    Counter::new("人", "にん", "people")
+        .exact(&[(1, "ひとり"), (2, "ふたり")])
+        .tails(&[(4, "よにん")])
+        .keywords(&[
+            "person", "people", "student", "child", "man", "woman", "teacher", "friend", "adult",
+            "customer", "doctor", "worker", "member", "employee", "guest",
+        ]),
+    Counter::new("杯", "はい", "cups, glasses and bowls of something")
+        .sound(Sound::H, AfterN::Voiced)
+        .keywords(&[
+            "cup", "glass", "bowl", "coffee", "tea", "beer", "drink", "wine", "sake",
+        ]),
+    Counter::new("着", "ちゃく", "suits and dresses")
+        .sound(Sound::T, AfterN::Keep)
+        .keywords(&[
+            "suit", "dress", "coat", "jacket", "kimono", "clothes", "uniform",
+        ]),
+    Counter::new("足", "そく", "pairs of footwear")
+        .sound(Sound::S, AfterN::Voiced)
+        .keywords(&["shoe", "sock", "boot", "sandal", "slipper", "stocking"]),
+    Counter::new("通", "つう", "letters and messages")
+        .sound(Sound::T, AfterN::Keep)
+        .keywords(&["letter", "mail", "email", "message", "telegram"]),
+    Counter::new("脚", "きゃく", "chairs and desks")
+        .sound(Sound::K, AfterN::Keep)
+        .keywords(&["chair", "desk", "table", "stool"]),
+    Counter::new("件", "けん", "cases and matters")
+        .sound(Sound::K, AfterN::Keep)
+        .keywords(&["case", "incident", "matter", "accident", "affair", "crime"]),
+    Counter::new("回", "かい", "times and occurrences")
+        .sound(Sound::K, AfterN::Keep)
+        .keywords(&["occurrence", "round", "episode"]),
+    Counter::new("階", "かい", "floors of a building")
+        .sound(Sound::K, AfterN::Voiced)
+        .keywords(&["floor", "story", "storey"]),
+    Counter::new("歳", "さい", "years of age")
+        .sound(Sound::S, AfterN::Keep)
+        .exact(&[(20, "はたち")])
+        .keywords(&["age"]),
+    Counter::new("分", "ふん", "minutes")
+        .sound(Sound::H, AfterN::SemiVoiced)
+        .tails(&[(4, "よんぷん")])
+        .keywords(&["minute"]),
+    Counter::new("日", "にち", "days")
+        .exact(&[
+            (2, "ふつか"),
+            (3, "みっか"),
+            (5, "いつか"),
+            (6, "むいか"),
+            (7, "なのか"),
+            (8, "ようか"),
+            (9, "ここのか"),
+            (10, "とおか"),
+            (20, "はつか"),
+        ])
+        .tails(&[(4, "よっか")])
+        .keywords(&["day"]),
+    Counter::new("か月", "かげつ", "months")
+        .sound(Sound::K, AfterN::Keep)
+        .keywords(&["month"]),
+    Counter::new("年", "ねん", "years")
+        .tails(&[(4, "よねん")])
+        .keywords(&["year"]),
+    Counter::new("個", "こ", "small, compact objects")
+        .sound(Sound::K, AfterN::Keep)
+        .keywords(&[
+            "apple", "egg", "ball", "box", "stone", "candy", "orange", "button", "piece", "fruit",
+            "tomato", "potato", "onion", "peach", "lemon", "cake",
+        ]),
+    Counter::new("つ", "つ", "things in general")
+        .exact(&[
+            (1, "ひとつ"),
+            (2, "ふたつ"),
+            (3, "みっつ"),
+            (4, "よっつ"),
+            (5, "いつつ"),
+            (6, "むっつ"),
+            (7, "ななつ"),
+            (8, "やっつ"),
+            (9, "ここのつ"),
+            (10, "とお"),
+        ])
+        .limit(10),
+];
+
+/// Counters which can be used with most things.
+static GENERAL: &[&str] = &["つ", "個"];
+
+/// Iterate over all known counters.
+pub fn all() -> impl Iterator<Item = &'static Counter> {
+    COUNTERS.iter()
+}
+
+/// Find a counter by how it's written or read.
+pub fn find(text: &str) -> Option<&'static Counter> {
+    COUNTERS
+        .iter()
+        .find(|c| c.text == text)
+        .or_else(|| COUNTERS.iter().find(|c| c.reading == text))
+}
+
+/// Suggest counters to use with the given entry, with the most suitable
+/// counter first.
+///
+/// Entries which are not nouns have no suggestions. Nouns which can't be
+/// matched to a specific counter are suggested general counters.
+pub fn suggest(entry: &jmdict::Entry<'_>) -> Vec<&'static Counter> {
+    let is_noun = entry
+        .senses
+        .iter()
+        .any(|s| s.pos.iter().any(|p| p.group() == PartOfSpeechGroup::Noun));
+
+    if !is_noun {
+        return Vec::new();
+    }
+
+    let mut scores = vec![0usize; COUNTERS.len()];
+
+    for (index, sense) in entry.senses.iter().enumerate() {
+        // Earlier senses are more representative of the phrase.
+        let weight = entry.senses.len() - index;
+
+        for gloss in &sense.gloss {
+            if gloss.ty == Some("expl") {
+                continue;
+            }
+
+            let Some(word) = head_word(gloss.text) else {
+                continue;
+            };
+
+            for (score, counter) in scores.iter_mut().zip(COUNTERS) {
+                if counter.keywords.iter().any(|k| is_keyword(&word, k)) {
+                    *score += weight;
+                }
+            }
+        }
+    }
+
+    let mut output = scores
+        .into_iter()
+        .zip(COUNTERS)
+        .filter(|(score, _)| *score > 0)
+        .collect::<Vec<_>>();
+
+    // Stable sort so that ties are broken by the order of the table.
+    output.sort_by_key(|(score, _)| Reverse(*score));
+
+    let mut output = output.into_iter().map(|(_, c)| c).collect::<Vec<_>>();
+
+    if output.is_empty() {
+        output.extend(GENERAL.iter().filter_map(|text| find(text)));
+    }
+
+    output
+}
+
+/// Get the head word of a glossary entry, which is its last word ignoring
+/// anything in parenthesis.
+fn head_word(text: &str) -> Option<String> {
+    let text = match text.find('(') {
+        Some(n) => &text[..n],
+        None => text,
+    };
+
+    let word = text
+        .split(|c: char| !c.is_alphabetic())
+        .rfind(|w| !w.is_empty())?;
+
+    Some(word.to_lowercase())
+}
+
+/// Test if a word matches a keyword, allowing for simple plurals.
+fn is_keyword(word: &str, keyword: &str) -> bool {
+    if word == keyword {
+        return true;
+    }
+
+    let Some(rest) = word.strip_prefix(keyword) else {
+        return false;
+    };
+
+    matches!(rest, "s" | "es")
+}
+
+fn lookup(table: &'static [(u32, &'static str)], number: u32) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(n, _)| *n == number)
+        .map(|(_, reading)| *reading)
+}
+
+/// A single unit of a number, like the さんびゃく in さんびゃくに.
+#[derive(Debug)]
+struct Unit {
+    /// The multiplier in front of the unit, like さん.
+    lead: &'static str,
+    /// The value of the unit, like 100.
+    value: u32,
+    /// How the unit is read, like びゃく.
+    word: &'static str,
+}
+
+/// Break a number into the units it's read as.
+fn units(number: u32) -> Option<Vec<Unit>> {
+    if number == 0 || number > MAX_NUMBER {
+        return None;
+    }
+
+    let mut output = Vec::new();
+
+    let man = number / 10_000;
+
+    if man > 0 {
+        small_units(man, &mut output);
+        output.push(Unit {
+            lead: "",
+            value: 10_000,
+            word: "まん",
+        });
+    }
+
+    small_units(number % 10_000, &mut output);
+    Some(output)
+}
+
+/// Units of a number below 10000.
+fn small_units(number: u32, output: &mut Vec<Unit>) {
+    const DIGITS: [&str; 10] = [
+        "",
+        "いち",
+        "に",
+        "さん",
+        "よん",
+        "ご",
+        "ろく",
+        "なな",
+        "はち",
+        "きゅう",
+    ];
+
+    let (lead, word) = match number / 1000 % 10 {
+        0 => ("", ""),
+        1 => ("", "せん"),
+        3 => ("さん", "ぜん"),
+        8 => ("はっ", "せん"),
+        n => (DIGITS[n as usize], "せん"),
+    };
+
+    if !word.is_empty() {
+        output.push(Unit {
+            lead,
+            value: 1000,
+            word,
+        });
+    }
+
+    let (lead, word) = match number / 100 % 10 {
+        0 => ("", ""),
+        1 => ("", "ひゃく"),
+        3 => ("さん", "びゃく"),
+        6 => ("ろっ", "ぴゃく"),
+        8 => ("はっ", "ぴゃく"),
+        n => (DIGITS[n as usize], "ひゃく"),
+    };
+
+    if !word.is_empty() {
+        output.push(Unit {
+            lead,
+            value: 100,
+            word,
+        });
+    }
+
+    match number / 10 % 10 {
+        0 => {}
+        n => output.push(Unit {
+            lead: if n == 1 { "" } else { DIGITS[n as usize] },
+            value: 10,
+            word: "じゅう",
+        }),
+    }
+
+    match number % 10 {
+        0 => {}
+        n => output.push(Unit {
+            lead: "",
+            value: n,
+            word: DIGITS[n as usize],
+        }),
+    }
+}
+
+/// Replace the last mora of a number with a geminating っ.
+fn geminate(word: &str) -> &str {
+    match word {
+        "いち" => "いっ",
+        "ろく" => "ろっ",
+        "はち" => "はっ",
+        "じゅう" => "じゅっ",
+        "ひゃく" => "ひゃっ",
+        "びゃく" => "びゃっ",
+        "ぴゃく" => "ぴゃっ",
+        word => word,
+    }
+}
+
+fn voiced(c: char) -> char {
+    match c {
+        'か' => 'が',
+        'き' => 'ぎ',
+        'く' => 'ぐ',
+        'け' => 'げ',
+        'こ' => 'ご',
+        'さ' => 'ざ',
+        'し' => 'じ',
+        'す' => 'ず',
+        'せ' => 'ぜ',
+        'そ' => 'ぞ',
+        'た' => 'だ',
+        'ち' => 'ぢ',
+        'つ' => 'づ',
+        'て' => 'で',
+        'と' => 'ど',
+        'は' => 'ば',
+        'ひ' => 'び',
+        'ふ' => 'ぶ',
+        'へ' => 'べ',
+        'ほ' => 'ぼ',
+        c => c,
+    }
+}
+
+fn semi_voiced(c: char) -> char {
+    match c {
+        'は' => 'ぱ',
+        'ひ' => 'ぴ',
+        'ふ' => 'ぷ',
+        'へ' => 'ぺ',
+        'ほ' => 'ぽ',
+        c => c,
+    }
+}
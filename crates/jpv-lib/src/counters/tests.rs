@@ -0,0 +1,80 @@
+use super::find;
+
+fn reading(counter: &str, number: u32) -> Option<String> {
+    find(counter).and_then(|c| c.reading_of(number))
+}
+
+#[test]
+fn hon() {
+    assert_eq!(reading("本", 1).as_deref(), Some("いっぽん"));
+    assert_eq!(reading("本", 2).as_deref(), Some("にほん"));
+    assert_eq!(reading("本", 3).as_deref(), Some("さんぼん"));
+    assert_eq!(reading("本", 4).as_deref(), Some("よんほん"));
+    assert_eq!(reading("本", 6).as_deref(), Some("ろっぽん"));
+    assert_eq!(reading("本", 8).as_deref(), Some("はっぽん"));
+    assert_eq!(reading("本", 10).as_deref(), Some("じゅっぽん"));
+    assert_eq!(reading("本", 23).as_deref(), Some("にじゅうさんぼん"));
+    assert_eq!(reading("本", 100).as_deref(), Some("ひゃっぽん"));
+    assert_eq!(reading("本", 600).as_deref(), Some("ろっぴゃっぽん"));
+    assert_eq!(reading("本", 1000).as_deref(), Some("せんぼん"));
+    assert_eq!(reading("本", 3000).as_deref(), Some("さんぜんぼん"));
+    assert_eq!(reading("本", 10000).as_deref(), Some("いちまんぼん"));
+}
+
+#[test]
+fn small_animals() {
+    assert_eq!(reading("匹", 1).as_deref(), Some("いっぴき"));
+    assert_eq!(reading("匹", 3).as_deref(), Some("さんびき"));
+    assert_eq!(reading("匹", 6).as_deref(), Some("ろっぴき"));
+    assert_eq!(reading("匹", 7).as_deref(), Some("ななひき"));
+}
+
+#[test]
+fn geminated_rows() {
+    assert_eq!(reading("冊", 1).as_deref(), Some("いっさつ"));
+    assert_eq!(reading("冊", 6).as_deref(), Some("ろくさつ"));
+    assert_eq!(reading("冊", 8).as_deref(), Some("はっさつ"));
+    assert_eq!(reading("冊", 100).as_deref(), Some("ひゃくさつ"));
+    assert_eq!(reading("個", 6).as_deref(), Some("ろっこ"));
+    assert_eq!(reading("個", 100).as_deref(), Some("ひゃっこ"));
+    assert_eq!(reading("頭", 10).as_deref(), Some("じゅっとう"));
+    assert_eq!(reading("階", 3).as_deref(), Some("さんがい"));
+    assert_eq!(reading("階", 4).as_deref(), Some("よんかい"));
+    assert_eq!(reading("分", 3).as_deref(), Some("さんぷん"));
+    assert_eq!(reading("分", 4).as_deref(), Some("よんぷん"));
+    assert_eq!(reading("分", 5).as_deref(), Some("ごふん"));
+}
+
+#[test]
+fn irregular() {
+    assert_eq!(reading("人", 1).as_deref(), Some("ひとり"));
+    assert_eq!(reading("人", 2).as_deref(), Some("ふたり"));
+    assert_eq!(reading("人", 4).as_deref(), Some("よにん"));
+    assert_eq!(reading("人", 11).as_deref(), Some("じゅういちにん"));
+    assert_eq!(reading("人", 14).as_deref(), Some("じゅうよにん"));
+    assert_eq!(reading("日", 14).as_deref(), Some("じゅうよっか"));
+    assert_eq!(reading("日", 20).as_deref(), Some("はつか"));
+    assert_eq!(reading("歳", 20).as_deref(), Some("はたち"));
+    assert_eq!(reading("羽", 3).as_deref(), Some("さんば"));
+    assert_eq!(reading("つ", 8).as_deref(), Some("やっつ"));
+    assert_eq!(reading("つ", 11), None);
+    assert_eq!(reading("本", 0), None);
+}
+
+#[test]
+fn find_by_reading() {
+    assert_eq!(find("ひき").map(|c| c.text), Some("匹"));
+    assert!(find("ぬ").is_none());
+}
+
+#[test]
+fn head_words() {
+    use super::{head_word, is_keyword};
+
+    assert_eq!(head_word("bottle (of sake)").as_deref(), Some("bottle"));
+    assert_eq!(head_word("fountain pen").as_deref(), Some("pen"));
+    assert_eq!(head_word("(something)"), None);
+    assert!(is_keyword("glasses", "glass"));
+    assert!(is_keyword("dogs", "dog"));
+    assert!(!is_keyword("cattle", "cat"));
+}
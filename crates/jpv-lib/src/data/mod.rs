@@ -34,7 +34,17 @@ pub fn open_from_args(indexes: &[PathBuf], dirs: &Dirs) -> Result<Vec<(Data, Loc
     let mut output = Vec::new();
 
     for path in paths {
-        let data = r#impl::open(path)?;
+        // A missing or unreadable index shouldn't prevent the others from
+        // being used.
+        let data = match r#impl::open(path) {
+            Ok(data) => data,
+            Err(error) => {
+                log::error!("Failed to open index {}", path.display());
+                log::error!("Caused by: {}", error);
+                continue;
+            }
+        };
+
         output.push((data, Location::Path(path.as_path().into())));
     }
 
@@ -0,0 +1,152 @@
+//! Optional block-compressed storage for entry payloads.
+//!
+//! When enabled, encoded entries are appended to blocks which are compressed
+//! as they fill up. Entry offsets then address a block in their upper bits and
+//! a position in the decompressed block in their lower bits. Lookup keys and
+//! other tables are unaffected, so only loading entries pays for
+//! decompression.
+
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use musli_zerocopy::{Buf, OwnedBuf, Ref};
+
+use super::stored;
+
+/// Number of bits in an entry offset addressing a position in a block.
+const OFFSET_BITS: u32 = 16;
+/// Blocks are compressed once they reach this size, which ensures that every
+/// entry starts at a position addressable by [`OFFSET_BITS`].
+const BLOCK_SIZE: usize = 1 << (OFFSET_BITS - 1);
+
+/// Stores encoded entries, optionally compressing them in blocks.
+pub(super) struct BlockWriter {
+    compress: bool,
+    current: Vec<u8>,
+    blocks: Vec<stored::Block>,
+    uncompressed: usize,
+    compressed: usize,
+}
+
+impl BlockWriter {
+    pub(super) fn new(compress: bool) -> Self {
+        Self {
+            compress,
+            current: Vec::new(),
+            blocks: Vec::new(),
+            uncompressed: 0,
+            compressed: 0,
+        }
+    }
+
+    /// Store an encoded entry, returning the offset it can be loaded from.
+    pub(super) fn store(&mut self, buf: &mut OwnedBuf, bytes: &[u8]) -> Result<u32> {
+        if !self.compress {
+            return Ok(buf.store_slice(bytes).offset() as u32);
+        }
+
+        if self.current.len() >= BLOCK_SIZE {
+            self.flush(buf);
+        }
+
+        let block = self.blocks.len();
+        ensure!(
+            block < 1 << (u32::BITS - OFFSET_BITS),
+            "Too many compressed blocks"
+        );
+
+        let offset = (block << OFFSET_BITS) | self.current.len();
+        self.current.extend_from_slice(bytes);
+        Ok(offset as u32)
+    }
+
+    fn flush(&mut self, buf: &mut OwnedBuf) {
+        if self.current.is_empty() {
+            return;
+        }
+
+        let data = lz4_flex::compress(&self.current);
+        self.uncompressed += self.current.len();
+        self.compressed += data.len();
+
+        let data = buf.store_slice(&data);
+
+        self.blocks.push(stored::Block {
+            data,
+            size: self.current.len() as u32,
+        });
+
+        self.current.clear();
+    }
+
+    /// Compress any remaining entries and store the table of blocks.
+    pub(super) fn finish(mut self, buf: &mut OwnedBuf) -> Ref<[stored::Block]> {
+        self.flush(buf);
+
+        if self.compress {
+            tracing::info!(
+                "Compressed {} bytes of entries into {} bytes across {} block(s)",
+                self.uncompressed,
+                self.compressed,
+                self.blocks.len()
+            );
+        }
+
+        buf.store_slice(&self.blocks)
+    }
+}
+
+/// Decompressed blocks of an index, which are populated on demand.
+pub(super) struct Blocks {
+    cache: Box<[OnceLock<Box<[u8]>>]>,
+}
+
+impl Blocks {
+    pub(super) fn new(len: usize) -> Self {
+        Self {
+            cache: (0..len).map(|_| OnceLock::new()).collect(),
+        }
+    }
+
+    /// Load the bytes of the entry at the given offset.
+    ///
+    /// The returned slice extends past the entry, which is fine since entries
+    /// are decoded until they end.
+    pub(super) fn load<'a>(
+        &'a self,
+        buf: &'a Buf,
+        blocks: Ref<[stored::Block]>,
+        offset: u32,
+    ) -> Result<&'a [u8]> {
+        if self.cache.is_empty() {
+            return buf
+                .get(offset as usize..)
+                .ok_or_else(|| anyhow!("Missing entry at {offset}"));
+        }
+
+        let index = (offset >> OFFSET_BITS) as usize;
+        let start = (offset & ((1 << OFFSET_BITS) - 1)) as usize;
+
+        let Some(cell) = self.cache.get(index) else {
+            return Err(anyhow!("Missing block {index} for entry at {offset}"));
+        };
+
+        let block = match cell.get() {
+            Some(block) => block,
+            None => {
+                let Some(block) = buf.load(blocks)?.get(index) else {
+                    return Err(anyhow!("Missing block {index} for entry at {offset}"));
+                };
+
+                let data = lz4_flex::decompress(buf.load(block.data)?, block.size as usize)
+                    .with_context(|| anyhow!("Decompressing block {index}"))?;
+
+                cell.get_or_init(|| data.into())
+            }
+        };
+
+        block
+            .get(start..)
+            .ok_or_else(|| anyhow!("Missing entry at {offset}"))
+    }
+}
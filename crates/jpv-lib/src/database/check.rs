@@ -0,0 +1,268 @@
+//! Integrity checks for built indexes.
+
+use std::collections::HashSet;
+use std::hash::Hasher;
+use std::mem::size_of;
+
+use anyhow::{anyhow, Result};
+use musli_zerocopy::Buf;
+use twox_hash::XxHash32;
+
+use crate::jmdict;
+use crate::jmnedict;
+use crate::kanjidic2;
+use crate::token::Token;
+
+use super::{stored, Index, ENCODING};
+
+/// Seed used when calculating index checksums.
+const SEED: u32 = 0x4a50_5644;
+/// The maximum number of problems recorded in a report.
+const MAX_PROBLEMS: usize = 100;
+
+/// Calculate the checksum of an index, which covers everything following the
+/// global header.
+pub(super) fn checksum(bytes: &[u8]) -> u32 {
+    let mut hasher = XxHash32::with_seed(SEED);
+    hasher.write(
+        bytes
+            .get(size_of::<stored::GlobalHeader>()..)
+            .unwrap_or_default(),
+    );
+    hasher.finish() as u32
+}
+
+/// The result of checking an index.
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct Check {
+    /// The size of the index in bytes.
+    pub size: usize,
+    /// If the stored checksum matches the contents of the index.
+    pub checksum: bool,
+    /// The number of keys in the lookup table.
+    pub keys: usize,
+    /// The number of distinct entries which were decoded.
+    pub entries: usize,
+    /// The number of cross-references which were followed.
+    pub references: usize,
+    /// The number of example sentences which were loaded.
+    pub sentences: usize,
+    /// Problems found, of which at most a limited number are recorded.
+    pub problems: Vec<String>,
+    /// The total number of problems found.
+    pub problem_count: usize,
+}
+
+impl Check {
+    /// Test if the check found no problems.
+    pub fn is_ok(&self) -> bool {
+        self.checksum && self.problem_count == 0
+    }
+
+    fn problem(&mut self, problem: impl FnOnce() -> String) {
+        self.problem_count += 1;
+
+        if self.problems.len() < MAX_PROBLEMS {
+            self.problems.push(problem());
+        }
+    }
+
+    fn test<T>(&mut self, result: Result<T>, what: impl FnOnce() -> String) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.problem(|| format!("{}: {error}", what()));
+                None
+            }
+        }
+    }
+}
+
+impl Index {
+    /// Check the integrity of the index.
+    ///
+    /// This verifies the checksum of the index and decodes every entry
+    /// reachable from the lookup table, following the cross-references stored
+    /// alongside them.
+    pub fn check(&self, shutdown: &Token) -> Result<Check> {
+        let buf = self.data.as_buf();
+
+        let mut check = Check {
+            size: buf.len(),
+            checksum: checksum(buf.get(..).unwrap_or_default()) == self.checksum,
+            ..Check::default()
+        };
+
+        check.test(buf.load(self.header.name).map_err(Into::into), || {
+            "Name".to_owned()
+        });
+
+        let mut seen = HashSet::new();
+
+        for entry in self.header.lookup.iter(buf) {
+            if shutdown.is_set() {
+                return Err(anyhow!("Check cancelled"));
+            }
+
+            check.keys += 1;
+
+            let Some((key, id)) = check.test(entry.map_err(Into::into), || "Lookup".to_owned())
+            else {
+                continue;
+            };
+
+            if let stored::Source::Inflection { inflection } = id.source {
+                let data = self
+                    .header
+                    .inflections
+                    .get(inflection as usize)
+                    .ok_or_else(|| anyhow!("Missing inflection {inflection}"))
+                    .and_then(|data| Ok(buf.load(data)?));
+
+                check.test(data, || format!("Key `{}`", String::from_utf8_lossy(key)));
+            }
+
+            let kind = Kind::from(id.source);
+
+            if seen.insert((id.offset, kind)) {
+                check.entries += 1;
+                self.check_entry(buf, &mut check, id.offset, kind);
+            }
+        }
+
+        for example in buf.load(self.header.examples)? {
+            check.references += 1;
+
+            let result = buf
+                .load(example.text)
+                .and_then(|_| buf.load(example.translation))
+                .map_err(Into::into)
+                .and_then(|_| self.decode::<jmdict::Entry<'_>>(example.offset));
+
+            check.test(result, || {
+                format!("Example for entry at {}", example.offset)
+            });
+        }
+
+        for (index, sentence) in buf.load(self.header.sentences)?.iter().enumerate() {
+            check.sentences += 1;
+
+            let result = buf
+                .load(sentence.text)
+                .and_then(|_| buf.load(sentence.translation));
+
+            check.test(result.map_err(Into::into), || format!("Sentence {index}"));
+        }
+
+        Ok(check)
+    }
+
+    fn decode<'de, T>(&'de self, offset: u32) -> Result<T>
+    where
+        T: musli::Decode<'de, musli::mode::DefaultMode>,
+    {
+        Ok(ENCODING.from_slice(self.entry_bytes(offset)?)?)
+    }
+
+    /// Decode an entry and check that the tables referring back to it agree.
+    fn check_entry(&self, buf: &Buf, check: &mut Check, offset: u32, kind: Kind) {
+        let what = || format!("{kind:?} entry at {offset}");
+
+        match kind {
+            Kind::Phrase => {
+                let Some(entry) = check.test(self.decode::<jmdict::Entry<'_>>(offset), what) else {
+                    return;
+                };
+
+                check.references += 1;
+
+                let pos = self
+                    .header
+                    .by_sequence
+                    .get(buf, &(entry.sequence as u32))
+                    .map_err(Into::into)
+                    .and_then(|pos| {
+                        pos.ok_or_else(|| anyhow!("Sequence {} is not indexed", entry.sequence))
+                    });
+
+                if let Some(pos) = check.test(pos, what) {
+                    if pos.offset != offset {
+                        check.problem(|| {
+                            format!(
+                                "Sequence {} refers to entry at {} instead of {offset}",
+                                entry.sequence, pos.offset
+                            )
+                        });
+                    }
+                }
+
+                let related = self.header.related.get(buf, &offset).map_err(Into::into);
+
+                let Some(Some(related)) = check.test(related, what) else {
+                    return;
+                };
+
+                let Some(related) = check.test(buf.load(*related).map_err(Into::into), what) else {
+                    return;
+                };
+
+                for &related in related {
+                    check.references += 1;
+                    let result = self.decode::<jmdict::Entry<'_>>(related);
+                    check.test(result, || format!("Entry related to {offset}"));
+                }
+            }
+            Kind::Kanji => {
+                let Some(character) =
+                    check.test(self.decode::<kanjidic2::Character<'_>>(offset), what)
+                else {
+                    return;
+                };
+
+                check.references += 1;
+
+                let by_literal = self
+                    .header
+                    .by_kanji_literal
+                    .get(buf, character.literal)
+                    .map_err(Into::into)
+                    .and_then(|value| {
+                        value.ok_or_else(|| anyhow!("Literal {} is not indexed", character.literal))
+                    });
+
+                if let Some(&found) = check.test(by_literal, what) {
+                    if found != offset {
+                        check.problem(|| {
+                            format!(
+                                "Literal {} refers to entry at {found} instead of {offset}",
+                                character.literal
+                            )
+                        });
+                    }
+                }
+            }
+            Kind::Name => {
+                check.test(self.decode::<jmnedict::Entry<'_>>(offset), what);
+            }
+        }
+    }
+}
+
+/// The kind of entry an identifier refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Phrase,
+    Kanji,
+    Name,
+}
+
+impl From<stored::Source> for Kind {
+    fn from(source: stored::Source) -> Self {
+        match source {
+            stored::Source::Kanji { .. } => Kind::Kanji,
+            stored::Source::Phrase { .. } | stored::Source::Inflection { .. } => Kind::Phrase,
+            stored::Source::Name { .. } => Kind::Name,
+        }
+    }
+}
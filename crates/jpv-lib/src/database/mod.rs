@@ -1,12 +1,19 @@
 //! Database that can be used as a dictionary.
 
 mod analyze_glossary;
+mod blocks;
+mod check;
+mod related;
 mod stored;
 mod string_indexer;
+#[cfg(test)]
+mod tests;
 
 use std::borrow::Cow;
 use std::collections::{hash_map, BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
+use std::hash::Hash;
+use std::ops::Range;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -16,26 +23,75 @@ use musli::mode::DefaultMode;
 use musli::{Decode, Encode};
 use musli_storage::int::Variable;
 use musli_storage::Encoding;
-use musli_zerocopy::{swiss, trie, OwnedBuf, Ref, ZeroCopy};
+use musli_zerocopy::{swiss, trie, OwnedBuf, Ref, Visit, ZeroCopy};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::config::Config;
+use crate::config::{Config, Ranking};
 use crate::data::Data;
+use crate::detect;
+use crate::difficulty::{self, Difficulty};
+use crate::ids;
 use crate::inflection::{self, Inflection};
 use crate::jmdict;
 use crate::jmnedict;
 use crate::kanjidic2;
+use crate::normalize::Normalized;
+use crate::priority;
+use crate::query::{self, Expr, Field, Resolver, Term};
 use crate::reporter::Reporter;
 use crate::romaji::{self, is_hiragana, is_katakana, Segment};
+use crate::tanaka;
 use crate::token::Token;
-use crate::{PartOfSpeech, Weight};
-use crate::{DICTIONARY_MAGIC, DICTIONARY_VERSION};
+use crate::{PartOfSpeech, PartOfSpeechGroup, Score, Weight};
+use crate::{DICTIONARY_MAGIC, DICTIONARY_OLDEST_VERSION, DICTIONARY_VERSION};
 
+pub use self::check::Check;
+
+use self::blocks::{BlockWriter, Blocks};
+use self::related::RelatedBuilder;
 use self::string_indexer::StringIndexer;
 
 /// Encoding used for storing database.
 const ENCODING: Encoding<DefaultMode, Variable, Variable> = Encoding::new();
+/// Sentence length in characters which is considered ideal for an example.
+const IDEAL_SENTENCE_LENGTH: f32 = 16.0;
+/// Score bonus given to examples which have been checked.
+const CHECKED_BONUS: f32 = 1.0;
+/// How deep the component tree of a kanji is expanded.
+const MAX_COMPONENT_DEPTH: usize = 6;
+/// Analysis prior for expressions, which tend to be the intended match when
+/// they apply.
+const EXPRESSION_PRIOR: f32 = 1.2;
+/// Analysis prior for function words like particles and conjunctions.
+const FUNCTION_PRIOR: f32 = 0.8;
+/// Analysis boost for inflected forms of a phrase.
+const INFLECTION_PRIOR: f32 = 1.2;
+/// Analysis prior for names.
+const NAME_PRIOR: f32 = 0.5;
+/// Analysis prior for names where the text around them suggests a name.
+const NAME_CONTEXT_PRIOR: f32 = 1.5;
+/// Analysis prior for names where nothing suggests a name, so that they don't
+/// shadow common words.
+const NAME_DEMOTED_PRIOR: f32 = 0.2;
+/// Honorifics and titles which follow names.
+const HONORIFICS: &[&str] = &[
+    "さん",
+    "様",
+    "さま",
+    "君",
+    "くん",
+    "ちゃん",
+    "氏",
+    "殿",
+    "先生",
+    "先輩",
+    "選手",
+];
+/// Middle dots, which separate the parts of names written in katakana.
+const MIDDLE_DOTS: [char; 2] = ['・', '･'];
+/// Analysis prior for single kanji.
+const KANJI_PRIOR: f32 = 0.5;
 
 /// An error raised while interacting with the database.
 #[derive(Debug, Error)]
@@ -44,6 +100,8 @@ pub enum IndexOpenError {
     MagicMismatch,
     #[error("Outdated")]
     Outdated,
+    #[error("Built by a newer version")]
+    Newer,
     #[error("{0}")]
     Error(
         #[from]
@@ -52,6 +110,28 @@ pub enum IndexOpenError {
     ),
 }
 
+/// An error raised while searching the database.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SearchError {
+    /// There are no dictionaries installed or enabled to search.
+    #[error("No dictionaries are installed or enabled")]
+    IndexMissing,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// An error raised while building a dictionary.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// The build was cancelled through its shutdown token.
+    #[error("Task shut down")]
+    Shutdown,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
 /// Used for diagnostics to indicate where a dictionary was loaded from.
 #[non_exhaustive]
 pub enum Location {
@@ -192,6 +272,9 @@ pub enum PhraseIndex {
     Romanized,
     /// Indexed by meaning.
     Meaning,
+    /// Indexed by the kanji of a kanji reading without okurigana, whose
+    /// matches come after everything which matched the text as written.
+    Skeleton,
 }
 
 /// Data stored for a given inflection.
@@ -248,6 +331,42 @@ impl Source {
             _ => false,
         }
     }
+
+    /// Test if the source matches the given query field.
+    pub fn is_field(&self, field: Field) -> bool {
+        match field {
+            Field::Kanji => matches!(
+                self,
+                Source::Phrase {
+                    index: PhraseIndex::Kanji | PhraseIndex::KanjiHalf | PhraseIndex::Skeleton
+                } | Source::Kanji {
+                    index: KanjiIndex::Literal
+                } | Source::Name {
+                    index: NameIndex::Literal
+                }
+            ),
+            Field::Reading => match self {
+                Source::Phrase { index } => matches!(
+                    index,
+                    PhraseIndex::Hiragana | PhraseIndex::Katakana | PhraseIndex::Romanized
+                ),
+                Source::Kanji { index } => {
+                    !matches!(index, KanjiIndex::Literal | KanjiIndex::Meaning)
+                }
+                Source::Name { index } => !matches!(index, NameIndex::Literal),
+                Source::Inflection { .. } => true,
+            },
+            Field::Gloss => matches!(
+                self,
+                Source::Phrase {
+                    index: PhraseIndex::Meaning
+                } | Source::Kanji {
+                    index: KanjiIndex::Meaning
+                }
+            ),
+            Field::Any => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
@@ -286,6 +405,8 @@ pub enum Input<'a> {
     Jmdict(&'a str),
     Kanjidic2(&'a str),
     Jmnedict(&'a str),
+    Tanaka(&'a str),
+    Ids(&'a str),
 }
 
 impl Input<'_> {
@@ -294,6 +415,8 @@ impl Input<'_> {
             Input::Jmdict(..) => "JMdict",
             Input::Kanjidic2(..) => "Kanjidic2",
             Input::Jmnedict(..) => "JMnedict",
+            Input::Tanaka(..) => "Tanaka corpus",
+            Input::Ids(..) => "IDS",
         }
     }
 }
@@ -303,14 +426,133 @@ pub struct Search<'a> {
     pub phrases: Vec<(EntryResultKey, jmdict::Entry<'a>)>,
     pub names: Vec<(EntryResultKey, jmnedict::Entry<'a>)>,
     pub characters: Vec<kanjidic2::Character<'a>>,
+    /// Number of phrases in the result which belong to each part of speech
+    /// group.
+    pub facets: BTreeMap<PartOfSpeechGroup, usize>,
+}
+
+/// An example sentence using a phrase in a specific sense.
+pub struct SenseExample<'a> {
+    /// The 0-based index of the sense the phrase is used in, if known.
+    pub sense: Option<usize>,
+    /// The Japanese sentence.
+    pub text: &'a str,
+    /// The English translation of the sentence.
+    pub translation: &'a str,
+    /// If the sentence has been checked as a good example of the phrase.
+    pub checked: bool,
+    /// How good the sentence is as an example, where higher is better.
+    pub score: f32,
+}
+
+/// A span of text matching a phrase.
+pub struct PhraseSpan<'a> {
+    /// The byte range of the text matching the phrase.
+    pub range: Range<usize>,
+    /// The matched phrase.
+    pub entry: jmdict::Entry<'a>,
+}
+
+/// A candidate produced by analysis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate<'q> {
+    /// The analyzed text.
+    pub string: &'q str,
+    /// The score of the best entry matching the text.
+    pub score: Score,
+}
+
+/// How names from JMnedict are weighed against other words when analyzing
+/// text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NameWeighting {
+    /// Names are demoted unless the text around them suggests a name, like an
+    /// honorific following it.
+    #[default]
+    Context,
+    /// Names are weighed the same regardless of the text around them.
+    Plain,
+}
+
+impl NameWeighting {
+    /// A stable identifier for the weighting.
+    pub fn ident(&self) -> &'static str {
+        match self {
+            NameWeighting::Context => "context",
+            NameWeighting::Plain => "plain",
+        }
+    }
+
+    /// Parse a weighting from its identifier.
+    pub fn parse(ident: &str) -> Option<Self> {
+        match ident {
+            "context" => Some(NameWeighting::Context),
+            "plain" => Some(NameWeighting::Plain),
+            _ => None,
+        }
+    }
+
+    /// The prior of a name found at `range` in `q`.
+    fn prior(self, q: &str, range: Range<usize>) -> f32 {
+        match self {
+            NameWeighting::Plain => NAME_PRIOR,
+            NameWeighting::Context if suggests_name(q, range) => NAME_CONTEXT_PRIOR,
+            NameWeighting::Context => NAME_DEMOTED_PRIOR,
+        }
+    }
+}
+
+/// Options used when analyzing text.
+#[derive(Default)]
+pub struct AnalyzeOptions<'a> {
+    /// Words found by a morphological analyzer, where candidates which end
+    /// where a word ends are put first. See [`crate::tokenizer`].
+    pub words: &'a [Range<usize>],
+    /// How names are weighed.
+    pub names: NameWeighting,
+}
+
+/// An example sentence matching a search.
+pub struct ExampleMatch<'a> {
+    /// The Japanese sentence.
+    pub text: &'a str,
+    /// The English translation of the sentence, if any.
+    pub translation: Option<&'a str>,
+    /// Byte ranges in `text` matching the query.
+    pub text_highlights: Vec<Range<usize>>,
+    /// Byte ranges in `translation` matching the query.
+    pub translation_highlights: Vec<Range<usize>>,
+    /// The phrase the example belongs to.
+    pub entry: jmdict::Entry<'a>,
 }
 
 /// Build a dictionary from the given jmdict and kanjidic sources.
+///
+/// If `compress` is set, entries are stored in compressed blocks which are
+/// decompressed as they are loaded.
 pub fn build(
     reporter: &dyn Reporter,
     shutdown: &Token,
     name: &str,
     input: Input<'_>,
+    compress: bool,
+) -> Result<OwnedBuf, BuildError> {
+    match build_index(reporter, shutdown, name, input, compress) {
+        Ok(buf) => Ok(buf),
+        Err(error) => match error.downcast::<BuildError>() {
+            Ok(error) => Err(error),
+            Err(error) => Err(BuildError::Other(error)),
+        },
+    }
+}
+
+fn build_index(
+    reporter: &dyn Reporter,
+    shutdown: &Token,
+    name: &str,
+    input: Input<'_>,
+    compress: bool,
 ) -> Result<OwnedBuf> {
     let mut buf = OwnedBuf::new();
 
@@ -319,6 +561,19 @@ pub fn build(
 
     let name = buf.store_unsized(name);
 
+    let mut sections = Sections::default();
+    sections.mark(&buf, "header");
+
+    // Interns strings stored alongside entries, which are heavily duplicated
+    // across example sentences and their readings.
+    //
+    // TODO: Glosses and readings inside of entries are not interned, so the
+    // duplication in them remains. Entries are encoded as self-contained
+    // payloads which are decoded by borrowing strings from them, so interning
+    // those requires storing offsets into this table in the entry encoding.
+    let mut strings = StringIndexer::new();
+
+    let mut entries = BlockWriter::new(compress);
     let mut output = Vec::new();
     let mut lookup = Vec::new();
 
@@ -327,6 +582,14 @@ pub fn build(
     let mut kanji_literals = HashMap::new();
     let mut inflections = Vec::new();
     let mut inflections_index = HashMap::new();
+    let mut related = RelatedBuilder::default();
+    let mut examples = Vec::new();
+    let mut seen_examples = HashSet::new();
+    let mut sentences = Vec::new();
+    let mut by_headword = HashMap::<_, Vec<_>>::new();
+    let mut components = HashMap::new();
+    let mut by_kanji = HashMap::<_, Vec<_>>::new();
+    let mut by_skeleton = HashMap::<_, BTreeSet<_>>::new();
 
     reporter.instrument_start(
         module_path!(),
@@ -341,7 +604,7 @@ pub fn build(
             let mut jmdict = jmdict::Parser::new(input);
 
             while let Some(entry) = jmdict.parse()? {
-                ensure!(!shutdown.is_set(), "Task shut down");
+                ensure!(!shutdown.is_set(), BuildError::Shutdown);
 
                 if count % 1000 == 0 {
                     reporter.instrument_progress(1000);
@@ -352,7 +615,7 @@ pub fn build(
                 output.clear();
                 ENCODING.to_writer(&mut output, &entry)?;
 
-                let entry_ref = buf.store_slice(&output).offset() as u32;
+                let entry_ref = entries.store(&mut buf, &output)?;
                 by_sequence.insert(
                     entry.sequence as u32,
                     stored::PhrasePos {
@@ -361,6 +624,35 @@ pub fn build(
                     },
                 );
 
+                related.insert(entry_ref, &entry);
+
+                for example in entry.senses.iter().flat_map(|s| &s.examples) {
+                    let mut text = None;
+                    let mut translation = "";
+
+                    for sentence in &example.sentences {
+                        match sentence.lang {
+                            Some("eng") => translation = sentence.text,
+                            Some("jpn") | None => text = Some(sentence.text),
+                            _ => {}
+                        }
+                    }
+
+                    let Some(text) = text else {
+                        continue;
+                    };
+
+                    if !seen_examples.insert(text) {
+                        continue;
+                    }
+
+                    examples.push(stored::Example {
+                        text: strings.store(&mut buf, text)?,
+                        translation: strings.store(&mut buf, translation)?,
+                        offset: entry_ref,
+                    });
+                }
+
                 for sense in &entry.senses {
                     for pos in &sense.pos {
                         by_pos.entry(pos).or_default().insert(stored::PhrasePos {
@@ -403,6 +695,30 @@ pub fn build(
                         Cow::Borrowed(el.text),
                         stored::Id::phrase(entry_ref, PhraseIndex::Kanji),
                     ));
+
+                    if let Some(skeleton) = kanji_skeleton(el.text) {
+                        by_skeleton.entry(skeleton).or_default().insert(entry_ref);
+                    }
+                }
+
+                // A phrase is only listed once for each kanji, even if it's
+                // used by several of its forms.
+                let kanji = entry
+                    .kanji_elements
+                    .iter()
+                    .flat_map(|el| el.text.chars())
+                    .filter(|&c| detect::is_kanji(c) && c != '々')
+                    .collect::<BTreeSet<_>>();
+
+                if !kanji.is_empty() {
+                    let frequency = phrase_frequency(&entry);
+
+                    for c in kanji {
+                        by_kanji
+                            .entry(u32::from(c))
+                            .or_default()
+                            .push((frequency, entry_ref));
+                    }
                 }
 
                 for (reading, c, _) in inflection::conjugate(&entry) {
@@ -441,7 +757,7 @@ pub fn build(
             let mut kanjidic2 = kanjidic2::Parser::new(input);
 
             while let Some(c) = kanjidic2.parse()? {
-                ensure!(!shutdown.is_set(), "Task shut down");
+                ensure!(!shutdown.is_set(), BuildError::Shutdown);
 
                 if count % 1000 == 0 {
                     reporter.instrument_progress(1000);
@@ -452,7 +768,7 @@ pub fn build(
                 output.clear();
                 ENCODING.to_writer(&mut output, &c)?;
 
-                let kanji_ref = buf.store_slice(&output).offset() as u32;
+                let kanji_ref = entries.store(&mut buf, &output)?;
 
                 kanji_literals.insert(c.literal, kanji_ref);
 
@@ -503,7 +819,7 @@ pub fn build(
             let mut jmnedict = jmnedict::Parser::new(input);
 
             while let Some(entry) = jmnedict.next()? {
-                ensure!(!shutdown.is_set(), "Task shut down");
+                ensure!(!shutdown.is_set(), BuildError::Shutdown);
 
                 if count % 1000 == 0 {
                     reporter.instrument_progress(1000);
@@ -514,7 +830,7 @@ pub fn build(
                 output.clear();
                 ENCODING.to_writer(&mut output, &entry)?;
 
-                let name_ref = buf.store_slice(&output).offset() as u32;
+                let name_ref = entries.store(&mut buf, &output)?;
 
                 for kanji in entry.kanji.iter().copied() {
                     lookup.push((
@@ -534,10 +850,82 @@ pub fn build(
                 }
             }
         }
+        Input::Tanaka(input) => {
+            let mut tanaka = tanaka::Parser::new(input);
+            let mut sentence_words = Vec::new();
+
+            while let Some(example) = tanaka.next()? {
+                ensure!(!shutdown.is_set(), BuildError::Shutdown);
+
+                if count % 1000 == 0 {
+                    reporter.instrument_progress(1000);
+                }
+
+                count += 1;
+
+                let sentence = sentences.len() as u32;
+
+                sentences.push(stored::Sentence {
+                    text: strings.store(&mut buf, example.text)?,
+                    translation: strings.store(&mut buf, example.translation)?,
+                    score: 0.0,
+                });
+
+                sentence_words.push(example.words.iter().map(|w| w.headword).collect::<Vec<_>>());
+
+                for word in &example.words {
+                    by_headword
+                        .entry(word.headword)
+                        .or_default()
+                        .push(stored::SenseExample {
+                            reading: strings.store(&mut buf, word.reading.unwrap_or_default())?,
+                            sentence,
+                            sense: word.sense.unwrap_or_default(),
+                            checked: u32::from(word.checked),
+                        });
+                }
+            }
+
+            // Words are weighed by how many sentences use them, which is only
+            // known once all sentences have been seen.
+            for (sentence, words) in sentences.iter_mut().zip(&sentence_words) {
+                let frequencies = words
+                    .iter()
+                    .map(|w| by_headword.get(w).map(Vec::len).unwrap_or_default());
+
+                sentence.score = sentence_score(buf.load(sentence.text)?, frequencies);
+            }
+        }
+        Input::Ids(input) => {
+            let mut ids = ids::Parser::new(input);
+
+            while let Some(entry) = ids.next()? {
+                ensure!(!shutdown.is_set(), BuildError::Shutdown);
+
+                if count % 1000 == 0 {
+                    reporter.instrument_progress(1000);
+                }
+
+                count += 1;
+
+                if ids::parse(entry.sequence).is_none() {
+                    tracing::warn!("Bad sequence for {}: {}", entry.character, entry.sequence);
+                    continue;
+                }
+
+                components.insert(
+                    u32::from(entry.character),
+                    strings.store(&mut buf, entry.sequence)?,
+                );
+            }
+        }
     }
 
     reporter.instrument_end(count);
 
+    let blocks = entries.finish(&mut buf);
+    sections.mark(&buf, "entries");
+
     lookup.sort_by(|(a, _), (b, _)| b.as_ref().cmp(a.as_ref()));
     tracing::info!("Inserting {} readings", lookup.len());
 
@@ -550,7 +938,7 @@ pub fn build(
         reporter.instrument_start(module_path!(), &"Inserting strings", Some(lookup.len()));
 
         for (index, (key, id)) in lookup.iter().enumerate() {
-            ensure!(!shutdown.is_set(), "Task shut down");
+            ensure!(!shutdown.is_set(), BuildError::Shutdown);
 
             if index % 100_000 == 0 {
                 reporter.instrument_progress(100_000);
@@ -574,12 +962,15 @@ pub fn build(
         };
 
         tracing::info!(
-            "Reused {} string(s) (out of {})",
+            "Reused {} string(s) (out of {}), saving {} bytes",
             indexer.reuse(),
-            indexer.total()
+            indexer.total(),
+            indexer.saved()
         );
     }
 
+    sections.mark(&buf, "lookup strings");
+
     drop(lookup);
 
     let step_len = readings2.len();
@@ -593,7 +984,7 @@ pub fn build(
             reporter.instrument_progress(100000);
         }
 
-        ensure!(!shutdown.is_set(), "Task shut down");
+        ensure!(!shutdown.is_set(), BuildError::Shutdown);
         lookup.insert(&buf, key, id)?;
     }
 
@@ -602,12 +993,13 @@ pub fn build(
     reporter.instrument_start(module_path!(), &"Saving index", None);
 
     let lookup = lookup.build(&mut buf)?;
+    sections.mark(&buf, "lookup");
 
     let by_pos = {
         let mut entries = Vec::new();
 
         for (key, set) in by_pos.into_iter() {
-            ensure!(!shutdown.is_set(), "Task shut down");
+            ensure!(!shutdown.is_set(), BuildError::Shutdown);
 
             let mut values = Vec::new();
 
@@ -621,20 +1013,134 @@ pub fn build(
         }
 
         tracing::info!("Storing by_pos: {}...", entries.len());
-        swiss::store_map(&mut buf, entries)?
+        store_map(&mut buf, entries, |buf| {
+            Ok((PartOfSpeech::Unclassified, buf.store_slice(&[])))
+        })?
     };
 
+    sections.mark(&buf, "by_pos");
+
     let by_kanji_literal = {
         tracing::info!("Storing by_kanji_literal: {}...", by_kanji_literal.len());
-        swiss::store_map(&mut buf, by_kanji_literal)?
+        store_map(&mut buf, by_kanji_literal, |buf| {
+            Ok((buf.store_unsized(""), 0))
+        })?
     };
 
+    sections.mark(&buf, "by_kanji_literal");
+
     let by_sequence = {
         tracing::info!("Storing by_sequence: {}...", by_sequence.len());
-        swiss::store_map(&mut buf, by_sequence)?
+        store_map(&mut buf, by_sequence, |_| {
+            let pos = stored::PhrasePos {
+                offset: 0,
+                reading: PhraseIndex::Entry,
+            };
+
+            Ok((u32::MAX, pos))
+        })?
+    };
+
+    sections.mark(&buf, "by_sequence");
+
+    let related = {
+        let mut entries = Vec::new();
+
+        for (offset, values) in related.build() {
+            ensure!(!shutdown.is_set(), BuildError::Shutdown);
+            let values = buf.store_slice(&values);
+            entries.push((offset, values));
+        }
+
+        tracing::info!("Storing related: {}...", entries.len());
+        store_map(&mut buf, entries, |buf| {
+            Ok((u32::MAX, buf.store_slice(&[])))
+        })?
+    };
+
+    sections.mark(&buf, "related");
+
+    tracing::info!("Storing examples: {}...", examples.len());
+    let examples = buf.store_slice(&examples);
+    sections.mark(&buf, "examples");
+
+    tracing::info!("Storing sentences: {}...", sentences.len());
+    let sentences = buf.store_slice(&sentences);
+    sections.mark(&buf, "sentences");
+
+    let by_headword = {
+        let mut entries = Vec::new();
+
+        for (headword, values) in by_headword {
+            ensure!(!shutdown.is_set(), BuildError::Shutdown);
+            let headword = strings.store(&mut buf, headword)?;
+            let values = buf.store_slice(&values);
+            entries.push((headword, values));
+        }
+
+        tracing::info!("Storing by_headword: {}...", entries.len());
+        store_map(&mut buf, entries, |buf| {
+            Ok((buf.store_unsized(""), buf.store_slice(&[])))
+        })?
+    };
+
+    sections.mark(&buf, "by_headword");
+
+    let components = {
+        tracing::info!("Storing components: {}...", components.len());
+        store_map(&mut buf, components, |buf| {
+            Ok((u32::MAX, buf.store_unsized("")))
+        })?
+    };
+
+    sections.mark(&buf, "components");
+
+    let by_kanji = {
+        let mut entries = Vec::new();
+
+        for (c, mut values) in by_kanji {
+            ensure!(!shutdown.is_set(), BuildError::Shutdown);
+
+            // Phrases which are equally frequent stay in dictionary order.
+            values.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+            let values = values
+                .into_iter()
+                .map(|(_, offset)| offset)
+                .collect::<Vec<_>>();
+
+            let values = buf.store_slice(&values);
+            entries.push((c, values));
+        }
+
+        tracing::info!("Storing by_kanji: {}...", entries.len());
+        store_map(&mut buf, entries, |buf| {
+            Ok((u32::MAX, buf.store_slice(&[])))
+        })?
+    };
+
+    sections.mark(&buf, "by_kanji");
+
+    let by_skeleton = {
+        let mut entries = Vec::new();
+
+        for (skeleton, values) in &by_skeleton {
+            ensure!(!shutdown.is_set(), BuildError::Shutdown);
+            let skeleton = strings.store(&mut buf, skeleton)?;
+            let values = buf.store_slice(&values.iter().copied().collect::<Vec<_>>());
+            entries.push((skeleton, values));
+        }
+
+        tracing::info!("Storing by_skeleton: {}...", entries.len());
+        store_map(&mut buf, entries, |buf| {
+            Ok((buf.store_unsized(""), buf.store_slice(&[])))
+        })?
     };
 
+    sections.mark(&buf, "by_skeleton");
+
     let inflections = buf.store_slice(&inflections);
+    sections.mark(&buf, "inflections");
 
     buf.load_uninit_mut(index).write(&stored::IndexHeader {
         name,
@@ -642,19 +1148,161 @@ pub fn build(
         by_pos,
         by_kanji_literal,
         by_sequence,
+        related,
+        examples,
+        sentences,
+        by_headword,
+        components,
+        by_kanji,
+        by_skeleton,
         inflections,
+        blocks,
     });
 
+    let checksum = check::checksum(buf.as_slice());
+
     buf.load_uninit_mut(header).write(&stored::GlobalHeader {
         magic: DICTIONARY_MAGIC,
         version: DICTIONARY_VERSION,
+        checksum,
         index: index.assume_init(),
     });
 
+    tracing::info!(
+        "Interned {} string(s) outside of entries, reused {} saving {} bytes",
+        strings.total(),
+        strings.reuse(),
+        strings.saved()
+    );
+
+    sections.report();
+
     reporter.instrument_end(0);
     Ok(buf)
 }
 
+/// Sizes of the sections written while building an index.
+#[derive(Default)]
+struct Sections {
+    start: usize,
+    sizes: Vec<(&'static str, usize)>,
+}
+
+impl Sections {
+    /// Mark the end of the named section, which covers everything written
+    /// since the previous mark.
+    fn mark(&mut self, buf: &OwnedBuf, name: &'static str) {
+        let len = buf.len();
+        self.sizes.push((name, len - self.start));
+        self.start = len;
+    }
+
+    /// Report section sizes through tracing.
+    fn report(&self) {
+        let total = self.start.max(1) as f32;
+
+        for &(name, size) in &self.sizes {
+            let percent = size as f32 / total * 100.0;
+            tracing::info!("Section {name}: {size} bytes ({percent:.1}%)");
+        }
+
+        tracing::info!("Total: {} bytes", self.start);
+    }
+}
+
+/// Score a corpus sentence, where higher is better.
+///
+/// Sentences close to the ideal length which are made up of common words
+/// make the best examples.
+/// Store a map, storing the given sentinel entry instead if it would be
+/// empty.
+///
+/// Swiss tables can't be empty, which indexes of a single dictionary would
+/// otherwise have. Sentinels use keys which are never looked up or map to
+/// an empty value, so that lookups behave as if the map was empty.
+fn store_map<K, V, I>(
+    buf: &mut OwnedBuf,
+    entries: I,
+    sentinel: impl FnOnce(&mut OwnedBuf) -> Result<(K, V)>,
+) -> Result<swiss::MapRef<K, V>>
+where
+    K: Visit + ZeroCopy,
+    K::Target: Hash,
+    V: ZeroCopy,
+    I: IntoIterator<Item = (K, V)>,
+    I::IntoIter: ExactSizeIterator,
+{
+    let entries = entries.into_iter();
+
+    if entries.len() == 0 {
+        let sentinel = sentinel(buf)?;
+        return Ok(swiss::store_map(buf, [sentinel])?);
+    }
+
+    Ok(swiss::store_map(buf, entries)?)
+}
+
+fn sentence_score(text: &str, frequencies: impl Iterator<Item = usize>) -> f32 {
+    let len = text.chars().count() as f32;
+    let length = -(len - IDEAL_SENTENCE_LENGTH).abs() / IDEAL_SENTENCE_LENGTH;
+
+    let (sum, count) = frequencies.fold((0.0f32, 0usize), |(sum, count), f| {
+        (sum + (1.0 + f as f32).ln(), count + 1)
+    });
+
+    // The logarithm of word frequencies in the corpus tops out around 10.
+    let frequency = if count == 0 {
+        0.0
+    } else {
+        sum / count as f32 / 10.0
+    };
+
+    length + frequency
+}
+
+/// Collect the byte ranges where `term` occurs in `text`.
+fn highlights(text: &str, term: &str, output: &mut Vec<Range<usize>>) {
+    for (start, string) in text.match_indices(term) {
+        output.push(start..start + string.len());
+    }
+}
+
+/// Collect the distinct part of speech groups used by the senses of an entry.
+fn entry_groups(entry: &jmdict::Entry<'_>) -> BTreeSet<PartOfSpeechGroup> {
+    let mut groups = BTreeSet::new();
+
+    for sense in &entry.senses {
+        for pos in sense.pos.iter() {
+            groups.insert(pos.group());
+        }
+    }
+
+    groups
+}
+
+/// The analysis prior of a phrase, which is the best prior of any part of
+/// speech it has.
+fn phrase_prior(entry: &jmdict::Entry<'_>) -> f32 {
+    entry_groups(entry)
+        .into_iter()
+        .map(|group| match group {
+            PartOfSpeechGroup::Expression => EXPRESSION_PRIOR,
+            PartOfSpeechGroup::Other => FUNCTION_PRIOR,
+            _ => 1.0,
+        })
+        .fold(FUNCTION_PRIOR, f32::max)
+}
+
+/// The frequency boost of a phrase, from its highest priority and the number
+/// of ranking lists it appears in.
+fn phrase_frequency(entry: &jmdict::Entry<'_>) -> f32 {
+    let kanji = entry.kanji_elements.iter().flat_map(|e| &e.priority);
+    let reading = entry.reading_elements.iter().flat_map(|e| &e.priority);
+    let priorities = kanji.chain(reading);
+    let highest = priorities.clone().map(|p| p.weight()).fold(1.0, f32::max);
+    highest + priority::list_bonus(priorities)
+}
+
 fn populate_analyzed<'a>(
     text: &'a str,
     lookup: &mut Vec<(Cow<'a, str>, stored::Id)>,
@@ -768,6 +1416,24 @@ fn full_to_half_string(input: &str) -> Option<String> {
     Some(output)
 }
 
+/// The kanji of a kanji reading without its okurigana, like `落着` for
+/// `落ち着く`.
+///
+/// Readings without okurigana have no skeleton, and neither do those with a
+/// single kanji since searching for it would match far too much.
+fn kanji_skeleton(text: &str) -> Option<String> {
+    let skeleton = text
+        .chars()
+        .filter(|&c| detect::is_kanji(c))
+        .collect::<String>();
+
+    if skeleton.len() == text.len() || skeleton.chars().count() < 2 {
+        return None;
+    }
+
+    Some(skeleton)
+}
+
 fn other_readings(
     output: &mut Vec<(Cow<'_, str>, stored::Id)>,
     text: &str,
@@ -790,7 +1456,10 @@ fn other_readings(
 // A loaded index.
 pub struct Index {
     header: stored::IndexHeader,
+    version: u32,
+    checksum: u32,
     data: Data,
+    blocks: Blocks,
 }
 
 impl Index {
@@ -806,12 +1475,32 @@ impl Index {
             return Err(IndexOpenError::MagicMismatch);
         }
 
-        if header.version != DICTIONARY_VERSION {
+        if header.version < DICTIONARY_OLDEST_VERSION {
             return Err(IndexOpenError::Outdated);
         }
 
+        if header.version > DICTIONARY_VERSION {
+            return Err(IndexOpenError::Newer);
+        }
+
+        let version = header.version;
+        let checksum = header.checksum;
         let header = *buf.load(header.index)?;
-        Ok(Self { header, data })
+        let blocks = Blocks::new(header.blocks.len());
+
+        Ok(Self {
+            header,
+            version,
+            checksum,
+            data,
+            blocks,
+        })
+    }
+
+    /// The database version the index was built with, which is at least
+    /// [`DICTIONARY_OLDEST_VERSION`].
+    pub fn version(&self) -> u32 {
+        self.version
     }
 
     /// Load the name of the index.
@@ -819,11 +1508,44 @@ impl Index {
         Ok(self.data.as_buf().load(self.header.name)?)
     }
 
+    /// Find the keys in the lookup table which the given string starts with,
+    /// shortest first.
+    ///
+    /// Prefixes are tested from the shortest and the search stops as soon as
+    /// no key continues the prefix being tested, so the cost depends on the
+    /// longest matching key rather than on the length of the string.
+    fn prefixes<'q>(&self, q: &'q str) -> Result<Vec<(&'q str, &[stored::Id])>> {
+        let buf = self.data.as_buf();
+        let mut output = Vec::new();
+
+        for (n, c) in q.char_indices() {
+            let prefix = &q[..n + c.len_utf8()];
+
+            if let Some(values) = self.header.lookup.get(buf, prefix)? {
+                output.push((prefix, values));
+                continue;
+            }
+
+            match self.header.lookup.values_in(buf, prefix).next() {
+                Some(value) => {
+                    value?;
+                }
+                None => break,
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Get the encoded bytes of the entry at the given offset.
+    fn entry_bytes(&self, offset: u32) -> Result<&[u8]> {
+        self.blocks
+            .load(self.data.as_buf(), self.header.blocks, offset)
+    }
+
     /// Get an entry from the database.
     fn entry_at(&self, id: Id) -> Result<Entry<'_>> {
-        let Some(bytes) = self.data.as_buf().get(id.offset as usize..) else {
-            return Err(anyhow!("Missing entry at {}", id.offset));
-        };
+        let bytes = self.entry_bytes(id.offset)?;
 
         Ok(match id.source {
             Source::Kanji { .. } => Entry::Kanji(ENCODING.from_slice(bytes)?),
@@ -839,10 +1561,14 @@ impl Index {
 pub struct Database {
     indexes: Arc<[Index]>,
     disabled: Arc<[String]>,
+    ranking: Arc<Ranking>,
 }
 
 impl Database {
     /// Open a sequence of indexes.
+    ///
+    /// Indexes which can't be loaded are skipped, so that the database can be
+    /// used with whatever is available.
     pub fn open<I>(iter: I, config: &Config) -> Result<Self>
     where
         I: IntoIterator<Item = (Data, Location)>,
@@ -860,8 +1586,17 @@ impl Database {
                 }
             };
 
-            if !config.is_enabled(index.name()?) {
-                disabled.push(index.name()?.to_owned());
+            let name = match index.name() {
+                Ok(name) => name,
+                Err(error) => {
+                    log::error!("Failed to read name of index from {location}");
+                    log::error!("Caused by: {}", error);
+                    continue;
+                }
+            };
+
+            if !config.is_enabled(name) {
+                disabled.push(name.to_owned());
                 continue;
             }
 
@@ -871,9 +1606,19 @@ impl Database {
         Ok(Self {
             indexes: indexes.into(),
             disabled: disabled.into(),
+            ranking: Arc::new(config.ranking.clone()),
         })
     }
 
+    /// Get the name of the index the given entry was loaded from.
+    pub fn index_name(&self, key: Key) -> Result<&str> {
+        let Some(index) = self.indexes.get(key.index as usize) else {
+            return Err(anyhow!("Missing index {}", key.index));
+        };
+
+        index.name()
+    }
+
     /// Get the identifiers of all installed indexes.
     pub fn installed(&self) -> Result<HashSet<String>> {
         let mut output = HashSet::with_capacity(self.indexes.len());
@@ -917,9 +1662,7 @@ impl Database {
                 continue;
             };
 
-            let Some(bytes) = d.data.as_buf().get(*index as usize..) else {
-                return Err(anyhow!("Missing entry at {}", *index));
-            };
+            let bytes = d.entry_bytes(*index)?;
 
             return Ok(Some(ENCODING.from_slice(bytes)?));
         }
@@ -927,46 +1670,328 @@ impl Database {
         Ok(None)
     }
 
-    /// Get identifier by sequence.
-    pub fn sequence_to_entry(&self, sequence: u32) -> Result<Option<jmdict::Entry<'_>>> {
-        for d in self.indexes.iter() {
-            let Some(pos) = d.header.by_sequence.get(d.data.as_buf(), &sequence)? else {
-                continue;
-            };
+    /// Get the component tree of a kanji, or `None` if there is no
+    /// description of it.
+    pub fn kanji_components(&self, c: char) -> Result<Option<ids::Component>> {
+        if self.ids_sequence(c)?.is_none() {
+            return Ok(None);
+        }
 
-            let Some(bytes) = d.data.as_buf().get(pos.offset as usize..) else {
-                return Err(anyhow!("Missing entry at {}", pos.offset));
-            };
+        let mut buf = [0; 4];
+        Ok(Some(self.component(c.encode_utf8(&mut buf), 0)?))
+    }
 
-            return Ok(Some(ENCODING.from_slice(bytes)?));
+    /// Expand a single component, recursively describing it if possible.
+    fn component(&self, text: &str, depth: usize) -> Result<ids::Component> {
+        let mut component = ids::Component {
+            text: text.to_owned(),
+            operator: None,
+            parts: Vec::new(),
+        };
+
+        if depth >= MAX_COMPONENT_DEPTH {
+            return Ok(component);
         }
 
-        Ok(None)
-    }
+        let mut chars = text.chars();
 
-    /// Get indexes by part of speech.
-    #[tracing::instrument(skip_all)]
-    pub fn by_pos(&self, pos: Set<PartOfSpeech>) -> Result<Vec<Id>> {
-        let mut unique = BTreeSet::new();
-        let mut output = Vec::new();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return Ok(component);
+        };
 
-        for (index, d) in self.indexes.iter().enumerate() {
-            let mut first = true;
-            unique.clear();
+        let Some(sequence) = self.ids_sequence(c)? else {
+            return Ok(component);
+        };
 
-            for pos in pos.iter() {
-                if let Some(by_pos) = d.header.by_pos.get(d.data.as_buf(), &pos)? {
-                    if first {
-                        first = false;
-                        unique.extend(by_pos.iter());
-                    } else {
-                        let new_set = by_pos.iter().collect::<HashSet<_>>();
-                        unique.retain(|n| new_set.contains(n));
-                    }
+        let Some(sequence) = ids::parse(sequence) else {
+            return Ok(component);
+        };
+
+        match sequence {
+            ids::Sequence::Component(other) => {
+                if other != text {
+                    component.parts.push(self.component(other, depth + 1)?);
                 }
             }
+            ids::Sequence::Compound(operator, parts) => {
+                component.operator = Some(operator);
 
-            for &by_pos in unique.iter() {
+                for part in parts {
+                    component.parts.push(self.sequence(part, depth + 1)?);
+                }
+            }
+        }
+
+        Ok(component)
+    }
+
+    fn sequence(&self, sequence: ids::Sequence<'_>, depth: usize) -> Result<ids::Component> {
+        match sequence {
+            ids::Sequence::Component(text) => self.component(text, depth),
+            ids::Sequence::Compound(operator, parts) => {
+                let mut output = Vec::with_capacity(parts.len());
+
+                for part in parts {
+                    output.push(self.sequence(part, depth + 1)?);
+                }
+
+                Ok(ids::Component {
+                    text: String::new(),
+                    operator: Some(operator),
+                    parts: output,
+                })
+            }
+        }
+    }
+
+    /// Get the ideographic description sequence of a character.
+    fn ids_sequence(&self, c: char) -> Result<Option<&str>> {
+        for d in self.indexes.iter() {
+            let buf = d.data.as_buf();
+
+            let Some(sequence) = d.header.components.get(buf, &u32::from(c))? else {
+                continue;
+            };
+
+            return Ok(Some(buf.load(*sequence)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Get identifier by sequence.
+    pub fn sequence_to_entry(&self, sequence: u32) -> Result<Option<jmdict::Entry<'_>>> {
+        for d in self.indexes.iter() {
+            let Some(pos) = d.header.by_sequence.get(d.data.as_buf(), &sequence)? else {
+                continue;
+            };
+
+            let bytes = d.entry_bytes(pos.offset)?;
+
+            return Ok(Some(ENCODING.from_slice(bytes)?));
+        }
+
+        Ok(None)
+    }
+
+    /// Get corpus examples which use the given phrase, with the best examples
+    /// first.
+    ///
+    /// Examples which specify a reading are only included if it matches one of
+    /// the readings of the phrase.
+    pub fn sense_examples(&self, entry: &jmdict::Entry<'_>) -> Result<Vec<SenseExample<'_>>> {
+        let mut output = Vec::new();
+        let mut seen = HashSet::new();
+
+        let headwords = if entry.kanji_elements.is_empty() {
+            entry
+                .reading_elements
+                .iter()
+                .map(|r| r.text)
+                .collect::<Vec<_>>()
+        } else {
+            entry
+                .kanji_elements
+                .iter()
+                .map(|k| k.text)
+                .collect::<Vec<_>>()
+        };
+
+        for (index, d) in self.indexes.iter().enumerate() {
+            let buf = d.data.as_buf();
+
+            for headword in &headwords {
+                let Some(examples) = d.header.by_headword.get(buf, *headword)? else {
+                    continue;
+                };
+
+                for example in buf.load(*examples)? {
+                    let reading = buf.load(example.reading)?;
+
+                    if !reading.is_empty()
+                        && !entry.reading_elements.iter().any(|r| r.text == reading)
+                    {
+                        continue;
+                    }
+
+                    if !seen.insert((index, example.sentence)) {
+                        continue;
+                    }
+
+                    let Some(sentence) =
+                        buf.load(d.header.sentences)?.get(example.sentence as usize)
+                    else {
+                        return Err(anyhow!("Missing sentence {}", example.sentence));
+                    };
+
+                    let checked = example.checked != 0;
+
+                    let score = if checked {
+                        sentence.score + CHECKED_BONUS
+                    } else {
+                        sentence.score
+                    };
+
+                    output.push(SenseExample {
+                        sense: (example.sense as usize).checked_sub(1),
+                        text: buf.load(sentence.text)?,
+                        translation: buf.load(sentence.translation)?,
+                        checked,
+                        score,
+                    });
+                }
+            }
+        }
+
+        output.sort_by(|a, b| b.score.total_cmp(&a.score));
+        Ok(output)
+    }
+
+    /// Get phrases related to the given entry, either by sharing kanji or
+    /// glossary words.
+    pub fn related(&self, entry: &jmdict::Entry<'_>) -> Result<Vec<jmdict::Entry<'_>>> {
+        let sequence = entry.sequence as u32;
+        let mut output = Vec::new();
+
+        for d in self.indexes.iter() {
+            let Some(pos) = d.header.by_sequence.get(d.data.as_buf(), &sequence)? else {
+                continue;
+            };
+
+            let Some(related) = d.header.related.get(d.data.as_buf(), &pos.offset)? else {
+                continue;
+            };
+
+            for &offset in d.data.as_buf().load(*related)? {
+                let bytes = d.entry_bytes(offset)?;
+
+                output.push(ENCODING.from_slice(bytes)?);
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Get the phrases written using the given kanji, the most frequently used
+    /// first, skipping the first `offset` of them and returning at most
+    /// `limit`.
+    pub fn entries_with_kanji(
+        &self,
+        c: char,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<jmdict::Entry<'_>>> {
+        let mut output = Vec::new();
+        let mut skip = offset;
+
+        for d in self.indexes.iter() {
+            if output.len() >= limit {
+                break;
+            }
+
+            let Some(entries) = d.header.by_kanji.get(d.data.as_buf(), &u32::from(c))? else {
+                continue;
+            };
+
+            let entries = d.data.as_buf().load(*entries)?;
+            let skipped = skip.min(entries.len());
+            skip -= skipped;
+
+            for &entry in entries[skipped..].iter().take(limit - output.len()) {
+                let bytes = d.entry_bytes(entry)?;
+                output.push(ENCODING.from_slice(bytes)?);
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Search example sentences for the given text.
+    ///
+    /// Every whitespace separated term has to occur in either the sentence or
+    /// its translation, where translations are matched case insensitively.
+    #[tracing::instrument(skip_all)]
+    pub fn search_examples(&self, input: &str) -> Result<Vec<ExampleMatch<'_>>> {
+        let terms = input
+            .split_whitespace()
+            .map(|term| term.to_ascii_lowercase())
+            .collect::<Vec<_>>();
+
+        let mut output = Vec::new();
+
+        if terms.is_empty() {
+            return Ok(output);
+        }
+
+        for d in self.indexes.iter() {
+            let buf = d.data.as_buf();
+
+            for example in buf.load(d.header.examples)? {
+                let text = buf.load(example.text)?;
+                let translation = buf.load(example.translation)?;
+                let lowercase = translation.to_ascii_lowercase();
+
+                let mut text_highlights = Vec::new();
+                let mut translation_highlights = Vec::new();
+                let mut matches = true;
+
+                for term in &terms {
+                    let before = text_highlights.len() + translation_highlights.len();
+                    highlights(text, term, &mut text_highlights);
+                    highlights(&lowercase, term, &mut translation_highlights);
+
+                    if text_highlights.len() + translation_highlights.len() == before {
+                        matches = false;
+                        break;
+                    }
+                }
+
+                if !matches {
+                    continue;
+                }
+
+                text_highlights.sort_by_key(|r| r.start);
+                translation_highlights.sort_by_key(|r| r.start);
+
+                let bytes = d.entry_bytes(example.offset)?;
+
+                output.push(ExampleMatch {
+                    text,
+                    translation: (!translation.is_empty()).then_some(translation),
+                    text_highlights,
+                    translation_highlights,
+                    entry: ENCODING.from_slice(bytes)?,
+                });
+            }
+        }
+
+        output.sort_by_key(|e| e.text.len());
+        Ok(output)
+    }
+
+    /// Get indexes by part of speech.
+    #[tracing::instrument(skip_all)]
+    pub fn by_pos(&self, pos: Set<PartOfSpeech>) -> Result<Vec<Id>> {
+        let mut unique = BTreeSet::new();
+        let mut output = Vec::new();
+
+        for (index, d) in self.indexes.iter().enumerate() {
+            let mut first = true;
+            unique.clear();
+
+            for pos in pos.iter() {
+                if let Some(by_pos) = d.header.by_pos.get(d.data.as_buf(), &pos)? {
+                    if first {
+                        first = false;
+                        unique.extend(by_pos.iter());
+                    } else {
+                        let new_set = by_pos.iter().collect::<HashSet<_>>();
+                        unique.retain(|n| new_set.contains(n));
+                    }
+                }
+            }
+
+            for &by_pos in unique.iter() {
                 tracing::trace!(?by_pos);
                 let pos = d.data.as_buf().load(by_pos)?;
                 output.push(self.convert_id(index, stored::Id::phrase(pos.offset, pos.reading))?);
@@ -991,6 +2016,33 @@ impl Database {
         Ok(output)
     }
 
+    /// Get every phrase in the database, in the order they are stored.
+    #[tracing::instrument(skip_all)]
+    pub fn phrases(&self) -> Result<Vec<Id>> {
+        let mut offsets = BTreeSet::new();
+        let mut output = Vec::new();
+
+        for (index, d) in self.indexes.iter().enumerate() {
+            offsets.clear();
+
+            for entry in d.header.lookup.iter(d.data.as_buf()) {
+                let (_, id) = entry?;
+
+                if let stored::Source::Phrase { .. } = id.source {
+                    offsets.insert(id.offset);
+                }
+            }
+
+            for &offset in &offsets {
+                let id = stored::Id::phrase(offset, PhraseIndex::Meaning);
+                output.push(self.convert_id(index, id)?);
+            }
+        }
+
+        tracing::trace!(output = output.len());
+        Ok(output)
+    }
+
     /// Perform a free text lookup.
     #[tracing::instrument(skip_all)]
     pub fn lookup(&self, query: &str) -> Result<Vec<Id>> {
@@ -1070,8 +2122,143 @@ impl Database {
         Ok(i.data.as_buf().load(data)?)
     }
 
+    /// Evaluate a structured query, returning all matching identifiers.
+    ///
+    /// See the [`query`] module for a description of the query syntax.
+    #[tracing::instrument(skip_all)]
+    pub fn query(&self, expr: &Expr<'_>) -> Result<Vec<Id>> {
+        self.query_with(expr, &())
+    }
+
+    /// Evaluate a structured query, resolving terms which match user data
+    /// through the given resolver.
+    #[tracing::instrument(skip_all)]
+    pub fn query_with(&self, expr: &Expr<'_>, resolver: &dyn Resolver) -> Result<Vec<Id>> {
+        Ok(self.evaluate(expr, resolver)?.unwrap_or_default())
+    }
+
+    /// Evaluate an expression, where `None` indicates that the expression
+    /// doesn't constrain the result.
+    fn evaluate(&self, expr: &Expr<'_>, resolver: &dyn Resolver) -> Result<Option<Vec<Id>>> {
+        let output = match expr {
+            Expr::Term(Term::Text { field, text }) => {
+                let mut output = self.lookup(text)?;
+
+                // Phrases found as written aren't also found with okurigana
+                // left out.
+                let found = output.iter().map(Id::key).collect::<HashSet<_>>();
+
+                for id in self.lookup_skeleton(text)? {
+                    if !found.contains(&id.key()) {
+                        output.push(id);
+                    }
+                }
+
+                output.retain(|id| id.source.is_field(*field));
+                output
+            }
+            Expr::Term(term @ (Term::Note(..) | Term::Tag(..))) => {
+                let mut output = Vec::new();
+
+                for sequence in resolver.resolve(term) {
+                    let Ok(sequence) = u32::try_from(sequence) else {
+                        continue;
+                    };
+
+                    output.extend(self.sequence_to_id(sequence)?);
+                }
+
+                output
+            }
+            Expr::Term(Term::Pos(pos)) => {
+                let mut output = Vec::new();
+
+                for pos in pos.iter() {
+                    let mut set = Set::new();
+                    set.insert(pos);
+                    output.extend(self.by_pos(set)?);
+                }
+
+                output
+            }
+            Expr::Or(exprs) => {
+                let mut output = Vec::new();
+
+                for expr in exprs {
+                    output.extend(self.evaluate(expr, resolver)?.unwrap_or_default());
+                }
+
+                output
+            }
+            Expr::And(exprs) => {
+                let mut included = Vec::new();
+                let mut excluded = HashSet::new();
+                let mut negated = false;
+
+                for expr in exprs {
+                    if let Expr::Not(expr) = expr {
+                        negated = true;
+
+                        for id in self.evaluate(expr, resolver)?.unwrap_or_default() {
+                            excluded.insert(id.key());
+                        }
+                    } else if let Some(ids) = self.evaluate(expr, resolver)? {
+                        included.push(ids);
+                    }
+                }
+
+                // Only negated expressions can't be enumerated, so they match
+                // nothing.
+                if included.is_empty() {
+                    return Ok(negated.then(Vec::new));
+                }
+
+                let mut keys = included
+                    .iter()
+                    .map(|ids| ids.iter().map(Id::key).collect::<HashSet<_>>());
+
+                let mut common = keys.next().unwrap_or_default();
+
+                for other in keys {
+                    common.retain(|key| other.contains(key));
+                }
+
+                common.retain(|key| !excluded.contains(key));
+
+                let mut output = Vec::new();
+
+                for ids in included {
+                    output.extend(ids.into_iter().filter(|id| common.contains(&id.key())));
+                }
+
+                output
+            }
+            // A lone negation can't be enumerated, so it matches nothing.
+            Expr::Not(..) => Vec::new(),
+            Expr::Empty => return Ok(None),
+        };
+
+        Ok(Some(output))
+    }
+
     /// Perform the given search.
-    pub fn search(&self, mut input: &str) -> Result<Search<'_>> {
+    ///
+    /// The input is parsed using the [`query`] language.
+    pub fn search(&self, input: &str) -> Result<Search<'_>, SearchError> {
+        self.search_with(input, &())
+    }
+
+    /// Perform the given search, resolving terms which match user data
+    /// through the given resolver.
+    pub fn search_with(
+        &self,
+        input: &str,
+        resolver: &dyn Resolver,
+    ) -> Result<Search<'_>, SearchError> {
+        if self.indexes.is_empty() {
+            return Err(SearchError::IndexMissing);
+        }
+
         let mut phrases = Vec::new();
         let mut names = Vec::new();
         let mut characters = Vec::new();
@@ -1079,28 +2266,15 @@ impl Database {
         let mut dedup_names = HashMap::new();
         let mut seen = HashSet::new();
 
-        let mut tags = fixed_map::Set::new();
-
-        input = input.trim();
-
-        while let Some(n) = input.rfind('#') {
-            let (prefix, suffix) = input.split_at(n);
-
-            input = prefix.trim();
+        let expr = query::parse(input);
+        let texts = expr.texts();
+        let input = texts.first().copied().unwrap_or_default();
 
-            if let Some(suffix) = suffix.strip_prefix('#') {
-                if let Some(pos) = PartOfSpeech::parse_keyword(suffix) {
-                    tags.insert(pos);
-                }
-            }
+        for text in &texts {
+            self.populate_kanji(text, &mut seen, &mut characters)?;
         }
 
-        let results = if input.is_empty() || input.chars().all(|c| matches!(c, '*' | '＊')) {
-            self.by_pos(tags)?
-        } else {
-            self.populate_kanji(input, &mut seen, &mut characters)?;
-            self.lookup(input)?
-        };
+        let results = self.query_with(&expr, resolver)?;
 
         for id in results {
             match self.entry_at(id)? {
@@ -1112,20 +2286,6 @@ impl Database {
                     continue;
                 }
                 Entry::Phrase(entry) => {
-                    if !tags.is_empty() {
-                        let mut matched = tags;
-
-                        entry.senses.iter().for_each(|sense| {
-                            for p in sense.pos.iter() {
-                                matched.remove(p);
-                            }
-                        });
-
-                        if !matched.is_empty() {
-                            continue;
-                        }
-                    }
-
                     let Some(&i) = dedup_phrases.get(&id.key()) else {
                         dedup_phrases.insert(id.key(), phrases.len());
 
@@ -1168,18 +2328,40 @@ impl Database {
             }
         }
 
+        let skeleton = Source::Phrase {
+            index: PhraseIndex::Skeleton,
+        };
+
         for (data, e) in &mut phrases {
             let inflection = data.sources.iter().any(|source| source.is_inflection());
-            data.weight = e.weight(input, inflection);
+            data.weight = e.weight(input, inflection, &self.ranking);
         }
 
         names.sort_by(|a, b| a.0.weight.cmp(&b.0.weight));
-        phrases.sort_by(|a, b| a.0.weight.cmp(&b.0.weight));
+
+        // Phrases only matching a query which left out okurigana come after
+        // everything which matched the query as written.
+        phrases.sort_by_key(|(data, _)| {
+            let demoted = data.sources.contains(&skeleton)
+                && data.sources.iter().all(|source| match source {
+                    Source::Phrase { index } => *index == PhraseIndex::Skeleton,
+                    Source::Inflection { .. } => false,
+                    _ => true,
+                });
+
+            (demoted, data.weight)
+        });
+
+        let mut facets = BTreeMap::new();
 
         for (_, entry) in &phrases {
             for kanji in &entry.kanji_elements {
                 self.populate_kanji(kanji.text, &mut seen, &mut characters)?;
             }
+
+            for group in entry_groups(entry) {
+                *facets.entry(group).or_default() += 1;
+            }
         }
 
         for (_, entry) in &names {
@@ -1192,9 +2374,129 @@ impl Database {
             phrases,
             names,
             characters,
+            facets,
         })
     }
 
+    /// Search kanji by reading and meaning, where kanji have to match both if
+    /// both are given.
+    ///
+    /// Readings are onyomi and kunyomi in kana or romaji, like `こう`, `コウ` or
+    /// `kou`, where kunyomi match with or without their okurigana, like `ひか`
+    /// or `ひか.る`. Meanings are English terms, like `light`. Both support a
+    /// trailing `*` to match by prefix.
+    ///
+    /// Kanji are ordered by how frequently they are used.
+    pub fn search_kanji(
+        &self,
+        reading: Option<&str>,
+        meaning: Option<&str>,
+    ) -> Result<Vec<kanjidic2::Character<'_>>> {
+        let reading = reading.map(str::trim).filter(|s| !s.is_empty());
+        let meaning = meaning
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty());
+
+        let mut found = None::<Vec<kanjidic2::Character<'_>>>;
+
+        if let Some(reading) = reading {
+            found = Some(self.lookup_kanji(reading, |index| {
+                !matches!(
+                    index,
+                    KanjiIndex::Literal | KanjiIndex::Meaning | KanjiIndex::Other
+                )
+            })?);
+        }
+
+        if let Some(meaning) = &meaning {
+            let by_meaning =
+                self.lookup_kanji(meaning, |index| matches!(index, KanjiIndex::Meaning))?;
+
+            found = Some(match found {
+                Some(mut found) => {
+                    let literals = by_meaning.iter().map(|c| c.literal).collect::<HashSet<_>>();
+                    found.retain(|c| literals.contains(c.literal));
+                    found
+                }
+                None => by_meaning,
+            });
+        }
+
+        let mut found = found.unwrap_or_default();
+
+        // Kanji without a frequency are rare, and are put last.
+        found.sort_by_key(|c| {
+            (
+                c.misc.freq.unwrap_or(u32::MAX),
+                c.misc.grade.unwrap_or(u8::MAX),
+                c.misc.stroke_count.unwrap_or(u8::MAX),
+            )
+        });
+
+        Ok(found)
+    }
+
+    /// Look up kanji indexed by the given query in the ways matched by
+    /// `filter`.
+    fn lookup_kanji(
+        &self,
+        query: &str,
+        filter: impl Fn(KanjiIndex) -> bool,
+    ) -> Result<Vec<kanjidic2::Character<'_>>> {
+        let mut seen = HashSet::new();
+        let mut output = Vec::new();
+
+        for id in self.lookup(query)? {
+            let Source::Kanji { index } = id.source else {
+                continue;
+            };
+
+            if !filter(index) {
+                continue;
+            }
+
+            if let Entry::Kanji(kanji) = self.entry_at(id)? {
+                if seen.insert(kanji.literal) {
+                    output.push(kanji);
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Look up phrases by the kanji of the given text, for phrases written
+    /// with okurigana which the text leaves out.
+    ///
+    /// Only text made up of at least two kanji is looked up this way.
+    fn lookup_skeleton(&self, text: &str) -> Result<Vec<Id>> {
+        let mut output = Vec::new();
+
+        if text.chars().count() < 2 || !text.chars().all(detect::is_kanji) {
+            return Ok(output);
+        }
+
+        let source = Source::Phrase {
+            index: PhraseIndex::Skeleton,
+        };
+
+        for (index, d) in self.indexes.iter().enumerate() {
+            let Some(offsets) = d.header.by_skeleton.get(d.data.as_buf(), text)? else {
+                continue;
+            };
+
+            for &offset in d.data.as_buf().load(*offsets)? {
+                output.push(Id {
+                    index: index as u32,
+                    offset,
+                    source,
+                });
+            }
+        }
+
+        Ok(output)
+    }
+
     fn populate_kanji<'this>(
         &'this self,
         input: &str,
@@ -1230,52 +2532,280 @@ impl Database {
         Ok(())
     }
 
+    /// Estimate the difficulty of a phrase.
+    pub fn difficulty(&self, entry: &jmdict::Entry<'_>) -> Result<Difficulty> {
+        let element = entry
+            .kanji_elements
+            .iter()
+            .find(|k| !k.is_search_only())
+            .or_else(|| entry.kanji_elements.first());
+
+        let mut priorities = Vec::new();
+        let mut kanji = Vec::new();
+
+        match element {
+            Some(element) => {
+                priorities.extend(element.priority.iter().copied());
+
+                let mut buf = [0; 4];
+
+                for c in element.text.chars() {
+                    if is_hiragana(c) || is_katakana(c) || !c.is_alphabetic() || c.is_ascii() {
+                        continue;
+                    }
+
+                    let info = match self.literal_to_kanji(c.encode_utf8(&mut buf))? {
+                        Some(character) => difficulty::KanjiInfo {
+                            grade: character.misc.grade,
+                            jlpt: character.misc.jlpt,
+                        },
+                        None => difficulty::KanjiInfo::default(),
+                    };
+
+                    kanji.push(info);
+                }
+            }
+            None => {
+                for reading in &entry.reading_elements {
+                    priorities.extend(reading.priority.iter().copied());
+                }
+            }
+        }
+
+        Ok(difficulty::estimate(&priorities, kanji))
+    }
+
+    /// Split text into the longest phrases which can be found in it, skipping
+    /// over anything which doesn't match a phrase.
+    ///
+    /// The text is [normalized] before phrases are looked up, while the
+    /// ranges of the spans refer to the text as given.
+    ///
+    /// [normalized]: crate::normalize
+    pub fn phrase_spans(&self, q: &str) -> Result<Vec<PhraseSpan<'_>>> {
+        self.phrase_spans_with(q, &[])
+    }
+
+    /// Split text into phrases like [`Database::phrase_spans`], but prefer
+    /// phrases which end where one of the given words ends, like the words
+    /// found by a morphological analyzer through [`crate::tokenizer`].
+    ///
+    /// Where no phrase ends with a word, the longest phrase is used.
+    pub fn phrase_spans_with(
+        &self,
+        q: &str,
+        words: &[Range<usize>],
+    ) -> Result<Vec<PhraseSpan<'_>>> {
+        let ends = words.iter().map(|word| word.end).collect::<HashSet<_>>();
+
+        let normalized = Normalized::new(q);
+        let q = normalized.as_str();
+
+        let mut output = Vec::new();
+        let mut start = 0;
+
+        while let Some(suffix) = q.get(start..).filter(|s| !s.is_empty()) {
+            let mut found = None;
+
+            if !ends.is_empty() {
+                found = self.longest_phrase(suffix, |len| {
+                    ends.contains(&normalized.original(start..start + len).end)
+                })?;
+            }
+
+            if found.is_none() {
+                found = self.longest_phrase(suffix, |_| true)?;
+            }
+
+            match found {
+                Some((len, entry)) => {
+                    output.push(PhraseSpan {
+                        range: normalized.original(start..start + len),
+                        entry,
+                    });
+
+                    start += len;
+                }
+                None => {
+                    start += suffix.chars().next().map_or(1, char::len_utf8);
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Find the longest phrase which the given text starts with and whose
+    /// length is accepted, picking the best weighted phrase if there are
+    /// several.
+    fn longest_phrase(
+        &self,
+        q: &str,
+        accept: impl Fn(usize) -> bool,
+    ) -> Result<Option<(usize, jmdict::Entry<'_>)>> {
+        let mut best = None::<(usize, Weight, jmdict::Entry<'_>)>;
+
+        for (index, d) in self.indexes.iter().enumerate() {
+            for (string, values) in d.prefixes(q)?.into_iter().rev() {
+                if matches!(&best, Some((len, ..)) if string.len() < *len) {
+                    break;
+                }
+
+                if !accept(string.len()) {
+                    continue;
+                }
+
+                for stored_id in values {
+                    let id = self.convert_id(index, *stored_id)?;
+
+                    let Entry::Phrase(e) = d.entry_at(id)? else {
+                        continue;
+                    };
+
+                    let weight = e.weight(string, id.source.is_inflection(), &self.ranking);
+
+                    let is_better = match &best {
+                        Some((len, w, _)) => string.len() > *len || weight > *w,
+                        None => true,
+                    };
+
+                    if is_better {
+                        best = Some((string.len(), weight, e));
+                    }
+                }
+            }
+        }
+
+        Ok(best.map(|(len, _, e)| (len, e)))
+    }
+
     /// Analyze the given string, looking it up in the database and returning
-    /// all prefix matching entries and their texts.
-    pub fn analyze<'q>(&self, q: &'q str, start: usize) -> Result<BTreeMap<Weight, &'q str>> {
-        let Some(suffix) = q.get(start..) else {
-            return Ok(BTreeMap::new());
+    /// all prefix matching texts as candidates ordered from best to worst.
+    pub fn analyze<'q>(&self, q: &'q str, start: usize) -> Result<Vec<Candidate<'q>>> {
+        self.analyze_span(q, start, q.len())
+    }
+
+    /// Analyze the given string like [`Database::analyze`], but only consider
+    /// candidates which lie entirely inside of `start..end`.
+    ///
+    /// Invalid ranges have no candidates.
+    pub fn analyze_span<'q>(
+        &self,
+        q: &'q str,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<Candidate<'q>>> {
+        self.analyze_span_with(q, start, end, &AnalyzeOptions::default())
+    }
+
+    /// Analyze the given string like [`Database::analyze_span`] with the
+    /// given options.
+    ///
+    /// The text is [normalized] before it's looked up, while candidates are
+    /// the text as given, so that they can be pointed out in it. Decorations
+    /// which are left out when normalizing have no candidates.
+    ///
+    /// [normalized]: crate::normalize
+    pub fn analyze_span_with<'q>(
+        &self,
+        q: &'q str,
+        start: usize,
+        end: usize,
+        options: &AnalyzeOptions<'_>,
+    ) -> Result<Vec<Candidate<'q>>> {
+        let Some(suffix) = q.get(start..end) else {
+            return Ok(Vec::new());
         };
 
-        let mut results = HashMap::<_, Weight>::new();
+        let normalized = Normalized::new(suffix);
+
+        if normalized.original(0..0).start != 0 {
+            return Ok(Vec::new());
+        }
 
-        let mut it = suffix.chars();
+        let mut results = HashMap::<_, Score>::new();
 
-        while !it.as_str().is_empty() {
-            for (index, d) in self.indexes.iter().enumerate() {
-                let Some(values) = d.header.lookup.get(d.data.as_buf(), it.as_str())? else {
-                    continue;
-                };
+        for (index, d) in self.indexes.iter().enumerate() {
+            for (string, values) in d.prefixes(normalized.as_str())? {
+                let chars = string.chars().count();
+                let string = &q[start..start + normalized.original(0..string.len()).end];
 
                 for stored_id in values {
                     let id = self.convert_id(index, *stored_id)?;
 
-                    let key = match d.entry_at(id)? {
-                        Entry::Phrase(e) => e.weight(it.as_str(), id.source.is_inflection()),
-                        Entry::Name(e) => e.weight(it.as_str()).boost(0.5),
-                        Entry::Kanji(e) => e.weight(it.as_str()).boost(0.5),
+                    let score = match d.entry_at(id)? {
+                        Entry::Phrase(e) => {
+                            let mut pos = phrase_prior(&e);
+
+                            if id.source.is_inflection() {
+                                pos *= INFLECTION_PRIOR;
+                            }
+
+                            Score::new(phrase_frequency(&e), chars, pos)
+                        }
+                        Entry::Name(..) => {
+                            let prior = options.names.prior(q, start..start + string.len());
+                            Score::new(1.0, chars, prior)
+                        }
+                        Entry::Kanji(..) => Score::new(1.0, chars, KANJI_PRIOR),
                     };
 
-                    match results.entry(it.as_str()) {
+                    match results.entry(string) {
                         hash_map::Entry::Occupied(mut e) => {
-                            e.insert((*e.get()).max(key));
+                            if score.total > e.get().total {
+                                e.insert(score);
+                            }
                         }
                         hash_map::Entry::Vacant(e) => {
-                            e.insert(key);
+                            e.insert(score);
                         }
                     }
                 }
             }
-
-            it.next_back();
         }
 
-        let mut inputs = BTreeMap::new();
-
-        for (string, key) in results {
-            inputs.insert(key, string);
+        let mut candidates = results
+            .into_iter()
+            .map(|(string, score)| Candidate { string, score })
+            .collect::<Vec<_>>();
+
+        candidates.sort_by(|a, b| {
+            b.score
+                .total
+                .total_cmp(&a.score.total)
+                .then_with(|| b.string.len().cmp(&a.string.len()))
+                .then_with(|| a.string.cmp(b.string))
+        });
+
+        if !options.words.is_empty() {
+            let ends = options
+                .words
+                .iter()
+                .map(|word| word.end)
+                .collect::<HashSet<_>>();
+
+            // The sort is stable, so candidates otherwise keep their order.
+            candidates.sort_by_key(|c| !ends.contains(&(start + c.string.len())));
         }
 
-        Ok(inputs)
+        Ok(candidates)
     }
 }
+
+/// Test if the text around `range` in `q` suggests that it's a name, like a
+/// following honorific, a middle dot joining it with another part of a name,
+/// or it being romaji starting with a capital letter.
+fn suggests_name(q: &str, range: Range<usize>) -> bool {
+    let (Some(before), Some(text), Some(after)) = (
+        q.get(..range.start),
+        q.get(range.clone()),
+        q.get(range.end..),
+    ) else {
+        return false;
+    };
+
+    HONORIFICS.iter().any(|h| after.starts_with(h))
+        || after.starts_with(MIDDLE_DOTS)
+        || before.ends_with(MIDDLE_DOTS)
+        || text.starts_with(|c: char| c.is_ascii_uppercase())
+}
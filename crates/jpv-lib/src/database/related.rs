@@ -0,0 +1,165 @@
+//! Build-time computation of related phrases.
+//!
+//! Phrases are related if they share kanji or if their glossaries share
+//! words. Features which are too common to be meaningful are ignored, which
+//! also bounds the amount of work performed per phrase.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::jmdict;
+use crate::romaji::{is_hiragana, is_katakana};
+
+/// Features which are shared by more phrases than this are ignored.
+const MAX_POSTINGS: usize = 128;
+/// The maximum number of related phrases stored for each phrase.
+const MAX_RELATED: usize = 8;
+/// The minimum score a candidate needs to be considered related.
+const MIN_SCORE: u32 = 2;
+/// Score contributed by each shared kanji.
+const KANJI_SCORE: u32 = 2;
+/// Score contributed by each shared glossary word.
+const GLOSS_SCORE: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Feature {
+    Kanji(char),
+    Gloss(Box<str>),
+}
+
+impl Feature {
+    fn score(&self) -> u32 {
+        match self {
+            Feature::Kanji(..) => KANJI_SCORE,
+            Feature::Gloss(..) => GLOSS_SCORE,
+        }
+    }
+}
+
+/// Collects features of phrases and computes which ones are related.
+#[derive(Default)]
+pub(super) struct RelatedBuilder {
+    phrases: Vec<(u32, Box<[Feature]>)>,
+    postings: HashMap<Feature, Vec<u32>>,
+}
+
+impl RelatedBuilder {
+    /// Insert a phrase stored at the given offset.
+    pub(super) fn insert(&mut self, offset: u32, entry: &jmdict::Entry<'_>) {
+        let mut features = BTreeSet::new();
+
+        for kanji in &entry.kanji_elements {
+            for c in kanji.text.chars() {
+                if is_hiragana(c) || is_katakana(c) || !c.is_alphabetic() || c.is_ascii() {
+                    continue;
+                }
+
+                features.insert(Feature::Kanji(c));
+            }
+        }
+
+        for sense in &entry.senses {
+            for gloss in &sense.gloss {
+                if gloss.ty == Some("expl") {
+                    continue;
+                }
+
+                for word in gloss.text.split(|c: char| !c.is_alphabetic()) {
+                    let word = word.to_lowercase();
+
+                    if word.len() < 3 || is_stop_word(&word) {
+                        continue;
+                    }
+
+                    features.insert(Feature::Gloss(word.into()));
+                }
+            }
+        }
+
+        let index = self.phrases.len() as u32;
+
+        for feature in &features {
+            self.postings
+                .entry(feature.clone())
+                .or_default()
+                .push(index);
+        }
+
+        self.phrases.push((offset, features.into_iter().collect()));
+    }
+
+    /// Compute related phrases, returning a list of offsets for every phrase
+    /// which has any.
+    pub(super) fn build(self) -> Vec<(u32, Vec<u32>)> {
+        let mut output = Vec::new();
+        let mut scores = HashMap::<u32, u32>::new();
+
+        for (index, (offset, features)) in self.phrases.iter().enumerate() {
+            scores.clear();
+
+            for feature in features.iter() {
+                let Some(postings) = self.postings.get(feature) else {
+                    continue;
+                };
+
+                if postings.len() > MAX_POSTINGS {
+                    continue;
+                }
+
+                for &other in postings {
+                    if other as usize != index {
+                        *scores.entry(other).or_default() += feature.score();
+                    }
+                }
+            }
+
+            let mut candidates = scores
+                .iter()
+                .filter(|(_, &score)| score >= MIN_SCORE)
+                .map(|(&other, &score)| (score, other))
+                .collect::<Vec<_>>();
+
+            if candidates.is_empty() {
+                continue;
+            }
+
+            // Highest score first, ties broken by insertion order so that the
+            // output is deterministic.
+            candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+            let related = candidates
+                .into_iter()
+                .take(MAX_RELATED)
+                .map(|(_, other)| self.phrases[other as usize].0)
+                .collect();
+
+            output.push((*offset, related));
+        }
+
+        output
+    }
+}
+
+fn is_stop_word(word: &str) -> bool {
+    matches!(
+        word,
+        "the"
+            | "and"
+            | "for"
+            | "with"
+            | "one"
+            | "someone"
+            | "something"
+            | "etc"
+            | "from"
+            | "that"
+            | "which"
+            | "who"
+            | "not"
+            | "being"
+            | "have"
+            | "make"
+            | "into"
+            | "its"
+            | "such"
+    )
+}
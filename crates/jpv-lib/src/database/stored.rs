@@ -18,6 +18,8 @@ impl trie::Flavor for CompactTrie {
 pub(super) struct GlobalHeader {
     pub(super) magic: u32,
     pub(super) version: u32,
+    /// Checksum of everything following the global header.
+    pub(super) checksum: u32,
     pub(super) index: Ref<IndexHeader, Little>,
 }
 
@@ -29,7 +31,64 @@ pub(super) struct IndexHeader {
     pub(super) by_pos: swiss::MapRef<PartOfSpeech, Ref<[PhrasePos]>>,
     pub(super) by_kanji_literal: swiss::MapRef<Ref<str>, u32>,
     pub(super) by_sequence: swiss::MapRef<u32, PhrasePos>,
+    pub(super) related: swiss::MapRef<u32, Ref<[u32]>>,
+    pub(super) examples: Ref<[Example]>,
+    pub(super) sentences: Ref<[Sentence]>,
+    pub(super) by_headword: swiss::MapRef<Ref<str>, Ref<[SenseExample]>>,
+    /// Ideographic description sequences by character.
+    pub(super) components: swiss::MapRef<u32, Ref<str>>,
+    /// Offsets of the phrases written using each kanji, the most frequently
+    /// used first.
+    pub(super) by_kanji: swiss::MapRef<u32, Ref<[u32]>>,
+    /// Offsets of phrases by the kanji of their kanji readings without
+    /// okurigana, like `落着` for `落ち着く`.
+    pub(super) by_skeleton: swiss::MapRef<Ref<str>, Ref<[u32]>>,
     pub(super) inflections: Ref<[InflectionData]>,
+    /// Compressed blocks of entries, empty if entries are stored inline.
+    pub(super) blocks: Ref<[Block]>,
+}
+
+/// A compressed block of entries.
+#[derive(Clone, Copy, ZeroCopy)]
+#[repr(C)]
+pub(super) struct Block {
+    pub(super) data: Ref<[u8]>,
+    /// The size of the block when decompressed.
+    pub(super) size: u32,
+}
+
+/// An example sentence and the phrase it belongs to.
+#[derive(Clone, Copy, ZeroCopy)]
+#[repr(C)]
+pub(super) struct Example {
+    pub(super) text: Ref<str>,
+    /// English translation, empty if there is none.
+    pub(super) translation: Ref<str>,
+    pub(super) offset: u32,
+}
+
+/// A sentence pair from an example corpus.
+#[derive(Clone, Copy, ZeroCopy)]
+#[repr(C)]
+pub(super) struct Sentence {
+    pub(super) text: Ref<str>,
+    pub(super) translation: Ref<str>,
+    /// How good the sentence is as an example, where higher is better.
+    pub(super) score: f32,
+}
+
+/// A reference to a sentence using a word in a specific sense.
+#[derive(Clone, Copy, ZeroCopy)]
+#[repr(C)]
+pub(super) struct SenseExample {
+    /// Reading of the word, empty if unspecified.
+    pub(super) reading: Ref<str>,
+    /// Index of the sentence in the sentences table.
+    pub(super) sentence: u32,
+    /// The 1-based sense the word is used in, or 0 if unspecified.
+    pub(super) sense: u32,
+    /// If the sentence has been checked as a good example of the word.
+    pub(super) checked: u32,
 }
 
 /// Extra information about an index.
@@ -19,6 +19,7 @@ pub struct StringIndexer<'a> {
     existing: BTreeMap<&'a str, usize>,
     reuse: usize,
     total: usize,
+    saved: usize,
 }
 
 impl<'a> StringIndexer<'a> {
@@ -27,6 +28,7 @@ impl<'a> StringIndexer<'a> {
             existing: BTreeMap::new(),
             reuse: 0,
             total: 0,
+            saved: 0,
         }
     }
 
@@ -40,6 +42,11 @@ impl<'a> StringIndexer<'a> {
         self.total
     }
 
+    /// Get the number of bytes saved by re-using strings.
+    pub fn saved(&self) -> usize {
+        self.saved
+    }
+
     fn find(&self, input: &str) -> Option<Ref<str>> {
         let (&key, &offset) = self.existing.range(input..).next()?;
 
@@ -57,6 +64,7 @@ impl<'a> StringIndexer<'a> {
 
         if let Some(r) = self.find(input) {
             self.reuse += 1;
+            self.saved += input.len();
             debug_assert_eq!(buf.load(r)?, input);
             return Ok(r);
         }
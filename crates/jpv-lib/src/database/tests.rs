@@ -0,0 +1,337 @@
+use musli_zerocopy::OwnedBuf;
+use tempfile::TempDir;
+
+use crate::config::Config;
+use crate::data;
+use crate::reporter::EmptyReporter;
+use crate::token::Token;
+
+use super::blocks::{BlockWriter, Blocks};
+use super::check::checksum;
+use super::stored;
+use super::string_indexer::StringIndexer;
+use super::{
+    build, suggests_name, Database, Input, Location, NameWeighting, PhraseIndex, SearchError,
+    Source,
+};
+
+const KANJIDIC2: &str = r#"<kanjidic2>
+<character>
+<literal>光</literal>
+<misc><grade>2</grade><stroke_count>6</stroke_count><freq>360</freq></misc>
+<reading_meaning><rmgroup>
+<reading r_type="ja_on">コウ</reading>
+<reading r_type="ja_kun">ひか.る</reading>
+<reading r_type="ja_kun">ひかり</reading>
+<meaning>ray</meaning>
+<meaning>light</meaning>
+</rmgroup></reading_meaning>
+</character>
+<character>
+<literal>高</literal>
+<misc><grade>2</grade><stroke_count>10</stroke_count><freq>65</freq></misc>
+<reading_meaning><rmgroup>
+<reading r_type="pinyin">gao1</reading>
+<reading r_type="ja_on">コウ</reading>
+<reading r_type="ja_kun">たか.い</reading>
+<meaning>tall</meaning>
+<meaning>high</meaning>
+</rmgroup></reading_meaning>
+</character>
+<character>
+<literal>軽</literal>
+<misc><grade>3</grade><stroke_count>12</stroke_count><freq>1090</freq></misc>
+<reading_meaning><rmgroup>
+<reading r_type="ja_on">ケイ</reading>
+<reading r_type="ja_kun">かる.い</reading>
+<meaning>lightly</meaning>
+<meaning>light</meaning>
+</rmgroup></reading_meaning>
+</character>
+</kanjidic2>
+"#;
+
+fn block_entries(compress: bool) {
+    let mut buf = OwnedBuf::new();
+    let mut writer = BlockWriter::new(compress);
+
+    let entries = (0..5000)
+        .map(|n| format!("entry {n}: {}", "食べる ".repeat(n % 7)))
+        .collect::<Vec<_>>();
+
+    let mut offsets = Vec::new();
+
+    for entry in &entries {
+        offsets.push(writer.store(&mut buf, entry.as_bytes()).unwrap());
+    }
+
+    let blocks = writer.finish(&mut buf);
+    assert_eq!(blocks.is_empty(), !compress);
+
+    let loaded = Blocks::new(blocks.len());
+
+    for (entry, offset) in entries.iter().zip(offsets) {
+        let bytes = loaded.load(&buf, blocks, offset).unwrap();
+        assert!(bytes.starts_with(entry.as_bytes()), "{entry}");
+    }
+}
+
+#[test]
+fn blocks() {
+    block_entries(false);
+    block_entries(true);
+}
+
+#[test]
+fn string_indexer() {
+    let mut buf = OwnedBuf::new();
+    let mut indexer = StringIndexer::new();
+
+    let a = indexer.store(&mut buf, "食べられる").unwrap();
+    let len = buf.len();
+
+    let b = indexer.store(&mut buf, "られる").unwrap();
+    let c = indexer.store(&mut buf, "食べられる").unwrap();
+    assert_eq!(buf.len(), len, "suffixes and duplicates reuse storage");
+
+    let d = indexer.store(&mut buf, "食べ").unwrap();
+    assert_eq!(buf.len(), len, "prefixes reuse storage");
+
+    let e = indexer.store(&mut buf, "飲む").unwrap();
+    assert!(buf.len() > len, "new strings are stored");
+
+    assert_eq!(buf.load(a).unwrap(), "食べられる");
+    assert_eq!(buf.load(b).unwrap(), "られる");
+    assert_eq!(buf.load(c).unwrap(), "食べられる");
+    assert_eq!(buf.load(d).unwrap(), "食べ");
+    assert_eq!(buf.load(e).unwrap(), "飲む");
+
+    assert_eq!(indexer.total(), 5);
+    assert_eq!(indexer.reuse(), 3);
+    assert_eq!(
+        indexer.saved(),
+        "られる".len() + "食べられる".len() + "食べ".len()
+    );
+}
+
+#[test]
+fn checksum_skips_header() {
+    let header = std::mem::size_of::<stored::GlobalHeader>();
+
+    let mut a = vec![0u8; header + 64];
+    let mut b = a.clone();
+    b[0] = 1;
+    assert_eq!(checksum(&a), checksum(&b));
+
+    a[header] = 1;
+    assert_ne!(checksum(&a), checksum(&b));
+}
+
+#[test]
+fn search_without_indexes() {
+    let db = Database::open([], &Config::default()).unwrap();
+    let error = db.search("食べる").err().unwrap();
+    assert!(matches!(error, SearchError::IndexMissing));
+}
+
+#[test]
+fn name_context() {
+    let text = "田中さんは本田・ジョンと話した";
+    let at = |name: &str| {
+        let start = text.find(name).unwrap();
+        start..start + name.len()
+    };
+
+    assert!(suggests_name(text, at("田中")));
+    assert!(suggests_name(text, at("本田")));
+    assert!(suggests_name(text, at("ジョン")));
+    assert!(!suggests_name(text, at("話")));
+    assert!(suggests_name("Tanaka", 0..6));
+    assert!(!suggests_name("tanaka", 0..6));
+}
+
+#[test]
+fn name_weighting_idents() {
+    for names in [NameWeighting::Context, NameWeighting::Plain] {
+        assert_eq!(NameWeighting::parse(names.ident()), Some(names));
+    }
+}
+
+const JMDICT: &str = r#"<JMdict>
+<entry><ent_seq>1</ent_seq>
+<k_ele><keb>光線</keb></k_ele>
+<r_ele><reb>こうせん</reb></r_ele>
+<sense><pos>&n;</pos><gloss>ray of light</gloss></sense>
+</entry>
+<entry><ent_seq>2</ent_seq>
+<k_ele><keb>光</keb><ke_pri>ichi1</ke_pri><ke_pri>news1</ke_pri></k_ele>
+<k_ele><keb>光り</keb></k_ele>
+<r_ele><reb>ひかり</reb></r_ele>
+<sense><pos>&n;</pos><gloss>light</gloss></sense>
+</entry>
+<entry><ent_seq>3</ent_seq>
+<k_ele><keb>日光</keb><ke_pri>news2</ke_pri></k_ele>
+<r_ele><reb>にっこう</reb></r_ele>
+<sense><pos>&n;</pos><gloss>sunlight</gloss></sense>
+</entry>
+<entry><ent_seq>4</ent_seq>
+<r_ele><reb>ひかる</reb></r_ele>
+<sense><pos>&v5r;</pos><gloss>to shine</gloss></sense>
+</entry>
+<entry><ent_seq>5</ent_seq>
+<k_ele><keb>落ち着く</keb><ke_pri>ichi1</ke_pri></k_ele>
+<k_ele><keb>落着く</keb></k_ele>
+<r_ele><reb>おちつく</reb></r_ele>
+<sense><pos>&v5k;</pos><gloss>to calm down</gloss></sense>
+</entry>
+<entry><ent_seq>6</ent_seq>
+<k_ele><keb>落着</keb></k_ele>
+<r_ele><reb>らくちゃく</reb></r_ele>
+<sense><pos>&n;</pos><gloss>settlement</gloss></sense>
+</entry>
+<entry><ent_seq>7</ent_seq>
+<k_ele><keb>光り輝く</keb></k_ele>
+<r_ele><reb>ひかりかがやく</reb></r_ele>
+<sense><pos>&v5k;</pos><gloss>to shine brilliantly</gloss></sense>
+</entry>
+<entry><ent_seq>8</ent_seq>
+<r_ele><reb>ピカピカ</reb></r_ele>
+<sense><pos>&adv;</pos><gloss>glitteringly</gloss></sense>
+</entry>
+</JMdict>
+"#;
+
+/// Build an index from the given input and open it, returning the
+/// directory it's stored in which is removed once dropped.
+fn open_index(name: &str, input: Input<'_>) -> (Database, TempDir) {
+    let buf = build(&EmptyReporter, &Token::default(), name, input, false).unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(format!("{name}.index"));
+    std::fs::write(&path, buf.as_slice()).unwrap();
+
+    let data = data::open(&path).unwrap();
+    let db = Database::open([(data, Location::Path(path.into()))], &Config::default()).unwrap();
+
+    (db, dir)
+}
+
+#[test]
+fn search_kanji() {
+    let (db, _dir) = open_index("kanjidic2", Input::Kanjidic2(KANJIDIC2));
+
+    let search = |reading: Option<&str>, meaning: Option<&str>| {
+        db.search_kanji(reading, meaning)
+            .unwrap()
+            .iter()
+            .map(|c| c.literal.to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    // Ordered by frequency.
+    assert_eq!(search(Some("こう"), None), ["高", "光"]);
+    assert_eq!(search(Some("コウ"), None), ["高", "光"]);
+    assert_eq!(search(Some("kou"), None), ["高", "光"]);
+    assert_eq!(search(Some("ひか"), None), ["光"]);
+    assert_eq!(search(Some("ひか.る"), None), ["光"]);
+    assert_eq!(search(None, Some("Light")), ["光", "軽"]);
+    assert_eq!(search(Some("こう"), Some("light")), ["光"]);
+    assert_eq!(search(None, Some("light*")), ["光", "軽"]);
+
+    // Neither literals nor readings in other languages are readings.
+    assert!(search(Some("光"), None).is_empty());
+    assert!(search(Some("gao1"), None).is_empty());
+    assert!(search(Some("light"), None).is_empty());
+    assert!(search(None, None).is_empty());
+
+    drop(db);
+}
+
+#[test]
+fn entries_with_kanji() {
+    let (db, _dir) = open_index("jmdict", Input::Jmdict(JMDICT));
+
+    let words = |c: char, limit: usize, offset: usize| {
+        db.entries_with_kanji(c, limit, offset)
+            .unwrap()
+            .iter()
+            .map(|e| e.sequence)
+            .collect::<Vec<_>>()
+    };
+
+    // The most frequently used first, and otherwise in dictionary order.
+    assert_eq!(words('光', 10, 0), [2, 3, 1, 7]);
+    assert_eq!(words('光', 2, 0), [2, 3]);
+    assert_eq!(words('光', 2, 2), [1, 7]);
+    assert_eq!(words('光', 10, 4), [] as [u64; 0]);
+    assert_eq!(words('線', 10, 0), [1]);
+    assert_eq!(words('日', 0, 0), [] as [u64; 0]);
+    assert_eq!(words('闇', 10, 0), [] as [u64; 0]);
+
+    drop(db);
+}
+
+#[test]
+fn search_without_okurigana() {
+    let (db, _dir) = open_index("jmdict", Input::Jmdict(JMDICT));
+
+    let search = |q: &str| {
+        db.search(q)
+            .unwrap()
+            .phrases
+            .iter()
+            .map(|(data, e)| {
+                let skeleton = data.sources.contains(&Source::Phrase {
+                    index: PhraseIndex::Skeleton,
+                });
+
+                (e.sequence, skeleton)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The phrase written as queried comes first.
+    assert_eq!(search("落着"), [(6, false), (5, true)]);
+    assert_eq!(search("光輝"), [(7, true)]);
+    assert_eq!(search("落ち着く"), [(5, false)]);
+
+    // Matching as written as well isn't demoted.
+    assert_eq!(search("落着 OR 落ち着く"), [(5, true), (6, false)]);
+
+    // The rest of the query applies to them too.
+    assert_eq!(search("落着 pos:noun"), [(6, false)]);
+    assert_eq!(search("落着 pos:verb"), [(5, true)]);
+    assert!(search("落着 -pos:verb")
+        .iter()
+        .all(|&(_, skeleton)| !skeleton));
+
+    // Single kanji would match too much.
+    assert!(search("光").iter().all(|&(_, skeleton)| !skeleton));
+
+    drop(db);
+}
+
+#[test]
+fn analyze_normalized() {
+    let (db, _dir) = open_index("jmdict", Input::Jmdict(JMDICT));
+
+    let q = "★ﾋﾟｶﾋﾟｶ☆日光";
+
+    // Decorations are not part of any candidate.
+    assert!(db.analyze(q, 0).unwrap().is_empty());
+
+    // Candidates are the text as given.
+    let candidates = db.analyze(q, 3).unwrap();
+    assert_eq!(candidates[0].string, "ﾋﾟｶﾋﾟｶ");
+
+    let spans = db
+        .phrase_spans(q)
+        .unwrap()
+        .iter()
+        .map(|span| (&q[span.range.clone()], span.entry.sequence))
+        .collect::<Vec<_>>();
+
+    assert_eq!(spans, [("ﾋﾟｶﾋﾟｶ", 8), ("日光", 3)]);
+
+    drop(db);
+}
@@ -0,0 +1,147 @@
+//! Detection of which script a query is written in.
+//!
+//! This is used to decide how a query should be searched for, so that romaji
+//! can be converted to kana while English is searched for as-is.
+
+#[cfg(test)]
+mod tests;
+
+use serde::{Deserialize, Serialize};
+
+use crate::romaji::{self, is_hiragana, is_katakana, Kind};
+
+/// The script a query is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Script {
+    /// The query has no text to classify.
+    Empty,
+    /// The query contains kanji.
+    Kanji,
+    /// The query contains kana but no kanji.
+    Kana,
+    /// The query is made up of latin letters which can all be read as
+    /// romaji.
+    Romaji,
+    /// The query is made up of latin letters, or anything else.
+    English,
+}
+
+impl Script {
+    /// Keyword used to refer to the script.
+    pub fn ident(&self) -> &'static str {
+        match self {
+            Script::Empty => "empty",
+            Script::Kanji => "kanji",
+            Script::Kana => "kana",
+            Script::Romaji => "romaji",
+            Script::English => "english",
+        }
+    }
+
+    /// Human readable title of the script.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Script::Empty => "Empty",
+            Script::Kanji => "Kanji",
+            Script::Kana => "Kana",
+            Script::Romaji => "Romaji",
+            Script::English => "English",
+        }
+    }
+}
+
+/// Detect the script of the given query.
+///
+/// Words containing a `:` are query syntax like `pos:verb` and are ignored.
+pub fn detect(input: &str) -> Script {
+    let mut has_text = false;
+    let mut is_romaji = true;
+
+    for word in words(input) {
+        for c in word.chars() {
+            if is_kanji(c) {
+                return Script::Kanji;
+            }
+        }
+
+        if word.chars().any(is_kana) {
+            return Script::Kana;
+        }
+
+        has_text = true;
+        is_romaji &= is_romaji_word(word);
+    }
+
+    match (has_text, is_romaji) {
+        (false, _) => Script::Empty,
+        (true, true) => Script::Romaji,
+        (true, false) => Script::English,
+    }
+}
+
+/// Convert the romaji in a query to hiragana, leaving query syntax as-is.
+pub fn to_hiragana(input: &str) -> String {
+    let mut output = String::new();
+
+    for (n, word) in input.split(' ').enumerate() {
+        if n > 0 {
+            output.push(' ');
+        }
+
+        if word.contains(':') {
+            output.push_str(word);
+            continue;
+        }
+
+        for segment in romaji::analyze(&word.to_lowercase()) {
+            output.push_str(hiragana(&segment));
+        }
+    }
+
+    output
+}
+
+/// Test if the given character is a kanji.
+pub fn is_kanji(c: char) -> bool {
+    matches!(c, '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' | '\u{f900}'..='\u{faff}' | '々')
+}
+
+fn is_kana(c: char) -> bool {
+    is_hiragana(c) || is_katakana(c) || c == 'ー'
+}
+
+/// Words of the query which are not query syntax.
+fn words(input: &str) -> impl Iterator<Item = &str> {
+    input
+        .split_whitespace()
+        .filter(|word| !word.contains(':') && word.chars().any(char::is_alphanumeric))
+}
+
+/// Test if a word is entirely made up of romaji, allowing for punctuation.
+fn is_romaji_word(word: &str) -> bool {
+    let word = word.to_lowercase();
+    let mut any = false;
+
+    for segment in romaji::analyze(&word) {
+        match segment.kind() {
+            Kind::Romaji => any = true,
+            Kind::Latin if segment == "n" => any = true,
+            Kind::Other => {}
+            _ => return false,
+        }
+    }
+
+    any
+}
+
+/// Convert a segment to hiragana.
+///
+/// A lone `n` can only be read as `ん`, since it would otherwise have been
+/// combined with the vowel following it.
+fn hiragana<'a>(segment: &romaji::Segment<'a>) -> &'a str {
+    match segment.hiragana() {
+        "n" => "ん",
+        string => string,
+    }
+}
@@ -0,0 +1,26 @@
+use super::{detect, to_hiragana, Script};
+
+#[test]
+fn scripts() {
+    assert_eq!(detect(""), Script::Empty);
+    assert_eq!(detect("  "), Script::Empty);
+    assert_eq!(detect("食べる"), Script::Kanji);
+    assert_eq!(detect("たべる"), Script::Kana);
+    assert_eq!(detect("コーヒー"), Script::Kana);
+    assert_eq!(detect("taberu"), Script::Romaji);
+    assert_eq!(detect("Nihongo"), Script::Romaji);
+    assert_eq!(detect("kitte wo kau"), Script::Romaji);
+    assert_eq!(detect("hello"), Script::English);
+    assert_eq!(detect("to eat"), Script::English);
+    assert_eq!(detect("pos:verb taberu"), Script::Romaji);
+    assert_eq!(detect("pos:verb"), Script::Empty);
+    assert_eq!(detect("123"), Script::English);
+}
+
+#[test]
+fn hiragana() {
+    assert_eq!(to_hiragana("taberu"), "たべる");
+    assert_eq!(to_hiragana("nihongo"), "にほんご");
+    assert_eq!(to_hiragana("Kitte wo kau"), "きって を かう");
+    assert_eq!(to_hiragana("pos:verb taberu"), "pos:verb たべる");
+}
@@ -0,0 +1,94 @@
+//! Estimation of how difficult a word is for a learner.
+//!
+//! Difficulty combines how frequently a word is used, as indicated by its
+//! priority tags, with how advanced the kanji it's written with are based on
+//! their school grade and JLPT level.
+
+#[cfg(test)]
+mod tests;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Priority;
+
+/// The hardest difficulty level.
+pub const MAX_LEVEL: u8 = 5;
+
+/// Words ranked before this are considered as common as they get.
+const COMMON_RANK: f32 = 500.0;
+/// Words ranked past this are considered as rare as they get.
+const RARE_RANK: f32 = 50000.0;
+/// How much word frequency contributes to difficulty, the rest is
+/// contributed by kanji.
+const FREQUENCY_WEIGHT: f32 = 0.6;
+
+/// The estimated difficulty of a word.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Difficulty {
+    /// Difficulty level from 1 (easiest) to 5 (hardest).
+    pub level: u8,
+    /// Difficulty score from 0.0 (easiest) to 1.0 (hardest).
+    pub score: f32,
+}
+
+/// Information about a kanji used to estimate difficulty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KanjiInfo {
+    /// School grade of the kanji.
+    pub grade: Option<u8>,
+    /// Level of the kanji in the pre-2010 JLPT.
+    pub jlpt: Option<u8>,
+}
+
+/// Estimate the difficulty of a word with the given priorities, written with
+/// the given kanji.
+pub fn estimate<'a, P, K>(priorities: P, kanji: K) -> Difficulty
+where
+    P: IntoIterator<Item = &'a Priority>,
+    K: IntoIterator<Item = KanjiInfo>,
+{
+    let rank = priorities.into_iter().map(|p| p.frequency_rank()).min();
+
+    // Words without any priority tags are rare.
+    let frequency = match rank {
+        Some(rank) => {
+            let rank = (rank as f32).clamp(COMMON_RANK, RARE_RANK);
+            (rank / COMMON_RANK).ln() / (RARE_RANK / COMMON_RANK).ln()
+        }
+        None => 1.0,
+    };
+
+    // Words are as hard as their hardest kanji, and kana-only words only
+    // depend on frequency.
+    let kanji = kanji
+        .into_iter()
+        .map(|k| (grade_score(k.grade) + jlpt_score(k.jlpt)) / 2.0)
+        .fold(0.0f32, f32::max);
+
+    let score = frequency * FREQUENCY_WEIGHT + kanji * (1.0 - FREQUENCY_WEIGHT);
+    let level = (1.0 + score * f32::from(MAX_LEVEL)).floor() as u8;
+
+    Difficulty {
+        level: level.clamp(1, MAX_LEVEL),
+        score,
+    }
+}
+
+/// Score a school grade, where kyōiku kanji are the easiest and kanji
+/// outside of the jōyō and jinmeiyō lists are the hardest.
+fn grade_score(grade: Option<u8>) -> f32 {
+    match grade {
+        Some(grade @ 1..=6) => f32::from(grade - 1) / 10.0,
+        Some(8) => 0.7,
+        Some(_) => 0.9,
+        None => 1.0,
+    }
+}
+
+/// Score a pre-2010 JLPT level, which goes from 4 (easiest) to 1 (hardest).
+fn jlpt_score(jlpt: Option<u8>) -> f32 {
+    match jlpt {
+        Some(level @ 1..=4) => f32::from(4 - level) / 3.0,
+        _ => 1.0,
+    }
+}
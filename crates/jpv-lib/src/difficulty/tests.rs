@@ -0,0 +1,48 @@
+use super::{estimate, KanjiInfo, MAX_LEVEL};
+use crate::Priority;
+
+fn priorities(tags: &[&str]) -> Vec<Priority> {
+    tags.iter().flat_map(|t| Priority::parse(t)).collect()
+}
+
+#[test]
+fn common_kana_word_is_easy() {
+    let difficulty = estimate(&priorities(&["ichi1", "nf01"]), []);
+    assert_eq!(difficulty.level, 1);
+}
+
+#[test]
+fn rare_word_with_rare_kanji_is_hard() {
+    let difficulty = estimate(&priorities(&[]), [KanjiInfo::default()]);
+    assert_eq!(difficulty.level, MAX_LEVEL);
+}
+
+#[test]
+fn harder_kanji_is_harder() {
+    let p = priorities(&["news1", "nf10"]);
+
+    let easy = estimate(
+        &p,
+        [KanjiInfo {
+            grade: Some(1),
+            jlpt: Some(4),
+        }],
+    );
+
+    let hard = estimate(
+        &p,
+        [
+            KanjiInfo {
+                grade: Some(1),
+                jlpt: Some(4),
+            },
+            KanjiInfo {
+                grade: Some(8),
+                jlpt: Some(1),
+            },
+        ],
+    );
+
+    assert!(easy.score < hard.score);
+    assert!(easy.level <= hard.level);
+}
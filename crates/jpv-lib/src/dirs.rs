@@ -2,43 +2,122 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use directories::ProjectDirs;
 
+/// A file which, when placed next to the executable, puts it in portable mode.
+pub const PORTABLE_MARKER: &str = "jpv.portable";
+
 /// Directories helper.
 pub struct Dirs {
-    project_dirs: ProjectDirs,
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+    cache_dir: PathBuf,
 }
 
 impl Dirs {
     /// Open directories for this project.
     pub fn open() -> Result<Dirs> {
+        let project_dirs = ProjectDirs::from("se", "tedro", "jpv")
+            .context("Could not figure out base directories")?;
+
+        Ok(Dirs {
+            config_dir: project_dirs.config_dir().to_owned(),
+            data_dir: project_dirs.data_dir().to_owned(),
+            cache_dir: project_dirs.cache_dir().to_owned(),
+        })
+    }
+
+    /// Open directories for a portable install, where everything is stored
+    /// in folders next to the executable.
+    pub fn portable() -> Result<Dirs> {
+        let root = executable_dir()?;
+
         Ok(Dirs {
-            project_dirs: directories::ProjectDirs::from("se", "tedro", "jpv")
-                .context("Could not figure out base directories")?,
+            config_dir: root.join("data"),
+            data_dir: root.join("data"),
+            cache_dir: root.join("cache"),
         })
     }
 
+    /// Test if a portable marker has been placed next to the executable.
+    pub fn is_portable() -> bool {
+        match executable_dir() {
+            Ok(root) => root.join(PORTABLE_MARKER).is_file(),
+            Err(..) => false,
+        }
+    }
+
+    /// Store data in the given directory instead.
+    ///
+    /// The configuration is stored alongside the data, so that all state is
+    /// kept in one folder which can be moved around.
+    pub fn with_data_dir(self, path: PathBuf) -> Self {
+        Self {
+            config_dir: path.clone(),
+            data_dir: path,
+            ..self
+        }
+    }
+
+    /// Store cached downloads in the given directory instead.
+    pub fn with_cache_dir(self, path: PathBuf) -> Self {
+        Self {
+            cache_dir: path,
+            ..self
+        }
+    }
+
     /// Get the configuration directory.
     pub fn config_dir(&self) -> &Path {
-        self.project_dirs.config_dir()
+        &self.config_dir
+    }
+
+    /// Get the directory where indexes and user data are stored.
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
     }
 
     /// Get the path of the configuration file.
     pub fn config_path(&self) -> PathBuf {
-        self.project_dirs.config_dir().join("config.toml")
+        self.config_dir.join("config.toml")
     }
 
     /// The path to an individual index.
     pub fn index_path(&self, name: &str) -> PathBuf {
-        self.project_dirs.data_dir().join(format!("{name}.index"))
+        self.data_dir.join(format!("{name}.index"))
+    }
+
+    /// The directory where user profiles are stored.
+    pub fn profiles_dir(&self) -> PathBuf {
+        self.data_dir.join("profiles")
+    }
+
+    /// The directory where documents opened in the reader are stored.
+    pub fn documents_dir(&self) -> PathBuf {
+        self.data_dir.join("documents")
+    }
+
+    /// The directory where audio recorded by the user is stored.
+    pub fn recordings_dir(&self) -> PathBuf {
+        self.data_dir.join("recordings")
+    }
+
+    /// The directory where mined sentence cards are stored.
+    pub fn mining_dir(&self) -> PathBuf {
+        self.data_dir.join("mining")
+    }
+
+    /// The directory where usage statistics are stored.
+    pub fn stats_dir(&self) -> PathBuf {
+        self.data_dir.join("stats")
     }
 
     /// Get dictionary path.
     pub fn indexes(&self) -> Result<Vec<PathBuf>> {
         let mut indexes = Vec::new();
 
-        let d = match fs::read_dir(self.project_dirs.data_dir()) {
+        let d = match fs::read_dir(&self.data_dir) {
             Ok(d) => d,
             Err(e) if e.kind() == io::ErrorKind::NotFound => {
                 return Ok(indexes);
@@ -69,6 +148,17 @@ impl Dirs {
     where
         P: AsRef<Path>,
     {
-        self.project_dirs.cache_dir().join(path)
+        self.cache_dir.join(path)
     }
 }
+
+/// The directory the running executable is in.
+fn executable_dir() -> Result<PathBuf> {
+    let exe = std::env::current_exe().context("Could not find the executable")?;
+
+    let Some(dir) = exe.parent() else {
+        bail!("{}: Missing parent directory", exe.display());
+    };
+
+    Ok(dir.to_owned())
+}
@@ -220,6 +220,89 @@ entity! {
     }
 }
 
+/// A coarse grouping of parts of speech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PartOfSpeechGroup {
+    Verb,
+    Noun,
+    Adjective,
+    Adverb,
+    Expression,
+    Other,
+}
+
+impl PartOfSpeechGroup {
+    pub const VALUES: &'static [PartOfSpeechGroup] = &[
+        PartOfSpeechGroup::Verb,
+        PartOfSpeechGroup::Noun,
+        PartOfSpeechGroup::Adjective,
+        PartOfSpeechGroup::Adverb,
+        PartOfSpeechGroup::Expression,
+        PartOfSpeechGroup::Other,
+    ];
+
+    /// Keyword used to refer to the group.
+    pub fn ident(&self) -> &'static str {
+        match self {
+            PartOfSpeechGroup::Verb => "verb",
+            PartOfSpeechGroup::Noun => "noun",
+            PartOfSpeechGroup::Adjective => "adjective",
+            PartOfSpeechGroup::Adverb => "adverb",
+            PartOfSpeechGroup::Expression => "expression",
+            PartOfSpeechGroup::Other => "other",
+        }
+    }
+
+    /// Human readable title of the group.
+    pub fn title(&self) -> &'static str {
+        match self {
+            PartOfSpeechGroup::Verb => "Verbs",
+            PartOfSpeechGroup::Noun => "Nouns",
+            PartOfSpeechGroup::Adjective => "Adjectives",
+            PartOfSpeechGroup::Adverb => "Adverbs",
+            PartOfSpeechGroup::Expression => "Expressions",
+            PartOfSpeechGroup::Other => "Other",
+        }
+    }
+
+    /// Parse a group keyword, accepting both singular and plural forms.
+    pub fn parse_keyword(string: &str) -> Option<PartOfSpeechGroup> {
+        let string = string.strip_suffix('s').unwrap_or(string);
+
+        match string {
+            "verb" => Some(PartOfSpeechGroup::Verb),
+            "noun" => Some(PartOfSpeechGroup::Noun),
+            "adjective" | "adj" => Some(PartOfSpeechGroup::Adjective),
+            "adverb" | "adv" => Some(PartOfSpeechGroup::Adverb),
+            "expression" | "exp" => Some(PartOfSpeechGroup::Expression),
+            "other" => Some(PartOfSpeechGroup::Other),
+            _ => None,
+        }
+    }
+}
+
+impl PartOfSpeech {
+    /// Get the coarse group this part of speech belongs to.
+    pub fn group(&self) -> PartOfSpeechGroup {
+        let ident = self.ident();
+
+        if ident.starts_with("adj") {
+            PartOfSpeechGroup::Adjective
+        } else if ident.starts_with("adv") {
+            PartOfSpeechGroup::Adverb
+        } else if ident == "exp" {
+            PartOfSpeechGroup::Expression
+        } else if ident.starts_with('n') && ident != "num" {
+            PartOfSpeechGroup::Noun
+        } else if ident.starts_with('v') || ident == "aux-v" {
+            PartOfSpeechGroup::Verb
+        } else {
+            PartOfSpeechGroup::Other
+        }
+    }
+}
+
 entity! {
     #[derive(Encode, Decode, Serialize, Deserialize)]
     pub enum KanjiInfo {
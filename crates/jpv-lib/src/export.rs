@@ -0,0 +1,272 @@
+//! Export dictionary entries to formats used by other dictionary readers.
+//!
+//! Entries are first converted into an [`Article`], which contains everything
+//! an exporter needs including the inflected forms of the word. Inflections
+//! are exported as alternate lookup words, so that conjugated words can be
+//! looked up in readers which don't know how to deinflect Japanese.
+
+#[cfg(test)]
+mod tests;
+
+pub mod kindle;
+pub mod pdf;
+pub mod stardict;
+
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+
+use crate::inflection;
+use crate::jmdict;
+use crate::Form;
+
+/// A dictionary entry prepared for export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Article {
+    /// The sequence number of the entry.
+    pub sequence: u64,
+    /// The word the article is listed under.
+    pub headword: String,
+    /// The reading of the headword, if it is written using kanji.
+    pub reading: Option<String>,
+    /// Other ways of writing the word.
+    pub variants: Vec<String>,
+    /// Meanings of the word.
+    pub senses: Vec<Sense>,
+    /// Inflected forms of the word.
+    pub inflections: Vec<InflectedForm>,
+}
+
+/// A single meaning of an article.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sense {
+    /// Descriptions of the parts of speech the sense applies to.
+    pub pos: Vec<&'static str>,
+    /// Glossary entries.
+    pub glosses: Vec<String>,
+    /// Additional information about the sense.
+    pub info: Option<String>,
+}
+
+/// An inflected form of an article.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InflectedForm {
+    /// The forms which make up the inflection.
+    pub forms: Vec<Form>,
+    /// The inflected text.
+    pub text: String,
+    /// The reading of the inflected text.
+    pub reading: String,
+}
+
+impl Article {
+    /// Build an article from a dictionary entry, only including glossary
+    /// entries in the given language unless it is `None`.
+    ///
+    /// Returns `None` if the entry has no glossary entries to export.
+    pub fn new(entry: &jmdict::Entry<'_>, lang: Option<&str>) -> Option<Self> {
+        let kanji = entry.kanji_elements.iter().filter(|k| !k.is_search_only());
+        let readings = entry
+            .reading_elements
+            .iter()
+            .filter(|r| !r.is_search_only());
+
+        let (headword, reading) = match kanji.clone().next() {
+            Some(k) => {
+                let reading = readings.clone().find(|r| r.applies_to(k.text));
+                (k.text, reading.map(|r| r.text))
+            }
+            None => {
+                let r = readings
+                    .clone()
+                    .next()
+                    .or_else(|| entry.reading_elements.first())?;
+                (r.text, None)
+            }
+        };
+
+        let mut variants = Vec::new();
+
+        for text in kanji.map(|k| k.text).chain(readings.map(|r| r.text)) {
+            if text != headword && Some(text) != reading && !variants.iter().any(|v| v == text) {
+                variants.push(text.to_owned());
+            }
+        }
+
+        let mut senses = Vec::new();
+
+        for sense in &entry.senses {
+            let glosses = sense
+                .gloss
+                .iter()
+                .filter(|g| lang.map_or(true, |lang| g.is_lang(lang)))
+                .map(|g| g.text.to_owned())
+                .collect::<Vec<_>>();
+
+            if glosses.is_empty() {
+                continue;
+            }
+
+            senses.push(Sense {
+                pos: sense.pos.iter().map(|pos| pos.help()).collect(),
+                glosses,
+                info: sense.info.map(str::to_owned),
+            });
+        }
+
+        if senses.is_empty() {
+            return None;
+        }
+
+        let mut inflections = Vec::new();
+        let mut seen = HashSet::new();
+
+        for (_, c, _) in inflection::conjugate(entry) {
+            for (inflection, fragments) in c.iter() {
+                let text = format!("{}{}", fragments.text(), fragments.suffix());
+
+                if !seen.insert(text.clone()) {
+                    continue;
+                }
+
+                inflections.push(InflectedForm {
+                    forms: inflection.iter().collect(),
+                    reading: format!("{}{}", fragments.reading(), fragments.suffix()),
+                    text,
+                });
+            }
+        }
+
+        Some(Self {
+            sequence: entry.sequence,
+            headword: headword.to_owned(),
+            reading: reading.map(str::to_owned),
+            variants,
+            senses,
+            inflections,
+        })
+    }
+
+    /// Other words the article can be looked up through, excluding the
+    /// headword.
+    pub fn words(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        seen.insert(self.headword.as_str());
+
+        let inflections = self
+            .inflections
+            .iter()
+            .flat_map(|i| [i.text.as_str(), i.reading.as_str()]);
+
+        self.reading
+            .as_deref()
+            .into_iter()
+            .chain(self.variants.iter().map(String::as_str))
+            .chain(inflections)
+            .filter(|word| seen.insert(*word))
+            .collect()
+    }
+
+    /// Render the article as HTML.
+    pub fn html(&self) -> String {
+        let mut o = String::new();
+        // Writing to a string can't fail.
+        _ = self.write_html(&mut o);
+        o
+    }
+
+    fn write_html(&self, o: &mut String) -> fmt::Result {
+        write!(o, "<p><b>{}</b>", Escape(&self.headword))?;
+
+        if let Some(reading) = &self.reading {
+            write!(o, " 【{}】", Escape(reading))?;
+        }
+
+        write!(o, "</p>")?;
+
+        if !self.variants.is_empty() {
+            write!(o, "<p>Also written as: ")?;
+
+            for (n, variant) in self.variants.iter().enumerate() {
+                if n > 0 {
+                    write!(o, ", ")?;
+                }
+
+                write!(o, "{}", Escape(variant))?;
+            }
+
+            write!(o, "</p>")?;
+        }
+
+        write!(o, "<ol>")?;
+
+        for sense in &self.senses {
+            write!(o, "<li>")?;
+
+            if !sense.pos.is_empty() {
+                write!(o, "<i>{}</i> ", Escape(&sense.pos.join(", ")))?;
+            }
+
+            write!(o, "{}", Escape(&sense.glosses.join("; ")))?;
+
+            if let Some(info) = &sense.info {
+                write!(o, " <small>({})</small>", Escape(info))?;
+            }
+
+            write!(o, "</li>")?;
+        }
+
+        write!(o, "</ol>")?;
+
+        if !self.inflections.is_empty() {
+            write!(o, "<p><b>Conjugations</b></p><ul>")?;
+
+            for i in &self.inflections {
+                write!(o, "<li>{}", Escape(&i.text))?;
+
+                if i.reading != i.text {
+                    write!(o, " 【{}】", Escape(&i.reading))?;
+                }
+
+                write!(o, " &ndash; ")?;
+
+                for (n, form) in i.forms.iter().enumerate() {
+                    if n > 0 {
+                        write!(o, ", ")?;
+                    }
+
+                    write!(o, "{}", Escape(form.describe()))?;
+                }
+
+                write!(o, "</li>")?;
+            }
+
+            write!(o, "</ul>")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape a string for use in HTML or XML.
+pub(crate) struct Escape<'a>(pub(crate) &'a str);
+
+impl fmt::Display for Escape<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rest = self.0;
+
+        while let Some(n) = rest.find(['&', '<', '>', '"']) {
+            f.write_str(&rest[..n])?;
+
+            f.write_str(match rest.as_bytes()[n] {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                _ => "&quot;",
+            })?;
+
+            rest = &rest[n + 1..];
+        }
+
+        f.write_str(rest)
+    }
+}
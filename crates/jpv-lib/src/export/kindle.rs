@@ -0,0 +1,152 @@
+//! Writer for Kindle dictionaries.
+//!
+//! This produces the source package of a dictionary, an OPF manifest and a
+//! number of HTML content files, which is converted into a MOBI file using
+//! kindlegen or Kindle Previewer. Inflected forms of each word are listed as
+//! `idx:iform` elements, so that the Kindle can look up conjugated words.
+
+use std::fmt::{self, Write};
+
+use super::{Article, Escape};
+
+/// The name of the lookup index entries are added to.
+const INDEX: &str = "japanese";
+
+/// Information about the exported dictionary.
+pub struct Info<'a> {
+    /// Unique identifier of the dictionary.
+    pub identifier: &'a str,
+    /// The title of the dictionary.
+    pub title: &'a str,
+    /// The language of the glossary entries, as a two-letter language code.
+    pub out_language: &'a str,
+}
+
+/// Render an entry for the given article.
+pub fn entry(article: &Article) -> String {
+    let mut o = String::new();
+    // Writing to a string can't fail.
+    _ = write_entry(&mut o, article);
+    o
+}
+
+fn write_entry(o: &mut String, article: &Article) -> fmt::Result {
+    write!(
+        o,
+        "<idx:entry name=\"{INDEX}\" scriptable=\"yes\" spell=\"yes\">"
+    )?;
+    write!(o, "<idx:orth value=\"{}\">", Escape(&article.headword))?;
+
+    let words = article.words();
+
+    if !words.is_empty() {
+        write!(o, "<idx:infl>")?;
+
+        for word in words {
+            write!(o, "<idx:iform value=\"{}\"/>", Escape(word))?;
+        }
+
+        write!(o, "</idx:infl>")?;
+    }
+
+    write!(o, "</idx:orth>")?;
+    o.push_str(&article.html());
+    write!(o, "</idx:entry><hr/>")?;
+    Ok(())
+}
+
+/// Wrap rendered entries into a content file.
+pub fn content(entries: &str) -> String {
+    let mut o = String::new();
+    o.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    o.push_str("<html xmlns:idx=\"https://kindlegen.s3.amazonaws.com/AmazonKindlePublishingGuidelines.pdf\" xmlns:mbp=\"https://kindlegen.s3.amazonaws.com/AmazonKindlePublishingGuidelines.pdf\">\n");
+    o.push_str(
+        "<head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=utf-8\"/></head>\n",
+    );
+    o.push_str("<body><mbp:frameset>\n");
+    o.push_str(entries);
+    o.push_str("\n</mbp:frameset></body>\n</html>\n");
+    o
+}
+
+/// Render the OPF manifest of a dictionary made up of the given content
+/// files.
+pub fn opf<I>(info: &Info<'_>, files: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut o = String::new();
+    // Writing to a string can't fail.
+    _ = write_opf(&mut o, info, files);
+    o
+}
+
+fn write_opf<I>(o: &mut String, info: &Info<'_>, files: I) -> fmt::Result
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    writeln!(o, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")?;
+    writeln!(o, "<package unique-identifier=\"uid\" version=\"2.0\">")?;
+    writeln!(o, "<metadata>")?;
+    writeln!(
+        o,
+        "<dc-metadata xmlns:dc=\"http://purl.org/metadata/dublin_core\">"
+    )?;
+    writeln!(
+        o,
+        "<dc:Identifier id=\"uid\">{}</dc:Identifier>",
+        Escape(info.identifier)
+    )?;
+    writeln!(o, "<dc:Title>{}</dc:Title>", Escape(info.title))?;
+    writeln!(o, "<dc:Language>ja</dc:Language>")?;
+    writeln!(o, "</dc-metadata>")?;
+    writeln!(o, "<x-metadata>")?;
+    writeln!(o, "<DictionaryInLanguage>ja</DictionaryInLanguage>")?;
+    writeln!(
+        o,
+        "<DictionaryOutLanguage>{}</DictionaryOutLanguage>",
+        Escape(info.out_language)
+    )?;
+    writeln!(o, "<DefaultLookupIndex>{INDEX}</DefaultLookupIndex>")?;
+    writeln!(o, "</x-metadata>")?;
+    writeln!(o, "</metadata>")?;
+
+    let mut spine = String::new();
+
+    writeln!(o, "<manifest>")?;
+
+    for (n, file) in files.into_iter().enumerate() {
+        writeln!(
+            o,
+            "<item id=\"content{n}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>",
+            Escape(file.as_ref())
+        )?;
+        writeln!(spine, "<itemref idref=\"content{n}\"/>")?;
+    }
+
+    writeln!(o, "</manifest>")?;
+    writeln!(o, "<spine>")?;
+    o.push_str(&spine);
+    writeln!(o, "</spine>")?;
+    writeln!(o, "</package>")?;
+    Ok(())
+}
+
+/// Convert a three-letter language code as used by JMdict into the two-letter
+/// code used by Kindle dictionaries.
+pub fn language_code(lang: &str) -> Option<&'static str> {
+    Some(match lang {
+        "eng" => "en",
+        "dut" => "nl",
+        "fre" => "fr",
+        "ger" => "de",
+        "hun" => "hu",
+        "rus" => "ru",
+        "slv" => "sl",
+        "spa" => "es",
+        "swe" => "sv",
+        _ => return None,
+    })
+}
@@ -0,0 +1,437 @@
+//! Writer for printable vocabulary lists in PDF.
+//!
+//! Text is set in one of the standard Japanese fonts of the `Adobe-Japan1`
+//! character collection, which PDF readers are expected to provide, so no font
+//! has to be embedded. Lines are broken by estimating the width of each
+//! character, where ASCII is set half-width and everything else full-width.
+
+use std::collections::HashSet;
+
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo};
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str, TextStr};
+use serde::{Deserialize, Serialize};
+
+use super::Article;
+
+/// The name the font is referred to by in page resources.
+const FONT: Name<'static> = Name(b"F1");
+/// The standard gothic font used for all text.
+const BASE_FONT: Name<'static> = Name(b"HeiseiKakuGo-W5");
+/// Unicode encoding which maps ASCII to half-width glyphs.
+const ENCODING: Name<'static> = Name(b"UniJIS-UCS2-HW-H");
+/// The last glyph in the collection which is half-width.
+const LAST_HALF_WIDTH: u16 = 632;
+/// Used in place of characters which can't be encoded.
+const REPLACEMENT: u16 = 0x3013;
+/// Page margins in points.
+const MARGIN: f32 = 48.0;
+/// Line height relative to the font size.
+const LEADING: f32 = 1.4;
+/// Smallest and largest permitted font sizes in points.
+const FONT_SIZES: (f32, f32) = (6.0, 24.0);
+
+/// Paper size of an exported document.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Paper {
+    #[default]
+    A4,
+    Letter,
+}
+
+impl Paper {
+    /// Width and height in points, in portrait orientation.
+    fn size(self) -> (f32, f32) {
+        match self {
+            Paper::A4 => (595.0, 842.0),
+            Paper::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+/// How an exported document is laid out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layout {
+    /// The size of the paper.
+    pub paper: Paper,
+    /// Turn pages sideways.
+    pub landscape: bool,
+    /// Size of glossary text in points, headwords are set larger.
+    pub font_size: f32,
+    /// Include readings of headwords written using kanji.
+    pub readings: bool,
+    /// The maximum number of senses included per word, where `0` includes
+    /// all of them.
+    pub senses: usize,
+    /// Include a table of conjugations for each word.
+    pub conjugations: bool,
+    /// The maximum number of forms combined in a listed conjugation, like
+    /// `2` to include the negative past but not the polite negative past.
+    pub conjugation_forms: usize,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            paper: Paper::A4,
+            landscape: false,
+            font_size: 11.0,
+            readings: true,
+            senses: 0,
+            conjugations: false,
+            conjugation_forms: 1,
+        }
+    }
+}
+
+/// Render a vocabulary list with the given title as a PDF document.
+pub fn vocabulary(title: &str, articles: &[Article], layout: &Layout) -> Vec<u8> {
+    let mut w = Writer::new(layout);
+
+    w.line(title, w.size * 1.6, 0.0);
+    w.rule();
+
+    for article in articles {
+        write_article(&mut w, article, layout);
+        w.space(w.size * 0.8);
+    }
+
+    w.finish(title)
+}
+
+fn write_article(w: &mut Writer, article: &Article, layout: &Layout) {
+    let size = w.size;
+
+    let mut headword = article.headword.clone();
+
+    if layout.readings {
+        if let Some(reading) = &article.reading {
+            headword.push_str(&format!("【{reading}】"));
+        }
+    }
+
+    // Keep the headword together with its first sense.
+    w.reserve(size * (1.4 + 1.0) * LEADING);
+    w.line(&headword, size * 1.4, 0.0);
+
+    if !article.variants.is_empty() {
+        let variants = format!("Also written as: {}", article.variants.join(", "));
+        w.line(&variants, size * 0.85, size);
+    }
+
+    let limit = match layout.senses {
+        0 => usize::MAX,
+        n => n,
+    };
+
+    for (n, sense) in article.senses.iter().take(limit).enumerate() {
+        let mut text = format!("{}. ", n + 1);
+
+        if !sense.pos.is_empty() {
+            text.push_str(&format!("({}) ", sense.pos.join(", ")));
+        }
+
+        text.push_str(&sense.glosses.join("; "));
+
+        if let Some(info) = &sense.info {
+            text.push_str(&format!(" [{info}]"));
+        }
+
+        w.line(&text, size, size);
+    }
+
+    if layout.conjugations && !article.inflections.is_empty() {
+        w.space(size * 0.4);
+        w.line("Conjugations", size * 0.9, size);
+
+        let column = w.width() * 0.45;
+        let max_forms = layout.conjugation_forms.max(1);
+
+        // Other ways of writing the word conjugate the same way, so only the
+        // first inflection with a given reading and forms is listed.
+        let mut seen = HashSet::new();
+
+        for i in &article.inflections {
+            if i.forms.is_empty() || i.forms.len() > max_forms {
+                continue;
+            }
+
+            if !seen.insert((&i.reading, &i.forms)) {
+                continue;
+            }
+            let mut text = i.text.clone();
+
+            if layout.readings && i.reading != i.text {
+                text.push_str(&format!("【{}】", i.reading));
+            }
+
+            let forms = i
+                .forms
+                .iter()
+                .map(|f| f.describe())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            w.row(&text, &forms, size * 0.9, size * 2.0, column);
+        }
+    }
+}
+
+/// Lays out lines of text over pages.
+struct Writer {
+    /// Page width and height.
+    page: (f32, f32),
+    /// The base font size.
+    size: f32,
+    /// Vertical position of the next line, from the bottom of the page.
+    y: f32,
+    current: Content,
+    pages: Vec<Vec<u8>>,
+}
+
+impl Writer {
+    fn new(layout: &Layout) -> Self {
+        let (width, height) = layout.paper.size();
+
+        let page = if layout.landscape {
+            (height, width)
+        } else {
+            (width, height)
+        };
+
+        let size = if layout.font_size.is_finite() {
+            layout.font_size.clamp(FONT_SIZES.0, FONT_SIZES.1)
+        } else {
+            Layout::default().font_size
+        };
+
+        Self {
+            page,
+            size,
+            y: page.1 - MARGIN,
+            current: Content::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    /// The width available for text.
+    fn width(&self) -> f32 {
+        self.page.0 - MARGIN * 2.0
+    }
+
+    /// Add vertical space.
+    fn space(&mut self, amount: f32) {
+        self.y -= amount;
+    }
+
+    /// Start a new page unless there's at least the given amount of space
+    /// left on the current one.
+    fn reserve(&mut self, amount: f32) {
+        if self.y - amount < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn new_page(&mut self) {
+        let content = std::mem::replace(&mut self.current, Content::new());
+        self.pages.push(content.finish());
+        self.y = self.page.1 - MARGIN;
+    }
+
+    /// Write text indented by the given amount, wrapping it over as many
+    /// lines as necessary.
+    fn line(&mut self, text: &str, size: f32, indent: f32) {
+        let width = (self.width() - indent).max(size);
+
+        for line in wrap(text, size, width) {
+            self.reserve(size * LEADING);
+            self.y -= size * LEADING;
+            self.show(line, size, MARGIN + indent);
+        }
+    }
+
+    /// Write a row of a two column table, where the first column starts at
+    /// `indent` and the second one at `column`.
+    fn row(&mut self, first: &str, second: &str, size: f32, indent: f32, column: f32) {
+        let first = wrap(first, size, (column - indent - size).max(size));
+        let second = wrap(second, size, (self.width() - column).max(size));
+
+        for n in 0..first.len().max(second.len()) {
+            self.reserve(size * LEADING);
+            self.y -= size * LEADING;
+
+            if let Some(text) = first.get(n) {
+                self.show(text, size, MARGIN + indent);
+            }
+
+            if let Some(text) = second.get(n) {
+                self.show(text, size, MARGIN + column);
+            }
+        }
+    }
+
+    /// Draw a horizontal line across the page.
+    fn rule(&mut self) {
+        self.reserve(self.size);
+        self.y -= self.size * 0.5;
+
+        self.current
+            .set_line_width(0.5)
+            .move_to(MARGIN, self.y)
+            .line_to(self.page.0 - MARGIN, self.y)
+            .stroke();
+
+        self.y -= self.size * 0.5;
+    }
+
+    fn show(&mut self, text: &str, size: f32, x: f32) {
+        self.current
+            .begin_text()
+            .set_font(FONT, size)
+            .next_line(x, self.y)
+            .show(Str(&encode(text)))
+            .end_text();
+    }
+
+    fn finish(mut self, title: &str) -> Vec<u8> {
+        self.new_page();
+
+        let catalog_id = Ref::new(1);
+        let tree_id = Ref::new(2);
+        let font_id = Ref::new(3);
+        let cid_font_id = Ref::new(4);
+        let descriptor_id = Ref::new(5);
+        let info_id = Ref::new(6);
+
+        let mut next = 7;
+        let mut alloc = || {
+            let id = Ref::new(next);
+            next += 1;
+            id
+        };
+
+        let pages = self
+            .pages
+            .into_iter()
+            .map(|content| (alloc(), alloc(), content))
+            .collect::<Vec<_>>();
+
+        let mut pdf = Pdf::new();
+
+        pdf.catalog(catalog_id).pages(tree_id);
+        pdf.pages(tree_id)
+            .kids(pages.iter().map(|(id, ..)| *id))
+            .count(pages.len() as i32);
+
+        for (page_id, content_id, content) in &pages {
+            {
+                let mut page = pdf.page(*page_id);
+                page.parent(tree_id)
+                    .media_box(Rect::new(0.0, 0.0, self.page.0, self.page.1))
+                    .contents(*content_id);
+                page.resources().fonts().pair(FONT, font_id);
+            }
+
+            pdf.stream(*content_id, content);
+        }
+
+        pdf.type0_font(font_id)
+            .base_font(BASE_FONT)
+            .encoding_predefined(ENCODING)
+            .descendant_font(cid_font_id);
+
+        {
+            let mut cid_font = pdf.cid_font(cid_font_id);
+
+            cid_font
+                .subtype(CidFontType::Type0)
+                .base_font(BASE_FONT)
+                .system_info(SystemInfo {
+                    registry: Str(b"Adobe"),
+                    ordering: Str(b"Japan1"),
+                    supplement: 2,
+                })
+                .font_descriptor(descriptor_id)
+                .default_width(1000.0);
+
+            cid_font.widths().same(1, LAST_HALF_WIDTH, 500.0);
+        }
+
+        pdf.font_descriptor(descriptor_id)
+            .name(BASE_FONT)
+            .flags(FontFlags::SYMBOLIC)
+            .bbox(Rect::new(-92.0, -250.0, 1010.0, 922.0))
+            .italic_angle(0.0)
+            .ascent(880.0)
+            .descent(-120.0)
+            .cap_height(737.0)
+            .stem_v(114.0);
+
+        pdf.document_info(info_id)
+            .title(TextStr(title))
+            .producer(TextStr("jpv"));
+
+        pdf.finish()
+    }
+}
+
+/// The estimated width of a character in ems.
+fn char_width(c: char) -> f32 {
+    if c.is_ascii() || ('\u{ff61}'..='\u{ff9f}').contains(&c) {
+        0.5
+    } else {
+        1.0
+    }
+}
+
+/// Break text into lines which fit the given width.
+///
+/// Lines are broken at spaces where possible, and otherwise between any two
+/// characters, since Japanese doesn't separate words with spaces.
+fn wrap(text: &str, size: f32, width: f32) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut rest = text.trim();
+
+    while !rest.is_empty() {
+        let mut used = 0.0;
+        let mut end = rest.len();
+        let mut space = None;
+
+        for (n, c) in rest.char_indices() {
+            used += char_width(c) * size;
+
+            if used > width && n > 0 {
+                end = n;
+                break;
+            }
+
+            if c == ' ' {
+                space = Some(n);
+            }
+        }
+
+        if end < rest.len() {
+            if let Some(space) = space {
+                end = space;
+            }
+        }
+
+        lines.push(rest[..end].trim_end());
+        rest = rest[end..].trim_start();
+    }
+
+    lines
+}
+
+/// Encode text as big endian UCS-2.
+fn encode(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len() * 2);
+
+    for c in text.chars() {
+        let code = u16::try_from(u32::from(c)).unwrap_or(REPLACEMENT);
+        out.extend_from_slice(&code.to_be_bytes());
+    }
+
+    out
+}
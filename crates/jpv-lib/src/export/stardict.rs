@@ -0,0 +1,156 @@
+//! Writer for dictionaries in the StarDict format, which is read by GoldenDict
+//! and many other dictionary applications.
+//!
+//! A StarDict dictionary consists of the following files sharing a base name:
+//! * `.ifo` - A text file describing the dictionary.
+//! * `.idx` - Sorted headwords, with the location of their definitions.
+//! * `.dict` - The definitions, which are stored as HTML.
+//! * `.syn` - Sorted synonyms, referring to headwords in the `.idx` file.
+
+use std::cmp::Ordering;
+use std::io::Write;
+
+use anyhow::{bail, Result};
+
+use super::Article;
+
+/// Version of the StarDict format written, which is the first version
+/// supporting synonym files.
+const VERSION: &str = "2.4.2";
+
+/// Information about the exported dictionary.
+pub struct Info<'a> {
+    /// The name of the dictionary.
+    pub bookname: &'a str,
+    /// A description of the dictionary.
+    pub description: Option<&'a str>,
+}
+
+struct Word {
+    word: String,
+    offset: u32,
+    size: u32,
+}
+
+/// Writes a StarDict dictionary.
+///
+/// Definitions are written to the `.dict` output as articles are added, while
+/// headwords and synonyms are kept in memory until [`StarDict::finish`] is
+/// called since they have to be sorted.
+pub struct StarDict<W> {
+    dict: W,
+    offset: u64,
+    words: Vec<Word>,
+    synonyms: Vec<(String, u32)>,
+}
+
+impl<W> StarDict<W>
+where
+    W: Write,
+{
+    /// Construct a new writer, writing definitions to the given output.
+    pub fn new(dict: W) -> Self {
+        Self {
+            dict,
+            offset: 0,
+            words: Vec::new(),
+            synonyms: Vec::new(),
+        }
+    }
+
+    /// Add an article to the dictionary.
+    pub fn add(&mut self, article: &Article) -> Result<()> {
+        let html = article.html();
+
+        let (Ok(offset), Ok(size)) = (u32::try_from(self.offset), u32::try_from(html.len())) else {
+            bail!("Dictionary is too large for the StarDict format");
+        };
+
+        self.dict.write_all(html.as_bytes())?;
+        self.offset += html.len() as u64;
+
+        let index = self.words.len() as u32;
+
+        self.words.push(Word {
+            word: article.headword.clone(),
+            offset,
+            size,
+        });
+
+        for word in article.words() {
+            self.synonyms.push((word.to_owned(), index));
+        }
+
+        Ok(())
+    }
+
+    /// Finish writing the dictionary, writing the `.idx` and `.syn` files to
+    /// the given outputs.
+    ///
+    /// Returns the contents of the `.ifo` file.
+    pub fn finish<I, S>(mut self, info: &Info<'_>, mut idx: I, mut syn: S) -> Result<String>
+    where
+        I: Write,
+        S: Write,
+    {
+        self.dict.flush()?;
+
+        let mut order = (0..self.words.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| compare(&self.words[a].word, &self.words[b].word));
+
+        // Synonyms refer to the position of a word in the sorted index.
+        let mut positions = vec![0u32; order.len()];
+        let mut idx_size = 0;
+
+        for (position, &n) in order.iter().enumerate() {
+            positions[n] = position as u32;
+
+            let word = &self.words[n];
+            idx.write_all(word.word.as_bytes())?;
+            idx.write_all(&[0])?;
+            idx.write_all(&word.offset.to_be_bytes())?;
+            idx.write_all(&word.size.to_be_bytes())?;
+            idx_size += word.word.len() + 9;
+        }
+
+        idx.flush()?;
+
+        self.synonyms.sort_by(|a, b| compare(&a.0, &b.0));
+
+        for (word, index) in &self.synonyms {
+            syn.write_all(word.as_bytes())?;
+            syn.write_all(&[0])?;
+            syn.write_all(&positions[*index as usize].to_be_bytes())?;
+        }
+
+        syn.flush()?;
+
+        let mut ifo = String::new();
+        ifo.push_str("StarDict's dict ifo file\n");
+        ifo.push_str(&format!("version={VERSION}\n"));
+        ifo.push_str(&format!("bookname={}\n", single_line(info.bookname)));
+        ifo.push_str(&format!("wordcount={}\n", self.words.len()));
+        ifo.push_str(&format!("synwordcount={}\n", self.synonyms.len()));
+        ifo.push_str(&format!("idxfilesize={idx_size}\n"));
+
+        if let Some(description) = info.description {
+            ifo.push_str(&format!("description={}\n", single_line(description)));
+        }
+
+        ifo.push_str("sametypesequence=h\n");
+        Ok(ifo)
+    }
+}
+
+/// Compare words in the order StarDict expects them to be sorted, which is
+/// ASCII case-insensitive with ties broken by a byte-wise comparison.
+pub(crate) fn compare(a: &str, b: &str) -> Ordering {
+    let a_lower = a.bytes().map(|c| c.to_ascii_lowercase());
+    let b_lower = b.bytes().map(|c| c.to_ascii_lowercase());
+    a_lower.cmp(b_lower).then_with(|| a.cmp(b))
+}
+
+/// Values in the `.ifo` file can't span multiple lines.
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
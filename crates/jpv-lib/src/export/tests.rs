@@ -0,0 +1,188 @@
+use std::cmp::Ordering;
+use std::slice;
+
+use super::kindle;
+use super::pdf::{self, Layout};
+use super::stardict::{compare, Info, StarDict};
+use super::{Article, Escape};
+use crate::jmdict;
+use crate::Form;
+
+const TABERU: &str = r#"
+<JMdict>
+<entry>
+<ent_seq>1358280</ent_seq>
+<k_ele><keb>食べる</keb></k_ele>
+<k_ele><keb>喰べる</keb></k_ele>
+<r_ele><reb>たべる</reb></r_ele>
+<sense>
+<pos>&v1;</pos>
+<pos>&vt;</pos>
+<gloss>to eat</gloss>
+<gloss>to consume</gloss>
+</sense>
+<sense>
+<gloss>to live on (e.g. a salary)</gloss>
+</sense>
+</entry>
+</JMdict>
+"#;
+
+fn taberu(lang: Option<&str>) -> Article {
+    let mut parser = jmdict::Parser::new(TABERU);
+    let entry = parser.parse().unwrap().unwrap();
+    Article::new(&entry, lang).unwrap()
+}
+
+#[test]
+fn article() {
+    let a = taberu(Some("eng"));
+
+    assert_eq!(a.sequence, 1358280);
+    assert_eq!(a.headword, "食べる");
+    assert_eq!(a.reading.as_deref(), Some("たべる"));
+    assert_eq!(a.variants, ["喰べる"]);
+    assert_eq!(a.senses.len(), 2);
+    assert_eq!(a.senses[0].glosses, ["to eat", "to consume"]);
+
+    let past = a
+        .inflections
+        .iter()
+        .find(|i| i.forms == [Form::Past])
+        .unwrap();
+
+    assert_eq!(past.text, "食べた");
+    assert_eq!(past.reading, "たべた");
+
+    let words = a.words();
+    assert!(words.contains(&"たべる"));
+    assert!(words.contains(&"喰べる"));
+    assert!(words.contains(&"食べた"));
+    assert!(words.contains(&"たべた"));
+    assert!(!words.contains(&"食べる"));
+
+    let html = a.html();
+    assert!(html.contains("<b>食べる</b> 【たべる】"));
+    assert!(html.contains("食べた 【たべた】 &ndash; past"));
+
+    assert_eq!(taberu(None), a);
+}
+
+#[test]
+fn missing_language() {
+    let mut parser = jmdict::Parser::new(TABERU);
+    let entry = parser.parse().unwrap().unwrap();
+    assert_eq!(Article::new(&entry, Some("fre")), None);
+}
+
+#[test]
+fn escape() {
+    assert_eq!(
+        Escape(r#"<a href="x">&</a>"#).to_string(),
+        "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+    );
+}
+
+#[test]
+fn sort_order() {
+    assert_eq!(compare("abc", "ABD"), Ordering::Less);
+    assert_eq!(compare("ABC", "abc"), Ordering::Less);
+    assert_eq!(compare("b", "A"), Ordering::Greater);
+    assert_eq!(compare("あ", "a"), Ordering::Greater);
+}
+
+#[test]
+fn stardict() {
+    let a = taberu(Some("eng"));
+
+    let mut dict = Vec::new();
+    let mut idx = Vec::new();
+    let mut syn = Vec::new();
+
+    let mut writer = StarDict::new(&mut dict);
+    writer.add(&a).unwrap();
+
+    let info = Info {
+        bookname: "jpv\ntest",
+        description: None,
+    };
+
+    let ifo = writer.finish(&info, &mut idx, &mut syn).unwrap();
+
+    assert_eq!(dict, a.html().as_bytes());
+
+    let mut expected = "食べる\0".as_bytes().to_vec();
+    expected.extend(0u32.to_be_bytes());
+    expected.extend((dict.len() as u32).to_be_bytes());
+    assert_eq!(idx, expected);
+
+    let words = a.words().len();
+    assert!(ifo.starts_with("StarDict's dict ifo file\nversion=2.4.2\nbookname=jpv test\n"));
+    assert!(ifo.contains("wordcount=1\n"));
+    assert!(ifo.contains(&format!("synwordcount={words}\n")));
+    assert!(ifo.contains(&format!("idxfilesize={}\n", idx.len())));
+    assert!(ifo.ends_with("sametypesequence=h\n"));
+
+    let size = a.words().iter().map(|w| w.len() + 5).sum::<usize>();
+    assert_eq!(syn.len(), size);
+
+    let first = syn.iter().position(|&b| b == 0).unwrap();
+    assert_eq!(&syn[first + 1..first + 5], 0u32.to_be_bytes());
+}
+
+#[test]
+fn kindle() {
+    let a = taberu(Some("eng"));
+
+    let entry = kindle::entry(&a);
+    assert!(entry.starts_with("<idx:entry name=\"japanese\" scriptable=\"yes\" spell=\"yes\"><idx:orth value=\"食べる\"><idx:infl>"));
+    assert!(entry.contains("<idx:iform value=\"食べた\"/>"));
+    assert!(entry.contains("<idx:iform value=\"たべる\"/>"));
+    assert!(!entry.contains("<idx:iform value=\"食べる\"/>"));
+    assert!(entry.ends_with("</idx:entry><hr/>"));
+
+    let info = kindle::Info {
+        identifier: "jpv",
+        title: "Dictionary & co",
+        out_language: kindle::language_code("eng").unwrap(),
+    };
+
+    let opf = kindle::opf(&info, ["jpv-0.html", "jpv-1.html"]);
+    assert!(opf.contains("<dc:Title>Dictionary &amp; co</dc:Title>"));
+    assert!(opf.contains("<DictionaryOutLanguage>en</DictionaryOutLanguage>"));
+    assert!(opf.contains(
+        "<item id=\"content1\" href=\"jpv-1.html\" media-type=\"application/xhtml+xml\"/>"
+    ));
+    assert!(opf.contains(
+        "<spine>\n<itemref idref=\"content0\"/>\n<itemref idref=\"content1\"/>\n</spine>"
+    ));
+}
+
+#[test]
+fn pdf() {
+    let a = taberu(Some("eng"));
+
+    let data = pdf::vocabulary("Words", slice::from_ref(&a), &Layout::default());
+    assert!(data.starts_with(b"%PDF-"));
+    assert!(contains(&data, b"/Count 1"));
+    assert!(contains(&data, b"/UniJIS-UCS2-HW-H"));
+    // The headword encoded as UCS-2.
+    assert!(contains(&data, b"<98DF3079308B"));
+
+    let layout = Layout {
+        conjugations: true,
+        ..Layout::default()
+    };
+
+    let articles = vec![a; 10];
+    let data = pdf::vocabulary("Words", &articles, &layout);
+    assert!(!contains(&data, b"/Count 1\n"));
+
+    // Only the conjugations of the headword are listed, not of its variants.
+    assert!(contains(&data, b"<98DF30793066"));
+    assert!(!contains(&data, b"<55B030793066"));
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
@@ -27,6 +27,30 @@ impl<'a> Furigana<'a, 1, 1> {
             suffix: Concat::new(suffix),
         }
     }
+
+    /// Construct furigana for text which is a phrase or an inflection of it,
+    /// like `食べた` for the phrase `食べる` read as `たべる`.
+    ///
+    /// The text has to share its kanji with the phrase, and whatever differs
+    /// after them has to be kana. Returns `None` if that's not the case.
+    pub fn inflected(text: &'a str, kanji: &str, reading: &'a str) -> Option<Self> {
+        let stem = text
+            .char_indices()
+            .zip(kanji.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map(|((n, c), _)| n + c.len_utf8())?;
+
+        let (text_stem, text_tail) = text.split_at(stem);
+        let kanji_tail = kanji.get(stem..)?;
+
+        if !kanji_tail.chars().all(is_kana) || !text_tail.chars().all(is_kana) {
+            return None;
+        }
+
+        let reading = reading.strip_suffix(kanji_tail)?;
+        Some(Self::new(text_stem, reading, text_tail))
+    }
 }
 
 impl<'a, const N: usize, const S: usize> Furigana<'a, N, S> {
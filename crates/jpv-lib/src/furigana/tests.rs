@@ -121,3 +121,26 @@ fn test_long_suffix() {
         ]
     );
 }
+
+#[test]
+fn test_inflected() {
+    let furigana = Furigana::inflected("食べた", "食べる", "たべる").unwrap();
+
+    assert_eq!(
+        furigana.iter().collect::<Vec<_>>(),
+        [
+            FuriganaGroup::Kanji("食", "た"),
+            FuriganaGroup::Kana("べ"),
+            FuriganaGroup::Kana("た"),
+        ]
+    );
+
+    let furigana = Furigana::inflected("見なかった", "見る", "みる").unwrap();
+    assert_eq!(furigana.to_string(), "見[み]なかった");
+
+    let furigana = Furigana::inflected("日本", "日本", "にほん").unwrap();
+    assert_eq!(furigana.to_string(), "日本[にほん]");
+
+    assert!(Furigana::inflected("食べ物", "食べる", "たべる").is_none());
+    assert!(Furigana::inflected("見る", "観る", "みる").is_none());
+}
@@ -0,0 +1,261 @@
+//! Graphs of phrases connected through cross references, antonyms and shared
+//! kanji or glossary words.
+//!
+//! Graphs are built breadth first starting from a single phrase, and are
+//! limited both in depth and in the number of phrases they contain so that
+//! densely connected phrases don't produce graphs which are too large to
+//! show.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{Database, Entry};
+use crate::jmdict;
+
+/// The maximum depth a graph can be built to.
+pub const MAX_DEPTH: usize = 3;
+/// The maximum number of phrases in a graph.
+pub const MAX_NODES: usize = 60;
+/// The maximum number of phrases sharing kanji or glossary words which are
+/// followed from each phrase.
+const MAX_RELATED: usize = 5;
+
+/// How two phrases are related.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Relation {
+    /// A sense refers to the other phrase, usually as a synonym or a related
+    /// word.
+    Reference,
+    /// A sense has the other phrase as its antonym.
+    Antonym,
+    /// The phrases share kanji or glossary words.
+    Related,
+}
+
+impl Relation {
+    /// A human readable title of the relation.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Relation::Reference => "See also",
+            Relation::Antonym => "Antonym",
+            Relation::Related => "Shares kanji or meaning",
+        }
+    }
+}
+
+/// A phrase in a graph.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Node {
+    pub sequence: u32,
+    /// How the phrase is usually written.
+    pub text: String,
+    /// The reading of the phrase, if it's different from its text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading: Option<String>,
+    /// The first glossary entry of the phrase.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub gloss: String,
+    /// Distance from the phrase the graph was built from.
+    pub depth: usize,
+}
+
+/// A relation from one phrase to another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Edge {
+    pub from: u32,
+    pub to: u32,
+    pub relation: Relation,
+}
+
+/// A graph of related phrases.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Graph {
+    /// Phrases in the order they were discovered, starting with the phrase the
+    /// graph was built from.
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+/// A parsed cross reference or antonym, like `聞く・きく・2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference<'a> {
+    /// The referenced kanji or reading.
+    pub text: &'a str,
+    /// The reading of the referenced phrase, if specified.
+    pub reading: Option<&'a str>,
+    /// The 1-based index of the referenced sense, if specified.
+    pub sense: Option<usize>,
+}
+
+impl<'a> Reference<'a> {
+    /// Parse a reference.
+    pub fn parse(input: &'a str) -> Self {
+        let mut it = input.split('・').map(str::trim);
+
+        let text = it.next().unwrap_or_default();
+        let mut reading = None;
+        let mut sense = None;
+
+        for part in it {
+            match part.parse::<usize>() {
+                Ok(n) => sense = Some(n),
+                Err(..) if !part.is_empty() => reading = Some(part),
+                Err(..) => {}
+            }
+        }
+
+        Self {
+            text,
+            reading,
+            sense,
+        }
+    }
+
+    /// Test if the reference refers to the given phrase.
+    pub fn matches(&self, entry: &jmdict::Entry<'_>) -> bool {
+        let has_text = entry.kanji_elements.iter().any(|k| k.text == self.text)
+            || entry.reading_elements.iter().any(|r| r.text == self.text);
+
+        let has_reading = match self.reading {
+            Some(reading) => entry.reading_elements.iter().any(|r| r.text == reading),
+            None => true,
+        };
+
+        has_text && has_reading
+    }
+}
+
+/// Build a graph of phrases related to the phrase with the given sequence.
+pub fn build(db: &Database, sequence: u32, depth: usize) -> Result<Graph> {
+    let depth = depth.min(MAX_DEPTH);
+    let mut graph = Graph::default();
+
+    let Some(entry) = db.sequence_to_entry(sequence)? else {
+        return Ok(graph);
+    };
+
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    seen.insert(sequence);
+    graph.nodes.push(node(&entry, 0));
+    queue.push_back((entry, 0));
+
+    while let Some((entry, d)) = queue.pop_front() {
+        if d >= depth {
+            continue;
+        }
+
+        let from = entry.sequence as u32;
+
+        for (relation, other) in neighbours(db, &entry)? {
+            let to = other.sequence as u32;
+
+            if to == from {
+                continue;
+            }
+
+            if !seen.contains(&to) {
+                if graph.nodes.len() >= MAX_NODES {
+                    continue;
+                }
+
+                seen.insert(to);
+                graph.nodes.push(node(&other, d + 1));
+                queue.push_back((other, d + 1));
+            }
+
+            let edge = Edge { from, to, relation };
+
+            // Relations are often specified in both directions, only keep one
+            // of them.
+            let exists = graph.edges.iter().any(|e| {
+                e.relation == relation
+                    && ((e.from == from && e.to == to) || (e.from == to && e.to == from))
+            });
+
+            if !exists {
+                graph.edges.push(edge);
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Phrases directly related to the given phrase.
+fn neighbours<'db>(
+    db: &'db Database,
+    entry: &jmdict::Entry<'_>,
+) -> Result<Vec<(Relation, jmdict::Entry<'db>)>> {
+    let mut output = Vec::new();
+
+    for sense in &entry.senses {
+        let references = sense
+            .xref
+            .iter()
+            .map(|xref| (Relation::Reference, xref))
+            .chain(sense.antonym.iter().map(|ant| (Relation::Antonym, ant)));
+
+        for (relation, text) in references {
+            let reference = Reference::parse(text);
+
+            if let Some(entry) = resolve(db, &reference)? {
+                output.push((relation, entry));
+            }
+        }
+    }
+
+    for entry in db.related(entry)?.into_iter().take(MAX_RELATED) {
+        output.push((Relation::Related, entry));
+    }
+
+    Ok(output)
+}
+
+/// Find the phrase a reference refers to.
+fn resolve<'db>(
+    db: &'db Database,
+    reference: &Reference<'_>,
+) -> Result<Option<jmdict::Entry<'db>>> {
+    for id in db.lookup(reference.text)? {
+        if let Entry::Phrase(entry) = db.entry_at(id)? {
+            if reference.matches(&entry) {
+                return Ok(Some(entry));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn node(entry: &jmdict::Entry<'_>, depth: usize) -> Node {
+    let reading = entry.reading_elements.first().map(|r| r.text);
+
+    let (text, reading) = match entry.kanji_elements.first() {
+        Some(kanji) => (kanji.text, reading),
+        None => (reading.unwrap_or_default(), None),
+    };
+
+    let gloss = entry
+        .senses
+        .iter()
+        .flat_map(|s| s.gloss.first())
+        .map(|g| g.text.to_owned())
+        .next()
+        .unwrap_or_default();
+
+    Node {
+        sequence: entry.sequence as u32,
+        text: text.to_owned(),
+        reading: reading.map(str::to_owned),
+        gloss,
+        depth,
+    }
+}
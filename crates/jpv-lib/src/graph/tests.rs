@@ -0,0 +1,40 @@
+use super::Reference;
+
+#[test]
+fn parse_reference() {
+    assert_eq!(
+        Reference::parse("聞く"),
+        Reference {
+            text: "聞く",
+            reading: None,
+            sense: None,
+        }
+    );
+
+    assert_eq!(
+        Reference::parse("聞く・きく"),
+        Reference {
+            text: "聞く",
+            reading: Some("きく"),
+            sense: None,
+        }
+    );
+
+    assert_eq!(
+        Reference::parse("聞く・きく・2"),
+        Reference {
+            text: "聞く",
+            reading: Some("きく"),
+            sense: Some(2),
+        }
+    );
+
+    assert_eq!(
+        Reference::parse("きく・1"),
+        Reference {
+            text: "きく",
+            reading: None,
+            sense: Some(1),
+        }
+    );
+}
@@ -0,0 +1,159 @@
+//! Parser for ideographic description sequences (IDS), as distributed by the
+//! CJKVI project in the `ids.txt` format.
+//!
+//! Each line describes a single character using a sequence like `⿰氵⿱廿⿻口夫`,
+//! where ideographic description characters such as `⿰` describe how the
+//! components which follow them are arranged.
+
+#[cfg(test)]
+mod tests;
+
+use std::str::Lines;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// A character and its description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry<'a> {
+    /// The character being described.
+    pub character: char,
+    /// The preferred description of the character.
+    pub sequence: &'a str,
+}
+
+/// A parsed ideographic description sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sequence<'a> {
+    /// A single component, which is either a character or an unencoded
+    /// component like `{01}` or `&CDP-8C5B;`.
+    Component(&'a str),
+    /// Components arranged by an ideographic description character.
+    Compound(char, Vec<Sequence<'a>>),
+}
+
+/// A node in the component tree of a character.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Component {
+    /// The component, empty for a group of components which is only
+    /// described by its parts.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+    /// How the parts are arranged, like `⿰` for left to right.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operator: Option<char>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<Component>,
+}
+
+pub struct Parser<'a> {
+    lines: Lines<'a>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Parse input.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            lines: input.lines(),
+            line: 0,
+        }
+    }
+
+    /// Get the next character description.
+    ///
+    /// Characters which can't be broken down any further are skipped.
+    pub(crate) fn next(&mut self) -> Result<Option<Entry<'a>>> {
+        loop {
+            let Some(line) = self.lines.next() else {
+                return Ok(None);
+            };
+
+            self.line += 1;
+
+            if line.trim().is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+
+            let mut fields = line.split('\t');
+
+            let (Some(_), Some(character), Some(sequence)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                bail!("{}: Expected code point, character and sequence", self.line);
+            };
+
+            let mut chars = character.chars();
+
+            let (Some(character), None) = (chars.next(), chars.next()) else {
+                continue;
+            };
+
+            let sequence = strip_tags(sequence);
+
+            if sequence.is_empty() || sequence.chars().eq([character]) {
+                continue;
+            }
+
+            return Ok(Some(Entry {
+                character,
+                sequence,
+            }));
+        }
+    }
+}
+
+/// Parse an ideographic description sequence.
+pub fn parse(input: &str) -> Option<Sequence<'_>> {
+    let mut rest = input;
+    let sequence = parse_sequence(&mut rest)?;
+    rest.is_empty().then_some(sequence)
+}
+
+fn parse_sequence<'a>(input: &mut &'a str) -> Option<Sequence<'a>> {
+    let c = input.chars().next()?;
+
+    if let Some(arity) = arity(c) {
+        *input = &input[c.len_utf8()..];
+
+        let mut parts = Vec::with_capacity(arity);
+
+        for _ in 0..arity {
+            parts.push(parse_sequence(input)?);
+        }
+
+        return Some(Sequence::Compound(c, parts));
+    }
+
+    let end = match c {
+        '{' => input.find('}')? + 1,
+        '&' => input.find(';')? + 1,
+        c => c.len_utf8(),
+    };
+
+    let (component, rest) = input.split_at(end);
+    *input = rest;
+    Some(Sequence::Component(component))
+}
+
+/// The number of components an ideographic description character arranges.
+fn arity(c: char) -> Option<usize> {
+    match c {
+        '⿰' | '⿱' | '⿴' | '⿵' | '⿶' | '⿷' | '⿸' | '⿹' | '⿺' | '⿻' | '⿼' | '⿽'
+        | '㇯' => Some(2),
+        '⿲' | '⿳' => Some(3),
+        '⿾' | '⿿' => Some(1),
+        _ => None,
+    }
+}
+
+/// Strip source tags like `[GTJ]` and markers like `^` and `$(X)` from a
+/// sequence.
+fn strip_tags(sequence: &str) -> &str {
+    let sequence = sequence.strip_prefix('^').unwrap_or(sequence);
+
+    match sequence.find(['[', '$']) {
+        Some(n) => &sequence[..n],
+        None => sequence,
+    }
+}
@@ -0,0 +1,80 @@
+use super::{parse, Entry, Parser, Sequence};
+
+#[test]
+fn sequences() {
+    assert_eq!(parse("木"), Some(Sequence::Component("木")));
+
+    assert_eq!(
+        parse("⿰木木"),
+        Some(Sequence::Compound(
+            '⿰',
+            vec![Sequence::Component("木"), Sequence::Component("木")]
+        ))
+    );
+
+    assert_eq!(
+        parse("⿰氵⿱廿⿻口夫"),
+        Some(Sequence::Compound(
+            '⿰',
+            vec![
+                Sequence::Component("氵"),
+                Sequence::Compound(
+                    '⿱',
+                    vec![
+                        Sequence::Component("廿"),
+                        Sequence::Compound(
+                            '⿻',
+                            vec![Sequence::Component("口"), Sequence::Component("夫")]
+                        ),
+                    ]
+                ),
+            ]
+        ))
+    );
+
+    assert_eq!(
+        parse("⿱{01}&CDP-8C5B;"),
+        Some(Sequence::Compound(
+            '⿱',
+            vec![
+                Sequence::Component("{01}"),
+                Sequence::Component("&CDP-8C5B;")
+            ]
+        ))
+    );
+
+    assert_eq!(parse("⿰木"), None);
+    assert_eq!(parse("⿰木木木"), None);
+    assert_eq!(parse(""), None);
+}
+
+#[test]
+fn lines() {
+    let input = "\
+;; comment
+U+6728\t木\t木
+U+6797\t林\t⿰木木[GTJKV]\t⿰木木[X]
+U+2FF0\t&CDP-8C5B;\t⿱一一
+U+6F22\t漢\t^⿰氵⿱廿⿻口夫$(GJ)
+";
+
+    let mut parser = Parser::new(input);
+
+    assert_eq!(
+        parser.next().unwrap(),
+        Some(Entry {
+            character: '林',
+            sequence: "⿰木木",
+        })
+    );
+
+    assert_eq!(
+        parser.next().unwrap(),
+        Some(Entry {
+            character: '漢',
+            sequence: "⿰氵⿱廿⿻口夫",
+        })
+    );
+
+    assert_eq!(parser.next().unwrap(), None);
+}
@@ -67,6 +67,7 @@ pub struct Reading {
 pub enum Kind {
     Verb,
     Adjective,
+    Copula,
 }
 
 /// Try to conjugate the given entry as a verb.
@@ -107,16 +108,22 @@ pub fn conjugate<'a>(entry: &Entry<'a>) -> Vec<(Reading, Inflections<'a>, Kind)>
                     };
 
                     macros::ichidan_te(|suffix, inflect| {
+                        // Kureru special class verbs have an irregular
+                        // command form, like くれ instead of くれろ.
+                        let suffix = if pos == PartOfSpeech::VerbIchidanS && inflect == [Command] {
+                            ""
+                        } else {
+                            suffix
+                        };
+
                         inflections.insert(inflect, &[], Fragments::new([k], [r], [suffix]));
                     });
 
                     kind = Kind::Verb;
-                    chau_stem = Some((Fragments::new([k], [r], ["っ"]), false));
+                    chau_stem = Some((Fragments::new([k], [r], []), false));
                 }
                 PartOfSpeech::VerbGodanKS => {
-                    let Some((mode, kanji_stem, reading_stem)) =
-                        extract_iku(kanji_text, reading_text)
-                    else {
+                    let Some((k, r)) = match_char(kanji_text, reading_text, 'く') else {
                         allowlist!();
                         continue;
                     };
@@ -125,25 +132,14 @@ pub fn conjugate<'a>(entry: &Entry<'a>) -> Vec<(Reading, Inflections<'a>, Kind)>
                         inflections.insert(
                             inflect,
                             &[],
-                            Fragments::new(
-                                [kanji_stem, mode.apply(prefix)],
-                                [reading_stem, prefix],
-                                [suffix],
-                            ),
+                            Fragments::new([k], [r], [prefix, suffix]),
                         );
                     });
 
                     kind = Kind::Verb;
-                    chau_stem = Some((
-                        Fragments::new(
-                            [kanji_stem, mode.apply("き")],
-                            [reading_stem, "き"],
-                            ["っ"],
-                        ),
-                        false,
-                    ));
+                    chau_stem = Some((Fragments::new([k], [r], ["っ"]), false));
                 }
-                PartOfSpeech::VerbGodanU | PartOfSpeech::VerbGodanUS => {
+                PartOfSpeech::VerbGodanU => {
                     let Some((k, r)) = match_char(kanji_text, reading_text, 'う') else {
                         allowlist!();
                         continue;
@@ -160,6 +156,23 @@ pub fn conjugate<'a>(entry: &Entry<'a>) -> Vec<(Reading, Inflections<'a>, Kind)>
                     kind = Kind::Verb;
                     chau_stem = Some((Fragments::new([k], [r], ["っ"]), false));
                 }
+                PartOfSpeech::VerbGodanUS => {
+                    let Some((k, r)) = match_char(kanji_text, reading_text, 'う') else {
+                        allowlist!();
+                        continue;
+                    };
+
+                    macros::godan_u_s_base(|prefix, suffix, inflect| {
+                        inflections.insert(
+                            inflect,
+                            &[],
+                            Fragments::new([k], [r], [prefix, suffix]),
+                        );
+                    });
+
+                    kind = Kind::Verb;
+                    chau_stem = Some((Fragments::new([k], [r], ["う"]), false));
+                }
                 PartOfSpeech::VerbGodanT => {
                     let Some((k, r)) = match_char(kanji_text, reading_text, 'つ') else {
                         allowlist!();
@@ -177,8 +190,28 @@ pub fn conjugate<'a>(entry: &Entry<'a>) -> Vec<(Reading, Inflections<'a>, Kind)>
                     kind = Kind::Verb;
                     chau_stem = Some((Fragments::new([k], [r], ["っ"]), false));
                 }
+                PartOfSpeech::VerbGodanRI => {
+                    let Some((k, r)) = match_char(kanji_text, reading_text, 'る') else {
+                        allowlist!();
+                        continue;
+                    };
+
+                    macros::godan_ru_base(|prefix, suffix, inflect| {
+                        // The negative forms of ある are formed from ない
+                        // directly, like ない instead of あらない.
+                        let fragments = if prefix == "ら" && suffix.starts_with('な') {
+                            Fragments::new([""], [""], [suffix])
+                        } else {
+                            Fragments::new([k], [r], [prefix, suffix])
+                        };
+
+                        inflections.insert(inflect, &[], fragments);
+                    });
+
+                    kind = Kind::Verb;
+                    chau_stem = Some((Fragments::new([k], [r], ["っ"]), false));
+                }
                 PartOfSpeech::VerbGodanR
-                | PartOfSpeech::VerbGodanRI
                 | PartOfSpeech::VerbGodanAru
                 | PartOfSpeech::VerbGodanUru => {
                     let Some((k, r)) = match_char(kanji_text, reading_text, 'る') else {
@@ -265,7 +298,7 @@ pub fn conjugate<'a>(entry: &Entry<'a>) -> Vec<(Reading, Inflections<'a>, Kind)>
                     kind = Kind::Verb;
                     chau_stem = Some((Fragments::new([k], [r], ["ん"]), true));
                 }
-                PartOfSpeech::VerbGodanN => {
+                PartOfSpeech::VerbGodanN | PartOfSpeech::VerbNu => {
                     let Some((k, r)) = match_char(kanji_text, reading_text, 'ぬ') else {
                         allowlist!();
                         continue;
@@ -322,6 +355,42 @@ pub fn conjugate<'a>(entry: &Entry<'a>) -> Vec<(Reading, Inflections<'a>, Kind)>
                     kind = Kind::Verb;
                     chau_stem = Some((Fragments::new([kanji_stem], [reading_stem], ["し"]), false));
                 }
+                PartOfSpeech::VerbSuru => {
+                    // Nouns which take the auxiliary verb する, like 勉強, are
+                    // conjugated as if する was appended to them.
+                    inflections.dictionary = Full::new(kanji_text, reading_text, "する");
+
+                    macros::suru_base(|prefix, suffix, inflect| {
+                        inflections.insert(
+                            inflect,
+                            &[],
+                            Fragments::new([kanji_text, prefix], [reading_text, prefix], [suffix]),
+                        );
+                    });
+
+                    kind = Kind::Verb;
+                    chau_stem = Some((
+                        Fragments::new([kanji_text, "し"], [reading_text, "し"], []),
+                        false,
+                    ));
+                }
+                PartOfSpeech::VerbZuru => {
+                    let Some((k, r)) = match_str(kanji_text, reading_text, "ずる") else {
+                        allowlist!();
+                        continue;
+                    };
+
+                    macros::zuru_base(|prefix, suffix, inflect| {
+                        inflections.insert(
+                            inflect,
+                            &[],
+                            Fragments::new([k, prefix], [r, prefix], [suffix]),
+                        );
+                    });
+
+                    kind = Kind::Verb;
+                    chau_stem = Some((Fragments::new([k, "じ"], [r, "じ"], []), false));
+                }
                 PartOfSpeech::VerbKuru => {
                     let Some((mode, kanji_stem, reading_prefix)) =
                         extract_kuru(kanji_text, reading_text)
@@ -393,6 +462,21 @@ pub fn conjugate<'a>(entry: &Entry<'a>) -> Vec<(Reading, Inflections<'a>, Kind)>
                     kind = Kind::Adjective;
                     chau_stem = None;
                 }
+                PartOfSpeech::Copular => {
+                    // Only the plain copula だ is conjugated, since the polite
+                    // です is already one of its inflections.
+                    let Some((k, r)) = match_char(kanji_text, reading_text, 'だ') else {
+                        allowlist!();
+                        continue;
+                    };
+
+                    macros::copula(|suffix, inflect| {
+                        inflections.insert(inflect, &[], Fragments::new([k], [r], [suffix]));
+                    });
+
+                    kind = Kind::Copula;
+                    chau_stem = None;
+                }
                 _ => {
                     continue;
                 }
@@ -410,7 +494,14 @@ pub fn conjugate<'a>(entry: &Entry<'a>) -> Vec<(Reading, Inflections<'a>, Kind)>
                 });
             }
 
-            if let Some(te) = inflections.get(inflect!(Te)).cloned() {
+            // The te-form of the copula, like 静かで, doesn't combine with
+            // auxiliary verbs the way the te-form of a verb does.
+            let te = inflections
+                .get(inflect!(Te))
+                .filter(|_| matches!(kind, Kind::Verb))
+                .cloned();
+
+            if let Some(te) = te {
                 inflections.insert(&[TeIru, Te, Short], &[], te.concat(["る"]));
                 inflections.insert(&[TeIru, Te, Short, Honorific], &[], te.concat(["ます"]));
                 inflections.insert(&[TeIru, Te, Past, Short], &[], te.concat(["た"]));
@@ -529,18 +620,6 @@ fn extract_ii<'a>(kanji: &'a str, reading: &'a str) -> Option<(SuffixMode, &'a s
     }
 }
 
-fn extract_iku<'a>(kanji: &'a str, reading: &'a str) -> Option<(SuffixMode, &'a str, &'a str)> {
-    let ('い' | 'ゆ', 'く', r) = s(reading)? else {
-        return None;
-    };
-
-    match s2(kanji)? {
-        ('行' | '往' | '逝', 'く', k, _) => Some((SuffixMode::Excluded, k, r)),
-        ('い' | 'ゆ', 'く', _, k) => Some((SuffixMode::Included, k, r)),
-        _ => None,
-    }
-}
-
 fn extract_suru<'a>(kanji: &'a str, reading: &'a str) -> Option<(SuffixMode, &'a str, &'a str)> {
     let ('す', 'る', r) = s(reading)? else {
         return None;
@@ -576,6 +655,17 @@ fn match_char<'a>(
     ))
 }
 
+fn match_str<'a>(
+    kanji_text: &'a str,
+    reading_text: &'a str,
+    suffix: &str,
+) -> Option<(&'a str, &'a str)> {
+    Some((
+        kanji_text.strip_suffix(suffix)?,
+        reading_text.strip_suffix(suffix)?,
+    ))
+}
+
 pub(crate) fn reading_permutations<'a>(
     entry: &Entry<'a>,
 ) -> Vec<(
@@ -31,6 +31,22 @@ pub(super) static U: &Godan = &Godan {
     de: false,
 };
 
+/// The U godan table for special class verbs like 問う, which keep their う in
+/// the te and past forms.
+pub(super) static U_S: &Godan = &Godan {
+    a: "わ",
+    i: "い",
+    u: "う",
+    e: "え",
+    o: "お",
+    te: "うて",
+    te_stem: "う",
+    past: "うた",
+    past_conditional: "うたら",
+    hypothetical_conversational: Some(("やぁ", "なけやぁ")),
+    de: false,
+};
+
 /// The TSU godan table.
 pub(super) static TSU: &Godan = &Godan {
     a: "た",
@@ -5,6 +5,26 @@ use crate::inflection::Form;
 
 use Form::*;
 
+/// Conjugate a derived form which itself inflects like an ichidan verb, like
+/// the passive 書かれる, by appending the ichidan suffixes to its stem.
+macro_rules! ichidan_derived {
+    ($r:ident($($prefix:expr,)?) $stem:literal, [$($form:ident),*]) => {
+        $r($($prefix,)? concat!($stem, "る"), &[$($form),*]);
+        $r($($prefix,)? concat!($stem, "ます"), &[$($form,)* Honorific]);
+        $r($($prefix,)? concat!($stem, "ない"), &[$($form,)* Negative]);
+        $r($($prefix,)? concat!($stem, "ません"), &[$($form,)* Negative, Honorific]);
+        $r($($prefix,)? concat!($stem, "た"), &[$($form,)* Past]);
+        $r($($prefix,)? concat!($stem, "ました"), &[$($form,)* Past, Honorific]);
+        $r($($prefix,)? concat!($stem, "なかった"), &[$($form,)* Past, Negative]);
+        $r($($prefix,)? concat!($stem, "ませんでした"), &[$($form,)* Past, Negative, Honorific]);
+        $r($($prefix,)? concat!($stem, "て"), &[$($form,)* Te]);
+        $r($($prefix,)? concat!($stem, "れば"), &[$($form,)* Hypothetical]);
+        $r($($prefix,)? concat!($stem, "なければ"), &[$($form,)* Hypothetical, Negative]);
+        $r($($prefix,)? concat!($stem, "たら"), &[$($form,)* Conditional]);
+        $r($($prefix,)? concat!($stem, "なかったら"), &[$($form,)* Conditional, Negative]);
+    };
+}
+
 /// Perform ichidan conjugations.
 pub fn ichidan(mut r: impl FnMut(&'static str, &[Form])) {
     r("る", &[]);
@@ -23,37 +43,29 @@ pub fn ichidan(mut r: impl FnMut(&'static str, &[Form])) {
     r("ないでください", &[Command, Negative, Honorific]);
     r("りゃ", &[Hypothetical, Conversation]);
     r("なけりゃ", &[Hypothetical, Conversation, Negative]);
-    r("ば", &[Hypothetical]);
+    r("れば", &[Hypothetical]);
     r("なければ", &[Hypothetical, Negative]);
     r("なきゃ", &[Hypothetical, Negative, Kya]);
     r("たら", &[Conditional]);
     r("ましたら", &[Conditional, Honorific]);
     r("なかったら", &[Conditional, Negative]);
     r("ませんでしたら", &[Conditional, Negative, Honorific]);
+    r("ると", &[To]);
+    r("ないと", &[To, Negative]);
     r("れる", &[Passive, Conversation]);
-    r("られる", &[Passive]);
-    r("られます", &[Passive, Honorific]);
-    r("られない", &[Passive, Negative]);
-    r("られません", &[Passive, Negative, Honorific]);
-    r("られた", &[Passive, Past]);
-    r("られました", &[Passive, Past, Honorific]);
-    r("られる", &[Potential]);
-    r("られます", &[Potential, Honorific]);
-    r("られない", &[Potential, Negative]);
-    r("られません", &[Potential, Negative, Honorific]);
-    r("られた", &[Potential, Past]);
-    r("られました", &[Potential, Past, Honorific]);
-    r("られなかった", &[Potential, Past, Negative]);
-    r("られませんでした", &[Potential, Past, Negative, Honorific]);
     r("よう", &[Volitional]);
     r("ましょう", &[Volitional, Honorific]);
     r("るだろう", &[Volitional, Darou]);
     r("るでしょう", &[Volitional, Darou, Honorific]);
     r("ないだろう", &[Volitional, Negative]);
     r("ないでしょう", &[Volitional, Negative, Honorific]);
-    r("させる", &[Causative]);
+    ichidan_derived!(r() "られ", [Passive]);
+    ichidan_derived!(r() "られ", [Potential]);
+    ichidan_derived!(r() "させ", [Causative]);
+    ichidan_derived!(r() "させられ", [Causative, Passive]);
     r("ながら", &[Simultaneous]);
     r("そう", &[LooksLike]);
+    r("ず", &[Zu]);
 }
 
 pub fn ichidan_te(mut r: impl FnMut(&'static str, &[Form])) {
@@ -90,29 +102,21 @@ pub(crate) fn godan_lit(g: &'static Godan, mut r: impl FnMut(&'static str, &'sta
     r(g.i, "ましたら", &[Conditional, Honorific]);
     r(g.a, "なかったら", &[Conditional, Negative]);
     r(g.i, "ませんでしたら", &[Conditional, Negative, Honorific]);
-    r(g.a, "れる", &[Passive]);
-    r(g.a, "れます", &[Passive, Honorific]);
-    r(g.a, "れない", &[Passive, Negative]);
-    r(g.a, "れません", &[Passive, Negative, Honorific]);
-    r(g.a, "れた", &[Passive, Past]);
-    r(g.a, "れました", &[Passive, Past, Honorific]);
-    r(g.e, "る", &[Potential]);
-    r(g.e, "ます", &[Potential, Honorific]);
-    r(g.e, "ない", &[Potential, Negative]);
-    r(g.e, "ません", &[Potential, Negative, Honorific]);
-    r(g.e, "た", &[Potential, Past]);
-    r(g.e, "ました", &[Potential, Past, Honorific]);
-    r(g.e, "なかった", &[Potential, Past, Negative]);
-    r(g.e, "ませんでした", &[Potential, Past, Negative, Honorific]);
+    r(g.u, "と", &[To]);
+    r(g.a, "ないと", &[To, Negative]);
     r(g.o, "う", &[Volitional]);
     r(g.i, "ましょう", &[Volitional, Honorific]);
     r(g.u, "だろう", &[Volitional, Darou]);
     r(g.u, "でしょう", &[Volitional, Darou, Honorific]);
     r(g.a, "ないだろう", &[Volitional, Negative]);
     r(g.a, "ないでしょう", &[Volitional, Negative, Honorific]);
-    r(g.a, "せる", &[Causative]);
+    ichidan_derived!(r(g.a,) "れ", [Passive]);
+    ichidan_derived!(r(g.e,) "", [Potential]);
+    ichidan_derived!(r(g.a,) "せ", [Causative]);
+    ichidan_derived!(r(g.a,) "せられ", [Causative, Passive]);
     r(g.i, "ながら", &[Simultaneous]);
     r(g.i, "そう", &[LooksLike]);
+    r(g.a, "ず", &[Zu]);
 }
 
 pub(crate) fn godan_u(r: impl FnMut(&'static str, &'static str, &[Form])) {
@@ -125,6 +129,12 @@ pub(crate) fn godan_u_base(mut r: impl FnMut(&'static str, &'static str, &[Form]
     godan_lit(godan::U, r);
 }
 
+pub(crate) fn godan_u_s_base(mut r: impl FnMut(&'static str, &'static str, &[Form])) {
+    r("", "い", &[Stem]);
+    r("", "うて", &[Te]);
+    godan_lit(godan::U_S, r);
+}
+
 pub(crate) fn godan_iku(r: impl FnMut(&'static str, &'static str, &[Form])) {
     godan_lit(godan::IKU, r);
 }
@@ -215,25 +225,21 @@ pub(crate) fn kuru(mut r: impl FnMut(&'static str, &'static str, &[Form])) {
     r("き", "ましたら", &[Conditional, Honorific]);
     r("こ", "なかったら", &[Conditional, Negative]);
     r("き", "ませんでしたら", &[Conditional, Negative, Honorific]);
-    r("こ", "られる", &[Passive]);
-    r("こ", "られます", &[Passive, Honorific]);
-    r("こ", "られない", &[Passive, Negative]);
-    r("こ", "られません", &[Passive, Negative, Honorific]);
-    r("こ", "られた", &[Passive, Past]);
-    r("こ", "られました", &[Passive, Past, Honorific]);
-    r("こ", "られる", &[Potential]);
+    r("く", "ると", &[To]);
+    r("こ", "ないと", &[To, Negative]);
     r("こ", "よう", &[Volitional]);
     r("き", "ましょう", &[Volitional, Honorific]);
     r("く", "るだろう", &[Volitional, Darou]);
     r("く", "るでしょう", &[Volitional, Darou, Honorific]);
     r("こ", "ないだろう", &[Volitional, Negative]);
     r("こ", "ないでしょう", &[Volitional, Negative, Honorific]);
-    r("こ", "させる", &[Causative]);
-    r("こ", "させます", &[Causative, Honorific]);
-    r("こ", "させない", &[Causative, Negative]);
-    r("こ", "させません", &[Causative, Negative, Honorific]);
+    ichidan_derived!(r("こ",) "られ", [Passive]);
+    ichidan_derived!(r("こ",) "られ", [Potential]);
+    ichidan_derived!(r("こ",) "させ", [Causative]);
+    ichidan_derived!(r("こ",) "させられ", [Causative, Passive]);
     r("き", "ながら", &[Simultaneous]);
     r("き", "そう", &[LooksLike]);
+    r("こ", "ず", &[Zu]);
 }
 
 pub(crate) fn kuru_base(mut r: impl FnMut(&'static str, &'static str, &[Form])) {
@@ -268,34 +274,26 @@ pub(crate) fn suru(mut r: impl FnMut(&'static str, &'static str, &[Form])) {
     r("し", "ましたら", &[Conditional, Honorific]);
     r("し", "なかったら", &[Conditional, Negative]);
     r("し", "ませんでしたら", &[Conditional, Negative, Honorific]);
-    r("さ", "れる", &[Passive]);
-    r("さ", "れます", &[Passive, Honorific]);
-    r("さ", "れない", &[Passive, Negative]);
-    r("さ", "れません", &[Passive, Negative, Honorific]);
-    r("さ", "れた", &[Passive, Past]);
-    r("さ", "れました", &[Passive, Past, Honorific]);
-    r("で", "きる", &[Potential]);
-    r("で", "きます", &[Potential, Honorific]);
-    r("で", "きない", &[Potential, Negative]);
-    r("で", "きません", &[Potential, Negative, Honorific]);
-    r("で", "きた", &[Potential, Past]);
-    r("で", "きました", &[Potential, Past, Honorific]);
-    r("で", "きなかった", &[Potential, Past, Negative]);
-    r("で", "きませんでした", &[Potential, Past, Negative, Honorific]);
+    r("す", "ると", &[To]);
+    r("し", "ないと", &[To, Negative]);
     r("し", "よう", &[Volitional]);
     r("し", "ましょう", &[Volitional, Honorific]);
     r("す", "るだろう", &[Volitional, Darou]);
     r("す", "るでしょう", &[Volitional, Darou, Honorific]);
     r("し", "ないだろう", &[Volitional, Negative]);
     r("し", "ないでしょう", &[Volitional, Negative, Honorific]);
+    ichidan_derived!(r("さ",) "れ", [Passive]);
+    ichidan_derived!(r("で",) "き", [Potential]);
+    ichidan_derived!(r("さ",) "せ", [Causative]);
+    ichidan_derived!(r("さ",) "せられ", [Causative, Passive]);
     r("し", "たろう", &[Volitional, Past]);
     r("し", "ましたろう", &[Volitional, Past, Honorific]);
     r("し", "ただろう", &[Volitional, Past, Darou]);
     r("し", "なかっただろう", &[Volitional, Past, Negative]);
     r("し", "なかったでしょう", &[Volitional, Past, Negative, Honorific]);
-    r("さ", "せる", &[Causative]);
     r("し", "ながら", &[Simultaneous]);
     r("し", "そう", &[LooksLike]);
+    r("せ", "ず", &[Zu]);
 }
 
 pub(crate) fn suru_base(mut r: impl FnMut(&'static str, &'static str, &[Form])) {
@@ -304,6 +302,55 @@ pub(crate) fn suru_base(mut r: impl FnMut(&'static str, &'static str, &[Form]))
     suru(r);
 }
 
+/// Conjugations for a zuru verb, which is an alternative form of a jiru verb.
+#[rustfmt::skip]
+pub(crate) fn zuru(mut r: impl FnMut(&'static str, &'static str, &[Form])) {
+    r("ず", "る", &[]);
+    r("じ", "ます", &[Honorific]);
+    r("じ", "ない", &[Negative]);
+    r("じ", "ません", &[Negative, Honorific]);
+    r("じ", "た", &[Past]);
+    r("じ", "ました", &[Past, Honorific]);
+    r("じ", "なかった", &[Past, Negative]);
+    r("じ", "ませんでした", &[Past, Negative, Honorific]);
+    r("じ", "ろ", &[Command]);
+    r("じ", "なさい", &[Command, Honorific]);
+    r("じ", "てください", &[Command, Honorific, CommandTeKudasai]);
+    r("ぜ", "よ", &[Command, CommandYo]);
+    r("ず", "るな", &[Command, Negative]);
+    r("じ", "ないでください", &[Command, Negative, Honorific]);
+    r("ず", "りゃ", &[Hypothetical, Conversation]);
+    r("じ", "なけりゃ", &[Hypothetical, Conversation, Negative]);
+    r("ず", "れば", &[Hypothetical]);
+    r("じ", "なければ", &[Hypothetical, Negative]);
+    r("じ", "なきゃ", &[Hypothetical, Negative, Kya]);
+    r("じ", "たら", &[Conditional]);
+    r("じ", "ましたら", &[Conditional, Honorific]);
+    r("じ", "なかったら", &[Conditional, Negative]);
+    r("じ", "ませんでしたら", &[Conditional, Negative, Honorific]);
+    r("ず", "ると", &[To]);
+    r("じ", "ないと", &[To, Negative]);
+    r("じ", "よう", &[Volitional]);
+    r("じ", "ましょう", &[Volitional, Honorific]);
+    r("ず", "るだろう", &[Volitional, Darou]);
+    r("ず", "るでしょう", &[Volitional, Darou, Honorific]);
+    r("じ", "ないだろう", &[Volitional, Negative]);
+    r("じ", "ないでしょう", &[Volitional, Negative, Honorific]);
+    ichidan_derived!(r("じ",) "られ", [Passive]);
+    ichidan_derived!(r("じ",) "られ", [Potential]);
+    ichidan_derived!(r("じ",) "させ", [Causative]);
+    ichidan_derived!(r("じ",) "させられ", [Causative, Passive]);
+    r("じ", "ながら", &[Simultaneous]);
+    r("じ", "そう", &[LooksLike]);
+    r("ぜ", "ず", &[Zu]);
+}
+
+pub(crate) fn zuru_base(mut r: impl FnMut(&'static str, &'static str, &[Form])) {
+    r("じ", "", &[Stem]);
+    r("じ", "て", &[Te]);
+    zuru(r);
+}
+
 #[cfg(fake)]
 macro_rules! suru {
     ($macro:path) => {};
@@ -317,8 +364,14 @@ pub(crate) fn adjective_i(mut r: impl FnMut(&'static str, &[Form])) {
     r("かったです", &[Past, Honorific]);
     r("くない", &[Negative]);
     r("くないです", &[Negative, Honorific]);
-    r("なかった", &[Past, Negative]);
-    r("なかったです", &[Past, Negative, Honorific]);
+    r("くなかった", &[Past, Negative]);
+    r("くなかったです", &[Past, Negative, Honorific]);
+    r("ければ", &[Hypothetical]);
+    r("くなければ", &[Hypothetical, Negative]);
+    r("かったら", &[Conditional]);
+    r("くなかったら", &[Conditional, Negative]);
+    r("いと", &[To]);
+    r("くないと", &[To, Negative]);
     r("いよう", &[Volitional]);
 }
 
@@ -329,20 +382,51 @@ pub(crate) fn adjective_ii(mut r: impl FnMut(&'static str, &'static str, &[Form]
     r("よ", "かったです", &[Past, Honorific]);
     r("よ", "くない", &[Negative]);
     r("よ", "くないです", &[Negative, Honorific]);
-    r("よ", "なかった", &[Past, Negative]);
-    r("よ", "なかったです", &[Past, Negative, Honorific]);
+    r("よ", "くなかった", &[Past, Negative]);
+    r("よ", "くなかったです", &[Past, Negative, Honorific]);
+    r("よ", "ければ", &[Hypothetical]);
+    r("よ", "くなければ", &[Hypothetical, Negative]);
+    r("よ", "かったら", &[Conditional]);
+    r("よ", "くなかったら", &[Conditional, Negative]);
+    r("い", "いと", &[To]);
+    r("よ", "くないと", &[To, Negative]);
     r("い", "いよう", &[Volitional]);
 }
 
 pub(crate) fn adjective_na(mut r: impl FnMut(&'static str, &[Form])) {
+    r("な", &[Attributive]);
+    copula(r);
+}
+
+/// Conjugations of the copula だ, which are shared with na-adjectives.
+pub(crate) fn copula(mut r: impl FnMut(&'static str, &[Form])) {
     r("だ", &[]);
     r("です", &[Honorific]);
     r("だった", &[Past]);
     r("でした", &[Past, Honorific]);
     r("ではない", &[Negative]);
+    r("じゃない", &[Negative, Conversation]);
     r("ではありません", &[Negative, Honorific]);
+    r("じゃありません", &[Negative, Honorific, Conversation]);
     r("ではなかった", &[Past, Negative]);
+    r("じゃなかった", &[Past, Negative, Conversation]);
     r("ではありませんでした", &[Past, Negative, Honorific]);
+    r(
+        "じゃありませんでした",
+        &[Past, Negative, Honorific, Conversation],
+    );
+    r("で", &[Te]);
+    r("なら", &[Hypothetical]);
+    r("でなければ", &[Hypothetical, Negative]);
+    r("じゃなければ", &[Hypothetical, Negative, Conversation]);
+    r("だったら", &[Conditional]);
+    r("でしたら", &[Conditional, Honorific]);
+    r("じゃなかったら", &[Conditional, Negative]);
+    r("だと", &[To]);
+    r("ではないと", &[To, Negative]);
+    r("じゃないと", &[To, Negative, Conversation]);
+    r("だろう", &[Volitional, Darou]);
+    r("でしょう", &[Volitional, Darou, Honorific]);
 }
 
 /// Helper to construct a particular [`Inflection`].
@@ -5,6 +5,9 @@ mod macros;
 pub use self::conjugate::{conjugate, Kind, Reading};
 mod conjugate;
 
+#[cfg(test)]
+mod tests;
+
 use std::fmt;
 use std::ops::{BitAndAssign, BitOr};
 use std::{collections::BTreeMap, ops::BitXor};
@@ -19,7 +22,7 @@ use serde::{Deserialize, Serialize};
 use crate::kana::{Fragments, Full, OwnedFull};
 
 macro_rules! form {
-    ($vis:vis enum $name:ident { $({$variant:ident $(= $d:literal)?, $describe:literal, $title:literal, $url:expr $(,)?}),* $(,)? }) => {
+    ($vis:vis enum $name:ident { $({$variant:ident $(= $d:literal)?, $plain:literal, $describe:literal, $title:literal, $url:expr $(,)?}),* $(,)? }) => {
         #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
         #[derive(Encode, Decode, Serialize, Deserialize, Key)]
         #[key(bitset = 8)]
@@ -29,10 +32,17 @@ macro_rules! form {
         }
 
         impl $name {
-            $vis const ALL: [Form; 31] = [
+            $vis const ALL: [Form; 34] = [
                 $(Form::$variant,)*
             ];
 
+            /// Plain name of the form, used when explaining an inflection.
+            $vis fn name(&self) -> &'static str {
+                match self {
+                    $(Form::$variant => $plain,)*
+                }
+            }
+
             /// Describe the form.
             $vis fn describe(&self) -> &'static str {
                 match self {
@@ -59,40 +69,82 @@ macro_rules! form {
 
 form! {
     pub enum Form {
-        {Stem, "stem", "stem / infinite", None},
-        {Honorific, "敬語", "敬語 (ていご) honorific speech", None},
-        {Negative, "not", "not doing ~, the absense of ~", None},
-        {Te, "～て", "～te form, by itself acts as a command", Some("https://www.tofugu.com/japanese-grammar/te-form/")},
-        {TeAru, "～てある", "～てある, resulting, is / has been done", Some("https://www.tofugu.com/japanese-grammar/tearu/")},
-        {TeIru, "～ている", "～ている, progressive, shows that something is currently happening or ongoing", Some("https://www.tofugu.com/japanese-grammar/verb-continuous-form-teiru/")},
-        {TeIku, "～ていく", "～ていく, starting, to start, to continue, to go on", Some("https://www.tofugu.com/japanese-grammar/teiku-tekuru/")},
-        {TeKuru, "～てくる", "～てくる, to do .. and come back, to become, to continue, to start ~", Some("https://www.tofugu.com/japanese-grammar/teiku-tekuru/")},
-        {TeOku, "～ておく", "～ておく, to do something in advance", Some("https://www.tofugu.com/japanese-grammar/teoku/")},
-        {TeShimau, "～てしまう", "～てしまう, to do something by accident, to finish completely", None},
-        {Tai, "～たい", "～たい, expressing desire", Some("https://www.tofugu.com/japanese-grammar/tai-form/")},
-        {EasyTo, "easy", "～やすい, easy to do ~", Some("https://www.tofugu.com/japanese-grammar/yasui/")},
-        {HardTo, "hard", "～にくい, hard to do ~", Some("https://www.tofugu.com/japanese-grammar/nikui/")},
-        {TaGaRu, "～たがる", "～たがる, noting desire", Some("https://www.tofugu.com/japanese-grammar/tagaru-form/")},
-        {Causative, "caus", "causative, make ~ do something, let / allow ~", Some("https://www.tofugu.com/japanese-grammar/verb-causative-form-saseru/")},
-        {Chau, "～ちゃう", "～ちゃう, to do something by accident, to finish completely", None},
-        {Command, "cmd", "command forms, よ / なさい / ください", Some("https://www.tofugu.com/japanese-grammar/verb-command-form-ro/")},
-        {CommandTeKudasai, "～てください", "～てください, alternate command form", Some("https://www.tofugu.com/japanese-grammar/kudasai/")},
-        {CommandYo, "～よ", "～よ, alternate command form", None},
-        {Conditional, "cond", "～たら, conditional, if ~, when ~", Some("https://www.tofugu.com/japanese-grammar/conditional-form-tara/")},
-        {Darou, "～だろう", "～だろう, alternate form", Some("https://www.tofugu.com/japanese-grammar/darou/")},
-        {Hypothetical, "hyp", "hypothetical, if ~", None},
-        {Kya, "～きゃ", "～きゃ, alternative hypothetical negative, if not ~", None},
-        {Passive, "psv", "～られる, passive, ~ was done to someone or something", Some("https://www.tofugu.com/japanese-grammar/verb-passive-form-rareru/")},
-        {Past, "past", "過去形 (かこけい) past tense", None},
-        {Potential, "pot", "potential, can do ~", Some("https://www.tofugu.com/japanese-grammar/verb-potential-form-reru/")},
-        {Simultaneous, "～ながら", "～ながら, simultaneous, while ~", Some("https://www.tofugu.com/japanese-grammar/verb-nagara/")},
-        {Volitional, "vol", "～よう, volitional / presumptive, let's do ~", Some("https://www.tofugu.com/japanese-grammar/verb-volitional-form-you/")},
-        {LooksLike, "～そう", "～そう, looks like", Some("https://www.tofugu.com/japanese-grammar/verb-sou/")},
-        {Short, "short", "alternate shortened form", None},
-        {Conversation, "clq", "conversational / colloquial", None},
+        {Stem, "stem", "stem", "stem / infinite", None},
+        {Honorific, "polite", "敬語", "敬語 (ていご) honorific speech", None},
+        {Negative, "negative", "not", "not doing ~, the absense of ~", None},
+        {Te, "te-form", "～て", "～te form, by itself acts as a command", Some("https://www.tofugu.com/japanese-grammar/te-form/")},
+        {TeAru, "te-aru", "～てある", "～てある, resulting, is / has been done", Some("https://www.tofugu.com/japanese-grammar/tearu/")},
+        {TeIru, "te-iru", "～ている", "～ている, progressive, shows that something is currently happening or ongoing", Some("https://www.tofugu.com/japanese-grammar/verb-continuous-form-teiru/")},
+        {TeIku, "te-iku", "～ていく", "～ていく, starting, to start, to continue, to go on", Some("https://www.tofugu.com/japanese-grammar/teiku-tekuru/")},
+        {TeKuru, "te-kuru", "～てくる", "～てくる, to do .. and come back, to become, to continue, to start ~", Some("https://www.tofugu.com/japanese-grammar/teiku-tekuru/")},
+        {TeOku, "te-oku", "～ておく", "～ておく, to do something in advance", Some("https://www.tofugu.com/japanese-grammar/teoku/")},
+        {TeShimau, "te-shimau", "～てしまう", "～てしまう, to do something by accident, to finish completely", None},
+        {Tai, "tai", "～たい", "～たい, expressing desire", Some("https://www.tofugu.com/japanese-grammar/tai-form/")},
+        {EasyTo, "easy to", "easy", "～やすい, easy to do ~", Some("https://www.tofugu.com/japanese-grammar/yasui/")},
+        {HardTo, "hard to", "hard", "～にくい, hard to do ~", Some("https://www.tofugu.com/japanese-grammar/nikui/")},
+        {TaGaRu, "tagaru", "～たがる", "～たがる, noting desire", Some("https://www.tofugu.com/japanese-grammar/tagaru-form/")},
+        {Causative, "causative", "caus", "causative, make ~ do something, let / allow ~", Some("https://www.tofugu.com/japanese-grammar/verb-causative-form-saseru/")},
+        {Chau, "chau", "～ちゃう", "～ちゃう, to do something by accident, to finish completely", None},
+        {Command, "command", "cmd", "command forms, よ / なさい / ください", Some("https://www.tofugu.com/japanese-grammar/verb-command-form-ro/")},
+        {CommandTeKudasai, "te-kudasai", "～てください", "～てください, alternate command form", Some("https://www.tofugu.com/japanese-grammar/kudasai/")},
+        {CommandYo, "yo", "～よ", "～よ, alternate command form", None},
+        {Conditional, "conditional", "cond", "～たら, conditional, if ~, when ~", Some("https://www.tofugu.com/japanese-grammar/conditional-form-tara/")},
+        {Darou, "darou", "～だろう", "～だろう, alternate form", Some("https://www.tofugu.com/japanese-grammar/darou/")},
+        {Hypothetical, "hypothetical", "hyp", "～ば, hypothetical, if ~", None},
+        {Kya, "kya", "～きゃ", "～きゃ, alternative hypothetical negative, if not ~", None},
+        {Passive, "passive", "psv", "～られる, passive, ~ was done to someone or something", Some("https://www.tofugu.com/japanese-grammar/verb-passive-form-rareru/")},
+        {Past, "past", "past", "過去形 (かこけい) past tense", None},
+        {Potential, "potential", "pot", "potential, can do ~", Some("https://www.tofugu.com/japanese-grammar/verb-potential-form-reru/")},
+        {Simultaneous, "nagara", "～ながら", "～ながら, simultaneous, while ~", Some("https://www.tofugu.com/japanese-grammar/verb-nagara/")},
+        {Volitional, "volitional", "vol", "～よう, volitional / presumptive, let's do ~", Some("https://www.tofugu.com/japanese-grammar/verb-volitional-form-you/")},
+        {LooksLike, "sou", "～そう", "～そう, looks like", Some("https://www.tofugu.com/japanese-grammar/verb-sou/")},
+        {Short, "short", "short", "alternate shortened form", None},
+        {Conversation, "colloquial", "clq", "conversational / colloquial", None},
+        {Zu, "zu", "～ず", "～ず, classical negative, without doing ~", None},
+        {Attributive, "attributive", "attr", "attributive, directly modifies a following noun", None},
+        {To, "to-conditional", "～と", "～と, conditional, whenever ~, ~ naturally leads to", None},
     }
 }
 
+/// The order in which forms are applied to the dictionary form of a word,
+/// which is used to explain an inflection.
+const ORDER: [Form; 34] = [
+    Form::Stem,
+    Form::Causative,
+    Form::Passive,
+    Form::Potential,
+    Form::Te,
+    Form::TeIru,
+    Form::TeAru,
+    Form::TeIku,
+    Form::TeKuru,
+    Form::TeOku,
+    Form::TeShimau,
+    Form::Chau,
+    Form::Tai,
+    Form::TaGaRu,
+    Form::EasyTo,
+    Form::HardTo,
+    Form::LooksLike,
+    Form::Simultaneous,
+    Form::Honorific,
+    Form::Negative,
+    Form::Zu,
+    Form::Past,
+    Form::Command,
+    Form::CommandTeKudasai,
+    Form::CommandYo,
+    Form::Conditional,
+    Form::Hypothetical,
+    Form::Kya,
+    Form::To,
+    Form::Volitional,
+    Form::Darou,
+    Form::Attributive,
+    Form::Short,
+    Form::Conversation,
+];
+
 #[derive(
     Default,
     Clone,
@@ -186,6 +238,27 @@ impl Inflection {
     pub fn iter(&self) -> impl Iterator<Item = Form> {
         self.form.iter()
     }
+
+    /// Iterate over forms in the order they are applied to the dictionary form
+    /// of a word.
+    pub fn explain(&self) -> impl Iterator<Item = Form> + '_ {
+        ORDER.into_iter().filter(|f| self.form.contains(*f))
+    }
+
+    /// Explain how the inflection is formed, like `passive → negative → past`.
+    pub fn explanation(&self) -> String {
+        let mut out = String::new();
+
+        for (n, form) in self.explain().enumerate() {
+            if n > 0 {
+                out.push_str(" → ");
+            }
+
+            out.push_str(form.name());
+        }
+
+        out
+    }
 }
 
 impl fmt::Debug for Inflection {
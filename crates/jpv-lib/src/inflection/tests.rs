@@ -0,0 +1,261 @@
+use std::fmt::Write;
+
+use fixed_map::Set;
+
+use crate::config::Config;
+use crate::data;
+use crate::database::{self, Database, Entry, Input, Location, Source};
+use crate::inflection::{conjugate, Form, Inflection};
+use crate::reporter::EmptyReporter;
+use crate::token::Token;
+use crate::PartOfSpeech;
+
+/// Expected conjugations, see the header of the file for its format.
+const CONJUGATIONS: &str = include_str!("conjugations.txt");
+
+struct Word<'a> {
+    line: usize,
+    kanji: Option<&'a str>,
+    reading: &'a str,
+    pos: &'a str,
+    expected: Vec<Expected<'a>>,
+}
+
+struct Expected<'a> {
+    line: usize,
+    inflection: Inflection,
+    text: &'a str,
+    reading: &'a str,
+}
+
+fn parse_form(form: &str) -> Option<Form> {
+    Form::ALL.into_iter().find(|f| format!("{f:?}") == form)
+}
+
+fn parse_fixture(input: &str) -> Vec<Word<'_>> {
+    let mut words = Vec::<Word<'_>>::new();
+
+    for (n, line) in input.lines().enumerate() {
+        let line_number = n + 1;
+
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if !line.starts_with(char::is_whitespace) {
+            let mut it = line.split_whitespace();
+
+            let (Some(kanji), Some(reading), Some(pos), None) =
+                (it.next(), it.next(), it.next(), it.next())
+            else {
+                panic!("{line_number}: expected `<kanji> <reading> <pos>`");
+            };
+
+            assert!(
+                PartOfSpeech::parse_keyword(pos).is_some(),
+                "{line_number}: unknown part of speech `{pos}`"
+            );
+
+            words.push(Word {
+                line: line_number,
+                kanji: (kanji != "-").then_some(kanji),
+                reading,
+                pos,
+                expected: Vec::new(),
+            });
+
+            continue;
+        }
+
+        let Some(word) = words.last_mut() else {
+            panic!("{line_number}: expectation without a word");
+        };
+
+        let Some((forms, values)) = line.split_once(':') else {
+            panic!("{line_number}: expected `<forms>: <text> [reading]`");
+        };
+
+        let mut set = Set::new();
+
+        for form in forms.split_whitespace() {
+            let Some(form) = parse_form(form) else {
+                panic!("{line_number}: unknown form `{form}`");
+            };
+
+            set.insert(form);
+        }
+
+        let mut it = values.split_whitespace();
+
+        let (Some(text), reading, None) = (it.next(), it.next(), it.next()) else {
+            panic!("{line_number}: expected `<forms>: <text> [reading]`");
+        };
+
+        word.expected.push(Expected {
+            line: line_number,
+            inflection: Inflection::new(set),
+            text,
+            reading: reading.unwrap_or(text),
+        });
+    }
+
+    words
+}
+
+/// Construct a JMdict document containing the given words, where each word is
+/// assigned a sequence number corresponding to its position.
+fn to_jmdict(words: &[Word<'_>]) -> String {
+    let mut xml = String::from("<JMdict>");
+
+    for (sequence, word) in words.iter().enumerate() {
+        xml.push_str("<entry>");
+        write!(xml, "<ent_seq>{sequence}</ent_seq>").unwrap();
+
+        if let Some(kanji) = word.kanji {
+            write!(xml, "<k_ele><keb>{kanji}</keb></k_ele>").unwrap();
+        }
+
+        write!(xml, "<r_ele><reb>{}</reb></r_ele>", word.reading).unwrap();
+        write!(
+            xml,
+            "<sense><pos>&{};</pos><gloss>test</gloss></sense>",
+            word.pos
+        )
+        .unwrap();
+        xml.push_str("</entry>");
+    }
+
+    xml.push_str("</JMdict>");
+    xml
+}
+
+#[test]
+fn conjugations() {
+    let words = parse_fixture(CONJUGATIONS);
+    let xml = to_jmdict(&words);
+    let mut parser = crate::jmdict::Parser::new(&xml);
+
+    let mut failures = Vec::new();
+
+    for word in &words {
+        let entry = parser.parse().unwrap().expect("missing entry");
+        let conjugations = conjugate(&entry);
+
+        let Some((_, inflections, _)) = conjugations.first() else {
+            failures.push(format!("{}: {} did not conjugate", word.line, word.reading));
+            continue;
+        };
+
+        for expected in &word.expected {
+            let Some(f) = inflections.get(expected.inflection) else {
+                failures.push(format!(
+                    "{}: missing {:?}",
+                    expected.line, expected.inflection
+                ));
+                continue;
+            };
+
+            let text = format!("{}{}", f.text(), f.suffix());
+            let reading = format!("{}{}", f.reading(), f.suffix());
+
+            if text != expected.text || reading != expected.reading {
+                failures.push(format!(
+                    "{}: {:?}: expected {} [{}], but got {text} [{reading}]",
+                    expected.line, expected.inflection, expected.text, expected.reading
+                ));
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Every inflection generated for a word should be found when looking it up in
+/// a database, and resolve back to the entry of the word.
+#[test]
+fn deinflect_round_trip() {
+    let words = parse_fixture(CONJUGATIONS);
+    let xml = to_jmdict(&words);
+
+    let buf = database::build(
+        &EmptyReporter,
+        &Token::default(),
+        "jmdict",
+        Input::Jmdict(&xml),
+        false,
+    )
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("inflection.index");
+    std::fs::write(&path, buf.as_slice()).unwrap();
+
+    let data = data::open(&path).unwrap();
+    let db = Database::open(
+        [(data, Location::Path(path.clone().into()))],
+        &Config::default(),
+    )
+    .unwrap();
+
+    let mut parser = crate::jmdict::Parser::new(&xml);
+    let mut failures = Vec::new();
+
+    for (sequence, word) in words.iter().enumerate() {
+        let entry = parser.parse().unwrap().expect("missing entry");
+
+        for (_, inflections, _) in conjugate(&entry) {
+            for (inflection, f) in inflections.iter() {
+                let text = format!("{}{}", f.text(), f.suffix());
+                let reading = format!("{}{}", f.reading(), f.suffix());
+
+                for query in [&text, &reading] {
+                    let mut found = false;
+
+                    for id in db.lookup(query).unwrap() {
+                        let Source::Inflection { data } = id.source() else {
+                            continue;
+                        };
+
+                        if data.inflection != *inflection {
+                            continue;
+                        }
+
+                        if let Entry::Phrase(e) = db.entry_at(id).unwrap() {
+                            found |= e.sequence == sequence as u64;
+                        }
+                    }
+
+                    if !found {
+                        failures.push(format!(
+                            "{}: {query} does not deinflect to {:?} of {}",
+                            word.line, inflection, word.reading
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    drop(db);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Every form should be part of explanations, ordered from the form applied
+/// first to the one applied last.
+#[test]
+fn explanations() {
+    for form in Form::ALL {
+        let mut set = Set::new();
+        set.insert(form);
+        assert_eq!(Inflection::new(set).explanation(), form.name());
+    }
+
+    let mut set = Set::new();
+    set.insert(Form::Past);
+    set.insert(Form::Negative);
+    set.insert(Form::Passive);
+    assert_eq!(
+        Inflection::new(set).explanation(),
+        "passive → negative → past"
+    );
+}
@@ -5,8 +5,9 @@ use fixed_map::Set;
 use musli::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 
+use crate::config::Ranking;
 use crate::entities::{Dialect, Field, KanjiInfo, Miscellaneous, PartOfSpeech, ReadingInfo};
-use crate::priority::Priority;
+use crate::priority::{self, Priority};
 use crate::Weight;
 
 #[borrowme::borrowme]
@@ -26,8 +27,8 @@ pub struct Entry<'a> {
 }
 
 impl Entry<'_> {
-    /// Entry weight.
-    pub fn weight(&self, input: &str, conjugation: bool) -> Weight {
+    /// Entry weight, using the given ranking weights.
+    pub fn weight(&self, input: &str, conjugation: bool, ranking: &Ranking) -> Weight {
         // Boost based on exact query.
         let mut query = 1.0f32;
         // Store the priority which performs the maximum boost.
@@ -35,43 +36,69 @@ impl Entry<'_> {
         // Perform boost by number of senses, maximum boost at 10 senses.
         let sense_count = 1.0 + self.senses.len().min(10) as f32 / 10.0;
         // Conjugation boost.
-        let conjugation = if conjugation { 1.2 } else { 1.0 };
+        let conjugation = if conjugation { ranking.inflection } else { 1.0 };
         // Calculate length boost.
         let length = (input.chars().count().min(10) as f32 / 10.0) * 1.2;
+        // Boost based on preferred parts of speech.
+        let pos = ranking.part_of_speech_boost(
+            self.senses
+                .iter()
+                .flat_map(|s| s.pos.iter())
+                .map(|p| p.group()),
+        );
+
+        // Scale the boost of a priority by how strongly it should apply.
+        let scaled = |p: &Priority| {
+            let scale = if p.is_frequency() {
+                ranking.frequency
+            } else {
+                ranking.priority
+            };
+
+            1.0 + (p.weight() - 1.0) * scale
+        };
 
         for element in &self.reading_elements {
             if element.text == input {
                 if element.no_kanji || self.kanji_elements.iter().all(|k| k.is_rare()) {
-                    query = query.max(3.0);
+                    query = query.max(ranking.exact_kana);
                 } else {
-                    query = query.max(2.0);
+                    query = query.max(ranking.exact_reading);
                 }
             }
 
             for p in &element.priority {
-                priority = priority.max(p.weight());
+                priority = priority.max(scaled(p));
             }
         }
 
         for element in &self.kanji_elements {
             if element.text == input {
-                query = query.max(3.0);
+                query = query.max(ranking.exact_kanji);
             }
 
             for p in &element.priority {
-                priority = priority.max(p.weight());
+                priority = priority.max(scaled(p));
             }
         }
 
+        let priorities = self
+            .kanji_elements
+            .iter()
+            .flat_map(|e| &e.priority)
+            .chain(self.reading_elements.iter().flat_map(|e| &e.priority));
+
+        priority += priority::list_bonus(priorities) * ranking.priority;
+
         for sense in &self.senses {
             for gloss in &sense.gloss {
                 if gloss.text == input {
-                    query = query.max(1.5);
+                    query = query.max(ranking.exact_gloss);
                 }
             }
         }
 
-        Weight::new(query * priority * sense_count * conjugation * length)
+        Weight::new(query * priority * sense_count * conjugation * length * pos)
     }
 }
 
@@ -99,6 +126,12 @@ impl<'a> KanjiElement<'a> {
         self.info.contains(KanjiInfo::SearchOnlyKanji)
     }
 
+    /// Test if this is a spelling which is rarely used, because it's
+    /// rarely-used, outdated or only intended for searching.
+    pub fn is_rarely_used(&self) -> bool {
+        is_rarely_used_kanji(&self.info)
+    }
+
     /// Debug the kanji element, while avoiding formatting elements which are
     /// not defined.
     pub fn debug_sparse(&self) -> impl fmt::Debug + '_ {
@@ -126,6 +159,20 @@ impl<'a> KanjiElement<'a> {
     }
 }
 
+impl OwnedKanjiElement {
+    /// Test if this is a spelling which is rarely used, because it's
+    /// rarely-used, outdated or only intended for searching.
+    pub fn is_rarely_used(&self) -> bool {
+        is_rarely_used_kanji(&self.info)
+    }
+}
+
+fn is_rarely_used_kanji(info: &Set<KanjiInfo>) -> bool {
+    info.contains(KanjiInfo::RareKanji)
+        || info.contains(KanjiInfo::OutdatedKanji)
+        || info.contains(KanjiInfo::SearchOnlyKanji)
+}
+
 #[borrowme::borrowme]
 #[derive(Clone, Debug, Serialize, Deserialize, Encode, Decode)]
 #[musli(packed)]
@@ -182,6 +229,12 @@ impl<'a> ReadingElement<'a> {
         self.info.contains(ReadingInfo::SearchOnlyKana)
     }
 
+    /// Test if this is a reading which is rarely used, because it's
+    /// rarely-used, obsolete or only intended for searching.
+    pub fn is_rarely_used(&self) -> bool {
+        is_rarely_used_kana(&self.info)
+    }
+
     /// Test if this reading applies to the given string.
     pub fn applies_to(&self, text: &str) -> bool {
         if self.no_kanji || self.is_search_only() {
@@ -207,6 +260,12 @@ impl OwnedReadingElement {
         self.info.contains(ReadingInfo::SearchOnlyKana)
     }
 
+    /// Test if this is a reading which is rarely used, because it's
+    /// rarely-used, obsolete or only intended for searching.
+    pub fn is_rarely_used(&self) -> bool {
+        is_rarely_used_kana(&self.info)
+    }
+
     /// Test if this reading applies to the given string.
     pub fn applies_to(&self, text: &str) -> bool {
         if self.applies_to_nothing() {
@@ -221,6 +280,12 @@ impl OwnedReadingElement {
     }
 }
 
+fn is_rarely_used_kana(info: &Set<ReadingInfo>) -> bool {
+    info.contains(ReadingInfo::RareKana)
+        || info.contains(ReadingInfo::ObsoleteKana)
+        || info.contains(ReadingInfo::SearchOnlyKana)
+}
+
 #[borrowme::borrowme]
 #[derive(Clone, Debug, Serialize, Deserialize, Encode, Decode)]
 #[musli(packed)]
@@ -265,6 +330,13 @@ pub struct Glossary<'a> {
     pub lang: Option<&'a str>,
 }
 
+impl Glossary<'_> {
+    /// Test if the glossary entry is in the given language.
+    pub fn is_lang(&self, arg: &str) -> bool {
+        self.lang.unwrap_or(DEFAULT_LANGUAGE) == arg
+    }
+}
+
 const DEFAULT_LANGUAGE: &str = "eng";
 
 #[borrowme::borrowme]
@@ -392,13 +464,7 @@ impl<'a> Sense<'a> {
     }
 
     pub fn is_lang(&self, arg: &str) -> bool {
-        for g in &self.gloss {
-            if g.lang.unwrap_or(DEFAULT_LANGUAGE) == arg {
-                return true;
-            }
-        }
-
-        false
+        self.gloss.iter().any(|g| g.is_lang(arg))
     }
 }
 
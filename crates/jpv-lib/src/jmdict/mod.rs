@@ -1,3 +1,6 @@
+#[cfg(test)]
+mod tests;
+
 pub use self::parser::Parser;
 mod parser;
 
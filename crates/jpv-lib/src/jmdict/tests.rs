@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+use fixed_map::Set;
+
+use super::{Entry, KanjiElement, ReadingElement};
+use crate::config::Ranking;
+use crate::entities::{KanjiInfo, ReadingInfo};
+use crate::priority::Priority;
+
+fn reading<'a>(text: &'a str, no_kanji: bool, priority: &[&str]) -> ReadingElement<'a> {
+    ReadingElement {
+        text,
+        no_kanji,
+        reading_string: HashSet::new(),
+        priority: priority.iter().flat_map(|p| Priority::parse(p)).collect(),
+        info: Set::new(),
+    }
+}
+
+fn entry<'a>(kanji: Option<&'a str>, reading: ReadingElement<'a>) -> Entry<'a> {
+    Entry {
+        sequence: 0,
+        reading_elements: vec![reading],
+        kanji_elements: kanji
+            .into_iter()
+            .map(|text| KanjiElement {
+                text,
+                priority: Vec::new(),
+                info: Set::new(),
+            })
+            .collect(),
+        senses: Vec::new(),
+    }
+}
+
+#[test]
+fn ranking() {
+    let common = entry(Some("猫"), reading("ねこ", false, &["ichi1", "nf05"]));
+    let kana = entry(None, reading("ねこ", true, &[]));
+
+    // Weights sort in reverse, so the lesser weight ranks first.
+    let default = Ranking::default();
+    assert!(common.weight("ねこ", false, &default) < kana.weight("ねこ", false, &default));
+
+    let ranking = Ranking {
+        exact_kana: 100.0,
+        ..Ranking::default()
+    };
+
+    assert!(common.weight("ねこ", false, &ranking) > kana.weight("ねこ", false, &ranking));
+
+    let ranking = Ranking {
+        priority: 0.0,
+        frequency: 0.0,
+        ..Ranking::default()
+    };
+
+    assert!(common.weight("ねこ", false, &ranking) > kana.weight("ねこ", false, &ranking));
+    assert!(kana.weight("ねこ", true, &default) < kana.weight("ねこ", false, &default));
+}
+
+#[test]
+fn ranking_lists() {
+    let several = entry(None, reading("ねこ", true, &["spec1", "ichi1", "news1"]));
+    let spec = entry(None, reading("ねこ", true, &["spec1"]));
+    let news = entry(None, reading("ねこ", true, &["news1"]));
+    let second = entry(None, reading("ねこ", true, &["news2"]));
+
+    let default = Ranking::default();
+    let weight = |e: &Entry<'_>| e.weight("ねこ", false, &default);
+
+    assert!(weight(&several) < weight(&spec));
+    assert!(weight(&spec) < weight(&news));
+    assert!(weight(&news) < weight(&second));
+
+    let ranking = Ranking {
+        priority: 0.0,
+        ..Ranking::default()
+    };
+
+    assert_eq!(
+        several.weight("ねこ", false, &ranking),
+        news.weight("ねこ", false, &ranking)
+    );
+}
+
+#[test]
+fn rarely_used() {
+    let mut kanji = KanjiElement {
+        text: "猫",
+        priority: Vec::new(),
+        info: Set::new(),
+    };
+
+    assert!(!kanji.is_rarely_used());
+    kanji.info.insert(KanjiInfo::Ateji);
+    assert!(!kanji.is_rarely_used());
+    kanji.info.insert(KanjiInfo::OutdatedKanji);
+    assert!(kanji.is_rarely_used());
+
+    let mut kana = reading("ねこ", false, &[]);
+    assert!(!kana.is_rarely_used());
+    kana.info.insert(ReadingInfo::RareKana);
+    assert!(kana.is_rarely_used());
+}
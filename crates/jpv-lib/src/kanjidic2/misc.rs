@@ -34,19 +34,25 @@ pub(crate) struct Builder<'a> {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Encode, Decode)]
 #[musli(packed)]
 pub struct Misc<'a> {
+    /// The school grade the kanji is taught in, where 1 through 6 are the
+    /// kyōiku kanji, 8 are the remaining jōyō kanji and 9 and 10 are jinmeiyō
+    /// kanji.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    grade: Option<u8>,
+    pub grade: Option<u8>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    stroke_count: Option<u8>,
+    pub stroke_count: Option<u8>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[borrowed_attr(serde(borrow))]
-    variant: Option<Variant<'a>>,
+    pub variant: Option<Variant<'a>>,
+    /// Frequency rank among the 2500 most used kanji in newspapers, where 1 is
+    /// the most frequent.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    freq: Option<u32>,
+    pub freq: Option<u32>,
+    /// Level in the pre-2010 JLPT, from 4 (easiest) to 1 (hardest).
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    jlpt: Option<u8>,
+    pub jlpt: Option<u8>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
-    radical_names: Vec<&'a str>,
+    pub radical_names: Vec<&'a str>,
 }
 
 impl<'a> Builder<'a> {
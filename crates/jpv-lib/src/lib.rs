@@ -8,7 +8,14 @@
 /// Dictionary magic `JPVD`.
 pub const DICTIONARY_MAGIC: u32 = 0x4a_50_56_44;
 /// Current database version in use.
-pub const DICTIONARY_VERSION: u32 = 5;
+pub const DICTIONARY_VERSION: u32 = 15;
+/// The oldest database version which can still be opened.
+///
+/// Changes which only add to the stored records can keep this as is, with
+/// readers checking the version of the index before using what was added.
+/// Anything else has to raise it to [`DICTIONARY_VERSION`] so that older
+/// indexes are rebuilt.
+pub const DICTIONARY_OLDEST_VERSION: u32 = 15;
 
 /// Helper to convert a type to its owned variant.
 pub use ::borrowme::to_owned;
@@ -30,6 +37,36 @@ pub use self::inflection::{Form, Inflection, Inflections, OwnedInflections};
 
 pub mod config;
 
+pub mod profile;
+
+pub mod quiz;
+
+pub mod graph;
+
+pub mod sync;
+
+pub mod export;
+
+pub mod reader;
+
+pub mod tokenizer;
+
+pub mod recordings;
+
+pub mod mining;
+
+pub mod anki;
+
+pub mod stats;
+
+pub mod capture;
+
+pub mod audio;
+
+pub mod ocr;
+
+pub mod link;
+
 pub mod data;
 
 pub mod api;
@@ -40,21 +77,34 @@ mod dirs;
 mod concat;
 pub use self::concat::Concat;
 
-pub use self::sort_key::{Key, Weight};
+pub use self::sort_key::{Key, Score, Weight};
 mod sort_key;
 
+pub mod ids;
 pub mod jmdict;
 pub mod jmnedict;
 pub mod kanjidic2;
+pub mod tanaka;
+
+pub mod counters;
+
+pub mod difficulty;
+pub use self::difficulty::Difficulty;
 
 pub mod entities;
-pub use self::entities::PartOfSpeech;
+pub use self::entities::{PartOfSpeech, PartOfSpeechGroup};
 
 mod furigana;
 pub use self::furigana::{Furigana, FuriganaGroup};
 
 pub mod romaji;
 
+pub mod detect;
+
+pub mod normalize;
+
+pub mod query;
+
 pub mod kana;
 
 mod priority;
@@ -0,0 +1,231 @@
+//! Compact links to queries.
+//!
+//! A query in the web application is described by parameters like
+//! `?q=...&at=...&index=...`, where percent-encoding makes Japanese text three
+//! times as long. Short links instead put every parameter in a single
+//! URL-safe base64 token, like `/s/AQFx...`, which the service expands back
+//! into the full query when the link is opened.
+//!
+//! Other applications trigger lookups through deep links like
+//! `jpv://lookup?q=食べる`, which are parsed with [`parse_deep_link`].
+
+#[cfg(test)]
+mod tests;
+
+use anyhow::{bail, ensure, Context, Result};
+
+/// Version of the encoding, stored as the first byte of a token so that it
+/// can be changed without breaking links which have already been shared.
+const VERSION: u8 = 1;
+
+/// The URI scheme of deep links.
+pub const SCHEME: &str = "jpv";
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode query parameters into a token.
+pub fn encode<'a, I>(params: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let mut data = vec![VERSION];
+
+    for (key, value) in params {
+        write_str(&mut data, key);
+        write_str(&mut data, value);
+    }
+
+    base64_encode(&data)
+}
+
+/// Decode a token into the query parameters it was encoded from.
+pub fn decode(token: &str) -> Result<Vec<(String, String)>> {
+    let data = base64_decode(token)?;
+
+    let Some((&version, mut data)) = data.split_first() else {
+        bail!("Link is empty");
+    };
+
+    ensure!(
+        version == VERSION,
+        "Link is of unsupported version {version}"
+    );
+
+    let mut params = Vec::new();
+
+    while !data.is_empty() {
+        let key = read_str(&mut data).context("Reading parameter name")?;
+        let value = read_str(&mut data).context("Reading parameter value")?;
+        params.push((key, value));
+    }
+
+    Ok(params)
+}
+
+/// Expand a token into the query string it was encoded from, like
+/// `q=%E9%A3%9F&index=1`.
+pub fn expand(token: &str) -> Result<String> {
+    let mut out = String::new();
+
+    for (key, value) in decode(token)? {
+        if !out.is_empty() {
+            out.push('&');
+        }
+
+        percent_encode(&mut out, &key);
+        out.push('=');
+        percent_encode(&mut out, &value);
+    }
+
+    Ok(out)
+}
+
+/// Parse a deep link like `jpv://lookup?q=食べる` into the query string it
+/// opens in the web application, like `q=%E9%A3%9F%E3%81%B9%E3%82%8B`.
+///
+/// The parameters of a lookup are the same as those of the web application.
+pub fn parse_deep_link(uri: &str) -> Result<String> {
+    let uri = uri.trim();
+
+    let rest = match uri.split_once(':') {
+        Some((scheme, rest)) if scheme.eq_ignore_ascii_case(SCHEME) => rest,
+        _ => bail!("Not a {SCHEME}:// link: {uri}"),
+    };
+
+    let rest = rest.trim_start_matches('/');
+    let rest = rest.split_once('#').map_or(rest, |(rest, _)| rest);
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+    match action.trim_end_matches('/') {
+        "lookup" => {}
+        action => bail!("Unsupported link action `{action}`"),
+    }
+
+    let mut out = String::new();
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        if !out.is_empty() {
+            out.push('&');
+        }
+
+        percent_encode(&mut out, &percent_decode(key)?);
+        out.push('=');
+        percent_encode(&mut out, &percent_decode(value)?);
+    }
+
+    Ok(out)
+}
+
+fn write_str(data: &mut Vec<u8>, string: &str) {
+    let mut len = string.len();
+
+    // Lengths are variable-length encoded, since they're almost always short.
+    while len >= 0x80 {
+        data.push((len & 0x7f) as u8 | 0x80);
+        len >>= 7;
+    }
+
+    data.push(len as u8);
+    data.extend_from_slice(string.as_bytes());
+}
+
+fn read_str(data: &mut &[u8]) -> Result<String> {
+    let mut len = 0usize;
+    let mut shift = 0;
+
+    loop {
+        let Some((&b, rest)) = data.split_first() else {
+            bail!("Link is truncated");
+        };
+
+        *data = rest;
+        ensure!(shift < usize::BITS, "Link has an invalid length");
+        len |= usize::from(b & 0x7f) << shift;
+
+        if b & 0x80 == 0 {
+            break;
+        }
+
+        shift += 7;
+    }
+
+    ensure!(len <= data.len(), "Link is truncated");
+    let (string, rest) = data.split_at(len);
+    *data = rest;
+    Ok(String::from_utf8(string.to_vec())?)
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - i * 8));
+
+        for i in 0..=chunk.len() {
+            out.push(char::from(ALPHABET[(n >> (18 - i * 6)) as usize & 0x3f]));
+        }
+    }
+
+    out
+}
+
+fn base64_decode(token: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(token.len() * 3 / 4);
+    let mut n = 0u32;
+    let mut bits = 0;
+
+    for c in token.trim_end_matches('=').bytes() {
+        let Some(value) = ALPHABET.iter().position(|&a| a == c) else {
+            bail!("Link contains invalid character `{}`", char::from(c));
+        };
+
+        n = n << 6 | value as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            out.push((n >> bits) as u8);
+        }
+    }
+
+    Ok(out)
+}
+
+fn percent_encode(out: &mut String, string: &str) {
+    for b in string.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(char::from(b));
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+}
+
+fn percent_decode(string: &str) -> Result<String> {
+    let mut out = Vec::with_capacity(string.len());
+    let mut bytes = string.bytes();
+
+    while let Some(b) = bytes.next() {
+        match b {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = [bytes.next(), bytes.next()];
+
+                let [Some(hi), Some(lo)] = hex.map(|b| b.and_then(|b| char::from(b).to_digit(16)))
+                else {
+                    bail!("Invalid escape in `{string}`");
+                };
+
+                out.push((hi << 4 | lo) as u8);
+            }
+            b => out.push(b),
+        }
+    }
+
+    String::from_utf8(out).with_context(|| format!("`{string}` is not UTF-8"))
+}
@@ -0,0 +1,77 @@
+use super::{decode, encode, expand, parse_deep_link};
+
+#[test]
+fn roundtrip() {
+    let params = [("q", "今日は食べる"), ("at", "6"), ("index", "1")];
+    let token = encode(params);
+
+    let decoded = decode(&token).unwrap();
+
+    let decoded = decoded
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(decoded, params);
+    assert!(token
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_')));
+}
+
+#[test]
+fn long_values() {
+    let text = "食".repeat(100);
+    let token = encode([("q", text.as_str())]);
+    assert_eq!(decode(&token).unwrap(), [("q".to_owned(), text)]);
+}
+
+#[test]
+fn expand_query() {
+    let token = encode([("q", "食 べ"), ("t", "a&b"), ("index", "2")]);
+    assert_eq!(
+        expand(&token).unwrap(),
+        "q=%E9%A3%9F%20%E3%81%B9&t=a%26b&index=2"
+    );
+}
+
+#[test]
+fn shorter_than_percent_encoding() {
+    let text = "吾輩は猫である。名前はまだ無い。";
+    let token = encode([("q", text)]);
+    assert!(token.len() < expand(&token).unwrap().len());
+}
+
+#[test]
+fn invalid() {
+    assert!(decode("").is_err());
+    assert!(decode("not a token!").is_err());
+    // Unsupported version.
+    assert!(decode("Ag").is_err());
+
+    let token = encode([("q", "食べる")]);
+    assert!(decode(&token[..token.len() - 2]).is_err());
+}
+
+#[test]
+fn deep_link() {
+    assert_eq!(
+        parse_deep_link("jpv://lookup?q=%E9%A3%9F%E3%81%B9%E3%82%8B").unwrap(),
+        "q=%E9%A3%9F%E3%81%B9%E3%82%8B"
+    );
+
+    // Unescaped text, as typed by hand, is escaped.
+    assert_eq!(
+        parse_deep_link("JPV://lookup/?q=食 べ+る&index=1#ignored").unwrap(),
+        "q=%E9%A3%9F%20%E3%81%B9%20%E3%82%8B&index=1"
+    );
+
+    assert_eq!(parse_deep_link("jpv:lookup").unwrap(), "");
+}
+
+#[test]
+fn invalid_deep_link() {
+    assert!(parse_deep_link("http://lookup?q=a").is_err());
+    assert!(parse_deep_link("jpv://delete?q=a").is_err());
+    assert!(parse_deep_link("jpv://lookup?q=%E9%A3").is_err());
+    assert!(parse_deep_link("jpv://lookup?q=%zz").is_err());
+}
@@ -0,0 +1,318 @@
+//! Sentence cards mined from captured text.
+//!
+//! A card holds a captured sentence, the word it was mined for and the
+//! meaning of that word, and optionally an image of where the sentence was
+//! captured from. Each card is stored as a JSON file in the mining directory
+//! named after its numerical identifier, next to its image if it has one.
+
+#[cfg(test)]
+mod tests;
+
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::export::Article;
+use crate::profile;
+
+/// The maximum length of a mined sentence in characters.
+pub const MAX_SENTENCE: usize = 1000;
+/// The maximum size of the image attached to a card.
+pub const MAX_IMAGE_SIZE: usize = 8 * 1024 * 1024;
+/// The maximum size of all attached images.
+pub const MAX_TOTAL_SIZE: u64 = 512 * 1024 * 1024;
+/// Extension of card descriptions.
+const EXTENSION: &str = "json";
+/// Extension of attached images.
+const IMAGE_EXTENSION: &str = "image";
+
+/// Characters which end a sentence.
+const SENTENCE_ENDS: [char; 7] = ['。', '！', '？', '!', '?', '\n', '…'];
+
+/// Get the sentence in `text` containing the given byte offset, including the
+/// character which ends it.
+pub fn sentence_at(text: &str, at: usize) -> &str {
+    let at = (0..=at.min(text.len()))
+        .rev()
+        .find(|&n| text.is_char_boundary(n))
+        .unwrap_or_default();
+
+    let start = text[..at].rfind(SENTENCE_ENDS).map_or(0, |n| {
+        n + text[n..].chars().next().map_or(0, char::len_utf8)
+    });
+
+    let end = text[at..].find(SENTENCE_ENDS).map_or(text.len(), |n| {
+        let n = at + n;
+        n + text[n..].chars().next().map_or(0, char::len_utf8)
+    });
+
+    text[start..end].trim()
+}
+
+/// A mined card.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Card {
+    pub id: u64,
+    /// The sentence the word was mined from.
+    pub sentence: String,
+    /// Sequence of the phrase the card is for.
+    pub sequence: u64,
+    /// The word, as it's written in the dictionary.
+    pub word: String,
+    /// The reading of the word, if it's written using kanji.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading: Option<String>,
+    /// Meanings of the word, one for each sense.
+    #[serde(default)]
+    pub glosses: Vec<String>,
+    /// The mime type of the attached image, like `image/png`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Size of the attached image in bytes.
+    #[serde(default)]
+    pub image_size: u64,
+    /// Seconds since the unix epoch when the card was mined.
+    pub added: u64,
+    /// The identifier of the note the card was exported to in Anki.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<u64>,
+}
+
+impl Card {
+    /// The range of the word in the sentence.
+    pub fn highlight(&self) -> Option<Range<usize>> {
+        highlight(&self.sentence, &self.word, self.reading.as_deref())
+    }
+}
+
+/// Find the range of the first occurrence of a word, or if it doesn't occur
+/// its reading, in a sentence.
+pub fn highlight(sentence: &str, word: &str, reading: Option<&str>) -> Option<Range<usize>> {
+    [Some(word), reading]
+        .into_iter()
+        .flatten()
+        .filter(|word| !word.is_empty())
+        .find_map(|word| {
+            let at = sentence.find(word)?;
+            Some(at..at + word.len())
+        })
+}
+
+/// A card to be mined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewCard {
+    pub sentence: String,
+    pub sequence: u64,
+    pub word: String,
+    pub reading: Option<String>,
+    pub glosses: Vec<String>,
+}
+
+impl NewCard {
+    /// Assemble a card for the given sentence from an exported article.
+    pub fn new(sentence: &str, article: &Article) -> Self {
+        Self {
+            sentence: sentence.trim().to_owned(),
+            sequence: article.sequence,
+            word: article.headword.clone(),
+            reading: article.reading.clone(),
+            glosses: article
+                .senses
+                .iter()
+                .map(|sense| sense.glosses.join("; "))
+                .collect(),
+        }
+    }
+}
+
+/// An image attached to a card.
+#[derive(Debug, Clone, Copy)]
+pub struct Image<'a> {
+    /// The mime type of the image.
+    pub mime: &'a str,
+    pub data: &'a [u8],
+}
+
+/// Cards stored in a directory.
+pub struct Mining {
+    dir: PathBuf,
+}
+
+impl Mining {
+    /// Access cards stored in the given directory.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            dir: dir.as_ref().to_owned(),
+        }
+    }
+
+    /// List all cards in ascending order of identifier.
+    pub fn list(&self) -> Result<Vec<Card>> {
+        let mut output = Vec::new();
+
+        let d = match fs::read_dir(&self.dir) {
+            Ok(d) => d,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(output);
+            }
+            Err(e) => {
+                return Err(e).context("Could not read mining directory");
+            }
+        };
+
+        for e in d {
+            let path = e?.path();
+
+            if path.extension() != Some(EXTENSION.as_ref()) {
+                continue;
+            }
+
+            let data = fs::read(&path).with_context(|| path.display().to_string())?;
+            let card: Card =
+                serde_json::from_slice(&data).with_context(|| path.display().to_string())?;
+            output.push(card);
+        }
+
+        output.sort_by_key(|c| c.id);
+        Ok(output)
+    }
+
+    /// Add a card, with an optional image of where its sentence was captured
+    /// from.
+    pub fn add(&self, card: NewCard, image: Option<Image<'_>>, added: u64) -> Result<Card> {
+        if card.sentence.is_empty() {
+            bail!("Missing sentence to mine");
+        }
+
+        if card.sentence.chars().count() > MAX_SENTENCE {
+            bail!("Sentence is longer than {MAX_SENTENCE} characters");
+        }
+
+        if let Some(image) = &image {
+            if !image.mime.starts_with("image/") {
+                bail!("Unsupported image type `{}`", image.mime);
+            }
+
+            if image.data.len() > MAX_IMAGE_SIZE {
+                bail!(
+                    "Image is larger than the limit of {} MiB",
+                    MAX_IMAGE_SIZE / 1024 / 1024
+                );
+            }
+        }
+
+        let cards = self.list()?;
+
+        if let Some(image) = &image {
+            let total = cards.iter().map(|c| c.image_size).sum::<u64>();
+
+            if total + image.data.len() as u64 > MAX_TOTAL_SIZE {
+                bail!(
+                    "Images would exceed the limit of {} MiB, delete some cards first",
+                    MAX_TOTAL_SIZE / 1024 / 1024
+                );
+            }
+        }
+
+        let card = Card {
+            id: cards.last().map_or(1, |c| c.id + 1),
+            sentence: card.sentence,
+            sequence: card.sequence,
+            word: card.word,
+            reading: card.reading,
+            glosses: card.glosses,
+            image: image.map(|image| image.mime.to_owned()),
+            image_size: image.map_or(0, |image| image.data.len() as u64),
+            added,
+            note: None,
+        };
+
+        if let Some(image) = image {
+            profile::write(
+                &self.dir,
+                &format!("{}.{IMAGE_EXTENSION}", card.id),
+                image.data,
+            )?;
+        }
+
+        self.save(&card)?;
+        Ok(card)
+    }
+
+    /// Load the given card.
+    pub fn load(&self, id: u64) -> Result<Card> {
+        let path = self.path(id, EXTENSION);
+
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                bail!("Missing card {id}");
+            }
+            Err(e) => {
+                return Err(e).with_context(|| path.display().to_string());
+            }
+        };
+
+        serde_json::from_slice(&data).with_context(|| path.display().to_string())
+    }
+
+    /// The path to the image attached to the given card, if it has one.
+    pub fn image_path(&self, card: &Card) -> Option<PathBuf> {
+        card.image.as_ref()?;
+        Some(self.path(card.id, IMAGE_EXTENSION))
+    }
+
+    /// Load the given card and its image.
+    pub fn image(&self, id: u64) -> Result<(Card, Vec<u8>)> {
+        let card = self.load(id)?;
+
+        let Some(path) = self.image_path(&card) else {
+            bail!("Card {id} has no image");
+        };
+
+        let image = fs::read(&path).with_context(|| path.display().to_string())?;
+        Ok((card, image))
+    }
+
+    /// Record the note the given card was exported to.
+    pub fn set_note(&self, id: u64, note: u64) -> Result<Card> {
+        let mut card = self.load(id)?;
+        card.note = Some(note);
+        self.save(&card)?;
+        Ok(card)
+    }
+
+    /// Delete the given card.
+    pub fn delete(&self, id: u64) -> Result<()> {
+        // Remove the description first, so that a partially deleted card
+        // isn't listed.
+        for extension in [EXTENSION, IMAGE_EXTENSION] {
+            match fs::remove_file(self.path(id, extension)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e).context("Could not remove card"),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn save(&self, card: &Card) -> Result<()> {
+        profile::write(
+            &self.dir,
+            &format!("{}.{EXTENSION}", card.id),
+            &serde_json::to_vec(card)?,
+        )
+    }
+
+    fn path(&self, id: u64, extension: &str) -> PathBuf {
+        self.dir.join(format!("{id}.{extension}"))
+    }
+}
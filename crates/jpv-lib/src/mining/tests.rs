@@ -0,0 +1,114 @@
+use super::{sentence_at, Card, Image, Mining, NewCard, MAX_IMAGE_SIZE, MAX_SENTENCE};
+
+fn new_card(sentence: &str) -> NewCard {
+    NewCard {
+        sentence: sentence.to_owned(),
+        sequence: 1358280,
+        word: "食べる".to_owned(),
+        reading: Some("たべる".to_owned()),
+        glosses: vec!["to eat".to_owned()],
+    }
+}
+
+fn card(sentence: &str, word: &str, reading: Option<&str>) -> Card {
+    Card {
+        id: 1,
+        sentence: sentence.to_owned(),
+        sequence: 1,
+        word: word.to_owned(),
+        reading: reading.map(str::to_owned),
+        glosses: vec!["to eat".to_owned()],
+        image: None,
+        image_size: 0,
+        added: 0,
+        note: None,
+    }
+}
+
+#[test]
+fn store() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    let mining = Mining::new(dir);
+
+    assert!(mining.list().unwrap().is_empty());
+    assert!(mining.add(new_card(""), None, 1).is_err());
+    assert!(mining
+        .add(new_card(&"あ".repeat(MAX_SENTENCE + 1)), None, 1)
+        .is_err());
+
+    let text = Image {
+        mime: "text/plain",
+        data: b"data",
+    };
+
+    assert!(mining.add(new_card("ご飯を食べる"), Some(text), 1).is_err());
+
+    let large = vec![0; MAX_IMAGE_SIZE + 1];
+
+    let large = Image {
+        mime: "image/png",
+        data: &large,
+    };
+
+    assert!(mining
+        .add(new_card("ご飯を食べる"), Some(large), 1)
+        .is_err());
+
+    let first = mining.add(new_card("ご飯を食べる"), None, 10).unwrap();
+
+    let image = Image {
+        mime: "image/png",
+        data: b"png",
+    };
+
+    let second = mining
+        .add(new_card("パンを食べた"), Some(image), 20)
+        .unwrap();
+    assert_eq!((first.id, second.id), (1, 2));
+    assert_eq!(first.image, None);
+    assert_eq!(second.image.as_deref(), Some("image/png"));
+    assert_eq!(second.image_size, 3);
+
+    assert!(mining.image(first.id).is_err());
+    let (card, data) = mining.image(second.id).unwrap();
+    assert_eq!(card, second);
+    assert_eq!(data, b"png");
+
+    let noted = mining.set_note(first.id, 42).unwrap();
+    assert_eq!(noted.note, Some(42));
+    assert_eq!(mining.load(first.id).unwrap(), noted);
+
+    mining.delete(second.id).unwrap();
+    mining.delete(second.id).unwrap();
+    assert!(mining.load(second.id).is_err());
+    assert!(mining.image(second.id).is_err());
+    assert_eq!(mining.list().unwrap(), [noted]);
+}
+
+#[test]
+fn highlight() {
+    let c = card("ご飯を食べる時", "食べる", Some("たべる"));
+    assert_eq!(c.highlight(), Some(9..18));
+
+    let c = card("ご飯をたべる", "食べる", Some("たべる"));
+    assert_eq!(c.highlight(), Some(9..18));
+
+    let c = card("ご飯を食べた", "食べる", Some("たべる"));
+    assert_eq!(c.highlight(), None);
+}
+
+#[test]
+fn sentences() {
+    let text = "雨が降った。傘を持っていない！どうしよう";
+    let second = text.find("傘").unwrap();
+    let third = text.find("ど").unwrap();
+
+    assert_eq!(sentence_at(text, 0), "雨が降った。");
+    assert_eq!(sentence_at(text, second), "傘を持っていない！");
+    assert_eq!(sentence_at(text, second + 1), "傘を持っていない！");
+    assert_eq!(sentence_at(text, third), "どうしよう");
+    assert_eq!(sentence_at(text, text.len()), "どうしよう");
+    assert_eq!(sentence_at(" 一行目\n二行目", 1), "一行目");
+    assert_eq!(sentence_at("", 0), "");
+}
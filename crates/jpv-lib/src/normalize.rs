@@ -0,0 +1,130 @@
+//! Normalization of text before it's analyzed.
+//!
+//! Text captured from games and other applications is often written with
+//! full-width ASCII, half-width katakana, wave dashes or rules in place of
+//! long vowel marks, and decorative symbols like `♪` sprinkled in between
+//! words, none of which the dictionary can be looked up with as written.
+//!
+//! [`Normalized`] maps such text to how the dictionary writes it, while
+//! keeping track of where each character of it comes from so that whatever
+//! is found can still be pointed out in the original text.
+
+#[cfg(test)]
+mod tests;
+
+use core::iter;
+use core::ops::Range;
+
+/// Half-width katakana and punctuation from `U+FF61` to `U+FF9F`, mapped to
+/// their full-width forms.
+const HALF_WIDTH: &str = "。「」、・ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン゛゜";
+
+/// Marks which stand in for a long vowel mark when they follow kana, like
+/// in `すご〜い` or `あ――`.
+const LONG_VOWEL_MARKS: &[char] = &['〜', '〰', '～', '―', '─', '━'];
+
+/// Symbols which only decorate text, and are left out of it.
+const DECORATIONS: &[char] = &[
+    '♪', '♫', '♬', '♩', '☆', '★', '♡', '♥', '❤', '◇', '◆', '○', '●', '◎', '□', '■', '△', '▲', '▽',
+    '▼',
+];
+
+/// Text normalized for lookups.
+///
+/// Byte offsets into the normalized text are mapped back to the original
+/// text with [`Normalized::original`].
+#[derive(Debug, Clone)]
+pub struct Normalized {
+    text: String,
+    /// The range of the original text which each byte of the normalized text
+    /// comes from.
+    sources: Vec<Range<usize>>,
+    /// The length of the original text.
+    len: usize,
+}
+
+impl Normalized {
+    /// Normalize the given text.
+    pub fn new(input: &str) -> Self {
+        let mut text = String::with_capacity(input.len());
+        let mut sources = Vec::with_capacity(input.len());
+        let mut it = input.char_indices().peekable();
+
+        while let Some((start, c)) = it.next() {
+            let mut end = start + c.len_utf8();
+
+            let c = match c {
+                c if DECORATIONS.contains(&c) => continue,
+                c if LONG_VOWEL_MARKS.contains(&c) && text.ends_with(is_kana) => 'ー',
+                '\u{3000}' => ' ',
+                '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+                '\u{FF61}'..='\u{FF9F}' => {
+                    let index = (c as u32 - 0xFF61) as usize;
+                    let mut c = HALF_WIDTH.chars().nth(index).unwrap_or(c);
+
+                    // Voiced sound marks are separate characters in half-width
+                    // katakana, like in `ｶﾞ`.
+                    if let Some(&(_, mark @ ('\u{FF9E}' | '\u{FF9F}'))) = it.peek() {
+                        if let Some(voiced) = voiced(c, mark == '\u{FF9F}') {
+                            it.next();
+                            end += mark.len_utf8();
+                            c = voiced;
+                        }
+                    }
+
+                    c
+                }
+                c => c,
+            };
+
+            text.push(c);
+            sources.extend(iter::repeat(start..end).take(c.len_utf8()));
+        }
+
+        Self {
+            text,
+            sources,
+            len: input.len(),
+        }
+    }
+
+    /// The normalized text.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Map a byte range of the normalized text to the range of the original
+    /// text it comes from.
+    ///
+    /// Characters left out of the normalized text are not part of the range
+    /// unless they're inside of it.
+    pub fn original(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.sources.get(range.start).map_or(self.len, |s| s.start);
+
+        if range.end <= range.start {
+            return start..start;
+        }
+
+        let end = self.sources.get(range.end - 1).map_or(self.len, |s| s.end);
+        start..end
+    }
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c, 'ぁ'..='ゖ' | 'ァ'..='ヺ' | 'ー')
+}
+
+/// The voiced form of a full-width katakana, or the semi-voiced form if
+/// `semi` is set.
+fn voiced(c: char, semi: bool) -> Option<char> {
+    let offset = match c {
+        'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' if semi => 2,
+        _ if semi => return None,
+        'ウ' => return Some('ヴ'),
+        'カ' | 'キ' | 'ク' | 'ケ' | 'コ' | 'サ' | 'シ' | 'ス' | 'セ' | 'ソ' | 'タ' | 'チ'
+        | 'ツ' | 'テ' | 'ト' | 'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ' => 1,
+        _ => return None,
+    };
+
+    char::from_u32(c as u32 + offset)
+}
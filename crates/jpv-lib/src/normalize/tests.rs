@@ -0,0 +1,49 @@
+use super::Normalized;
+
+#[test]
+fn unchanged() {
+    let n = Normalized::new("今日は食べる");
+    assert_eq!(n.as_str(), "今日は食べる");
+    assert_eq!(n.original(3..9), 3..9);
+}
+
+#[test]
+fn full_width() {
+    let n = Normalized::new("ＣＤ１２！\u{3000}です");
+    assert_eq!(n.as_str(), "CD12! です");
+    // `CD` comes from six bytes.
+    assert_eq!(n.original(0..2), 0..6);
+    assert_eq!(n.original(6..12), 18..24);
+}
+
+#[test]
+fn half_width() {
+    let n = Normalized::new("ｶﾞｯｺｰ｡ﾊﾟﾝﾞ");
+    assert_eq!(n.as_str(), "ガッコー。パン゛");
+
+    // `ガ` comes from both `ｶ` and `ﾞ`.
+    assert_eq!(n.original(0..3), 0..6);
+    assert_eq!(n.original(0..12), 0..15);
+}
+
+#[test]
+fn long_vowels() {
+    assert_eq!(Normalized::new("すご〜い").as_str(), "すごーい");
+    assert_eq!(Normalized::new("えっ～").as_str(), "えっー");
+    assert_eq!(Normalized::new("あ――").as_str(), "あーー");
+
+    // Only after kana.
+    assert_eq!(Normalized::new("10〜20").as_str(), "10〜20");
+    assert_eq!(Normalized::new("～").as_str(), "~");
+}
+
+#[test]
+fn decorations() {
+    let n = Normalized::new("★光る♪です");
+    assert_eq!(n.as_str(), "光るです");
+
+    assert_eq!(n.original(0..6), 3..9);
+    // Decorations inside of a range are part of it.
+    assert_eq!(n.original(0..12), 3..18);
+    assert_eq!(n.original(12..12), 18..18);
+}
@@ -0,0 +1,136 @@
+//! Text recognized in images.
+//!
+//! OCR engines report what they recognize as blocks of text, like the speech
+//! bubbles of a manga page, each with how confident the engine is in it if it
+//! can tell.
+//!
+//! External engines, see [`crate::config::OcrEngine`], either write the
+//! recognized text as it is, which is treated as a single block, or a JSON
+//! object describing each block:
+//!
+//! ```json
+//! {"blocks": [{"text": "吾輩は猫である", "confidence": 0.93}]}
+//! ```
+//!
+//! What is recognized in an image is kept in a [`Cache`], which corrections
+//! made by the user are also fed into.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+/// A block of recognized text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    pub text: String,
+    /// How confident the engine is in the text, from `0.0` to `1.0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+}
+
+/// Text recognized in an image.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recognized {
+    pub blocks: Vec<Block>,
+}
+
+impl Recognized {
+    /// Recognized text as a single block.
+    pub fn text(text: String, confidence: Option<f32>) -> Self {
+        Self {
+            blocks: vec![Block { text, confidence }],
+        }
+    }
+
+    /// Parse the output of an external engine.
+    ///
+    /// Blocks without text are left out, and confidences are clamped to the
+    /// range they're expected to be in.
+    pub fn parse(output: &str) -> Self {
+        let output = output.trim();
+
+        let mut this = if output.starts_with('{') {
+            match serde_json::from_str::<Self>(output) {
+                Ok(this) => this,
+                Err(..) => Self::text(output.to_owned(), None),
+            }
+        } else {
+            Self::text(output.to_owned(), None)
+        };
+
+        this.blocks.retain(|block| !block.text.trim().is_empty());
+
+        for block in &mut this.blocks {
+            block.confidence = block
+                .confidence
+                .filter(|confidence| confidence.is_finite())
+                .map(|confidence| confidence.clamp(0.0, 1.0));
+        }
+
+        this
+    }
+
+    /// The text of all blocks, one block per line.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+
+        for block in &self.blocks {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+
+            text.push_str(block.text.trim());
+        }
+
+        text
+    }
+}
+
+/// A bounded cache of text recognized in images, keyed by a hash of the image.
+///
+/// When the cache is full the oldest entry is evicted.
+pub struct Cache {
+    capacity: usize,
+    entries: HashMap<u64, Recognized>,
+    order: VecDeque<u64>,
+}
+
+impl Cache {
+    /// Construct an empty cache holding at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Get the text recognized in the image with the given key.
+    pub fn get(&self, key: u64) -> Option<&Recognized> {
+        self.entries.get(&key)
+    }
+
+    /// Insert the text recognized in the image with the given key.
+    pub fn insert(&mut self, key: u64, recognized: Recognized) {
+        if self.entries.insert(key, recognized).is_some() {
+            return;
+        }
+
+        self.order.push_back(key);
+
+        while self.order.len() > self.capacity {
+            if let Some(key) = self.order.pop_front() {
+                self.entries.remove(&key);
+            }
+        }
+    }
+
+    /// Correct the text recognized in the image with the given key, which is
+    /// then trusted fully.
+    pub fn correct(&mut self, key: u64, text: String) {
+        self.insert(key, Recognized::text(text, Some(1.0)));
+    }
+}
@@ -0,0 +1,67 @@
+use super::{Block, Cache, Recognized};
+
+fn block(text: &str, confidence: Option<f32>) -> Block {
+    Block {
+        text: text.to_owned(),
+        confidence,
+    }
+}
+
+#[test]
+fn plain_text() {
+    let recognized = Recognized::parse("  吾輩は猫である\n");
+    assert_eq!(recognized.blocks, [block("吾輩は猫である", None)]);
+
+    assert!(Recognized::parse(" \n").blocks.is_empty());
+
+    // Output which only looks like JSON is text.
+    let recognized = Recognized::parse("{猫}");
+    assert_eq!(recognized.blocks, [block("{猫}", None)]);
+}
+
+#[test]
+fn json_blocks() {
+    let recognized = Recognized::parse(
+        r#"{"blocks": [{"text": "吾輩は", "confidence": 0.9}, {"text": " "}, {"text": "猫", "confidence": 1.5}, {"text": "である"}]}"#,
+    );
+
+    assert_eq!(
+        recognized.blocks,
+        [
+            block("吾輩は", Some(0.9)),
+            block("猫", Some(1.0)),
+            block("である", None)
+        ]
+    );
+
+    assert_eq!(recognized.to_text(), "吾輩は\n猫\nである");
+}
+
+#[test]
+fn cache_evicts_oldest() {
+    let mut cache = Cache::new(2);
+    cache.insert(1, Recognized::text("一".to_owned(), None));
+    cache.insert(2, Recognized::text("二".to_owned(), None));
+    // Replacing an entry doesn't make it newer.
+    cache.insert(1, Recognized::text("壱".to_owned(), None));
+    cache.insert(3, Recognized::text("三".to_owned(), None));
+
+    assert!(cache.get(1).is_none());
+    assert_eq!(cache.get(2).map(Recognized::to_text).as_deref(), Some("二"));
+    assert_eq!(cache.get(3).map(Recognized::to_text).as_deref(), Some("三"));
+}
+
+#[test]
+fn cache_correction() {
+    let mut cache = Cache::new(2);
+    cache.insert(
+        1,
+        Recognized::parse(r#"{"blocks": [{"text": "吾輩は描", "confidence": 0.3}]}"#),
+    );
+    cache.correct(1, "吾輩は猫".to_owned());
+
+    assert_eq!(
+        cache.get(1).map(|recognized| &recognized.blocks[..]),
+        Some(&[block("吾輩は猫", Some(1.0))][..])
+    );
+}
@@ -1,7 +1,16 @@
 use musli::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Encode, Decode)]
+// Weights of the first level of each ranking list, see `Priority::weight`.
+const ICHI_WEIGHT: f32 = 4.0;
+const NEWS_WEIGHT: f32 = 2.0;
+const GAI_WEIGHT: f32 = 2.0;
+const SPEC_WEIGHT: f32 = 4.4;
+const FREQUENCY_WEIGHT: f32 = 4.0;
+/// Bonus for each ranking list beyond the first that a word appears in.
+const LIST_BONUS: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
 #[serde(rename_all = "kebab-case")]
 pub enum PriorityKind {
     /// Common words.
@@ -17,7 +26,7 @@ pub enum PriorityKind {
     WordFrequency,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Encode, Decode)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
 #[musli(packed)]
 pub struct Priority {
     level: u8,
@@ -70,23 +79,72 @@ impl Priority {
         }
     }
 
+    /// Estimate the frequency rank of a word with this priority, where 1 is
+    /// the most frequent word.
+    ///
+    /// Word frequency categories cover 500 words each, while the first level
+    /// of the other lists roughly covers the 12000 most common words and the
+    /// second level the 12000 after that.
+    pub(crate) fn frequency_rank(&self) -> usize {
+        match self.kind {
+            PriorityKind::WordFrequency => self.level() * 500,
+            _ if self.level <= 1 => 6000,
+            _ => 18000,
+        }
+    }
+
+    /// Test if this is a word frequency category.
+    pub(crate) fn is_frequency(&self) -> bool {
+        matches!(self.kind, PriorityKind::WordFrequency)
+    }
+
     /// Weight for these priorities.
+    ///
+    /// The first level of each list has the following weight, while the
+    /// second level has three quarters of it. Word frequency categories go
+    /// from `nf01` to `nf48` and approach half of the weight linearly with
+    /// each category.
+    ///
+    /// | Tag    | Weight |
+    /// |--------|--------|
+    /// | `spec` | 4.4    |
+    /// | `ichi` | 4.0    |
+    /// | `nf`   | 4.0    |
+    /// | `news` | 2.0    |
+    /// | `gai`  | 2.0    |
     pub(crate) fn weight(&self) -> f32 {
         let level = self.level.saturating_sub(1) as f32;
 
-        // Calculate the range-based priority.
-        macro_rules! range {
-            ($max:expr) => {
-                1.0 + ($max - level.min($max)) / $max
-            };
-        }
+        // Scale the weight of the first level by how far down the levels of
+        // the list this priority is.
+        let range = |weight: f32, max: f32| weight / 2.0 * (1.0 + (max - level.min(max)) / max);
 
         match self.kind {
-            PriorityKind::Ichi => range!(2.0) * 2.0,
-            PriorityKind::News => range!(2.0),
-            PriorityKind::Gai => range!(2.0),
-            PriorityKind::Spec => range!(2.0) * 2.2,
-            PriorityKind::WordFrequency => range!(50.0) * 2.0,
+            PriorityKind::Ichi => range(ICHI_WEIGHT, 2.0),
+            PriorityKind::News => range(NEWS_WEIGHT, 2.0),
+            PriorityKind::Gai => range(GAI_WEIGHT, 2.0),
+            PriorityKind::Spec => range(SPEC_WEIGHT, 2.0),
+            PriorityKind::WordFrequency => range(FREQUENCY_WEIGHT, 50.0),
         }
     }
 }
+
+/// The bonus for a word which appears in several of the `ichi`, `news`,
+/// `spec` and `gai` ranking lists, since such words are more common than words
+/// which only appear in one of them.
+///
+/// Each list beyond the first adds a bonus of `0.1` to the weight of the word.
+pub(crate) fn list_bonus<'a, I>(priorities: I) -> f32
+where
+    I: IntoIterator<Item = &'a Priority>,
+{
+    let mut kinds = Vec::new();
+
+    for p in priorities {
+        if !p.is_frequency() && !kinds.contains(&p.kind) {
+            kinds.push(p.kind);
+        }
+    }
+
+    kinds.len().saturating_sub(1) as f32 * LIST_BONUS
+}
@@ -0,0 +1,847 @@
+//! Per-user state, stored in named profiles.
+//!
+//! Each profile is stored as a JSON file in the profiles directory, which
+//! also keeps track of which profile is currently selected. This allows
+//! multiple learners sharing a machine to keep their history, word lists and
+//! review state apart.
+
+#[cfg(test)]
+mod tests;
+
+pub mod known;
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::database::NameWeighting;
+use crate::query::{Resolver, Term};
+
+/// The profile which is used if no other profile has been selected.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The maximum number of history entries kept in a profile.
+const MAX_HISTORY: usize = 1000;
+/// The initial ease factor of a review card, in thousandths.
+const INITIAL_EASE: u32 = 2500;
+/// The lowest ease factor of a review card, in thousandths.
+const MINIMUM_EASE: u32 = 1300;
+/// How much the ease factor is lowered when a word is forgotten.
+const LAPSE_EASE: u32 = 200;
+/// The maximum length of a profile name.
+const MAX_NAME: usize = 64;
+/// The file keeping track of the currently selected profile.
+const CURRENT: &str = "current";
+/// Extension of profile files.
+const EXTENSION: &str = "json";
+/// Setting for how names are weighed when analyzing text.
+const NAMES_SETTING: &str = "names";
+/// Setting for how readings are shown above kanji.
+const READINGS_SETTING: &str = "readings";
+/// Setting for how densely entries and characters are shown.
+const DENSITY_SETTING: &str = "density";
+/// Setting for how much Japanese text is scaled, in percent.
+const TEXT_SCALE_SETTING: &str = "text-scale";
+/// The scale of Japanese text unless another one has been set, in percent.
+pub const DEFAULT_TEXT_SCALE: u32 = 100;
+/// The smallest and largest scales of Japanese text, in percent.
+const TEXT_SCALE_RANGE: (u32, u32) = (75, 300);
+
+/// The state of a single profile.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Things which have been looked up, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<HistoryEntry>,
+    /// Named word lists.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lists: BTreeMap<String, WordList>,
+    /// Review state of words, by sequence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub srs: BTreeMap<u64, SrsCard>,
+    /// Words which are known, by sequence, typically imported from elsewhere.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub known: BTreeSet<u64>,
+    /// Words which have been marked as being learned, by sequence.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub learning: BTreeSet<u64>,
+    /// Seconds since the unix epoch when the status of a word was last set,
+    /// by sequence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub statuses: BTreeMap<u64, u64>,
+    /// Personal notes and tags attached to phrases, by sequence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<u64, Annotation>,
+    /// Settings of the profile.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub settings: BTreeMap<String, String>,
+    /// When things were removed from the profile.
+    #[serde(default, skip_serializing_if = "Removed::is_empty")]
+    pub removed: Removed,
+}
+
+impl Profile {
+    /// How names are weighed when analyzing text.
+    pub fn name_weighting(&self) -> NameWeighting {
+        self.settings
+            .get(NAMES_SETTING)
+            .and_then(|ident| NameWeighting::parse(ident))
+            .unwrap_or_default()
+    }
+
+    /// Set how names are weighed when analyzing text.
+    pub fn set_name_weighting(&mut self, names: NameWeighting) {
+        self.settings
+            .insert(NAMES_SETTING.to_owned(), names.ident().to_owned());
+    }
+
+    /// How readings are shown above kanji.
+    pub fn readings(&self) -> Readings {
+        self.settings
+            .get(READINGS_SETTING)
+            .and_then(|ident| Readings::parse(ident))
+            .unwrap_or_default()
+    }
+
+    /// Set how readings are shown above kanji.
+    pub fn set_readings(&mut self, readings: Readings) {
+        self.settings
+            .insert(READINGS_SETTING.to_owned(), readings.ident().to_owned());
+    }
+
+    /// How densely entries and characters are shown.
+    pub fn density(&self) -> Density {
+        self.settings
+            .get(DENSITY_SETTING)
+            .and_then(|ident| Density::parse(ident))
+            .unwrap_or_default()
+    }
+
+    /// Set how densely entries and characters are shown.
+    pub fn set_density(&mut self, density: Density) {
+        self.settings
+            .insert(DENSITY_SETTING.to_owned(), density.ident().to_owned());
+    }
+
+    /// How much Japanese text is scaled, in percent.
+    pub fn text_scale(&self) -> u32 {
+        let (min, max) = TEXT_SCALE_RANGE;
+
+        self.settings
+            .get(TEXT_SCALE_SETTING)
+            .and_then(|scale| scale.parse::<u32>().ok())
+            .map_or(DEFAULT_TEXT_SCALE, |scale| scale.clamp(min, max))
+    }
+
+    /// Set how much Japanese text is scaled, in percent. The scale is clamped
+    /// to what can reasonably be shown.
+    pub fn set_text_scale(&mut self, scale: u32) {
+        let (min, max) = TEXT_SCALE_RANGE;
+
+        self.settings.insert(
+            TEXT_SCALE_SETTING.to_owned(),
+            scale.clamp(min, max).to_string(),
+        );
+    }
+
+    /// Add the given text to the history, moving it to the end if it's
+    /// already present.
+    pub fn add_history(&mut self, text: &str, timestamp: u64) {
+        self.history.retain(|e| e.text != text);
+
+        self.history.push(HistoryEntry {
+            text: text.to_owned(),
+            timestamp,
+        });
+
+        if let Some(n) = self.history.len().checked_sub(MAX_HISTORY) {
+            self.history.drain(..n);
+        }
+    }
+
+    /// Remove the given text from the history.
+    pub fn remove_history(&mut self, text: &str, removed: u64) {
+        self.history.retain(|e| e.text != text);
+        self.removed.history.insert(text.to_owned(), removed);
+    }
+
+    /// Remove the word with the given sequence from the named word list.
+    pub fn remove_from_list(&mut self, name: &str, sequence: u64, removed: u64) {
+        if let Some(list) = self.lists.get_mut(name) {
+            list.entries.retain(|e| e.sequence != sequence);
+        }
+
+        self.removed
+            .lists
+            .entry(name.to_owned())
+            .or_default()
+            .insert(sequence, removed);
+    }
+
+    /// Test if the word with the given sequence is known or being learned.
+    pub fn is_known(&self, sequence: u64) -> bool {
+        self.word_status(sequence) != WordStatus::Unknown
+    }
+
+    /// Get the status of the word with the given sequence.
+    ///
+    /// Words which are being reviewed or have been added to any word list are
+    /// being learned unless they have been marked as known.
+    pub fn word_status(&self, sequence: u64) -> WordStatus {
+        if self.known.contains(&sequence) {
+            return WordStatus::Known;
+        }
+
+        if self.learning.contains(&sequence)
+            || self.srs.contains_key(&sequence)
+            || self
+                .lists
+                .values()
+                .any(|list| list.entries.iter().any(|e| e.sequence == sequence))
+        {
+            return WordStatus::Learning;
+        }
+
+        WordStatus::Unknown
+    }
+
+    /// Mark the word with the given sequence as having the given status.
+    pub fn set_word_status(&mut self, sequence: u64, status: WordStatus, modified: u64) {
+        self.statuses.insert(sequence, modified);
+        self.known.remove(&sequence);
+        self.learning.remove(&sequence);
+
+        match status {
+            WordStatus::Unknown => {}
+            WordStatus::Learning => {
+                self.learning.insert(sequence);
+            }
+            WordStatus::Known => {
+                self.known.insert(sequence);
+            }
+        }
+    }
+
+    /// Record that the word with the given sequence was forgotten, such as
+    /// when a practice question about it was answered incorrectly.
+    ///
+    /// The word is scheduled for review immediately, adding it for review if
+    /// it isn't already.
+    pub fn lapse(&mut self, sequence: u64, now: u64) -> &SrsCard {
+        let card = self.srs.entry(sequence).or_insert(SrsCard {
+            due: now,
+            interval: 0,
+            ease: INITIAL_EASE,
+            repetitions: 0,
+            modified: now,
+        });
+
+        if card.repetitions > 0 || card.interval > 0 {
+            card.ease = card.ease.saturating_sub(LAPSE_EASE).max(MINIMUM_EASE);
+        }
+
+        card.due = now;
+        card.interval = 0;
+        card.repetitions = 0;
+        card.modified = now;
+        card
+    }
+
+    /// Set the note and tags of the phrase with the given sequence, removing
+    /// the annotation if both are empty.
+    ///
+    /// Tags are trimmed and deduplicated ignoring case, and a leading `#` is
+    /// removed.
+    pub fn annotate(
+        &mut self,
+        sequence: u64,
+        note: &str,
+        tags: &[String],
+        modified: u64,
+    ) -> Option<&Annotation> {
+        let mut normalized = Vec::<String>::new();
+
+        for tag in tags {
+            let tag = tag.trim().trim_start_matches('#').trim();
+
+            if !tag.is_empty()
+                && !normalized
+                    .iter()
+                    .any(|t| t.to_lowercase() == tag.to_lowercase())
+            {
+                normalized.push(tag.to_owned());
+            }
+        }
+
+        let note = note.trim();
+
+        if note.is_empty() && normalized.is_empty() {
+            self.annotations.remove(&sequence);
+            self.removed.annotations.insert(sequence, modified);
+            return None;
+        }
+
+        self.annotations.insert(
+            sequence,
+            Annotation {
+                note: note.to_owned(),
+                tags: normalized,
+                modified,
+            },
+        );
+
+        self.annotations.get(&sequence)
+    }
+
+    /// Merge another copy of this profile into this one.
+    ///
+    /// History, word lists, word statuses and annotations are combined, where
+    /// whichever copy changed an entry most recently wins, including by
+    /// removing it. History keeps the latest time an entry was looked up, and
+    /// word lists the earliest time a word was added. Review state is taken
+    /// from whichever copy was modified most recently. Settings which are set
+    /// locally are kept.
+    pub fn merge(&mut self, other: Profile) {
+        self.removed.merge(other.removed);
+        let removed = &self.removed;
+
+        let mut history = HashMap::new();
+
+        for e in self.history.drain(..).chain(other.history) {
+            if removed
+                .history
+                .get(&e.text)
+                .is_some_and(|&r| r >= e.timestamp)
+            {
+                continue;
+            }
+
+            let timestamp = history.entry(e.text).or_insert(e.timestamp);
+            *timestamp = (*timestamp).max(e.timestamp);
+        }
+
+        self.history = history
+            .into_iter()
+            .map(|(text, timestamp)| HistoryEntry { text, timestamp })
+            .collect();
+        self.history
+            .sort_by(|a, b| (a.timestamp, &a.text).cmp(&(b.timestamp, &b.text)));
+
+        if let Some(n) = self.history.len().checked_sub(MAX_HISTORY) {
+            self.history.drain(..n);
+        }
+
+        // Entries are compared against removals before they're combined,
+        // since a word added again after being removed is only newer than the
+        // removal in the copy it was added again in.
+        for (name, list) in &mut self.lists {
+            let removed = removed.lists.get(name);
+            list.entries
+                .retain(|e| !removed.is_some_and(|r| r.get(&e.sequence) >= Some(&e.added)));
+        }
+
+        for (name, list) in other.lists {
+            let removed = removed.lists.get(&name);
+            let local = self.lists.entry(name).or_default();
+
+            for e in list.entries {
+                if removed.is_some_and(|r| r.get(&e.sequence) >= Some(&e.added)) {
+                    continue;
+                }
+
+                match local.entries.iter_mut().find(|l| l.sequence == e.sequence) {
+                    Some(l) => {
+                        l.added = l.added.min(e.added);
+                    }
+                    None => {
+                        local.entries.push(e);
+                    }
+                }
+            }
+
+            local.entries.sort_by_key(|e| (e.added, e.sequence));
+        }
+
+        for (sequence, card) in other.srs {
+            match self.srs.get(&sequence) {
+                Some(local) if local.modified >= card.modified => {}
+                _ => {
+                    self.srs.insert(sequence, card);
+                }
+            }
+        }
+
+        let sequences = other
+            .known
+            .iter()
+            .chain(&other.learning)
+            .chain(other.statuses.keys())
+            .copied()
+            .collect::<BTreeSet<_>>();
+
+        for sequence in sequences {
+            let modified = other.statuses.get(&sequence);
+
+            match modified.cmp(&self.statuses.get(&sequence)) {
+                // Words whose status was set in neither copy, like known
+                // words imported before statuses were kept track of, are
+                // combined.
+                Ordering::Equal => {
+                    if other.known.contains(&sequence) {
+                        self.known.insert(sequence);
+                    }
+
+                    if other.learning.contains(&sequence) {
+                        self.learning.insert(sequence);
+                    }
+                }
+                Ordering::Greater => {
+                    self.known.remove(&sequence);
+                    self.learning.remove(&sequence);
+
+                    if other.known.contains(&sequence) {
+                        self.known.insert(sequence);
+                    } else if other.learning.contains(&sequence) {
+                        self.learning.insert(sequence);
+                    }
+
+                    if let Some(&modified) = modified {
+                        self.statuses.insert(sequence, modified);
+                    }
+                }
+                Ordering::Less => {}
+            }
+        }
+
+        self.learning
+            .retain(|sequence| !self.known.contains(sequence));
+
+        for (sequence, annotation) in other.annotations {
+            match self.annotations.get(&sequence) {
+                Some(local) if local.modified >= annotation.modified => {}
+                _ => {
+                    self.annotations.insert(sequence, annotation);
+                }
+            }
+        }
+
+        let removed = &self.removed.annotations;
+        self.annotations
+            .retain(|sequence, a| removed.get(sequence).map_or(true, |&r| a.modified > r));
+
+        for (key, value) in other.settings {
+            self.settings.entry(key).or_insert(value);
+        }
+    }
+}
+
+/// Resolves notes and tags in queries against the annotations in the profile.
+impl Resolver for Profile {
+    fn resolve(&self, term: &Term<'_>) -> Vec<u64> {
+        let annotated = |f: &dyn Fn(&Annotation) -> bool| {
+            self.annotations
+                .iter()
+                .filter(|(_, a)| f(a))
+                .map(|(&sequence, _)| sequence)
+                .collect()
+        };
+
+        match term {
+            Term::Note(text) => {
+                let text = text.to_lowercase();
+                annotated(&|a| a.note.to_lowercase().contains(&text))
+            }
+            Term::Tag(tag) => {
+                let tag = tag.trim_start_matches('#').to_lowercase();
+                annotated(&|a| a.tags.iter().any(|t| t.to_lowercase() == tag))
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A personal note and tags attached to a phrase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Seconds since the unix epoch when the annotation was last modified.
+    pub modified: u64,
+}
+
+/// How well a word is known.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WordStatus {
+    #[default]
+    Unknown,
+    Learning,
+    Known,
+}
+
+impl WordStatus {
+    /// All word statuses.
+    pub const ALL: [WordStatus; 3] = [WordStatus::Unknown, WordStatus::Learning, WordStatus::Known];
+
+    /// A stable identifier for the status.
+    pub fn ident(&self) -> &'static str {
+        match self {
+            WordStatus::Unknown => "unknown",
+            WordStatus::Learning => "learning",
+            WordStatus::Known => "known",
+        }
+    }
+
+    /// A human readable title for the status.
+    pub fn title(&self) -> &'static str {
+        match self {
+            WordStatus::Unknown => "Unknown",
+            WordStatus::Learning => "Learning",
+            WordStatus::Known => "Known",
+        }
+    }
+}
+
+/// How readings, or furigana, are shown above kanji.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Readings {
+    /// Readings are always shown.
+    #[default]
+    Always,
+    /// Readings are shown while hovering over the word they belong to.
+    Hover,
+    /// Readings are never shown.
+    Never,
+}
+
+impl Readings {
+    /// All ways of showing readings.
+    pub const ALL: [Readings; 3] = [Readings::Always, Readings::Hover, Readings::Never];
+
+    /// A stable identifier for the setting.
+    pub fn ident(&self) -> &'static str {
+        match self {
+            Readings::Always => "always",
+            Readings::Hover => "hover",
+            Readings::Never => "never",
+        }
+    }
+
+    /// A human readable title for the setting.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Readings::Always => "Always",
+            Readings::Hover => "On hover",
+            Readings::Never => "Never",
+        }
+    }
+
+    /// Parse the setting from its identifier.
+    pub fn parse(ident: &str) -> Option<Self> {
+        match ident {
+            "always" => Some(Readings::Always),
+            "hover" => Some(Readings::Hover),
+            "never" => Some(Readings::Never),
+            _ => None,
+        }
+    }
+}
+
+/// How densely entries and characters are shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Density {
+    /// Everything is shown.
+    #[default]
+    Normal,
+    /// Only the first gloss of each sense and meaning of each character is
+    /// shown, with less space between them.
+    Compact,
+}
+
+impl Density {
+    /// All densities.
+    pub const ALL: [Density; 2] = [Density::Normal, Density::Compact];
+
+    /// A stable identifier for the density.
+    pub fn ident(&self) -> &'static str {
+        match self {
+            Density::Normal => "normal",
+            Density::Compact => "compact",
+        }
+    }
+
+    /// A human readable title for the density.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Density::Normal => "Normal",
+            Density::Compact => "Compact",
+        }
+    }
+
+    /// Parse a density from its identifier.
+    pub fn parse(ident: &str) -> Option<Self> {
+        match ident {
+            "normal" => Some(Density::Normal),
+            "compact" => Some(Density::Compact),
+            _ => None,
+        }
+    }
+}
+
+/// Seconds since the unix epoch when things were removed from a profile, so
+/// that merging in a copy which still has them doesn't bring them back.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Removed {
+    /// History entries, by text.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub history: BTreeMap<String, u64>,
+    /// Words removed from word lists, by the name of the list and sequence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lists: BTreeMap<String, BTreeMap<u64, u64>>,
+    /// Annotations, by sequence.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<u64, u64>,
+}
+
+impl Removed {
+    fn is_empty(&self) -> bool {
+        self.history.is_empty() && self.lists.is_empty() && self.annotations.is_empty()
+    }
+
+    /// Combine removals, keeping the latest time something was removed.
+    fn merge(&mut self, other: Removed) {
+        fn latest<K>(to: &mut BTreeMap<K, u64>, from: BTreeMap<K, u64>)
+        where
+            K: Ord,
+        {
+            for (key, removed) in from {
+                let current = to.entry(key).or_insert(removed);
+                *current = (*current).max(removed);
+            }
+        }
+
+        latest(&mut self.history, other.history);
+        latest(&mut self.annotations, other.annotations);
+
+        for (name, removed) in other.lists {
+            latest(self.lists.entry(name).or_default(), removed);
+        }
+    }
+}
+
+/// Something which has been looked up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub text: String,
+    /// Seconds since the unix epoch when the entry was added.
+    pub timestamp: u64,
+}
+
+/// A list of words.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordList {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<ListEntry>,
+}
+
+/// A word in a list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListEntry {
+    pub sequence: u64,
+    /// Seconds since the unix epoch when the word was added.
+    pub added: u64,
+}
+
+/// Spaced repetition state of a word.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SrsCard {
+    /// Seconds since the unix epoch when the word is next due for review.
+    pub due: u64,
+    /// The current review interval in days.
+    pub interval: u32,
+    /// The ease factor in thousandths.
+    pub ease: u32,
+    /// The number of successful reviews in a row.
+    pub repetitions: u32,
+    /// Seconds since the unix epoch when the card was last modified.
+    pub modified: u64,
+}
+
+/// Test if the given string is a valid profile name.
+///
+/// Names are used as file names, so they are limited to ASCII alphanumerics,
+/// `-` and `_`.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_'))
+}
+
+/// Profiles stored in a directory.
+pub struct Profiles {
+    dir: PathBuf,
+}
+
+impl Profiles {
+    /// Access profiles stored in the given directory.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            dir: dir.as_ref().to_owned(),
+        }
+    }
+
+    /// List the names of all profiles, which always includes the default
+    /// profile.
+    pub fn list(&self) -> Result<Vec<String>> {
+        let mut names = vec![DEFAULT_PROFILE.to_owned()];
+
+        let d = match fs::read_dir(&self.dir) {
+            Ok(d) => d,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(names);
+            }
+            Err(e) => {
+                return Err(e).context("Could not read profiles directory");
+            }
+        };
+
+        for e in d {
+            let path = e?.path();
+
+            if path.extension() != Some(EXTENSION.as_ref()) {
+                continue;
+            }
+
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            if is_valid_name(name) && name != DEFAULT_PROFILE {
+                names.push(name.to_owned());
+            }
+        }
+
+        names[1..].sort();
+        Ok(names)
+    }
+
+    /// Get the name of the currently selected profile.
+    pub fn current(&self) -> Result<String> {
+        let name = match fs::read_to_string(self.dir.join(CURRENT)) {
+            Ok(name) => name,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(DEFAULT_PROFILE.to_owned());
+            }
+            Err(e) => {
+                return Err(e).context("Could not read current profile");
+            }
+        };
+
+        let name = name.trim();
+
+        if !is_valid_name(name) {
+            return Ok(DEFAULT_PROFILE.to_owned());
+        }
+
+        Ok(name.to_owned())
+    }
+
+    /// Select the given profile, creating it if it doesn't exist.
+    pub fn select(&self, name: &str) -> Result<()> {
+        if !is_valid_name(name) {
+            bail!("Invalid profile name `{name}`");
+        }
+
+        if !self.path(name).exists() {
+            self.save(name, &Profile::default())?;
+        }
+
+        write(&self.dir, CURRENT, name.as_bytes())
+    }
+
+    /// Load the given profile, which is empty if it hasn't been saved yet.
+    pub fn load(&self, name: &str) -> Result<Profile> {
+        if !is_valid_name(name) {
+            bail!("Invalid profile name `{name}`");
+        }
+
+        let path = self.path(name);
+
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Profile::default());
+            }
+            Err(e) => {
+                return Err(e).with_context(|| path.display().to_string());
+            }
+        };
+
+        serde_json::from_slice(&data).with_context(|| path.display().to_string())
+    }
+
+    /// Save the given profile.
+    pub fn save(&self, name: &str, profile: &Profile) -> Result<()> {
+        if !is_valid_name(name) {
+            bail!("Invalid profile name `{name}`");
+        }
+
+        let data = serde_json::to_vec_pretty(profile)?;
+        write(&self.dir, &format!("{name}.{EXTENSION}"), &data)
+    }
+
+    /// Delete the given profile.
+    ///
+    /// The default profile can't be deleted, and if the deleted profile is
+    /// currently selected the default profile is selected instead.
+    pub fn delete(&self, name: &str) -> Result<()> {
+        if name == DEFAULT_PROFILE {
+            bail!("The default profile can't be deleted");
+        }
+
+        if !is_valid_name(name) {
+            bail!("Invalid profile name `{name}`");
+        }
+
+        if self.current()? == name {
+            self.select(DEFAULT_PROFILE)?;
+        }
+
+        match fs::remove_file(self.path(name)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).context("Could not remove profile"),
+        }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.{EXTENSION}"))
+    }
+}
+
+/// Write a file in the given directory by first writing to a temporary file
+/// and then moving it in place.
+pub(crate) fn write(dir: &Path, name: &str, data: &[u8]) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| dir.display().to_string())?;
+
+    let path = dir.join(name);
+    let temp = dir.join(format!(".{name}.tmp"));
+
+    fs::write(&temp, data).with_context(|| temp.display().to_string())?;
+    fs::rename(&temp, &path).with_context(|| path.display().to_string())?;
+    Ok(())
+}
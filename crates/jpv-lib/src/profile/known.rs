@@ -0,0 +1,161 @@
+//! Parsing of known words exported from other tools.
+//!
+//! Both plain text exports from Anki and CSV files are supported. The words
+//! are taken from the first column, ignoring columns which Anki uses for
+//! metadata, like the note type or the deck.
+
+/// Parse words from an Anki plain text export or a CSV file.
+///
+/// Formatting like HTML tags and Anki-style furigana such as `食[た]べる` is
+/// stripped, and duplicates are removed.
+pub fn parse(input: &str) -> Vec<String> {
+    let input = input.trim_start_matches('\u{feff}');
+
+    let mut separator = None;
+    let mut reserved = Vec::new();
+    let mut body = input;
+
+    // Anki exports start with a number of `#key:value` header lines.
+    while let Some(line) = body.lines().next().filter(|l| l.starts_with('#')) {
+        body = body[line.len()..].trim_start_matches(['\r', '\n']);
+
+        let Some((key, value)) = line[1..].split_once(':') else {
+            continue;
+        };
+
+        match key.trim() {
+            "separator" => {
+                separator = parse_separator(value.trim());
+            }
+            key if key.ends_with(" column") => {
+                if let Ok(n) = value.trim().parse::<usize>() {
+                    reserved.push(n.saturating_sub(1));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let separator = separator.unwrap_or_else(|| {
+        let first = body.lines().next().unwrap_or_default();
+
+        if first.contains('\t') {
+            '\t'
+        } else {
+            ','
+        }
+    });
+
+    let column = (0..).find(|n| !reserved.contains(n)).unwrap_or_default();
+
+    let mut output = Vec::new();
+
+    for record in records(body, separator) {
+        let Some(field) = record.get(column) else {
+            continue;
+        };
+
+        let word = clean(field);
+
+        if !word.is_empty() && !output.contains(&word) {
+            output.push(word);
+        }
+    }
+
+    output
+}
+
+/// Parse the separator named in an Anki export header.
+fn parse_separator(value: &str) -> Option<char> {
+    Some(match value.to_lowercase().as_str() {
+        "tab" => '\t',
+        "comma" => ',',
+        "semicolon" => ';',
+        "space" => ' ',
+        "pipe" => '|',
+        "colon" => ':',
+        _ => {
+            let mut chars = value.chars();
+            let c = chars.next()?;
+
+            if chars.next().is_some() {
+                return None;
+            }
+
+            c
+        }
+    })
+}
+
+/// Split input into records of fields, where fields may be quoted and
+/// quoted fields may contain separators and line breaks.
+fn records(input: &str, separator: char) -> Vec<Vec<String>> {
+    let mut output = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut it = input.chars().peekable();
+
+    while let Some(c) = it.next() {
+        match c {
+            '"' if quoted => {
+                if it.next_if_eq(&'"').is_some() {
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => {
+                quoted = true;
+            }
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                output.push(std::mem::take(&mut record));
+            }
+            c if c == separator && !quoted => {
+                record.push(std::mem::take(&mut field));
+            }
+            c => {
+                field.push(c);
+            }
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        output.push(record);
+    }
+
+    output
+}
+
+/// Clean up a field, removing HTML tags, entities and furigana.
+fn clean(field: &str) -> String {
+    let mut output = String::new();
+    let mut in_tag = false;
+    let mut in_reading = false;
+
+    for c in field.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if in_tag => {}
+            '[' => in_reading = true,
+            ']' if in_reading => in_reading = false,
+            _ if in_reading => {}
+            c => output.push(c),
+        }
+    }
+
+    let output = output
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&");
+
+    // Anki separates furigana groups with spaces, which aren't part of the
+    // word.
+    output.split_whitespace().collect()
+}
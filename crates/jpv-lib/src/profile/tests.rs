@@ -0,0 +1,411 @@
+use std::cell::RefCell;
+
+use anyhow::Result;
+
+use super::{
+    is_valid_name, known, Annotation, Density, ListEntry, Profile, Profiles, Readings, SrsCard,
+    WordList, WordStatus, DEFAULT_PROFILE, DEFAULT_TEXT_SCALE, MAX_HISTORY,
+};
+use crate::database::NameWeighting;
+use crate::query::{self, Expr, Resolver};
+use crate::sync::{self, SyncBackend};
+
+/// A sync backend keeping the shared copy of a profile in memory.
+#[derive(Default)]
+struct Memory(RefCell<Option<Profile>>);
+
+impl SyncBackend for Memory {
+    fn pull(&self, _: &str) -> Result<Option<Profile>> {
+        Ok(self.0.borrow().clone())
+    }
+
+    fn push(&self, _: &str, profile: &Profile) -> Result<()> {
+        *self.0.borrow_mut() = Some(profile.clone());
+        Ok(())
+    }
+}
+
+fn card(interval: u32, modified: u64) -> SrsCard {
+    SrsCard {
+        due: 0,
+        interval,
+        ease: 2500,
+        repetitions: 1,
+        modified,
+    }
+}
+
+fn list(entries: &[(u64, u64)]) -> WordList {
+    WordList {
+        entries: entries
+            .iter()
+            .map(|&(sequence, added)| ListEntry { sequence, added })
+            .collect(),
+    }
+}
+
+#[test]
+fn names() {
+    assert!(is_valid_name("default"));
+    assert!(is_valid_name("john-john_2"));
+    assert!(!is_valid_name(""));
+    assert!(!is_valid_name("../etc"));
+    assert!(!is_valid_name("a b"));
+    assert!(!is_valid_name("日本"));
+    assert!(!is_valid_name(&"a".repeat(65)));
+}
+
+#[test]
+fn history() {
+    let mut profile = Profile::default();
+    profile.add_history("日本", 1);
+    profile.add_history("猫", 2);
+    profile.add_history("日本", 3);
+
+    let texts = profile
+        .history
+        .iter()
+        .map(|e| (e.text.as_str(), e.timestamp))
+        .collect::<Vec<_>>();
+
+    assert_eq!(texts, [("猫", 2), ("日本", 3)]);
+
+    for n in 0..MAX_HISTORY as u64 {
+        profile.add_history(&n.to_string(), n);
+    }
+
+    assert_eq!(profile.history.len(), MAX_HISTORY);
+    assert_eq!(profile.history[0].text, "0");
+}
+
+#[test]
+fn merge() {
+    let mut local = Profile::default();
+    local.add_history("猫", 1);
+    local.add_history("犬", 4);
+    local
+        .lists
+        .insert("n5".to_owned(), list(&[(1, 10), (2, 20)]));
+    local.srs.insert(1, card(1, 100));
+    local.srs.insert(2, card(2, 300));
+    local
+        .settings
+        .insert("mode".to_owned(), "hiragana".to_owned());
+
+    let mut remote = Profile::default();
+    remote.add_history("鳥", 2);
+    remote.add_history("猫", 3);
+    remote
+        .lists
+        .insert("n5".to_owned(), list(&[(2, 15), (3, 30)]));
+    remote.lists.insert("n4".to_owned(), list(&[(4, 40)]));
+    remote.srs.insert(1, card(5, 200));
+    remote.srs.insert(2, card(6, 200));
+    remote.srs.insert(3, card(7, 200));
+    remote
+        .settings
+        .insert("mode".to_owned(), "katakana".to_owned());
+    remote.settings.insert("group".to_owned(), "yes".to_owned());
+
+    local.merge(remote);
+
+    let history = local
+        .history
+        .iter()
+        .map(|e| (e.text.as_str(), e.timestamp))
+        .collect::<Vec<_>>();
+
+    assert_eq!(history, [("鳥", 2), ("猫", 3), ("犬", 4)]);
+    assert_eq!(local.lists["n5"], list(&[(1, 10), (2, 15), (3, 30)]));
+    assert_eq!(local.lists["n4"], list(&[(4, 40)]));
+    assert_eq!(local.srs[&1], card(5, 200));
+    assert_eq!(local.srs[&2], card(2, 300));
+    assert_eq!(local.srs[&3], card(7, 200));
+    assert_eq!(local.settings["mode"], "hiragana");
+    assert_eq!(local.settings["group"], "yes");
+}
+
+#[test]
+fn remove_then_sync() {
+    let shared = Memory::default();
+
+    let mut a = Profile::default();
+    a.add_history("猫", 1);
+    a.lists.insert("n5".to_owned(), list(&[(1, 10), (2, 10)]));
+    a.set_word_status(3, WordStatus::Known, 10);
+    a.annotate(4, "Black cat", &[], 10);
+    sync::sync(&shared, "test", &mut a).unwrap();
+
+    let mut b = Profile::default();
+    sync::sync(&shared, "test", &mut b).unwrap();
+    assert_eq!(a, b);
+
+    b.remove_history("猫", 20);
+    b.remove_from_list("n5", 1, 20);
+    b.set_word_status(3, WordStatus::Unknown, 20);
+    assert_eq!(b.annotate(4, "", &[], 20), None);
+    sync::sync(&shared, "test", &mut b).unwrap();
+    sync::sync(&shared, "test", &mut a).unwrap();
+
+    assert!(a.history.is_empty());
+    assert_eq!(a.lists["n5"], list(&[(2, 10)]));
+    assert_eq!(a.word_status(3), WordStatus::Unknown);
+    assert!(a.annotations.is_empty());
+
+    // Adding things again after they've been removed brings them back.
+    a.add_history("猫", 30);
+    a.lists.get_mut("n5").unwrap().entries.push(ListEntry {
+        sequence: 1,
+        added: 30,
+    });
+    a.set_word_status(3, WordStatus::Known, 30);
+    a.annotate(4, "White cat", &[], 30);
+    sync::sync(&shared, "test", &mut a).unwrap();
+    sync::sync(&shared, "test", &mut b).unwrap();
+
+    assert_eq!(b.history[0].text, "猫");
+    assert_eq!(b.lists["n5"], list(&[(2, 10), (1, 30)]));
+    assert_eq!(b.word_status(3), WordStatus::Known);
+    assert_eq!(b.annotations[&4].note, "White cat");
+}
+
+#[test]
+fn merge_word_status() {
+    let mut local = Profile::default();
+    local.set_word_status(1, WordStatus::Known, 10);
+    local.set_word_status(1, WordStatus::Unknown, 20);
+    local.set_word_status(2, WordStatus::Learning, 20);
+
+    // A copy which still has the word as known from before it was demoted.
+    let mut remote = Profile::default();
+    remote.set_word_status(1, WordStatus::Known, 10);
+    remote.set_word_status(2, WordStatus::Known, 30);
+    remote.known.insert(3);
+
+    local.merge(remote);
+    assert_eq!(local.word_status(1), WordStatus::Unknown);
+    assert_eq!(local.word_status(2), WordStatus::Known);
+    assert_eq!(local.word_status(3), WordStatus::Known);
+    assert!(local.learning.is_empty());
+}
+
+#[test]
+fn store() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    let profiles = Profiles::new(dir);
+
+    assert_eq!(profiles.list().unwrap(), [DEFAULT_PROFILE]);
+    assert_eq!(profiles.current().unwrap(), DEFAULT_PROFILE);
+
+    profiles.select("learner").unwrap();
+    assert_eq!(profiles.current().unwrap(), "learner");
+    assert_eq!(profiles.list().unwrap(), [DEFAULT_PROFILE, "learner"]);
+
+    let mut profile = profiles.load("learner").unwrap();
+    profile.add_history("猫", 1);
+    profiles.save("learner", &profile).unwrap();
+    assert_eq!(profiles.load("learner").unwrap(), profile);
+    assert_eq!(profiles.load(DEFAULT_PROFILE).unwrap(), Profile::default());
+
+    assert!(profiles.select("../learner").is_err());
+    assert!(profiles.delete(DEFAULT_PROFILE).is_err());
+
+    profiles.delete("learner").unwrap();
+    assert_eq!(profiles.current().unwrap(), DEFAULT_PROFILE);
+    assert_eq!(profiles.list().unwrap(), [DEFAULT_PROFILE]);
+}
+
+#[test]
+fn known() {
+    let mut profile = Profile::default();
+    profile.lists.insert("study".to_owned(), list(&[(1, 10)]));
+    profile.srs.insert(2, card(1, 10));
+
+    assert!(profile.is_known(1));
+    assert!(profile.is_known(2));
+    assert!(!profile.is_known(3));
+}
+
+#[test]
+fn imported_known() {
+    let mut profile = Profile::default();
+    profile.known.insert(5);
+    assert!(profile.is_known(5));
+
+    let mut remote = Profile::default();
+    remote.known.insert(6);
+    profile.merge(remote);
+    assert_eq!(profile.known.iter().copied().collect::<Vec<_>>(), [5, 6]);
+}
+
+#[test]
+fn known_words() {
+    let anki = "#separator:tab\n#html:true\n#guid column:1\n#notetype column:2\n\
+        abc\tBasic\t食[た]べる\t<b>to eat</b>\n\
+        def\tBasic\t\"<div>日本[にほん] 語[ご]</div>\"\tJapanese\n\
+        ghi\tBasic\t\"猫\"\t\"a\ncat\"\n\
+        jkl\tBasic\t食べる\tduplicate\n";
+
+    assert_eq!(known::parse(anki), ["食べる", "日本語", "猫"]);
+
+    let csv = "\u{feff}word,meaning\r\n\"犬\",\"dog, hound\"\r\n&nbsp;鳥,bird\r\n,empty\r\n";
+    assert_eq!(known::parse(csv), ["word", "犬", "鳥"]);
+
+    let lines = "水\n火\n";
+    assert_eq!(known::parse(lines), ["水", "火"]);
+
+    let semicolon = "#separator:Semicolon\n空;sky\n";
+    assert_eq!(known::parse(semicolon), ["空"]);
+}
+
+#[test]
+fn word_status() {
+    let mut profile = Profile::default();
+    profile.srs.insert(1, card(1, 10));
+
+    assert_eq!(profile.word_status(1), WordStatus::Learning);
+    assert_eq!(profile.word_status(2), WordStatus::Unknown);
+
+    profile.set_word_status(1, WordStatus::Known, 10);
+    profile.set_word_status(2, WordStatus::Learning, 10);
+    assert_eq!(profile.word_status(1), WordStatus::Known);
+    assert_eq!(profile.word_status(2), WordStatus::Learning);
+    assert!(profile.is_known(2));
+
+    profile.set_word_status(2, WordStatus::Known, 10);
+    assert!(profile.learning.is_empty());
+
+    profile.set_word_status(2, WordStatus::Unknown, 10);
+    assert_eq!(profile.word_status(2), WordStatus::Unknown);
+    assert!(!profile.is_known(2));
+
+    let mut remote = Profile::default();
+    remote.set_word_status(1, WordStatus::Learning, 5);
+    remote.set_word_status(3, WordStatus::Learning, 5);
+    profile.merge(remote);
+    assert_eq!(profile.word_status(1), WordStatus::Known);
+    assert_eq!(profile.word_status(3), WordStatus::Learning);
+}
+
+#[test]
+fn annotations() {
+    let mut profile = Profile::default();
+
+    let tags = ["#Food".to_owned(), " food ".to_owned(), "n5".to_owned()];
+    let a = profile.annotate(1, " Black cat ", &tags, 10).unwrap();
+    assert_eq!(a.note, "Black cat");
+    assert_eq!(a.tags, ["Food", "n5"]);
+
+    profile.annotate(2, "", &["N5".to_owned()], 10);
+
+    let resolve = |q| {
+        let Expr::Term(term) = query::parse(q) else {
+            panic!("expected term");
+        };
+
+        profile.resolve(&term)
+    };
+
+    assert_eq!(resolve("note:cat"), [1]);
+    assert_eq!(resolve("tag:n5"), [1, 2]);
+    assert_eq!(resolve("tag:food"), [1]);
+    assert_eq!(resolve("tag:drink"), Vec::<u64>::new());
+
+    let mut remote = Profile::default();
+    remote.annotate(1, "White cat", &[], 20);
+    remote.annotate(2, "Older", &[], 5);
+
+    let mut local = profile.clone();
+    local.merge(remote);
+    assert_eq!(local.annotations[&1].note, "White cat");
+    assert_eq!(local.annotations[&2].tags, ["N5"]);
+
+    assert_eq!(profile.annotate(1, " ", &[], 30), None);
+    assert!(!profile.annotations.contains_key(&1));
+    assert_eq!(
+        profile.annotations[&2],
+        Annotation {
+            note: String::new(),
+            tags: vec!["N5".to_owned()],
+            modified: 10,
+        }
+    );
+}
+
+#[test]
+fn lapse() {
+    let mut profile = Profile::default();
+    profile.srs.insert(1, card(10, 10));
+
+    let lapsed = profile.lapse(1, 100).clone();
+    assert_eq!(lapsed.due, 100);
+    assert_eq!(lapsed.interval, 0);
+    assert_eq!(lapsed.repetitions, 0);
+    assert_eq!(lapsed.ease, 2300);
+    assert_eq!(lapsed.modified, 100);
+
+    let added = profile.lapse(2, 200).clone();
+    assert_eq!(added.due, 200);
+    assert_eq!(added.ease, 2500);
+    assert_eq!(profile.word_status(2), WordStatus::Learning);
+
+    assert_eq!(profile.lapse(2, 300).ease, 2500);
+}
+
+#[test]
+fn name_weighting() {
+    let mut profile = Profile::default();
+    assert_eq!(profile.name_weighting(), NameWeighting::Context);
+
+    profile.set_name_weighting(NameWeighting::Plain);
+    assert_eq!(profile.name_weighting(), NameWeighting::Plain);
+
+    profile
+        .settings
+        .insert("names".to_owned(), "unknown".to_owned());
+    assert_eq!(profile.name_weighting(), NameWeighting::Context);
+}
+
+#[test]
+fn readings() {
+    let mut profile = Profile::default();
+    assert_eq!(profile.readings(), Readings::Always);
+
+    for readings in Readings::ALL {
+        profile.set_readings(readings);
+        assert_eq!(profile.readings(), readings);
+    }
+
+    profile
+        .settings
+        .insert("readings".to_owned(), "sometimes".to_owned());
+    assert_eq!(profile.readings(), Readings::Always);
+}
+
+#[test]
+fn density() {
+    let mut profile = Profile::default();
+    assert_eq!(profile.density(), Density::Normal);
+    assert_eq!(profile.text_scale(), DEFAULT_TEXT_SCALE);
+
+    profile.set_density(Density::Compact);
+    assert_eq!(profile.density(), Density::Compact);
+
+    profile.set_text_scale(150);
+    assert_eq!(profile.text_scale(), 150);
+
+    // Scales are kept within what can reasonably be shown.
+    profile.set_text_scale(10);
+    assert_eq!(profile.text_scale(), 75);
+
+    profile
+        .settings
+        .insert("text-scale".to_owned(), "1000".to_owned());
+    assert_eq!(profile.text_scale(), 300);
+
+    profile
+        .settings
+        .insert("text-scale".to_owned(), "large".to_owned());
+    assert_eq!(profile.text_scale(), DEFAULT_TEXT_SCALE);
+}
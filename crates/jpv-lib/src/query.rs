@@ -0,0 +1,475 @@
+//! Structured query language used for advanced searches.
+//!
+//! Plain text is searched for as-is, so `to eat` looks up the glossary phrase
+//! `to eat`. On top of that the following syntax is supported:
+//!
+//! * `kanji:食`, `reading:たべる` and `gloss:eat` restrict a term to only
+//!   match kanji, readings or glossary entries respectively.
+//! * `pos:verb` matches any part of speech in the given group (`verb`, `noun`,
+//!   `adjective`, `adverb`, `expression`), while `pos:v1` or `#v1` matches a
+//!   specific part of speech.
+//! * `note:text` matches phrases with a personal note containing the text,
+//!   and `tag:name` matches phrases with the given personal tag. These are
+//!   evaluated through a [`Resolver`] since they aren't part of the
+//!   dictionary.
+//! * `a AND b` or `a & b` requires both terms to match. Terms separated by
+//!   other syntax like field prefixes are implicitly joined with `AND`.
+//! * `a OR b` or `a | b` requires either term to match.
+//! * `NOT a`, `-a` or `!a` excludes entries matching a term.
+//! * `(` and `)` groups expressions, and `"to eat"` quotes text so that it is
+//!   not interpreted as syntax.
+//!
+//! Parsing is lenient, so that queries which are being typed are still
+//! meaningful. Unbalanced parenthesis are closed at the end of the input and
+//! dangling operators are ignored.
+
+#[cfg(test)]
+mod tests;
+
+use std::borrow::Cow;
+
+use fixed_map::Set;
+
+use crate::entities::{PartOfSpeech, PartOfSpeechGroup};
+
+/// The field a text term is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Field {
+    /// Match against anything indexed.
+    Any,
+    /// Match against kanji.
+    Kanji,
+    /// Match against readings.
+    Reading,
+    /// Match against glossary entries.
+    Gloss,
+}
+
+impl Field {
+    fn parse(string: &str) -> Option<Field> {
+        match string {
+            "kanji" => Some(Field::Kanji),
+            "reading" => Some(Field::Reading),
+            "gloss" => Some(Field::Gloss),
+            _ => None,
+        }
+    }
+}
+
+/// A single term in a query.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Term<'a> {
+    /// Look up the given text in the specified field.
+    Text { field: Field, text: Cow<'a, str> },
+    /// Match entries which has any of the given parts of speech.
+    Pos(Set<PartOfSpeech>),
+    /// Match entries with a personal note containing the given text.
+    Note(Cow<'a, str>),
+    /// Match entries with the given personal tag.
+    Tag(Cow<'a, str>),
+}
+
+/// Resolves terms which match user data rather than the dictionary, like
+/// notes and tags, into the sequences of the phrases they match.
+pub trait Resolver {
+    /// Resolve the given term.
+    fn resolve(&self, term: &Term<'_>) -> Vec<u64>;
+}
+
+/// Resolves nothing, so terms matching user data match no phrases.
+impl Resolver for () {
+    #[inline]
+    fn resolve(&self, _: &Term<'_>) -> Vec<u64> {
+        Vec::new()
+    }
+}
+
+/// A parsed query expression.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Expr<'a> {
+    /// The empty expression, which doesn't constrain the result.
+    Empty,
+    /// A single term.
+    Term(Term<'a>),
+    /// All expressions must match.
+    And(Vec<Expr<'a>>),
+    /// Any expression must match.
+    Or(Vec<Expr<'a>>),
+    /// The expression must not match.
+    Not(Box<Expr<'a>>),
+}
+
+impl<'a> Expr<'a> {
+    /// Test if the expression is empty.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Expr::Empty)
+    }
+
+    /// Collect all kanji and reading text which is not negated in the
+    /// expression.
+    ///
+    /// This is used to weigh results and to populate related kanji.
+    pub fn texts(&self) -> Vec<&str> {
+        fn walk<'b>(expr: &'b Expr<'_>, output: &mut Vec<&'b str>) {
+            match expr {
+                Expr::Term(Term::Text { field, text }) => {
+                    if matches!(field, Field::Any | Field::Kanji | Field::Reading) {
+                        output.push(text.as_ref());
+                    }
+                }
+                Expr::And(exprs) | Expr::Or(exprs) => {
+                    for expr in exprs {
+                        walk(expr, output);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut output = Vec::new();
+        walk(self, &mut output);
+        output
+    }
+
+    fn join(mut exprs: Vec<Expr<'a>>, f: fn(Vec<Expr<'a>>) -> Expr<'a>) -> Expr<'a> {
+        exprs.retain(|e| !e.is_empty());
+
+        match exprs.len() {
+            0 => Expr::Empty,
+            1 => exprs.pop().unwrap_or(Expr::Empty),
+            _ => f(exprs),
+        }
+    }
+}
+
+/// Parse the given query.
+pub fn parse(input: &str) -> Expr<'_> {
+    let mut parser = Parser { input, o: 0 };
+    let mut expr = parser.or();
+
+    // Stray closing parenthesis are ignored.
+    while !parser.is_eof() {
+        parser.step();
+        let rest = parser.or();
+        expr = Expr::join(vec![expr, rest], Expr::And);
+    }
+
+    expr
+}
+
+/// Lowercase the text portions of a query, leaving operators intact.
+pub fn to_lowercase(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for (n, word) in input.split(' ').enumerate() {
+        if n > 0 {
+            output.push(' ');
+        }
+
+        if is_keyword(word) {
+            output.push_str(word);
+        } else {
+            output.push_str(&word.to_lowercase());
+        }
+    }
+
+    output
+}
+
+fn is_keyword(word: &str) -> bool {
+    matches!(word, "AND" | "OR" | "NOT")
+}
+
+fn is_wildcard(text: &str) -> bool {
+    text.chars().all(|c| matches!(c, '*' | '＊'))
+}
+
+/// Test if the given prefix introduces a term matching user data.
+fn is_user_prefix(prefix: &str) -> bool {
+    matches!(prefix, "note" | "tag")
+}
+
+fn is_special(c: char) -> bool {
+    matches!(c, '(' | ')' | '|' | '&' | '"')
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    o: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.o..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn is_eof(&self) -> bool {
+        self.o >= self.input.len()
+    }
+
+    fn step(&mut self) {
+        if let Some(c) = self.peek() {
+            self.o += c.len_utf8();
+        }
+    }
+
+    fn space(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.step();
+        }
+    }
+
+    /// Peek the next whitespace or syntax delimited word.
+    fn peek_word(&self) -> &'a str {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || is_special(c))
+            .unwrap_or(rest.len());
+        &rest[..end]
+    }
+
+    /// Consume the given keyword if it's next in the input.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        self.space();
+
+        if self.peek_word() == keyword {
+            self.o += keyword.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Expr<'a> {
+        let mut exprs = vec![self.and()];
+
+        loop {
+            self.space();
+
+            if self.peek() == Some('|') {
+                self.step();
+            } else if !self.keyword("OR") {
+                break;
+            }
+
+            exprs.push(self.and());
+        }
+
+        Expr::join(exprs, Expr::Or)
+    }
+
+    fn and(&mut self) -> Expr<'a> {
+        let mut exprs = Vec::new();
+
+        loop {
+            self.space();
+
+            match self.peek() {
+                None | Some(')' | '|') => break,
+                Some('&') => {
+                    self.step();
+                    continue;
+                }
+                _ => {}
+            }
+
+            match self.peek_word() {
+                "OR" => break,
+                "AND" => {
+                    self.o += "AND".len();
+                    continue;
+                }
+                _ => {}
+            }
+
+            exprs.push(self.unary());
+        }
+
+        Expr::join(exprs, Expr::And)
+    }
+
+    fn unary(&mut self) -> Expr<'a> {
+        self.space();
+
+        let negate = match self.peek() {
+            Some('-' | '!') => {
+                self.step();
+                true
+            }
+            _ => self.keyword("NOT"),
+        };
+
+        if negate {
+            return match self.unary() {
+                Expr::Empty => Expr::Empty,
+                expr => Expr::Not(Box::new(expr)),
+            };
+        }
+
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Expr<'a> {
+        self.space();
+
+        match self.peek() {
+            Some('(') => {
+                self.step();
+                let expr = self.or();
+                self.space();
+
+                if self.peek() == Some(')') {
+                    self.step();
+                }
+
+                expr
+            }
+            Some('"') => {
+                let text = self.quoted();
+                text_term(Field::Any, text)
+            }
+            Some('#') => {
+                self.step();
+                let word = self.peek_word();
+                self.o += word.len();
+
+                match PartOfSpeech::parse_keyword(word) {
+                    Some(pos) => {
+                        let mut set = Set::new();
+                        set.insert(pos);
+                        Expr::Term(Term::Pos(set))
+                    }
+                    None => Expr::Empty,
+                }
+            }
+            _ => {
+                let word = self.peek_word();
+
+                if let Some((prefix, value)) = word.split_once(':') {
+                    if prefix == "pos" {
+                        self.o += word.len();
+                        return pos_term(value);
+                    }
+
+                    if let Some(field) = Field::parse(prefix) {
+                        self.o += prefix.len() + 1;
+                        let text = self.value(value);
+                        return text_term(field, text);
+                    }
+
+                    if is_user_prefix(prefix) {
+                        self.o += prefix.len() + 1;
+                        let text = self.value(value);
+
+                        if text.trim().is_empty() {
+                            return Expr::Empty;
+                        }
+
+                        return Expr::Term(match prefix {
+                            "note" => Term::Note(text),
+                            _ => Term::Tag(text),
+                        });
+                    }
+                }
+
+                self.words()
+            }
+        }
+    }
+
+    /// Parse a sequence of plain words, which are joined by whitespace.
+    fn words(&mut self) -> Expr<'a> {
+        let start = self.o;
+        let mut end = self.o;
+
+        loop {
+            let word = self.peek_word();
+
+            if word.is_empty() || is_keyword(word) || word.starts_with(['-', '!', '#']) {
+                break;
+            }
+
+            if let Some((prefix, _)) = word.split_once(':') {
+                if prefix == "pos" || Field::parse(prefix).is_some() || is_user_prefix(prefix) {
+                    break;
+                }
+            }
+
+            self.o += word.len();
+            end = self.o;
+
+            let before = self.o;
+            self.space();
+
+            if self.peek().map_or(true, is_special) {
+                self.o = before;
+                break;
+            }
+        }
+
+        if start == end {
+            // Nothing could be consumed, so skip the offending character to
+            // guarantee progress.
+            self.step();
+            return Expr::Empty;
+        }
+
+        text_term(Field::Any, Cow::Borrowed(&self.input[start..end]))
+    }
+
+    /// Parse the value following a prefix, which is either quoted or the
+    /// rest of the given word.
+    fn value(&mut self, word: &'a str) -> Cow<'a, str> {
+        if self.peek() == Some('"') {
+            self.quoted()
+        } else {
+            self.o += word.len();
+            Cow::Borrowed(word)
+        }
+    }
+
+    /// Parse a quoted string.
+    fn quoted(&mut self) -> Cow<'a, str> {
+        self.step();
+        let rest = self.rest();
+        let end = rest.find('"').unwrap_or(rest.len());
+        self.o += end;
+        self.step();
+        Cow::Borrowed(&rest[..end])
+    }
+}
+
+fn text_term(field: Field, text: Cow<'_, str>) -> Expr<'_> {
+    if text.trim().is_empty() || is_wildcard(&text) {
+        return Expr::Empty;
+    }
+
+    Expr::Term(Term::Text { field, text })
+}
+
+fn pos_term(value: &str) -> Expr<'_> {
+    let mut set = Set::new();
+
+    if let Some(group) = PartOfSpeechGroup::parse_keyword(value) {
+        for &pos in PartOfSpeech::VALUES {
+            if pos.group() == group {
+                set.insert(pos);
+            }
+        }
+    } else if let Some(pos) = PartOfSpeech::parse_keyword(value) {
+        set.insert(pos);
+    }
+
+    if set.is_empty() {
+        return Expr::Empty;
+    }
+
+    Expr::Term(Term::Pos(set))
+}
@@ -0,0 +1,145 @@
+use std::borrow::Cow;
+
+use fixed_map::Set;
+
+use super::{parse, to_lowercase, Expr, Field, Term};
+use crate::PartOfSpeech;
+
+fn text(field: Field, text: &str) -> Expr<'_> {
+    Expr::Term(Term::Text {
+        field,
+        text: Cow::Borrowed(text),
+    })
+}
+
+fn pos(pos: PartOfSpeech) -> Expr<'static> {
+    let mut set = Set::new();
+    set.insert(pos);
+    Expr::Term(Term::Pos(set))
+}
+
+#[test]
+fn plain_text() {
+    assert_eq!(parse(""), Expr::Empty);
+    assert_eq!(parse("  "), Expr::Empty);
+    assert_eq!(parse("*"), Expr::Empty);
+    assert_eq!(parse("to eat"), text(Field::Any, "to eat"));
+    assert_eq!(parse("食べ*"), text(Field::Any, "食べ*"));
+    assert_eq!(parse("e-mail"), text(Field::Any, "e-mail"));
+}
+
+#[test]
+fn fields() {
+    assert_eq!(parse("kanji:食"), text(Field::Kanji, "食"));
+    assert_eq!(parse("reading:たべる"), text(Field::Reading, "たべる"));
+    assert_eq!(parse("gloss:\"to eat\""), text(Field::Gloss, "to eat"));
+    assert_eq!(parse("re:zero"), text(Field::Any, "re:zero"));
+
+    assert_eq!(
+        parse("kanji:食 gloss:eat"),
+        Expr::And(vec![text(Field::Kanji, "食"), text(Field::Gloss, "eat")])
+    );
+}
+
+#[test]
+fn tags() {
+    assert_eq!(parse("#v1"), pos(PartOfSpeech::VerbIchidan));
+    assert_eq!(parse("#unknown"), Expr::Empty);
+    assert_eq!(parse("pos:v5k"), pos(PartOfSpeech::VerbGodanK));
+
+    assert_eq!(
+        parse("たべる #v1"),
+        Expr::And(vec![
+            text(Field::Any, "たべる"),
+            pos(PartOfSpeech::VerbIchidan)
+        ])
+    );
+
+    let Expr::Term(Term::Pos(verbs)) = parse("pos:verb") else {
+        panic!("expected pos");
+    };
+
+    assert!(verbs.contains(PartOfSpeech::VerbIchidan));
+    assert!(verbs.contains(PartOfSpeech::VerbSuru));
+    assert!(!verbs.contains(PartOfSpeech::Noun));
+}
+
+#[test]
+fn user_data() {
+    assert_eq!(
+        parse("note:cat"),
+        Expr::Term(Term::Note(Cow::Borrowed("cat")))
+    );
+    assert_eq!(
+        parse("note:\"black cat\""),
+        Expr::Term(Term::Note(Cow::Borrowed("black cat")))
+    );
+    assert_eq!(parse("tag:"), Expr::Empty);
+
+    assert_eq!(
+        parse("食べる tag:food"),
+        Expr::And(vec![
+            text(Field::Any, "食べる"),
+            Expr::Term(Term::Tag(Cow::Borrowed("food")))
+        ])
+    );
+}
+
+#[test]
+fn operators() {
+    assert_eq!(
+        parse("eat OR drink"),
+        Expr::Or(vec![text(Field::Any, "eat"), text(Field::Any, "drink")])
+    );
+
+    assert_eq!(
+        parse("eat | drink"),
+        Expr::Or(vec![text(Field::Any, "eat"), text(Field::Any, "drink")])
+    );
+
+    assert_eq!(
+        parse("eat AND drink"),
+        Expr::And(vec![text(Field::Any, "eat"), text(Field::Any, "drink")])
+    );
+
+    assert_eq!(
+        parse("eat -drink"),
+        Expr::And(vec![
+            text(Field::Any, "eat"),
+            Expr::Not(Box::new(text(Field::Any, "drink")))
+        ])
+    );
+
+    assert_eq!(
+        parse("NOT kanji:食"),
+        Expr::Not(Box::new(text(Field::Kanji, "食")))
+    );
+}
+
+#[test]
+fn grouping() {
+    assert_eq!(
+        parse("(eat | drink) #v1"),
+        Expr::And(vec![
+            Expr::Or(vec![text(Field::Any, "eat"), text(Field::Any, "drink")]),
+            pos(PartOfSpeech::VerbIchidan),
+        ])
+    );
+
+    // Lenient parsing of incomplete queries.
+    assert_eq!(
+        parse("(eat | drink"),
+        Expr::Or(vec![text(Field::Any, "eat"), text(Field::Any, "drink")])
+    );
+
+    assert_eq!(parse("eat)"), text(Field::Any, "eat"));
+    assert_eq!(parse("eat OR"), text(Field::Any, "eat"));
+    assert_eq!(parse("eat -"), text(Field::Any, "eat"));
+    assert_eq!(parse("\"a (b)\""), text(Field::Any, "a (b)"));
+}
+
+#[test]
+fn lowercase() {
+    assert_eq!(to_lowercase("Eat OR Drink"), "eat OR drink");
+    assert_eq!(to_lowercase("NOT Japan"), "NOT japan");
+}
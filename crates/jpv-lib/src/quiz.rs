@@ -0,0 +1,87 @@
+//! Conjugation practice.
+//!
+//! A question asks for a word which is being learned to be put into a given
+//! inflection, and answers are checked against the conjugations produced by
+//! [`conjugate`].
+//!
+//! [`conjugate`]: crate::inflection::conjugate
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeSet;
+
+use crate::inflection::{Form, Inflection, Inflections};
+use crate::kana::Fragments;
+use crate::profile::Profile;
+
+/// The maximum number of forms in an inflection which is asked for.
+const MAX_FORMS: usize = 3;
+
+/// Forms which are never asked for, since they are colloquial or alternate
+/// spellings of other forms.
+const SKIPPED: [Form; 4] = [Form::Conversation, Form::Short, Form::Kya, Form::Chau];
+
+/// Words in the profile which can be practiced, by sequence.
+///
+/// If a list is specified only words in that list are used, otherwise every
+/// word which is being learned or reviewed is.
+pub fn vocabulary(profile: &Profile, list: Option<&str>) -> BTreeSet<u64> {
+    if let Some(list) = list {
+        return profile
+            .lists
+            .get(list)
+            .into_iter()
+            .flat_map(|l| l.entries.iter().map(|e| e.sequence))
+            .collect();
+    }
+
+    let mut out = BTreeSet::new();
+    out.extend(profile.learning.iter().copied());
+    out.extend(profile.srs.keys().copied());
+
+    for list in profile.lists.values() {
+        out.extend(list.entries.iter().map(|e| e.sequence));
+    }
+
+    out.retain(|sequence| !profile.known.contains(sequence));
+    out
+}
+
+/// Inflections of a word which can be asked for.
+pub fn questions<'a, 'b>(
+    inflections: &'b Inflections<'a>,
+) -> impl Iterator<Item = (Inflection, &'b Fragments<'a>)> + 'b {
+    inflections.iter().filter_map(|(inflection, fragments)| {
+        let forms = inflection.iter().count();
+
+        if forms == 0 || forms > MAX_FORMS || SKIPPED.iter().any(|f| inflection.contains(*f)) {
+            return None;
+        }
+
+        Some((*inflection, fragments))
+    })
+}
+
+/// Accepted answers for the given conjugation, written either with kanji or
+/// in kana.
+pub fn answers(fragments: &Fragments<'_>) -> Vec<String> {
+    let text = fragments.text().chars().collect::<String>();
+    let reading = fragments.reading().chars().collect::<String>();
+    let suffix = fragments.suffix().chars().collect::<String>();
+
+    let mut out = vec![format!("{text}{suffix}")];
+
+    if reading != text {
+        out.push(format!("{reading}{suffix}"));
+    }
+
+    out
+}
+
+/// Test if an answer is correct for the given conjugation, ignoring
+/// surrounding whitespace.
+pub fn is_correct(fragments: &Fragments<'_>, answer: &str) -> bool {
+    let answer = answer.trim();
+    !answer.is_empty() && answers(fragments).iter().any(|a| a == answer)
+}
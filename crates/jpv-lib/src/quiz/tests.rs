@@ -0,0 +1,70 @@
+use super::{answers, is_correct, questions, vocabulary};
+use crate::inflection::{Form, Inflections};
+use crate::kana::{Fragments, Full};
+use crate::profile::{ListEntry, Profile, WordList};
+
+#[test]
+fn check_answers() {
+    let fragments = Fragments::new(["食"], ["た"], ["べなかった"]);
+
+    assert_eq!(answers(&fragments), ["食べなかった", "たべなかった"]);
+    assert!(is_correct(&fragments, "食べなかった"));
+    assert!(is_correct(&fragments, " たべなかった\n"));
+    assert!(!is_correct(&fragments, "食べない"));
+    assert!(!is_correct(&fragments, ""));
+
+    let fragments = Fragments::new(["する"], ["する"], ["した"]);
+    assert_eq!(answers(&fragments), ["するした"]);
+}
+
+#[test]
+fn skipped_questions() {
+    let mut inflections = Inflections::new(Full::new("食", "た", "べる"));
+    let word = || Fragments::new(["食"], ["た"], ["べ"]);
+
+    inflections.insert(&[Form::Negative], &[], word());
+    inflections.insert(&[Form::Negative, Form::Past], &[Form::Honorific], word());
+    inflections.insert(&[Form::Negative, Form::Conversation], &[], word());
+    inflections.insert(
+        &[Form::Causative, Form::Passive, Form::Negative, Form::Past],
+        &[],
+        word(),
+    );
+
+    let mut asked = questions(&inflections)
+        .map(|(inflection, _)| inflection.explanation())
+        .collect::<Vec<_>>();
+
+    asked.sort();
+
+    assert_eq!(asked, ["negative", "polite → negative → past"]);
+}
+
+#[test]
+fn practiced_words() {
+    let mut profile = Profile::default();
+    profile.learning.insert(1);
+    profile.learning.insert(2);
+    profile.known.insert(2);
+    profile.lists.insert(
+        "verbs".to_owned(),
+        WordList {
+            entries: vec![ListEntry {
+                sequence: 3,
+                added: 0,
+            }],
+        },
+    );
+
+    assert_eq!(
+        vocabulary(&profile, None).into_iter().collect::<Vec<_>>(),
+        [1, 3]
+    );
+    assert_eq!(
+        vocabulary(&profile, Some("verbs"))
+            .into_iter()
+            .collect::<Vec<_>>(),
+        [3]
+    );
+    assert!(vocabulary(&profile, Some("missing")).is_empty());
+}
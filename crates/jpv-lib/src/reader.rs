@@ -0,0 +1,405 @@
+//! Documents stored for reading.
+//!
+//! Documents are stored as JSON files in the documents directory, named after
+//! their numerical identifier. The text of a document is split into pages
+//! when it's read, and the page the reader is at is stored in the document so
+//! that reading can be resumed later.
+
+#[cfg(test)]
+mod tests;
+
+pub mod epub;
+pub mod html;
+pub mod subtitles;
+
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::profile;
+
+/// The approximate number of characters on a page.
+pub const PAGE_SIZE: usize = 1000;
+/// The maximum number of characters in a segment.
+const MAX_SEGMENT: usize = 200;
+/// Characters which end a sentence.
+const TERMINATORS: &[char] = &['。', '！', '？', '!', '?', '…'];
+/// Characters which close a sentence which has been terminated, like quotes.
+const CLOSING: &[char] = &['」', '』', '）', ')', '"', '”', '’'];
+/// Extension of document files.
+const EXTENSION: &str = "json";
+
+/// A stored document.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Document {
+    /// The title of the document.
+    pub title: String,
+    /// The text of the document.
+    pub text: String,
+    /// Seconds since the unix epoch when the document was added.
+    pub added: u64,
+    /// The page the reader is at.
+    #[serde(default)]
+    pub position: usize,
+    /// Chapters of the document, ordered by where they start.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<Chapter>,
+    /// Timed lines of the document, if it was imported from subtitles.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<Line>,
+}
+
+/// A chapter in a document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub title: String,
+    /// The byte offset in the text where the chapter starts.
+    pub start: usize,
+}
+
+/// A timed line in a document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Line {
+    /// Milliseconds from the start when the line is shown.
+    pub start: u64,
+    /// Milliseconds from the start when the line is hidden.
+    pub end: u64,
+    /// The byte range of the line in the text, relative to the text of the
+    /// page when sent as part of a page.
+    pub range: Range<usize>,
+}
+
+/// An entry in the table of contents of a document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TocEntry {
+    pub title: String,
+    /// The 0-based page the entry starts on.
+    pub page: usize,
+}
+
+impl Document {
+    /// Construct a new document.
+    pub fn new(title: &str, text: &str, added: u64) -> Self {
+        Self {
+            title: title.trim().to_owned(),
+            text: text.replace("\r\n", "\n"),
+            added,
+            position: 0,
+            chapters: Vec::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Construct a document from subtitles, with one line for each cue.
+    pub fn from_subtitles(title: &str, cues: Vec<subtitles::Cue>, added: u64) -> Self {
+        let mut text = String::new();
+        let mut lines = Vec::with_capacity(cues.len());
+
+        for cue in cues {
+            let start = text.len();
+            text.push_str(&cue.text.replace('\n', " "));
+
+            lines.push(Line {
+                start: cue.start,
+                end: cue.end,
+                range: start..text.len(),
+            });
+
+            text.push('\n');
+        }
+
+        Self {
+            title: title.trim().to_owned(),
+            text,
+            added,
+            position: 0,
+            chapters: Vec::new(),
+            lines,
+        }
+    }
+
+    /// Construct a document from a book.
+    pub fn from_book(book: epub::Book, added: u64) -> Self {
+        Self {
+            title: book.title.trim().to_owned(),
+            text: book.text,
+            added,
+            position: 0,
+            chapters: book.chapters,
+            lines: Vec::new(),
+        }
+    }
+
+    /// The pages of the document, where each chapter starts a new page.
+    pub fn pages(&self) -> Vec<Range<usize>> {
+        let breaks = self.chapters.iter().map(|c| c.start).collect::<Vec<_>>();
+        paginate(&self.text, &breaks)
+    }
+
+    /// The table of contents of the document.
+    pub fn toc(&self, pages: &[Range<usize>]) -> Vec<TocEntry> {
+        self.chapters
+            .iter()
+            .map(|c| TocEntry {
+                title: c.title.clone(),
+                page: page_of(pages, c.start),
+            })
+            .collect()
+    }
+
+    /// The timed lines on the given page, with ranges relative to the page.
+    pub fn page_lines(&self, page: &Range<usize>) -> Vec<Line> {
+        self.lines
+            .iter()
+            .filter(|l| page.contains(&l.range.start))
+            .map(|l| Line {
+                start: l.start,
+                end: l.end,
+                range: l.range.start - page.start..l.range.end.min(page.end) - page.start,
+            })
+            .collect()
+    }
+
+    /// Find the line which is shown at the given time, or the last line
+    /// shown before it. Times before the first line find the first line.
+    pub fn line_at(&self, time: u64) -> Option<&Line> {
+        let n = self.lines.partition_point(|l| l.start <= time);
+        self.lines.get(n.saturating_sub(1))
+    }
+
+    /// Find lines containing the given text.
+    pub fn search_lines<'a>(&'a self, q: &'a str) -> impl Iterator<Item = &'a Line> + 'a {
+        self.lines
+            .iter()
+            .filter(move |l| self.text[l.range.clone()].contains(q))
+    }
+
+    /// Summarize the document.
+    pub fn info(&self, id: u64) -> DocumentInfo {
+        DocumentInfo {
+            id,
+            title: self.title.clone(),
+            pages: self.pages().len(),
+            position: self.position,
+            added: self.added,
+        }
+    }
+}
+
+/// A summary of a stored document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentInfo {
+    /// The identifier of the document.
+    pub id: u64,
+    /// The title of the document.
+    pub title: String,
+    /// The number of pages in the document.
+    pub pages: usize,
+    /// The page the reader is at.
+    pub position: usize,
+    /// Seconds since the unix epoch when the document was added.
+    pub added: u64,
+}
+
+/// Split text into short segments, like lines and sentences, which are
+/// suitable for analysis.
+///
+/// Segments are never longer than a fixed number of characters, so very long
+/// sentences are split arbitrarily.
+pub fn segments(text: &str) -> Vec<Range<usize>> {
+    let mut output = Vec::new();
+    let mut start = 0;
+    let mut count = 0;
+    let mut terminated = false;
+
+    for (i, c) in text.char_indices() {
+        // Closing characters, repeated terminators and line breaks belong to
+        // the sentence they follow.
+        if terminated && c != '\n' && !CLOSING.contains(&c) && !TERMINATORS.contains(&c) {
+            output.push(start..i);
+            start = i;
+            count = 0;
+            terminated = false;
+        }
+
+        count += 1;
+
+        if c == '\n' || TERMINATORS.contains(&c) {
+            terminated = true;
+        }
+
+        if count >= MAX_SEGMENT {
+            let end = i + c.len_utf8();
+            output.push(start..end);
+            start = end;
+            count = 0;
+            terminated = false;
+        }
+    }
+
+    if start < text.len() {
+        output.push(start..text.len());
+    }
+
+    output
+}
+
+/// Split text into pages of roughly [`PAGE_SIZE`] characters, breaking pages
+/// between segments.
+pub fn pages(text: &str) -> Vec<Range<usize>> {
+    paginate(text, &[])
+}
+
+/// Split text into pages, also starting a new page at each of the given
+/// sorted byte offsets.
+fn paginate(text: &str, breaks: &[usize]) -> Vec<Range<usize>> {
+    let mut output = Vec::new();
+    let mut page = 0..0;
+    let mut count = 0;
+    let mut breaks = breaks.iter().copied().peekable();
+
+    for segment in segments(text) {
+        let len = text[segment.clone()].chars().count();
+        let mut is_break = false;
+
+        while breaks.next_if(|&b| b <= segment.start).is_some() {
+            is_break = true;
+        }
+
+        if count > 0 && (is_break || count + len > PAGE_SIZE) {
+            output.push(page.clone());
+            page = segment.start..segment.start;
+            count = 0;
+        }
+
+        page.end = segment.end;
+        count += len;
+    }
+
+    if !page.is_empty() {
+        output.push(page);
+    }
+
+    output
+}
+
+/// Find the 0-based page containing the given byte offset.
+pub fn page_of(pages: &[Range<usize>], offset: usize) -> usize {
+    pages
+        .iter()
+        .position(|p| p.contains(&offset))
+        .unwrap_or_default()
+}
+
+/// Documents stored in a directory.
+pub struct Documents {
+    dir: PathBuf,
+}
+
+impl Documents {
+    /// Access documents stored in the given directory.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            dir: dir.as_ref().to_owned(),
+        }
+    }
+
+    /// List the identifiers of all documents in ascending order.
+    pub fn ids(&self) -> Result<Vec<u64>> {
+        let mut ids = Vec::new();
+
+        let d = match fs::read_dir(&self.dir) {
+            Ok(d) => d,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(ids);
+            }
+            Err(e) => {
+                return Err(e).context("Could not read documents directory");
+            }
+        };
+
+        for e in d {
+            let path = e?.path();
+
+            if path.extension() != Some(EXTENSION.as_ref()) {
+                continue;
+            }
+
+            if let Some(id) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.parse().ok())
+            {
+                ids.push(id);
+            }
+        }
+
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// List all documents.
+    pub fn list(&self) -> Result<Vec<DocumentInfo>> {
+        let mut output = Vec::new();
+
+        for id in self.ids()? {
+            output.push(self.load(id)?.info(id));
+        }
+
+        Ok(output)
+    }
+
+    /// Add a document, returning its identifier.
+    pub fn add(&self, document: &Document) -> Result<u64> {
+        if document.text.trim().is_empty() {
+            bail!("Document is empty");
+        }
+
+        let id = self.ids()?.last().map_or(1, |id| id + 1);
+        self.save(id, document)?;
+        Ok(id)
+    }
+
+    /// Load the given document.
+    pub fn load(&self, id: u64) -> Result<Document> {
+        let path = self.path(id);
+
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                bail!("Missing document {id}");
+            }
+            Err(e) => {
+                return Err(e).with_context(|| path.display().to_string());
+            }
+        };
+
+        serde_json::from_slice(&data).with_context(|| path.display().to_string())
+    }
+
+    /// Save the given document.
+    pub fn save(&self, id: u64, document: &Document) -> Result<()> {
+        let data = serde_json::to_vec(document)?;
+        profile::write(&self.dir, &format!("{id}.{EXTENSION}"), &data)
+    }
+
+    /// Delete the given document.
+    pub fn delete(&self, id: u64) -> Result<()> {
+        match fs::remove_file(self.path(id)) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e).context("Could not remove document"),
+        }
+    }
+
+    fn path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("{id}.{EXTENSION}"))
+    }
+}
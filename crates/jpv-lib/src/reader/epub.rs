@@ -0,0 +1,542 @@
+//! Extraction of text from EPUB books.
+//!
+//! This only deals with the XML documents inside of a book, reading files out
+//! of the archive is left to the caller. The text of every document in the
+//! spine is concatenated, and entries in the table of contents are mapped to
+//! where their documents start.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use relative_path::RelativePath;
+use serde::{Deserialize, Serialize};
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+use super::Chapter;
+
+/// The path of the container file, which points to the package document.
+const CONTAINER: &str = "META-INF/container.xml";
+
+/// How ruby annotations are handled when extracting text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ruby {
+    /// Remove readings, only keeping the annotated text.
+    #[default]
+    Strip,
+    /// Keep readings in parenthesis after the annotated text.
+    Preserve,
+}
+
+/// The text extracted from a book.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Book {
+    pub title: String,
+    pub text: String,
+    pub chapters: Vec<Chapter>,
+}
+
+/// Read a book, using `open` to read files from the archive by their path.
+pub fn read<F>(mut open: F, ruby: Ruby) -> Result<Book>
+where
+    F: FnMut(&str) -> Result<Vec<u8>>,
+{
+    let container = utf8(open(CONTAINER)?, CONTAINER)?;
+    let package_path = rootfile(&container)?;
+    let package = utf8(open(&package_path)?, &package_path)?;
+    let package = Package::parse(&package, &package_path).context("Reading package")?;
+
+    let mut book = Book {
+        title: package.title.unwrap_or_default(),
+        ..Book::default()
+    };
+
+    let mut starts = HashMap::new();
+
+    for path in &package.spine {
+        let data = utf8(open(path)?, path)?;
+        let text = text(&data, ruby).with_context(|| path.clone())?;
+
+        if text.is_empty() {
+            continue;
+        }
+
+        if !book.text.is_empty() && !book.text.ends_with('\n') {
+            book.text.push('\n');
+        }
+
+        starts.insert(path.as_str(), book.text.len());
+        book.text.push_str(&text);
+    }
+
+    let toc = match (&package.nav, &package.ncx) {
+        (Some(path), _) => nav(&utf8(open(path)?, path)?, path)?,
+        (None, Some(path)) => ncx(&utf8(open(path)?, path)?, path)?,
+        (None, None) => Vec::new(),
+    };
+
+    for (title, path) in toc {
+        let Some(&start) = starts.get(path.as_str()) else {
+            continue;
+        };
+
+        if book.chapters.iter().any(|c| c.start == start) {
+            continue;
+        }
+
+        book.chapters.push(Chapter { title, start });
+    }
+
+    book.chapters.sort_by_key(|c| c.start);
+    Ok(book)
+}
+
+/// Extract the text of an XHTML document.
+///
+/// Block elements are put on lines of their own, and whitespace in the
+/// source which spans lines is removed since it isn't significant in
+/// Japanese.
+pub fn text(input: &str, ruby: Ruby) -> Result<String> {
+    let mut o = String::new();
+    let mut stack = Vec::new();
+    let mut skip = 0usize;
+    let mut readings = Vec::<String>::new();
+
+    for token in Tokenizer::from(input) {
+        match token? {
+            Token::ElementStart { local, .. } => {
+                let name = local.as_str();
+                let skipped = skip > 0 || is_skipped(name, ruby);
+
+                if skipped {
+                    skip += 1;
+                }
+
+                if !skipped {
+                    match name {
+                        "ruby" => readings.push(String::new()),
+                        "br" => o.push('\n'),
+                        name if is_block(name) => newline(&mut o),
+                        _ => {}
+                    }
+                }
+
+                stack.push((name, skipped));
+            }
+            Token::ElementEnd { end, .. } => {
+                let name = match end {
+                    ElementEnd::Open => continue,
+                    ElementEnd::Close(_, local) => local.as_str(),
+                    ElementEnd::Empty => match stack.last() {
+                        Some(&(name, _)) => name,
+                        None => continue,
+                    },
+                };
+
+                let Some((_, skipped)) = stack.pop() else {
+                    continue;
+                };
+
+                if skipped {
+                    skip -= 1;
+                    continue;
+                }
+
+                match name {
+                    "ruby" => {
+                        let reading = readings.pop().unwrap_or_default();
+
+                        if !reading.is_empty() {
+                            o.push('（');
+                            o.push_str(&reading);
+                            o.push('）');
+                        }
+                    }
+                    name if is_block(name) => newline(&mut o),
+                    _ => {}
+                }
+            }
+            Token::Text { text } | Token::Cdata { text, .. } if skip == 0 => {
+                let in_rt = stack.iter().any(|&(name, _)| name == "rt");
+
+                let out = match readings.last_mut() {
+                    Some(reading) if in_rt => reading,
+                    _ => &mut o,
+                };
+
+                push_text(out, text.as_str());
+            }
+            _ => {}
+        }
+    }
+
+    let len = o.trim_end().len();
+    o.truncate(len);
+    Ok(o)
+}
+
+/// Find the path of the package document in the container file.
+fn rootfile(input: &str) -> Result<String> {
+    for token in Tokenizer::from(input) {
+        if let Token::Attribute { local, value, .. } = token? {
+            if local.as_str() == "full-path" {
+                return Ok(unescape(value.as_str()));
+            }
+        }
+    }
+
+    Err(anyhow::anyhow!("Missing rootfile in {CONTAINER}"))
+}
+
+/// The parts of a package document which are needed to read a book.
+struct Package {
+    title: Option<String>,
+    /// Paths of the documents making up the book, in reading order.
+    spine: Vec<String>,
+    /// Path of the EPUB 3 navigation document.
+    nav: Option<String>,
+    /// Path of the EPUB 2 table of contents.
+    ncx: Option<String>,
+}
+
+struct Item {
+    href: String,
+    media_type: String,
+    properties: String,
+}
+
+impl Package {
+    fn parse(input: &str, path: &str) -> Result<Self> {
+        let mut title = None;
+        let mut items = HashMap::new();
+        let mut spine = Vec::new();
+        let mut toc = None;
+
+        let mut element = "";
+        let mut attributes = HashMap::new();
+        let mut in_title = false;
+
+        let mut finish = |element: &str, attributes: &mut HashMap<&str, String>| {
+            let mut take = |key: &str| attributes.remove(key).unwrap_or_default();
+
+            match element {
+                "item" => {
+                    let id = take("id");
+
+                    let item = Item {
+                        href: take("href"),
+                        media_type: take("media-type"),
+                        properties: take("properties"),
+                    };
+
+                    items.insert(id, item);
+                }
+                "itemref" if take("linear") != "no" => {
+                    spine.push(take("idref"));
+                }
+                "spine" => {
+                    toc = attributes.remove("toc");
+                }
+                _ => {}
+            }
+
+            attributes.clear();
+        };
+
+        for token in Tokenizer::from(input) {
+            match token? {
+                Token::ElementStart { local, .. } => {
+                    element = local.as_str();
+                    in_title = element == "title";
+                }
+                Token::Attribute { local, value, .. } => {
+                    attributes.insert(local.as_str(), unescape(value.as_str()));
+                }
+                Token::ElementEnd { .. } => {
+                    finish(element, &mut attributes);
+                    element = "";
+                }
+                Token::Text { text } if in_title && title.is_none() => {
+                    let text = unescape(text.as_str()).trim().to_owned();
+
+                    if !text.is_empty() {
+                        title = Some(text);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let resolve = |href: &str| resolve(path, href);
+
+        let spine = spine
+            .iter()
+            .filter_map(|id| items.get(id))
+            .map(|item| resolve(&item.href))
+            .collect();
+
+        let nav = items
+            .values()
+            .find(|item| item.properties.split_whitespace().any(|p| p == "nav"))
+            .map(|item| resolve(&item.href));
+
+        let ncx = toc
+            .and_then(|id| items.get(&id))
+            .or_else(|| {
+                items
+                    .values()
+                    .find(|item| item.media_type == "application/x-dtbncx+xml")
+            })
+            .map(|item| resolve(&item.href));
+
+        Ok(Self {
+            title,
+            spine,
+            nav,
+            ncx,
+        })
+    }
+}
+
+/// Read the table of contents from an EPUB 3 navigation document, as pairs
+/// of titles and document paths.
+fn nav(input: &str, path: &str) -> Result<Vec<(String, String)>> {
+    let mut output = Vec::new();
+    let mut depth = 0usize;
+    let mut toc = None;
+    let mut link = None::<(String, String)>;
+    let mut is_nav = false;
+
+    for token in Tokenizer::from(input) {
+        match token? {
+            Token::ElementStart { local, .. } => {
+                depth += 1;
+                is_nav = local.as_str() == "nav";
+
+                if local.as_str() == "a" && toc.is_some() {
+                    link = Some((String::new(), String::new()));
+                }
+            }
+            Token::Attribute { local, value, .. } => match local.as_str() {
+                "type" if is_nav && value.as_str().split_whitespace().any(|t| t == "toc") => {
+                    toc = Some(depth);
+                }
+                "href" => {
+                    if let Some((_, href)) = &mut link {
+                        *href = resolve(path, &unescape(value.as_str()));
+                    }
+                }
+                _ => {}
+            },
+            Token::ElementEnd { end, .. } => {
+                if matches!(end, ElementEnd::Open) {
+                    continue;
+                }
+
+                if let ElementEnd::Close(_, local) = end {
+                    if local.as_str() == "a" {
+                        if let Some((title, href)) = link.take() {
+                            output.push((title.trim().to_owned(), href));
+                        }
+                    }
+                }
+
+                if toc == Some(depth) {
+                    toc = None;
+                }
+
+                depth = depth.saturating_sub(1);
+            }
+            Token::Text { text } => {
+                if let Some((title, _)) = &mut link {
+                    push_text(title, text.as_str());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(output)
+}
+
+/// Read the table of contents from an EPUB 2 NCX document, as pairs of titles
+/// and document paths.
+fn ncx(input: &str, path: &str) -> Result<Vec<(String, String)>> {
+    let mut output = Vec::new();
+    let mut element = "";
+    let mut title = String::new();
+
+    for token in Tokenizer::from(input) {
+        match token? {
+            Token::ElementStart { local, .. } => {
+                element = local.as_str();
+
+                if element == "navPoint" {
+                    title.clear();
+                }
+            }
+            Token::Attribute { local, value, .. }
+                if element == "content" && local.as_str() == "src" =>
+            {
+                let href = resolve(path, &unescape(value.as_str()));
+                output.push((title.trim().to_owned(), href));
+            }
+            Token::Text { text } if element == "text" => {
+                push_text(&mut title, text.as_str());
+            }
+            Token::ElementEnd { end, .. } if !matches!(end, ElementEnd::Open) => {
+                element = "";
+            }
+            _ => {}
+        }
+    }
+
+    Ok(output)
+}
+
+fn utf8(data: Vec<u8>, path: &str) -> Result<String> {
+    String::from_utf8(data).with_context(|| format!("{path}: Not UTF-8"))
+}
+
+/// Test if an element is skipped when extracting text.
+pub(super) fn is_skipped(name: &str, ruby: Ruby) -> bool {
+    match name {
+        "head" | "script" | "style" | "rp" => true,
+        "rt" => ruby == Ruby::Strip,
+        _ => false,
+    }
+}
+
+/// Test if an element is a block which is put on its own line.
+pub(super) fn is_block(name: &str) -> bool {
+    matches!(
+        name,
+        "p" | "div"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "li"
+            | "tr"
+            | "blockquote"
+            | "section"
+            | "article"
+            | "hr"
+    )
+}
+
+/// Start a new line unless already at the start of one.
+pub(super) fn newline(o: &mut String) {
+    if !o.is_empty() && !o.ends_with('\n') {
+        o.push('\n');
+    }
+}
+
+/// Push text, removing whitespace which spans lines in the source.
+pub(super) fn push_text(o: &mut String, text: &str) {
+    let text = unescape(text);
+    let mut lines = text.split('\n');
+
+    if let Some(first) = lines.next() {
+        let first = first.trim_end_matches([' ', '\t', '\r']);
+
+        if o.ends_with('\n') {
+            o.push_str(first.trim_start());
+        } else {
+            o.push_str(first);
+        }
+    }
+
+    for line in lines {
+        o.push_str(line.trim_matches([' ', '\t', '\r']));
+    }
+}
+
+/// Resolve a link relative to the document it's found in, dropping any
+/// fragment.
+fn resolve(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or_default();
+    let href = percent_decode(href);
+
+    let dir = RelativePath::new(base)
+        .parent()
+        .unwrap_or(RelativePath::new(""));
+    dir.join_normalized(href).into_string()
+}
+
+fn percent_decode(input: &str) -> String {
+    let input = input.as_bytes();
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut n = 0;
+
+    while let Some(&b) = input.get(n) {
+        let decoded = (b == b'%')
+            .then(|| input.get(n + 1..n + 3))
+            .flatten()
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+
+        match decoded {
+            Some(b) => {
+                bytes.push(b);
+                n += 3;
+            }
+            None => {
+                bytes.push(b);
+                n += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Decode character and entity references.
+fn unescape(input: &str) -> String {
+    let mut o = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(n) = rest.find('&') {
+        o.push_str(&rest[..n]);
+        rest = &rest[n..];
+
+        let Some(end) = rest.find(';').filter(|&end| end <= 10) else {
+            o.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+
+        let c = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            entity => match entity.strip_prefix('#') {
+                Some(n) => match n.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => n.parse().ok(),
+                }
+                .and_then(char::from_u32),
+                None => None,
+            },
+        };
+
+        match c {
+            Some(c) => {
+                o.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                o.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    o.push_str(rest);
+    o
+}
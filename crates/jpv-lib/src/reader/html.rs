@@ -0,0 +1,179 @@
+//! Extraction of text from HTML, like what's copied from a web page.
+//!
+//! Unlike the documents in a book, HTML copied to the clipboard is rarely
+//! well-formed XML, so tags are scanned for leniently and unknown or
+//! mismatched tags are ignored rather than failing.
+
+use super::epub::{is_block, is_skipped, newline, push_text, Ruby};
+
+/// Elements which never have content or a closing tag.
+const VOID: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Extract the text of an HTML fragment or document.
+///
+/// Block elements are put on lines of their own like with EPUB documents,
+/// and how readings in ruby annotations are handled is decided by `ruby`.
+pub fn text(input: &str, ruby: Ruby) -> String {
+    let mut x = Extract {
+        o: String::new(),
+        text: String::new(),
+        stack: Vec::new(),
+        skip: 0,
+        readings: Vec::new(),
+        ruby,
+    };
+
+    let mut rest = input;
+
+    while let Some(n) = rest.find('<') {
+        x.text(&rest[..n]);
+        rest = &rest[n..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let is_tag = matches!(
+            rest[1..].chars().next(),
+            Some(c) if c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?')
+        );
+
+        let end = rest.find('>').filter(|_| is_tag);
+
+        let Some(end) = end else {
+            x.text("<");
+            rest = &rest[1..];
+            continue;
+        };
+
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with(['!', '?']) {
+            continue;
+        }
+
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+
+        let name = tag
+            .split(|c: char| c.is_ascii_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if closing {
+            x.close(&name);
+        } else {
+            let empty = tag.ends_with('/') || VOID.contains(&name.as_str());
+            x.open(name.clone());
+
+            if empty {
+                x.close(&name);
+            }
+        }
+    }
+
+    x.text(rest);
+    x.flush();
+
+    let mut o = x.o;
+    let len = o.trim_end().len();
+    o.truncate(len);
+    o
+}
+
+struct Extract {
+    o: String,
+    /// Text which hasn't been pushed yet, so that text interrupted by a `<`
+    /// which doesn't start a tag is pushed in one piece.
+    text: String,
+    stack: Vec<(String, bool)>,
+    skip: usize,
+    readings: Vec<String>,
+    ruby: Ruby,
+}
+
+impl Extract {
+    fn open(&mut self, name: String) {
+        self.flush();
+        let skipped = self.skip > 0 || is_skipped(&name, self.ruby);
+
+        if skipped {
+            self.skip += 1;
+        } else {
+            match name.as_str() {
+                "ruby" => self.readings.push(String::new()),
+                "br" => self.o.push('\n'),
+                name if is_block(name) => newline(&mut self.o),
+                _ => {}
+            }
+        }
+
+        self.stack.push((name, skipped));
+    }
+
+    /// Close the most recently opened element with the given name, along
+    /// with any elements opened inside of it which weren't closed.
+    fn close(&mut self, name: &str) {
+        self.flush();
+
+        if !self.stack.iter().any(|(open, _)| open == name) {
+            return;
+        }
+
+        while let Some((open, skipped)) = self.stack.pop() {
+            if skipped {
+                self.skip -= 1;
+            } else {
+                match open.as_str() {
+                    "ruby" => {
+                        let reading = self.readings.pop().unwrap_or_default();
+
+                        if !reading.is_empty() {
+                            self.o.push('（');
+                            self.o.push_str(&reading);
+                            self.o.push('）');
+                        }
+                    }
+                    // Line breaks are pushed when they're opened.
+                    "br" => {}
+                    open if is_block(open) => newline(&mut self.o),
+                    _ => {}
+                }
+            }
+
+            if open == name {
+                break;
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.skip == 0 {
+            self.text.push_str(text);
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.text.is_empty() {
+            return;
+        }
+
+        let text = std::mem::take(&mut self.text);
+        let in_rt = self.stack.iter().any(|(name, _)| name == "rt");
+
+        let out = match self.readings.last_mut() {
+            Some(reading) if in_rt => reading,
+            _ => &mut self.o,
+        };
+
+        push_text(out, &text);
+    }
+}
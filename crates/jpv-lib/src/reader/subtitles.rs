@@ -0,0 +1,229 @@
+//! Parsing of subtitle files.
+//!
+//! Both SubRip (`.srt`) and Advanced SubStation Alpha (`.ass` and `.ssa`)
+//! subtitles are supported. Styling is removed, only the timing and the text
+//! of each line is kept.
+
+use anyhow::{bail, Context, Result};
+
+/// A single timed subtitle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    /// Milliseconds from the start when the subtitle is shown.
+    pub start: u64,
+    /// Milliseconds from the start when the subtitle is hidden.
+    pub end: u64,
+    pub text: String,
+}
+
+/// Parse subtitles, using the file name to determine the format.
+///
+/// The returned cues are sorted by when they are shown.
+pub fn parse(name: &str, input: &str) -> Result<Vec<Cue>> {
+    let input = input.trim_start_matches('\u{feff}');
+    let name = name.to_lowercase();
+
+    let mut cues = if name.ends_with(".ass") || name.ends_with(".ssa") {
+        parse_ass(input)?
+    } else if name.ends_with(".srt") {
+        parse_srt(input)?
+    } else {
+        bail!("Unsupported subtitle format: {name}");
+    };
+
+    cues.retain(|c| !c.text.is_empty());
+    cues.sort_by_key(|c| (c.start, c.end));
+    Ok(cues)
+}
+
+/// Parse SubRip subtitles.
+pub fn parse_srt(input: &str) -> Result<Vec<Cue>> {
+    let mut cues = Vec::new();
+    let mut lines = input.lines().map(str::trim).enumerate().peekable();
+
+    while let Some((n, line)) = lines.next() {
+        let Some((start, end)) = line.split_once("-->") else {
+            continue;
+        };
+
+        let start = parse_time(start.trim()).with_context(|| format!("Line {}", n + 1))?;
+        let end = parse_time(end.split_whitespace().next().unwrap_or_default())
+            .with_context(|| format!("Line {}", n + 1))?;
+
+        let mut text = String::new();
+
+        while let Some((_, line)) = lines.next_if(|(_, line)| !line.is_empty()) {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+
+            strip_tags(&mut text, line);
+        }
+
+        cues.push(Cue {
+            start,
+            end,
+            text: text.trim().to_owned(),
+        });
+    }
+
+    Ok(cues)
+}
+
+/// Parse Advanced SubStation Alpha subtitles.
+pub fn parse_ass(input: &str) -> Result<Vec<Cue>> {
+    let mut cues = Vec::new();
+    let mut in_events = false;
+    let mut format = Vec::new();
+
+    for (n, line) in input.lines().enumerate() {
+        let line = line.trim();
+
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+
+        if !in_events {
+            continue;
+        }
+
+        let Some((kind, rest)) = line.split_once(':') else {
+            continue;
+        };
+
+        match kind {
+            "Format" => {
+                format = rest.split(',').map(|f| f.trim().to_lowercase()).collect();
+            }
+            "Dialogue" => {
+                let field = |name: &str| format.iter().position(|f| f == name);
+
+                let (Some(start), Some(end), Some(text)) =
+                    (field("start"), field("end"), field("text"))
+                else {
+                    bail!("Line {}: Dialogue before a valid format", n + 1);
+                };
+
+                // The text is the last field, and may itself contain commas.
+                let fields = rest.splitn(format.len(), ',').collect::<Vec<_>>();
+
+                let get = |index: usize| {
+                    fields
+                        .get(index)
+                        .map(|f| f.trim())
+                        .with_context(|| format!("Line {}: Missing field", n + 1))
+                };
+
+                let start = parse_time(get(start)?).with_context(|| format!("Line {}", n + 1))?;
+                let end = parse_time(get(end)?).with_context(|| format!("Line {}", n + 1))?;
+
+                let mut output = String::new();
+                strip_overrides(&mut output, get(text)?);
+
+                cues.push(Cue {
+                    start,
+                    end,
+                    text: output.trim().to_owned(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(cues)
+}
+
+/// Parse a timestamp like `01:02:03,450`, `1:02:03.45` or `02:03` into
+/// milliseconds.
+pub fn parse_time(input: &str) -> Result<u64> {
+    let (rest, fraction) = match input.rsplit_once([',', '.']) {
+        Some((rest, fraction)) => (rest, Some(fraction)),
+        None => (input, None),
+    };
+
+    let mut seconds = 0u64;
+    let mut parts = 0;
+
+    for part in rest.split(':') {
+        let value = part
+            .trim()
+            .parse::<u64>()
+            .with_context(|| format!("Invalid timestamp: {input}"))?;
+        seconds = seconds * 60 + value;
+        parts += 1;
+    }
+
+    if parts > 3 {
+        bail!("Invalid timestamp: {input}");
+    }
+
+    let millis = match fraction {
+        Some(fraction) if !fraction.is_empty() && fraction.len() <= 3 => {
+            let value = fraction
+                .parse::<u64>()
+                .with_context(|| format!("Invalid timestamp: {input}"))?;
+            value * 10u64.pow(3 - fraction.len() as u32)
+        }
+        Some(..) => bail!("Invalid timestamp: {input}"),
+        None => 0,
+    };
+
+    Ok(seconds * 1000 + millis)
+}
+
+/// Format milliseconds as a timestamp like `1:02:03` or `02:03`.
+pub fn format_time(millis: u64) -> String {
+    let seconds = millis / 1000;
+    let (h, m, s) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m:02}:{s:02}")
+    }
+}
+
+/// Strip HTML-like formatting tags and override blocks from SubRip text.
+fn strip_tags(o: &mut String, input: &str) {
+    let mut rest = input;
+
+    while let Some(n) = rest.find(['<', '{']) {
+        let close = if rest.as_bytes()[n] == b'<' { '>' } else { '}' };
+
+        match rest[n..].find(close) {
+            Some(end) => {
+                o.push_str(&rest[..n]);
+                rest = &rest[n + end + 1..];
+            }
+            None => break,
+        }
+    }
+
+    o.push_str(rest);
+}
+
+/// Strip override blocks and escapes from SubStation Alpha text.
+fn strip_overrides(o: &mut String, input: &str) {
+    let mut it = input.chars();
+
+    while let Some(c) = it.next() {
+        match c {
+            '{' => {
+                for c in it.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+            }
+            '\\' => match it.clone().next() {
+                Some('N' | 'n' | 'h') => {
+                    it.next();
+                    o.push(' ');
+                }
+                _ => o.push(c),
+            },
+            c => o.push(c),
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+
+use super::epub::{self, Ruby};
+use super::html;
+use super::subtitles;
+use super::{pages, segments, Chapter, Document, Documents, TocEntry, MAX_SEGMENT, PAGE_SIZE};
+
+fn split<'a>(text: &'a str, ranges: &[std::ops::Range<usize>]) -> Vec<&'a str> {
+    ranges.iter().map(|r| &text[r.clone()]).collect()
+}
+
+#[test]
+fn sentences() {
+    let text = "猫が好き。「本当？」と聞いた！？\n次の行";
+    assert_eq!(
+        split(text, &segments(text)),
+        ["猫が好き。", "「本当？」", "と聞いた！？\n", "次の行"]
+    );
+
+    let long = "あ".repeat(MAX_SEGMENT + 10);
+    let segments = segments(&long);
+    assert_eq!(segments.len(), 2);
+    assert_eq!(long[segments[0].clone()].chars().count(), MAX_SEGMENT);
+}
+
+#[test]
+fn paging() {
+    assert!(pages("").is_empty());
+
+    let sentence = format!("{}。", "あ".repeat(99));
+    let text = sentence.repeat(25);
+    let pages = pages(&text);
+
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[0].start, 0);
+    assert_eq!(pages[2].end, text.len());
+
+    for w in pages.windows(2) {
+        assert_eq!(w[0].end, w[1].start);
+    }
+
+    assert_eq!(text[pages[0].clone()].chars().count(), PAGE_SIZE);
+}
+
+#[test]
+fn store() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    let documents = Documents::new(dir);
+
+    assert!(documents.list().unwrap().is_empty());
+    assert!(documents.add(&Document::new("empty", " \n", 1)).is_err());
+
+    let first = documents
+        .add(&Document::new(
+            " 猫 ",
+            "吾輩は猫である。\r\n名前はまだ無い。",
+            1,
+        ))
+        .unwrap();
+    let second = documents.add(&Document::new("犬", "犬", 2)).unwrap();
+    assert_eq!((first, second), (1, 2));
+
+    let mut document = documents.load(first).unwrap();
+    assert_eq!(document.title, "猫");
+    assert_eq!(document.text, "吾輩は猫である。\n名前はまだ無い。");
+
+    document.position = 1;
+    documents.save(first, &document).unwrap();
+
+    let list = documents.list().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0], document.info(first));
+    assert_eq!(list[0].pages, 1);
+    assert_eq!(list[0].position, 1);
+
+    documents.delete(first).unwrap();
+    assert!(documents.load(first).is_err());
+    assert_eq!(documents.add(&Document::new("鳥", "鳥", 3)).unwrap(), 3);
+}
+
+#[test]
+fn chapters() {
+    let mut document = Document::new("本", &"あ。".repeat(10), 0);
+    document.chapters = vec![
+        Chapter {
+            title: "一".to_owned(),
+            start: 0,
+        },
+        Chapter {
+            title: "二".to_owned(),
+            start: "あ。".len() * 4,
+        },
+    ];
+
+    let pages = document.pages();
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[1].start, "あ。".len() * 4);
+
+    assert_eq!(
+        document.toc(&pages),
+        [
+            TocEntry {
+                title: "一".to_owned(),
+                page: 0
+            },
+            TocEntry {
+                title: "二".to_owned(),
+                page: 1
+            },
+        ]
+    );
+}
+
+#[test]
+fn xhtml() {
+    let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml">
+<head><title>無視</title><style>p { margin: 0; }</style></head>
+<body>
+  <h1>第一章</h1>
+  <p><ruby>吾輩<rp>(</rp><rt>わがはい</rt><rp>)</rp></ruby>は猫
+    である。</p>
+  <p>名前は&amp;まだ<br/>無い。&#x3042;</p>
+</body>
+</html>"#;
+
+    assert_eq!(
+        epub::text(input, Ruby::Strip).unwrap(),
+        "第一章\n吾輩は猫である。\n名前は&まだ\n無い。あ"
+    );
+
+    assert_eq!(
+        epub::text(input, Ruby::Preserve).unwrap(),
+        "第一章\n吾輩（わがはい）は猫である。\n名前は&まだ\n無い。あ"
+    );
+}
+
+#[test]
+fn html_fragments() {
+    let input = "<html><head><meta charset='utf-8'><style>p { margin: 0; }</style></head><body>\
+<!--StartFragment--><div><ruby>吾輩<rp>(</rp><rt>わがはい</rt><rp>)</rp></ruby>は<b>猫</div>\
+<p>1 < 2 &amp; 名前は<br>まだ無い。</p><script>if (a<b) {}</script><!--EndFragment--></body></html>";
+
+    assert_eq!(
+        html::text(input, Ruby::Strip),
+        "吾輩は猫\n1 < 2 & 名前は\nまだ無い。"
+    );
+
+    assert_eq!(
+        html::text(input, Ruby::Preserve),
+        "吾輩（わがはい）は猫\n1 < 2 & 名前は\nまだ無い。"
+    );
+
+    assert_eq!(html::text("猫<RUBY>犬<RT>いぬ</ruby>", Ruby::Strip), "猫犬");
+    assert_eq!(html::text("</p>未完成の<", Ruby::Strip), "未完成の<");
+}
+
+const CONTAINER: &str = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles>
+<rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+</rootfiles>
+</container>"#;
+
+const PACKAGE: &str = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:title>吾輩は猫である</dc:title>
+</metadata>
+<manifest>
+<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+<item id="ncx" href="toc.ncx" media-type="application/x-dtbncx+xml"/>
+<item id="cover" href="text/cover.xhtml" media-type="application/xhtml+xml"/>
+<item id="c1" href="text/chapter%201.xhtml" media-type="application/xhtml+xml"/>
+<item id="c2" href="text/chapter2.xhtml" media-type="application/xhtml+xml"/>
+</manifest>
+<spine toc="ncx">
+<itemref idref="cover" linear="no"/>
+<itemref idref="c1"/>
+<itemref idref="c2"/>
+</spine>
+</package>"#;
+
+const NAV: &str = r#"<?xml version="1.0"?>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<body>
+<nav epub:type="landmarks"><ol><li><a href="text/cover.xhtml">表紙</a></li></ol></nav>
+<nav epub:type="toc"><ol>
+<li><a href="text/chapter%201.xhtml"><span>一</span></a></li>
+<li><a href="text/chapter2.xhtml#start">二</a></li>
+</ol></nav>
+</body>
+</html>"#;
+
+const NCX: &str = r#"<?xml version="1.0"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/">
+<navMap>
+<navPoint id="p1"><navLabel><text>第一</text></navLabel><content src="text/chapter%201.xhtml"/></navPoint>
+<navPoint id="p2"><navLabel><text>第二</text></navLabel><content src="text/chapter2.xhtml"/></navPoint>
+</navMap>
+</ncx>"#;
+
+fn files() -> HashMap<&'static str, String> {
+    let files = [
+        ("META-INF/container.xml", CONTAINER),
+        ("OEBPS/content.opf", PACKAGE),
+        ("OEBPS/nav.xhtml", NAV),
+        ("OEBPS/toc.ncx", NCX),
+        (
+            "OEBPS/text/cover.xhtml",
+            "<html><body><p>表紙</p></body></html>",
+        ),
+        (
+            "OEBPS/text/chapter 1.xhtml",
+            "<html><body><p>一つ目。</p></body></html>",
+        ),
+        (
+            "OEBPS/text/chapter2.xhtml",
+            "<html><body><p>二つ目。</p></body></html>",
+        ),
+    ];
+
+    files
+        .into_iter()
+        .map(|(path, data)| (path, data.to_owned()))
+        .collect()
+}
+
+fn read(files: &HashMap<&str, String>) -> anyhow::Result<epub::Book> {
+    epub::read(
+        |path| {
+            let data = files.get(path).with_context(|| format!("Missing {path}"))?;
+            Ok(data.as_bytes().to_vec())
+        },
+        Ruby::Strip,
+    )
+}
+
+#[test]
+fn epub_book() {
+    let mut files = files();
+    let book = read(&files).unwrap();
+
+    assert_eq!(book.title, "吾輩は猫である");
+    assert_eq!(book.text, "一つ目。\n二つ目。");
+    assert_eq!(
+        book.chapters,
+        [
+            Chapter {
+                title: "一".to_owned(),
+                start: 0
+            },
+            Chapter {
+                title: "二".to_owned(),
+                start: "一つ目。\n".len()
+            },
+        ]
+    );
+
+    files.remove("OEBPS/nav.xhtml");
+    let package = PACKAGE.replace(" properties=\"nav\"", "");
+    files.insert("OEBPS/content.opf", package);
+
+    let book = read(&files).unwrap();
+    let titles = book
+        .chapters
+        .iter()
+        .map(|c| c.title.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["第一", "第二"]);
+
+    files.remove("OEBPS/text/chapter2.xhtml");
+    assert!(read(&files).is_err());
+}
+
+const SRT: &str = "\u{feff}1
+00:00:01,000 --> 00:00:02,500
+<i>こんにちは</i>
+世界
+
+2
+00:01:00,000 --> 00:01:03,000 X1:0
+{\\an8}さようなら
+";
+
+const ASS: &str = r"[Script Info]
+Title: テスト
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,無視
+Dialogue: 0,0:00:05.50,0:00:07.00,Default,,0,0,0,,{\i1}二番目{\i0}、です\Nね
+Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,一番目
+";
+
+#[test]
+fn subtitle_files() {
+    let cues = subtitles::parse("show.srt", SRT).unwrap();
+    assert_eq!(cues.len(), 2);
+    assert_eq!((cues[0].start, cues[0].end), (1000, 2500));
+    assert_eq!(cues[0].text, "こんにちは 世界");
+    assert_eq!((cues[1].start, cues[1].end), (60000, 63000));
+    assert_eq!(cues[1].text, "さようなら");
+
+    let cues = subtitles::parse("show.ASS", ASS).unwrap();
+    let texts = cues.iter().map(|c| c.text.as_str()).collect::<Vec<_>>();
+    assert_eq!(texts, ["一番目", "二番目、です ね"]);
+    assert_eq!((cues[1].start, cues[1].end), (5500, 7000));
+
+    assert!(subtitles::parse("show.vtt", SRT).is_err());
+}
+
+#[test]
+fn timestamps() {
+    assert_eq!(subtitles::parse_time("01:02:03,450").unwrap(), 3723450);
+    assert_eq!(subtitles::parse_time("1:02:03.45").unwrap(), 3723450);
+    assert_eq!(subtitles::parse_time("02:03").unwrap(), 123000);
+    assert!(subtitles::parse_time("1:2:3:4").is_err());
+    assert!(subtitles::parse_time("a:00").is_err());
+
+    assert_eq!(subtitles::format_time(3723450), "1:02:03");
+    assert_eq!(subtitles::format_time(123000), "02:03");
+}
+
+#[test]
+fn timed_lines() {
+    let cues = subtitles::parse("show.srt", SRT).unwrap();
+    let document = Document::from_subtitles("番組", cues, 0);
+
+    assert_eq!(document.text, "こんにちは 世界\nさようなら\n");
+    assert_eq!(document.line_at(0).unwrap().start, 1000);
+    assert_eq!(document.line_at(59999).unwrap().start, 1000);
+    assert_eq!(document.line_at(60000).unwrap().start, 60000);
+
+    let found = document.search_lines("さよう").collect::<Vec<_>>();
+    assert_eq!(found.len(), 1);
+    assert_eq!(&document.text[found[0].range.clone()], "さようなら");
+
+    let pages = document.pages();
+    assert_eq!(pages.len(), 1);
+
+    let lines = document.page_lines(&pages[0]);
+    assert_eq!(lines, document.lines);
+}
@@ -0,0 +1,189 @@
+//! Audio recorded by the user and attached to phrases.
+//!
+//! Each recording is stored as two files in the recordings directory, named
+//! after its numerical identifier: the audio itself and a JSON file
+//! describing it. The size of individual recordings and the total size of
+//! all recordings are limited so that the directory doesn't grow unbounded.
+
+#[cfg(test)]
+mod tests;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::profile;
+
+/// The maximum size of a single recording.
+pub const MAX_RECORDING_SIZE: usize = 2 * 1024 * 1024;
+/// The maximum size of all recordings.
+pub const MAX_TOTAL_SIZE: u64 = 256 * 1024 * 1024;
+/// The maximum number of recordings attached to a single phrase.
+pub const MAX_PER_PHRASE: usize = 10;
+/// Extension of recording descriptions.
+const EXTENSION: &str = "json";
+/// Extension of recorded audio.
+const AUDIO_EXTENSION: &str = "audio";
+
+/// A stored recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recording {
+    pub id: u64,
+    /// Sequence of the phrase the recording is attached to.
+    pub sequence: u64,
+    /// The mime type of the audio, like `audio/webm`.
+    pub mime: String,
+    /// Size of the audio in bytes.
+    pub size: u64,
+    /// Seconds since the unix epoch when the recording was added.
+    pub added: u64,
+}
+
+/// Recordings stored in a directory.
+pub struct Recordings {
+    dir: PathBuf,
+}
+
+impl Recordings {
+    /// Access recordings stored in the given directory.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            dir: dir.as_ref().to_owned(),
+        }
+    }
+
+    /// List all recordings in ascending order of identifier.
+    pub fn list(&self) -> Result<Vec<Recording>> {
+        let mut output = Vec::new();
+
+        let d = match fs::read_dir(&self.dir) {
+            Ok(d) => d,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(output);
+            }
+            Err(e) => {
+                return Err(e).context("Could not read recordings directory");
+            }
+        };
+
+        for e in d {
+            let path = e?.path();
+
+            if path.extension() != Some(EXTENSION.as_ref()) {
+                continue;
+            }
+
+            let data = fs::read(&path).with_context(|| path.display().to_string())?;
+            let recording: Recording =
+                serde_json::from_slice(&data).with_context(|| path.display().to_string())?;
+            output.push(recording);
+        }
+
+        output.sort_by_key(|r| r.id);
+        Ok(output)
+    }
+
+    /// List the recordings attached to the phrase with the given sequence.
+    pub fn for_sequence(&self, sequence: u64) -> Result<Vec<Recording>> {
+        let mut recordings = self.list()?;
+        recordings.retain(|r| r.sequence == sequence);
+        Ok(recordings)
+    }
+
+    /// Add a recording to the phrase with the given sequence.
+    pub fn add(&self, sequence: u64, mime: &str, data: &[u8], added: u64) -> Result<Recording> {
+        if !mime.starts_with("audio/") {
+            bail!("Unsupported recording type `{mime}`");
+        }
+
+        if data.is_empty() {
+            bail!("Recording is empty");
+        }
+
+        if data.len() > MAX_RECORDING_SIZE {
+            bail!(
+                "Recording is larger than the limit of {} KiB",
+                MAX_RECORDING_SIZE / 1024
+            );
+        }
+
+        let recordings = self.list()?;
+
+        if recordings.iter().filter(|r| r.sequence == sequence).count() >= MAX_PER_PHRASE {
+            bail!("Phrases can have at most {MAX_PER_PHRASE} recordings");
+        }
+
+        let total = recordings.iter().map(|r| r.size).sum::<u64>();
+
+        if total + data.len() as u64 > MAX_TOTAL_SIZE {
+            bail!(
+                "Recordings would exceed the limit of {} MiB, delete some first",
+                MAX_TOTAL_SIZE / 1024 / 1024
+            );
+        }
+
+        let recording = Recording {
+            id: recordings.last().map_or(1, |r| r.id + 1),
+            sequence,
+            mime: mime.to_owned(),
+            size: data.len() as u64,
+            added,
+        };
+
+        let id = recording.id;
+        profile::write(&self.dir, &format!("{id}.{AUDIO_EXTENSION}"), data)?;
+        profile::write(
+            &self.dir,
+            &format!("{id}.{EXTENSION}"),
+            &serde_json::to_vec(&recording)?,
+        )?;
+        Ok(recording)
+    }
+
+    /// Load the given recording and its audio.
+    pub fn load(&self, id: u64) -> Result<(Recording, Vec<u8>)> {
+        let path = self.path(id, EXTENSION);
+
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                bail!("Missing recording {id}");
+            }
+            Err(e) => {
+                return Err(e).with_context(|| path.display().to_string());
+            }
+        };
+
+        let recording =
+            serde_json::from_slice(&data).with_context(|| path.display().to_string())?;
+
+        let path = self.path(id, AUDIO_EXTENSION);
+        let audio = fs::read(&path).with_context(|| path.display().to_string())?;
+        Ok((recording, audio))
+    }
+
+    /// Delete the given recording.
+    pub fn delete(&self, id: u64) -> Result<()> {
+        // Remove the description first, so that a partially deleted recording
+        // isn't listed.
+        for extension in [EXTENSION, AUDIO_EXTENSION] {
+            match fs::remove_file(self.path(id, extension)) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e).context("Could not remove recording"),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn path(&self, id: u64, extension: &str) -> PathBuf {
+        self.dir.join(format!("{id}.{extension}"))
+    }
+}
@@ -0,0 +1,43 @@
+use std::slice;
+
+use super::{Recordings, MAX_PER_PHRASE, MAX_RECORDING_SIZE};
+
+#[test]
+fn store() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    let recordings = Recordings::new(dir);
+
+    assert!(recordings.list().unwrap().is_empty());
+    assert!(recordings.add(1, "text/plain", b"data", 1).is_err());
+    assert!(recordings.add(1, "audio/webm", b"", 1).is_err());
+    assert!(recordings
+        .add(1, "audio/webm", &vec![0; MAX_RECORDING_SIZE + 1], 1)
+        .is_err());
+
+    let first = recordings.add(1, "audio/webm", b"first", 10).unwrap();
+    let second = recordings.add(2, "audio/ogg", b"second", 20).unwrap();
+    assert_eq!((first.id, second.id), (1, 2));
+    assert_eq!(first.size, 5);
+
+    assert_eq!(recordings.for_sequence(1).unwrap(), slice::from_ref(&first));
+
+    let (recording, audio) = recordings.load(second.id).unwrap();
+    assert_eq!(recording, second);
+    assert_eq!(audio, b"second");
+
+    recordings.delete(first.id).unwrap();
+    recordings.delete(first.id).unwrap();
+    assert!(recordings.load(first.id).is_err());
+    assert_eq!(recordings.list().unwrap(), slice::from_ref(&second));
+
+    let third = recordings.add(1, "audio/webm", b"third", 30).unwrap();
+    assert_eq!(third.id, 3);
+
+    for n in 1..MAX_PER_PHRASE {
+        recordings.add(3, "audio/webm", b"more", n as u64).unwrap();
+    }
+
+    assert!(recordings.add(3, "audio/webm", b"more", 100).is_ok());
+    assert!(recordings.add(3, "audio/webm", b"more", 100).is_err());
+}
@@ -46,11 +46,75 @@ pub enum Transform {
     Romaji,
 }
 
+/// The kind of text a segment is made up of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Romaji which can be converted to kana.
+    Romaji,
+    /// Hiragana.
+    Hiragana,
+    /// Katakana.
+    Katakana,
+    /// A latin letter which is not part of any romaji, like the `p` in
+    /// `apple`.
+    Latin,
+    /// Anything else, like numbers, punctuation or kanji.
+    Other,
+}
+
 /// Perform an analysis.
 pub fn analyze(input: &str) -> Analysis<'_> {
     Analysis { input }
 }
 
+/// Transform the romaji and kana in the input.
+///
+/// Words made up of latin letters are only transformed if they are entirely
+/// romaji, so that embedded English words like in `taberu apple` are left
+/// untouched. A lone `n` counts as romaji, since it might be completed into
+/// `na` or `nn` while it is being typed.
+pub fn transform(input: &str, transform: Transform) -> String {
+    let mut output = String::new();
+    let mut word = Vec::new();
+
+    let apply = |output: &mut String, segment: &Segment<'_>| {
+        output.push_str(match transform {
+            Transform::Hiragana => segment.hiragana(),
+            Transform::Katakana => segment.katakana(),
+            Transform::Romaji => segment.romanize(),
+        });
+    };
+
+    let flush = |output: &mut String, word: &mut Vec<Segment<'_>>| {
+        let is_romaji = word
+            .iter()
+            .all(|s| s.kind() == Kind::Romaji || s.string == "n");
+
+        for segment in word.drain(..) {
+            if is_romaji {
+                apply(output, &segment);
+            } else {
+                output.push_str(segment.string);
+            }
+        }
+    };
+
+    for segment in analyze(input) {
+        match segment.kind() {
+            Kind::Romaji | Kind::Latin => {
+                word.push(segment);
+            }
+            _ => {
+                flush(&mut output, &mut word);
+                apply(&mut output, &segment);
+            }
+        }
+    }
+
+    flush(&mut output, &mut word);
+    output
+}
+
 /// A string being analyzed.
 pub struct Analysis<'a> {
     input: &'a str,
@@ -120,6 +184,38 @@ pub struct Segment<'a> {
 }
 
 impl<'a> Segment<'a> {
+    /// The kind of text the segment is made up of.
+    pub fn kind(&self) -> Kind {
+        macro_rules! implement_match {
+            (
+                $((
+                    $n:expr,
+                    $hira:tt, $kata:tt,
+                    $(w = $w:expr,)*
+                ),)*
+                $(
+                    kana ($kana:expr, $(w = $kw:expr,)*),
+                )*
+            ) => {
+                match &self.string[..] {
+                    $(
+                        $hira => Kind::Hiragana,
+                        $kata => Kind::Katakana,
+                        $($w => Kind::Romaji,)*
+                    )*
+                    $(
+                        $kana => Kind::Katakana,
+                        $($kw => Kind::Romaji,)*
+                    )*
+                    string if string.chars().all(|c| c.is_ascii_alphabetic()) => Kind::Latin,
+                    _ => Kind::Other,
+                }
+            }
+        }
+
+        romaji_table!(implement_match)
+    }
+
     /// Convert the analyzed segment into hiragana.
     pub fn hiragana(&self) -> &'a str {
         macro_rules! implement_match {
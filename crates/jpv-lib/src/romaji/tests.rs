@@ -1,4 +1,4 @@
-use super::analyze;
+use super::{analyze, transform, Kind, Transform};
 
 #[test]
 fn segmentations() {
@@ -103,3 +103,28 @@ fn katakana() {
 
     romaji_table!(test);
 }
+
+#[test]
+fn kinds() {
+    let kinds = |input| analyze(input).map(|s| s.kind()).collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds("kaカか"),
+        [Kind::Romaji, Kind::Katakana, Kind::Hiragana]
+    );
+    assert_eq!(kinds("p3個"), [Kind::Latin, Kind::Other, Kind::Other]);
+}
+
+#[test]
+fn mixed() {
+    assert_eq!(
+        transform("taberu apple 3個", Transform::Hiragana),
+        "たべる apple 3個"
+    );
+    assert_eq!(transform("kaタカナ", Transform::Hiragana), "かたかな");
+    assert_eq!(
+        transform("kame to neko", Transform::Katakana),
+        "カメ ト ネコ"
+    );
+    assert_eq!(transform("nihon", Transform::Hiragana), "にほn");
+}
@@ -49,3 +49,34 @@ impl Ord for Weight {
         }
     }
 }
+
+/// The score of a candidate produced by analysis, along with the factors it
+/// is made up of so that the ranking can be explained.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    /// The combined score, where higher is better.
+    pub total: f32,
+    /// Boost from how frequently the best matching word is used.
+    pub frequency: f32,
+    /// Boost from the length of the candidate, which grows quadratically so
+    /// that longer matches are preferred.
+    pub length: f32,
+    /// Prior from the part of speech or kind of the best matching entry,
+    /// including a boost for inflected forms.
+    pub pos: f32,
+}
+
+impl Score {
+    /// Construct a score from its factors.
+    pub fn new(frequency: f32, chars: usize, pos: f32) -> Self {
+        let length = chars.min(10) as f32;
+        let length = length * length;
+
+        Self {
+            total: frequency * length * pos,
+            frequency,
+            length,
+            pos,
+        }
+    }
+}
@@ -0,0 +1,480 @@
+//! Daily counts of how the dictionary is used.
+//!
+//! Statistics never leave the machine they're collected on. They're stored as
+//! a single JSON file holding one set of counters per day, and days older than
+//! the configured retention are pruned as new activity is recorded.
+//!
+//! Text read through captures is also tracked per day and source, and every
+//! capture is appended to a CSV log which can be exported for analysis
+//! elsewhere.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write as _};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::profile;
+
+/// The name of the file statistics are stored in.
+const FILE: &str = "stats.json";
+/// The name of the file captures are logged to.
+const LOG_FILE: &str = "immersion.csv";
+/// The header of the capture log.
+const LOG_HEADER: &str = "timestamp,date,source,characters\n";
+/// Seconds in a day.
+const DAY: u64 = 24 * 60 * 60;
+/// The longest time between two captures from the same source which is
+/// counted as time spent reading.
+const IDLE: u64 = 5 * 60;
+
+/// The source of captures which aren't labelled with one.
+pub const DEFAULT_SOURCE: &str = "clipboard";
+
+/// A kind of activity which is counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    /// A search was performed.
+    Search,
+    /// Text or an image was captured from another application.
+    Capture,
+    /// A quiz question was answered.
+    Review,
+    /// A word was marked as being learned or as known.
+    Save,
+}
+
+/// Activity during a single day.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Day {
+    pub searches: u32,
+    pub captures: u32,
+    pub reviews: u32,
+    pub saved: u32,
+}
+
+impl Day {
+    /// Count the given activity.
+    pub fn add(&mut self, activity: Activity) {
+        let count = match activity {
+            Activity::Search => &mut self.searches,
+            Activity::Capture => &mut self.captures,
+            Activity::Review => &mut self.reviews,
+            Activity::Save => &mut self.saved,
+        };
+
+        *count = count.saturating_add(1);
+    }
+
+    /// The total number of activities during the day.
+    pub fn total(&self) -> u32 {
+        self.searches
+            .saturating_add(self.captures)
+            .saturating_add(self.reviews)
+            .saturating_add(self.saved)
+    }
+
+    /// Test if there was no activity during the day.
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    /// Add the counts of another day to this one.
+    pub fn merge(&mut self, other: &Day) {
+        self.searches = self.searches.saturating_add(other.searches);
+        self.captures = self.captures.saturating_add(other.captures);
+        self.reviews = self.reviews.saturating_add(other.reviews);
+        self.saved = self.saved.saturating_add(other.saved);
+    }
+}
+
+/// Text read from captures during a day.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Immersion {
+    /// The number of captured lines.
+    pub lines: u32,
+    /// The number of characters read, as counted by [`characters`].
+    pub characters: u64,
+    /// Seconds spent reading, estimated from the time between captures.
+    pub seconds: u64,
+    /// The unix timestamp of the last capture.
+    pub last: u64,
+}
+
+impl Immersion {
+    /// Add the counts of another day or source to this one.
+    pub fn merge(&mut self, other: &Immersion) {
+        self.lines = self.lines.saturating_add(other.lines);
+        self.characters = self.characters.saturating_add(other.characters);
+        self.seconds = self.seconds.saturating_add(other.seconds);
+        self.last = self.last.max(other.last);
+    }
+
+    /// Characters read per hour, if enough time has been spent reading to
+    /// tell.
+    pub fn speed(&self) -> Option<u64> {
+        (self.seconds >= 60).then(|| self.characters.saturating_mul(3600) / self.seconds)
+    }
+}
+
+/// Collected statistics.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    /// Activity indexed by days since the unix epoch.
+    #[serde(default)]
+    pub days: BTreeMap<u64, Day>,
+    /// Text read indexed by days since the unix epoch and source.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub immersion: BTreeMap<u64, BTreeMap<String, Immersion>>,
+}
+
+impl Stats {
+    /// Load statistics stored in the given directory.
+    pub fn load<P>(dir: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = dir.as_ref().join(FILE);
+
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| path.display().to_string()),
+        };
+
+        serde_json::from_slice(&data).with_context(|| path.display().to_string())
+    }
+
+    /// Save statistics to the given directory.
+    pub fn save<P>(&self, dir: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let data = serde_json::to_vec(self)?;
+        profile::write(dir.as_ref(), FILE, &data)
+    }
+
+    /// Remove the statistics and the capture log stored in the given
+    /// directory.
+    pub fn remove<P>(dir: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        for file in [FILE, LOG_FILE] {
+            let path = dir.as_ref().join(file);
+
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e).with_context(|| path.display().to_string()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Count an activity which happened at the given unix timestamp.
+    pub fn record(&mut self, activity: Activity, timestamp: u64) {
+        self.days.entry(day(timestamp)).or_default().add(activity);
+    }
+
+    /// Count text with the given number of characters captured from a
+    /// source at the given unix timestamp.
+    ///
+    /// The time since the previous capture from the same source is counted as
+    /// time spent reading, unless it was long enough ago for reading to have
+    /// been interrupted.
+    pub fn read(&mut self, source: &str, characters: usize, timestamp: u64) {
+        let immersion = self
+            .immersion
+            .entry(day(timestamp))
+            .or_default()
+            .entry(source.to_owned())
+            .or_default();
+
+        if immersion.lines > 0 {
+            let elapsed = timestamp.saturating_sub(immersion.last);
+
+            if elapsed <= IDLE {
+                immersion.seconds = immersion.seconds.saturating_add(elapsed);
+            }
+        }
+
+        immersion.lines = immersion.lines.saturating_add(1);
+        immersion.characters = immersion.characters.saturating_add(characters as u64);
+        immersion.last = immersion.last.max(timestamp);
+    }
+
+    /// Activity during the given day.
+    pub fn get(&self, day: u64) -> Day {
+        self.days.get(&day).copied().unwrap_or_default()
+    }
+
+    /// Text read during the given day from every source.
+    pub fn immersion(&self, day: u64) -> Immersion {
+        let mut total = Immersion::default();
+
+        for immersion in self
+            .immersion
+            .get(&day)
+            .into_iter()
+            .flat_map(|s| s.values())
+        {
+            total.merge(immersion);
+        }
+
+        total
+    }
+
+    /// Text read from each source during every day statistics are kept for.
+    pub fn sources(&self) -> BTreeMap<&str, Immersion> {
+        let mut sources = BTreeMap::<&str, Immersion>::new();
+
+        for day in self.immersion.values() {
+            for (source, immersion) in day {
+                sources.entry(source).or_default().merge(immersion);
+            }
+        }
+
+        sources
+    }
+
+    /// Remove days which are older than `retention` days counting back from
+    /// the given unix timestamp, where a retention of `0` keeps every day.
+    pub fn prune(&mut self, timestamp: u64, retention: u32) {
+        if retention == 0 {
+            return;
+        }
+
+        let oldest = (day(timestamp) + 1).saturating_sub(u64::from(retention));
+        self.days.retain(|day, _| *day >= oldest);
+        self.immersion.retain(|day, _| *day >= oldest);
+    }
+
+    /// The number of consecutive days with activity up until the given unix
+    /// timestamp.
+    ///
+    /// A streak isn't broken until a full day has passed without activity, so
+    /// if there has been no activity yet today the streak up until yesterday
+    /// is returned.
+    pub fn streak(&self, timestamp: u64) -> u32 {
+        let today = day(timestamp);
+
+        let mut current = if self.is_active(today) {
+            today
+        } else {
+            match today.checked_sub(1) {
+                Some(yesterday) => yesterday,
+                None => return 0,
+            }
+        };
+
+        let mut streak = 0;
+
+        while self.is_active(current) {
+            streak += 1;
+
+            let Some(previous) = current.checked_sub(1) else {
+                break;
+            };
+
+            current = previous;
+        }
+
+        streak
+    }
+
+    /// The longest number of consecutive days with activity.
+    pub fn longest_streak(&self) -> u32 {
+        let mut longest = 0;
+        let mut streak = 0;
+        let mut last = None;
+
+        for (&day, counts) in &self.days {
+            if counts.is_empty() {
+                continue;
+            }
+
+            streak = match last {
+                Some(last) if last + 1 == day => streak + 1,
+                _ => 1,
+            };
+
+            longest = longest.max(streak);
+            last = Some(day);
+        }
+
+        longest
+    }
+
+    fn is_active(&self, day: u64) -> bool {
+        self.days.get(&day).is_some_and(|d| !d.is_empty())
+    }
+}
+
+/// A capture in the capture log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capture<'a> {
+    /// The unix timestamp of the capture.
+    pub timestamp: u64,
+    /// The source the capture came from.
+    pub source: &'a str,
+    /// The number of characters read, as counted by [`characters`].
+    pub characters: usize,
+}
+
+impl fmt::Display for Capture<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},", self.timestamp, date(day(self.timestamp)))?;
+
+        if self.source.contains([',', '"', '\n', '\r']) {
+            f.write_char('"')?;
+
+            for c in self.source.chars() {
+                if c == '"' {
+                    f.write_char('"')?;
+                }
+
+                f.write_char(c)?;
+            }
+
+            f.write_char('"')?;
+        } else {
+            f.write_str(self.source)?;
+        }
+
+        write!(f, ",{}", self.characters)
+    }
+}
+
+/// Append a capture to the log stored in the given directory.
+pub fn log_capture<P>(dir: P, capture: &Capture<'_>) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    let path = dir.join(LOG_FILE);
+
+    fs::create_dir_all(dir).with_context(|| dir.display().to_string())?;
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| path.display().to_string())?;
+
+    let mut line = String::new();
+
+    if file.metadata()?.len() == 0 {
+        line.push_str(LOG_HEADER);
+    }
+
+    _ = writeln!(line, "{capture}");
+
+    file.write_all(line.as_bytes())
+        .with_context(|| path.display().to_string())
+}
+
+/// Read the capture log stored in the given directory as CSV.
+pub fn read_log<P>(dir: P) -> Result<Vec<u8>>
+where
+    P: AsRef<Path>,
+{
+    let path = dir.as_ref().join(LOG_FILE);
+
+    match fs::read(&path) {
+        Ok(data) => Ok(data),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(LOG_HEADER.as_bytes().to_vec()),
+        Err(e) => Err(e).with_context(|| path.display().to_string()),
+    }
+}
+
+/// Remove captures from the log stored in the given directory which are
+/// older than `retention` days counting back from the given unix timestamp,
+/// where a retention of `0` keeps every capture.
+///
+/// The log is only rewritten if its oldest capture is too old.
+pub fn prune_log<P>(dir: P, timestamp: u64, retention: u32) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    if retention == 0 {
+        return Ok(());
+    }
+
+    let dir = dir.as_ref();
+    let path = dir.join(LOG_FILE);
+    let oldest = (day(timestamp) + 1).saturating_sub(u64::from(retention)) * DAY;
+
+    let is_kept = |line: &str| {
+        let Some((timestamp, _)) = line.split_once(',') else {
+            return false;
+        };
+
+        timestamp.parse::<u64>().is_ok_and(|t| t >= oldest)
+    };
+
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).with_context(|| path.display().to_string()),
+    };
+
+    let mut lines = BufReader::new(file).lines().skip(1);
+
+    let Some(first) = lines.next() else {
+        return Ok(());
+    };
+
+    if is_kept(&first.with_context(|| path.display().to_string())?) {
+        return Ok(());
+    }
+
+    let mut data = String::from(LOG_HEADER);
+
+    for line in lines {
+        let line = line.with_context(|| path.display().to_string())?;
+
+        if is_kept(&line) {
+            data.push_str(&line);
+            data.push('\n');
+        }
+    }
+
+    profile::write(dir, LOG_FILE, data.as_bytes())
+}
+
+/// The number of characters read in the given text, which doesn't count
+/// whitespace or punctuation.
+pub fn characters(text: &str) -> usize {
+    text.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+/// The day since the unix epoch of the given unix timestamp.
+pub fn day(timestamp: u64) -> u64 {
+    timestamp / DAY
+}
+
+/// Format a day since the unix epoch as a `YYYY-MM-DD` date.
+pub fn date(day: u64) -> String {
+    // Convert days since the epoch to a civil date in the proleptic Gregorian
+    // calendar using eras of 400 years.
+    let z = day as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!("{y:04}-{m:02}-{d:02}")
+}
@@ -0,0 +1,172 @@
+use super::{
+    characters, date, day, log_capture, prune_log, read_log, Activity, Capture, Day, Immersion,
+    Stats,
+};
+
+const DAY: u64 = 24 * 60 * 60;
+
+#[test]
+fn dates() {
+    assert_eq!(date(0), "1970-01-01");
+    assert_eq!(date(59), "1970-03-01");
+    assert_eq!(date(day(951_782_400)), "2000-02-29");
+    assert_eq!(date(day(1_700_000_000)), "2023-11-14");
+}
+
+#[test]
+fn record_and_prune() {
+    let mut stats = Stats::default();
+    let now = 100 * DAY + 10;
+
+    stats.record(Activity::Search, now);
+    stats.record(Activity::Search, now + 1);
+    stats.record(Activity::Review, now);
+    stats.record(Activity::Save, now - DAY);
+    stats.record(Activity::Capture, now - 10 * DAY);
+
+    assert_eq!(
+        stats.get(100),
+        Day {
+            searches: 2,
+            captures: 0,
+            reviews: 1,
+            saved: 0,
+        }
+    );
+
+    stats.prune(now, 0);
+    assert_eq!(stats.days.len(), 3);
+
+    stats.prune(now, 2);
+    assert_eq!(stats.days.keys().copied().collect::<Vec<_>>(), [99, 100]);
+}
+
+#[test]
+fn streaks() {
+    let mut stats = Stats::default();
+    let now = 100 * DAY;
+
+    for d in [90, 91, 92, 93, 97, 98, 99] {
+        stats.record(Activity::Search, d * DAY);
+    }
+
+    assert_eq!(stats.streak(now), 3);
+    assert_eq!(stats.streak(now + DAY), 0);
+    assert_eq!(stats.longest_streak(), 4);
+
+    stats.record(Activity::Review, now);
+    assert_eq!(stats.streak(now), 4);
+    assert_eq!(stats.streak(0), 0);
+}
+
+#[test]
+fn store() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+
+    assert_eq!(Stats::load(dir).unwrap(), Stats::default());
+
+    let mut stats = Stats::default();
+    stats.record(Activity::Capture, 42 * DAY);
+    stats.save(dir).unwrap();
+    assert_eq!(Stats::load(dir).unwrap(), stats);
+
+    Stats::remove(dir).unwrap();
+    Stats::remove(dir).unwrap();
+    assert_eq!(Stats::load(dir).unwrap(), Stats::default());
+}
+
+#[test]
+fn count_characters() {
+    assert_eq!(characters(""), 0);
+    assert_eq!(characters("「ラーメン、食べたい！」"), 8);
+    assert_eq!(characters(" 今日は\n１２月です。 "), 8);
+    assert_eq!(characters("abc def"), 6);
+}
+
+#[test]
+fn immersion() {
+    let mut stats = Stats::default();
+    let now = 100 * DAY + 10;
+
+    stats.read("clipboard", 10, now);
+    stats.read("clipboard", 20, now + 30);
+    stats.read("game", 5, now + 40);
+    // Too long after the last capture to be counted as reading.
+    stats.read("clipboard", 30, now + 30 + 3600);
+    stats.read("clipboard", 7, now - 10 * DAY);
+
+    assert_eq!(
+        stats.immersion(100),
+        Immersion {
+            lines: 4,
+            characters: 65,
+            seconds: 30,
+            last: now + 30 + 3600,
+        }
+    );
+
+    assert_eq!(stats.immersion(100).speed(), None);
+    assert_eq!(stats.immersion(99), Immersion::default());
+
+    let sources = stats.sources();
+    assert_eq!(sources["clipboard"].characters, 67);
+    assert_eq!(sources["game"].lines, 1);
+
+    stats.read("clipboard", 40, now + 3600 + 90);
+    assert_eq!(stats.immersion(100).seconds, 90);
+    assert_eq!(stats.immersion(100).speed(), Some(105 * 3600 / 90));
+
+    stats.prune(now, 2);
+    assert_eq!(stats.immersion.keys().copied().collect::<Vec<_>>(), [100]);
+    assert_eq!(stats.days.len(), 0);
+}
+
+#[test]
+fn capture_log() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path();
+    let now = 100 * DAY;
+
+    assert_eq!(
+        read_log(dir).unwrap(),
+        b"timestamp,date,source,characters\n"
+    );
+
+    for (timestamp, source) in [
+        (now - 5 * DAY, "old"),
+        (now, "a, \"b\""),
+        (now + 1, "clipboard"),
+    ] {
+        let capture = Capture {
+            timestamp,
+            source,
+            characters: 3,
+        };
+
+        log_capture(dir, &capture).unwrap();
+    }
+
+    prune_log(dir, now, 0).unwrap();
+
+    let log = String::from_utf8(read_log(dir).unwrap()).unwrap();
+    assert_eq!(log.lines().count(), 4);
+
+    prune_log(dir, now, 2).unwrap();
+    prune_log(dir, now, 2).unwrap();
+
+    let log = String::from_utf8(read_log(dir).unwrap()).unwrap();
+
+    assert_eq!(
+        log,
+        "timestamp,date,source,characters\n\
+         8640000,1970-04-11,\"a, \"\"b\"\"\",3\n\
+         8640001,1970-04-11,clipboard,3\n"
+    );
+
+    Stats::remove(dir).unwrap();
+    assert_eq!(
+        read_log(dir).unwrap(),
+        b"timestamp,date,source,characters\n"
+    );
+}
@@ -0,0 +1,61 @@
+//! Synchronization of profiles between installs.
+//!
+//! A sync backend stores a copy of each profile somewhere which is shared
+//! between installs. Syncing pulls the shared copy, merges it into the local
+//! profile with [`Profile::merge`] and pushes the result back.
+
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::profile::{Profile, Profiles};
+
+/// A place where profiles are shared between installs.
+pub trait SyncBackend {
+    /// Fetch the shared copy of a profile, if there is one.
+    fn pull(&self, name: &str) -> Result<Option<Profile>>;
+
+    /// Store the shared copy of a profile.
+    fn push(&self, name: &str, profile: &Profile) -> Result<()>;
+}
+
+/// A sync backend storing profiles in a directory, which is expected to be
+/// kept in sync between machines by something like a shared network drive or
+/// a file synchronization service.
+pub struct FileSync {
+    profiles: Profiles,
+}
+
+impl FileSync {
+    /// Sync profiles through the given directory.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            profiles: Profiles::new(dir),
+        }
+    }
+}
+
+impl SyncBackend for FileSync {
+    fn pull(&self, name: &str) -> Result<Option<Profile>> {
+        // Profiles which haven't been pushed yet load as empty, which merges
+        // as a no-op.
+        Ok(Some(self.profiles.load(name)?))
+    }
+
+    fn push(&self, name: &str, profile: &Profile) -> Result<()> {
+        self.profiles.save(name, profile)
+    }
+}
+
+/// Sync the given local profile with a backend, merging in any changes from
+/// the shared copy before pushing it back.
+pub fn sync(backend: &dyn SyncBackend, name: &str, local: &mut Profile) -> Result<()> {
+    if let Some(remote) = backend.pull(name)? {
+        local.merge(remote);
+    }
+
+    backend.push(name, local)
+}
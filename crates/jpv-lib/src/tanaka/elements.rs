@@ -0,0 +1,28 @@
+/// An example sentence pair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example<'a> {
+    /// The Japanese sentence.
+    pub text: &'a str,
+    /// The English translation.
+    pub translation: &'a str,
+    /// Identifier of the sentence pair in the corpus.
+    pub id: Option<&'a str>,
+    /// Words used in the sentence.
+    pub words: Vec<Word<'a>>,
+}
+
+/// A word index entry from a `B:` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word<'a> {
+    /// The dictionary form of the word.
+    pub headword: &'a str,
+    /// Reading used to disambiguate the headword.
+    pub reading: Option<&'a str>,
+    /// The 1-based sense the word is used in.
+    pub sense: Option<u32>,
+    /// The form the word takes in the sentence, if it differs from the
+    /// headword.
+    pub surface: Option<&'a str>,
+    /// The sentence has been checked as a good example of the word.
+    pub checked: bool,
+}
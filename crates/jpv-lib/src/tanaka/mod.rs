@@ -0,0 +1,15 @@
+//! Parser for the Tanaka corpus of example sentences, as distributed by the
+//! EDRDG in the `examples.utf` format.
+//!
+//! Each example consists of an `A:` line with the Japanese sentence and its
+//! English translation, followed by a `B:` line listing the dictionary words
+//! used in the sentence together with the sense they are used in.
+
+#[cfg(test)]
+mod tests;
+
+pub use self::parser::Parser;
+pub mod parser;
+
+pub use self::elements::{Example, Word};
+mod elements;
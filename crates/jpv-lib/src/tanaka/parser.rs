@@ -0,0 +1,126 @@
+use std::str::Lines;
+
+use anyhow::{bail, Context, Result};
+
+use super::{Example, Word};
+
+pub struct Parser<'a> {
+    lines: Lines<'a>,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Parse input.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            lines: input.lines(),
+            line: 0,
+        }
+    }
+
+    /// Get the next parsed example.
+    pub(crate) fn next(&mut self) -> Result<Option<Example<'a>>> {
+        let Some(a) = self.next_line() else {
+            return Ok(None);
+        };
+
+        let Some(a) = a.strip_prefix("A: ") else {
+            bail!("{}: Expected `A:` line", self.line);
+        };
+
+        let Some(b) = self.next_line() else {
+            bail!("{}: Expected `B:` line", self.line);
+        };
+
+        let Some(b) = b.strip_prefix("B: ") else {
+            bail!("{}: Expected `B:` line", self.line);
+        };
+
+        let (text, rest) = a
+            .split_once('\t')
+            .with_context(|| format!("{}: Missing translation", self.line))?;
+
+        let (translation, id) = match rest.rsplit_once("#ID=") {
+            Some((translation, id)) => (translation, Some(id)),
+            None => (rest, None),
+        };
+
+        let mut words = Vec::new();
+
+        for word in b.split_whitespace() {
+            let Some(word) = parse_word(word) else {
+                bail!("{}: Bad word `{word}`", self.line);
+            };
+
+            words.push(word);
+        }
+
+        Ok(Some(Example {
+            text,
+            translation,
+            id,
+            words,
+        }))
+    }
+
+    /// Get the next non-empty line.
+    fn next_line(&mut self) -> Option<&'a str> {
+        loop {
+            let line = self.lines.next()?;
+            self.line += 1;
+
+            let line = line.trim_end_matches('\r');
+
+            if !line.trim().is_empty() {
+                return Some(line);
+            }
+        }
+    }
+}
+
+/// Parse a single word in the format `headword(reading)[sense]{surface}~`,
+/// where everything except the headword is optional.
+pub(super) fn parse_word(input: &str) -> Option<Word<'_>> {
+    let (input, checked) = match input.strip_suffix('~') {
+        Some(input) => (input, true),
+        None => (input, false),
+    };
+
+    let end = input.find(['(', '[', '{']).unwrap_or(input.len());
+    let (headword, mut rest) = input.split_at(end);
+
+    if headword.is_empty() {
+        return None;
+    }
+
+    let mut reading = None;
+    let mut sense = None;
+    let mut surface = None;
+
+    while let Some(c) = rest.chars().next() {
+        let close = match c {
+            '(' => ')',
+            '[' => ']',
+            '{' => '}',
+            _ => return None,
+        };
+
+        let end = rest.find(close)?;
+        let value = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        match c {
+            '(' => reading = Some(value),
+            '[' => sense = Some(value.parse().ok()?),
+            _ => surface = Some(value),
+        }
+    }
+
+    Some(Word {
+        headword,
+        reading,
+        sense,
+        surface,
+        checked,
+    })
+}
@@ -0,0 +1,100 @@
+use super::parser::parse_word;
+use super::{Example, Parser, Word};
+
+fn word(headword: &str) -> Word<'_> {
+    Word {
+        headword,
+        reading: None,
+        sense: None,
+        surface: None,
+        checked: false,
+    }
+}
+
+#[test]
+fn words() {
+    assert_eq!(parse_word("は"), Some(word("は")));
+
+    assert_eq!(
+        parse_word("私(わたくし)[01]"),
+        Some(Word {
+            reading: Some("わたくし"),
+            sense: Some(1),
+            ..word("私")
+        })
+    );
+
+    assert_eq!(
+        parse_word("見つける{見つけたら}~"),
+        Some(Word {
+            surface: Some("見つけたら"),
+            checked: true,
+            ..word("見つける")
+        })
+    );
+
+    assert_eq!(
+        parse_word("彼(かれ)[02]{彼の}~"),
+        Some(Word {
+            reading: Some("かれ"),
+            sense: Some(2),
+            surface: Some("彼の"),
+            checked: true,
+            ..word("彼")
+        })
+    );
+
+    assert_eq!(parse_word("(かれ)"), None);
+    assert_eq!(parse_word("彼[x]"), None);
+    assert_eq!(parse_word("彼(かれ"), None);
+}
+
+#[test]
+fn examples() {
+    let input = "A: 何かを見つけたら私に教えて下さい。\tIf you find something, please tell me.#ID=1_2\n\
+                 B: 何か{何かを}~ 見つける{見つけたら} 私(わたくし)[01] に 教える{教えて} 下さる{下さい}\n\
+                 \n\
+                 A: 彼は走る。\tHe runs.\n\
+                 B: 彼(かれ)[01] は 走る~\n";
+
+    let mut parser = Parser::new(input);
+
+    let example = parser.next().unwrap().unwrap();
+    assert_eq!(example.text, "何かを見つけたら私に教えて下さい。");
+    assert_eq!(
+        example.translation,
+        "If you find something, please tell me."
+    );
+    assert_eq!(example.id, Some("1_2"));
+    assert_eq!(example.words.len(), 6);
+    assert_eq!(example.words[2].sense, Some(1));
+
+    assert_eq!(
+        parser.next().unwrap(),
+        Some(Example {
+            text: "彼は走る。",
+            translation: "He runs.",
+            id: None,
+            words: vec![
+                Word {
+                    reading: Some("かれ"),
+                    sense: Some(1),
+                    ..word("彼")
+                },
+                word("は"),
+                Word {
+                    checked: true,
+                    ..word("走る")
+                },
+            ],
+        })
+    );
+
+    assert_eq!(parser.next().unwrap(), None);
+}
+
+#[test]
+fn missing_b_line() {
+    let mut parser = Parser::new("A: 彼は走る。\tHe runs.\n");
+    assert!(parser.next().is_err());
+}
@@ -0,0 +1,42 @@
+//! Reading the output of morphological analyzers like MeCab, which split text
+//! into words more accurately than matching the longest phrases found in the
+//! dictionary.
+//!
+//! The analyzer is expected to write one word per line in MeCab's default
+//! format, where each word is followed by a tab and its features, like
+//! `食べ\t動詞,一般,...`, and each line of input is terminated by `EOS`.
+
+#[cfg(test)]
+mod tests;
+
+use std::ops::Range;
+
+/// Get the byte ranges of the words in `text` from the `output` of an
+/// analyzer.
+///
+/// Whitespace is dropped by analyzers, so words are located by searching the
+/// text. Words which can't be found in it are skipped.
+pub fn words(text: &str, output: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start = 0;
+
+    for line in output.lines() {
+        let Some((word, _)) = line.split_once('\t') else {
+            continue;
+        };
+
+        if word.is_empty() {
+            continue;
+        }
+
+        let Some(n) = text[start..].find(word) else {
+            continue;
+        };
+
+        let begin = start + n;
+        start = begin + word.len();
+        words.push(begin..start);
+    }
+
+    words
+}
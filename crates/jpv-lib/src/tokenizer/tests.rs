@@ -0,0 +1,48 @@
+use super::words;
+
+#[test]
+fn mecab_output() {
+    let text = "昨日、寿司を食べた。\n美味しかった";
+
+    let output = "\
+昨日\t名詞,普通名詞,副詞可能,*,*,*,キノウ,昨日,昨日,キノー,昨日,キノー,和,*,*,*,*
+、\t補助記号,読点,*,*,*,*,,、,、,,、,,記号,*,*,*,*
+寿司\t名詞,普通名詞,一般,*,*,*,スシ,寿司,寿司,スシ,寿司,スシ,和,*,*,*,*
+を\t助詞,格助詞,*,*,*,*,ヲ,を,を,オ,を,オ,和,*,*,*,*
+食べ\t動詞,一般,*,*,下一段-バ行,連用形-一般,タベル,食べる,食べ,タベ,食べる,タベル,和,*,*,*,*
+た\t助動詞,*,*,*,助動詞-タ,終止形-一般,タ,た,た,タ,た,タ,和,*,*,*,*
+。\t補助記号,句点,*,*,*,*,,。,。,,。,,記号,*,*,*,*
+EOS
+美味しかっ\t形容詞,一般,*,*,形容詞,連用形-促音便,オイシイ,美味しい,美味しかっ,オイシカッ,美味しい,オイシイ,和,*,*,*,*
+た\t助動詞,*,*,*,助動詞-タ,終止形-一般,タ,た,た,タ,た,タ,和,*,*,*,*
+EOS
+";
+
+    let words = words(text, output)
+        .into_iter()
+        .map(|range| &text[range])
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        words,
+        [
+            "昨日",
+            "、",
+            "寿司",
+            "を",
+            "食べ",
+            "た",
+            "。",
+            "美味しかっ",
+            "た"
+        ]
+    );
+}
+
+#[test]
+fn skips_unknown_words() {
+    let text = "猫 が";
+    let output = "猫\t名詞\nいぬ\t名詞\nが\t助詞\nEOS\n";
+
+    assert_eq!(words(text, output), [0..3, 4..7]);
+}
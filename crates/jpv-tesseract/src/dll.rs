@@ -1,6 +1,6 @@
 use std::ffi::c_void;
-use std::ffi::CString;
-use std::ffi::{c_char, c_int};
+use std::ffi::{c_char, c_float, c_int, c_uint};
+use std::ffi::{CStr, CString};
 use std::io;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
@@ -13,7 +13,10 @@ use libloading::os::windows::{Symbol, LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR};
 
 use crate::error::Error;
 use crate::error::ErrorKind::*;
-use crate::Result;
+use crate::{Line, Result};
+
+/// The `RIL_TEXTLINE` page iterator level.
+const RIL_TEXTLINE: c_uint = 2;
 
 /// Open the tesseract library.
 pub fn open(language: &str) -> Result<Tesseract> {
@@ -75,6 +78,11 @@ pub fn open(language: &str) -> Result<Tesseract> {
         let tess_base_api_delete = symbol!("TessBaseAPIDelete");
         let tess_base_api_set_image = symbol!("TessBaseAPISetImage");
         let tess_base_api_get_utf8_text = symbol!("TessBaseAPIGetUTF8Text");
+        let tess_base_api_get_iterator = symbol!("TessBaseAPIGetIterator");
+        let tess_result_iterator_get_utf8_text = symbol!("TessResultIteratorGetUTF8Text");
+        let tess_result_iterator_confidence = symbol!("TessResultIteratorConfidence");
+        let tess_result_iterator_next = symbol!("TessResultIteratorNext");
+        let tess_result_iterator_delete = symbol!("TessResultIteratorDelete");
         let tess_delete_text = symbol!("TessDeleteText");
 
         let inner = Arc::new(Inner {
@@ -83,6 +91,11 @@ pub fn open(language: &str) -> Result<Tesseract> {
             tess_base_api_delete,
             tess_base_api_set_image,
             tess_base_api_get_utf8_text,
+            tess_base_api_get_iterator,
+            tess_result_iterator_get_utf8_text,
+            tess_result_iterator_confidence,
+            tess_result_iterator_next,
+            tess_result_iterator_delete,
             tess_delete_text,
             _lib: lib,
         });
@@ -109,6 +122,15 @@ struct Inner {
     tess_base_api_set_image:
         Symbol<unsafe extern "C" fn(*mut BaseApiPtr, *const u8, c_int, c_int, c_int, c_int)>,
     tess_base_api_get_utf8_text: Symbol<unsafe extern "C" fn(*mut BaseApiPtr) -> *mut c_char>,
+    tess_base_api_get_iterator:
+        Symbol<unsafe extern "C" fn(*mut BaseApiPtr) -> *mut ResultIteratorPtr>,
+    tess_result_iterator_get_utf8_text:
+        Symbol<unsafe extern "C" fn(*const ResultIteratorPtr, c_uint) -> *mut c_char>,
+    tess_result_iterator_confidence:
+        Symbol<unsafe extern "C" fn(*const ResultIteratorPtr, c_uint) -> c_float>,
+    tess_result_iterator_next:
+        Symbol<unsafe extern "C" fn(*mut ResultIteratorPtr, c_uint) -> c_int>,
+    tess_result_iterator_delete: Symbol<unsafe extern "C" fn(*mut ResultIteratorPtr)>,
     tess_delete_text: Symbol<unsafe extern "C" fn(*mut c_char)>,
     _lib: libloading::os::windows::Library,
 }
@@ -147,6 +169,9 @@ impl Deref for TesseractString {
 #[repr(transparent)]
 struct BaseApiPtr(c_void);
 
+#[repr(transparent)]
+struct ResultIteratorPtr(c_void);
+
 /// A base API instance, associated with a specific language.
 pub struct Tesseract {
     path: Box<Path>,
@@ -183,6 +208,44 @@ impl Tesseract {
         Ok(self.get_utf8_text())
     }
 
+    /// The lines of the text most recently recognized, with how confident
+    /// tesseract is in each of them.
+    pub fn lines(&mut self) -> Vec<Line> {
+        let mut lines = Vec::new();
+
+        unsafe {
+            let iter = (self.inner.tess_base_api_get_iterator)(self.base);
+
+            if iter.is_null() {
+                return lines;
+            }
+
+            loop {
+                let text = (self.inner.tess_result_iterator_get_utf8_text)(iter, RIL_TEXTLINE);
+
+                if !text.is_null() {
+                    lines.push(Line {
+                        text: CStr::from_ptr(text).to_string_lossy().into_owned(),
+                        confidence: (self.inner.tess_result_iterator_confidence)(
+                            iter,
+                            RIL_TEXTLINE,
+                        ),
+                    });
+
+                    (self.inner.tess_delete_text)(text);
+                }
+
+                if (self.inner.tess_result_iterator_next)(iter, RIL_TEXTLINE) == 0 {
+                    break;
+                }
+            }
+
+            (self.inner.tess_result_iterator_delete)(iter);
+        }
+
+        lines
+    }
+
     fn set_image(
         &mut self,
         image_data: &[u8],
@@ -2,6 +2,7 @@ use std::ops::Deref;
 use std::path::Path;
 
 use crate::error::{Error, ErrorKind};
+use crate::Line;
 
 /// Open the tesseract API, all though it is never supported with the fake implementation.
 pub fn open(_: &str) -> Result<Tesseract, Error> {
@@ -39,4 +40,9 @@ impl Tesseract {
     ) -> Result<TesseractString, Error> {
         Err(Error::new(ErrorKind::Unsupported))
     }
+
+    /// The lines of the text most recently recognized.
+    pub fn lines(&mut self) -> Vec<Line> {
+        Vec::new()
+    }
 }
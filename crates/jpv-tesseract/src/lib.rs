@@ -15,3 +15,12 @@ mod error;
 #[cfg_attr(windows, path = "dll.rs")]
 mod r#impl;
 pub use self::r#impl::{open, Tesseract, TesseractString};
+
+/// A line of recognized text.
+#[derive(Debug, Clone)]
+pub struct Line {
+    /// The text of the line.
+    pub text: String,
+    /// How confident tesseract is in the line, from `0` to `100`.
+    pub confidence: f32,
+}
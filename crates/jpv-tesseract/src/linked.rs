@@ -1,5 +1,5 @@
-use std::ffi::CString;
 use std::ffi::{c_char, c_int};
+use std::ffi::{CStr, CString};
 use std::ops::Deref;
 use std::path::Path;
 use std::ptr;
@@ -7,12 +7,14 @@ use std::slice;
 use std::str;
 
 use tesseract_sys::{
-    TessBaseAPICreate, TessBaseAPIDelete, TessBaseAPIGetUTF8Text, TessBaseAPIInit3,
-    TessBaseAPISetImage, TessDeleteText,
+    TessBaseAPICreate, TessBaseAPIDelete, TessBaseAPIGetIterator, TessBaseAPIGetUTF8Text,
+    TessBaseAPIInit3, TessBaseAPISetImage, TessDeleteText, TessPageIteratorLevel_RIL_TEXTLINE,
+    TessResultIteratorConfidence, TessResultIteratorDelete, TessResultIteratorGetUTF8Text,
+    TessResultIteratorNext,
 };
 
 use crate::error::{Error, ErrorKind};
-use crate::Result;
+use crate::{Line, Result};
 
 /// Try and open the tesseract API.
 pub fn open(language: &str) -> Result<Tesseract> {
@@ -90,6 +92,42 @@ impl Tesseract {
         Ok(self.get_utf8_text())
     }
 
+    /// The lines of the text most recently recognized, with how confident
+    /// tesseract is in each of them.
+    pub fn lines(&mut self) -> Vec<Line> {
+        let level = TessPageIteratorLevel_RIL_TEXTLINE;
+        let mut lines = Vec::new();
+
+        unsafe {
+            let iter = TessBaseAPIGetIterator(self.base);
+
+            if iter.is_null() {
+                return lines;
+            }
+
+            loop {
+                let text = TessResultIteratorGetUTF8Text(iter, level);
+
+                if !text.is_null() {
+                    lines.push(Line {
+                        text: CStr::from_ptr(text).to_string_lossy().into_owned(),
+                        confidence: TessResultIteratorConfidence(iter, level),
+                    });
+
+                    TessDeleteText(text);
+                }
+
+                if TessResultIteratorNext(iter, level) == 0 {
+                    break;
+                }
+            }
+
+            TessResultIteratorDelete(iter);
+        }
+
+        lines
+    }
+
     fn set_image(
         &mut self,
         image_data: &[u8],
@@ -0,0 +1,146 @@
+//! Adding notes to a locally running Anki through AnkiConnect.
+//!
+//! See <https://foosoft.net/projects/anki-connect/>.
+
+use std::path::Path;
+
+use anyhow::Result;
+use lib::anki::{self, Content};
+use lib::config::Anki;
+
+/// The version of the AnkiConnect API spoken.
+#[cfg(feature = "reqwest")]
+const VERSION: u32 = 6;
+
+/// An image attached to a note.
+pub(crate) struct Picture<'a> {
+    /// Where Anki reads the image from, so it has to run on the same machine
+    /// as the service.
+    pub(crate) path: &'a Path,
+    /// The name the image is stored under in Anki, whose extension Anki uses
+    /// to decide how to show it.
+    pub(crate) filename: String,
+}
+
+/// The outcome of adding a note.
+pub(crate) enum Added {
+    /// The note was added.
+    Note(u64),
+    /// Notes for the same word already exist.
+    Duplicates(Vec<u64>),
+}
+
+/// Find notes for the same word as the given content.
+pub(crate) async fn find_notes(config: &Anki, content: &Content<'_>) -> Result<Vec<u64>> {
+    let Some(query) = anki::duplicates_query(config, content) else {
+        return Ok(Vec::new());
+    };
+
+    invoke(config, "findNotes", serde_json::json!({ "query": query })).await
+}
+
+/// Add a note for the given content, unless notes for the same word already
+/// exist and duplicates aren't allowed.
+pub(crate) async fn add_note(
+    config: &Anki,
+    content: &Content<'_>,
+    picture: Option<Picture<'_>>,
+    allow_duplicate: bool,
+) -> Result<Added> {
+    if !allow_duplicate {
+        let notes = find_notes(config, content).await?;
+
+        if !notes.is_empty() {
+            return Ok(Added::Duplicates(notes));
+        }
+    }
+
+    let fields = anki::note(config, content);
+
+    let mut note = serde_json::json!({
+        "deckName": config.deck,
+        "modelName": config.model,
+        "fields": fields.fields,
+        "tags": config.tags,
+        "options": {
+            "allowDuplicate": allow_duplicate,
+            "duplicateScope": "deck",
+        },
+    });
+
+    if let (Some(picture), Some(field)) = (picture, &fields.image) {
+        note["picture"] = serde_json::json!([{
+            "path": picture.path,
+            "filename": picture.filename,
+            "fields": [field],
+        }]);
+    }
+
+    let note = invoke(config, "addNote", serde_json::json!({ "note": note })).await?;
+    Ok(Added::Note(note))
+}
+
+/// The extension an image of the given mime type is stored under.
+pub(crate) fn extension(mime: &str) -> &'static str {
+    match mime {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/webp" => "webp",
+        "image/tiff" => "tiff",
+        _ => "png",
+    }
+}
+
+/// Invoke an action, returning its result.
+#[cfg(feature = "reqwest")]
+async fn invoke<T>(config: &Anki, action: &str, params: serde_json::Value) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    use std::time::Duration;
+
+    use anyhow::{bail, Context};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Response<T> {
+        result: Option<T>,
+        error: Option<String>,
+    }
+
+    let client = reqwest::ClientBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    let body = serde_json::json!({
+        "action": action,
+        "version": VERSION,
+        "params": params,
+    });
+
+    let body = client
+        .post(&config.url)
+        .header("Content-Type", "application/json")
+        .body(serde_json::to_vec(&body)?)
+        .send()
+        .await
+        .context("Could not reach AnkiConnect, is Anki running with it installed?")?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    let response: Response<T> =
+        serde_json::from_slice(&body).context("Reading AnkiConnect response")?;
+
+    if let Some(error) = response.error {
+        bail!("AnkiConnect: {error}");
+    }
+
+    response
+        .result
+        .with_context(|| format!("AnkiConnect: No result from `{action}`"))
+}
+
+#[cfg(not(feature = "reqwest"))]
+async fn invoke<T>(_: &Anki, _: &str, _: serde_json::Value) -> Result<T> {
+    anyhow::bail!("Adding notes to Anki is not supported")
+}
@@ -1,29 +1,56 @@
 use std::collections::HashMap;
-use std::io::Read;
+use std::io::{Cursor, Read};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, RwLock};
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
 
 use anyhow::{anyhow, bail, Context, Result};
 use flate2::read::GzDecoder;
-use lib::config::{Config, IndexFormat};
-use lib::database::{self, Database, Input};
+use lib::anki::Content;
+use lib::capture::Filters;
+use lib::config::{Anki, Config, IndexFormat, Transcription};
+use lib::database::{self, BuildError, Database, Input, NameWeighting};
+use lib::mining::{self, Card, Mining, NewCard};
+use lib::ocr::Recognized;
+use lib::profile::{Density, Profile, Profiles, Readings};
+use lib::reader::epub::Ruby;
+use lib::reader::{Document, DocumentInfo, Documents};
+use lib::recordings::{Recording, Recordings};
 use lib::reporter::Reporter;
+use lib::stats::{self, Activity, Stats};
+use lib::sync::{self, FileSync};
 use lib::token::Token;
 use lib::{api, data, Dirs};
+use parking_lot::Mutex as SyncMutex;
 use tempfile::NamedTempFile;
 use tokio::fs;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::sync::{oneshot, Mutex};
+use tokio::sync::oneshot;
 
-use crate::reporter::EventsReporter;
+use crate::anki;
+use crate::clipboard;
+use crate::epub;
+use crate::ocr;
+use crate::reporter::{EventsReporter, Progress};
 use crate::system::{self, SystemEvents};
 use crate::tasks::{CompletedTask, TaskCompletion, Tasks};
+use crate::tokenizer;
+use crate::tts;
+use crate::update;
+use crate::user_data;
+use crate::whisper;
 use crate::Args;
 
+/// Magic bytes at the start of gzip compressed data.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The number of images whose recognized text is cached.
+const OCR_CACHE_CAPACITY: usize = 256;
+
 pub(crate) struct Mutable {
     config: Config,
+    capture_filters: Arc<Filters>,
     database: Database,
     pub(crate) tasks: HashMap<Box<str>, system::TaskProgress>,
 }
@@ -31,15 +58,68 @@ pub(crate) struct Mutable {
 /// Events emitted by modifying the background service.
 pub enum BackgroundEvent {
     /// Save configuration file.
-    SaveConfig(Config, oneshot::Sender<()>),
+    SaveConfig(Box<Config>, oneshot::Sender<()>),
     /// Force a database rebuild.
     InstallAll(bool),
 }
 
+/// An image captured from the clipboard.
+#[derive(Clone)]
+struct Screenshot {
+    mime: String,
+    data: Vec<u8>,
+}
+
 struct Shared {
     dirs: Dirs,
-    tesseract: Option<Mutex<tesseract::Tesseract>>,
+    /// User profiles, locked while a profile is being modified.
+    profiles: SyncMutex<Profiles>,
+    /// The currently selected profile, kept once it's loaded until it's
+    /// modified or another one is selected. Only locked while holding
+    /// `profiles`.
+    profile: SyncMutex<Option<Arc<Profile>>>,
+    /// Documents stored in the reader, locked while a document is being
+    /// modified.
+    documents: SyncMutex<Documents>,
+    /// Audio recorded by the user.
+    recordings: SyncMutex<Recordings>,
+    /// Mined sentence cards.
+    mining: SyncMutex<Mining>,
+    /// The image most recently captured from the clipboard, which can be
+    /// attached to mined cards.
+    screenshot: SyncMutex<Option<Screenshot>>,
+    /// Usage statistics.
+    stats: SyncMutex<Stats>,
+    tesseract: Option<Arc<ocr::Tesseract>>,
     ocr: AtomicBool,
+    /// Text recognized in captured images, including corrections made by the
+    /// user.
+    ocr_cache: SyncMutex<lib::ocr::Cache>,
+    /// Allocator for client identifiers.
+    clients: AtomicU32,
+    /// The client which currently holds capture focus, or `0` if none does.
+    capture_focus: AtomicU32,
+    /// Checks for newer releases.
+    updates: update::Checker,
+    /// Handlers of captured clipboard data.
+    clipboard: clipboard::Registry,
+    /// Speech recognition used to transcribe captured audio.
+    whisper: whisper::Whisper,
+    /// The port the service is listening on, which isn't the configured one
+    /// if that was in use.
+    port: u16,
+}
+
+/// What the background service is set up with.
+pub(crate) struct Setup {
+    pub(crate) dirs: Dirs,
+    pub(crate) config: Config,
+    pub(crate) database: Database,
+    pub(crate) tesseract: Option<tesseract::Tesseract>,
+    pub(crate) log: crate::log::Capture,
+    /// The port the service is listening on, which isn't the configured one
+    /// if that was in use.
+    pub(crate) port: u16,
 }
 
 #[derive(Clone)]
@@ -49,44 +129,690 @@ pub struct Background {
     system_events: SystemEvents,
     mutable: Arc<RwLock<Mutable>>,
     log: crate::log::Capture,
+    /// Whether this handle is used to serve clients which shouldn't have
+    /// access to user data.
+    read_only: bool,
 }
 
 impl Background {
     pub(crate) fn new(
-        dirs: Dirs,
+        setup: Setup,
         channel: UnboundedSender<BackgroundEvent>,
-        config: Config,
-        database: Database,
         system_events: SystemEvents,
-        tesseract: Option<tesseract::Tesseract>,
-        log: crate::log::Capture,
     ) -> Result<Self> {
-        let tesseract = tesseract.map(Mutex::new);
+        let Setup {
+            dirs,
+            config,
+            database,
+            tesseract,
+            log,
+            port,
+        } = setup;
+
+        let tesseract = tesseract.map(|tesseract| Arc::new(ocr::Tesseract::new(tesseract)));
+
+        let profiles = Profiles::new(dirs.profiles_dir());
+        let documents = Documents::new(dirs.documents_dir());
+        let recordings = Recordings::new(dirs.recordings_dir());
+        let mining = Mining::new(dirs.mining_dir());
+
+        let stats = match Stats::load(dirs.stats_dir()) {
+            Ok(stats) => stats,
+            Err(error) => {
+                tracing::warn!("Failed to load statistics: {error}");
+                Stats::default()
+            }
+        };
+
+        let capture_filters = Arc::new(capture_filters(&config));
 
         Ok(Self {
             shared: Arc::new(Shared {
+                profiles: SyncMutex::new(profiles),
+                profile: SyncMutex::new(None),
+                documents: SyncMutex::new(documents),
+                recordings: SyncMutex::new(recordings),
+                mining: SyncMutex::new(mining),
+                screenshot: SyncMutex::new(None),
+                stats: SyncMutex::new(stats),
                 dirs,
                 tesseract,
                 ocr: AtomicBool::new(config.ocr),
+                ocr_cache: SyncMutex::new(lib::ocr::Cache::new(OCR_CACHE_CAPACITY)),
+                clients: AtomicU32::new(1),
+                capture_focus: AtomicU32::new(0),
+                updates: update::Checker::default(),
+                clipboard: clipboard::Registry::default(),
+                whisper: whisper::Whisper::new(),
+                port,
             }),
             channel,
             system_events,
             mutable: Arc::new(RwLock::new(Mutable {
                 config,
+                capture_filters,
                 database,
                 tasks: HashMap::new(),
             })),
             log,
+            read_only: false,
         })
     }
 
-    /// Get tesseract API handle.
-    pub(crate) fn tesseract(&self) -> Option<&Mutex<tesseract::Tesseract>> {
+    /// A handle which serves the dictionary without access to user data.
+    ///
+    /// The profile it sees is always empty, and modifying it fails.
+    pub(crate) fn read_only(&self) -> Self {
+        Self {
+            read_only: true,
+            ..self.clone()
+        }
+    }
+
+    /// Test if this handle doesn't have access to user data.
+    pub(crate) fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Get the handlers of captured clipboard data.
+    pub(crate) fn clipboard(&self) -> &clipboard::Registry {
+        &self.shared.clipboard
+    }
+
+    /// Get the speech recognition used to transcribe captured audio.
+    pub(crate) fn whisper(&self) -> &whisper::Whisper {
+        &self.shared.whisper
+    }
+
+    /// Get the OCR engine, which is the configured one or tesseract if it's
+    /// installed. Returns `None` if OCR is disabled.
+    pub(crate) fn ocr(&self) -> Option<Arc<dyn ocr::Engine>> {
         if !self.shared.ocr.load(Ordering::SeqCst) {
             return None;
         }
 
-        self.shared.tesseract.as_ref()
+        if let Some(engine) = self.mutable.read().unwrap().config.ocr_engine.clone() {
+            return Some(ocr::backend(engine));
+        }
+
+        let tesseract = self.shared.tesseract.clone()?;
+        Some(tesseract)
+    }
+
+    /// Get the text previously recognized in the image with the given key.
+    pub(crate) fn recognized(&self, key: u64) -> Option<Recognized> {
+        self.shared.ocr_cache.lock().get(key).cloned()
+    }
+
+    /// Cache the text recognized in the image with the given key.
+    pub(crate) fn cache_recognized(&self, key: u64, recognized: Recognized) {
+        self.shared.ocr_cache.lock().insert(key, recognized);
+    }
+
+    /// Correct the text recognized in the image with the given key.
+    pub(crate) fn correct_ocr(&self, key: u64, text: String) {
+        self.shared.ocr_cache.lock().correct(key, text);
+    }
+
+    /// Get a newer release of the service, if checking for them is enabled.
+    ///
+    /// Failing to check is only logged, since it's expected while offline.
+    pub(crate) async fn update(&self) -> api::UpdateResponse {
+        let available = if self.config().check_updates {
+            match self.shared.updates.available().await {
+                Ok(release) => release,
+                Err(error) => {
+                    tracing::warn!("Failed to check for updates: {error}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        api::UpdateResponse {
+            version: crate::VERSION.to_owned(),
+            available,
+        }
+    }
+
+    /// The port the service is listening on.
+    pub(crate) fn port(&self) -> u16 {
+        self.shared.port
+    }
+
+    /// Allocate a unique identifier for a newly connected client.
+    pub(crate) fn new_client_id(&self) -> u32 {
+        self.shared.clients.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Get the client which currently holds capture focus.
+    pub(crate) fn capture_focus(&self) -> Option<u32> {
+        match self.shared.capture_focus.load(Ordering::SeqCst) {
+            0 => None,
+            client => Some(client),
+        }
+    }
+
+    /// Claim or release capture focus for the given client.
+    ///
+    /// Releasing only has an effect if the client currently holds focus.
+    pub(crate) fn set_capture_focus(&self, client: u32, claim: bool) {
+        let changed = if claim {
+            self.shared.capture_focus.swap(client, Ordering::SeqCst) != client
+        } else {
+            self.shared
+                .capture_focus
+                .compare_exchange(client, 0, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        };
+
+        if changed {
+            self.system_events
+                .send(system::Event::CaptureFocus(self.capture_focus()));
+        }
+    }
+
+    /// Get the available profiles and the currently selected one.
+    pub(crate) fn profiles(&self) -> Result<api::ProfilesResponse> {
+        let profile = self.profile();
+        let profiles = self.shared.profiles.lock();
+        let current = profiles.current()?;
+
+        Ok(api::ProfilesResponse {
+            current,
+            profiles: profiles.list()?,
+            sync: self.config().sync_dir.is_some(),
+            names: profile.name_weighting(),
+            readings: profile.readings(),
+            density: profile.density(),
+            text_scale: profile.text_scale(),
+        })
+    }
+
+    /// Synchronize the currently selected profile through the configured
+    /// sync directory.
+    pub(crate) fn sync_profile(&self) -> Result<()> {
+        let Some(sync_dir) = self.config().sync_dir else {
+            bail!("Sync is not configured");
+        };
+
+        let backend = FileSync::new(sync_dir);
+
+        {
+            let profiles = self.shared.profiles.lock();
+            let name = profiles.current()?;
+            let mut profile = profiles.load(&name)?;
+            sync::sync(&backend, &name, &mut profile)?;
+            profiles.save(&name, &profile)?;
+            *self.shared.profile.lock() = Some(Arc::new(profile));
+            tracing::info!("Synchronized profile `{name}`");
+        }
+
+        self.system_events.send(system::Event::ProfileChanged);
+        Ok(())
+    }
+
+    /// Select the given profile, creating it if it doesn't exist.
+    pub(crate) fn select_profile(&self, name: &str) -> Result<api::ProfilesResponse> {
+        {
+            let profiles = self.shared.profiles.lock();
+            profiles.select(name)?;
+            *self.shared.profile.lock() = None;
+        }
+
+        self.system_events.send(system::Event::ProfileChanged);
+        self.profiles()
+    }
+
+    /// Change how names are weighed when analyzing text in the currently
+    /// selected profile.
+    pub(crate) fn set_name_weighting(&self, names: NameWeighting) -> Result<api::ProfilesResponse> {
+        self.update_profile(|profile| profile.set_name_weighting(names))?;
+        self.system_events.send(system::Event::ProfileChanged);
+        self.profiles()
+    }
+
+    /// Change how readings are shown above kanji in the currently selected
+    /// profile.
+    pub(crate) fn set_readings(&self, readings: Readings) -> Result<api::ProfilesResponse> {
+        self.update_profile(|profile| profile.set_readings(readings))?;
+        self.system_events.send(system::Event::ProfileChanged);
+        self.profiles()
+    }
+
+    /// Change how densely entries and characters are shown in the currently
+    /// selected profile.
+    pub(crate) fn set_density(
+        &self,
+        density: Density,
+        text_scale: u32,
+    ) -> Result<api::ProfilesResponse> {
+        self.update_profile(|profile| {
+            profile.set_density(density);
+            profile.set_text_scale(text_scale);
+        })?;
+
+        self.system_events.send(system::Event::ProfileChanged);
+        self.profiles()
+    }
+
+    /// Delete the given profile.
+    pub(crate) fn delete_profile(&self, name: &str) -> Result<api::ProfilesResponse> {
+        {
+            let profiles = self.shared.profiles.lock();
+            profiles.delete(name)?;
+            *self.shared.profile.lock() = None;
+        }
+
+        self.system_events.send(system::Event::ProfileChanged);
+        self.profiles()
+    }
+
+    /// Get the currently selected profile.
+    ///
+    /// A profile which fails to load is treated as empty, so that it doesn't
+    /// get in the way of looking things up.
+    pub(crate) fn profile(&self) -> Arc<Profile> {
+        if self.read_only {
+            return Arc::default();
+        }
+
+        let profiles = self.shared.profiles.lock();
+        let mut cached = self.shared.profile.lock();
+
+        if let Some(profile) = &*cached {
+            return profile.clone();
+        }
+
+        let profile = match profiles.current().and_then(|name| profiles.load(&name)) {
+            Ok(profile) => profile,
+            Err(error) => {
+                tracing::warn!("Failed to load profile, using an empty one: {error}");
+                Profile::default()
+            }
+        };
+
+        cached.insert(Arc::new(profile)).clone()
+    }
+
+    /// Modify the currently selected profile.
+    pub(crate) fn update_profile<F, O>(&self, f: F) -> Result<O>
+    where
+        F: FnOnce(&mut Profile) -> O,
+    {
+        if self.read_only {
+            bail!("Profiles can't be modified in a shared dictionary");
+        }
+
+        let profiles = self.shared.profiles.lock();
+        let name = profiles.current()?;
+        let mut profile = profiles.load(&name)?;
+        let output = f(&mut profile);
+        profiles.save(&name, &profile)?;
+        *self.shared.profile.lock() = Some(Arc::new(profile));
+        Ok(output)
+    }
+
+    /// List the documents stored in the reader.
+    pub(crate) fn documents(&self) -> Result<Vec<DocumentInfo>> {
+        self.shared.documents.lock().list()
+    }
+
+    /// Add a document to the reader.
+    pub(crate) fn add_document(&self, document: Document) -> Result<DocumentInfo> {
+        let id = self.shared.documents.lock().add(&document)?;
+        Ok(document.info(id))
+    }
+
+    /// Add a book read from an EPUB archive to the reader.
+    pub(crate) fn add_epub(&self, data: &[u8], ruby: Ruby, added: u64) -> Result<DocumentInfo> {
+        let book = epub::read(Cursor::new(data), ruby)?;
+        self.add_document(Document::from_book(book, added))
+    }
+
+    /// Delete a document from the reader.
+    pub(crate) fn delete_document(&self, id: u64) -> Result<()> {
+        self.shared.documents.lock().delete(id)
+    }
+
+    /// Load a document from the reader.
+    pub(crate) fn document(&self, id: u64) -> Result<Document> {
+        self.shared.documents.lock().load(id)
+    }
+
+    /// Modify a document in the reader.
+    pub(crate) fn update_document<F, O>(&self, id: u64, f: F) -> Result<O>
+    where
+        F: FnOnce(&mut Document) -> O,
+    {
+        let documents = self.shared.documents.lock();
+        let mut document = documents.load(id)?;
+        let output = f(&mut document);
+        documents.save(id, &document)?;
+        Ok(output)
+    }
+
+    /// List the recordings attached to a phrase.
+    pub(crate) fn recordings(&self, sequence: u64) -> Result<Vec<Recording>> {
+        self.shared.recordings.lock().for_sequence(sequence)
+    }
+
+    /// Attach a recording to a phrase.
+    pub(crate) fn add_recording(
+        &self,
+        sequence: u64,
+        mime: &str,
+        data: &[u8],
+        added: u64,
+    ) -> Result<Recording> {
+        self.shared
+            .recordings
+            .lock()
+            .add(sequence, mime, data, added)
+    }
+
+    /// Load a recording and its audio.
+    pub(crate) fn recording(&self, id: u64) -> Result<(Recording, Vec<u8>)> {
+        self.shared.recordings.lock().load(id)
+    }
+
+    /// Delete a recording.
+    pub(crate) fn delete_recording(&self, id: u64) -> Result<()> {
+        self.shared.recordings.lock().delete(id)
+    }
+
+    /// Remember an image captured from the clipboard, so that it can be
+    /// attached to cards mined from it.
+    pub(crate) fn set_screenshot(&self, mime: &str, data: Vec<u8>) {
+        if data.len() > mining::MAX_IMAGE_SIZE {
+            return;
+        }
+
+        *self.shared.screenshot.lock() = Some(Screenshot {
+            mime: mime.to_owned(),
+            data,
+        });
+    }
+
+    /// List mined cards.
+    pub(crate) fn mined(&self) -> Result<Vec<Card>> {
+        self.shared.mining.lock().list()
+    }
+
+    /// Mine a card, optionally attaching the most recently captured image.
+    pub(crate) fn mine(&self, card: NewCard, screenshot: bool, added: u64) -> Result<Card> {
+        let screenshot = if screenshot {
+            self.shared.screenshot.lock().clone()
+        } else {
+            None
+        };
+
+        let image = screenshot.as_ref().map(|screenshot| mining::Image {
+            mime: &screenshot.mime,
+            data: &screenshot.data,
+        });
+
+        self.shared.mining.lock().add(card, image, added)
+    }
+
+    /// Load a mined card and its image.
+    pub(crate) fn mined_image(&self, id: u64) -> Result<(Card, Vec<u8>)> {
+        self.shared.mining.lock().image(id)
+    }
+
+    /// Delete a mined card.
+    pub(crate) fn delete_mined(&self, id: u64) -> Result<()> {
+        self.shared.mining.lock().delete(id)
+    }
+
+    /// Export mined cards to Anki.
+    pub(crate) async fn export_mined(
+        &self,
+        ids: &[u64],
+        allow_duplicates: bool,
+    ) -> Result<api::ExportMinedResponse> {
+        let config = self.anki()?;
+        let mut duplicates = Vec::new();
+
+        for &id in ids {
+            let (card, image) = {
+                let mining = self.shared.mining.lock();
+                let card = mining.load(id)?;
+                let image = mining.image_path(&card);
+                (card, image)
+            };
+
+            let picture = image
+                .as_deref()
+                .zip(card.image.as_deref())
+                .map(|(path, mime)| anki::Picture {
+                    path,
+                    filename: format!(
+                        "jpv-{}-{}.{}",
+                        card.sequence,
+                        card.id,
+                        anki::extension(mime)
+                    ),
+                });
+
+            let content = Content::card(&card);
+
+            match anki::add_note(&config, &content, picture, allow_duplicates).await? {
+                anki::Added::Note(note) => {
+                    self.shared.mining.lock().set_note(id, note)?;
+                }
+                anki::Added::Duplicates(..) => {
+                    duplicates.push(id);
+                }
+            }
+        }
+
+        Ok(api::ExportMinedResponse {
+            cards: self.mined()?,
+            duplicates,
+        })
+    }
+
+    /// Find the notes in Anki for the same word as the given card.
+    pub(crate) async fn anki_notes(&self, card: &NewCard) -> Result<Vec<u64>> {
+        let config = self.anki()?;
+        anki::find_notes(&config, &Content::new_card(card)).await
+    }
+
+    /// Add a note to Anki for the given card, returning the notes for the same
+    /// word.
+    pub(crate) async fn add_anki_note(
+        &self,
+        card: &NewCard,
+        allow_duplicate: bool,
+    ) -> Result<Vec<u64>> {
+        let config = self.anki()?;
+        let content = Content::new_card(card);
+
+        match anki::add_note(&config, &content, None, allow_duplicate).await? {
+            anki::Added::Note(note) => {
+                let mut notes = anki::find_notes(&config, &content).await?;
+
+                if !notes.contains(&note) {
+                    notes.push(note);
+                }
+
+                Ok(notes)
+            }
+            anki::Added::Duplicates(notes) => Ok(notes),
+        }
+    }
+
+    /// The configuration of Anki, if adding notes to it is enabled.
+    fn anki(&self) -> Result<Anki> {
+        let config = self.config().anki;
+
+        if !config.enabled {
+            bail!("Adding notes to Anki is not enabled in the settings");
+        }
+
+        Ok(config)
+    }
+
+    /// Count an activity in the usage statistics, if they're enabled.
+    ///
+    /// Failing to store statistics is logged rather than reported, since it
+    /// shouldn't interrupt the activity being counted.
+    pub(crate) fn record_activity(&self, activity: Activity) {
+        let config = self.config();
+
+        if self.read_only || !config.stats.enabled {
+            return;
+        }
+
+        let now = unix_timestamp();
+        let mut stats = self.shared.stats.lock();
+        stats.record(activity, now);
+        stats.prune(now, config.stats.retention);
+
+        if let Err(error) = stats.save(self.shared.dirs.stats_dir()) {
+            tracing::warn!("Failed to store statistics: {error}");
+        }
+    }
+
+    /// Count text captured from the given source in the immersion
+    /// statistics, and log the capture, if statistics are enabled.
+    pub(crate) fn record_capture(&self, source: &str, text: &str) {
+        let config = self.config();
+
+        if self.read_only || !config.stats.enabled {
+            return;
+        }
+
+        let characters = stats::characters(text);
+
+        if characters == 0 {
+            return;
+        }
+
+        let now = unix_timestamp();
+        let dir = self.shared.dirs.stats_dir();
+        let mut stats = self.shared.stats.lock();
+        stats.read(source, characters, now);
+        stats.prune(now, config.stats.retention);
+
+        if let Err(error) = stats.save(&dir) {
+            tracing::warn!("Failed to store statistics: {error}");
+        }
+
+        let capture = stats::Capture {
+            timestamp: now,
+            source,
+            characters,
+        };
+
+        if let Err(error) = stats::log_capture(&dir, &capture) {
+            tracing::warn!("Failed to log capture: {error}");
+        }
+
+        if let Err(error) = stats::prune_log(&dir, now, config.stats.retention) {
+            tracing::warn!("Failed to prune capture log: {error}");
+        }
+    }
+
+    /// The log of captures as CSV.
+    pub(crate) fn capture_log(&self) -> Result<Vec<u8>> {
+        let _stats = self.shared.stats.lock();
+        stats::read_log(self.shared.dirs.stats_dir())
+    }
+
+    /// Usage statistics for the given number of days, counting back from
+    /// today.
+    pub(crate) fn stats(&self, days: u32) -> api::StatsResponse {
+        let config = self.config();
+        let now = unix_timestamp();
+
+        let mut stats = self.shared.stats.lock();
+        stats.prune(now, config.stats.retention);
+
+        let today = stats::day(now);
+        let first = (today + 1).saturating_sub(u64::from(days.min(api::MAX_STATS_DAYS)));
+
+        let days = (first..=today)
+            .map(|day| api::StatsDay {
+                date: stats::date(day),
+                activity: stats.get(day),
+                immersion: stats.immersion(day),
+            })
+            .collect();
+
+        let mut total = stats::Day::default();
+
+        for day in stats.days.values() {
+            total.merge(day);
+        }
+
+        let mut immersion = stats::Immersion::default();
+        let mut sources = Vec::new();
+
+        for (source, source_immersion) in stats.sources() {
+            immersion.merge(&source_immersion);
+
+            sources.push(api::StatsSource {
+                source: source.to_owned(),
+                immersion: source_immersion,
+            });
+        }
+
+        api::StatsResponse {
+            days,
+            total,
+            streak: stats.streak(now),
+            longest_streak: stats.longest_streak(),
+            immersion,
+            sources,
+        }
+    }
+
+    /// Remove all collected usage statistics.
+    pub(crate) fn clear_stats(&self) -> Result<()> {
+        let mut stats = self.shared.stats.lock();
+        Stats::remove(self.shared.dirs.stats_dir())?;
+        *stats = Stats::default();
+        Ok(())
+    }
+
+    /// Export all user data as a zip archive.
+    pub(crate) fn export_user_data(&self) -> Result<Vec<u8>> {
+        let config = self.config();
+        let profiles = self.shared.profiles.lock();
+        let out = user_data::export(Cursor::new(Vec::new()), &profiles, &config)?;
+        Ok(out.into_inner())
+    }
+
+    /// Import user data from a zip archive, replacing profiles with the same
+    /// name and the current configuration except for the programs it runs.
+    pub(crate) async fn import_user_data(&self, data: &[u8]) -> Result<()> {
+        let data = user_data::read(Cursor::new(data))?;
+        {
+            let profiles = self.shared.profiles.lock();
+            data.store_profiles(&profiles)?;
+            *self.shared.profile.lock() = None;
+        }
+
+        self.system_events.send(system::Event::ProfileChanged);
+
+        if let Some(mut config) = data.config {
+            let current = self.config();
+            let imported = config.clone();
+            config.keep_commands(&current);
+
+            if config != imported {
+                tracing::warn!("Ignoring programs in imported configuration");
+            }
+
+            if !self.update_config(config).await {
+                bail!("Failed to update configuration");
+            }
+        }
+
+        Ok(())
     }
 
     /// Get the current log backfill.
@@ -98,16 +824,21 @@ impl Background {
     pub(crate) async fn update_config(&self, config: Config) -> bool {
         let (sender, receiver) = oneshot::channel();
 
-        let _ = self
-            .channel
-            .send(BackgroundEvent::SaveConfig(config.clone(), sender));
+        let _ = self.channel.send(BackgroundEvent::SaveConfig(
+            Box::new(config.clone()),
+            sender,
+        ));
 
         if receiver.await.is_err() {
             return false;
         }
 
         self.shared.ocr.store(config.ocr, Ordering::SeqCst);
-        self.mutable.write().unwrap().config = config;
+        let capture_filters = Arc::new(capture_filters(&config));
+        let mut mutable = self.mutable.write().unwrap();
+        mutable.config = config;
+        mutable.capture_filters = capture_filters;
+        drop(mutable);
         self.system_events.send(system::Event::Refresh);
         true
     }
@@ -122,6 +853,47 @@ impl Background {
         self.mutable.read().unwrap().config.clone()
     }
 
+    /// Test if captures from the given source are ignored.
+    pub(crate) fn is_ignored_source(&self, source: &str) -> bool {
+        self.mutable
+            .read()
+            .unwrap()
+            .config
+            .is_ignored_source(source)
+    }
+
+    /// The filters applied to captured text.
+    pub(crate) fn capture_filters(&self) -> Arc<Filters> {
+        self.mutable.read().unwrap().capture_filters.clone()
+    }
+
+    /// The configured text-to-speech backend, if any.
+    pub(crate) fn tts(&self) -> Option<Box<dyn tts::Backend>> {
+        self.mutable
+            .read()
+            .unwrap()
+            .config
+            .tts
+            .clone()
+            .map(tts::backend)
+    }
+
+    /// The configured morphological analyzer, if any.
+    pub(crate) fn tokenizer(&self) -> Option<Arc<dyn tokenizer::Tokenizer>> {
+        self.mutable
+            .read()
+            .unwrap()
+            .config
+            .tokenizer
+            .clone()
+            .map(tokenizer::backend)
+    }
+
+    /// How captured audio is transcribed, if it is.
+    pub(crate) fn transcription(&self) -> Option<Transcription> {
+        self.mutable.read().unwrap().config.transcription.clone()
+    }
+
     /// Access the database currently in use.
     pub(crate) fn database(&self) -> Database {
         self.mutable.read().unwrap().database.clone()
@@ -148,6 +920,30 @@ impl Background {
         );
     }
 
+    /// Report the progress of work done outside of the task queue, like
+    /// transcribing audio, until the returned guard is dropped.
+    pub(crate) fn progress(&self, name: &str) -> Progress {
+        self.mutable.write().unwrap().tasks.insert(
+            name.into(),
+            system::TaskProgress {
+                name: name.into(),
+                value: 0,
+                total: None,
+                text: String::new(),
+                step: 0,
+                steps: 1,
+            },
+        );
+
+        Progress {
+            reporter: Arc::new(EventsReporter {
+                inner: self.mutable.clone(),
+                system_events: self.system_events.clone(),
+                name: Some(name.into()),
+            }),
+        }
+    }
+
     /// Mark the given task as completed.
     pub(crate) fn complete_task(&self, completed: CompletedTask) {
         let Some(name) = completed.name() else {
@@ -179,7 +975,7 @@ impl Background {
                 ensure_parent_dir(&path).await?;
 
                 let config_dir = self.shared.dirs.config_dir().to_owned();
-                let new_config = config.clone();
+                let new_config = (*config).clone();
 
                 let task = tokio::task::spawn_blocking(move || {
                     let config = lib::toml::to_string_pretty(&config)?;
@@ -252,10 +1048,14 @@ impl Background {
                             };
 
                             if let Err(error) = future.await {
-                                tracing::error!("Failed to build index");
-
-                                for error in error.chain() {
-                                    tracing::error!("Caused by: {error}");
+                                if let Some(BuildError::Shutdown) = error.downcast_ref() {
+                                    tracing::info!("Building index was cancelled");
+                                } else {
+                                    tracing::error!("Failed to build index");
+
+                                    for error in error.chain() {
+                                        tracing::error!("Caused by: {error}");
+                                    }
                                 }
                             }
 
@@ -277,6 +1077,7 @@ pub struct ToDownload {
     pub index_path: Box<Path>,
     pub path: Option<Box<Path>>,
     pub format: IndexFormat,
+    pub compressed: bool,
 }
 
 /// Download override paths.
@@ -317,6 +1118,7 @@ pub fn config_to_download(
             index_path: dirs.index_path(id).into(),
             path,
             format: index.format,
+            compressed: index.compressed,
         });
     }
 
@@ -376,6 +1178,7 @@ pub(crate) async fn build(
     let start = Instant::now();
     let kind = download.format;
     let name = download.name.clone();
+    let compressed = download.compressed;
 
     let mut task = tokio::task::spawn_blocking({
         let reporter = reporter.clone();
@@ -385,9 +1188,11 @@ pub(crate) async fn build(
                 IndexFormat::Jmdict => Input::Jmdict(&data[..]),
                 IndexFormat::Kanjidic2 => Input::Kanjidic2(&data[..]),
                 IndexFormat::Jmnedict => Input::Jmnedict(&data[..]),
+                IndexFormat::Tanaka => Input::Tanaka(&data[..]),
+                IndexFormat::Ids => Input::Ids(&data[..]),
             };
 
-            database::build(&*reporter, &shutdown_token, &name, input)
+            database::build(&*reporter, &shutdown_token, &name, input, compressed)
         }
     });
 
@@ -452,11 +1257,19 @@ async fn read_or_download(
 
     reporter.instrument_end(bytes.len());
 
-    let mut input = GzDecoder::new(&bytes[..]);
-    let mut string = String::new();
-    input
-        .read_to_string(&mut string)
-        .with_context(|| path.display().to_string())?;
+    // Some sources are distributed uncompressed, so only decompress data
+    // which starts with the gzip magic bytes.
+    let string = if bytes.starts_with(&GZIP_MAGIC) {
+        let mut input = GzDecoder::new(&bytes[..]);
+        let mut string = String::new();
+        input
+            .read_to_string(&mut string)
+            .with_context(|| path.display().to_string())?;
+        string
+    } else {
+        String::from_utf8(bytes).with_context(|| path.display().to_string())?
+    };
+
     Ok((path, string))
 }
 
@@ -518,3 +1331,25 @@ async fn ensure_parent_dir(path: &Path) -> Result<&Path> {
 
     Ok(parent)
 }
+
+/// Seconds since the unix epoch.
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Compile the configured capture filters.
+///
+/// Filters are validated before they're saved, so if they still can't be
+/// compiled, like when they're imported, captures are left unfiltered.
+fn capture_filters(config: &Config) -> Filters {
+    match Filters::new(&config.capture_filters) {
+        Ok(filters) => filters,
+        Err(error) => {
+            tracing::warn!("Ignoring capture filters: {error:#}");
+            Filters::default()
+        }
+    }
+}
@@ -0,0 +1,593 @@
+//! Handlers for data captured from the clipboard, chosen by its mime type.
+//!
+//! Every handler turns the data it handles into the event sent to clients,
+//! and knows what text it holds so that it can be counted in statistics.
+//! Handlers registered later take precedence, so a feature can take over a
+//! type from the built-in handlers by registering its own.
+
+use std::borrow::Cow;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::Result;
+use lib::api;
+use lib::capture::{self, Filters};
+use lib::reader::epub::Ruby;
+use lib::reader::html;
+use lib::reporter::Reporter;
+
+use crate::background::Background;
+use crate::ocr;
+use crate::system::{Event, SendClipboardData};
+
+/// The future returned when handling captured data, resolving to the event
+/// sent to clients if there is one.
+pub(crate) type Handled<'a> =
+    Pin<Box<dyn Future<Output = Result<Option<api::OwnedClientEvent>>> + Send + 'a>>;
+
+/// A handler of captured clipboard data.
+pub(crate) trait Handler: Send + Sync {
+    /// Test if data of the given mime type is handled.
+    fn handles(&self, mimetype: &str) -> bool;
+
+    /// The text held by captured data, if any.
+    fn text<'a>(&self, data: &'a [u8]) -> Option<Cow<'a, str>>;
+
+    /// The application captured data says it comes from, for data which
+    /// can carry it.
+    fn source(&self, _: &[u8]) -> Option<String> {
+        None
+    }
+
+    /// Handle captured data.
+    fn handle<'a>(&'a self, bg: &'a Background, clipboard: &'a SendClipboardData) -> Handled<'a>;
+}
+
+/// The registered clipboard handlers.
+pub(crate) struct Registry {
+    handlers: Vec<Box<dyn Handler>>,
+}
+
+impl Registry {
+    /// Construct a registry without any handlers.
+    pub(crate) fn new() -> Self {
+        Self {
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Register a handler, which takes precedence over those registered
+    /// before it.
+    pub(crate) fn register<H>(&mut self, handler: H)
+    where
+        H: 'static + Handler,
+    {
+        self.handlers.push(Box::new(handler));
+    }
+
+    /// Find the handler of the given mime type.
+    pub(crate) fn find(&self, mimetype: &str) -> Option<&dyn Handler> {
+        self.handlers
+            .iter()
+            .rev()
+            .find(|handler| handler.handles(mimetype))
+            .map(Box::as_ref)
+    }
+
+    /// The application a capture event comes from, if known.
+    pub(crate) fn capture_source<'a>(&self, event: &'a Event) -> Option<Cow<'a, str>> {
+        if let Event::SendClipboardData(clipboard) = event {
+            let source = self
+                .find(&clipboard.mimetype)
+                .and_then(|handler| handler.source(&clipboard.data));
+
+            if let Some(source) = source {
+                return Some(Cow::Owned(source));
+            }
+        }
+
+        event.capture_source().map(Cow::Borrowed)
+    }
+
+    /// The text captured by a capture event, if it captured text.
+    pub(crate) fn captured_text<'a>(&self, event: &'a Event) -> Option<Cow<'a, str>> {
+        match event {
+            Event::SendClipboardData(clipboard) => {
+                self.find(&clipboard.mimetype)?.text(&clipboard.data)
+            }
+            Event::SendText(text) => Some(Cow::Borrowed(text)),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        registry.register(Utf8Text);
+        registry.register(EscapedText);
+        registry.register(Json);
+        registry.register(Html);
+        registry.register(Image);
+        registry.register(Audio);
+        registry
+    }
+}
+
+/// Text encoded as UTF-8.
+struct Utf8Text;
+
+impl Handler for Utf8Text {
+    fn handles(&self, mimetype: &str) -> bool {
+        matches!(mimetype, "UTF8_STRING" | "text/plain;charset=utf-8")
+    }
+
+    fn text<'a>(&self, data: &'a [u8]) -> Option<Cow<'a, str>> {
+        Some(String::from_utf8_lossy(data))
+    }
+
+    fn handle<'a>(&'a self, bg: &'a Background, clipboard: &'a SendClipboardData) -> Handled<'a> {
+        Box::pin(async move {
+            Ok(text(
+                &bg.capture_filters(),
+                &clipboard.data,
+                clipboard.source.as_deref(),
+            ))
+        })
+    }
+}
+
+/// Text where non-ASCII characters are escaped as `\uXXXX`.
+struct EscapedText;
+
+impl Handler for EscapedText {
+    fn handles(&self, mimetype: &str) -> bool {
+        matches!(mimetype, "STRING" | "text/plain")
+    }
+
+    fn text<'a>(&self, data: &'a [u8]) -> Option<Cow<'a, str>> {
+        decode_escaped(data).map(Cow::Owned)
+    }
+
+    fn handle<'a>(&'a self, bg: &'a Background, clipboard: &'a SendClipboardData) -> Handled<'a> {
+        Box::pin(async move {
+            let Some(data) = decode_escaped(&clipboard.data) else {
+                tracing::warn!("failed to decode");
+                return Ok(None);
+            };
+
+            Ok(text(
+                &bg.capture_filters(),
+                data.as_bytes(),
+                clipboard.source.as_deref(),
+            ))
+        })
+    }
+}
+
+/// A line captured as [`api::CaptureJson`], which is sent to clients as a
+/// [`api::CapturedLine`].
+struct Json;
+
+impl Handler for Json {
+    fn handles(&self, mimetype: &str) -> bool {
+        mimetype.split(';').next().map(str::trim) == Some("application/json")
+    }
+
+    fn text<'a>(&self, data: &'a [u8]) -> Option<Cow<'a, str>> {
+        let json = capture::parse_json(data).ok()?;
+        Some(Cow::Owned(json.text))
+    }
+
+    fn source(&self, data: &[u8]) -> Option<String> {
+        capture::parse_json(data).ok()?.source
+    }
+
+    fn handle<'a>(&'a self, bg: &'a Background, clipboard: &'a SendClipboardData) -> Handled<'a> {
+        Box::pin(async move {
+            let json = match capture::parse_json(&clipboard.data) {
+                Ok(json) => json,
+                Err(error) => {
+                    tracing::warn!("Ignoring captured line: {error:#}");
+                    return Ok(None);
+                }
+            };
+
+            let Some(text) = bg.capture_filters().apply(&json.text).map(Cow::into_owned) else {
+                return Ok(None);
+            };
+
+            let source = json.source.or_else(|| clipboard.source.clone());
+
+            Ok(Some(api::OwnedClientEvent::Broadcast(
+                api::OwnedBroadcast {
+                    kind: api::OwnedBroadcastKind::CapturedLine(api::OwnedCapturedLine {
+                        text,
+                        translation: json.translation,
+                        source,
+                    }),
+                },
+            )))
+        })
+    }
+}
+
+/// HTML copied from a web page, whose text is extracted. Readings in ruby
+/// annotations are removed, so that the annotated words can be looked up.
+struct Html;
+
+impl Html {
+    fn decode(data: &[u8]) -> String {
+        let input = match data {
+            [0xff, 0xfe, rest @ ..] => utf16(rest, u16::from_le_bytes),
+            [0xfe, 0xff, rest @ ..] => utf16(rest, u16::from_be_bytes),
+            [0xef, 0xbb, 0xbf, rest @ ..] => String::from_utf8_lossy(rest).into_owned(),
+            data => String::from_utf8_lossy(data).into_owned(),
+        };
+
+        html::text(&input, Ruby::Strip)
+    }
+}
+
+impl Handler for Html {
+    fn handles(&self, mimetype: &str) -> bool {
+        mimetype.split(';').next().map(str::trim) == Some("text/html")
+    }
+
+    fn text<'a>(&self, data: &'a [u8]) -> Option<Cow<'a, str>> {
+        Some(Cow::Owned(Self::decode(data)))
+    }
+
+    fn handle<'a>(&'a self, bg: &'a Background, clipboard: &'a SendClipboardData) -> Handled<'a> {
+        Box::pin(async move {
+            let data = Self::decode(&clipboard.data);
+
+            Ok(text(
+                &bg.capture_filters(),
+                data.as_bytes(),
+                clipboard.source.as_deref(),
+            ))
+        })
+    }
+}
+
+/// An image, which is remembered so that it can be attached to mined cards
+/// and whose text is recognized if OCR is enabled.
+struct Image;
+
+impl Handler for Image {
+    fn handles(&self, mimetype: &str) -> bool {
+        mimetype.starts_with("image/")
+    }
+
+    fn text<'a>(&self, _: &'a [u8]) -> Option<Cow<'a, str>> {
+        None
+    }
+
+    fn handle<'a>(&'a self, bg: &'a Background, clipboard: &'a SendClipboardData) -> Handled<'a> {
+        Box::pin(async move {
+            use image::ImageFormat;
+
+            let ty = clipboard.mimetype.as_str();
+            bg.set_screenshot(ty, clipboard.data.clone());
+
+            let Some(engine) = bg.ocr() else {
+                return Ok(None);
+            };
+
+            let format = match ty {
+                "image/png" => ImageFormat::Png,
+                "image/tiff" => ImageFormat::Tiff,
+                "image/webp" => ImageFormat::WebP,
+                "image/jpeg" | "image/jpg" => ImageFormat::Jpeg,
+                _ => return Ok(None),
+            };
+
+            tracing::trace!(len = clipboard.data.len(), "Decoding image");
+
+            let image = match image::load_from_memory_with_format(&clipboard.data[..], format) {
+                Ok(image) => image,
+                Err(error) => {
+                    tracing::warn!("Failed to load clipboard image: {error}");
+                    return Ok(None);
+                }
+            };
+
+            recognize(bg, engine, image, clipboard.source.as_deref()).await
+        })
+    }
+}
+
+/// Speech, which is transcribed if transcription is configured.
+struct Audio;
+
+impl Handler for Audio {
+    fn handles(&self, mimetype: &str) -> bool {
+        mimetype.starts_with("audio/")
+    }
+
+    fn text<'a>(&self, _: &'a [u8]) -> Option<Cow<'a, str>> {
+        None
+    }
+
+    fn handle<'a>(&'a self, bg: &'a Background, clipboard: &'a SendClipboardData) -> Handled<'a> {
+        Box::pin(async move {
+            let Some(transcription) = bg.transcription() else {
+                tracing::debug!("Ignoring captured audio, since transcription isn't configured");
+                return Ok(None);
+            };
+
+            let samples = match lib::audio::decode(&clipboard.mimetype, &clipboard.data) {
+                Ok(samples) => samples,
+                Err(error) => {
+                    tracing::warn!("Failed to decode captured audio: {error:#}");
+                    return Ok(None);
+                }
+            };
+
+            let text = match transcribe(bg, transcription.model, samples).await {
+                Ok(text) => text,
+                Err(error) => {
+                    tracing::warn!("Transcription failed: {error:#}");
+                    return Ok(None);
+                }
+            };
+
+            tracing::trace!(text, "Transcribed");
+
+            Ok(self::text(
+                &bg.capture_filters(),
+                text.as_bytes(),
+                clipboard.source.as_deref(),
+            ))
+        })
+    }
+}
+
+/// Transcribe speech on a blocking task, reporting its progress to clients.
+async fn transcribe(bg: &Background, model: String, samples: Vec<f32>) -> Result<String> {
+    let _progress = bg.progress("Transcribing audio");
+    let reporter = _progress.reporter.clone();
+    reporter.instrument_start(module_path!(), &"Transcribing audio", Some(100));
+
+    let bg = bg.clone();
+
+    // The progress is completed once the guard is dropped when returning.
+    tokio::task::spawn_blocking(move || {
+        let mut done = 0;
+
+        bg.whisper().transcribe(&model, &samples, move |percent| {
+            let percent = usize::try_from(percent).unwrap_or_default().min(100);
+
+            if percent > done {
+                reporter.instrument_progress(percent - done);
+                done = percent;
+            }
+        })
+    })
+    .await?
+}
+
+/// Recognize the text in an image, returning the event sending it to clients.
+///
+/// Text recognized in an image is cached, so that the same image isn't
+/// recognized again and so that corrections made by the user stick.
+pub(crate) async fn recognize(
+    bg: &Background,
+    engine: Arc<dyn ocr::Engine>,
+    image: image::DynamicImage,
+    source: Option<&str>,
+) -> Result<Option<api::OwnedClientEvent>> {
+    let key = crate::hash::hash((image.width(), image.height(), image.as_bytes()));
+
+    let recognized = match bg.recognized(key) {
+        Some(recognized) => {
+            tracing::debug!(key, "Using cached recognition");
+            recognized
+        }
+        None => {
+            let recognized = tokio::task::spawn_blocking(move || engine.recognize(&image)).await?;
+
+            let recognized = match recognized {
+                Ok(recognized) => recognized,
+                Err(error) => {
+                    tracing::warn!("Image recognition failed: {error:#}");
+                    return Ok(None);
+                }
+            };
+
+            bg.cache_recognized(key, recognized.clone());
+            recognized
+        }
+    };
+
+    for block in &recognized.blocks {
+        tracing::debug!(text = block.text, confidence = ?block.confidence, "Recognized block");
+    }
+
+    let text = recognized.to_text();
+
+    let trimmed = trim_whitespace(&text[..]);
+
+    tracing::trace!(text = &text[..], ?trimmed, "Recognized");
+
+    let Some(text) = bg.capture_filters().apply(&trimmed).map(Cow::into_owned) else {
+        return Ok(None);
+    };
+
+    let lines = recognized
+        .blocks
+        .into_iter()
+        .map(|block| api::OwnedRecognizedLine {
+            text: block.text.trim().to_owned(),
+            confidence: block.confidence,
+        })
+        .collect();
+
+    Ok(Some(api::OwnedClientEvent::Broadcast(
+        api::OwnedBroadcast {
+            kind: api::OwnedBroadcastKind::RecognizedText(api::OwnedRecognizedText {
+                key,
+                text,
+                lines,
+                source: source.map(str::to_owned),
+            }),
+        },
+    )))
+}
+
+/// Clean up captured text and apply capture filters to it, returning the
+/// event sending it to clients unless it's dropped.
+pub(crate) fn text(
+    filters: &Filters,
+    data: &[u8],
+    source: Option<&str>,
+) -> Option<api::OwnedClientEvent> {
+    let data = filter_data(data);
+    let data = filter_text(filters, &data)?;
+    Some(event("text/plain", &data, source))
+}
+
+fn event(ty: &str, data: &[u8], source: Option<&str>) -> api::OwnedClientEvent {
+    api::OwnedClientEvent::Broadcast(api::OwnedBroadcast {
+        kind: api::OwnedBroadcastKind::SendClipboardData(api::OwnedSendClipboard {
+            ty: Some(ty.to_owned()),
+            data: data.into(),
+            source: source.map(str::to_owned),
+        }),
+    })
+}
+
+/// Apply capture filters to captured text, returning `None` if the capture
+/// should be dropped. Data which isn't UTF-8 is left as it is.
+fn filter_text<'a>(filters: &Filters, data: &'a [u8]) -> Option<Cow<'a, [u8]>> {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return Some(Cow::Borrowed(data));
+    };
+
+    Some(match filters.apply(text)? {
+        Cow::Borrowed(text) => Cow::Borrowed(text.as_bytes()),
+        Cow::Owned(text) => Cow::Owned(text.into_bytes()),
+    })
+}
+
+fn trim_whitespace(input: &str) -> Cow<'_, str> {
+    let mut output = String::new();
+    let mut c = input.char_indices();
+
+    'ws: {
+        for (n, c) in c.by_ref() {
+            if c.is_whitespace() {
+                output.push_str(&input[..n]);
+                break 'ws;
+            }
+        }
+
+        return Cow::Borrowed(input);
+    };
+
+    for (_, c) in c {
+        if !c.is_whitespace() {
+            output.push(c);
+        }
+    }
+
+    Cow::Owned(output)
+}
+
+fn filter_data(mut data: &[u8]) -> Cow<'_, [u8]> {
+    fn filter(b: u8) -> bool {
+        b.is_ascii_control() || b.is_ascii_whitespace()
+    }
+
+    while let [a, rest @ ..] = data {
+        if filter(*a) {
+            data = rest;
+            continue;
+        }
+
+        break;
+    }
+
+    while let [rest @ .., a] = data {
+        if filter(*a) {
+            data = rest;
+            continue;
+        }
+
+        break;
+    }
+
+    let mut output = Vec::new();
+    let mut it = data.iter().enumerate();
+
+    'ws: {
+        for (n, b) in it.by_ref() {
+            if filter(*b) {
+                output.extend_from_slice(&data[..n]);
+                break 'ws;
+            }
+        }
+
+        return Cow::Borrowed(data);
+    }
+
+    for (_, b) in it {
+        if filter(*b) {
+            continue;
+        }
+
+        output.push(*b);
+    }
+
+    Cow::Owned(output)
+}
+
+/// Decode clipboard data where non-ASCII characters are escaped as `\uXXXX`.
+fn decode_escaped(data: &[u8]) -> Option<String> {
+    fn h(b: u8) -> Option<u32> {
+        let b = match b {
+            b'a'..=b'f' => b - b'a' + 10,
+            b'A'..=b'F' => b - b'A' + 10,
+            b'0'..=b'9' => b - b'0',
+            _ => return None,
+        };
+
+        Some(b as u32)
+    }
+
+    let mut s = String::new();
+
+    let mut it = data.iter().copied();
+
+    while let Some(b) = it.next() {
+        match (b, it.clone().next()) {
+            (b'\\', Some(b'u')) => {
+                it.next();
+                let [a, b, c, d] = [it.next()?, it.next()?, it.next()?, it.next()?];
+                let [a, b, c, d] = [h(a)?, h(b)?, h(c)?, h(d)?];
+                let c = a << 12 | b << 8 | c << 4 | d;
+                s.push(char::from_u32(c)?);
+            }
+            (b'\\', Some(b'\\')) => {
+                it.next();
+                s.push('\\');
+            }
+            (c, _) if c.is_ascii() => {
+                s.push(c as char);
+            }
+            _ => {}
+        }
+    }
+
+    Some(s)
+}
+
+/// Decode UTF-16 without its byte order mark.
+fn utf16(data: &[u8], decode: fn([u8; 2]) -> u16) -> String {
+    let units = data.chunks_exact(2).map(|c| decode([c[0], c[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
@@ -20,6 +20,10 @@ pub(crate) struct BuildArgs {
     /// Force a dictionary rebuild.
     #[arg(long, short = 'f')]
     force: bool,
+    /// Store entries in compressed blocks, regardless of what is configured
+    /// for each dictionary.
+    #[arg(long)]
+    compress: bool,
 }
 
 pub(crate) async fn run(
@@ -40,7 +44,9 @@ pub(crate) async fn run(
 
     let to_download = crate::background::config_to_download(&config, dirs, overrides);
 
-    for to_download in to_download {
+    for mut to_download in to_download {
+        to_download.compressed |= build_args.compress;
+
         let tracing_reporter = Arc::new(EmptyReporter);
         let (_sender, shutdown) = oneshot::channel();
 
@@ -0,0 +1,123 @@
+use std::time::Instant;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use lib::config::Config;
+use lib::data;
+use lib::database::{Database, Index};
+use lib::token::Token;
+use lib::Dirs;
+
+use crate::Args;
+
+/// Inputs used for sample lookups, covering kanji, kana, romaji and English.
+const SAMPLES: &[&str] = &["日本", "たべる", "taberu", "食", "water"];
+
+#[derive(Parser)]
+pub(crate) struct CheckArgs {
+    /// Print every problem found instead of only a summary of them.
+    #[arg(long)]
+    verbose: bool,
+}
+
+pub(crate) async fn run(
+    args: &Args,
+    check_args: &CheckArgs,
+    dirs: &Dirs,
+    config: Config,
+) -> Result<()> {
+    let shutdown = Token::default();
+    let mut failed = 0;
+
+    if args.index.is_empty() {
+        for (id, index) in &config.indexes {
+            if index.enabled && !dirs.index_path(id).is_file() {
+                println!("{id}: not built, run `jpv build` to build it");
+                failed += 1;
+            }
+        }
+    }
+
+    for (data, location) in data::open_from_args(&args.index[..], dirs)? {
+        let index = match Index::open(data) {
+            Ok(index) => index,
+            Err(error) => {
+                println!("{location}: failed to open: {error}");
+                failed += 1;
+                continue;
+            }
+        };
+
+        let start = Instant::now();
+        let check = index.check(&shutdown)?;
+        let duration = Instant::now().duration_since(start);
+
+        let status = if check.is_ok() { "ok" } else { "FAILED" };
+        println!("{} ({location}): {status} in {duration:?}", index.name()?);
+        println!("  Version: {}", index.version());
+        println!("  Size: {} bytes", check.size);
+
+        if check.checksum {
+            println!("  Checksum: ok");
+        } else {
+            println!("  Checksum: mismatch, the index might be corrupt or partially written");
+        }
+
+        println!("  Keys: {}", check.keys);
+        println!("  Entries: {}", check.entries);
+        println!("  References: {}", check.references);
+        println!("  Sentences: {}", check.sentences);
+
+        if check.problem_count > 0 {
+            println!("  Problems: {}", check.problem_count);
+
+            let limit = if check_args.verbose { usize::MAX } else { 10 };
+
+            for problem in check.problems.iter().take(limit) {
+                println!("  - {problem}");
+            }
+
+            if check.problems.len() > limit {
+                println!("  ... use `--verbose` to see all recorded problems");
+            }
+        }
+
+        if !check.is_ok() {
+            failed += 1;
+        }
+    }
+
+    let db = Database::open(data::open_from_args(&args.index[..], dirs)?, &config)?;
+
+    println!("Sample lookups:");
+
+    let mut found = 0;
+
+    for &input in SAMPLES {
+        let start = Instant::now();
+        let search = db.search(input)?;
+        let duration = Instant::now().duration_since(start);
+
+        let count = search.phrases.len() + search.names.len() + search.characters.len();
+        found += count;
+
+        println!(
+            "  {input}: {} phrase(s), {} name(s), {} character(s) in {duration:?}",
+            search.phrases.len(),
+            search.names.len(),
+            search.characters.len(),
+        );
+    }
+
+    if found == 0 {
+        println!("Sample lookups returned no results");
+        failed += 1;
+    }
+
+    if failed > 0 {
+        bail!("Check found {failed} problem(s)");
+    }
+
+    println!("All checks passed");
+    Ok(())
+}
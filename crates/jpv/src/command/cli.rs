@@ -11,6 +11,7 @@ use lib::config::Config;
 use lib::data;
 use lib::database::{Database, Entry, Id};
 use lib::inflection;
+use lib::query;
 use lib::{Dirs, Form, Furigana, PartOfSpeech};
 
 use crate::Args;
@@ -49,8 +50,10 @@ pub(crate) struct CliArgs {
     /// json, json-pretty.
     #[arg(long)]
     output_format: Option<String>,
-    /// Search arguments to filter by. Must be either kana or kanji, which is
-    /// matched against entries searched for.
+    /// Search arguments to filter by. Each argument is a query, which supports
+    /// field prefixes like `kanji:`, `reading:`, `gloss:` and `pos:verb`,
+    /// `#tag` filters, `AND`, `OR` and `NOT` operators and grouping using
+    /// parenthesis.
     #[arg(name = "arguments")]
     arguments: Vec<String>,
 }
@@ -97,12 +100,13 @@ pub(crate) async fn run(
 
     for input in &cli_args.arguments {
         let seed = cli_args.sequences.is_empty();
+        let expr = query::parse(input);
 
         if seed {
-            to_look_up.extend(db.lookup(input)?);
+            to_look_up.extend(db.query(&expr)?);
         } else {
             let filter = db
-                .lookup(input)?
+                .query(&expr)?
                 .into_iter()
                 .map(|id| id.key())
                 .collect::<HashSet<_>>();
@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use lib::config::Config;
+use lib::data;
+use lib::database::{Database, Entry, Id};
+use lib::export::kindle;
+use lib::export::stardict::{self, StarDict};
+use lib::export::Article;
+use lib::jmdict;
+use lib::query;
+use lib::Dirs;
+
+use crate::Args;
+
+/// The number of entries in each content file of a Kindle dictionary.
+const KINDLE_ENTRIES_PER_FILE: usize = 10000;
+
+#[derive(Parser)]
+pub(crate) struct ExportArgs {
+    /// Format to export to. Available options are: stardict, kindle.
+    #[arg(long)]
+    format: String,
+    /// Directory to write the exported dictionary to. Defaults to the current
+    /// directory.
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Base name of the exported files. Defaults to `jpv`.
+    #[arg(long)]
+    name: Option<String>,
+    /// Export glossary entries for the specified language. Defaults to "eng".
+    #[arg(long)]
+    lang: Option<String>,
+    /// Export glossary entries for any language. Overrides `--lang <lang>`.
+    #[arg(long)]
+    any_lang: bool,
+    /// Only export entries matching these queries, which use the same syntax
+    /// as `jpv cli`. By default every entry is exported.
+    #[arg(name = "arguments")]
+    arguments: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    StarDict,
+    Kindle,
+}
+
+pub(crate) async fn run(
+    args: &Args,
+    export_args: &ExportArgs,
+    dirs: &Dirs,
+    config: Config,
+) -> Result<()> {
+    let format = match export_args.format.as_str() {
+        "stardict" => Format::StarDict,
+        "kindle" => Format::Kindle,
+        name => bail!("Unsupported export format: {}", name),
+    };
+
+    let indexes = data::open_from_args(&args.index[..], dirs)?;
+    let db = Database::open(indexes, &config)?;
+
+    let ids = if export_args.arguments.is_empty() {
+        db.phrases()?
+    } else {
+        let mut ids = Vec::new();
+
+        for input in &export_args.arguments {
+            ids.extend(db.query(&query::parse(input))?);
+        }
+
+        ids
+    };
+
+    let lang = (!export_args.any_lang).then(|| export_args.lang.as_deref().unwrap_or("eng"));
+    let entries = entries(&db, ids)?;
+    let articles = entries
+        .values()
+        .filter_map(|entry| Article::new(entry, lang));
+
+    let output = export_args.output.as_deref().unwrap_or(Path::new("."));
+    let name = export_args.name.as_deref().unwrap_or("jpv");
+
+    fs::create_dir_all(output).with_context(|| output.display().to_string())?;
+
+    let count = match format {
+        Format::StarDict => write_stardict(output, name, articles)?,
+        Format::Kindle => {
+            let out_language = lang.and_then(kindle::language_code).unwrap_or("en");
+            write_kindle(output, name, out_language, articles)?
+        }
+    };
+
+    tracing::info!("Exported {count} entries to {}", output.display());
+
+    Ok(())
+}
+
+/// Load the phrases referenced by the given identifiers, ordered by sequence.
+fn entries(db: &Database, ids: Vec<Id>) -> Result<BTreeMap<u64, jmdict::Entry<'_>>> {
+    let mut entries = BTreeMap::new();
+
+    for id in ids {
+        if let Entry::Phrase(entry) = db.entry_at(id)? {
+            entries.entry(entry.sequence).or_insert(entry);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Write a StarDict dictionary, returning the number of exported entries.
+fn write_stardict<I>(output: &Path, name: &str, articles: I) -> Result<usize>
+where
+    I: IntoIterator<Item = Article>,
+{
+    let create = |extension: &str| -> Result<BufWriter<File>> {
+        let path = output.join(format!("{name}.{extension}"));
+        let file = File::create(&path).with_context(|| path.display().to_string())?;
+        Ok(BufWriter::new(file))
+    };
+
+    let mut writer = StarDict::new(create("dict")?);
+    let mut count = 0;
+
+    for article in articles {
+        writer.add(&article)?;
+        count += 1;
+    }
+
+    let info = stardict::Info {
+        bookname: "Japanese Dictionary (jpv)",
+        description: Some("Exported from jpv, including conjugated forms of words."),
+    };
+
+    let ifo = writer.finish(&info, create("idx")?, create("syn")?)?;
+
+    let path = output.join(format!("{name}.ifo"));
+    fs::write(&path, ifo).with_context(|| path.display().to_string())?;
+    Ok(count)
+}
+
+/// Write the source package of a Kindle dictionary, returning the number of
+/// exported entries.
+fn write_kindle<I>(output: &Path, name: &str, out_language: &str, articles: I) -> Result<usize>
+where
+    I: IntoIterator<Item = Article>,
+{
+    let mut files = Vec::new();
+    let mut entries = String::new();
+    let mut count = 0;
+
+    let mut flush = |entries: &mut String| -> Result<()> {
+        let file = format!("{name}-{}.html", files.len());
+        let path = output.join(&file);
+        fs::write(&path, kindle::content(entries)).with_context(|| path.display().to_string())?;
+        files.push(file);
+        entries.clear();
+        Ok(())
+    };
+
+    for article in articles {
+        entries.push_str(&kindle::entry(&article));
+        count += 1;
+
+        if count % KINDLE_ENTRIES_PER_FILE == 0 {
+            flush(&mut entries)?;
+        }
+    }
+
+    if !entries.is_empty() {
+        flush(&mut entries)?;
+    }
+
+    let info = kindle::Info {
+        identifier: name,
+        title: "Japanese Dictionary (jpv)",
+        out_language,
+    };
+
+    let path = output.join(format!("{name}.opf"));
+    fs::write(&path, kindle::opf(&info, &files)).with_context(|| path.display().to_string())?;
+
+    tracing::info!(
+        "Convert {} into a MOBI file using kindlegen or Kindle Previewer",
+        path.display()
+    );
+
+    Ok(count)
+}
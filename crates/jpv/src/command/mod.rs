@@ -1,4 +1,10 @@
 pub mod build;
+pub mod check;
 pub mod cli;
+pub mod export;
+pub mod open;
+pub mod rpc;
+pub mod send;
 pub mod send_clipboard;
 pub mod service;
+pub mod user_data;
@@ -0,0 +1,32 @@
+use anyhow::{bail, Result};
+use clap::Parser;
+use lib::Dirs;
+
+use crate::dbus;
+use crate::instance;
+use crate::open_uri;
+
+#[derive(Parser)]
+pub(crate) struct OpenArgs {
+    /// The link to open, like `jpv://lookup?q=食べる`.
+    uri: String,
+}
+
+pub(crate) async fn run(args: &OpenArgs, dirs: &Dirs) -> Result<()> {
+    let query = lib::link::parse_deep_link(&args.uri)?;
+
+    // D-Bus starts the service if needed, which then opens the link.
+    match dbus::open(&args.uri).await {
+        Ok(true) => return Ok(()),
+        Ok(false) => {}
+        Err(error) => tracing::debug!("Failed to open link through D-Bus: {error}"),
+    }
+
+    let Some(address) = instance::find(dirs).await else {
+        bail!("The service isn't running, start it with `jpv service`");
+    };
+
+    let address = address.trim_end_matches('/');
+    open_uri::open(&format!("{address}/?{query}"));
+    Ok(())
+}
@@ -0,0 +1,468 @@
+//! JSON-RPC over standard input and output, or over a unix socket, so that
+//! editors can look up words without going through the service.
+//!
+//! Each line received is a JSON-RPC 2.0 request, and each response is written
+//! on a line of its own. Notifications, which have no `id`, are not responded
+//! to. A batch of requests sent as an array is responded to with an array.
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use lib::config::Config;
+use lib::data;
+use lib::database::{AnalyzeOptions, Database, SearchError};
+use lib::detect::is_kanji;
+use lib::{jmdict, Dirs, Furigana, FuriganaGroup, Score};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use crate::tokenizer::{self, Tokenizer};
+use crate::Args;
+
+/// Invalid JSON was received.
+const PARSE_ERROR: i64 = -32700;
+/// The JSON received is not a valid request.
+const INVALID_REQUEST: i64 = -32600;
+/// The requested method does not exist.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// The parameters of the method are invalid.
+const INVALID_PARAMS: i64 = -32602;
+/// Performing the method failed.
+const INTERNAL_ERROR: i64 = -32603;
+/// No dictionaries are installed or enabled.
+const INDEX_MISSING: i64 = -32000;
+
+/// The number of phrases returned by a search unless a limit is specified.
+const DEFAULT_LIMIT: usize = 20;
+
+#[derive(Parser)]
+pub(crate) struct RpcArgs {
+    /// Listen for connections on the given unix socket, instead of reading
+    /// requests from standard input.
+    #[arg(long, value_name = "path")]
+    socket: Option<PathBuf>,
+}
+
+/// What requests are answered from.
+struct Lookup {
+    db: Database,
+    tokenizer: Option<Arc<dyn Tokenizer>>,
+}
+
+pub(crate) async fn run(
+    args: &Args,
+    rpc_args: &RpcArgs,
+    dirs: &Dirs,
+    config: Config,
+) -> Result<()> {
+    let indexes = data::open_from_args(&args.index[..], dirs)?;
+    let db = Database::open(indexes, &config)?;
+    let tokenizer = config.tokenizer.map(tokenizer::backend);
+    let lookup = Lookup { db, tokenizer };
+
+    match &rpc_args.socket {
+        Some(path) => listen(Arc::new(lookup), path).await,
+        None => serve(&lookup, tokio::io::stdin(), tokio::io::stdout()).await,
+    }
+}
+
+#[cfg(unix)]
+async fn listen(lookup: Arc<Lookup>, path: &Path) -> Result<()> {
+    use anyhow::{bail, Context};
+    use tokio::net::{UnixListener, UnixStream};
+    use tokio::signal::ctrl_c;
+
+    if path.exists() {
+        // A socket which nothing answers on is left over from an earlier run
+        // which didn't shut down cleanly.
+        if UnixStream::connect(path).await.is_ok() {
+            bail!("{} is already in use", path.display());
+        }
+
+        std::fs::remove_file(path).with_context(|| path.display().to_string())?;
+    }
+
+    let listener = UnixListener::bind(path).with_context(|| path.display().to_string())?;
+    tracing::info!("Listening on {}", path.display());
+
+    let result = loop {
+        tokio::select! {
+            stream = listener.accept() => {
+                let (stream, _) = match stream {
+                    Ok(stream) => stream,
+                    Err(error) => break Err(error.into()),
+                };
+
+                let lookup = lookup.clone();
+
+                tokio::spawn(async move {
+                    let (reader, writer) = stream.into_split();
+
+                    if let Err(error) = serve(&lookup, reader, writer).await {
+                        tracing::error!(?error, "Connection failed");
+                    }
+                });
+            }
+            _ = ctrl_c() => {
+                break Ok(());
+            }
+        }
+    };
+
+    _ = std::fs::remove_file(path);
+    result
+}
+
+#[cfg(not(unix))]
+async fn listen(_: Arc<Lookup>, _: &Path) -> Result<()> {
+    anyhow::bail!("Unix sockets are not supported on this platform")
+}
+
+/// Serve requests read from one connection until it's closed.
+async fn serve<R, W>(lookup: &Lookup, reader: R, mut writer: W) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(reply) = handle(lookup, &line) else {
+            continue;
+        };
+
+        let mut bytes = serde_json::to_vec(&reply)?;
+        bytes.push(b'\n');
+        writer.write_all(&bytes).await?;
+        writer.flush().await?;
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Error>,
+}
+
+impl Response {
+    fn new(id: Value, result: Result<Value, Error>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Error {
+    code: i64,
+    message: String,
+}
+
+impl Error {
+    fn new(code: i64, message: impl fmt::Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for Error {
+    fn from(error: anyhow::Error) -> Self {
+        Self::new(INTERNAL_ERROR, error)
+    }
+}
+
+impl From<SearchError> for Error {
+    fn from(error: SearchError) -> Self {
+        match error {
+            SearchError::IndexMissing => Self::new(INDEX_MISSING, error),
+            error => Self::new(INTERNAL_ERROR, error),
+        }
+    }
+}
+
+/// What is sent in response to a line.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Reply {
+    Single(Response),
+    Batch(Vec<Response>),
+}
+
+/// Handle a single line, returning the reply to send if any.
+fn handle(lookup: &Lookup, line: &str) -> Option<Reply> {
+    let request = match serde_json::from_str::<Value>(line) {
+        Ok(request) => request,
+        Err(error) => {
+            let error = Error::new(PARSE_ERROR, error);
+            return Some(Reply::Single(Response::new(Value::Null, Err(error))));
+        }
+    };
+
+    let Value::Array(requests) = request else {
+        return handle_request(lookup, request).map(Reply::Single);
+    };
+
+    if requests.is_empty() {
+        let error = Error::new(INVALID_REQUEST, "Empty batch");
+        return Some(Reply::Single(Response::new(Value::Null, Err(error))));
+    }
+
+    let responses = requests
+        .into_iter()
+        .filter_map(|request| handle_request(lookup, request))
+        .collect::<Vec<_>>();
+
+    // A batch of only notifications is not responded to.
+    if responses.is_empty() {
+        return None;
+    }
+
+    Some(Reply::Batch(responses))
+}
+
+/// Handle a single request, returning the response to send if any.
+fn handle_request(lookup: &Lookup, request: Value) -> Option<Response> {
+    let request = match serde_json::from_value::<Request>(request) {
+        Ok(request) => request,
+        Err(error) => {
+            let error = Error::new(INVALID_REQUEST, error);
+            return Some(Response::new(Value::Null, Err(error)));
+        }
+    };
+
+    let result = call(lookup, &request.method, request.params);
+    Some(Response::new(request.id?, result))
+}
+
+fn call(lookup: &Lookup, method: &str, params: Value) -> Result<Value, Error> {
+    match method {
+        "search" => reply(search(&lookup.db, parse(params)?)),
+        "analyze" => reply(analyze(lookup, parse(params)?)),
+        "furigana" => reply(furigana(lookup, parse(params)?)),
+        _ => Err(Error::new(
+            METHOD_NOT_FOUND,
+            format_args!("No method named `{method}`"),
+        )),
+    }
+}
+
+fn parse<T>(params: Value) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_value(params).map_err(|error| Error::new(INVALID_PARAMS, error))
+}
+
+fn reply<T>(result: Result<T, Error>) -> Result<Value, Error>
+where
+    T: Serialize,
+{
+    serde_json::to_value(result?).map_err(|error| Error::new(INTERNAL_ERROR, error))
+}
+
+/// Analysis and furigana silently produce nothing without dictionaries, which
+/// would be confusing to editors.
+fn ensure_installed(db: &Database) -> Result<(), Error> {
+    if db.installed()?.is_empty() {
+        return Err(SearchError::IndexMissing.into());
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct Phrase<'a> {
+    sequence: u64,
+    kanji: Vec<&'a str>,
+    readings: Vec<&'a str>,
+    glosses: Vec<&'a str>,
+}
+
+fn search(db: &Database, params: SearchParams) -> Result<Vec<Phrase<'_>>, Error> {
+    let search = db.search(&params.q)?;
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+
+    let phrases = search
+        .phrases
+        .into_iter()
+        .take(limit)
+        .map(|(_, entry)| Phrase {
+            sequence: entry.sequence,
+            kanji: entry.kanji_elements.iter().map(|k| k.text).collect(),
+            readings: entry.reading_elements.iter().map(|r| r.text).collect(),
+            glosses: entry
+                .senses
+                .iter()
+                .filter(|sense| sense.is_lang("eng"))
+                .flat_map(|sense| sense.gloss.iter().map(|g| g.text))
+                .collect(),
+        })
+        .collect();
+
+    Ok(phrases)
+}
+
+#[derive(Deserialize)]
+struct AnalyzeParams {
+    q: String,
+    /// Byte offset in `q` to analyze from.
+    #[serde(default)]
+    start: usize,
+}
+
+#[derive(Serialize)]
+struct Candidate {
+    text: String,
+    score: Score,
+}
+
+fn analyze(lookup: &Lookup, params: AnalyzeParams) -> Result<Vec<Candidate>, Error> {
+    ensure_installed(&lookup.db)?;
+
+    let words = tokenizer::words(lookup.tokenizer.as_deref(), &params.q);
+
+    let options = AnalyzeOptions {
+        words: &words,
+        ..AnalyzeOptions::default()
+    };
+
+    let candidates = lookup
+        .db
+        .analyze_span_with(&params.q, params.start, params.q.len(), &options)?
+        .into_iter()
+        .map(|candidate| Candidate {
+            text: candidate.string.to_owned(),
+            score: candidate.score,
+        })
+        .collect();
+
+    Ok(candidates)
+}
+
+#[derive(Deserialize)]
+struct FuriganaParams {
+    text: String,
+}
+
+/// A segment of text, with the reading of it if it contains kanji.
+#[derive(Serialize)]
+struct Segment {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reading: Option<String>,
+}
+
+fn furigana(lookup: &Lookup, params: FuriganaParams) -> Result<Vec<Segment>, Error> {
+    ensure_installed(&lookup.db)?;
+
+    let tokenizer = lookup.tokenizer.as_deref();
+
+    let text = params.text.as_str();
+    let mut segments = Vec::new();
+    let mut last = 0;
+
+    for span in tokenizer::phrase_spans(&lookup.db, tokenizer, text)? {
+        push_text(&mut segments, &text[last..span.range.start]);
+
+        let phrase = &text[span.range.clone()];
+
+        match reading_of(phrase, &span.entry) {
+            Some(furigana) => {
+                for group in furigana.iter() {
+                    match group {
+                        FuriganaGroup::Kanji(kanji, reading) => segments.push(Segment {
+                            text: kanji.to_owned(),
+                            reading: Some(reading.to_owned()),
+                        }),
+                        FuriganaGroup::Kana(kana) => push_text(&mut segments, kana),
+                    }
+                }
+            }
+            None => push_text(&mut segments, phrase),
+        }
+
+        last = span.range.end;
+    }
+
+    push_text(&mut segments, &text[last..]);
+    Ok(segments)
+}
+
+/// Push text without a reading, joining it with the previous segment if that
+/// doesn't have one either.
+fn push_text(segments: &mut Vec<Segment>, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+
+    if let Some(last) = segments.last_mut().filter(|s| s.reading.is_none()) {
+        last.text.push_str(text);
+        return;
+    }
+
+    segments.push(Segment {
+        text: text.to_owned(),
+        reading: None,
+    });
+}
+
+/// Find the reading of a phrase, which might be an inflection of the
+/// dictionary entry it was matched against.
+fn reading_of<'a>(phrase: &'a str, entry: &jmdict::Entry<'a>) -> Option<Furigana<'a, 1, 1>> {
+    if !phrase.chars().any(is_kanji) {
+        return None;
+    }
+
+    entry.kanji_elements.iter().find_map(|kanji| {
+        let reading = entry
+            .reading_elements
+            .iter()
+            .find(|reading| reading.applies_to(kanji.text))?;
+
+        Furigana::inflected(phrase, kanji.text, reading.text)
+    })
+}
@@ -0,0 +1,222 @@
+use serde_json::{json, Value};
+
+use super::{
+    Lookup, INDEX_MISSING, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR,
+};
+
+fn lookup(db: lib::database::Database) -> Lookup {
+    Lookup {
+        db,
+        tokenizer: None,
+    }
+}
+
+/// Handle a line, returning the reply as JSON.
+fn handle(lookup: &Lookup, line: &str) -> Option<Value> {
+    let reply = super::handle(lookup, line)?;
+    Some(serde_json::to_value(reply).unwrap())
+}
+
+fn error(id: Value, code: i64) -> impl Fn(&Value) {
+    move |reply| {
+        assert_eq!(reply["jsonrpc"], "2.0");
+        assert_eq!(reply["id"], id, "{reply}");
+        assert_eq!(reply["error"]["code"], code, "{reply}");
+        assert!(reply.get("result").is_none(), "{reply}");
+    }
+}
+
+#[test]
+fn methods() {
+    let (db, _dir) = crate::testing::database();
+    let lookup = lookup(db);
+
+    let reply = handle(
+        &lookup,
+        r#"{"jsonrpc":"2.0","id":1,"method":"search","params":{"q":"光","limit":1}}"#,
+    );
+
+    assert_eq!(
+        reply,
+        Some(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": [{
+                "sequence": 2,
+                "kanji": ["光"],
+                "readings": ["ひかり"],
+                "glosses": ["light", "illumination", "hope"],
+            }],
+        }))
+    );
+
+    let reply = handle(
+        &lookup,
+        r#"{"jsonrpc":"2.0","id":"a","method":"furigana","params":{"text":"光が"}}"#,
+    );
+
+    assert_eq!(
+        reply,
+        Some(json!({
+            "jsonrpc": "2.0",
+            "id": "a",
+            "result": [
+                {"text": "光", "reading": "ひかり"},
+                {"text": "が"},
+            ],
+        }))
+    );
+
+    let reply = handle(
+        &lookup,
+        r#"{"jsonrpc":"2.0","id":2,"method":"analyze","params":{"q":"光線"}}"#,
+    )
+    .unwrap();
+
+    let candidates = reply["result"].as_array().unwrap();
+    assert!(candidates.iter().any(|c| c["text"] == "光線"), "{reply}");
+    assert!(candidates.iter().any(|c| c["text"] == "光"), "{reply}");
+}
+
+#[test]
+fn errors() {
+    let (db, _dir) = crate::testing::database();
+    let lookup = lookup(db);
+
+    let check = |line: &str, id: Value, code: i64| {
+        error(id, code)(&handle(&lookup, line).unwrap());
+    };
+
+    check(
+        r#"{"jsonrpc":"2.0","id":1,"method":"define"}"#,
+        json!(1),
+        METHOD_NOT_FOUND,
+    );
+    check(
+        r#"{"jsonrpc":"2.0","id":2,"method":"search","params":{}}"#,
+        json!(2),
+        INVALID_PARAMS,
+    );
+    check(
+        r#"{"jsonrpc":"2.0","id":3,"method":"search","params":{"q":1}}"#,
+        json!(3),
+        INVALID_PARAMS,
+    );
+    check(
+        r#"{"jsonrpc":"2.0","id":4,"method":"search"}"#,
+        json!(4),
+        INVALID_PARAMS,
+    );
+    check(r#"{"jsonrpc":"2.0","id":5,"#, Value::Null, PARSE_ERROR);
+    check("光", Value::Null, PARSE_ERROR);
+    check(r#"{"jsonrpc":"2.0","id":6}"#, Value::Null, INVALID_REQUEST);
+    check("1", Value::Null, INVALID_REQUEST);
+}
+
+#[test]
+fn index_missing() {
+    let lookup = lookup(crate::testing::empty_database());
+
+    for method in ["search", "analyze"] {
+        let line =
+            format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{method}","params":{{"q":"光"}}}}"#);
+        error(json!(1), INDEX_MISSING)(&handle(&lookup, &line).unwrap());
+    }
+
+    let line = r#"{"jsonrpc":"2.0","id":1,"method":"furigana","params":{"text":"光"}}"#;
+    error(json!(1), INDEX_MISSING)(&handle(&lookup, line).unwrap());
+}
+
+#[test]
+fn notifications() {
+    let (db, _dir) = crate::testing::database();
+    let lookup = lookup(db);
+
+    // Notifications are not responded to, even if they fail.
+    assert_eq!(
+        handle(
+            &lookup,
+            r#"{"jsonrpc":"2.0","method":"search","params":{"q":"光"}}"#
+        ),
+        None
+    );
+    assert_eq!(
+        handle(&lookup, r#"{"jsonrpc":"2.0","method":"define"}"#),
+        None
+    );
+}
+
+#[test]
+fn batch() {
+    let (db, _dir) = crate::testing::database();
+    let lookup = lookup(db);
+
+    let reply = handle(
+        &lookup,
+        r#"[
+            {"jsonrpc":"2.0","id":1,"method":"search","params":{"q":"光線"}},
+            {"jsonrpc":"2.0","method":"search","params":{"q":"光"}},
+            {"jsonrpc":"2.0","id":2,"method":"define"},
+            {"jsonrpc":"2.0","id":3,"method":"search","params":[]},
+            1
+        ]"#,
+    )
+    .unwrap();
+
+    // Responses are in the order of the requests, leaving out notifications.
+    let [search, define, params, invalid] = reply.as_array().unwrap().as_slice() else {
+        panic!("{reply}");
+    };
+
+    assert_eq!(search["id"], 1);
+    assert_eq!(search["result"][0]["kanji"], json!(["光線"]));
+    error(json!(2), METHOD_NOT_FOUND)(define);
+    error(json!(3), INVALID_PARAMS)(params);
+    error(Value::Null, INVALID_REQUEST)(invalid);
+
+    // An empty batch is an invalid request in itself.
+    let reply = handle(&lookup, "[]").unwrap();
+    assert!(reply.is_object(), "{reply}");
+    error(Value::Null, INVALID_REQUEST)(&reply);
+
+    // A batch of only notifications isn't responded to.
+    let reply = handle(
+        &lookup,
+        r#"[{"jsonrpc":"2.0","method":"search","params":{"q":"光"}},{"jsonrpc":"2.0","method":"define"}]"#,
+    );
+    assert_eq!(reply, None);
+}
+
+#[tokio::test]
+async fn serve() {
+    let (db, _dir) = crate::testing::database();
+    let lookup = lookup(db);
+
+    let input = concat!(
+        "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"define\"}\n",
+        "\n",
+        "{\"jsonrpc\":\"2.0\",\"method\":\"define\"}\n",
+        "[{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"search\",\"params\":{\"q\":\"光線\"}}]\n",
+    );
+
+    let mut output = Vec::new();
+    super::serve(&lookup, input.as_bytes(), &mut output)
+        .await
+        .unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+
+    // Blank lines and notifications are skipped, and every reply is written on
+    // a line of its own.
+    let [first, second] = lines.as_slice() else {
+        panic!("{output}");
+    };
+
+    let first = serde_json::from_str::<Value>(first).unwrap();
+    error(json!(1), METHOD_NOT_FOUND)(&first);
+
+    let second = serde_json::from_str::<Value>(second).unwrap();
+    assert_eq!(second[0]["id"], 2);
+    assert_eq!(second[0]["result"][0]["sequence"], 1);
+}
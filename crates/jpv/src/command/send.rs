@@ -0,0 +1,97 @@
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use lib::Dirs;
+
+use crate::dbus;
+use crate::instance;
+
+/// Text is sent as UTF-8, unlike plain text from the clipboard.
+const MIMETYPE: &str = "text/plain;charset=utf-8";
+
+#[derive(Parser)]
+pub(crate) struct SendArgs {
+    /// Read the text from standard input, which is also done when no text or
+    /// file is given, like in `cat chapter.txt | jpv send`.
+    #[arg(long, conflicts_with_all = ["file", "text"])]
+    stdin: bool,
+    /// Read the text from the given file.
+    #[arg(long, value_name = "path", conflicts_with = "text")]
+    file: Option<PathBuf>,
+    /// The application the text comes from, which captures can be filtered
+    /// by.
+    #[arg(long)]
+    source: Option<String>,
+    /// The text to send.
+    text: Option<String>,
+}
+
+pub(crate) async fn run(args: &SendArgs, dirs: &Dirs) -> Result<()> {
+    let text = match (&args.text, &args.file) {
+        (Some(text), _) => text.clone(),
+        (None, Some(path)) => {
+            std::fs::read_to_string(path).with_context(|| path.display().to_string())?
+        }
+        (None, None) => {
+            let mut stdin = std::io::stdin();
+
+            if !args.stdin && stdin.is_terminal() {
+                bail!("Nothing to send, give the text, a --file or pipe it to standard input");
+            }
+
+            let mut text = String::new();
+            stdin
+                .read_to_string(&mut text)
+                .context("Reading standard input")?;
+            text
+        }
+    };
+
+    if text.trim().is_empty() {
+        bail!("Nothing to send, the text is empty");
+    }
+
+    let source = args.source.as_deref();
+
+    // D-Bus starts the service if needed.
+    match dbus::send_clipboard(Some(MIMETYPE), text.as_bytes(), source).await {
+        Ok(()) => return Ok(()),
+        Err(error) => tracing::debug!("Failed to send through D-Bus: {error}"),
+    }
+
+    let Some(address) = instance::find(dirs).await else {
+        bail!("The service isn't running, start it with `jpv service`");
+    };
+
+    post(&address, text, source).await
+}
+
+/// Post the text to the capture endpoint of the service at the given address.
+#[cfg(feature = "reqwest")]
+async fn post(address: &str, text: String, source: Option<&str>) -> Result<()> {
+    let client = reqwest::ClientBuilder::new().build()?;
+
+    let mut request = client
+        .post(format!("{}/api/capture", address.trim_end_matches('/')))
+        .header("Content-Type", MIMETYPE)
+        .body(text);
+
+    if let Some(source) = source {
+        request = request.query(&[("source", source)]);
+    }
+
+    request
+        .send()
+        .await
+        .with_context(|| format!("Sending to {address}"))?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(not(feature = "reqwest"))]
+async fn post(_: &str, _: String, _: Option<&str>) -> Result<()> {
+    bail!("Sending to the service over HTTP is not supported")
+}
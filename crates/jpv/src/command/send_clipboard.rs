@@ -10,9 +10,13 @@ pub(crate) struct SendClipboardArgs {
     /// The mime type of the data to send.
     #[arg(long = "type", name = "type")]
     ty: Option<String>,
-    /// A secondary argument to send.
+    /// A translation of the text, which is sent along with it as JSON.
+    #[arg(long, alias = "secondary")]
+    translation: Option<String>,
+    /// The application the data comes from, which captures can be filtered
+    /// by.
     #[arg(long)]
-    secondary: Option<String>,
+    source: Option<String>,
     /// The data to send.
     data: OsString,
 }
@@ -20,17 +24,19 @@ pub(crate) struct SendClipboardArgs {
 pub(crate) async fn run(args: &SendClipboardArgs) -> Result<()> {
     match args.ty.as_deref() {
         Some("application/json") => {
-            let json = lib::api::SendClipboardJson {
-                primary: args.data.to_string_lossy().into_owned(),
-                secondary: args.secondary.clone(),
+            let json = lib::api::CaptureJson {
+                text: args.data.to_string_lossy().into_owned(),
+                translation: args.translation.clone(),
+                source: None,
             };
 
             let data = serde_json::to_vec(&json)?;
-            crate::dbus::send_clipboard(args.ty.as_deref(), &data).await?;
+            crate::dbus::send_clipboard(args.ty.as_deref(), &data, args.source.as_deref()).await?;
         }
         _ => {
             let data = to_bytes(&args.data);
-            crate::dbus::send_clipboard(args.ty.as_deref(), data.as_ref()).await?;
+            crate::dbus::send_clipboard(args.ty.as_deref(), data.as_ref(), args.source.as_deref())
+                .await?;
         }
     }
 
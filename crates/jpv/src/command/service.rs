@@ -1,19 +1,25 @@
 use std::error::Error;
+use std::io;
 use std::net::SocketAddr;
 use std::net::TcpListener;
+use std::path::PathBuf;
 use std::pin::pin;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use async_fuse::Fuse;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use lib::config::Config;
 use lib::data;
+use lib::stats::{self, Activity};
 use lib::Dirs;
 use tokio::signal::ctrl_c;
 use tokio::sync::Notify;
 
-use crate::background::Background;
+use crate::background::{self, Background};
 use crate::dbus;
+use crate::dict;
+use crate::install;
+use crate::instance::{self, Instance};
 use crate::open_uri;
 use crate::system;
 use crate::tasks::Tasks;
@@ -35,14 +41,30 @@ async fn shutdown_signal() -> Result<()> {
     Ok(())
 }
 
+#[derive(Subcommand)]
+pub(crate) enum ServiceCommand {
+    /// Install the service into the desktop of the current user, so that it's
+    /// started through D-Bus or systemd when needed and can be launched like
+    /// any other application. On Windows it's started in the tray when
+    /// logging in.
+    Install,
+    /// Remove what `install` installed.
+    Uninstall,
+}
+
 #[derive(Default, Parser)]
 pub(crate) struct ServiceArgs {
+    #[command(subcommand)]
+    pub(crate) command: Option<ServiceCommand>,
     /// Run the dictionary as a background service. This will prevent a browser window from being opened to the service once it's started.
     #[arg(long)]
     pub(crate) background: bool,
     /// Do not open the URI of the dictionary when started.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "open")]
     pub(crate) no_open: bool,
+    /// Open the URI of the dictionary in the default browser once it's ready, even if this is disabled in the configuration.
+    #[arg(long)]
+    pub(crate) open: bool,
     /// Disable D-Bus binding.
     #[cfg(all(unix, feature = "dbus"))]
     #[arg(long)]
@@ -51,9 +73,27 @@ pub(crate) struct ServiceArgs {
     #[cfg(all(unix, feature = "dbus"))]
     #[arg(long)]
     pub(crate) dbus_system: bool,
-    /// Bind to the given address. Default is `127.0.0.1:44714`.
+    /// Bind to the given address. Default is `127.0.0.1:44714`, or any free port if that is used by another application.
     #[arg(long, value_name = "address")]
     bind: Option<String>,
+    /// Listen on the given port instead of the one in the bind address. Use `0` to pick any free port.
+    #[arg(long, value_name = "port")]
+    port: Option<u16>,
+    /// Share a read-only dictionary with other machines, like a classroom, by binding to the given address, like `0.0.0.0:44715`. Only searching is available through it, without access to user data or captures.
+    #[arg(long, value_name = "address")]
+    share: Option<String>,
+    /// The number of requests each address can make per minute to the shared dictionary.
+    #[arg(long, value_name = "count", default_value_t = 120)]
+    share_limit: u32,
+    /// Answer clients speaking the dict protocol from RFC 2229, like `dict` or Emacs' `dictionary-mode`, by binding to the given address, like `127.0.0.1:2628`.
+    #[arg(long, value_name = "address")]
+    dict: Option<String>,
+    /// Serve the dictionary under the given path, like `/jpv/`, when it's behind a reverse proxy which forwards that path.
+    #[arg(long, value_name = "path")]
+    base_path: Option<String>,
+    /// Serve the web application from the given directory instead of the one built into the binary, like `crates/web/dist` while it is rebuilt by `trunk watch`. Open pages are reloaded when it changes.
+    #[arg(long, value_name = "dir")]
+    assets: Option<PathBuf>,
 }
 
 pub(crate) async fn run(
@@ -64,12 +104,50 @@ pub(crate) async fn run(
     system_events: system::SystemEvents,
     log: crate::log::Capture,
 ) -> Result<()> {
-    let addr: SocketAddr = service_args
+    match &service_args.command {
+        Some(ServiceCommand::Install) => return install::install(args.data_dir.as_deref()),
+        Some(ServiceCommand::Uninstall) => return install::uninstall(),
+        None => {}
+    }
+
+    let mut addr: SocketAddr = service_args
         .bind
         .as_deref()
         .unwrap_or(self::web::BIND)
         .parse()?;
 
+    if let Some(port) = service_args.port {
+        addr.set_port(port);
+    }
+
+    // Only the default port is swapped for a free one, since other
+    // applications are configured to reach the service where they're told.
+    let fallback = service_args.bind.is_none() && service_args.port.is_none();
+
+    let share_addr = service_args
+        .share
+        .as_deref()
+        .map(str::parse::<SocketAddr>)
+        .transpose()
+        .context("Parsing --share address")?;
+
+    let dict_addr = service_args
+        .dict
+        .as_deref()
+        .map(str::parse::<SocketAddr>)
+        .transpose()
+        .context("Parsing --dict address")?;
+
+    let base = web::BasePath::new(service_args.base_path.as_deref().unwrap_or_default())
+        .context("Parsing --base-path")?;
+
+    let assets = service_args
+        .assets
+        .clone()
+        .map(web::Assets::new)
+        .transpose()
+        .context("Using --assets")?;
+
     let shutdown = Notify::new();
 
     let mut dbus = match dbus::setup(service_args)
@@ -92,7 +170,7 @@ pub(crate) async fn run(
         }
     };
 
-    let mut windows = match windows::setup()? {
+    let mut windows = match windows::setup(args.data_dir.as_deref())? {
         system::Setup::Start(windows) => windows,
         system::Setup::Port(port) => {
             tracing::info!("Listening on http://localhost:{port}");
@@ -105,24 +183,94 @@ pub(crate) async fn run(
             return Ok(());
         }
         system::Setup::Busy => {
+            // Another instance holds the mutex, so open it if we know where
+            // it is.
+            if let Some(address) = instance::running(&dirs).await {
+                tracing::info!("Already running on {address}");
+
+                if !service_args.no_open {
+                    open_uri::open(&address);
+                }
+            }
+
+            return Ok(());
+        }
+    };
+
+    let mut lock = match instance::acquire(&dirs).await? {
+        Instance::Acquired(lock) => lock,
+        Instance::Running(address) => {
+            tracing::info!("Already running on {address}");
+
+            if !service_args.no_open {
+                open_uri::open(&address);
+            }
+
+            return Ok(());
+        }
+        Instance::Starting => {
+            tracing::info!("Another instance is starting");
             return Ok(());
         }
     };
 
-    let listener = TcpListener::bind(addr)?;
+    let listener = match TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(error) if error.kind() == io::ErrorKind::AddrInUse => {
+            // The lock might be missing if the instance was started with a
+            // different data directory, so check what's on the port.
+            let address = format!("http://{addr}{}/", base.as_str());
+
+            if instance::probe(&address).await {
+                tracing::info!("Already running on {address}");
+
+                if !service_args.no_open {
+                    open_uri::open(&address);
+                }
+
+                return Ok(());
+            }
+
+            if !fallback {
+                return Err(error).with_context(|| anyhow!("Binding to {addr}"));
+            }
+
+            let listener = TcpListener::bind(SocketAddr::new(addr.ip(), 0))?;
+
+            tracing::warn!(
+                "Port {} is used by another application, using port {} instead",
+                addr.port(),
+                listener.local_addr()?.port()
+            );
+
+            listener
+        }
+        Err(error) => return Err(error.into()),
+    };
+
     let local_addr = listener.local_addr()?;
-    let local_port = web::PORT.unwrap_or(local_addr.port());
+    // When serving assets from a directory the application is no longer
+    // served separately by trunk.
+    let local_port = match &assets {
+        Some(..) => local_addr.port(),
+        None => web::PORT.unwrap_or(local_addr.port()),
+    };
 
     let mut windows = match &mut windows {
-        Some(windows) => Fuse::new(windows.start(local_port, shutdown.notified(), &system_events)),
+        Some(windows) => {
+            Fuse::new(windows.start(local_port, &base, shutdown.notified(), &system_events))
+        }
         None => Fuse::empty(),
     };
 
     let mut dbus = match &mut dbus {
-        Some(dbus) => Fuse::new(dbus.start(local_port, shutdown.notified(), &system_events)),
+        Some(dbus) => Fuse::new(dbus.start(local_port, &base, shutdown.notified(), &system_events)),
         None => Fuse::empty(),
     };
 
+    let open = service_args.open
+        || (!service_args.no_open && !service_args.background && config.open_browser);
+
     // SAFETY: we know this is only initialized once here exclusively.
     let indexes = data::open_from_args(&args.index[..], &dirs)?;
     let db = lib::database::Database::open(indexes, &config)?;
@@ -153,26 +301,79 @@ pub(crate) async fn run(
         }
     };
 
-    let background = Background::new(
+    let setup = background::Setup {
         dirs,
-        channel,
         config,
-        db,
-        system_events.clone(),
+        database: db,
         tesseract,
         log,
-    )?;
+        port: local_addr.port(),
+    };
+
+    let background = Background::new(setup, channel, system_events.clone())?;
 
     let mut server = pin!(web::setup(
         listener,
         background.clone(),
-        system_events.clone()
+        system_events.clone(),
+        base.clone(),
+        assets.clone(),
     )?);
-    tracing::info!("Listening on http://{local_addr}");
+    tracing::info!("Listening on http://{local_addr}{}/", base.as_str());
 
-    if !service_args.no_open {
-        let address = format!("http://localhost:{local_port}");
+    if let Some(assets) = &assets {
+        tracing::info!("Serving web application from {}", assets.dir().display());
+    }
+
+    let mut shared = pin!(match share_addr {
+        Some(share_addr) => {
+            let listener = TcpListener::bind(share_addr)?;
+            let share_addr = listener.local_addr()?;
+
+            let server = web::setup_shared(
+                listener,
+                background.clone(),
+                system_events.clone(),
+                base.clone(),
+                web::RateLimit::new(service_args.share_limit),
+            )?;
+
+            tracing::info!(
+                "Sharing a read-only dictionary on http://{share_addr}{}/",
+                base.as_str()
+            );
+            Fuse::new(server)
+        }
+        None => Fuse::empty(),
+    });
+
+    let mut dict = pin!(match dict_addr {
+        Some(dict_addr) => {
+            let listener = TcpListener::bind(dict_addr)?;
+            let dict_addr = listener.local_addr()?;
+            let server = dict::setup(listener, background.clone())?;
+            tracing::info!("Answering dict clients on {dict_addr}");
+            Fuse::new(server)
+        }
+        None => Fuse::empty(),
+    });
+
+    // The lock records the service itself, which is what's probed by other
+    // instances, even when the application is served by trunk.
+    let service_address = format!("http://localhost:{}{}/", local_addr.port(), base.as_str());
+
+    if let Err(error) = lock.write(&service_address) {
+        tracing::warn!("Failed to write lock file: {error}");
+    }
+
+    // The database is loaded and the server is listening by now, so the
+    // interface is ready as soon as the browser opens.
+    let address = format!("http://localhost:{local_port}{}/", base.as_str());
+
+    if open {
         open_uri::open(&address);
+    } else {
+        tracing::info!("Open {address} in a browser to use the dictionary");
     }
 
     let mut tasks = Tasks::new();
@@ -190,6 +391,10 @@ pub(crate) async fn run(
         Ok::<_, anyhow::Error>(())
     }));
 
+    // Captures are counted here rather than by clients, since several
+    // clients might be connected when something is captured.
+    let mut captures = system_events.subscribe();
+
     let mut needs_shutdown_signal = dbus.is_empty() && windows.is_empty();
 
     while needs_shutdown_signal || !dbus.is_empty() || !windows.is_empty() {
@@ -198,6 +403,14 @@ pub(crate) async fn run(
                 result?;
                 tracing::info!("Server shut down");
             }
+            result = shared.as_mut() => {
+                result?;
+                tracing::info!("Shared dictionary shut down");
+            }
+            result = dict.as_mut() => {
+                result?;
+                tracing::info!("Dict server shut down");
+            }
             result = dbus.as_pin_mut() => {
                 result?;
                 tracing::info!("D-Bus integration shut down");
@@ -211,6 +424,19 @@ pub(crate) async fn run(
             Some(event) = receiver.recv() => {
                 background.handle_event(event, args, &mut tasks).await.context("Handling background event")?;
             }
+            Ok(event) = captures.recv() => {
+                let source = background.clipboard().capture_source(&event);
+
+                if event.is_capture() && !source.as_deref().is_some_and(|source| background.is_ignored_source(source)) {
+                    background.record_activity(Activity::Capture);
+
+                    if let Some(text) = background.clipboard().captured_text(&event) {
+                        if let Some(text) = background.capture_filters().apply(&text) {
+                            background.record_capture(source.as_deref().unwrap_or(stats::DEFAULT_SOURCE), &text);
+                        }
+                    }
+                }
+            }
             result = tasks.wait() => {
                 let completed = result?;
                 background.complete_task(completed);
@@ -0,0 +1,58 @@
+use std::fs::File;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use lib::config::Config;
+use lib::profile::Profiles;
+use lib::Dirs;
+
+use crate::user_data;
+
+#[derive(Parser)]
+pub(crate) struct ExportArgs {
+    /// The archive to write.
+    path: PathBuf,
+}
+
+#[derive(Parser)]
+pub(crate) struct ImportArgs {
+    /// The archive to read.
+    path: PathBuf,
+    /// Don't replace the current configuration with the one in the archive.
+    #[arg(long)]
+    no_config: bool,
+}
+
+pub(crate) fn export(args: &ExportArgs, dirs: &Dirs, config: &Config) -> Result<()> {
+    let profiles = Profiles::new(dirs.profiles_dir());
+
+    let file = File::create(&args.path).with_context(|| args.path.display().to_string())?;
+    user_data::export(file, &profiles, config)?;
+
+    tracing::info!("Exported user data to {}", args.path.display());
+    Ok(())
+}
+
+pub(crate) fn import(args: &ImportArgs, dirs: &Dirs) -> Result<()> {
+    let file = File::open(&args.path).with_context(|| args.path.display().to_string())?;
+    let data = user_data::read(file)?;
+
+    let profiles = Profiles::new(dirs.profiles_dir());
+    data.store_profiles(&profiles)?;
+
+    if let (Some(config), false) = (&data.config, args.no_config) {
+        let path = dirs.config_path();
+        std::fs::create_dir_all(dirs.config_dir())?;
+        std::fs::write(&path, lib::toml::to_string_pretty(config)?)?;
+        tracing::info!("Wrote new configuration to {}", path.display());
+    }
+
+    tracing::info!(
+        "Imported {} profiles from {}",
+        data.profiles.len(),
+        args.path.display()
+    );
+
+    Ok(())
+}
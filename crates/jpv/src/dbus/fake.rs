@@ -3,10 +3,14 @@ use anyhow::{bail, Result};
 use crate::command::service::ServiceArgs;
 use crate::system::Setup;
 
-pub(crate) async fn send_clipboard(_: Option<&str>, _: &[u8]) -> Result<()> {
+pub(crate) async fn send_clipboard(_: Option<&str>, _: &[u8], _: Option<&str>) -> Result<()> {
     bail!("Sending the clipboard is not supported")
 }
 
+pub(crate) async fn open(_: &str) -> Result<bool> {
+    Ok(false)
+}
+
 pub(crate) async fn setup(_: &ServiceArgs) -> Result<Setup> {
     Ok(Setup::Start(None))
 }
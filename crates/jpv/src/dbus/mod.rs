@@ -7,4 +7,4 @@ mod r#impl;
 mod r#impl;
 
 pub(crate) use r#impl::shutdown;
-pub(crate) use r#impl::{send_clipboard, setup};
+pub(crate) use r#impl::{open, send_clipboard, setup};
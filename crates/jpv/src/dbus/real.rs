@@ -10,20 +10,35 @@ use tokio_dbus::{ty, BodyBuf, Connection, Flags, Message, MessageKind, ObjectPat
 use crate::command::service::ServiceArgs;
 use crate::open_uri;
 use crate::system::{Event, SendClipboardData, Setup, Start, SystemEvents};
+use crate::web::BasePath;
 
 const NAME: &str = "se.tedro.JapaneseDictionary";
 const PATH: &ObjectPath = ObjectPath::new_const(b"/se/tedro/JapaneseDictionary");
 
-pub(crate) async fn send_clipboard(ty: Option<&str>, data: &[u8]) -> Result<()> {
+pub(crate) async fn send_clipboard(
+    ty: Option<&str>,
+    data: &[u8],
+    source: Option<&str>,
+) -> Result<()> {
     let mut c = Connection::session_bus().await?;
 
     let mimetype = ty.unwrap_or("text/plain");
 
     let (_, send, body) = c.buffers();
-    body.arguments((mimetype, data))?;
+
+    let method = match source {
+        Some(source) => {
+            body.arguments((mimetype, data, source))?;
+            "SendClipboardDataFrom"
+        }
+        None => {
+            body.arguments((mimetype, data))?;
+            "SendClipboardData"
+        }
+    };
 
     let m = send
-        .method_call(PATH, "SendClipboardData")
+        .method_call(PATH, method)
         .with_interface(NAME)
         .with_destination(NAME)
         .with_body(body)
@@ -35,6 +50,37 @@ pub(crate) async fn send_clipboard(ty: Option<&str>, data: &[u8]) -> Result<()>
     Ok(())
 }
 
+/// Open a deep link in the service through the D-Bus interface of desktop
+/// applications, starting the service if it isn't running.
+pub(crate) async fn open(uri: &str) -> Result<bool> {
+    let mut c = Connection::session_bus().await?;
+
+    let (_, send, body) = c.buffers();
+
+    let mut uris = body.store_array::<ty::Str>()?;
+    uris.store(uri);
+    uris.finish();
+    body.store_array::<(ty::Str, ty::Variant)>()?.finish();
+
+    let m = send
+        .method_call(PATH, "Open")
+        .with_interface("org.freedesktop.Application")
+        .with_destination(NAME)
+        .with_body(body);
+
+    send.write_message(m)?;
+    c.wait().await?;
+
+    let message = c.last_message()?;
+
+    if let MessageKind::Error { error_name, .. } = message.kind() {
+        let reason = message.body().read::<str>().unwrap_or_default();
+        bail!("{error_name}: {reason}");
+    }
+
+    Ok(true)
+}
+
 pub(crate) async fn shutdown() -> Result<()> {
     let mut c = Connection::session_bus().await?;
 
@@ -108,6 +154,7 @@ impl Start for DbusStart {
     fn start<'a>(
         &'a mut self,
         port: u16,
+        base: &'a BasePath,
         shutdown: Notified<'a>,
         system_events: &'a SystemEvents,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
@@ -115,6 +162,7 @@ impl Start for DbusStart {
             let mut shutdown = pin!(Fuse::new(shutdown));
             let mut state = State {
                 port,
+                base,
                 system_events,
             };
 
@@ -164,6 +212,8 @@ impl Start for DbusStart {
 
 struct State<'a> {
     port: u16,
+    /// The path the service is served under.
+    base: &'a BasePath,
     system_events: &'a SystemEvents,
 }
 
@@ -189,10 +239,26 @@ fn handle_method_call<'a>(
     let m = match interface {
         "org.freedesktop.Application" => match member {
             "Activate" => {
-                let address = format!("http://localhost:{}", state.port);
+                let address = format!("http://localhost:{}{}/", state.port, state.base.as_str());
                 open_uri::open(&address);
                 (msg.method_return(send.next_serial()), None)
             }
+            "Open" => {
+                let mut body = msg.body();
+                let mut uris = body.load_array::<ty::Str>()?;
+
+                while let Some(uri) = uris.read()? {
+                    let query = lib::link::parse_deep_link(uri)?;
+                    let address = format!(
+                        "http://localhost:{}{}/?{query}",
+                        state.port,
+                        state.base.as_str()
+                    );
+                    open_uri::open(&address);
+                }
+
+                (msg.method_return(send.next_serial()), None)
+            }
             method => bail!("Unknown method: {method}"),
         },
         "se.tedro.JapaneseDictionary" => match member {
@@ -200,18 +266,28 @@ fn handle_method_call<'a>(
                 body.store(state.port)?;
                 (msg.method_return(send.next_serial()).with_body(body), None)
             }
-            "SendClipboardData" => {
+            "SendClipboardData" | "SendClipboardDataFrom" => {
                 let mut body = msg.body();
                 let mimetype = body.read::<str>()?;
                 let data = body.read::<[u8]>()?;
 
-                tracing::trace!(?mimetype, len = data.len());
+                // The source is only passed to the latter, and is empty
+                // when the sender doesn't know it.
+                let source = match member {
+                    "SendClipboardDataFrom" => Some(body.read::<str>()?.trim()),
+                    _ => None,
+                };
+
+                let source = source.filter(|s| !s.is_empty()).map(str::to_owned);
+
+                tracing::trace!(?mimetype, ?source, len = data.len());
 
                 state
                     .system_events
                     .send(Event::SendClipboardData(SendClipboardData {
                         mimetype: mimetype.to_owned(),
                         data: data.to_vec(),
+                        source,
                     }));
 
                 (msg.method_return(send.next_serial()), None)
@@ -0,0 +1,445 @@
+//! A server for the dict protocol described in RFC 2229, so that existing
+//! clients like `dict` or Emacs' `dictionary-mode` can look up words.
+//!
+//! Only a single database named `jpv` is served, which is searched like the
+//! search box in the interface.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+use std::future::Future;
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Result;
+use lib::database::{Database, SearchError};
+use lib::jmdict;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+use crate::background::Background;
+
+/// The name of the database served.
+const DATABASE: &str = "jpv";
+/// The description of the database served.
+const DESCRIPTION: &str = "Japanese dictionary";
+/// The maximum number of definitions returned by `DEFINE`.
+const DEFINE_LIMIT: usize = 20;
+/// The maximum number of words returned by `MATCH`.
+const MATCH_LIMIT: usize = 100;
+
+/// Commands which are recognized, used to tell malformed commands apart from
+/// unknown ones.
+const COMMANDS: &[&str] = &[
+    "DEFINE", "MATCH", "SHOW", "CLIENT", "STATUS", "HELP", "QUIT", "OPTION", "AUTH", "SASLAUTH",
+];
+
+const HELP: &str = "\
+DEFINE database word         -- look up word in database
+MATCH database strategy word -- match words in database using strategy
+SHOW DB                      -- list all accessible databases
+SHOW STRAT                   -- list available matching strategies
+SHOW INFO database           -- provide information about the database
+SHOW SERVER                  -- provide site-specific information
+CLIENT info                  -- identify client to server
+STATUS                       -- display timing information
+HELP                         -- display this help information
+QUIT                         -- terminate connection";
+
+/// Counter used to give each connection a unique message id.
+static CONNECTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Strategies supported by `MATCH`.
+#[derive(Clone, Copy)]
+enum Strategy {
+    Exact,
+    Prefix,
+}
+
+impl Strategy {
+    const ALL: [(&'static str, &'static str, Strategy); 2] = [
+        ("exact", "Match words exactly", Strategy::Exact),
+        ("prefix", "Match prefixes", Strategy::Prefix),
+    ];
+
+    fn parse(name: &str) -> Option<Self> {
+        // The `.` strategy is the default chosen by the server.
+        if name == "." {
+            return Some(Strategy::Prefix);
+        }
+
+        Self::ALL
+            .iter()
+            .find(|(n, ..)| n.eq_ignore_ascii_case(name))
+            .map(|&(.., strategy)| strategy)
+    }
+}
+
+/// Set up a server answering dict clients on the given listener.
+pub(crate) fn setup(
+    listener: TcpListener,
+    background: Background,
+) -> Result<impl Future<Output = Result<()>>> {
+    listener.set_nonblocking(true)?;
+    let listener = tokio::net::TcpListener::from_std(listener)?;
+
+    Ok(async move {
+        loop {
+            let (stream, addr) = listener.accept().await?;
+            let background = background.clone();
+
+            tokio::spawn(async move {
+                if let Err(error) = connection(stream, background).await {
+                    tracing::warn!(%addr, ?error, "Dict connection failed");
+                }
+            });
+        }
+    })
+}
+
+async fn connection(stream: TcpStream, background: Background) -> Result<()> {
+    let (reader, writer) = stream.into_split();
+    serve(reader, writer, || background.database()).await
+}
+
+/// Answer commands read from `reader` until the client quits, taking the
+/// database to search from `database` for each command.
+async fn serve<R, W>(reader: R, mut writer: W, database: impl Fn() -> Database) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = BufReader::new(reader).lines();
+
+    let id = CONNECTIONS.fetch_add(1, Ordering::Relaxed);
+    let banner = format!(
+        "220 jpv {} <> <{}.{id}@jpv>\r\n",
+        crate::VERSION.trim(),
+        std::process::id()
+    );
+    writer.write_all(banner.as_bytes()).await?;
+
+    while let Some(line) = lines.next_line().await? {
+        let db = database();
+        let (reply, quit) = tokio::task::spawn_blocking(move || respond(&db, &line)).await??;
+
+        writer.write_all(reply.out.as_bytes()).await?;
+
+        if quit {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// A reply being built, in the form it's sent.
+#[derive(Default)]
+struct Reply {
+    out: String,
+}
+
+impl Reply {
+    /// Add a status line.
+    fn status(&mut self, code: u16, text: impl fmt::Display) {
+        _ = write!(self.out, "{code} {text}\r\n");
+    }
+
+    /// Add a block of text, which is terminated by a line with a single dot.
+    fn text(&mut self, text: &str) {
+        for line in text.lines() {
+            // Lines starting with a dot are escaped by doubling it.
+            if line.starts_with('.') {
+                self.out.push('.');
+            }
+
+            self.out.push_str(line);
+            self.out.push_str("\r\n");
+        }
+
+        self.out.push_str(".\r\n");
+    }
+
+    fn ok(&mut self) {
+        self.status(250, "ok");
+    }
+}
+
+/// Respond to a single command, returning the reply and whether the
+/// connection should be closed.
+fn respond(db: &Database, line: &str) -> Result<(Reply, bool)> {
+    let mut reply = Reply::default();
+
+    let Some(words) = split(line) else {
+        reply.status(501, "syntax error, illegal parameters");
+        return Ok((reply, false));
+    };
+
+    let Some((command, args)) = words.split_first() else {
+        reply.status(500, "syntax error, command not recognized");
+        return Ok((reply, false));
+    };
+
+    let command = command.to_ascii_uppercase();
+
+    match (command.as_str(), args) {
+        ("DEFINE", [database, word]) => {
+            if is_database(&mut reply, database) {
+                define(db, &mut reply, word)?;
+            }
+        }
+        ("MATCH", [database, strategy, word]) => {
+            if is_database(&mut reply, database) {
+                match Strategy::parse(strategy) {
+                    Some(strategy) => matches(db, &mut reply, strategy, word)?,
+                    None => reply.status(
+                        551,
+                        "invalid strategy, use \"SHOW STRAT\" for a list of strategies",
+                    ),
+                }
+            }
+        }
+        ("SHOW", [what]) => match what.to_ascii_uppercase().as_str() {
+            "DB" | "DATABASES" => {
+                reply.status(110, "1 databases present");
+                reply.text(&format!("{DATABASE} {}", quote(DESCRIPTION)));
+                reply.ok();
+            }
+            "STRAT" | "STRATEGIES" => {
+                reply.status(
+                    111,
+                    format_args!("{} strategies present", Strategy::ALL.len()),
+                );
+
+                let mut text = String::new();
+
+                for (name, description, _) in Strategy::ALL {
+                    _ = writeln!(text, "{name} {}", quote(description));
+                }
+
+                reply.text(&text);
+                reply.ok();
+            }
+            "SERVER" => {
+                reply.status(114, "server information follows");
+                reply.text(&format!("jpv {}", crate::VERSION.trim()));
+                reply.ok();
+            }
+            _ => reply.status(501, "syntax error, illegal parameters"),
+        },
+        ("SHOW", [what, database]) if what.eq_ignore_ascii_case("INFO") => {
+            if is_database(&mut reply, database) {
+                let mut installed = db.installed()?.into_iter().collect::<Vec<_>>();
+                installed.sort();
+
+                let installed = match installed.as_slice() {
+                    [] => String::from("none"),
+                    names => names.join(", "),
+                };
+
+                reply.status(112, "database information follows");
+                reply.text(&format!(
+                    "{DESCRIPTION} served by jpv.\n\nInstalled dictionaries: {installed}"
+                ));
+                reply.ok();
+            }
+        }
+        ("CLIENT", _) => reply.ok(),
+        ("STATUS", []) => reply.status(210, "status ok"),
+        ("HELP", []) => {
+            reply.status(113, "help text follows");
+            reply.text(HELP);
+            reply.ok();
+        }
+        ("QUIT", []) => {
+            reply.status(221, "bye");
+            return Ok((reply, true));
+        }
+        ("OPTION" | "AUTH" | "SASLAUTH", _) => reply.status(502, "command not implemented"),
+        (command, _) if COMMANDS.contains(&command) => {
+            reply.status(501, "syntax error, illegal parameters")
+        }
+        _ => reply.status(500, "unknown command"),
+    }
+
+    Ok((reply, false))
+}
+
+/// Test if the named database is served, replying with an error if not.
+///
+/// Besides the database itself, `*` and `!` refer to all databases and the
+/// first database with a match respectively.
+fn is_database(reply: &mut Reply, name: &str) -> bool {
+    if matches!(name, DATABASE | "*" | "!") {
+        return true;
+    }
+
+    reply.status(
+        550,
+        "invalid database, use \"SHOW DB\" for a list of databases",
+    );
+    false
+}
+
+fn define(db: &Database, reply: &mut Reply, word: &str) -> Result<()> {
+    let Some(phrases) = search(db, reply, word)? else {
+        return Ok(());
+    };
+
+    let phrases = phrases.into_iter().take(DEFINE_LIMIT).collect::<Vec<_>>();
+
+    if phrases.is_empty() {
+        reply.status(552, "no match");
+        return Ok(());
+    }
+
+    reply.status(150, format_args!("{} definitions retrieved", phrases.len()));
+
+    for entry in &phrases {
+        reply.status(
+            151,
+            format_args!("{} {DATABASE} {}", quote(word), quote(DESCRIPTION)),
+        );
+        reply.text(&definition(entry));
+    }
+
+    reply.ok();
+    Ok(())
+}
+
+fn matches(db: &Database, reply: &mut Reply, strategy: Strategy, word: &str) -> Result<()> {
+    let query = match strategy {
+        Strategy::Exact => word.to_owned(),
+        Strategy::Prefix => format!("{word}*"),
+    };
+
+    let Some(phrases) = search(db, reply, &query)? else {
+        return Ok(());
+    };
+
+    let mut seen = HashSet::new();
+    let mut text = String::new();
+
+    for entry in &phrases {
+        let headword = entry
+            .kanji_elements
+            .first()
+            .map(|kanji| kanji.text)
+            .or_else(|| entry.reading_elements.first().map(|reading| reading.text));
+
+        let Some(headword) = headword else {
+            continue;
+        };
+
+        if seen.len() == MATCH_LIMIT {
+            break;
+        }
+
+        if seen.insert(headword) {
+            _ = writeln!(text, "{DATABASE} {}", quote(headword));
+        }
+    }
+
+    if seen.is_empty() {
+        reply.status(552, "no match");
+        return Ok(());
+    }
+
+    reply.status(152, format_args!("{} matches found", seen.len()));
+    reply.text(&text);
+    reply.ok();
+    Ok(())
+}
+
+/// Search the database, replying with an error if there are no dictionaries.
+fn search<'a>(
+    db: &'a Database,
+    reply: &mut Reply,
+    query: &str,
+) -> Result<Option<Vec<jmdict::Entry<'a>>>> {
+    match db.search(query) {
+        Ok(search) => Ok(Some(
+            search.phrases.into_iter().map(|(_, entry)| entry).collect(),
+        )),
+        Err(SearchError::IndexMissing) => {
+            reply.status(554, "no databases present");
+            Ok(None)
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Format a phrase as a definition, like:
+///
+/// ```text
+/// 食べる 【たべる】
+///   1. to eat
+///   2. to live on (e.g. a salary); to live off; to subsist on
+/// ```
+fn definition(entry: &jmdict::Entry<'_>) -> String {
+    let kanji = join(entry.kanji_elements.iter().map(|kanji| kanji.text));
+    let readings = join(entry.reading_elements.iter().map(|reading| reading.text));
+
+    let mut text = if kanji.is_empty() {
+        readings
+    } else {
+        format!("{kanji} 【{readings}】")
+    };
+
+    text.push('\n');
+
+    let senses = entry.senses.iter().filter(|sense| sense.is_lang("eng"));
+
+    for (n, sense) in senses.enumerate() {
+        let glosses = sense
+            .gloss
+            .iter()
+            .map(|gloss| gloss.text)
+            .collect::<Vec<_>>();
+        _ = writeln!(text, "  {}. {}", n + 1, glosses.join("; "));
+    }
+
+    text
+}
+
+fn join<'a>(texts: impl Iterator<Item = &'a str>) -> String {
+    texts.collect::<Vec<_>>().join("・")
+}
+
+/// Quote a string so that it's read as a single word.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Split a command into words, where words can be quoted with single or double
+/// quotes and characters can be escaped with a backslash.
+///
+/// Returns `None` if a quote or an escape is left unterminated.
+fn split(line: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut quote = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (_, '\\') => word.get_or_insert_with(String::new).push(chars.next()?),
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (_, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+
+    words.extend(word);
+    Some(words)
+}
@@ -0,0 +1,127 @@
+/// Send the given input to a connection and collect everything it replies
+/// after the banner, along with how the connection ended.
+async fn session(input: &[u8]) -> (String, anyhow::Result<()>) {
+    let (db, _dir) = crate::testing::database();
+    let mut output = Vec::new();
+    let result = super::serve(input, &mut output, || db.clone()).await;
+
+    let output = String::from_utf8(output).unwrap();
+    let (banner, rest) = output.split_once("\r\n").unwrap();
+    assert!(banner.starts_with("220 jpv "), "{banner:?}");
+    (rest.to_owned(), result)
+}
+
+fn respond(line: &str) -> String {
+    let (db, _dir) = crate::testing::database();
+    let (reply, quit) = super::respond(&db, line).unwrap();
+    assert!(!quit);
+    reply.out
+}
+
+#[test]
+fn split() {
+    let split = |line| super::split(line).map(|words| words.join("|"));
+
+    assert_eq!(split("DEFINE jpv 光").as_deref(), Some("DEFINE|jpv|光"));
+    assert_eq!(
+        split("  define   jpv  光  ").as_deref(),
+        Some("define|jpv|光")
+    );
+    assert_eq!(
+        split("MATCH jpv . \"light of day\"").as_deref(),
+        Some("MATCH|jpv|.|light of day")
+    );
+    assert_eq!(split("CLIENT 'a \"b\"'").as_deref(), Some("CLIENT|a \"b\""));
+    assert_eq!(
+        split(r#"DEFINE jpv a\ b\"c"#).as_deref(),
+        Some("DEFINE|jpv|a b\"c")
+    );
+    assert_eq!(split("DEFINE jpv \"\"").as_deref(), Some("DEFINE|jpv|"));
+    assert_eq!(split("").as_deref(), Some(""));
+    assert_eq!(split("DEFINE jpv \"光"), None);
+    assert_eq!(split("DEFINE jpv 光\\"), None);
+}
+
+#[test]
+fn commands() {
+    assert_eq!(respond(""), "500 syntax error, command not recognized\r\n");
+    assert_eq!(respond("FROB"), "500 unknown command\r\n");
+    assert_eq!(
+        respond("DEFINE jpv"),
+        "501 syntax error, illegal parameters\r\n"
+    );
+    assert_eq!(
+        respond("DEFINE jpv \"光"),
+        "501 syntax error, illegal parameters\r\n"
+    );
+    assert_eq!(
+        respond("AUTH user secret"),
+        "502 command not implemented\r\n"
+    );
+    assert_eq!(respond("client test"), "250 ok\r\n");
+    assert_eq!(respond("STATUS"), "210 status ok\r\n");
+    assert_eq!(
+        respond("DEFINE other 光"),
+        "550 invalid database, use \"SHOW DB\" for a list of databases\r\n"
+    );
+    assert_eq!(
+        respond("MATCH jpv soundex 光"),
+        "551 invalid strategy, use \"SHOW STRAT\" for a list of strategies\r\n"
+    );
+    assert_eq!(
+        respond("SHOW DB"),
+        "110 1 databases present\r\njpv \"Japanese dictionary\"\r\n.\r\n250 ok\r\n"
+    );
+}
+
+#[test]
+fn define() {
+    assert_eq!(
+        respond("DEFINE jpv 光"),
+        "150 1 definitions retrieved\r\n\
+         151 \"光\" jpv \"Japanese dictionary\"\r\n\
+         光 【ひかり】\r\n\
+         \x20 1. light; illumination\r\n\
+         \x20 2. hope\r\n\
+         .\r\n\
+         250 ok\r\n"
+    );
+
+    assert_eq!(respond("DEFINE * 星"), "552 no match\r\n");
+}
+
+#[test]
+fn matches() {
+    assert_eq!(
+        respond("MATCH jpv prefix 光"),
+        "152 2 matches found\r\njpv \"光\"\r\njpv \"光線\"\r\n.\r\n250 ok\r\n"
+    );
+    assert_eq!(
+        respond("MATCH jpv exact 光線"),
+        "152 1 matches found\r\njpv \"光線\"\r\n.\r\n250 ok\r\n"
+    );
+    assert_eq!(respond("MATCH jpv exact 星"), "552 no match\r\n");
+}
+
+#[tokio::test]
+async fn quit() {
+    let (out, result) = session(b"STATUS\r\nQUIT\r\nSTATUS\r\n").await;
+    result.unwrap();
+    // Nothing after `QUIT` is answered.
+    assert_eq!(out, "210 status ok\r\n221 bye\r\n");
+}
+
+#[tokio::test]
+async fn closed() {
+    let (out, result) = session(b"STATUS\r\n").await;
+    result.unwrap();
+    assert_eq!(out, "210 status ok\r\n");
+}
+
+#[tokio::test]
+async fn malformed() {
+    let (out, result) = session(b"STATUS\r\nDEFINE jpv \xff\r\nSTATUS\r\n").await;
+    // Input which isn't UTF-8 ends the connection.
+    assert!(result.is_err());
+    assert_eq!(out, "210 status ok\r\n");
+}
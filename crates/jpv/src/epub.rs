@@ -0,0 +1,35 @@
+//! Import of EPUB books into the reader.
+
+use std::io::{Read, Seek};
+
+use anyhow::{bail, Context, Result};
+use lib::reader::epub::{self, Book, Ruby};
+use zip::ZipArchive;
+
+/// The maximum uncompressed size of a single file in a book.
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Read the text of a book from an EPUB archive.
+pub(crate) fn read<R>(input: R, ruby: Ruby) -> Result<Book>
+where
+    R: Read + Seek,
+{
+    let mut zip = ZipArchive::new(input).context("Reading archive")?;
+
+    epub::read(
+        |path| {
+            let mut file = zip
+                .by_name(path)
+                .with_context(|| format!("Missing {path}"))?;
+
+            if file.size() > MAX_FILE_SIZE {
+                bail!("{path}: File is too large");
+            }
+
+            let mut data = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut data)?;
+            Ok(data)
+        },
+        ruby,
+    )
+}
@@ -0,0 +1,11 @@
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+pub(crate) fn install(_: Option<&Path>) -> Result<()> {
+    bail!("Installing the service is not supported on this platform")
+}
+
+pub(crate) fn uninstall() -> Result<()> {
+    bail!("Uninstalling the service is not supported on this platform")
+}
@@ -0,0 +1,217 @@
+//! Desktops following the freedesktop.org specifications, like GNOME and KDE.
+//!
+//! Everything is installed for the current user, so that the files end up in
+//! the same places as a package would put them but under the home directory:
+//!
+//! * A systemd user unit running the service, which is also what D-Bus
+//!   activation starts.
+//! * A D-Bus service file, when built with D-Bus support.
+//! * A desktop entry and icon, so that it can be launched like any other
+//!   application.
+//! * A hidden desktop entry handling `jpv://` links.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use directories::BaseDirs;
+
+const NAME: &str = "se.tedro.JapaneseDictionary";
+const UNIT: &str = "jpv.service";
+const ICON: &[u8] = include_bytes!("../../res/jpv256.png");
+
+/// A file which is installed.
+struct File {
+    path: PathBuf,
+    contents: Vec<u8>,
+}
+
+pub(crate) fn install(data_dir: Option<&Path>) -> Result<()> {
+    for file in files(data_dir)? {
+        if let Some(parent) = file.path.parent() {
+            fs::create_dir_all(parent).with_context(|| parent.display().to_string())?;
+        }
+
+        fs::write(&file.path, &file.contents).with_context(|| file.path.display().to_string())?;
+        tracing::info!("Wrote {}", file.path.display());
+    }
+
+    refresh();
+    tracing::info!("Start the service when logging in with `systemctl --user enable {UNIT}`");
+    Ok(())
+}
+
+pub(crate) fn uninstall() -> Result<()> {
+    // Removes the link starting the unit when logging in, if there is one.
+    run("systemctl", &["--user", "disable", UNIT]);
+
+    for file in files(None)? {
+        match fs::remove_file(&file.path) {
+            Ok(()) => tracing::info!("Removed {}", file.path.display()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(error).with_context(|| file.path.display().to_string());
+            }
+        }
+    }
+
+    refresh();
+    Ok(())
+}
+
+/// The files which are installed.
+fn files(data_dir: Option<&Path>) -> Result<Vec<File>> {
+    let base = BaseDirs::new().context("Could not figure out base directories")?;
+    let data = base.data_dir();
+
+    let exe = std::env::current_exe().context("Could not figure out the executable")?;
+
+    let mut jpv = quote(&exe);
+
+    if let Some(data_dir) = data_dir {
+        let data_dir = data_dir
+            .canonicalize()
+            .with_context(|| data_dir.display().to_string())?;
+        write!(jpv, " --data-dir {}", quote(&data_dir))?;
+    }
+
+    let dbus = cfg!(feature = "dbus");
+
+    let mut unit = String::new();
+    writeln!(unit, "[Unit]")?;
+    writeln!(unit, "Description=Japanese Dictionary")?;
+    writeln!(unit)?;
+    writeln!(unit, "[Service]")?;
+
+    if dbus {
+        writeln!(unit, "Type=dbus")?;
+        writeln!(unit, "BusName={NAME}")?;
+    }
+
+    writeln!(unit, "ExecStart={jpv} service --no-open")?;
+    writeln!(unit)?;
+    writeln!(unit, "[Install]")?;
+    writeln!(unit, "WantedBy=default.target")?;
+
+    // Without D-Bus, launching the application starts the service unless it's
+    // already running, and opens it either way.
+    let mut entry = String::new();
+    writeln!(entry, "[Desktop Entry]")?;
+    writeln!(entry, "Type=Application")?;
+    writeln!(entry, "Keywords=dictionary;jpv;")?;
+    writeln!(entry, "Name=Japanese Dictionary")?;
+    writeln!(entry, "Comment=Japanese Dictionary by John-John Tedro")?;
+
+    if dbus {
+        writeln!(entry, "Exec={jpv} service --background")?;
+    } else {
+        writeln!(entry, "Exec={jpv} service --open")?;
+    }
+
+    writeln!(entry, "Icon={NAME}")?;
+    writeln!(entry, "StartupNotify=true")?;
+    writeln!(entry, "Terminal=false")?;
+
+    if dbus {
+        writeln!(entry, "DBusActivatable=true")?;
+    }
+
+    let mut links = String::new();
+    writeln!(links, "[Desktop Entry]")?;
+    writeln!(links, "Type=Application")?;
+    writeln!(links, "Name=Japanese Dictionary")?;
+    writeln!(links, "Exec={jpv} open %u")?;
+    writeln!(links, "Icon={NAME}")?;
+    writeln!(links, "MimeType=x-scheme-handler/{};", lib::link::SCHEME)?;
+    writeln!(links, "NoDisplay=true")?;
+    writeln!(links, "Terminal=false")?;
+
+    let mut files = vec![
+        File {
+            path: base.config_dir().join("systemd").join("user").join(UNIT),
+            contents: unit.into_bytes(),
+        },
+        File {
+            path: data.join("applications").join(format!("{NAME}.desktop")),
+            contents: entry.into_bytes(),
+        },
+        File {
+            path: data
+                .join("applications")
+                .join(format!("{NAME}.Open.desktop")),
+            contents: links.into_bytes(),
+        },
+        File {
+            path: data
+                .join("icons")
+                .join("hicolor")
+                .join("256x256")
+                .join("apps")
+                .join(format!("{NAME}.png")),
+            contents: ICON.to_vec(),
+        },
+    ];
+
+    if dbus {
+        let mut service = String::new();
+        writeln!(service, "[D-BUS Service]")?;
+        writeln!(service, "Name={NAME}")?;
+        writeln!(service, "Exec={jpv} service --no-open")?;
+        writeln!(service, "SystemdService={UNIT}")?;
+
+        files.push(File {
+            path: data
+                .join("dbus-1")
+                .join("services")
+                .join(format!("{NAME}.service")),
+            contents: service.into_bytes(),
+        });
+    }
+
+    Ok(files)
+}
+
+/// Let systemd and the desktop pick up installed files.
+fn refresh() {
+    run("systemctl", &["--user", "daemon-reload"]);
+
+    if let Some(base) = BaseDirs::new() {
+        let applications = base.data_dir().join("applications");
+        let applications = applications.to_string_lossy();
+        run("update-desktop-database", &[applications.as_ref()]);
+    }
+}
+
+/// Run a command whose failure isn't fatal, like when systemd isn't used.
+fn run(command: &str, args: &[&str]) {
+    match Command::new(command).args(args).output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => tracing::debug!("{command}: {}", output.status),
+        Err(error) => tracing::debug!("{command}: {error}"),
+    }
+}
+
+/// Quote a path for use in desktop entries and systemd units, which both
+/// split arguments by whitespace unless quoted.
+fn quote(path: &Path) -> String {
+    let path = path.to_string_lossy();
+
+    if !path.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        return path.into_owned();
+    }
+
+    let mut out = String::from("\"");
+
+    for c in path.chars() {
+        if matches!(c, '"' | '\\' | '`' | '$') {
+            out.push('\\');
+        }
+
+        out.push(c);
+    }
+
+    out.push('"');
+    out
+}
@@ -0,0 +1,18 @@
+//! Installing the service into the desktop of the current user, so that it's
+//! started when needed and can be launched like any other application.
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[path = "freedesktop.rs"]
+mod r#impl;
+
+#[cfg(windows)]
+#[path = "windows.rs"]
+mod r#impl;
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+#[path = "fake.rs"]
+mod r#impl;
+
+#[cfg(windows)]
+pub(crate) use self::r#impl::is_installed;
+pub(crate) use self::r#impl::{install, uninstall};
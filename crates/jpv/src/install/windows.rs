@@ -0,0 +1,111 @@
+//! Windows, where the service is registered for the current user the same way
+//! as the installer does it:
+//!
+//! * A value under the `Run` key, which starts the service in the tray when
+//!   logging in.
+//! * The handler of `jpv://` links under the classes of the current user.
+
+use std::os::windows::process::CommandExt;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+const RUN: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+const NAME: &str = "jpv";
+
+/// Keeps `reg.exe` from flashing a console window when called from the tray.
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+pub(crate) fn install(data_dir: Option<&Path>) -> Result<()> {
+    let exe = std::env::current_exe().context("Could not figure out the executable")?;
+    let exe = exe.to_string_lossy();
+
+    let mut service = format!("\"{exe}\"");
+
+    if let Some(data_dir) = data_dir {
+        let data_dir = data_dir
+            .canonicalize()
+            .with_context(|| data_dir.display().to_string())?;
+        service.push_str(&format!(" --data-dir \"{}\"", data_dir.display()));
+    }
+
+    service.push_str(" service --no-open");
+
+    reg_add(RUN, Some(NAME), &service)?;
+    tracing::info!("Starting the service when logging in");
+
+    let classes = classes();
+    let command = format!("\"{exe}\" open \"%1\"");
+
+    reg_add(&classes, None, "URL:Japanese Dictionary")?;
+    reg_add(&classes, Some("URL Protocol"), "")?;
+    reg_add(
+        &format!(r"{classes}\DefaultIcon"),
+        None,
+        &format!("\"{exe}\",0"),
+    )?;
+    reg_add(&format!(r"{classes}\shell\open\command"), None, &command)?;
+    tracing::info!("Handling {}:// links", lib::link::SCHEME);
+    Ok(())
+}
+
+pub(crate) fn uninstall() -> Result<()> {
+    if reg(&["delete", RUN, "/v", NAME, "/f"])? {
+        tracing::info!("Removed starting the service when logging in");
+    }
+
+    if reg(&["delete", &classes(), "/f"])? {
+        tracing::info!("Removed handling {}:// links", lib::link::SCHEME);
+    }
+
+    Ok(())
+}
+
+/// Test if the service is started when logging in.
+pub(crate) fn is_installed() -> bool {
+    reg(&["query", RUN, "/v", NAME]).unwrap_or(false)
+}
+
+/// The key of the link handler, which takes precedence over the one
+/// registered by the installer for all users.
+fn classes() -> String {
+    format!(r"HKCU\Software\Classes\{}", lib::link::SCHEME)
+}
+
+fn reg_add(key: &str, name: Option<&str>, data: &str) -> Result<()> {
+    let mut args = vec!["add", key];
+
+    match name {
+        Some(name) => args.extend(["/v", name]),
+        None => args.push("/ve"),
+    }
+
+    args.extend(["/t", "REG_SZ", "/d", data, "/f"]);
+
+    if !reg(&args)? {
+        bail!("Failed to write {key}");
+    }
+
+    Ok(())
+}
+
+/// Run `reg.exe`, returning `false` if it fails, like when a key which is
+/// deleted doesn't exist.
+fn reg(args: &[&str]) -> Result<bool> {
+    let output = Command::new("reg")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .context("Running reg.exe")?;
+
+    if !output.status.success() {
+        tracing::debug!(
+            "reg {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(output.status.success())
+}
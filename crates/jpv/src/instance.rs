@@ -0,0 +1,220 @@
+//! Detecting an instance of the service which is already running.
+//!
+//! D-Bus and Windows have their own ways of making sure only one service is
+//! running. Everywhere else, a lock file in the data directory records the
+//! address of the running service, which is probed to tell whether it is
+//! still up or the lock was left behind by an instance which didn't shut down
+//! cleanly.
+
+use std::fs::OpenOptions;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use lib::Dirs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[cfg(test)]
+mod tests;
+
+/// The name of the lock file.
+const LOCK_FILE: &str = "service.lock";
+/// How long an instance may take to start before a lock file it hasn't
+/// recorded its address in is considered stale.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(300);
+/// How long to wait for a running instance to respond.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+/// The most of the response to a probe which is read.
+const PROBE_LIMIT: u64 = 4096;
+
+/// The outcome of checking for a running instance.
+pub(crate) enum Instance {
+    /// No other instance is running and this one holds the lock.
+    Acquired(Lock),
+    /// Another instance is running at the given address.
+    Running(String),
+    /// Another instance holds the lock, but hasn't recorded its address yet.
+    Starting,
+}
+
+/// A lock held by the running instance, which is removed when dropped.
+pub(crate) struct Lock {
+    path: PathBuf,
+    /// The address recorded in the lock file, which is empty until written.
+    address: String,
+}
+
+impl Lock {
+    /// Record the address the service can be opened at.
+    pub(crate) fn write(&mut self, address: &str) -> Result<()> {
+        std::fs::write(&self.path, address)
+            .with_context(|| anyhow::anyhow!("{}", self.path.display()))?;
+        self.address = address.to_owned();
+        Ok(())
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        // The lock could have been taken over by another instance if it was
+        // considered stale, in which case it's no longer ours to remove.
+        match std::fs::read_to_string(&self.path) {
+            Ok(address) if address == self.address => {}
+            Ok(..) => return,
+            Err(error) => {
+                if error.kind() != io::ErrorKind::NotFound {
+                    tracing::warn!("{}: {error}", self.path.display());
+                }
+
+                return;
+            }
+        }
+
+        if let Err(error) = std::fs::remove_file(&self.path) {
+            if error.kind() != io::ErrorKind::NotFound {
+                tracing::warn!("{}: {error}", self.path.display());
+            }
+        }
+    }
+}
+
+/// Check for a running instance, taking the lock if there is none.
+///
+/// The lock file is created atomically, so only one of several instances
+/// started at the same time acquires it.
+pub(crate) async fn acquire(dirs: &Dirs) -> Result<Instance> {
+    let path = dirs.data_dir().join(LOCK_FILE);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| anyhow::anyhow!("{}", parent.display()))?;
+    }
+
+    // A stale lock is removed once, after which whichever instance creates
+    // the lock first holds it.
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(..) => {
+                return Ok(Instance::Acquired(Lock {
+                    path,
+                    address: String::new(),
+                }));
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(error) => {
+                return Err(error).with_context(|| anyhow::anyhow!("{}", path.display()));
+            }
+        }
+
+        let address = match std::fs::read_to_string(&path) {
+            Ok(address) => address,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => {
+                return Err(error).with_context(|| anyhow::anyhow!("{}", path.display()));
+            }
+        };
+
+        if address.trim().is_empty() {
+            if !is_stale(&path) {
+                return Ok(Instance::Starting);
+            }
+        } else if probe(address.trim()).await {
+            return Ok(Instance::Running(address.trim().to_owned()));
+        }
+
+        // Only remove the lock if it hasn't been taken over by an instance
+        // which got to it first.
+        if std::fs::read_to_string(&path).ok().as_deref() == Some(address.as_str()) {
+            tracing::debug!("Removing stale lock file {}", path.display());
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+
+    Ok(Instance::Starting)
+}
+
+/// Test if a lock file which has no address recorded was left behind by an
+/// instance which didn't finish starting.
+fn is_stale(path: &Path) -> bool {
+    let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) else {
+        return false;
+    };
+
+    modified
+        .elapsed()
+        .is_ok_and(|elapsed| elapsed > STARTUP_TIMEOUT)
+}
+
+/// Get the address of a running instance recorded in the lock file, if it
+/// responds.
+pub(crate) async fn running(dirs: &Dirs) -> Option<String> {
+    let path = dirs.data_dir().join(LOCK_FILE);
+    let address = std::fs::read_to_string(&path).ok()?;
+    let address = address.trim();
+
+    if probe(address).await {
+        return Some(address.to_owned());
+    }
+
+    tracing::debug!("Ignoring stale lock file {}", path.display());
+    None
+}
+
+/// Find the address of a running instance, either through the lock file or
+/// by probing the default address.
+pub(crate) async fn find(dirs: &Dirs) -> Option<String> {
+    if let Some(address) = running(dirs).await {
+        return Some(address);
+    }
+
+    let address = format!("http://{}/", crate::web::BIND);
+    probe(&address).await.then_some(address)
+}
+
+/// Test if the service at the given address, like `http://localhost:44714/`,
+/// is an instance of this service.
+pub(crate) async fn probe(address: &str) -> bool {
+    let Some(rest) = address.strip_prefix("http://") else {
+        return false;
+    };
+
+    let (host, path) = match rest.split_once('/') {
+        Some((host, path)) => (host, path.trim_end_matches('/')),
+        None => (rest, ""),
+    };
+
+    let path = if path.is_empty() {
+        String::from("/api/version")
+    } else {
+        format!("/{path}/api/version")
+    };
+
+    let request = async {
+        let mut stream = TcpStream::connect(host).await.ok()?;
+
+        let request = format!("GET {path} HTTP/1.0\r\nHost: {host}\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.ok()?;
+
+        let mut response = Vec::new();
+        stream
+            .take(PROBE_LIMIT)
+            .read_to_end(&mut response)
+            .await
+            .ok()?;
+
+        Some(response)
+    };
+
+    let Ok(Some(response)) = tokio::time::timeout(PROBE_TIMEOUT, request).await else {
+        return false;
+    };
+
+    let response = String::from_utf8_lossy(&response);
+
+    let Some((head, body)) = response.split_once("\r\n\r\n") else {
+        return false;
+    };
+
+    head.split_whitespace().nth(1) == Some("200") && body.contains("\"version\"")
+}
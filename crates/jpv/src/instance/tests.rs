@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use lib::Dirs;
+
+use super::{acquire, Instance, LOCK_FILE};
+
+fn dirs(name: &str) -> (Dirs, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("jpv-instance-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let dirs = Dirs::open().unwrap().with_data_dir(dir.clone());
+    (dirs, dir)
+}
+
+#[tokio::test]
+async fn single_instance() {
+    let (dirs, dir) = dirs("single");
+
+    let Instance::Acquired(mut lock) = acquire(&dirs).await.unwrap() else {
+        panic!("Expected the lock to be acquired");
+    };
+
+    // Another instance can't take the lock while the first one is starting.
+    assert!(matches!(acquire(&dirs).await.unwrap(), Instance::Starting));
+
+    // Nothing answers on the recorded address, so the lock is stale.
+    lock.write("http://127.0.0.1:1/").unwrap();
+
+    let Instance::Acquired(other) = acquire(&dirs).await.unwrap() else {
+        panic!("Expected a stale lock to be taken over");
+    };
+
+    // The lock was taken over, so it's not removed by the first instance.
+    drop(lock);
+    assert!(dir.join(LOCK_FILE).is_file());
+
+    drop(other);
+    assert!(!dir.join(LOCK_FILE).exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -164,6 +164,10 @@
 //! more notable features are:
 //!
 //! * `jpv cli <query>` can be used to perform commandline queries.
+//! * `jpv export --format stardict` exports the dictionary in the StarDict format
+//!   used by GoldenDict and other dictionary readers.
+//! * `jpv export --format kindle` exports the dictionary as a Kindle dictionary
+//!   package, which can be converted into a MOBI file using Kindle Previewer.
 //! * `jpv send-clipboard --type text/plain hello` can be used to inject a phrase
 //!   into the dictionary for analysis (requires the `dbus` feature).
 //!
@@ -239,16 +243,30 @@
 
 #![cfg_attr(all(not(feature = "cli"), windows), windows_subsystem = "windows")]
 
+mod anki;
 mod background;
+mod clipboard;
 mod command;
 mod dbus;
+mod dict;
+mod epub;
 mod hash;
+mod install;
+mod instance;
 mod log;
+mod ocr;
 mod open_uri;
 mod reporter;
 mod system;
 mod tasks;
+#[cfg(test)]
+mod testing;
+mod tokenizer;
+mod tts;
+mod update;
+mod user_data;
 mod web;
+mod whisper;
 mod windows;
 
 #[allow(unused)]
@@ -272,17 +290,52 @@ enum Command {
     Service(command::service::ServiceArgs),
     /// Perform a cli lookup.
     Cli(command::cli::CliArgs),
+    /// Send text to the service to be analyzed like a clipboard capture, like
+    /// `cat chapter.txt | jpv send`.
+    Send(command::send::SendArgs),
     /// Send clipboard to the service.
     SendClipboard(command::send_clipboard::SendClipboardArgs),
+    /// Open a `jpv://` link, like `jpv://lookup?q=食べる`, in the running
+    /// service. This is what other applications launch to look things up.
+    Open(command::open::OpenArgs),
     /// Build the dictionary database. This must be performed before the cli or service can be used.
     Build(command::build::BuildArgs),
+    /// Check the integrity of built dictionaries and print a report, which
+    /// is useful after an interrupted build or when filing a bug report.
+    Check(command::check::CheckArgs),
+    /// Export the dictionary to a format used by other dictionary readers.
+    Export(command::export::ExportArgs),
+    /// Export profiles and configuration to a zip archive.
+    ExportUserData(command::user_data::ExportArgs),
+    /// Import profiles and configuration from a zip archive created with
+    /// `export-user-data`.
+    ImportUserData(command::user_data::ImportArgs),
+    /// Answer JSON-RPC requests for searches, analysis and furigana on
+    /// standard input and output, or on a unix socket, for editor
+    /// integration.
+    Rpc(command::rpc::RpcArgs),
 }
 
 #[derive(Parser)]
 struct Args {
+    /// Print the version and exit.
+    #[arg(long, short = 'V')]
+    version: bool,
+    /// Together with `--version`, check if a newer release is available.
+    #[arg(long, requires = "version")]
+    check_update: bool,
     /// Specify paths to indexes to use.
     #[arg(long, value_name = "index")]
     index: Vec<PathBuf>,
+    /// Run in portable mode, where dictionaries, user data and configuration are stored next to the executable. This is also enabled by placing a file named `jpv.portable` next to it.
+    #[arg(long)]
+    portable: bool,
+    /// Store dictionaries, user data and configuration in the given directory, so that everything is kept in one folder like in containers or portable installs.
+    #[arg(long, value_name = "dir", env = "JPV_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+    /// Store cached downloads in the given directory.
+    #[arg(long, value_name = "dir", env = "JPV_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
     /// Command to run, by default this runs the service.
     #[command(subcommand)]
     command: Option<Command>,
@@ -294,9 +347,14 @@ async fn main() -> Result<()> {
 
     let args = Args::try_parse()?;
 
+    if args.version {
+        return print_version(args.check_update).await;
+    }
+
     let directive = match &args.command {
-        // Logging is not desired for CLI tool by default.
-        Some(Command::Cli(..)) => None,
+        // Logging is not desired for CLI tool by default, nor when responses
+        // are written to standard output.
+        Some(Command::Cli(..) | Command::Rpc(..)) => None,
         _ => Some("jpv=info"),
     };
 
@@ -318,7 +376,25 @@ async fn main() -> Result<()> {
     let layer = filter.with_subscriber(layer);
     layer.try_init()?;
 
-    let dirs = Dirs::open()?;
+    let mut dirs = if args.portable || Dirs::is_portable() {
+        let dirs = Dirs::portable()?;
+        tracing::info!(
+            "Running in portable mode from {}",
+            dirs.data_dir().display()
+        );
+        dirs
+    } else {
+        Dirs::open()?
+    };
+
+    if let Some(data_dir) = &args.data_dir {
+        dirs = dirs.with_data_dir(data_dir.clone());
+        tracing::info!("Storing data in {}", dirs.data_dir().display());
+    }
+
+    if let Some(cache_dir) = &args.cache_dir {
+        dirs = dirs.with_cache_dir(cache_dir.clone());
+    }
 
     let config = Config::load(&dirs).context("Loading configuration")?;
 
@@ -335,12 +411,55 @@ async fn main() -> Result<()> {
         Some(Command::Cli(cli_args)) => {
             self::command::cli::run(&args, cli_args, &dirs, config).await?;
         }
+        Some(Command::Send(send_args)) => {
+            self::command::send::run(send_args, &dirs).await?;
+        }
         Some(Command::SendClipboard(send_clipboard_args)) => {
             self::command::send_clipboard::run(send_clipboard_args).await?;
         }
+        Some(Command::Open(open_args)) => {
+            self::command::open::run(open_args, &dirs).await?;
+        }
         Some(Command::Build(build_args)) => {
             self::command::build::run(&args, build_args, &dirs, config).await?;
         }
+        Some(Command::Check(check_args)) => {
+            self::command::check::run(&args, check_args, &dirs, config).await?;
+        }
+        Some(Command::Export(export_args)) => {
+            self::command::export::run(&args, export_args, &dirs, config).await?;
+        }
+        Some(Command::ExportUserData(export_args)) => {
+            self::command::user_data::export(export_args, &dirs, &config)?;
+        }
+        Some(Command::ImportUserData(import_args)) => {
+            self::command::user_data::import(import_args, &dirs)?;
+        }
+        Some(Command::Rpc(rpc_args)) => {
+            self::command::rpc::run(&args, rpc_args, &dirs, config).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Print the version, and optionally whether a newer release is available.
+async fn print_version(check_update: bool) -> Result<()> {
+    println!("jpv {VERSION}");
+
+    if !check_update {
+        return Ok(());
+    }
+
+    let latest = update::latest().await.context("Checking for updates")?;
+
+    if update::is_newer(VERSION, &latest.version) {
+        println!(
+            "A newer release {} is available at {}",
+            latest.version, latest.url
+        );
+    } else {
+        println!("Latest release is {}", latest.version);
     }
 
     Ok(())
@@ -0,0 +1,114 @@
+//! Engines used to recognize text in captured images.
+
+use std::io::{Cursor, Write};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::{bail, Context, Result};
+use image::{DynamicImage, ImageOutputFormat};
+use lib::config;
+use lib::ocr::{Block, Recognized};
+use parking_lot::Mutex;
+
+/// An OCR engine.
+pub(crate) trait Engine: Send + Sync {
+    /// Recognize the text in an image, blocking until done.
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognized>;
+}
+
+/// Construct the external engine described by the given configuration.
+pub(crate) fn backend(config: config::OcrEngine) -> Arc<dyn Engine> {
+    Arc::new(External { config })
+}
+
+/// Tesseract, which is loaded once when the service starts.
+pub(crate) struct Tesseract {
+    tesseract: Mutex<tesseract::Tesseract>,
+}
+
+impl Tesseract {
+    pub(crate) fn new(tesseract: tesseract::Tesseract) -> Self {
+        Self {
+            tesseract: Mutex::new(tesseract),
+        }
+    }
+}
+
+impl Engine for Tesseract {
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognized> {
+        let data = image.as_bytes();
+        let width = usize::try_from(image.width())?;
+        let height = usize::try_from(image.height())?;
+        let bytes_per_pixel = usize::from(image.color().bytes_per_pixel());
+
+        tracing::trace!(len = data.len(), width, height, bytes_per_pixel);
+
+        let mut tesseract = self.tesseract.lock();
+        let text = tesseract.image_to_text(data, width, height, bytes_per_pixel)?;
+        let lines = tesseract.lines();
+
+        if lines.is_empty() {
+            return Ok(Recognized::text(text.to_owned(), None));
+        }
+
+        let blocks = lines
+            .into_iter()
+            .filter(|line| !line.text.trim().is_empty())
+            .map(|line| Block {
+                text: line.text,
+                confidence: Some((line.confidence / 100.0).clamp(0.0, 1.0)),
+            })
+            .collect();
+
+        Ok(Recognized { blocks })
+    }
+}
+
+/// An external program, like a wrapper around manga-ocr, which reads a PNG
+/// image from its standard input.
+struct External {
+    config: config::OcrEngine,
+}
+
+impl Engine for External {
+    fn recognize(&self, image: &DynamicImage) -> Result<Recognized> {
+        let mut png = Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, ImageOutputFormat::Png)
+            .context("Encoding image")?;
+        let png = png.into_inner();
+
+        let mut child = Command::new(&self.config.command)
+            .args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run OCR engine `{}`", self.config.command))?;
+
+        let mut stdin = child.stdin.take().context("OCR engine is missing input")?;
+
+        // Input is written separately, since the program might not read all
+        // of it before some of its output has been read.
+        let output = thread::scope(|s| {
+            s.spawn(move || {
+                // Failing to write shows up as a failure of the program.
+                _ = stdin.write_all(&png);
+            });
+
+            child.wait_with_output()
+        })?;
+
+        if !output.status.success() {
+            bail!(
+                "OCR engine `{}` failed: {}",
+                self.config.command,
+                output.status
+            );
+        }
+
+        let output = String::from_utf8(output.stdout).context("OCR engine output is not UTF-8")?;
+        Ok(Recognized::parse(&output))
+    }
+}
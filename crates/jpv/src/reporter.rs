@@ -4,7 +4,7 @@ use std::sync::{Arc, RwLock};
 use lib::reporter::Reporter;
 
 use crate::background::Mutable;
-use crate::system::{Event, SystemEvents};
+use crate::system::{Event, SystemEvents, TaskCompleted};
 
 pub(crate) struct EventsReporter {
     pub(crate) inner: Arc<RwLock<Mutable>>,
@@ -12,6 +12,28 @@ pub(crate) struct EventsReporter {
     pub(crate) name: Option<Box<str>>,
 }
 
+/// Progress of work done outside of the task queue, which is completed when
+/// dropped.
+pub(crate) struct Progress {
+    pub(crate) reporter: Arc<EventsReporter>,
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        let Some(name) = self.reporter.name.as_deref() else {
+            return;
+        };
+
+        let Some(task) = self.reporter.inner.write().unwrap().tasks.remove(name) else {
+            return;
+        };
+
+        self.reporter
+            .system_events
+            .send(Event::TaskCompleted(TaskCompleted { name: task.name }));
+    }
+}
+
 impl Reporter for EventsReporter {
     fn instrument_start(&self, _: &'static str, text: &dyn fmt::Display, total: Option<usize>) {
         use std::fmt::Write;
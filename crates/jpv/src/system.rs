@@ -6,11 +6,14 @@ use lib::api;
 use tokio::sync::broadcast::{Receiver, Sender};
 use tokio::sync::futures::Notified;
 
+use crate::web::BasePath;
+
 /// Service startup.
 pub(crate) trait Start {
     fn start<'a>(
         &'a mut self,
         port: u16,
+        base: &'a BasePath,
         shutdown: Notified<'a>,
         system_events: &'a SystemEvents,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>>;
@@ -28,6 +31,8 @@ pub(crate) enum Setup {
 pub(crate) struct SendClipboardData {
     pub(crate) mimetype: String,
     pub(crate) data: Vec<u8>,
+    /// The application the data was captured from, if known.
+    pub(crate) source: Option<String>,
 }
 
 #[derive(Clone)]
@@ -59,10 +64,33 @@ pub(crate) enum Event {
     TaskProgress(TaskProgress),
     /// Emit that a task has completed.
     TaskCompleted(TaskCompleted),
+    /// Indicate that capture focus has changed to the given client.
+    CaptureFocus(Option<u32>),
+    /// Indicate that the selected profile has changed.
+    ProfileChanged,
     /// Indicate that clients should refresh their state.
     Refresh,
 }
 
+impl Event {
+    /// Test if this is a capture event, which should only be delivered to the
+    /// client holding capture focus.
+    pub(crate) fn is_capture(&self) -> bool {
+        matches!(
+            self,
+            Event::SendClipboardData(..) | Event::SendDynamicImage(..) | Event::SendText(..)
+        )
+    }
+
+    /// The application a capture event was captured from, if known.
+    pub(crate) fn capture_source(&self) -> Option<&str> {
+        match self {
+            Event::SendClipboardData(clipboard) => clipboard.source.as_deref(),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct SystemEvents(Sender<Event>);
 
@@ -0,0 +1,49 @@
+//! Helpers shared by tests.
+
+use lib::config::Config;
+use lib::data;
+use lib::database::{build, Database, Input, Location};
+use lib::reporter::EmptyReporter;
+use lib::token::Token;
+use tempfile::TempDir;
+
+const JMDICT: &str = r#"<JMdict>
+<entry><ent_seq>1</ent_seq>
+<k_ele><keb>光線</keb></k_ele>
+<r_ele><reb>こうせん</reb></r_ele>
+<sense><pos>&n;</pos><gloss>ray of light</gloss></sense>
+</entry>
+<entry><ent_seq>2</ent_seq>
+<k_ele><keb>光</keb><ke_pri>ichi1</ke_pri><ke_pri>news1</ke_pri></k_ele>
+<r_ele><reb>ひかり</reb></r_ele>
+<sense><pos>&n;</pos><gloss>light</gloss><gloss>illumination</gloss></sense>
+<sense><pos>&n;</pos><gloss>hope</gloss></sense>
+</entry>
+</JMdict>
+"#;
+
+/// Open a database with a couple of dictionary entries in a temporary
+/// directory, which is removed once the returned guard is dropped.
+pub(crate) fn database() -> (Database, TempDir) {
+    let buf = build(
+        &EmptyReporter,
+        &Token::default(),
+        "jmdict",
+        Input::Jmdict(JMDICT),
+        false,
+    )
+    .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("jmdict.index");
+    std::fs::write(&path, buf.as_slice()).unwrap();
+
+    let data = data::open(&path).unwrap();
+    let db = Database::open([(data, Location::Path(path.into()))], &Config::default()).unwrap();
+    (db, dir)
+}
+
+/// Open a database without any dictionaries.
+pub(crate) fn empty_database() -> Database {
+    Database::open([], &Config::default()).unwrap()
+}
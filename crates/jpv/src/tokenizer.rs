@@ -0,0 +1,154 @@
+//! Morphological analyzers used to split text into words, which guide how
+//! text is split into phrases.
+
+use std::io::{Read, Write};
+use std::ops::Range;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use lib::config;
+use lib::database::{Database, PhraseSpan};
+use tokio::task;
+
+/// How long a tokenizer has to split text before it's stopped.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A morphological analyzer.
+pub(crate) trait Tokenizer: Send + Sync {
+    /// Split text into words, returning the byte range of each word.
+    fn tokenize(&self, text: &str) -> Result<Vec<Range<usize>>>;
+}
+
+/// Construct the analyzer described by the given configuration.
+pub(crate) fn backend(config: config::Tokenizer) -> Arc<dyn Tokenizer> {
+    Arc::new(External { config })
+}
+
+/// An external program like `mecab`, which reads text from its standard input.
+struct External {
+    config: config::Tokenizer,
+}
+
+impl Tokenizer for External {
+    fn tokenize(&self, text: &str) -> Result<Vec<Range<usize>>> {
+        let mut child = Command::new(&self.config.command)
+            .args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run tokenizer `{}`", self.config.command))?;
+
+        let mut stdin = child.stdin.take().context("Tokenizer is missing input")?;
+        let mut stdout = child.stdout.take().context("Tokenizer is missing output")?;
+
+        // Input is written separately, since the program might not read all
+        // of it before some of its output has been read.
+        let (status, output) = thread::scope(|s| {
+            s.spawn(move || {
+                // Failing to write shows up as a failure of the program.
+                _ = stdin.write_all(text.as_bytes());
+                _ = stdin.write_all(b"\n");
+            });
+
+            let reader = s.spawn(move || {
+                let mut output = Vec::new();
+                stdout.read_to_end(&mut output).map(|_| output)
+            });
+
+            let deadline = Instant::now() + TIMEOUT;
+
+            let status = loop {
+                if let Some(status) = child.try_wait()? {
+                    break status;
+                }
+
+                if Instant::now() >= deadline {
+                    // Killing the program closes its output, which lets the
+                    // reader finish.
+                    _ = child.kill();
+                    _ = child.wait();
+                    bail!("Tokenizer `{}` timed out", self.config.command);
+                }
+
+                thread::sleep(Duration::from_millis(5));
+            };
+
+            let output = match reader.join() {
+                Ok(output) => output?,
+                Err(..) => bail!("Reading tokenizer output panicked"),
+            };
+
+            Ok((status, output))
+        })?;
+
+        if !status.success() {
+            bail!("Tokenizer `{}` failed: {}", self.config.command, status);
+        }
+
+        let output = String::from_utf8(output).context("Tokenizer output is not UTF-8")?;
+        Ok(lib::tokenizer::words(text, &output))
+    }
+}
+
+/// Split text into phrases, guided by the words found by the tokenizer if
+/// there is one.
+pub(crate) fn phrase_spans<'a>(
+    db: &'a Database,
+    tokenizer: Option<&dyn Tokenizer>,
+    text: &str,
+) -> Result<Vec<PhraseSpan<'a>>> {
+    let words = words(tokenizer, text);
+    db.phrase_spans_with(text, &words)
+}
+
+/// Split text into words, where a failing tokenizer falls back to only
+/// matching phrases in the dictionary.
+pub(crate) fn words(tokenizer: Option<&dyn Tokenizer>, text: &str) -> Vec<Range<usize>> {
+    let Some(tokenizer) = tokenizer else {
+        return Vec::new();
+    };
+
+    match tokenizer.tokenize(text) {
+        Ok(words) => words,
+        Err(error) => {
+            tracing::warn!(?error, "Tokenizer failed, only matching phrases");
+            Vec::new()
+        }
+    }
+}
+
+/// Split text into phrases like [`phrase_spans`], running the tokenizer on a
+/// blocking thread since it waits for an external program.
+pub(crate) async fn spawn_phrase_spans<'a>(
+    db: &'a Database,
+    tokenizer: Option<&Arc<dyn Tokenizer>>,
+    text: &str,
+) -> Result<Vec<PhraseSpan<'a>>> {
+    let words = spawn_words(tokenizer, text).await;
+    db.phrase_spans_with(text, &words)
+}
+
+/// Split text into words like [`words`], running the tokenizer on a blocking
+/// thread since it waits for an external program.
+pub(crate) async fn spawn_words(
+    tokenizer: Option<&Arc<dyn Tokenizer>>,
+    text: &str,
+) -> Vec<Range<usize>> {
+    let Some(tokenizer) = tokenizer.cloned() else {
+        return Vec::new();
+    };
+
+    let text = text.to_owned();
+
+    match task::spawn_blocking(move || words(Some(&*tokenizer), &text)).await {
+        Ok(words) => words,
+        Err(error) => {
+            tracing::warn!(?error, "Tokenizer panicked, only matching phrases");
+            Vec::new()
+        }
+    }
+}
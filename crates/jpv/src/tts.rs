@@ -0,0 +1,85 @@
+//! Text-to-speech backends used to read text out loud.
+
+use std::io;
+use std::process::Stdio;
+
+use anyhow::{Context, Result};
+use axum::body::{boxed, BoxBody, Bytes, StreamBody};
+use futures::stream::{self, Stream};
+use lib::config;
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, ChildStdout, Command};
+
+/// The maximum number of characters which are read out loud at once.
+pub(crate) const MAX_TEXT: usize = 256;
+/// Size of chunks audio is streamed in.
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Speech being synthesized.
+pub(crate) struct Speech {
+    /// The content type of the audio.
+    pub(crate) mime: String,
+    /// The audio, streamed as it's being synthesized.
+    pub(crate) body: BoxBody,
+}
+
+/// A text-to-speech backend.
+pub(crate) trait Backend: Send + Sync {
+    /// Start synthesizing the given text.
+    fn speak(&self, text: &str) -> Result<Speech>;
+}
+
+/// Construct the backend described by the given configuration.
+pub(crate) fn backend(config: config::Tts) -> Box<dyn Backend> {
+    Box::new(External { config })
+}
+
+/// An external program which writes audio to its standard output.
+struct External {
+    config: config::Tts,
+}
+
+impl Backend for External {
+    fn speak(&self, text: &str) -> Result<Speech> {
+        let mut child = Command::new(&self.config.external.command)
+            .args(self.config.args_for(text))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "Failed to run text-to-speech engine `{}`",
+                    self.config.external.command
+                )
+            })?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .context("Text-to-speech engine is missing output")?;
+
+        Ok(Speech {
+            mime: self.config.mime.clone(),
+            body: boxed(StreamBody::new(read(child, stdout))),
+        })
+    }
+}
+
+/// Stream the output of a program, keeping the program alive until its output
+/// has been read.
+fn read(child: Child, stdout: ChildStdout) -> impl Stream<Item = io::Result<Bytes>> {
+    stream::try_unfold((child, stdout), |(mut child, mut stdout)| async move {
+        let mut buf = vec![0; CHUNK_SIZE];
+        let n = stdout.read(&mut buf).await?;
+
+        if n == 0 {
+            child.wait().await?;
+            return Ok(None);
+        }
+
+        buf.truncate(n);
+        Ok(Some((Bytes::from(buf), (child, stdout))))
+    })
+}
@@ -0,0 +1,117 @@
+//! Checking for newer releases of the service on GitHub.
+//!
+//! Nothing is downloaded, users are only told where to get a newer release.
+
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use lib::api;
+use tokio::sync::Mutex;
+
+/// The latest published release.
+#[cfg(feature = "reqwest")]
+const LATEST_URL: &str = "https://api.github.com/repos/udoprog/jpv/releases/latest";
+/// How long the outcome of a check is reused before checking again.
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Checks for newer releases, reusing the outcome of recent checks.
+#[derive(Default)]
+pub(crate) struct Checker {
+    last: Mutex<Option<(Instant, Option<api::Release>)>>,
+}
+
+impl Checker {
+    /// Get a release newer than the running service, if there is one.
+    pub(crate) async fn available(&self) -> Result<Option<api::Release>> {
+        let mut last = self.last.lock().await;
+
+        if let Some((checked, release)) = &*last {
+            if checked.elapsed() < CHECK_INTERVAL {
+                return Ok(release.clone());
+            }
+        }
+
+        // Failures are remembered as well, so that an unreachable GitHub isn't
+        // asked again on every request.
+        let release = check().await;
+        let cached = release.as_ref().ok().cloned().flatten();
+        *last = Some((Instant::now(), cached));
+        release
+    }
+}
+
+/// Check for a release newer than the running service.
+pub(crate) async fn check() -> Result<Option<api::Release>> {
+    let release = latest().await?;
+
+    if !is_newer(crate::VERSION, &release.version) {
+        return Ok(None);
+    }
+
+    Ok(Some(release))
+}
+
+/// Get the latest published release.
+#[cfg(feature = "reqwest")]
+pub(crate) async fn latest() -> Result<api::Release> {
+    use anyhow::Context;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct GitHubRelease {
+        tag_name: String,
+        html_url: String,
+    }
+
+    let client = reqwest::ClientBuilder::new()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    let body = client
+        .get(LATEST_URL)
+        .header("User-Agent", crate::USER_AGENT)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    let release: GitHubRelease = serde_json::from_slice(&body).context("Reading latest release")?;
+
+    Ok(api::Release {
+        version: release.tag_name,
+        url: release.html_url,
+    })
+}
+
+#[cfg(not(feature = "reqwest"))]
+pub(crate) async fn latest() -> Result<api::Release> {
+    anyhow::bail!("Checking for updates is not supported")
+}
+
+/// Test if `latest` is newer than `current` by comparing the numbers in them,
+/// so that both `2024.1.5` and `2024-01-05` work.
+///
+/// Development builds and releases like `nightly` aren't numbered, so they're
+/// never considered outdated.
+pub(crate) fn is_newer(current: &str, latest: &str) -> bool {
+    match (numbers(current), numbers(latest)) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => false,
+    }
+}
+
+fn numbers(version: &str) -> Option<Vec<u64>> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+
+    if !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse().ok())
+        .collect()
+}
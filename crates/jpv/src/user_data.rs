@@ -0,0 +1,150 @@
+//! Export and import of user data as a zip archive.
+//!
+//! The archive contains a manifest, the service configuration and one JSON
+//! file per profile with its history, word lists, review state and settings.
+
+use std::io::{Read, Seek, Write};
+
+use anyhow::{bail, Context, Result};
+use lib::config::Config;
+use lib::profile::{self, Profile, Profiles};
+use serde::{Deserialize, Serialize};
+use zip::read::ZipFile;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// The current version of the archive format.
+const VERSION: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+const CONFIG: &str = "config.toml";
+const PROFILES: &str = "profiles/";
+/// The maximum uncompressed size of a single file in an archive.
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    /// The profile which was selected when the archive was created.
+    current: String,
+}
+
+/// User data read from an archive.
+pub(crate) struct UserData {
+    /// The profile which was selected when the archive was created.
+    pub(crate) current: String,
+    pub(crate) config: Option<Config>,
+    pub(crate) profiles: Vec<(String, Profile)>,
+}
+
+impl UserData {
+    /// Store the profiles in the archive, replacing any existing profiles
+    /// with the same names, and select the profile which was selected when
+    /// the archive was created.
+    pub(crate) fn store_profiles(&self, profiles: &Profiles) -> Result<()> {
+        for (name, profile) in &self.profiles {
+            profiles.save(name, profile)?;
+        }
+
+        profiles.select(&self.current)?;
+        Ok(())
+    }
+}
+
+/// Write all user data as an archive to the given output.
+pub(crate) fn export<W>(out: W, profiles: &Profiles, config: &Config) -> Result<W>
+where
+    W: Write + Seek,
+{
+    let mut zip = ZipWriter::new(out);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let manifest = Manifest {
+        version: VERSION,
+        current: profiles.current()?,
+    };
+
+    zip.start_file(MANIFEST, options)?;
+    serde_json::to_writer_pretty(&mut zip, &manifest)?;
+
+    zip.start_file(CONFIG, options)?;
+    zip.write_all(lib::toml::to_string_pretty(config)?.as_bytes())?;
+
+    for name in profiles.list()? {
+        let profile = profiles.load(&name)?;
+        zip.start_file(format!("{PROFILES}{name}.json"), options)?;
+        serde_json::to_writer_pretty(&mut zip, &profile)?;
+    }
+
+    Ok(zip.finish()?)
+}
+
+/// Read user data from an archive.
+pub(crate) fn read<R>(input: R) -> Result<UserData>
+where
+    R: Read + Seek,
+{
+    let mut zip = ZipArchive::new(input).context("Reading archive")?;
+
+    let manifest: Manifest = {
+        let file = zip.by_name(MANIFEST).context("Missing manifest")?;
+        serde_json::from_slice(&read_file(file)?).context("Reading manifest")?
+    };
+
+    if manifest.version > VERSION {
+        bail!(
+            "Archive version {} is not supported, expected at most {VERSION}",
+            manifest.version
+        );
+    }
+
+    let config = match zip.by_name(CONFIG) {
+        Ok(file) => {
+            let data = String::from_utf8(read_file(file)?).context("Reading configuration")?;
+            Some(lib::toml::from_str(&data).context("Reading configuration")?)
+        }
+        Err(zip::result::ZipError::FileNotFound) => None,
+        Err(error) => return Err(error.into()),
+    };
+
+    let names = zip
+        .file_names()
+        .filter_map(|path| path.strip_prefix(PROFILES)?.strip_suffix(".json"))
+        .filter(|name| profile::is_valid_name(name))
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+
+    let mut profiles = Vec::with_capacity(names.len());
+
+    for name in names {
+        let file = zip.by_name(&format!("{PROFILES}{name}.json"))?;
+        let profile = serde_json::from_slice(&read_file(file)?)
+            .with_context(|| format!("Profile `{name}`"))?;
+        profiles.push((name, profile));
+    }
+
+    let current = if profile::is_valid_name(&manifest.current) {
+        manifest.current
+    } else {
+        profile::DEFAULT_PROFILE.to_owned()
+    };
+
+    Ok(UserData {
+        current,
+        config,
+        profiles,
+    })
+}
+
+/// Read a file in an archive, refusing files which are too large.
+fn read_file(file: ZipFile<'_>) -> Result<Vec<u8>> {
+    let size = file.size();
+
+    if size > MAX_FILE_SIZE {
+        bail!("{}: File is too large", file.name());
+    }
+
+    let mut data = Vec::with_capacity(size as usize);
+    // The size is declared by the archive, so never read past it.
+    file.take(size).read_to_end(&mut data)?;
+    Ok(data)
+}
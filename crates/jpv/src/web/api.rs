@@ -6,3 +6,7 @@ pub(crate) static PORT: Option<u16> = Some(8080);
 pub(crate) fn router() -> Router {
     super::common_routes(Router::new())
 }
+
+pub(crate) fn shared_router() -> Router {
+    super::shared_routes(Router::new())
+}
@@ -0,0 +1,84 @@
+//! Serving the service under a subpath, like behind a reverse proxy.
+
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use axum::response::Redirect;
+use axum::routing::get;
+use axum::Router;
+
+/// The path the service is served under.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct BasePath(Arc<str>);
+
+impl BasePath {
+    /// Parse a base path, like `/jpv/`.
+    ///
+    /// Leading and trailing slashes are optional, and an empty path or `/`
+    /// serves from the root.
+    pub(crate) fn new(path: &str) -> Result<Self> {
+        let mut out = String::new();
+
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            let valid = segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '~'));
+
+            if !valid || segment == "." || segment == ".." {
+                bail!("Invalid base path segment `{segment}`");
+            }
+
+            out.push('/');
+            out.push_str(segment);
+        }
+
+        Ok(Self(out.into()))
+    }
+
+    /// The base path without a trailing slash, like `/jpv`, or an empty
+    /// string if served from the root.
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Test if the service is served from the root.
+    pub(crate) fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Rewrite absolute paths in served HTML and JSON documents so that they
+    /// point into the base path.
+    pub(crate) fn rebase(&self, content: &str) -> String {
+        let base = self.as_str();
+
+        let mut content = content
+            .replace("href=\"/", &format!("href=\"{base}/"))
+            .replace("src=\"/", &format!("src=\"{base}/"))
+            .replace("'/", &format!("'{base}/"))
+            .replace("\": \"/", &format!("\": \"{base}/"));
+
+        // Let the web application know where it is served from.
+        content = content.replacen("<head>", &format!("<head><base href=\"{base}/\" />"), 1);
+        content
+    }
+
+    /// Mount the given router under the base path, redirecting requests to
+    /// the root into it.
+    pub(crate) fn mount(&self, router: Router) -> Router {
+        if self.is_root() {
+            return router;
+        }
+
+        let index = format!("{}/", self.as_str());
+        let target = index.clone();
+        let redirect = get(move || async move { Redirect::temporary(&target) });
+
+        // Nesting under a path with a trailing slash is what makes the
+        // application itself match it, so the path without one is
+        // redirected.
+        Router::new()
+            .route("/", redirect.clone())
+            .route(self.as_str(), redirect)
+            .nest(&index, router)
+    }
+}
@@ -3,9 +3,11 @@ use std::borrow::Cow;
 use axum::http::{header, StatusCode, Uri};
 use axum::response::{IntoResponse, Response};
 use axum::routing::get;
-use axum::Router;
+use axum::{Extension, Router};
 use rust_embed::RustEmbed;
 
+use super::BasePath;
+
 pub(crate) static BIND: &str = "127.0.0.1:44714";
 pub(crate) static PORT: Option<u16> = None;
 
@@ -19,26 +21,54 @@ pub(crate) fn router() -> Router {
         .fallback(index_handler)
 }
 
-async fn index_handler() -> impl IntoResponse {
-    StaticFile(Cow::Borrowed("index.html"))
+pub(crate) fn shared_router() -> Router {
+    let router = Router::new().route("/", get(index_handler));
+
+    let router = super::shared_routes(router);
+
+    router
+        .route("/*file", get(static_handler))
+        .fallback(index_handler)
 }
 
-async fn static_handler(uri: Uri) -> impl IntoResponse {
-    StaticFile(Cow::Owned(uri.path().trim_start_matches('/').to_string()))
+async fn index_handler(Extension(base): Extension<BasePath>) -> impl IntoResponse {
+    StaticFile(Cow::Borrowed("index.html"), base)
 }
 
+async fn static_handler(Extension(base): Extension<BasePath>, uri: Uri) -> impl IntoResponse {
+    let path = uri.path().trim_start_matches('/');
+
+    // Paths without an extension are routes handled by the web application.
+    if Asset::get(path).is_none() && !path.contains('.') {
+        return StaticFile(Cow::Borrowed("index.html"), base);
+    }
+
+    StaticFile(Cow::Owned(path.to_string()), base)
+}
+
+/// Files which refer to other files by absolute paths, which have to be
+/// rewritten when served under a base path.
+const REBASED: [&str; 2] = ["index.html", "manifest.webmanifest"];
+
 #[derive(RustEmbed)]
 #[folder = "../web/dist"]
 struct Asset;
 
-pub struct StaticFile(Cow<'static, str>);
+pub struct StaticFile(Cow<'static, str>, BasePath);
 
 impl IntoResponse for StaticFile {
     fn into_response(self) -> Response {
         match Asset::get(self.0.as_ref()) {
             Some(content) => {
                 let mime = mime_guess::from_path(self.0.as_ref()).first_or_octet_stream();
-                ([(header::CONTENT_TYPE, mime.as_ref())], content.data).into_response()
+                let headers = [(header::CONTENT_TYPE, mime.as_ref())];
+
+                if !self.1.is_root() && REBASED.contains(&self.0.as_ref()) {
+                    let content = String::from_utf8_lossy(&content.data);
+                    return (headers, self.1.rebase(&content)).into_response();
+                }
+
+                (headers, content.data).into_response()
             }
             None => (StatusCode::NOT_FOUND, "404 Not Found").into_response(),
         }
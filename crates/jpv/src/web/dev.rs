@@ -0,0 +1,224 @@
+//! Serving the web application from a directory during development.
+//!
+//! Instead of the embedded application, files are read from disk on every
+//! request, like from `crates/web/dist` while `trunk watch` rebuilds it. Pages
+//! are reloaded when anything in the directory changes, so working on the
+//! interface doesn't require rebuilding the service.
+
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+use anyhow::{bail, Result};
+use axum::http::{header, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Extension, Router};
+
+use super::BasePath;
+
+/// How often pages poll for changes, in milliseconds.
+const POLL_INTERVAL: u32 = 1000;
+
+/// A directory the web application is served from.
+#[derive(Debug, Clone)]
+pub(crate) struct Assets {
+    dir: Arc<Path>,
+}
+
+impl Assets {
+    /// Serve assets from the given directory, which has to contain an
+    /// `index.html`.
+    pub(crate) fn new(dir: PathBuf) -> Result<Self> {
+        if !dir.join("index.html").is_file() {
+            bail!("{}: Missing index.html", dir.display());
+        }
+
+        Ok(Self { dir: dir.into() })
+    }
+
+    /// The directory assets are served from.
+    pub(crate) fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Resolve a request path to a file in the directory, refusing anything
+    /// which would escape it.
+    fn resolve(&self, path: &str) -> Option<PathBuf> {
+        let mut out = self.dir.to_path_buf();
+
+        for component in Path::new(path).components() {
+            match component {
+                Component::Normal(part) => out.push(part),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+
+        Some(out)
+    }
+
+    /// A token which changes whenever a file in the directory is modified,
+    /// added or removed.
+    fn version(&self) -> String {
+        let mut latest = UNIX_EPOCH;
+        let mut count = 0usize;
+        let mut queue = vec![self.dir.to_path_buf()];
+
+        while let Some(dir) = queue.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+
+                if metadata.is_dir() {
+                    queue.push(entry.path());
+                    continue;
+                }
+
+                count += 1;
+
+                if let Ok(modified) = metadata.modified() {
+                    latest = latest.max(modified);
+                }
+            }
+        }
+
+        let since = latest.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        format!("{}.{}-{count}", since.as_secs(), since.subsec_nanos())
+    }
+}
+
+/// Construct a router serving the application from the given directory
+/// alongside the regular API.
+pub(crate) fn router(assets: Assets) -> Router {
+    let router = Router::new()
+        .route("/", get(index_handler))
+        .route("/_dev/version", get(version_handler));
+
+    let router = super::common_routes(router);
+
+    router
+        .route("/*file", get(static_handler))
+        .fallback(index_handler)
+        .layer(Extension(assets))
+}
+
+async fn version_handler(Extension(assets): Extension<Assets>) -> impl IntoResponse {
+    let version = tokio::task::spawn_blocking(move || assets.version()).await;
+
+    match version {
+        Ok(version) => ([(header::CACHE_CONTROL, "no-store")], version).into_response(),
+        Err(..) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+async fn index_handler(
+    Extension(assets): Extension<Assets>,
+    Extension(base): Extension<BasePath>,
+) -> Response {
+    serve(&assets, &base, "index.html").await
+}
+
+async fn static_handler(
+    Extension(assets): Extension<Assets>,
+    Extension(base): Extension<BasePath>,
+    uri: Uri,
+) -> Response {
+    let path = uri.path().trim_start_matches('/');
+
+    // Paths without an extension are routes handled by the web application.
+    if !path.contains('.') {
+        return serve(&assets, &base, "index.html").await;
+    }
+
+    serve(&assets, &base, path).await
+}
+
+async fn serve(assets: &Assets, base: &BasePath, path: &str) -> Response {
+    let Some(file) = assets.resolve(path) else {
+        return (StatusCode::NOT_FOUND, "404 Not Found").into_response();
+    };
+
+    let data = match tokio::fs::read(&file).await {
+        Ok(data) => data,
+        Err(error) => {
+            tracing::warn!("{}: {error}", file.display());
+            return (StatusCode::NOT_FOUND, "404 Not Found").into_response();
+        }
+    };
+
+    let headers = [
+        (header::CONTENT_TYPE, content_type(&file)),
+        (header::CACHE_CONTROL, "no-store"),
+    ];
+
+    if path == "index.html" {
+        let content = String::from_utf8_lossy(&data);
+
+        let content = if base.is_root() {
+            content.into_owned()
+        } else {
+            base.rebase(&content)
+        };
+
+        return (headers, inject_reload(&content, base)).into_response();
+    }
+
+    if path == "manifest.webmanifest" && !base.is_root() {
+        let content = String::from_utf8_lossy(&data);
+        return (headers, base.rebase(&content)).into_response();
+    }
+
+    (headers, data).into_response()
+}
+
+/// Add a script to the page which reloads it when the assets change.
+fn inject_reload(content: &str, base: &BasePath) -> String {
+    let script = format!(
+        "<script>(function() {{\
+            let version = null;\
+            setInterval(async function() {{\
+                try {{\
+                    const response = await fetch(\"{base}/_dev/version\");\
+                    const current = await response.text();\
+                    if (version !== null && version !== current) {{ location.reload(); }}\
+                    version = current;\
+                }} catch (e) {{}}\
+            }}, {POLL_INTERVAL});\
+        }})();</script>",
+        base = base.as_str(),
+    );
+
+    match content.rfind("</body>") {
+        Some(n) => format!("{}{script}{}", &content[..n], &content[n..]),
+        None => format!("{content}{script}"),
+    }
+}
+
+/// The content type of a file, covering what trunk produces.
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+
+    match extension {
+        "html" => "text/html; charset=utf-8",
+        "js" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" => "application/json",
+        "webmanifest" => "application/manifest+json",
+        "wasm" => "application/wasm",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "ico" => "image/x-icon",
+        "woff2" => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
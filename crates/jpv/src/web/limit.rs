@@ -0,0 +1,105 @@
+//! Per-address rate limiting for the shared dictionary.
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::extract::{ConnectInfo, State};
+use axum::http::{Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use lib::api;
+use parking_lot::Mutex as SyncMutex;
+
+/// The number of addresses which are tracked before addresses which have
+/// been idle long enough to have a full allowance are forgotten.
+const MAX_ADDRESSES: usize = 1024;
+
+/// Limits how many requests each address can make per minute.
+///
+/// Each address gets an allowance of requests which is refilled continuously,
+/// so short bursts are permitted as long as the average rate stays below the
+/// limit.
+pub(crate) struct RateLimit {
+    per_minute: u32,
+    buckets: SyncMutex<HashMap<IpAddr, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, now: Instant, capacity: f64) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * capacity / 60.0).min(capacity);
+        self.updated = now;
+    }
+}
+
+impl RateLimit {
+    /// Construct a rate limit which allows the given number of requests per
+    /// minute from each address.
+    pub(crate) fn new(per_minute: u32) -> Self {
+        Self {
+            per_minute: per_minute.max(1),
+            buckets: SyncMutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request from the given address, returning `false` if it has
+    /// made too many requests.
+    pub(crate) fn check(&self, addr: IpAddr) -> bool {
+        self.check_at(addr, Instant::now())
+    }
+
+    /// Count a request from the given address made at the given time.
+    fn check_at(&self, addr: IpAddr, now: Instant) -> bool {
+        let capacity = f64::from(self.per_minute);
+
+        let mut buckets = self.buckets.lock();
+
+        if buckets.len() >= MAX_ADDRESSES {
+            buckets.retain(|_, bucket| {
+                bucket.refill(now, capacity);
+                bucket.tokens < capacity
+            });
+        }
+
+        let bucket = buckets.entry(addr).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+
+        bucket.refill(now, capacity);
+
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+/// Middleware rejecting requests from addresses which exceed the rate limit.
+pub(super) async fn middleware<B>(
+    State(limit): State<Arc<RateLimit>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    if !limit.check(remote.ip()) {
+        tracing::debug!(?remote, "Rate limited");
+        let error = api::ApiError::new(api::ErrorKind::RateLimited, "Too many requests");
+        return (StatusCode::TOO_MANY_REQUESTS, Json(error)).into_response();
+    }
+
+    next.run(request).await
+}
@@ -0,0 +1,77 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::{middleware, Router};
+use tower::ServiceExt;
+
+use super::RateLimit;
+
+const FIRST: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1));
+const SECOND: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 2));
+
+/// A router which answers every request allowed by the given limit.
+fn router(limit: Arc<RateLimit>) -> Router {
+    Router::new()
+        .route("/", get(|| async { "ok" }))
+        .layer(middleware::from_fn_with_state(limit, super::middleware))
+}
+
+async fn request(router: &Router, addr: IpAddr) -> StatusCode {
+    let mut request = Request::get("/").body(Body::empty()).unwrap();
+
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::new(addr, 4000)));
+
+    router.clone().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn accept() {
+    let router = router(Arc::new(RateLimit::new(2)));
+    assert_eq!(request(&router, FIRST).await, StatusCode::OK);
+    assert_eq!(request(&router, FIRST).await, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn reject() {
+    let router = router(Arc::new(RateLimit::new(2)));
+    assert_eq!(request(&router, FIRST).await, StatusCode::OK);
+    assert_eq!(request(&router, FIRST).await, StatusCode::OK);
+    assert_eq!(request(&router, FIRST).await, StatusCode::TOO_MANY_REQUESTS);
+
+    // Each address has an allowance of its own.
+    assert_eq!(request(&router, SECOND).await, StatusCode::OK);
+}
+
+#[test]
+fn refill() {
+    let limit = RateLimit::new(60);
+    let start = Instant::now();
+
+    for _ in 0..60 {
+        assert!(limit.check_at(FIRST, start));
+    }
+
+    assert!(!limit.check_at(FIRST, start));
+
+    // One request is allowed again for each second which has passed.
+    let later = start + Duration::from_secs(2);
+    assert!(limit.check_at(FIRST, later));
+    assert!(limit.check_at(FIRST, later));
+    assert!(!limit.check_at(FIRST, later));
+
+    // The allowance doesn't grow past the limit while idle.
+    let idle = later + Duration::from_secs(3600);
+
+    for _ in 0..60 {
+        assert!(limit.check_at(FIRST, idle));
+    }
+
+    assert!(!limit.check_at(FIRST, idle));
+}
@@ -6,34 +6,84 @@ mod r#impl;
 #[path = "api.rs"]
 mod r#impl;
 
+mod base;
+mod dev;
+mod limit;
+mod protocol;
+#[cfg(test)]
+mod tests;
+mod trace;
 mod ws;
 
+pub(crate) use self::base::BasePath;
+pub(crate) use self::dev::Assets;
+pub(crate) use self::limit::RateLimit;
 pub(crate) use self::r#impl::{BIND, PORT};
 
 use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 use std::future::Future;
 use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
 
-use anyhow::Result;
-use axum::body::{boxed, Body};
-use axum::extract::{Path, Query};
-use axum::http::StatusCode;
-use axum::response::{IntoResponse, Response};
-use axum::routing::{get, post};
+use anyhow::{bail, Context, Result};
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, Query};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::{get, post, MethodRouter};
 use axum::{Extension, Json, Router};
-use lib::api;
+use lib::api::{self, Request};
+use lib::capture;
 use lib::config::Config;
+use lib::counters;
+use lib::database::{AnalyzeOptions, Database, Entry, EntryResultKey, SenseExample};
+use lib::export::{pdf, Article};
+use lib::graph::{self, Graph};
+use lib::inflection;
+use lib::jmdict;
+use lib::mining::{Card, NewCard};
+use lib::profile::{known, Profile, WordStatus};
+use lib::quiz;
+use lib::reader::{self, subtitles, Document, DocumentInfo};
+use lib::recordings::{self, Recording};
+use lib::stats::Activity;
+use lib::PartOfSpeech;
+use rand::seq::SliceRandom;
 use serde::Serialize;
 use tower_http::cors::{AllowMethods, AllowOrigin, CorsLayer};
 
 use crate::background::Background;
 use crate::system;
+use crate::tokenizer;
+use crate::tts;
+
+/// The maximum number of examples returned in a single response.
+const EXAMPLES_LIMIT: usize = 100;
+/// The number of corpus examples shown for each sense by default.
+const SENSE_EXAMPLES: usize = 2;
+/// The maximum number of characters in a document title.
+const DOCUMENT_TITLE_LIMIT: usize = 64;
+/// The maximum number of unknown words returned for a document.
+const UNKNOWN_WORDS_LIMIT: usize = 50;
+/// The maximum number of lines returned when searching a document.
+const LINES_LIMIT: usize = 100;
+/// The maximum size of an uploaded EPUB book.
+const EPUB_SIZE_LIMIT: usize = 256 * 1024 * 1024;
+/// The maximum size of imported user data.
+const USER_DATA_SIZE_LIMIT: usize = 256 * 1024 * 1024;
+/// The maximum size of captured data, which can be an image.
+const CAPTURE_SIZE_LIMIT: usize = 32 * 1024 * 1024;
 
 pub(crate) fn setup(
     listener: TcpListener,
     background: Background,
     system_events: system::SystemEvents,
+    base: BasePath,
+    assets: Option<Assets>,
 ) -> Result<impl Future<Output = Result<()>>> {
     let server = match axum::Server::from_tcp(listener) {
         Ok(server) => server,
@@ -42,14 +92,51 @@ pub(crate) fn setup(
         }
     };
 
-    let cors = CorsLayer::new()
-        .allow_origin(AllowOrigin::any())
-        .allow_methods(AllowMethods::any());
+    let router = match assets {
+        Some(assets) => self::dev::router(assets),
+        None => self::r#impl::router(),
+    };
 
-    let app = self::r#impl::router()
+    let app = base
+        .mount(router)
+        .layer(Extension(base))
         .layer(Extension(background))
         .layer(Extension(system_events))
-        .layer(cors);
+        .layer(middleware::from_fn(self::protocol::layer))
+        .layer(middleware::from_fn(self::trace::layer));
+
+    let service = server.serve(app.into_make_service_with_connect_info::<SocketAddr>());
+
+    Ok(async move {
+        service.await?;
+        Ok(())
+    })
+}
+
+/// Set up a server sharing the dictionary with other machines.
+///
+/// Only searching the dictionary is available through it, without access to
+/// user data or captures, and requests from each address are rate limited.
+pub(crate) fn setup_shared(
+    listener: TcpListener,
+    background: Background,
+    system_events: system::SystemEvents,
+    base: BasePath,
+    limit: RateLimit,
+) -> Result<impl Future<Output = Result<()>>> {
+    let server = axum::Server::from_tcp(listener)?;
+
+    let limit = Arc::new(limit);
+
+    let app = base
+        .mount(self::r#impl::shared_router())
+        .layer(Extension(base))
+        .layer(Extension(background.read_only()))
+        .layer(Extension(system_events))
+        .layer(Extension(limit.clone()))
+        .layer(middleware::from_fn_with_state(limit, limit::middleware))
+        .layer(middleware::from_fn(self::protocol::layer))
+        .layer(middleware::from_fn(self::trace::layer));
 
     let service = server.serve(app.into_make_service_with_connect_info::<SocketAddr>());
 
@@ -59,43 +146,262 @@ pub(crate) fn setup(
     })
 }
 
+/// A route served by the service.
+struct Route {
+    path: &'static str,
+    handler: MethodRouter,
+    /// Whether the route only reads from the dictionary, and is available when
+    /// it's shared.
+    shared: bool,
+    /// The kind of websocket request which is handled like the route, if any.
+    request: Option<&'static str>,
+    /// Whether the route serves personal data, which other origins are not
+    /// allowed to read.
+    private: bool,
+}
+
+impl Route {
+    fn new(path: &'static str, handler: MethodRouter) -> Self {
+        Self {
+            path,
+            handler,
+            shared: false,
+            request: None,
+            private: false,
+        }
+    }
+
+    /// Mark the route as only reading from the dictionary.
+    fn shared(self) -> Self {
+        Self {
+            shared: true,
+            ..self
+        }
+    }
+
+    /// Set the kind of websocket request which is handled like the route.
+    fn request(self, kind: &'static str) -> Self {
+        Self {
+            request: Some(kind),
+            ..self
+        }
+    }
+
+    /// Mark the route as serving personal data, which is only readable from
+    /// the same origin.
+    fn private(self) -> Self {
+        Self {
+            private: true,
+            ..self
+        }
+    }
+}
+
+/// All routes served by the service.
+fn routes() -> Vec<Route> {
+    vec![
+        Route::new("/api/version", get(version)).shared(),
+        Route::new("/api/config", get(config).post(update_config)),
+        Route::new(
+            "/api/config/capture-filters/preview",
+            post(preview_capture_filters),
+        ),
+        Route::new("/api/rebuild", post(rebuild)),
+        Route::new(
+            "/api/capture",
+            post(capture).layer(DefaultBodyLimit::max(CAPTURE_SIZE_LIMIT)),
+        ),
+        Route::new("/api/ocr/correct", post(correct_ocr)),
+        Route::new("/api/status", get(status)),
+        Route::new("/api/onboard", post(onboard)),
+        Route::new("/api/update", get(update)),
+        Route::new("/api/profiles", get(profiles)),
+        Route::new("/api/profiles/select", post(select_profile)),
+        Route::new("/api/profiles/delete", post(delete_profile)),
+        Route::new("/api/profiles/names", post(set_name_weighting)),
+        Route::new("/api/profiles/readings", post(set_readings)),
+        Route::new("/api/profiles/density", post(set_density)),
+        Route::new("/api/sync", post(sync)),
+        Route::new("/api/history", get(history).post(add_history)).private(),
+        Route::new("/api/known-words/import", post(import_known_words)),
+        Route::new("/api/export/pdf", post(export_pdf)),
+        Route::new("/api/documents", get(documents)),
+        Route::new("/api/documents/add", post(add_document)),
+        Route::new(
+            "/api/documents/epub",
+            post(add_epub).layer(DefaultBodyLimit::max(EPUB_SIZE_LIMIT)),
+        ),
+        Route::new("/api/documents/subtitles", post(add_subtitles)),
+        Route::new("/api/documents/delete", post(delete_document)),
+        Route::new("/api/documents/page", get(page)),
+        Route::new("/api/documents/stats", get(document_stats)),
+        Route::new("/api/documents/lines", get(search_lines)),
+        Route::new(
+            "/api/user-data",
+            get(export_user_data)
+                .post(import_user_data)
+                .layer(DefaultBodyLimit::max(USER_DATA_SIZE_LIMIT)),
+        )
+        .private(),
+        Route::new("/api/analyze", get(analyze))
+            .shared()
+            .request(api::AnalyzeRequest::KIND),
+        Route::new("/api/difficulty", get(difficulty))
+            .shared()
+            .request(api::DifficultyRequest::KIND),
+        Route::new("/api/coverage", post(text_coverage)),
+        Route::new("/api/word-status", get(word_status).post(set_word_status)),
+        Route::new("/api/annotation", post(set_annotation)),
+        Route::new(
+            "/api/user-audio",
+            get(recordings)
+                .post(add_recording)
+                .layer(DefaultBodyLimit::max(recordings::MAX_RECORDING_SIZE)),
+        ),
+        Route::new("/api/user-audio/:id", get(recording)).private(),
+        Route::new("/api/user-audio/delete", post(delete_recording)),
+        Route::new("/api/mining", get(mined).post(mine)).private(),
+        Route::new("/api/mining/:id/image", get(mined_image)).private(),
+        Route::new("/api/mining/delete", post(delete_mined)),
+        Route::new("/api/mining/export", post(export_mined)),
+        Route::new("/api/anki", get(anki_notes).post(add_anki_note)),
+        Route::new("/api/tts", get(text_to_speech)),
+        Route::new("/api/quiz", get(quiz)),
+        Route::new("/api/quiz/answer", post(quiz_answer)),
+        Route::new("/api/search", get(search))
+            .shared()
+            .request(api::SearchRequest::KIND),
+        Route::new("/api/search/summaries", get(search_summaries))
+            .shared()
+            .request(api::SearchSummariesRequest::KIND),
+        Route::new("/api/examples", get(examples))
+            .shared()
+            .request(api::SearchExamplesRequest::KIND),
+        Route::new("/api/entry/:sequence", get(entry))
+            .shared()
+            .request(api::EntryRequest::KIND),
+        Route::new("/api/entries", post(entries))
+            .shared()
+            .request(api::EntriesRequest::KIND),
+        Route::new("/api/related/:sequence", get(related))
+            .shared()
+            .request(api::RelatedRequest::KIND),
+        Route::new("/api/graph", get(graph))
+            .shared()
+            .request(api::GraphRequest::KIND),
+        Route::new("/api/stats", get(stats)),
+        Route::new("/api/stats/clear", post(clear_stats)),
+        Route::new("/api/stats/captures.csv", get(capture_log)).private(),
+        Route::new("/api/sense-examples", get(sense_examples))
+            .shared()
+            .request(api::SenseExamplesRequest::KIND),
+        Route::new("/api/counters/:sequence", get(counters))
+            .shared()
+            .request(api::CountersRequest::KIND),
+        Route::new("/api/count", get(count))
+            .shared()
+            .request(api::CountRequest::KIND),
+        Route::new("/api/kanji/search", get(kanji_search))
+            .shared()
+            .request(api::KanjiSearchRequest::KIND),
+        Route::new("/api/kanji/:literal", get(kanji)).shared(),
+        Route::new("/api/kanji/:literal/components", get(kanji_components))
+            .shared()
+            .request(api::KanjiComponentsRequest::KIND),
+        Route::new("/api/kanji/:literal/words", get(kanji_words))
+            .shared()
+            .request(api::KanjiWordsRequest::KIND),
+        Route::new("/s/:token", get(short_link)).shared(),
+        Route::new("/ws", get(ws::entry)).shared(),
+    ]
+}
+
+/// Kinds of websocket requests which only read from the dictionary, and are
+/// available when it's shared.
+fn shared_requests() -> &'static [&'static str] {
+    static REQUESTS: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+    REQUESTS.get_or_init(|| {
+        routes()
+            .into_iter()
+            .filter(|route| route.shared)
+            .filter_map(|route| route.request)
+            .collect()
+    })
+}
+
+/// Routes which only read from the dictionary, and are available when it's
+/// shared.
+fn shared_routes(router: Router) -> Router {
+    routes()
+        .into_iter()
+        .filter(|route| route.shared)
+        .fold(router, |router, route| {
+            router.route(route.path, route.handler)
+        })
+}
+
+/// All routes, where only routes which don't serve personal data can be used
+/// from other origins.
 fn common_routes(router: Router) -> Router {
-    router
-        .route("/api/version", get(version))
-        .route("/api/config", get(config).post(update_config))
-        .route("/api/rebuild", post(rebuild))
-        .route("/api/analyze", get(analyze))
-        .route("/api/search", get(search))
-        .route("/api/entry/:sequence", get(entry))
-        .route("/api/kanji/:literal", get(kanji))
-        .route("/ws", get(ws::entry))
+    let cors = CorsLayer::new()
+        .allow_origin(AllowOrigin::any())
+        .allow_methods(AllowMethods::any())
+        .expose_headers([self::trace::HEADER.clone()]);
+
+    routes().into_iter().fold(router, |router, route| {
+        if route.private {
+            router.route(route.path, route.handler)
+        } else {
+            router.route(route.path, route.handler.layer(cors.clone()))
+        }
+    })
 }
 
 type RequestResult<T> = std::result::Result<T, RequestError>;
 
 struct RequestError {
     error: anyhow::Error,
-    status: Option<StatusCode>,
+    kind: Option<api::ErrorKind>,
 }
 
 impl RequestError {
-    fn not_found<M>(msg: M) -> Self
+    fn new<M>(kind: api::ErrorKind, msg: M) -> Self
     where
         M: fmt::Display + fmt::Debug + Send + Sync + 'static,
     {
         Self {
             error: anyhow::Error::msg(msg),
-            status: Some(StatusCode::NOT_FOUND),
+            kind: Some(kind),
         }
     }
 
-    fn internal<M>(msg: M) -> Self
+    fn not_found<M>(msg: M) -> Self
     where
         M: fmt::Display + fmt::Debug + Send + Sync + 'static,
     {
-        Self {
-            error: anyhow::Error::msg(msg),
-            status: Some(StatusCode::INTERNAL_SERVER_ERROR),
+        Self::new(api::ErrorKind::NotFound, msg)
+    }
+
+    fn bad_request<M>(msg: M) -> Self
+    where
+        M: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        Self::new(api::ErrorKind::BadRequest, msg)
+    }
+
+    fn unavailable<M>(msg: M) -> Self
+    where
+        M: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        Self::new(api::ErrorKind::Unavailable, msg)
+    }
+
+    /// Convert into the error returned to clients.
+    fn into_api(self) -> api::ApiError {
+        match self.kind {
+            Some(kind) => api::ApiError::new(kind, self.error),
+            None => api::ApiError::from_anyhow(&self.error),
         }
     }
 }
@@ -103,10 +409,7 @@ impl RequestError {
 impl From<anyhow::Error> for RequestError {
     #[inline]
     fn from(error: anyhow::Error) -> Self {
-        Self {
-            error,
-            status: None,
-        }
+        Self { error, kind: None }
     }
 }
 
@@ -114,20 +417,261 @@ async fn entry(
     Path(sequence): Path<u32>,
     Extension(bg): Extension<Background>,
 ) -> RequestResult<Json<api::OwnedEntryResponse>> {
-    let db = bg.database();
-
-    let Some(entry) = db.sequence_to_entry(sequence)? else {
+    let Some(response) = handle_entry_request(&bg, api::EntryRequest { sequence })? else {
         return Err(RequestError::not_found(format!(
             "Missing entry by id `{}`",
             sequence
         )));
     };
 
-    Ok(Json(api::OwnedEntryResponse {
+    Ok(Json(response))
+}
+
+fn handle_entry_request(
+    bg: &Background,
+    request: api::EntryRequest,
+) -> Result<Option<api::OwnedEntryResponse>> {
+    let db = bg.database();
+
+    let Some(entry) = db.sequence_to_entry(request.sequence)? else {
+        return Ok(None);
+    };
+
+    let related = db.related(&entry)?;
+    let difficulty = db.difficulty(&entry)?;
+
+    Ok(Some(api::OwnedEntryResponse {
         entry: lib::to_owned(entry),
+        related: lib::to_owned(related),
+        difficulty: Some(difficulty),
     }))
 }
 
+/// Get several phrases at once.
+async fn entries(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::EntriesRequest>,
+) -> RequestResult<Json<api::OwnedEntriesResponse>> {
+    Ok(Json(handle_entries_request(&bg, request)?))
+}
+
+fn handle_entries_request(
+    bg: &Background,
+    request: api::EntriesRequest,
+) -> Result<api::OwnedEntriesResponse> {
+    if request.sequences.len() > api::MAX_ENTRIES {
+        bail!("At most {} phrases can be requested", api::MAX_ENTRIES);
+    }
+
+    let db = bg.database();
+    let mut entries = Vec::with_capacity(request.sequences.len());
+
+    for sequence in request.sequences {
+        let Some(entry) = db.sequence_to_entry(sequence)? else {
+            continue;
+        };
+
+        entries.push(api::OwnedComparedEntry {
+            sense_examples: best_sense_examples(&db, &entry)?,
+            difficulty: Some(db.difficulty(&entry)?),
+            entry: lib::to_owned(entry),
+        });
+    }
+
+    Ok(api::OwnedEntriesResponse { entries })
+}
+
+/// Get a graph of related phrases.
+async fn graph(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::GraphRequest>,
+) -> RequestResult<Json<Graph>> {
+    Ok(Json(handle_graph_request(&bg, request)?))
+}
+
+fn handle_graph_request(bg: &Background, request: api::GraphRequest) -> Result<Graph> {
+    graph::build(&bg.database(), request.seq, request.depth)
+}
+
+/// Get usage statistics.
+async fn stats(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::StatsRequest>,
+) -> RequestResult<Json<api::StatsResponse>> {
+    Ok(Json(handle_stats_request(&bg, request)))
+}
+
+fn handle_stats_request(bg: &Background, request: api::StatsRequest) -> api::StatsResponse {
+    bg.stats(request.days)
+}
+
+/// Remove all collected usage statistics.
+async fn clear_stats(Extension(bg): Extension<Background>) -> RequestResult<Json<api::Empty>> {
+    bg.clear_stats()?;
+    Ok(Json(api::Empty))
+}
+
+/// Export the log of captures as CSV.
+async fn capture_log(Extension(bg): Extension<Background>) -> RequestResult<Response> {
+    let data = bg.capture_log()?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+        (
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"jpv-captures.csv\"",
+        ),
+    ];
+
+    Ok((headers, data).into_response())
+}
+
+async fn related(
+    Path(sequence): Path<u32>,
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::OwnedRelatedResponse>> {
+    Ok(Json(handle_related_request(
+        &bg,
+        api::RelatedRequest { sequence },
+    )?))
+}
+
+fn handle_related_request(
+    bg: &Background,
+    request: api::RelatedRequest,
+) -> Result<api::OwnedRelatedResponse> {
+    let db = bg.database();
+
+    let related = match db.sequence_to_entry(request.sequence)? {
+        Some(entry) => db.related(&entry)?,
+        None => Vec::new(),
+    };
+
+    Ok(api::OwnedRelatedResponse {
+        related: lib::to_owned(related),
+    })
+}
+
+async fn counters(
+    Path(sequence): Path<u32>,
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::OwnedCountersResponse>> {
+    Ok(Json(handle_counters_request(
+        &bg,
+        api::CountersRequest { sequence },
+    )?))
+}
+
+fn handle_counters_request(
+    bg: &Background,
+    request: api::CountersRequest,
+) -> Result<api::OwnedCountersResponse> {
+    let db = bg.database();
+
+    let counters = match db.sequence_to_entry(request.sequence)? {
+        Some(entry) => counters::suggest(&entry),
+        None => Vec::new(),
+    };
+
+    let counters = counters
+        .into_iter()
+        .map(|c| api::OwnedCounter {
+            text: c.text.to_owned(),
+            reading: c.reading.to_owned(),
+            description: c.description.to_owned(),
+            readings: (1..=10).filter_map(|n| c.reading_of(n)).collect(),
+        })
+        .collect();
+
+    Ok(api::OwnedCountersResponse { counters })
+}
+
+async fn count(Query(request): Query<api::CountRequest>) -> Json<api::CountResponse> {
+    Json(handle_count_request(request))
+}
+
+fn handle_count_request(request: api::CountRequest) -> api::CountResponse {
+    let reading = counters::find(&request.counter).and_then(|c| c.reading_of(request.number));
+    api::CountResponse { reading }
+}
+
+async fn sense_examples(
+    Query(request): Query<api::SenseExamplesRequest>,
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::OwnedSenseExamplesResponse>> {
+    Ok(Json(handle_sense_examples_request(&bg, request)?))
+}
+
+fn handle_sense_examples_request(
+    bg: &Background,
+    request: api::SenseExamplesRequest,
+) -> Result<api::OwnedSenseExamplesResponse> {
+    let db = bg.database();
+
+    let Some(entry) = db.sequence_to_entry(request.sequence)? else {
+        return Ok(api::OwnedSenseExamplesResponse {
+            examples: Vec::new(),
+            total: 0,
+        });
+    };
+
+    // Examples which are not tagged with a sense are shown for the first
+    // sense.
+    let examples = db
+        .sense_examples(&entry)?
+        .into_iter()
+        .filter(|e| e.sense.unwrap_or_default() == request.sense)
+        .collect::<Vec<_>>();
+
+    let total = examples.len();
+
+    let examples = examples
+        .into_iter()
+        .skip(request.offset)
+        .take(request.limit.unwrap_or(usize::MAX))
+        .map(owned_sense_example)
+        .collect();
+
+    Ok(api::OwnedSenseExamplesResponse { examples, total })
+}
+
+/// Collect the best corpus examples for each sense of a phrase.
+fn best_sense_examples(
+    db: &Database,
+    entry: &jmdict::Entry<'_>,
+) -> Result<Vec<api::OwnedSenseExamples>> {
+    let mut by_sense = BTreeMap::<_, api::OwnedSenseExamples>::new();
+
+    // Examples are sorted by score, so the first ones seen are the best.
+    for e in db.sense_examples(entry)? {
+        let sense = e.sense.unwrap_or_default();
+
+        let output = by_sense
+            .entry(sense)
+            .or_insert_with(|| api::OwnedSenseExamples {
+                sense,
+                total: 0,
+                examples: Vec::new(),
+            });
+
+        output.total += 1;
+
+        if output.examples.len() < SENSE_EXAMPLES {
+            output.examples.push(owned_sense_example(e));
+        }
+    }
+
+    Ok(by_sense.into_values().collect())
+}
+
+fn owned_sense_example(e: SenseExample<'_>) -> api::OwnedSenseExample {
+    api::OwnedSenseExample {
+        text: e.text.to_owned(),
+        translation: e.translation.to_owned(),
+        checked: e.checked,
+    }
+}
+
 async fn kanji(
     Path(literal): Path<String>,
     Extension(bg): Extension<Background>,
@@ -140,11 +684,98 @@ async fn kanji(
         )));
     };
 
+    let components = match literal.chars().next() {
+        Some(c) => db.kanji_components(c)?,
+        None => None,
+    };
+
     Ok(Json(api::OwnedKanjiResponse {
         entry: lib::to_owned(entry),
+        components,
     }))
 }
 
+async fn kanji_components(
+    Path(literal): Path<String>,
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::KanjiComponentsResponse>> {
+    Ok(Json(handle_kanji_components_request(
+        &bg,
+        api::KanjiComponentsRequest { literal },
+    )?))
+}
+
+/// Get phrases written using a kanji.
+async fn kanji_words(
+    Path(literal): Path<String>,
+    Extension(bg): Extension<Background>,
+    Query(mut request): Query<api::KanjiWordsRequest>,
+) -> RequestResult<Json<api::OwnedKanjiWordsResponse>> {
+    request.literal = literal;
+    Ok(Json(handle_kanji_words_request(&bg, request)?))
+}
+
+fn handle_kanji_words_request(
+    bg: &Background,
+    request: api::KanjiWordsRequest,
+) -> Result<api::OwnedKanjiWordsResponse> {
+    let Some(c) = request.literal.chars().next() else {
+        return Ok(api::OwnedKanjiWordsResponse {
+            words: Vec::new(),
+            more: false,
+        });
+    };
+
+    let db = bg.database();
+    let limit = request.limit.min(api::MAX_KANJI_WORDS);
+
+    // One more is loaded to tell if there are more.
+    let mut words = db.entries_with_kanji(c, limit + 1, request.offset)?;
+    let more = words.len() > limit;
+    words.truncate(limit);
+
+    Ok(lib::to_owned(api::KanjiWordsResponse { words, more }))
+}
+
+/// Search kanji by reading and meaning.
+async fn kanji_search(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::KanjiSearchRequest>,
+) -> RequestResult<Json<api::OwnedKanjiSearchResponse>> {
+    if request.reading.is_none() && request.meaning.is_none() {
+        return Err(RequestError::bad_request(
+            "Missing a reading or meaning to search for",
+        ));
+    }
+
+    Ok(Json(handle_kanji_search_request(&bg, request)?))
+}
+
+fn handle_kanji_search_request(
+    bg: &Background,
+    request: api::KanjiSearchRequest,
+) -> Result<api::OwnedKanjiSearchResponse> {
+    let db = bg.database();
+
+    let characters = db.search_kanji(request.reading.as_deref(), request.meaning.as_deref())?;
+
+    Ok(lib::to_owned(api::KanjiSearchResponse { characters }))
+}
+
+fn handle_kanji_components_request(
+    bg: &Background,
+    request: api::KanjiComponentsRequest,
+) -> Result<api::KanjiComponentsResponse> {
+    let db = bg.database();
+
+    let components = match request.literal.chars().next() {
+        Some(c) => db.kanji_components(c)?,
+        None => None,
+    };
+
+    Ok(api::KanjiComponentsResponse { components })
+}
+
 async fn search(
     Query(request): Query<api::SearchRequest>,
     Extension(bg): Extension<Background>,
@@ -156,101 +787,1581 @@ fn handle_search_request(
     bg: &Background,
     request: api::SearchRequest,
 ) -> Result<api::OwnedSearchResponse> {
+    let mut output = None;
+
+    handle_search_stream(bg, request, usize::MAX, |response| {
+        output = Some(response);
+        Ok(())
+    })?;
+
+    output.context("Missing search response")
+}
+
+/// Perform a search, emitting responses of at most `chunk` phrases as they
+/// are produced.
+///
+/// Phrases are expensive to produce, so this allows the first ones to be
+/// shown before the rest are done.
+fn handle_search_stream(
+    bg: &Background,
+    request: api::SearchRequest,
+    chunk: usize,
+    mut emit: impl FnMut(api::OwnedSearchResponse) -> Result<()>,
+) -> Result<()> {
     let db = bg.database();
-    let search = db.search(&request.q)?;
+    let profile = bg.profile();
+    let search = db.search_with(&request.q, &*profile)?;
 
-    let mut phrases = Vec::new();
-    let mut names = Vec::new();
+    let groups = if request.group {
+        group_phrases(search.phrases)
+    } else {
+        search.phrases.into_iter().map(|p| vec![p]).collect()
+    };
 
-    for (key, phrase) in search.phrases {
-        phrases.push(api::OwnedSearchPhrase {
-            key,
-            phrase: lib::to_owned(phrase),
-        });
-    }
+    let mut names = Vec::new();
 
     for (key, name) in search.names {
         names.push(api::OwnedSearchName {
+            dictionary: db.index_name(key.key)?.to_owned(),
             key,
             name: lib::to_owned(name),
         });
     }
 
-    Ok(api::OwnedSearchResponse {
-        phrases,
+    let mut ids = names
+        .iter()
+        .map(|n| n.dictionary.as_str())
+        .collect::<BTreeSet<_>>();
+
+    for (key, _) in groups.iter().flatten() {
+        ids.insert(db.index_name(key.key)?);
+    }
+
+    let dictionaries = dictionaries(&bg.config(), ids);
+
+    let total = groups.len();
+    let mut groups = groups.into_iter();
+    let mut first = Some((
         names,
-        characters: lib::to_owned(search.characters),
-    })
-}
+        lib::to_owned(search.characters),
+        search.facets,
+        dictionaries,
+    ));
+    let mut offset = 0;
 
-#[derive(Serialize)]
-struct VersionResponse {
-    version: &'static str,
-}
+    loop {
+        let phrases = groups
+            .by_ref()
+            .take(chunk.max(1))
+            .map(|group| owned_group(&db, &profile, group))
+            .collect::<Result<Vec<_>>>()?;
 
-/// Get the current service version.
-async fn version() -> RequestResult<Json<VersionResponse>> {
-    Ok(Json(VersionResponse {
-        version: crate::VERSION,
-    }))
-}
+        let len = phrases.len();
+        let more = offset + len < total;
+        let (names, characters, facets, dictionaries) = first.take().unwrap_or_default();
 
-/// Read the current service configuration.
-async fn config(Extension(bg): Extension<Background>) -> RequestResult<Json<Config>> {
-    Ok(Json(bg.config()))
-}
+        emit(api::OwnedSearchResponse {
+            phrases,
+            names,
+            characters,
+            facets,
+            dictionaries,
+            offset,
+            more,
+        })?;
 
-/// Read the current service configuration.
-async fn update_config(
-    Extension(bg): Extension<Background>,
-    Json(config): Json<Config>,
-) -> RequestResult<Json<api::Empty>> {
-    if !bg.update_config(config).await {
-        return Err(RequestError::internal("Failed to update configuration"));
-    }
+        if !more {
+            return Ok(());
+        }
 
-    Ok(Json(api::Empty))
+        offset += len;
+    }
 }
 
-/// Trigger a rebuild of the database.
-async fn rebuild(Extension(bg): Extension<Background>) -> RequestResult<Json<api::Empty>> {
-    bg.rebuild().await;
-    Ok(Json(api::Empty))
+/// Describe the given dictionaries using the configuration they are installed
+/// with.
+fn dictionaries<'a>(
+    config: &Config,
+    ids: impl IntoIterator<Item = &'a str>,
+) -> Vec<api::Dictionary> {
+    let defaults = Config::default();
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let index = config.indexes.get(id)?;
+
+            Some(api::Dictionary {
+                id: id.to_owned(),
+                title: index
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| index.format.title().to_owned()),
+                format: index.format,
+                user: !defaults.indexes.contains_key(id),
+                url: index.url.clone(),
+                help: index.help.clone(),
+                attribution: index.format.attribution().map(str::to_owned),
+            })
+        })
+        .collect()
 }
 
-/// Perform text analysis.
-async fn analyze(
-    Query(request): Query<api::AnalyzeRequest>,
+async fn search_summaries(
+    Query(request): Query<api::SearchSummariesRequest>,
     Extension(bg): Extension<Background>,
-) -> RequestResult<Json<api::OwnedAnalyzeResponse>> {
-    Ok(Json(handle_analyze_request(&bg, request)?))
+) -> RequestResult<Json<api::OwnedSearchSummariesResponse>> {
+    Ok(Json(handle_search_summaries_request(&bg, request)?))
 }
 
-fn handle_analyze_request(
+fn handle_search_summaries_request(
+    bg: &Background,
+    request: api::SearchSummariesRequest,
+) -> Result<api::OwnedSearchSummariesResponse> {
+    let db = bg.database();
+    let profile = bg.profile();
+    let search = db.search_with(&request.q, &*profile)?;
+
+    let mut entries = Vec::with_capacity(search.phrases.len() + search.names.len());
+
+    for (_, phrase) in &search.phrases {
+        let reading = preferred(&phrase.reading_elements, |r| r.is_rarely_used());
+        let kanji = preferred(&phrase.kanji_elements, |k| k.is_rarely_used());
+
+        let mut priority = Vec::new();
+
+        for p in kanji
+            .iter()
+            .flat_map(|k| &k.priority)
+            .chain(reading.iter().flat_map(|r| &r.priority))
+        {
+            if !priority.contains(p) {
+                priority.push(*p);
+            }
+        }
+
+        let reading = reading.map(|r| r.text);
+
+        let Some(term) = kanji.map(|k| k.text).or(reading) else {
+            continue;
+        };
+
+        entries.push(api::EntrySummary {
+            kind: api::SummaryKind::Phrase,
+            sequence: phrase.sequence,
+            term,
+            reading: reading.filter(|r| *r != term),
+            gloss: phrase
+                .senses
+                .first()
+                .and_then(|s| s.gloss.first())
+                .map(|g| g.text),
+            priority,
+        });
+    }
+
+    for (_, name) in &search.names {
+        let reading = name.reading.first().map(|r| r.text);
+        let kanji = name.kanji.first().copied();
+
+        let Some(term) = kanji.or(reading) else {
+            continue;
+        };
+
+        entries.push(api::EntrySummary {
+            kind: api::SummaryKind::Name,
+            sequence: name.sequence,
+            term,
+            reading: reading.filter(|r| *r != term),
+            gloss: name.translations.first().map(|t| t.text),
+            priority: Vec::new(),
+        });
+    }
+
+    Ok(api::OwnedSearchSummariesResponse {
+        entries: lib::to_owned(entries),
+    })
+}
+
+async fn examples(
+    Query(request): Query<api::SearchExamplesRequest>,
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::OwnedSearchExamplesResponse>> {
+    Ok(Json(handle_search_examples_request(&bg, request)?))
+}
+
+fn handle_search_examples_request(
+    bg: &Background,
+    request: api::SearchExamplesRequest,
+) -> Result<api::OwnedSearchExamplesResponse> {
+    let db = bg.database();
+    let examples = db.search_examples(&request.q)?;
+    let total = examples.len();
+
+    let examples = examples
+        .into_iter()
+        .take(EXAMPLES_LIMIT)
+        .map(|e| {
+            let kanji = e.entry.kanji_elements.first().map(|k| k.text);
+
+            let reading = e
+                .entry
+                .reading_elements
+                .iter()
+                .find(|r| match kanji {
+                    Some(kanji) => r.applies_to(kanji),
+                    None => true,
+                })
+                .map(|r| r.text);
+
+            lib::to_owned(api::SearchExample {
+                text: e.text,
+                translation: e.translation,
+                text_highlights: e.text_highlights,
+                translation_highlights: e.translation_highlights,
+                sequence: e.entry.sequence,
+                kanji,
+                reading,
+            })
+        })
+        .collect();
+
+    Ok(api::OwnedSearchExamplesResponse { examples, total })
+}
+
+/// Cluster phrases which share kanji elements, so that near-duplicate entries
+/// are presented as variants of the best ranked one.
+///
+/// Phrases are expected to already be sorted by weight, and the first phrase
+/// in each group is the best ranked one.
+fn group_phrases(
+    phrases: Vec<(EntryResultKey, jmdict::Entry<'_>)>,
+) -> Vec<Vec<(EntryResultKey, jmdict::Entry<'_>)>> {
+    let mut groups = Vec::<Vec<_>>::new();
+    let mut by_kanji = HashMap::new();
+
+    for (key, phrase) in phrases {
+        let existing = phrase
+            .kanji_elements
+            .iter()
+            .find_map(|kanji| by_kanji.get(kanji.text).copied());
+
+        let index = existing.unwrap_or(groups.len());
+
+        for kanji in &phrase.kanji_elements {
+            by_kanji.entry(kanji.text).or_insert(index);
+        }
+
+        match groups.get_mut(index) {
+            Some(group) => group.push((key, phrase)),
+            None => groups.push(vec![(key, phrase)]),
+        }
+    }
+
+    groups
+}
+
+/// Convert a group of phrases into an owned phrase, where the phrases after
+/// the first become its variants.
+fn owned_group(
+    db: &Database,
+    profile: &Profile,
+    group: Vec<(EntryResultKey, jmdict::Entry<'_>)>,
+) -> Result<api::OwnedSearchPhrase> {
+    let mut it = group.into_iter();
+
+    let Some((key, phrase)) = it.next() else {
+        bail!("Empty phrase group");
+    };
+
+    let mut output = owned_phrase(db, profile, key, phrase)?;
+
+    for (key, phrase) in it {
+        output
+            .variants
+            .push(owned_phrase(db, profile, key, phrase)?);
+    }
+
+    Ok(output)
+}
+
+fn owned_phrase(
+    db: &Database,
+    profile: &Profile,
+    key: EntryResultKey,
+    phrase: jmdict::Entry<'_>,
+) -> Result<api::OwnedSearchPhrase> {
+    Ok(api::OwnedSearchPhrase {
+        dictionary: db.index_name(key.key)?.to_owned(),
+        key,
+        sense_examples: best_sense_examples(db, &phrase)?,
+        difficulty: Some(db.difficulty(&phrase)?),
+        annotation: profile.annotations.get(&phrase.sequence).cloned(),
+        phrase: lib::to_owned(phrase),
+        variants: Vec::new(),
+    })
+}
+
+#[derive(Serialize)]
+struct VersionResponse {
+    version: &'static str,
+    /// The version of the protocol spoken by the service.
+    protocol: String,
+}
+
+/// Get the current service version.
+async fn version() -> RequestResult<Json<VersionResponse>> {
+    Ok(Json(VersionResponse {
+        version: crate::VERSION,
+        protocol: api::compat::PROTOCOL_VERSION.to_string(),
+    }))
+}
+
+/// Expand a short link into the query it describes.
+async fn short_link(
+    Extension(base): Extension<BasePath>,
+    Path(token): Path<String>,
+) -> RequestResult<Redirect> {
+    let query = lib::link::expand(&token)
+        .map_err(|error| RequestError::bad_request(format!("Invalid link: {error}")))?;
+    Ok(Redirect::temporary(&format!("{}/?{query}", base.as_str())))
+}
+
+/// Read the current service configuration.
+async fn config(Extension(bg): Extension<Background>) -> RequestResult<Json<Config>> {
+    Ok(Json(bg.config()))
+}
+
+/// Read the current service configuration.
+async fn update_config(
+    Extension(bg): Extension<Background>,
+    Json(config): Json<Config>,
+) -> RequestResult<Json<api::Empty>> {
+    handle_update_config_request(&bg, config).await?;
+    Ok(Json(api::Empty))
+}
+
+async fn handle_update_config_request(bg: &Background, mut config: Config) -> Result<()> {
+    config.keep_commands(&bg.config());
+
+    if let Err(error) = capture::Filters::new(&config.capture_filters) {
+        return Err(api::ApiError::new(api::ErrorKind::BadRequest, format!("{error:#}")).into());
+    }
+
+    if !bg.update_config(config).await {
+        bail!("Failed to update configuration");
+    }
+
+    Ok(())
+}
+
+/// Preview what capture filters do to a sample of captured text.
+async fn preview_capture_filters(
+    Json(request): Json<api::PreviewCaptureFiltersRequest>,
+) -> RequestResult<Json<api::PreviewCaptureFiltersResponse>> {
+    Ok(Json(handle_preview_capture_filters_request(request)))
+}
+
+fn handle_preview_capture_filters_request(
+    request: api::PreviewCaptureFiltersRequest,
+) -> api::PreviewCaptureFiltersResponse {
+    let preview = capture::preview(&request.filters, &request.text);
+
+    api::PreviewCaptureFiltersResponse {
+        text: preview.text,
+        errors: preview
+            .errors
+            .into_iter()
+            .map(|(index, message)| api::CaptureFilterError { index, message })
+            .collect(),
+    }
+}
+
+/// Capture data as if it was copied to the clipboard.
+async fn capture(
+    Extension(system_events): Extension<system::SystemEvents>,
+    Query(request): Query<api::CaptureRequest>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> RequestResult<Json<api::Empty>> {
+    let mimetype = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/plain");
+
+    // Text sent over HTTP is UTF-8, unlike the escaped text which the
+    // `text/plain` type otherwise signals.
+    let mimetype = match mimetype.split(';').next().unwrap_or_default().trim() {
+        "text/plain" => "text/plain;charset=utf-8",
+        mimetype => mimetype,
+    };
+
+    let source = request
+        .source
+        .map(|source| source.trim().to_owned())
+        .filter(|source| !source.is_empty());
+
+    system_events.send(system::Event::SendClipboardData(
+        system::SendClipboardData {
+            mimetype: mimetype.to_owned(),
+            data: body.to_vec(),
+            source,
+        },
+    ));
+
+    Ok(Json(api::Empty))
+}
+
+/// Correct the text recognized in a captured image.
+async fn correct_ocr(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::CorrectOcrRequest>,
+) -> RequestResult<Json<api::Empty>> {
+    handle_correct_ocr_request(&bg, request);
+    Ok(Json(api::Empty))
+}
+
+fn handle_correct_ocr_request(bg: &Background, request: api::CorrectOcrRequest) {
+    let text = request.text.trim();
+
+    if !text.is_empty() {
+        bg.correct_ocr(request.key, text.to_owned());
+    }
+}
+
+/// Trigger a rebuild of the database.
+async fn rebuild(Extension(bg): Extension<Background>) -> RequestResult<Json<api::Empty>> {
+    bg.rebuild().await;
+    Ok(Json(api::Empty))
+}
+
+/// Report which dictionaries are installed and which are missing.
+async fn status(Extension(bg): Extension<Background>) -> RequestResult<Json<api::StatusResponse>> {
+    Ok(Json(handle_status_request(&bg)?))
+}
+
+fn handle_status_request(bg: &Background) -> Result<api::StatusResponse> {
+    let installed = bg.database().installed()?;
+
+    let missing = bg
+        .config()
+        .indexes
+        .into_iter()
+        .filter(|(id, index)| index.enabled && !installed.contains(id))
+        .map(|(id, index)| api::MissingIndex {
+            id,
+            format: index.format,
+            description: index.description,
+        })
+        .collect();
+
+    let mut installed = installed.into_iter().collect::<Vec<_>>();
+    installed.sort();
+
+    Ok(api::StatusResponse {
+        installed,
+        missing,
+        onboarded: bg.config().onboarded,
+        port: bg.port(),
+    })
+}
+
+/// Complete the first-run setup.
+async fn onboard(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::OnboardRequest>,
+) -> RequestResult<Json<api::Empty>> {
+    handle_onboard_request(&bg, request).await?;
+    Ok(Json(api::Empty))
+}
+
+async fn handle_onboard_request(bg: &Background, request: api::OnboardRequest) -> Result<()> {
+    let mut config = bg.config();
+
+    let needs_license = config
+        .indexes
+        .values()
+        .any(|index| index.enabled && index.format.attribution().is_some());
+
+    if request.install && needs_license && !request.accept_license {
+        return Err(api::ApiError::new(
+            api::ErrorKind::BadRequest,
+            "The license of the dictionaries has to be accepted to install them",
+        )
+        .into());
+    }
+
+    config.onboarded = true;
+    config.ocr = request.ocr;
+
+    if !bg.update_config(config).await {
+        bail!("Failed to update configuration");
+    }
+
+    if request.install {
+        bg.rebuild().await;
+    }
+
+    Ok(())
+}
+
+/// Report whether a newer release of the service is available.
+async fn update(Extension(bg): Extension<Background>) -> RequestResult<Json<api::UpdateResponse>> {
+    Ok(Json(bg.update().await))
+}
+
+/// List available profiles.
+async fn profiles(
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::ProfilesResponse>> {
+    Ok(Json(bg.profiles()?))
+}
+
+/// Select a profile, creating it if it doesn't exist.
+async fn select_profile(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::SelectProfileRequest>,
+) -> RequestResult<Json<api::ProfilesResponse>> {
+    Ok(Json(bg.select_profile(&request.name)?))
+}
+
+/// Delete a profile.
+async fn delete_profile(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::DeleteProfileRequest>,
+) -> RequestResult<Json<api::ProfilesResponse>> {
+    Ok(Json(bg.delete_profile(&request.name)?))
+}
+
+/// Change how names are weighed when analyzing text in the current profile.
+async fn set_name_weighting(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::SetNameWeightingRequest>,
+) -> RequestResult<Json<api::ProfilesResponse>> {
+    Ok(Json(bg.set_name_weighting(request.names)?))
+}
+
+/// Change how readings are shown above kanji in the current profile.
+async fn set_readings(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::SetReadingsRequest>,
+) -> RequestResult<Json<api::ProfilesResponse>> {
+    Ok(Json(bg.set_readings(request.readings)?))
+}
+
+/// Change how densely entries and characters are shown in the current
+/// profile.
+async fn set_density(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::SetDensityRequest>,
+) -> RequestResult<Json<api::ProfilesResponse>> {
+    Ok(Json(bg.set_density(request.density, request.text_scale)?))
+}
+
+/// Synchronize the current profile with other installs.
+async fn sync(Extension(bg): Extension<Background>) -> RequestResult<Json<api::Empty>> {
+    bg.sync_profile()?;
+    Ok(Json(api::Empty))
+}
+
+/// Get the history of the current profile.
+async fn history(
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::HistoryResponse>> {
+    Ok(Json(handle_history_request(&bg)?))
+}
+
+fn handle_history_request(bg: &Background) -> Result<api::HistoryResponse> {
+    let mut entries = bg.profile().history.clone();
+    entries.reverse();
+    Ok(api::HistoryResponse { entries })
+}
+
+/// Add to the history of the current profile.
+async fn add_history(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::AddHistoryRequest>,
+) -> RequestResult<Json<api::Empty>> {
+    handle_add_history_request(&bg, request)?;
+    Ok(Json(api::Empty))
+}
+
+fn handle_add_history_request(bg: &Background, request: api::AddHistoryRequest) -> Result<()> {
+    let text = request.text.trim();
+
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let timestamp = unix_timestamp();
+    bg.update_profile(|profile| profile.add_history(text, timestamp))?;
+    bg.record_activity(Activity::Search);
+    Ok(())
+}
+
+/// Import known words into the current profile.
+async fn import_known_words(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::ImportKnownWordsRequest>,
+) -> RequestResult<Json<api::ImportKnownWordsResponse>> {
+    Ok(Json(handle_import_known_words_request(&bg, request)?))
+}
+
+fn handle_import_known_words_request(
+    bg: &Background,
+    request: api::ImportKnownWordsRequest,
+) -> Result<api::ImportKnownWordsResponse> {
+    let words = known::parse(&request.text);
+    let db = bg.database();
+
+    let mut sequences = Vec::new();
+    let mut missing = Vec::new();
+
+    for word in &words {
+        let len = sequences.len();
+
+        for id in db.lookup(word)? {
+            if id.source().is_inflection() {
+                continue;
+            }
+
+            let Entry::Phrase(entry) = db.entry_at(id)? else {
+                continue;
+            };
+
+            let matches = entry.kanji_elements.iter().any(|k| k.text == word)
+                || entry.reading_elements.iter().any(|r| r.text == word);
+
+            if matches {
+                sequences.push(entry.sequence);
+            }
+        }
+
+        if sequences.len() == len {
+            missing.push(word.clone());
+        }
+    }
+
+    let modified = unix_timestamp();
+
+    let (added, known) = bg.update_profile(|profile| {
+        let before = profile.known.len();
+
+        for sequence in sequences {
+            profile.set_word_status(sequence, WordStatus::Known, modified);
+        }
+
+        (profile.known.len() - before, profile.known.len())
+    })?;
+
+    Ok(api::ImportKnownWordsResponse {
+        words: words.len(),
+        added,
+        missing,
+        known,
+    })
+}
+
+/// List the documents stored in the reader.
+async fn documents(
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::DocumentsResponse>> {
+    Ok(Json(handle_documents_request(&bg)?))
+}
+
+fn handle_documents_request(bg: &Background) -> Result<api::DocumentsResponse> {
+    Ok(api::DocumentsResponse {
+        documents: bg.documents()?,
+    })
+}
+
+/// Add a document to the reader.
+async fn add_document(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::AddDocumentRequest>,
+) -> RequestResult<Json<DocumentInfo>> {
+    Ok(Json(handle_add_document_request(&bg, request)?))
+}
+
+fn handle_add_document_request(
+    bg: &Background,
+    request: api::AddDocumentRequest,
+) -> Result<DocumentInfo> {
+    let title = match request.title.trim() {
+        "" => request.text.lines().map(str::trim).find(|l| !l.is_empty()),
+        title => Some(title),
+    };
+
+    let title = title.unwrap_or_default();
+    let title = title.chars().take(DOCUMENT_TITLE_LIMIT).collect::<String>();
+    bg.add_document(Document::new(&title, &request.text, unix_timestamp()))
+}
+
+/// Add subtitles to the reader.
+async fn add_subtitles(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::AddSubtitlesRequest>,
+) -> RequestResult<Json<DocumentInfo>> {
+    Ok(Json(handle_add_subtitles_request(&bg, request)?))
+}
+
+fn handle_add_subtitles_request(
+    bg: &Background,
+    request: api::AddSubtitlesRequest,
+) -> Result<DocumentInfo> {
+    let cues = subtitles::parse(&request.name, &request.text)?;
+
+    let title = match request.name.rsplit_once('.') {
+        Some((title, _)) => title,
+        None => &request.name,
+    };
+
+    let title = title.chars().take(DOCUMENT_TITLE_LIMIT).collect::<String>();
+    bg.add_document(Document::from_subtitles(&title, cues, unix_timestamp()))
+}
+
+/// Add a book from an EPUB archive to the reader.
+async fn add_epub(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::AddEpubRequest>,
+    body: Bytes,
+) -> RequestResult<Json<DocumentInfo>> {
+    Ok(Json(bg.add_epub(&body, request.ruby, unix_timestamp())?))
+}
+
+/// Delete a document from the reader.
+async fn delete_document(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::DeleteDocumentRequest>,
+) -> RequestResult<Json<api::DocumentsResponse>> {
+    Ok(Json(handle_delete_document_request(&bg, request)?))
+}
+
+fn handle_delete_document_request(
+    bg: &Background,
+    request: api::DeleteDocumentRequest,
+) -> Result<api::DocumentsResponse> {
+    bg.delete_document(request.id)?;
+    handle_documents_request(bg)
+}
+
+/// Read a page of a document, storing it as the reading position.
+async fn page(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::PageRequest>,
+) -> RequestResult<Json<api::PageResponse>> {
+    Ok(Json(handle_page_request(&bg, request)?))
+}
+
+fn handle_page_request(bg: &Background, request: api::PageRequest) -> Result<api::PageResponse> {
+    bg.update_document(request.id, |document| {
+        let pages = document.pages();
+
+        let line = request
+            .time
+            .and_then(|time| document.line_at(time))
+            .map(|line| line.range.start);
+
+        let page = match line {
+            Some(offset) => reader::page_of(&pages, offset),
+            None => request.page.unwrap_or(document.position),
+        };
+
+        let page = page.min(pages.len().saturating_sub(1));
+        document.position = page;
+
+        let range = pages.get(page).cloned().unwrap_or_default();
+        let lines = document.page_lines(&range);
+
+        let line = line.and_then(|offset| {
+            lines
+                .iter()
+                .position(|l| l.range.start + range.start == offset)
+        });
+
+        api::PageResponse {
+            document: document.info(request.id),
+            page,
+            text: document.text[range].to_owned(),
+            toc: document.toc(&pages),
+            lines,
+            line,
+        }
+    })
+}
+
+/// Get statistics about the words in a document.
+async fn document_stats(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::DocumentStatsRequest>,
+) -> RequestResult<Json<api::CoverageResponse>> {
+    Ok(Json(handle_document_stats_request(&bg, request).await?))
+}
+
+async fn handle_document_stats_request(
+    bg: &Background,
+    request: api::DocumentStatsRequest,
+) -> Result<api::CoverageResponse> {
+    let document = bg.document(request.id)?;
+
+    let segments = reader::segments(&document.text)
+        .into_iter()
+        .map(|segment| &document.text[segment]);
+
+    coverage(bg, segments, Some(UNKNOWN_WORDS_LIMIT)).await
+}
+
+/// Get how much of a text is covered by known words.
+async fn text_coverage(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::CoverageRequest>,
+) -> RequestResult<Json<api::CoverageResponse>> {
+    Ok(Json(handle_coverage_request(&bg, request).await?))
+}
+
+async fn handle_coverage_request(
+    bg: &Background,
+    request: api::CoverageRequest,
+) -> Result<api::CoverageResponse> {
+    let segments = reader::segments(&request.q)
+        .into_iter()
+        .map(|segment| &request.q[segment]);
+
+    coverage(bg, segments, None).await
+}
+
+/// Count the words in the given segments of text, and how many of them are
+/// known in the current profile.
+///
+/// At most `limit` unknown words are returned if specified.
+async fn coverage<'a, I>(
+    bg: &Background,
+    segments: I,
+    limit: Option<usize>,
+) -> Result<api::CoverageResponse>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let profile = bg.profile();
+    let db = bg.database();
+    let tokenizer = bg.tokenizer();
+
+    let mut words = 0;
+    let mut counts = HashMap::<u64, (usize, &str)>::new();
+
+    for text in segments {
+        for span in tokenizer::spawn_phrase_spans(&db, tokenizer.as_ref(), text).await? {
+            if is_grammatical(&span.entry) {
+                continue;
+            }
+
+            words += 1;
+
+            counts
+                .entry(span.entry.sequence)
+                .or_insert((0, dictionary_form(&span.entry)))
+                .0 += 1;
+        }
+    }
+
+    let unique = counts.len();
+    let mut known = 0;
+    let mut unknown_words = Vec::new();
+
+    for (sequence, (count, text)) in counts {
+        if profile.is_known(sequence) {
+            known += count;
+            continue;
+        }
+
+        unknown_words.push(api::UnknownWord {
+            text: text.to_owned(),
+            sequence,
+            count,
+        });
+    }
+
+    let unknown = unknown_words.len();
+
+    unknown_words.sort_by(|a, b| (Reverse(a.count), &a.text).cmp(&(Reverse(b.count), &b.text)));
+
+    if let Some(limit) = limit {
+        unknown_words.truncate(limit);
+    }
+
+    let coverage = match words {
+        0 => 100.0,
+        words => known as f32 * 100.0 / words as f32,
+    };
+
+    Ok(api::CoverageResponse {
+        words,
+        known,
+        coverage,
+        unique,
+        unknown,
+        unknown_words,
+    })
+}
+
+/// The first element which isn't rarely used, or the first element if all of
+/// them are.
+fn preferred<T>(elements: &[T], is_rarely_used: impl Fn(&T) -> bool) -> Option<&T> {
+    elements
+        .iter()
+        .find(|e| !is_rarely_used(e))
+        .or(elements.first())
+}
+
+/// The form of a phrase as it's listed in a dictionary.
+fn dictionary_form<'a>(entry: &jmdict::Entry<'a>) -> &'a str {
+    let kanji = entry.kanji_elements.iter().find(|k| !k.is_search_only());
+    let reading = entry.reading_elements.iter().find(|r| !r.is_search_only());
+
+    match (kanji, reading) {
+        (Some(k), _) => k.text,
+        (None, Some(r)) => r.text,
+        (None, None) => entry.reading_elements.first().map_or("", |r| r.text),
+    }
+}
+
+/// Search the timed lines of a document.
+async fn search_lines(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::SearchLinesRequest>,
+) -> RequestResult<Json<api::SearchLinesResponse>> {
+    Ok(Json(handle_search_lines_request(&bg, request)?))
+}
+
+fn handle_search_lines_request(
+    bg: &Background,
+    request: api::SearchLinesRequest,
+) -> Result<api::SearchLinesResponse> {
+    let document = bg.document(request.id)?;
+    let q = request.q.trim();
+
+    if q.is_empty() {
+        return Ok(api::SearchLinesResponse { lines: Vec::new() });
+    }
+
+    let pages = document.pages();
+
+    let lines = document
+        .search_lines(q)
+        .take(LINES_LIMIT)
+        .map(|line| api::LineMatch {
+            start: line.start,
+            page: reader::page_of(&pages, line.range.start),
+            text: document.text[line.range.clone()].to_owned(),
+        })
+        .collect();
+
+    Ok(api::SearchLinesResponse { lines })
+}
+
+/// Test if a phrase is a grammatical word like a particle, which isn't
+/// interesting to count as a word in a text.
+fn is_grammatical(entry: &jmdict::Entry<'_>) -> bool {
+    let Some(sense) = entry.senses.first() else {
+        return false;
+    };
+
+    !sense.pos.is_empty()
+        && sense.pos.iter().all(|pos| {
+            matches!(
+                pos,
+                PartOfSpeech::Particle
+                    | PartOfSpeech::Auxiliary
+                    | PartOfSpeech::AuxiliaryAdjective
+                    | PartOfSpeech::AuxiliaryVerb
+                    | PartOfSpeech::Conjunction
+                    | PartOfSpeech::Copular
+            )
+        })
+}
+
+/// Seconds since the unix epoch.
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Export all user data as a zip archive.
+async fn export_user_data(Extension(bg): Extension<Background>) -> RequestResult<Response> {
+    let data = bg.export_user_data()?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "application/zip"),
+        (
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"jpv-user-data.zip\"",
+        ),
+    ];
+
+    Ok((headers, data).into_response())
+}
+
+/// Export phrases as a printable PDF document.
+async fn export_pdf(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::ExportPdfRequest>,
+) -> RequestResult<Response> {
+    let data = handle_export_pdf_request(&bg, request)?;
+
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf"),
+        (
+            header::CONTENT_DISPOSITION,
+            "inline; filename=\"jpv-vocabulary.pdf\"",
+        ),
+    ];
+
+    Ok((headers, data).into_response())
+}
+
+fn handle_export_pdf_request(bg: &Background, request: api::ExportPdfRequest) -> Result<Vec<u8>> {
+    let sequences = if request.sequences.is_empty() {
+        let profile = bg.profile();
+
+        quiz::vocabulary(&profile, request.list.as_deref())
+            .into_iter()
+            .flat_map(|sequence| u32::try_from(sequence).ok())
+            .collect()
+    } else {
+        request.sequences
+    };
+
+    if sequences.len() > api::MAX_EXPORT_ENTRIES {
+        bail!(
+            "At most {} phrases can be exported",
+            api::MAX_EXPORT_ENTRIES
+        );
+    }
+
+    let db = bg.database();
+    let mut articles = Vec::with_capacity(sequences.len());
+
+    for sequence in sequences {
+        let Some(entry) = db.sequence_to_entry(sequence)? else {
+            continue;
+        };
+
+        if let Some(article) = Article::new(&entry, request.lang.as_deref()) {
+            articles.push(article);
+        }
+    }
+
+    let title = match (&request.title, &request.list) {
+        (Some(title), _) => title.as_str(),
+        (None, Some(list)) => list.as_str(),
+        (None, None) => "Vocabulary",
+    };
+
+    Ok(pdf::vocabulary(title, &articles, &request.layout))
+}
+
+/// Import user data from a zip archive.
+async fn import_user_data(
+    Extension(bg): Extension<Background>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> RequestResult<Json<api::Empty>> {
+    let mime = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    // Other web pages can only send this content type after the browser has
+    // asked whether they're allowed to, which they aren't.
+    if mime.split(';').next().unwrap_or_default().trim() != "application/zip" {
+        return Err(RequestError::bad_request(
+            "User data must be imported as application/zip",
+        ));
+    }
+
+    bg.import_user_data(&body).await?;
+    Ok(Json(api::Empty))
+}
+
+/// Perform text analysis.
+async fn analyze(
+    Query(request): Query<api::AnalyzeRequest>,
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::OwnedAnalyzeResponse>> {
+    Ok(Json(handle_analyze_request(&bg, request).await?))
+}
+
+async fn handle_analyze_request(
     bg: &Background,
     request: api::AnalyzeRequest,
 ) -> Result<api::OwnedAnalyzeResponse> {
     let mut data = Vec::new();
 
     let db = bg.database();
+    let words = tokenizer::spawn_words(bg.tokenizer().as_ref(), &request.q).await;
 
-    for (key, string) in db.analyze(&request.q, request.start)? {
+    let options = AnalyzeOptions {
+        words: &words,
+        names: bg.profile().name_weighting(),
+    };
+
+    let end = request.end.unwrap_or(request.q.len());
+
+    for candidate in db.analyze_span_with(&request.q, request.start, end, &options)? {
         data.push(api::OwnedAnalyzeEntry {
-            key,
-            string: string.to_owned(),
+            string: candidate.string.to_owned(),
+            score: candidate.score,
         });
     }
 
-    data.sort_by(|a, b| (Reverse(a.string.len()), &a.key).cmp(&(Reverse(b.string.len()), &b.key)));
     Ok(api::OwnedAnalyzeResponse { data })
 }
 
+async fn difficulty(
+    Query(request): Query<api::DifficultyRequest>,
+    Extension(bg): Extension<Background>,
+) -> RequestResult<Json<api::DifficultyResponse>> {
+    Ok(Json(handle_difficulty_request(&bg, request).await?))
+}
+
+async fn handle_difficulty_request(
+    bg: &Background,
+    request: api::DifficultyRequest,
+) -> Result<api::DifficultyResponse> {
+    let db = bg.database();
+    let tokenizer = bg.tokenizer();
+
+    let mut words = Vec::new();
+
+    for span in tokenizer::spawn_phrase_spans(&db, tokenizer.as_ref(), &request.q).await? {
+        words.push(api::WordDifficulty {
+            range: span.range,
+            sequence: span.entry.sequence,
+            difficulty: db.difficulty(&span.entry)?,
+        });
+    }
+
+    Ok(api::DifficultyResponse { words })
+}
+
+/// Get the status of the words in a text.
+async fn word_status(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::WordStatusRequest>,
+) -> RequestResult<Json<api::WordStatusResponse>> {
+    Ok(Json(handle_word_status_request(&bg, request).await?))
+}
+
+async fn handle_word_status_request(
+    bg: &Background,
+    request: api::WordStatusRequest,
+) -> Result<api::WordStatusResponse> {
+    let profile = bg.profile();
+    let db = bg.database();
+    let tokenizer = bg.tokenizer();
+
+    let mut words = Vec::new();
+
+    for span in tokenizer::spawn_phrase_spans(&db, tokenizer.as_ref(), &request.q).await? {
+        if is_grammatical(&span.entry) {
+            continue;
+        }
+
+        words.push(api::WordStatusSpan {
+            range: span.range,
+            sequence: span.entry.sequence,
+            status: profile.word_status(span.entry.sequence),
+        });
+    }
+
+    Ok(api::WordStatusResponse { words })
+}
+
+/// Change the status of a word.
+async fn set_word_status(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::SetWordStatusRequest>,
+) -> RequestResult<Json<api::Empty>> {
+    handle_set_word_status_request(&bg, request)?;
+    Ok(Json(api::Empty))
+}
+
+fn handle_set_word_status_request(
+    bg: &Background,
+    request: api::SetWordStatusRequest,
+) -> Result<()> {
+    let modified = unix_timestamp();
+
+    bg.update_profile(|profile| {
+        profile.set_word_status(request.sequence, request.status, modified)
+    })?;
+
+    if request.status != WordStatus::Unknown {
+        bg.record_activity(Activity::Save);
+    }
+
+    Ok(())
+}
+
+/// Set the note and tags of a phrase.
+async fn set_annotation(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::SetAnnotationRequest>,
+) -> RequestResult<Json<api::SetAnnotationResponse>> {
+    Ok(Json(handle_set_annotation_request(&bg, request)?))
+}
+
+fn handle_set_annotation_request(
+    bg: &Background,
+    request: api::SetAnnotationRequest,
+) -> Result<api::SetAnnotationResponse> {
+    let modified = unix_timestamp();
+
+    let annotation = bg.update_profile(|profile| {
+        profile
+            .annotate(request.sequence, &request.note, &request.tags, modified)
+            .cloned()
+    })?;
+
+    Ok(api::SetAnnotationResponse { annotation })
+}
+
+/// List the audio recorded by the user for a phrase.
+async fn recordings(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::RecordingsRequest>,
+) -> RequestResult<Json<api::RecordingsResponse>> {
+    Ok(Json(handle_recordings_request(&bg, request)?))
+}
+
+fn handle_recordings_request(
+    bg: &Background,
+    request: api::RecordingsRequest,
+) -> Result<api::RecordingsResponse> {
+    Ok(api::RecordingsResponse {
+        recordings: bg.recordings(request.sequence)?,
+    })
+}
+
+/// Attach recorded audio to a phrase.
+async fn add_recording(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::AddRecordingRequest>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> RequestResult<Json<Recording>> {
+    let mime = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    Ok(Json(bg.add_recording(
+        request.sequence,
+        mime,
+        &body,
+        unix_timestamp(),
+    )?))
+}
+
+/// Get the audio of a recording.
+async fn recording(
+    Extension(bg): Extension<Background>,
+    Path(id): Path<u64>,
+) -> RequestResult<Response> {
+    let (recording, audio) = bg
+        .recording(id)
+        .map_err(|error| RequestError::not_found(error.to_string()))?;
+
+    let headers = [(header::CONTENT_TYPE, recording.mime)];
+    Ok((headers, audio).into_response())
+}
+
+/// Delete a recording.
+async fn delete_recording(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::DeleteRecordingRequest>,
+) -> RequestResult<Json<api::Empty>> {
+    handle_delete_recording_request(&bg, request)?;
+    Ok(Json(api::Empty))
+}
+
+fn handle_delete_recording_request(
+    bg: &Background,
+    request: api::DeleteRecordingRequest,
+) -> Result<()> {
+    bg.delete_recording(request.id)
+}
+
+/// List mined cards.
+async fn mined(Extension(bg): Extension<Background>) -> RequestResult<Json<api::MinedResponse>> {
+    Ok(Json(api::MinedResponse { cards: bg.mined()? }))
+}
+
+/// Mine a card from a captured sentence.
+async fn mine(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::MineRequest>,
+) -> RequestResult<Json<Card>> {
+    Ok(Json(handle_mine_request(&bg, request)?))
+}
+
+fn handle_mine_request(bg: &Background, request: api::MineRequest) -> Result<Card> {
+    let card = new_card(bg, &request.sentence, request.sequence)?;
+    bg.mine(card, request.screenshot, unix_timestamp())
+}
+
+/// Assemble a card for the phrase with the given sequence.
+fn new_card(bg: &Background, sentence: &str, sequence: u64) -> Result<NewCard> {
+    let sequence = u32::try_from(sequence)?;
+    let db = bg.database();
+
+    let Some(entry) = db.sequence_to_entry(sequence)? else {
+        bail!("Missing entry by id `{sequence}`");
+    };
+
+    let Some(article) = Article::new(&entry, Some("eng")) else {
+        bail!("Entry `{sequence}` has no meanings in English");
+    };
+
+    Ok(NewCard::new(sentence, &article))
+}
+
+/// Get the image attached to a mined card.
+async fn mined_image(
+    Extension(bg): Extension<Background>,
+    Path(id): Path<u64>,
+) -> RequestResult<Response> {
+    let (card, image) = bg
+        .mined_image(id)
+        .map_err(|error| RequestError::not_found(error.to_string()))?;
+
+    let headers = [(header::CONTENT_TYPE, card.image.unwrap_or_default())];
+    Ok((headers, image).into_response())
+}
+
+/// Delete a mined card.
+async fn delete_mined(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::DeleteMinedRequest>,
+) -> RequestResult<Json<api::Empty>> {
+    bg.delete_mined(request.id)?;
+    Ok(Json(api::Empty))
+}
+
+/// Export mined cards to Anki.
+async fn export_mined(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::ExportMinedRequest>,
+) -> RequestResult<Json<api::ExportMinedResponse>> {
+    let response = bg
+        .export_mined(&request.ids, request.allow_duplicates)
+        .await?;
+
+    Ok(Json(response))
+}
+
+/// Find the notes in Anki for a phrase.
+async fn anki_notes(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::AnkiNotesRequest>,
+) -> RequestResult<Json<api::AnkiNotesResponse>> {
+    Ok(Json(handle_anki_notes_request(&bg, request).await?))
+}
+
+async fn handle_anki_notes_request(
+    bg: &Background,
+    request: api::AnkiNotesRequest,
+) -> Result<api::AnkiNotesResponse> {
+    let card = new_card(bg, "", request.sequence)?;
+    let notes = bg.anki_notes(&card).await?;
+    Ok(api::AnkiNotesResponse { notes })
+}
+
+/// Add a note to Anki for a phrase.
+async fn add_anki_note(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::AddAnkiNoteRequest>,
+) -> RequestResult<Json<api::AnkiNotesResponse>> {
+    Ok(Json(handle_add_anki_note_request(&bg, request).await?))
+}
+
+async fn handle_add_anki_note_request(
+    bg: &Background,
+    request: api::AddAnkiNoteRequest,
+) -> Result<api::AnkiNotesResponse> {
+    let card = new_card(bg, "", request.sequence)?;
+    let notes = bg.add_anki_note(&card, request.allow_duplicate).await?;
+    Ok(api::AnkiNotesResponse { notes })
+}
+
+/// Read text out loud, streaming the synthesized audio.
+async fn text_to_speech(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::TtsRequest>,
+) -> RequestResult<Response> {
+    let text = request.text.trim();
+
+    if text.is_empty() {
+        return Err(RequestError::bad_request("Missing text to read"));
+    }
+
+    if text.chars().count() > tts::MAX_TEXT {
+        return Err(RequestError::bad_request(format!(
+            "Text is longer than {} characters",
+            tts::MAX_TEXT
+        )));
+    }
+
+    let Some(backend) = bg.tts() else {
+        return Err(RequestError::unavailable(
+            "Text-to-speech is not configured",
+        ));
+    };
+
+    let speech = backend.speak(text)?;
+    let headers = [(header::CONTENT_TYPE, speech.mime)];
+    Ok((headers, speech.body).into_response())
+}
+
+/// Get a conjugation practice question.
+async fn quiz(
+    Extension(bg): Extension<Background>,
+    Query(request): Query<api::QuizRequest>,
+) -> RequestResult<Json<api::QuizResponse>> {
+    Ok(Json(handle_quiz_request(&bg, request)?))
+}
+
+fn handle_quiz_request(bg: &Background, request: api::QuizRequest) -> Result<api::QuizResponse> {
+    let profile = bg.profile();
+    let db = bg.database();
+
+    let mut rng = rand::thread_rng();
+
+    let mut sequences = quiz::vocabulary(&profile, request.list.as_deref())
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    sequences.shuffle(&mut rng);
+
+    for sequence in sequences {
+        let Ok(sequence) = u32::try_from(sequence) else {
+            continue;
+        };
+
+        let Some(entry) = db.sequence_to_entry(sequence)? else {
+            continue;
+        };
+
+        let conjugations = inflection::conjugate(&entry);
+
+        let mut candidates = Vec::new();
+
+        for (_, inflections, _) in &conjugations {
+            for (inflection, _) in quiz::questions(inflections) {
+                candidates.push((&inflections.dictionary, inflection));
+            }
+        }
+
+        let Some((dictionary, inflection)) = candidates.choose(&mut rng) else {
+            continue;
+        };
+
+        return Ok(api::QuizResponse {
+            question: Some(api::QuizQuestion {
+                sequence: entry.sequence,
+                text: format!("{}{}", dictionary.text, dictionary.suffix),
+                reading: format!("{}{}", dictionary.reading, dictionary.suffix),
+                inflection: *inflection,
+                explanation: inflection.explanation(),
+                gloss: entry
+                    .senses
+                    .first()
+                    .and_then(|s| s.gloss.first())
+                    .map(|g| g.text.to_owned())
+                    .unwrap_or_default(),
+            }),
+        });
+    }
+
+    Ok(api::QuizResponse { question: None })
+}
+
+/// Answer a conjugation practice question.
+async fn quiz_answer(
+    Extension(bg): Extension<Background>,
+    Json(request): Json<api::QuizAnswerRequest>,
+) -> RequestResult<Json<api::QuizAnswerResponse>> {
+    Ok(Json(handle_quiz_answer_request(&bg, request)?))
+}
+
+fn handle_quiz_answer_request(
+    bg: &Background,
+    request: api::QuizAnswerRequest,
+) -> Result<api::QuizAnswerResponse> {
+    let db = bg.database();
+
+    let sequence = u32::try_from(request.sequence).context("Sequence out of range")?;
+
+    let Some(entry) = db.sequence_to_entry(sequence)? else {
+        bail!("No entry with sequence {}", request.sequence);
+    };
+
+    let mut correct = false;
+    let mut expected = Vec::new();
+
+    for (_, inflections, _) in inflection::conjugate(&entry) {
+        let dictionary = &inflections.dictionary;
+
+        if format!("{}{}", dictionary.text, dictionary.suffix) != request.text {
+            continue;
+        }
+
+        let Some(fragments) = inflections.get(request.inflection) else {
+            continue;
+        };
+
+        correct |= quiz::is_correct(fragments, &request.answer);
+
+        for answer in quiz::answers(fragments) {
+            if !expected.contains(&answer) {
+                expected.push(answer);
+            }
+        }
+    }
+
+    if expected.is_empty() {
+        bail!("No such inflection of {}", request.text);
+    }
+
+    if !correct {
+        let now = unix_timestamp();
+
+        bg.update_profile(|profile| {
+            profile.lapse(request.sequence, now);
+        })?;
+    }
+
+    bg.record_activity(Activity::Review);
+    Ok(api::QuizAnswerResponse { correct, expected })
+}
+
 impl IntoResponse for RequestError {
     fn into_response(self) -> Response {
-        tracing::error!("{}", self.error);
-        let bytes = format!("{}", self.error).into_bytes();
-        let mut response = Response::new(boxed(Body::from(bytes)));
-        *response.status_mut() = self.status.unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
-        response
+        let mut error = self.into_api();
+
+        if let Some(id) = self::trace::current() {
+            error = error.with_request_id(&id);
+        }
+
+        if error.kind == api::ErrorKind::Internal {
+            tracing::error!("{}", error.message);
+        } else {
+            tracing::debug!("{:?}: {}", error.kind, error.message);
+        }
+
+        let status =
+            StatusCode::from_u16(error.kind.status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(error)).into_response()
     }
 }
@@ -0,0 +1,30 @@
+//! Checking the version of the protocol HTTP clients speak.
+//!
+//! Clients which don't declare a version, like scripts using the API, are
+//! always served.
+
+use axum::http::Request;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use lib::api::compat;
+
+use super::RequestError;
+
+/// Middleware refusing requests from clients speaking an unsupported version
+/// of the protocol.
+pub(crate) async fn layer<B>(request: Request<B>, next: Next<B>) -> Response {
+    let Some(value) = request.headers().get(compat::HEADER) else {
+        return next.run(request).await;
+    };
+
+    let version = match value.to_str() {
+        Ok(value) => value.parse::<compat::Version>(),
+        Err(..) => return RequestError::bad_request("Invalid protocol version").into_response(),
+    };
+
+    if let Err(error) = version.and_then(compat::check) {
+        return RequestError::from(anyhow::Error::new(error)).into_response();
+    }
+
+    next.run(request).await
+}
@@ -0,0 +1,45 @@
+use axum::body::Body;
+use axum::http::{header, Method, Request};
+use axum::Router;
+use tower::ServiceExt;
+
+/// Send a preflight request for a `GET` to the given path from another
+/// origin, returning the origins allowed to read it.
+async fn preflight(path: &str) -> Option<String> {
+    let router = super::common_routes(Router::new());
+
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri(path)
+        .header(header::ORIGIN, "https://example.com")
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = router.oneshot(request).await.unwrap();
+
+    let origin = response
+        .headers()
+        .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)?;
+
+    Some(origin.to_str().unwrap().to_owned())
+}
+
+#[tokio::test]
+async fn cors() {
+    assert_eq!(preflight("/api/search").await.as_deref(), Some("*"));
+    assert_eq!(preflight("/api/entry/1").await.as_deref(), Some("*"));
+}
+
+#[tokio::test]
+async fn private_cors() {
+    for path in super::routes()
+        .into_iter()
+        .filter(|route| route.private)
+        .map(|route| route.path)
+    {
+        assert_eq!(preflight(path).await, None, "{path}");
+    }
+
+    assert_eq!(preflight("/api/user-data").await, None);
+}
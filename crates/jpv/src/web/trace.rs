@@ -0,0 +1,58 @@
+//! Identifiers for requests, which tie together everything logged while
+//! handling one and are returned to clients so that they can be included in
+//! reports.
+
+use axum::http::{HeaderName, HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use tracing::Instrument;
+
+/// The header a request identifier is accepted from and returned in.
+pub(crate) static HEADER: HeaderName = HeaderName::from_static("x-request-id");
+/// The longest identifier which is accepted from clients.
+const MAX_LENGTH: usize = 64;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The identifier of the request which is currently being handled.
+pub(crate) fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Middleware assigning an identifier to each request, unless the client
+/// provided one, and handling the request in a span carrying it.
+pub(crate) async fn layer<B>(request: Request<B>, next: Next<B>) -> Response {
+    let id = request
+        .headers()
+        .get(&HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid(id))
+        .map(str::to_owned)
+        .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>()));
+
+    let span = tracing::info_span!(
+        "request",
+        %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let future = next.run(request).instrument(span);
+    let mut response = REQUEST_ID.scope(id.clone(), future).await;
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(HEADER.clone(), value);
+    }
+
+    response
+}
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_LENGTH
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use axum::extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade};
@@ -13,67 +14,125 @@ use lib::api::{self, Request};
 use rand::prelude::*;
 use rand::rngs::SmallRng;
 use tokio::sync::broadcast::Receiver;
-use tokio::sync::Mutex;
 use tokio::time::Duration;
 use tracing::{Instrument, Level};
 
 use crate::background::Background;
+use crate::clipboard;
 use crate::system;
 
+use super::RateLimit;
+
 pub(super) async fn entry(
     ws: WebSocketUpgrade,
     Extension(bg): Extension<Background>,
     Extension(system_events): Extension<system::SystemEvents>,
     ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    limit: Option<Extension<Arc<RateLimit>>>,
 ) -> impl IntoResponse {
     let receiver = system_events.subscribe();
+    let client = bg.new_client_id();
+    let limit = limit.map(|Extension(limit)| limit);
+    // The identifier of the upgrade request, which the connection is logged
+    // under.
+    let upgrade = super::trace::current();
 
     ws.on_upgrade(move |socket| async move {
-        let span = tracing::span!(Level::INFO, "websocket", ?remote);
-
-        if let Err(error) = run(receiver, socket, &bg).instrument(span).await {
+        let span = tracing::span!(
+            Level::INFO,
+            "websocket",
+            ?remote,
+            client,
+            upgrade = upgrade.as_deref()
+        );
+
+        let result = run(receiver, socket, &bg, client, remote, limit.as_deref())
+            .instrument(span)
+            .await;
+
+        if let Err(error) = result {
             tracing::error!(?error);
         }
+
+        bg.set_capture_focus(client, false);
     })
 }
 
-fn decode_escaped(data: &[u8]) -> Option<String> {
-    fn h(b: u8) -> Option<u32> {
-        let b = match b {
-            b'a'..=b'f' => b - b'a' + 10,
-            b'A'..=b'F' => b - b'A' + 10,
-            b'0'..=b'9' => b - b'0',
-            _ => return None,
-        };
+/// Where the responses to a request are sent, and how they're encoded.
+#[derive(Clone, Copy)]
+struct Reply {
+    version: api::compat::Version,
+    index: usize,
+    serial: u32,
+}
 
-        Some(b as u32)
+impl Reply {
+    fn new(request: &api::ClientRequestEnvelope) -> Self {
+        Self {
+            version: request.version,
+            index: request.index,
+            serial: request.serial,
+        }
     }
+}
 
-    let mut s = String::new();
+/// Stream search responses to the client as they are produced.
+async fn stream_search(
+    bg: &Background,
+    sink: &mut SplitSink<WebSocket, Message>,
+    reply: Reply,
+    request: api::SearchRequest,
+    chunk: usize,
+    id: &str,
+) -> Result<()> {
+    let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    let bg = bg.clone();
+    let span = tracing::Span::current();
+
+    let task = tokio::task::spawn_blocking(move || {
+        span.in_scope(|| {
+            super::handle_search_stream(&bg, request, chunk, |response| {
+                tx.blocking_send(response)
+                    .map_err(|_| anyhow!("Search was cancelled"))
+            })
+        })
+    });
 
-    let mut it = data.iter().copied();
+    while let Some(response) = rx.recv().await {
+        send_response(sink, reply, serde_json::to_value(&response)?, None).await?;
+    }
 
-    while let Some(b) = it.next() {
-        match (b, it.clone().next()) {
-            (b'\\', Some(b'u')) => {
-                it.next();
-                let [a, b, c, d] = [it.next()?, it.next()?, it.next()?, it.next()?];
-                let [a, b, c, d] = [h(a)?, h(b)?, h(c)?, h(d)?];
-                let c = a << 12 | b << 8 | c << 4 | d;
-                s.push(char::from_u32(c)?);
-            }
-            (b'\\', Some(b'\\')) => {
-                it.next();
-                s.push('\\');
-            }
-            (c, _) if c.is_ascii() => {
-                s.push(c as char);
-            }
-            _ => {}
-        }
+    if let Err(error) = task.await? {
+        send_response(
+            sink,
+            reply,
+            serde_json::Value::Null,
+            Some(api::ApiError::from_anyhow(&error).with_request_id(id)),
+        )
+        .await?;
     }
 
-    Some(s)
+    Ok(())
+}
+
+async fn send_response(
+    sink: &mut SplitSink<WebSocket, Message>,
+    reply: Reply,
+    body: serde_json::Value,
+    error: Option<api::ApiError>,
+) -> Result<()> {
+    let payload = api::compat::encode_response(
+        reply.version,
+        api::ClientResponseEnvelope {
+            index: reply.index,
+            serial: reply.serial,
+            body,
+            error,
+        },
+    )?;
+
+    sink.send(Message::Binary(payload)).await?;
+    Ok(())
 }
 
 async fn log_backfill(
@@ -89,74 +148,68 @@ async fn log_backfill(
     Ok(())
 }
 
+async fn capture_focus(
+    sink: &mut SplitSink<WebSocket, Message>,
+    focus: Option<u32>,
+    client: u32,
+) -> Result<()> {
+    let event = api::ClientEvent::Broadcast(api::Broadcast {
+        kind: api::BroadcastKind::CaptureFocus(api::CaptureFocus {
+            focused: focus == Some(client),
+            claimed: focus.is_some(),
+        }),
+    });
+
+    let json = serde_json::to_vec(&event)?;
+    sink.send(Message::Binary(json)).await?;
+    Ok(())
+}
+
 async fn system_event(
     bg: &Background,
     sink: &mut SplitSink<WebSocket, Message>,
     event: system::Event,
+    client: u32,
 ) -> Result<()> {
-    match event {
-        system::Event::SendClipboardData(clipboard) => match clipboard.mimetype.as_str() {
-            "UTF8_STRING" | "text/plain;charset=utf-8" => {
-                let data = filter_data(&clipboard.data);
-
-                let event = api::ClientEvent::Broadcast(api::Broadcast {
-                    kind: api::BroadcastKind::SendClipboardData(api::SendClipboard {
-                        ty: Some("text/plain"),
-                        data: data.as_ref(),
-                    }),
-                });
-
-                let json = serde_json::to_vec(&event)?;
-                sink.send(Message::Binary(json)).await?;
-            }
-            "STRING" | "text/plain" => {
-                let Some(data) = decode_escaped(&clipboard.data[..]) else {
-                    tracing::warn!("failed to decode");
-                    return Ok(());
-                };
+    if event.is_capture() && bg.capture_focus().is_some_and(|focus| focus != client) {
+        return Ok(());
+    }
 
-                let data = filter_data(&data);
+    if bg
+        .clipboard()
+        .capture_source(&event)
+        .is_some_and(|source| bg.is_ignored_source(&source))
+    {
+        return Ok(());
+    }
 
-                let event = api::ClientEvent::Broadcast(api::Broadcast {
-                    kind: api::BroadcastKind::SendClipboardData(api::SendClipboard {
-                        ty: Some("text/plain"),
-                        data: data.as_ref(),
-                    }),
-                });
+    match event {
+        system::Event::SendClipboardData(clipboard) => {
+            let Some(handler) = bg.clipboard().find(&clipboard.mimetype) else {
+                tracing::trace!(clipboard.mimetype, "Unsupported clipboard data");
+                return Ok(());
+            };
 
-                let json = serde_json::to_vec(&event)?;
-                sink.send(Message::Binary(json)).await?;
-            }
-            ty @ "application/json" => {
-                let event = api::ClientEvent::Broadcast(api::Broadcast {
-                    kind: api::BroadcastKind::SendClipboardData(api::SendClipboard {
-                        ty: Some(ty),
-                        data: &clipboard.data,
-                    }),
-                });
-
-                let json = serde_json::to_vec(&event)?;
-                sink.send(Message::Binary(json)).await?;
-            }
-            ty => {
-                let Some(tesseract) = bg.tesseract() else {
-                    return Ok(());
-                };
+            let Some(event) = handler.handle(bg, &clipboard).await? else {
+                return Ok(());
+            };
 
-                let Some(event) = handle_mimetype_image(tesseract, ty, &clipboard).await? else {
-                    return Ok(());
-                };
+            let json = serde_json::to_vec(&event)?;
+            sink.send(Message::Binary(json)).await?;
+        }
+        system::Event::SendDynamicImage(image) => {
+            let mut png = std::io::Cursor::new(Vec::new());
 
-                let json = serde_json::to_vec(&event)?;
-                sink.send(Message::Binary(json)).await?;
+            match image.write_to(&mut png, image::ImageOutputFormat::Png) {
+                Ok(()) => bg.set_screenshot("image/png", png.into_inner()),
+                Err(error) => tracing::warn!(?error, "Failed to encode captured image"),
             }
-        },
-        system::Event::SendDynamicImage(image) => {
-            let Some(tesseract) = bg.tesseract() else {
+
+            let Some(engine) = bg.ocr() else {
                 return Ok(());
             };
 
-            let Some(event) = handle_image(tesseract, image).await? else {
+            let Some(event) = clipboard::recognize(bg, engine, image, None).await? else {
                 return Ok(());
             };
 
@@ -164,14 +217,9 @@ async fn system_event(
             sink.send(Message::Binary(json)).await?;
         }
         system::Event::SendText(text) => {
-            let data = filter_data(&text);
-
-            let event = api::ClientEvent::Broadcast(api::Broadcast {
-                kind: api::BroadcastKind::SendClipboardData(api::SendClipboard {
-                    ty: Some("text/plain"),
-                    data: data.as_ref(),
-                }),
-            });
+            let Some(event) = clipboard::text(&bg.capture_filters(), text.as_bytes(), None) else {
+                return Ok(());
+            };
 
             let json = serde_json::to_vec(&event)?;
             sink.send(Message::Binary(json)).await?;
@@ -208,6 +256,17 @@ async fn system_event(
 
             sink.send(Message::Binary(json)).await?;
         }
+        system::Event::CaptureFocus(focus) => {
+            capture_focus(sink, focus, client).await?;
+        }
+        system::Event::ProfileChanged => {
+            let event = api::ClientEvent::Broadcast(api::Broadcast {
+                kind: api::BroadcastKind::ProfileChanged,
+            });
+
+            let json = serde_json::to_vec(&event)?;
+            sink.send(Message::Binary(json)).await?;
+        }
         system::Event::Refresh => {
             let event = api::ClientEvent::Broadcast(api::Broadcast {
                 kind: api::BroadcastKind::Refresh,
@@ -221,75 +280,19 @@ async fn system_event(
     Ok(())
 }
 
-async fn handle_mimetype_image(
-    tesseract: &Mutex<tesseract::Tesseract>,
-    ty: &str,
-    c: &system::SendClipboardData,
-) -> Result<Option<api::OwnedClientEvent>> {
-    use image::ImageFormat;
-
-    let format = match ty {
-        "image/png" => ImageFormat::Png,
-        "image/tiff" => ImageFormat::Tiff,
-        "image/webp" => ImageFormat::WebP,
-        "image/jpeg" | "image/jpg" => ImageFormat::Jpeg,
-        _ => return Ok(None),
-    };
-
-    tracing::trace!(len = c.data.len(), "Decoding image");
-
-    let image = match image::load_from_memory_with_format(&c.data[..], format) {
-        Ok(image) => image,
-        Err(error) => {
-            tracing::warn!(?error, "Failed to load clipboard image");
-            return Ok(None);
-        }
-    };
-
-    handle_image(tesseract, image).await
-}
-
-async fn handle_image(
-    tesseract: &Mutex<tesseract::Tesseract>,
-    image: image::DynamicImage,
-) -> Result<Option<api::OwnedClientEvent>> {
-    let data = image.as_bytes();
-    let width = usize::try_from(image.width())?;
-    let height = usize::try_from(image.height())?;
-    let bytes_per_pixel = usize::try_from(image.color().bytes_per_pixel())?;
-
-    tracing::trace!(len = data.len(), width, height, bytes_per_pixel);
-
-    let text = match tesseract
-        .lock()
-        .await
-        .image_to_text(data, width, height, bytes_per_pixel)
-    {
-        Ok(text) => text,
-        Err(error) => {
-            tracing::warn!(?error, "Image recognition failed");
-            return Ok(None);
-        }
-    };
-
-    let trimmed = trim_whitespace(&text[..]);
-
-    tracing::trace!(text = &text[..], ?trimmed, "Recognized");
-
-    Ok(Some(api::OwnedClientEvent::Broadcast(
-        api::OwnedBroadcast {
-            kind: api::OwnedBroadcastKind::SendClipboardData(api::OwnedSendClipboard {
-                ty: Some("text/plain".to_owned()),
-                data: filter_data(trimmed.as_ref()).into(),
-            }),
-        },
-    )))
+/// Test if a request only reads from the dictionary, and can be served when
+/// it's shared.
+fn is_shared_request(kind: &str) -> bool {
+    super::shared_requests().contains(&kind)
 }
 
 async fn run(
     mut system_events: Receiver<system::Event>,
     socket: WebSocket,
     bg: &Background,
+    client: u32,
+    remote: SocketAddr,
+    limit: Option<&RateLimit>,
 ) -> Result<()> {
     tracing::trace!("Accepted");
 
@@ -308,9 +311,13 @@ async fn run(
     let mut ping_interval = tokio::time::interval(PING_TIMEOUT);
     ping_interval.reset();
 
-    let log = bg.log();
+    // Clients of a shared dictionary don't see the log or captures.
+    if !bg.is_read_only() {
+        let log = bg.log();
 
-    log_backfill(&mut sender, log).await?;
+        log_backfill(&mut sender, log).await?;
+        capture_focus(&mut sender, bg.capture_focus(), client).await?;
+    }
 
     let close_here = loop {
         tokio::select! {
@@ -330,7 +337,11 @@ async fn run(
                     break Some((CLOSE_NORMAL, "system shutting down"));
                 };
 
-                if let Err(error) = system_event(bg, &mut sender, event).await {
+                if bg.is_read_only() {
+                    continue;
+                }
+
+                if let Err(error) = system_event(bg, &mut sender, event, client).await {
                     tracing::error!(?error, "Failed to process system event");
                 };
             }
@@ -352,65 +363,32 @@ async fn run(
 
                         tracing::trace!("Got request: {:?}", request);
 
-                        let result: Result<serde_json::Value> = match request.kind.as_str() {
-                            api::SearchRequest::KIND => {
-                                let request = serde_json::from_value(request.body)?;
-                                let response = super::handle_search_request(bg, request)?;
-                                Ok(serde_json::to_value(&response)?)
-                            },
-                            api::AnalyzeRequest::KIND => {
-                                let request = serde_json::from_value(request.body)?;
-                                let response = super::handle_analyze_request(bg, request)?;
-                                Ok(serde_json::to_value(&response)?)
-                            },
-                            api::InstallAllRequest::KIND => {
-                                bg.rebuild().await;
-                                Ok(serde_json::Value::Null)
-                            }
-                            api::GetConfig::KIND => {
-                                let database = bg.database();
-
-                                let missing_ocr = if bg.tesseract().is_none() {
-                                    Some(api::MissingOcr::for_platform())
-                                } else {
-                                    None
-                                };
-
-                                let result = api::GetConfigResult {
-                                    config: bg.config(),
-                                    installed: database.installed()?,
-                                    missing_ocr,
-                                };
-
-                                Ok(serde_json::to_value(&result)?)
-                            }
-                            api::UpdateConfigRequest::KIND => {
-                                let config = serde_json::from_value(request.body)?;
-
-                                if !bg.update_config(config).await {
-                                    Err(anyhow!("Failed to update configuration"))
-                                } else {
-                                    Ok(serde_json::Value::Null)
-                                }
-                            }
-                            _ => {
-                                Err(anyhow!("Unsupported request"))
-                            }
-                        };
+                        // Identifies the request among those of all clients.
+                        let id = format!("{client}.{}.{}", request.index, request.serial);
 
-                        let (body, error) = match result {
-                            Ok(value) => (value, None),
-                            Err(error) => (serde_json::Value::Null, Some(error.to_string())),
+                        let reply = Reply::new(&request);
+
+                        let denied = if let Err(error) = api::compat::check(request.version) {
+                            Some(error)
+                        } else if bg.is_read_only() && !is_shared_request(&request.kind) {
+                            Some(api::ApiError::new(api::ErrorKind::Forbidden, "Not available in a shared dictionary"))
+                        } else if limit.is_some_and(|limit| !limit.check(remote.ip())) {
+                            Some(api::ApiError::new(api::ErrorKind::RateLimited, "Too many requests"))
+                        } else {
+                            None
                         };
 
-                        let payload = serde_json::to_vec(&api::OwnedClientEvent::ClientResponse(api::ClientResponseEnvelope {
-                            index: request.index,
-                            serial: request.serial,
-                            body,
-                            error,
-                        }))?;
+                        if let Some(denied) = denied {
+                            let denied = denied.with_request_id(&id);
+                            send_response(&mut sender, reply, serde_json::Value::Null, Some(denied)).await?;
+                            continue;
+                        }
 
-                        sender.send(Message::Binary(payload)).await?;
+                        let span = tracing::info_span!("request", %id, kind = %request.kind);
+
+                        handle_request(bg, &mut sender, client, request, &id)
+                            .instrument(span)
+                            .await?;
                     },
                     Message::Ping(payload) => {
                         sender.send(Message::Pong(payload)).await?;
@@ -453,79 +431,326 @@ async fn run(
     Ok(())
 }
 
-fn trim_whitespace(input: &str) -> Cow<'_, str> {
-    let mut output = String::new();
-    let mut c = input.char_indices();
+/// Handle a single request received over the websocket, where `id`
+/// identifies it in logs and errors.
+async fn handle_request(
+    bg: &Background,
+    sender: &mut SplitSink<WebSocket, Message>,
+    client: u32,
+    request: api::ClientRequestEnvelope,
+    id: &str,
+) -> Result<()> {
+    let reply = Reply::new(&request);
 
-    'ws: {
-        for (n, c) in c.by_ref() {
-            if c.is_whitespace() {
-                output.push_str(&input[..n]);
-                break 'ws;
-            }
-        }
+    let result: Result<serde_json::Value> = match request.kind.as_str() {
+        api::SearchRequest::KIND => {
+            let search: api::SearchRequest = serde_json::from_value(request.body)?;
 
-        return Cow::Borrowed(input);
-    };
+            if let Some(chunk) = search.chunk {
+                stream_search(bg, sender, reply, search, chunk, id).await?;
+                return Ok(());
+            }
 
-    for (_, c) in c {
-        if !c.is_whitespace() {
-            output.push(c);
+            let response = super::handle_search_request(bg, search)?;
+            Ok(serde_json::to_value(&response)?)
         }
-    }
-
-    Cow::Owned(output)
-}
-
-fn filter_data<T>(data: &T) -> Cow<'_, [u8]>
-where
-    T: ?Sized + AsRef<[u8]>,
-{
-    let mut data = data.as_ref();
-
-    fn filter(b: u8) -> bool {
-        b.is_ascii_control() || b.is_ascii_whitespace()
-    }
-
-    while let [a, rest @ ..] = data {
-        if filter(*a) {
-            data = rest;
-            continue;
+        api::SearchSummariesRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_search_summaries_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
         }
+        api::EntryRequest::KIND => {
+            let request: api::EntryRequest = serde_json::from_value(request.body)?;
+            let sequence = request.sequence;
 
-        break;
-    }
-
-    while let [rest @ .., a] = data {
-        if filter(*a) {
-            data = rest;
-            continue;
+            match super::handle_entry_request(bg, request)? {
+                Some(response) => Ok(serde_json::to_value(&response)?),
+                None => Err(anyhow!("Missing entry by id `{sequence}`")),
+            }
+        }
+        api::StatsRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_stats_request(bg, request);
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::ClearStatsRequest::KIND => {
+            bg.clear_stats()?;
+            Ok(serde_json::Value::Null)
+        }
+        api::GraphRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_graph_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::EntriesRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_entries_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::AnalyzeRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_analyze_request(bg, request).await?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::SearchExamplesRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_search_examples_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::SenseExamplesRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_sense_examples_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::DifficultyRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_difficulty_request(bg, request).await?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::WordStatusRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_word_status_request(bg, request).await?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::SetAnnotationRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_set_annotation_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::RecordingsRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_recordings_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::DeleteRecordingRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            super::handle_delete_recording_request(bg, request)?;
+            Ok(serde_json::Value::Null)
+        }
+        api::MinedRequest::KIND => {
+            let response = api::MinedResponse { cards: bg.mined()? };
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::MineRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_mine_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::DeleteMinedRequest::KIND => {
+            let request: api::DeleteMinedRequest = serde_json::from_value(request.body)?;
+            bg.delete_mined(request.id)?;
+            Ok(serde_json::Value::Null)
+        }
+        api::ExportMinedRequest::KIND => {
+            let request: api::ExportMinedRequest = serde_json::from_value(request.body)?;
+            let response = bg
+                .export_mined(&request.ids, request.allow_duplicates)
+                .await?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::AnkiNotesRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_anki_notes_request(bg, request).await?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::AddAnkiNoteRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_add_anki_note_request(bg, request).await?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::QuizRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_quiz_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::QuizAnswerRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_quiz_answer_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
         }
+        api::SetWordStatusRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            super::handle_set_word_status_request(bg, request)?;
+            Ok(serde_json::Value::Null)
+        }
+        api::KanjiComponentsRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_kanji_components_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::KanjiWordsRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_kanji_words_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::KanjiSearchRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_kanji_search_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::CountersRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_counters_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::CountRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_count_request(request);
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::RelatedRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_related_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::StatusRequest::KIND => {
+            let response = super::handle_status_request(bg)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::OnboardRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            super::handle_onboard_request(bg, request).await?;
+            Ok(serde_json::Value::Null)
+        }
+        api::UpdateRequest::KIND => {
+            let response = bg.update().await;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::InstallAllRequest::KIND => {
+            bg.rebuild().await;
+            Ok(serde_json::Value::Null)
+        }
+        api::GetConfig::KIND => {
+            let database = bg.database();
 
-        break;
-    }
+            let missing_ocr = if bg.ocr().is_none() {
+                Some(api::MissingOcr::for_platform())
+            } else {
+                None
+            };
 
-    let mut output = Vec::new();
-    let mut it = data.iter().enumerate();
+            let result = api::GetConfigResult {
+                config: bg.config(),
+                installed: database.installed()?,
+                missing_ocr,
+            };
 
-    'ws: {
-        for (n, b) in it.by_ref() {
-            if filter(*b) {
-                output.extend_from_slice(&data[..n]);
-                break 'ws;
-            }
+            Ok(serde_json::to_value(&result)?)
         }
-
-        return Cow::Borrowed(data);
-    }
-
-    for (_, b) in it {
-        if filter(*b) {
-            continue;
+        api::CaptureFocusRequest::KIND => {
+            let request: api::CaptureFocusRequest = serde_json::from_value(request.body)?;
+            bg.set_capture_focus(client, request.claim);
+            Ok(serde_json::Value::Null)
+        }
+        api::ProfilesRequest::KIND => Ok(serde_json::to_value(bg.profiles()?)?),
+        api::SelectProfileRequest::KIND => {
+            let request: api::SelectProfileRequest = serde_json::from_value(request.body)?;
+            Ok(serde_json::to_value(bg.select_profile(&request.name)?)?)
+        }
+        api::DeleteProfileRequest::KIND => {
+            let request: api::DeleteProfileRequest = serde_json::from_value(request.body)?;
+            Ok(serde_json::to_value(bg.delete_profile(&request.name)?)?)
+        }
+        api::SetNameWeightingRequest::KIND => {
+            let request: api::SetNameWeightingRequest = serde_json::from_value(request.body)?;
+            Ok(serde_json::to_value(bg.set_name_weighting(request.names)?)?)
+        }
+        api::SetReadingsRequest::KIND => {
+            let request: api::SetReadingsRequest = serde_json::from_value(request.body)?;
+            Ok(serde_json::to_value(bg.set_readings(request.readings)?)?)
         }
+        api::SetDensityRequest::KIND => {
+            let request: api::SetDensityRequest = serde_json::from_value(request.body)?;
+            Ok(serde_json::to_value(
+                bg.set_density(request.density, request.text_scale)?,
+            )?)
+        }
+        api::SyncRequest::KIND => {
+            bg.sync_profile()?;
+            Ok(serde_json::Value::Null)
+        }
+        api::AddHistoryRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            super::handle_add_history_request(bg, request)?;
+            Ok(serde_json::Value::Null)
+        }
+        api::HistoryRequest::KIND => {
+            let response = super::handle_history_request(bg)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::ImportKnownWordsRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_import_known_words_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::DocumentsRequest::KIND => {
+            let response = super::handle_documents_request(bg)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::AddDocumentRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_add_document_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::AddSubtitlesRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_add_subtitles_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::SearchLinesRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_search_lines_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::DeleteDocumentRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_delete_document_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::PageRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_page_request(bg, request)?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::DocumentStatsRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_document_stats_request(bg, request).await?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::CoverageRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_coverage_request(bg, request).await?;
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::UpdateConfigRequest::KIND => {
+            let config = serde_json::from_value(request.body)?;
+            super::handle_update_config_request(bg, config).await?;
+            Ok(serde_json::Value::Null)
+        }
+        api::PreviewCaptureFiltersRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            let response = super::handle_preview_capture_filters_request(request);
+            Ok(serde_json::to_value(&response)?)
+        }
+        api::CorrectOcrRequest::KIND => {
+            let request = serde_json::from_value(request.body)?;
+            super::handle_correct_ocr_request(bg, request);
+            Ok(serde_json::Value::Null)
+        }
+        _ => Err(api::ApiError::new(api::ErrorKind::BadRequest, "Unsupported request").into()),
+    };
 
-        output.push(*b);
-    }
+    let (body, error) = match result {
+        Ok(value) => (value, None),
+        Err(error) => {
+            tracing::debug!(?error, "Request failed");
+            (
+                serde_json::Value::Null,
+                Some(api::ApiError::from_anyhow(&error).with_request_id(id)),
+            )
+        }
+    };
 
-    Cow::Owned(output)
+    send_response(sender, reply, body, error).await?;
+    Ok(())
 }
@@ -0,0 +1,17 @@
+use anyhow::{bail, Result};
+
+/// Stand-in for when the `whisper` feature isn't enabled.
+pub(crate) struct Whisper;
+
+impl Whisper {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+
+    pub(crate) fn transcribe<P>(&self, _: &str, _: &[f32], _: P) -> Result<String>
+    where
+        P: FnMut(i32) + 'static,
+    {
+        bail!("Transcribing audio is not supported, the `whisper` feature is not enabled")
+    }
+}
@@ -0,0 +1,11 @@
+//! Transcription of captured audio through whisper.cpp.
+
+#[cfg(feature = "whisper")]
+#[path = "real.rs"]
+mod r#impl;
+
+#[cfg(not(feature = "whisper"))]
+#[path = "fake.rs"]
+mod r#impl;
+
+pub(crate) use self::r#impl::Whisper;
@@ -0,0 +1,60 @@
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+/// Speech recognition, keeping the most recently used model loaded.
+pub(crate) struct Whisper {
+    model: Mutex<Option<(String, WhisperContext)>>,
+}
+
+impl Whisper {
+    pub(crate) fn new() -> Self {
+        Self {
+            model: Mutex::new(None),
+        }
+    }
+
+    /// Transcribe Japanese speech in mono samples at
+    /// [`lib::audio::SAMPLE_RATE`] with the model at the given path, calling
+    /// `progress` with how far along it is in percent.
+    ///
+    /// This blocks until done, so it's called from a blocking task.
+    pub(crate) fn transcribe<P>(&self, model: &str, samples: &[f32], progress: P) -> Result<String>
+    where
+        P: FnMut(i32) + 'static,
+    {
+        let mut loaded = self.model.lock();
+
+        let context = match &mut *loaded {
+            Some((path, context)) if path == model => context,
+            loaded => {
+                tracing::info!("Loading whisper model from {model}");
+
+                let context =
+                    WhisperContext::new_with_params(model, WhisperContextParameters::default())
+                        .with_context(|| format!("Loading whisper model from {model}"))?;
+
+                &mut loaded.insert((model.to_owned(), context)).1
+            }
+        };
+
+        let mut state = context.create_state().context("Creating whisper state")?;
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some("ja"));
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_progress_callback_safe(Some(progress));
+
+        state.full(params, samples).context("Transcribing audio")?;
+
+        let mut text = String::new();
+
+        for segment in 0..state.full_n_segments()? {
+            text.push_str(state.full_get_segment_text(segment)?.trim());
+        }
+
+        Ok(text)
+    }
+}
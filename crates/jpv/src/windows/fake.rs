@@ -1,7 +1,9 @@
+use std::path::Path;
+
 use anyhow::Result;
 
 use crate::system::Setup;
 
-pub fn setup() -> Result<Setup> {
+pub fn setup(_: Option<&Path>) -> Result<Setup> {
     Ok(Setup::Start(None))
 }
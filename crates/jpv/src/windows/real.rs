@@ -1,5 +1,6 @@
 use std::future::Future;
 use std::io::Cursor;
+use std::path::{Path, PathBuf};
 use std::pin::{pin, Pin};
 
 use anyhow::Result;
@@ -7,30 +8,39 @@ use async_fuse::Fuse;
 use tokio::sync::futures::Notified;
 use winctx::event::{ClipboardEvent, Event, MouseButton};
 
+use crate::install;
 use crate::open_uri;
 use crate::system::{self, Setup, Start, SystemEvents};
+use crate::web::BasePath;
 use crate::VERSION;
 
 const ICON: &[u8] = include_bytes!("../../res/jpv22.ico");
 const NAME: &str = "se.tedro.JapaneseDictionary";
 
 /// Setup system integration.
-pub(crate) fn setup() -> Result<Setup> {
+pub(crate) fn setup(data_dir: Option<&Path>) -> Result<Setup> {
     let Some(mutex) = winctx::NamedMutex::create_acquired(NAME)? else {
         return Ok(Setup::Busy);
     };
 
-    Ok(Setup::Start(Some(Box::new(Windows { _mutex: mutex }))))
+    Ok(Setup::Start(Some(Box::new(Windows {
+        _mutex: mutex,
+        data_dir: data_dir.map(Path::to_owned),
+    }))))
 }
 
 struct Windows {
     _mutex: winctx::NamedMutex,
+    /// The data directory the service uses, which it's started with when
+    /// logging in as well.
+    data_dir: Option<PathBuf>,
 }
 
 impl Start for Windows {
     fn start<'a>(
         &'a mut self,
         port: u16,
+        base: &'a BasePath,
         shutdown: Notified<'a>,
         system_events: &'a SystemEvents,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
@@ -46,6 +56,12 @@ impl Start for Windows {
             let open = menu
                 .push_entry(format_args!("Japanese Dictionary ({VERSION})"))
                 .id();
+
+            // Offered unless the service is already started when logging in,
+            // which is undone with `jpv service uninstall`.
+            let autostart =
+                (!install::is_installed()).then(|| menu.push_entry("Start when logging in").id());
+
             let exit = menu.push_entry("Quit").id();
 
             let (sender, mut event_loop) = window.build().await?;
@@ -70,17 +86,23 @@ impl Start for Windows {
                             },
                             Event::MenuItemClicked { item_id, .. } => {
                                 if item_id == open {
-                                    let address = format!("http://localhost:{port}");
+                                    let address = format!("http://localhost:{port}{}/", base.as_str());
                                     open_uri::open(&address);
                                 }
 
+                                if Some(item_id) == autostart {
+                                    if let Err(error) = install::install(self.data_dir.as_deref()) {
+                                        tracing::error!("Failed to start when logging in: {error}");
+                                    }
+                                }
+
                                 if item_id == exit {
                                     sender.shutdown();
                                 }
                             },
                             Event::IconClicked { event, .. } => {
                                 if event.buttons.test(MouseButton::Left) {
-                                    let address = format!("http://localhost:{port}");
+                                    let address = format!("http://localhost:{port}{}/", base.as_str());
                                     open_uri::open(&address);
                                 }
                             },
@@ -0,0 +1,43 @@
+//! Keyboard support for elements which act as buttons.
+//!
+//! Many clickable elements are spans rather than buttons so that they flow
+//! with the text they're part of. They're marked with `role="button"` and made
+//! focusable, and this makes them activate on Enter and Space like real
+//! buttons do.
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, KeyboardEvent};
+
+/// Install a document-wide listener activating focused elements with a
+/// button role when Enter or Space is pressed.
+pub(crate) fn install() {
+    let document = gloo::utils::document();
+
+    let listener = EventListener::new(&document, "keydown", |event| {
+        let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+            return;
+        };
+
+        if !matches!(event.key().as_str(), "Enter" | " ") {
+            return;
+        }
+
+        let Some(element) = event
+            .target()
+            .and_then(|target| target.dyn_into::<HtmlElement>().ok())
+        else {
+            return;
+        };
+
+        if element.get_attribute("role").as_deref() != Some("button") {
+            return;
+        }
+
+        // Prevent Space from scrolling the page.
+        event.prevent_default();
+        element.click();
+    });
+
+    listener.forget();
+}
@@ -0,0 +1,48 @@
+//! The path the application is served under.
+//!
+//! When the service runs behind a reverse proxy under a subpath it declares
+//! the path through a `<base href="...">` element, which the router picks up
+//! on its own. Addresses of the API are built through here so that they're
+//! prefixed the same way.
+
+use web_sys::window;
+
+/// The path the application is served under, without a trailing slash, like
+/// `/jpv`, or an empty string if it is served from the root.
+pub(crate) fn path() -> String {
+    let base = gloo::utils::document()
+        .query_selector("base")
+        .ok()
+        .flatten()
+        .and_then(|element| element.get_attribute("href"));
+
+    match base {
+        Some(base) => base.trim_end_matches('/').to_owned(),
+        None => String::new(),
+    }
+}
+
+/// An absolute path to the given path under the base path, used for links.
+pub(crate) fn href(path: &str) -> String {
+    format!("{}{path}", self::path())
+}
+
+/// The full address of the given path on the service, like
+/// `http://localhost:44714/jpv/api/tts`.
+pub(crate) fn url(path: &str) -> Option<String> {
+    let origin = window()?.location().origin().ok()?;
+    Some(format!("{origin}{}", href(path)))
+}
+
+/// The address of the websocket of the service.
+pub(crate) fn ws() -> Option<String> {
+    let location = window()?.location();
+    let host = location.host().ok()?;
+
+    let scheme = match location.protocol().ok()?.as_str() {
+        "https:" => "wss",
+        _ => "ws",
+    };
+
+    Some(format!("{scheme}://{host}{}", href("/ws")))
+}
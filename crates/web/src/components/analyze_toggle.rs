@@ -1,8 +1,32 @@
+use std::ops::Range;
 use std::rc::Rc;
 
+use gloo::timers::callback::Timeout;
+use lib::api;
+use lib::profile::WordStatus;
 use yew::prelude::*;
 
 use super::spacing;
+use crate::i18n::{t, Text};
+use crate::query::Interaction;
+
+/// Words at or above this difficulty level are highlighted as hard.
+const HARD_LEVEL: u8 = 4;
+/// How long a character has to be pressed to be analyzed, in milliseconds.
+const LONG_PRESS: u32 = 500;
+
+pub(crate) enum Msg {
+    /// A pointer was pressed on the character at the given offset.
+    Down(usize),
+    /// A pressed pointer moved over the character at the given offset.
+    Move(usize),
+    /// The pointer was released.
+    Up,
+    /// The pointer was cancelled or left the text.
+    Cancel,
+    /// The character at the given offset was pressed for long enough.
+    LongPress(usize),
+}
 
 #[derive(Properties, PartialEq)]
 pub(crate) struct Props {
@@ -11,24 +35,117 @@ pub(crate) struct Props {
     pub(crate) index: usize,
     #[prop_or_default]
     pub(crate) analyze_at: Option<usize>,
+    /// Difficulty of words in the query, used to highlight hard words.
+    #[prop_or_default]
+    pub(crate) difficulty: Rc<[api::WordDifficulty]>,
+    /// Status of words in the query, used to highlight words by how well they
+    /// are known.
+    #[prop_or_default]
+    pub(crate) word_status: Rc<[api::WordStatusSpan]>,
+    /// How characters are selected for analysis.
+    #[prop_or_default]
+    pub(crate) interaction: Interaction,
     pub(crate) on_analyze: Callback<usize>,
     pub(crate) on_analyze_cycle: Callback<()>,
+    /// Called when an explicit range of the query is selected for analysis.
+    #[prop_or_default]
+    pub(crate) on_analyze_span: Callback<Range<usize>>,
+    /// Called when the status of a word is changed.
+    #[prop_or_default]
+    pub(crate) on_word_status: Callback<(u64, WordStatus)>,
 }
 
-pub(crate) struct AnalyzeToggle;
+pub(crate) struct AnalyzeToggle {
+    /// Pending long press, and the offset of the pressed character.
+    press: Option<(usize, Timeout)>,
+    /// Offsets of the first and last characters in the selection being
+    /// dragged.
+    selection: Option<(usize, usize)>,
+}
 
 impl Component for AnalyzeToggle {
-    type Message = ();
+    type Message = Msg;
     type Properties = Props;
 
     fn create(_: &Context<Self>) -> Self {
-        Self
+        Self {
+            press: None,
+            selection: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Down(i) => match ctx.props().interaction {
+                Interaction::Click => false,
+                Interaction::LongPress => {
+                    let link = ctx.link().clone();
+                    let timeout = Timeout::new(LONG_PRESS, move || {
+                        link.send_message(Msg::LongPress(i));
+                    });
+                    self.press = Some((i, timeout));
+                    false
+                }
+                Interaction::Select => {
+                    self.selection = Some((i, i));
+                    true
+                }
+            },
+            Msg::Move(i) => {
+                if matches!(self.press, Some((start, _)) if start != i) {
+                    self.press = None;
+                }
+
+                match &mut self.selection {
+                    Some((_, end)) if *end != i => {
+                        *end = i;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Msg::Up => {
+                // A short tap on the analyzed word still cycles through it.
+                if let Some((i, _)) = self.press.take() {
+                    if is_cycle(ctx.props(), i) {
+                        ctx.props().on_analyze_cycle.emit(());
+                    }
+                }
+
+                let Some((a, b)) = self.selection.take() else {
+                    return false;
+                };
+
+                if a == b {
+                    activate(ctx.props(), a);
+                } else {
+                    let (start, last) = (a.min(b), a.max(b));
+                    let query = &ctx.props().query;
+                    let len = query[last..].chars().next().map_or(0, char::len_utf8);
+                    ctx.props().on_analyze_span.emit(start..last + len);
+                }
+
+                true
+            }
+            Msg::Cancel => {
+                self.press = None;
+                self.selection.take().is_some()
+            }
+            Msg::LongPress(i) => {
+                self.press = None;
+                activate(ctx.props(), i);
+                false
+            }
+        }
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let mut rem = 0usize;
 
         let string = ctx.props().analyzed.get(ctx.props().index);
+        let interaction = ctx.props().interaction;
+
+        let selected = self.selection.map(|(a, b)| a.min(b)..=a.max(b));
 
         let query = ctx.props().query.char_indices().map(|(i, c)| {
             let sub = ctx.props().query.get(i..).unwrap_or_default();
@@ -44,44 +161,185 @@ impl Component for AnalyzeToggle {
                 Some(i)
             };
 
-            let onclick = match event {
+            let onclick = (interaction == Interaction::Click).then(|| match event {
                 Some(i) => ctx.props().on_analyze.reform(move |_| i),
                 None => ctx.props().on_analyze_cycle.reform(|_| ()),
-            };
+            });
+
+            let hard = ctx
+                .props()
+                .difficulty
+                .iter()
+                .any(|w| w.range.contains(&i) && w.difficulty.level >= HARD_LEVEL);
+
+            let status = ctx
+                .props()
+                .word_status
+                .iter()
+                .find(|w| w.range.contains(&i))
+                .map(|w| format!("status-{}", w.status.ident()));
 
             let class = classes! {
                 (rem > 0).then_some("active"),
+                hard.then_some("hard"),
+                status,
                 (!(event.is_none() && ctx.props().analyzed.len() <= 1)).then_some("clickable"),
+                selected.as_ref().filter(|s| s.contains(&i)).map(|_| "selected"),
                 "analyze-span"
             };
 
             rem = rem.saturating_sub(1);
-            html!(<span {class} {onclick}>{c}</span>)
+            html!(<span {class} {onclick} data-offset={i.to_string()}>{c}</span>)
         });
 
+        let (onpointerdown, onpointermove, onpointerup, oncancel, oncontextmenu) =
+            if interaction == Interaction::Click {
+                (None, None, None, None, None)
+            } else {
+                (
+                    Some(ctx.link().batch_callback(|e: PointerEvent| {
+                        if e.button() != 0 {
+                            return None;
+                        }
+
+                        Some(Msg::Down(offset_at(&e)?))
+                    })),
+                    Some(
+                        ctx.link()
+                            .batch_callback(|e: PointerEvent| Some(Msg::Move(offset_at(&e)?))),
+                    ),
+                    Some(ctx.link().callback(|_: PointerEvent| Msg::Up)),
+                    Some(ctx.link().callback(|_: PointerEvent| Msg::Cancel)),
+                    Some(Callback::from(|e: MouseEvent| e.prevent_default())),
+                )
+            };
+
+        let text_class = classes! {
+            "block",
+            "row",
+            "analyze-text",
+            format!("interaction-{}", interaction.ident()),
+        };
+
         let analyze_hint = if ctx.props().analyzed.len() > 1 {
             Some(html! {
                 <div class="block row hint">
-                    {format!("{} / {} (click character to cycle)", ctx.props().index + 1, ctx.props().analyzed.len())}
+                    {format!("{} / {} {}", ctx.props().index + 1, ctx.props().analyzed.len(), t(Text::CycleHint))}
                 </div>
             })
         } else if ctx.props().analyzed.is_empty() {
             Some(html! {
                 <div class="block row hint">
-                    <span>{"Hint:"}</span>
+                    <span>{t(Text::Hint)}</span>
                     {spacing()}
-                    <span>{"Click character for substring search"}</span>
+                    <span>{hint(interaction)}</span>
                 </div>
             })
         } else {
             None
         };
 
+        let word_status = ctx.props().analyze_at.and_then(|at| {
+            let word = ctx
+                .props()
+                .word_status
+                .iter()
+                .find(|w| w.range.contains(&at))?;
+
+            let text = ctx.props().query.get(word.range.clone())?;
+
+            let buttons = WordStatus::ALL.into_iter().map(|status| {
+                let sequence = word.sequence;
+                let onclick = ctx
+                    .props()
+                    .on_word_status
+                    .reform(move |_| (sequence, status));
+
+                let class = classes! {
+                    "btn",
+                    "btn-sm",
+                    format!("status-{}", status.ident()),
+                    (status == word.status).then_some("active"),
+                };
+
+                html!(<button {class} {onclick}>{status.title()}</button>)
+            });
+
+            Some(html! {
+                <div class="block row row-spaced word-status">
+                    <span>{text.to_owned()}</span>
+                    {for buttons}
+                </div>
+            })
+        });
+
         html! {
             <div id="analyze">
-                <div class="block row analyze-text">{for query}</div>
+                <div class={text_class} {onpointerdown} {onpointermove} {onpointerup} onpointercancel={oncancel.clone()} onpointerleave={oncancel} {oncontextmenu}>{for query}</div>
+                {word_status}
                 {analyze_hint}
             </div>
         }
     }
 }
+
+/// Test if activating the character at the given offset cycles through the
+/// analyzed words rather than analyzing from it.
+fn is_cycle(props: &Props, i: usize) -> bool {
+    let (Some(analyze_at), Some(string)) = (props.analyze_at, props.analyzed.get(props.index))
+    else {
+        return false;
+    };
+
+    i == analyze_at
+        && props
+            .query
+            .get(i..)
+            .is_some_and(|sub| sub.starts_with(string.as_ref()))
+}
+
+/// Activate the character at the given offset, like clicking it would.
+fn activate(props: &Props, i: usize) {
+    if is_cycle(props, i) {
+        props.on_analyze_cycle.emit(());
+    } else {
+        props.on_analyze.emit(i);
+    }
+}
+
+/// Find the offset of the character under the pointer.
+///
+/// Since touch pointers are captured by the element they were first pressed
+/// on, the event target can't be used.
+fn offset_at(e: &PointerEvent) -> Option<usize> {
+    let element =
+        gloo::utils::document().element_from_point(e.client_x() as f32, e.client_y() as f32)?;
+    element.get_attribute("data-offset")?.parse().ok()
+}
+
+fn hint(interaction: Interaction) -> &'static str {
+    match interaction {
+        Interaction::Click => t(Text::ClickHint),
+        Interaction::LongPress => t(Text::LongPressHint),
+        Interaction::Select => t(Text::SelectHint),
+    }
+}
+
+/// Update the status of every occurrence of a word.
+pub(crate) fn set_word_status(
+    words: &[api::WordStatusSpan],
+    sequence: u64,
+    status: WordStatus,
+) -> Rc<[api::WordStatusSpan]> {
+    words
+        .iter()
+        .map(|w| api::WordStatusSpan {
+            status: if w.sequence == sequence {
+                status
+            } else {
+                w.status
+            },
+            ..w.clone()
+        })
+        .collect()
+}
@@ -1,27 +1,133 @@
+use lib::api;
+use lib::ids;
+use lib::jmdict::OwnedEntry;
 use lib::kanjidic2::OwnedCharacter;
 use yew::prelude::*;
 
+use crate::base;
+use crate::error::Error;
+use crate::i18n::{self, t, Text};
+use crate::ws;
+
 use super::{colon, comma, romaji, ruby, seq};
 
 const ONYOMI: lib::Furigana<'static, 1, 1> = lib::Furigana::new("音読み", "おんよみ", "");
 const KUNYOMI: lib::Furigana<'static, 1, 1> = lib::Furigana::new("訓読み", "くんよみ", "");
 
-pub enum Msg {}
+/// The number of phrases using the character loaded at a time.
+const WORDS_LIMIT: usize = 10;
+
+pub enum Msg {
+    LoadComponents,
+    Components(api::KanjiComponentsResponse),
+    LoadWords,
+    Words(api::OwnedKanjiWordsResponse),
+    Change(String),
+    Error(Error),
+}
 
-#[derive(Properties, PartialEq)]
+#[derive(Properties)]
 pub struct Props {
+    pub ws: ws::Handle,
     pub embed: bool,
     pub character: OwnedCharacter,
+    pub onchange: Callback<(String, Option<String>), ()>,
+}
+
+impl PartialEq for Props {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.embed == other.embed && self.character == other.character
+    }
 }
 
-pub(crate) struct Character;
+pub(crate) struct Character {
+    /// The loaded component tree, where `Some(None)` means that the character
+    /// has no known decomposition.
+    components: Option<Option<ids::Component>>,
+    pending_components: ws::Request,
+    /// Loaded phrases written using the character, the most common first.
+    words: Option<Vec<OwnedEntry>>,
+    /// Whether there are more phrases to load.
+    more_words: bool,
+    pending_words: ws::Request,
+}
 
 impl Component for Character {
     type Message = Msg;
     type Properties = Props;
 
     fn create(_: &Context<Self>) -> Self {
-        Self
+        Self {
+            components: None,
+            pending_components: ws::Request::empty(),
+            words: None,
+            more_words: false,
+            pending_words: ws::Request::empty(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::LoadComponents => {
+                self.pending_components = ctx.props().ws.request(
+                    api::KanjiComponentsRequest {
+                        literal: ctx.props().character.literal.clone(),
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Components(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::Components(response) => {
+                self.pending_components = ws::Request::empty();
+                self.components = Some(response.components);
+                true
+            }
+            Msg::LoadWords => {
+                self.pending_words = ctx.props().ws.request(
+                    api::KanjiWordsRequest {
+                        literal: ctx.props().character.literal.clone(),
+                        limit: WORDS_LIMIT,
+                        offset: self.words.as_ref().map_or(0, Vec::len),
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Words(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::Words(response) => {
+                self.pending_words = ws::Request::empty();
+                self.words
+                    .get_or_insert_with(Vec::new)
+                    .extend(response.words);
+                self.more_words = response.more;
+                true
+            }
+            Msg::Change(text) => {
+                ctx.props().onchange.emit((text, None));
+                false
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                false
+            }
+        }
+    }
+
+    fn changed(&mut self, _: &Context<Self>, _: &Self::Properties) -> bool {
+        self.components = None;
+        self.pending_components = ws::Request::empty();
+        self.words = None;
+        self.more_words = false;
+        self.pending_words = ws::Request::empty();
+        true
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
@@ -59,7 +165,7 @@ impl Component for Character {
         let kunyomi = kunyomi
             .peek()
             .is_some()
-            .then(move || html!(<div class="readings row row-bottom"><span class="highlight clickable" title={romaji(ONYOMI)}>{ruby(KUNYOMI)}</span>{colon()}{for kunyomi}</div>));
+            .then(move || html!(<div class="readings row row-bottom"><span class="highlight clickable" title={romaji(KUNYOMI)}>{ruby(KUNYOMI)}</span>{colon()}{for kunyomi}</div>));
 
         let mut meanings = seq(
             c.reading_meaning
@@ -68,7 +174,7 @@ impl Component for Character {
                 .filter(|r| r.lang.is_none()),
             |r, not_last| {
                 let sep = not_last.then(comma);
-                html!(<>{r.text.clone()}{for sep}</>)
+                html!(<><span class="meaning">{r.text.clone()}</span>{for sep}</>)
             },
         )
         .peekable();
@@ -78,13 +184,154 @@ impl Component for Character {
             .is_some()
             .then(move || html!(<div class="readings row">{for meanings}</div>));
 
+        let components = (!ctx.props().embed).then(|| self.render_components(ctx));
+        let words = (!ctx.props().embed).then(|| self.render_words(ctx));
+
+        let mut metadata = Vec::new();
+
+        if let Some(grade) = c.misc.grade {
+            metadata.push(format!("{} {grade}", t(Text::Grade)));
+        }
+
+        if let Some(jlpt) = c.misc.jlpt {
+            metadata.push(format!("JLPT {jlpt} {}", t(Text::OldJlpt)));
+        }
+
+        if let Some(freq) = c.misc.freq {
+            metadata.push(format!("{} #{freq}", t(Text::Frequency)));
+        }
+
+        if let Some(strokes) = c.misc.stroke_count {
+            metadata.push(i18n::strokes(strokes.into()));
+        }
+
+        let metadata = (!metadata.is_empty()).then(|| {
+            let metadata = seq(metadata, |text, not_last| {
+                let sep = not_last.then(comma);
+                html!(<><span>{text}</span>{for sep}</>)
+            });
+
+            html!(<div class="row character-metadata">{for metadata}</div>)
+        });
+
         html! {
-            <div class="character">
-                <div class="literal text highlight"><a href={format!("/api/kanji/{}", c.literal)} target="_api">{c.literal.clone()}</a></div>
+            <div class="character" role="article" aria-label={c.literal.clone()}>
+                <div class="literal text highlight" lang="ja"><a href={base::href(&format!("/api/kanji/{}", c.literal))} target="_api">{c.literal.clone()}</a></div>
+                {for metadata}
                 {for meanings}
                 {for onyomi}
                 {for kunyomi}
+                {for components}
+                {for words}
             </div>
         }
     }
 }
+
+impl Character {
+    fn render_components(&self, ctx: &Context<Self>) -> Html {
+        let Some(components) = &self.components else {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::LoadComponents);
+            return html!(<div class="row"><span class="clickable" role="button" tabindex="0" {onclick}>{t(Text::Components)}</span></div>);
+        };
+
+        let Some(component) = components else {
+            return html!(<div class="row"><span>{t(Text::NoComponents)}</span></div>);
+        };
+
+        html!(<ul class="components">{render_component(ctx, component)}</ul>)
+    }
+
+    fn render_words(&self, ctx: &Context<Self>) -> Html {
+        let Some(words) = &self.words else {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::LoadWords);
+            return html!(<div class="row"><span class="clickable" role="button" tabindex="0" {onclick}>{t(Text::CommonWords)}</span></div>);
+        };
+
+        if words.is_empty() {
+            return html!(<div class="row"><span>{t(Text::NoCommonWords)}</span></div>);
+        }
+
+        let literal = ctx.props().character.literal.as_str();
+
+        let words = words.iter().map(|entry| {
+            // Prefer the form which uses the character.
+            let text = entry
+                .kanji_elements
+                .iter()
+                .find(|k| k.text.contains(literal))
+                .or_else(|| entry.kanji_elements.first())
+                .map(|k| k.text.clone())
+                .unwrap_or_default();
+
+            let reading = entry.reading_elements.first().map(|r| r.text.clone());
+
+            let meaning = entry
+                .senses
+                .first()
+                .and_then(|s| s.gloss.first())
+                .map(|g| g.text.clone());
+
+            let onclick = ctx.link().callback({
+                let text = text.clone();
+                move |_: MouseEvent| Msg::Change(text.clone())
+            });
+
+            html! {
+                <li>
+                    <span class="text clickable" lang="ja" role="button" tabindex="0" {onclick}>{text}</span>
+                    {for reading.map(|reading| html!(<span class="kanji-word-reading" lang="ja">{reading}</span>))}
+                    {for meaning.map(|meaning| html!(<span class="kanji-word-meaning">{meaning}</span>))}
+                </li>
+            }
+        });
+
+        let more = self.more_words.then(|| {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::LoadWords);
+            html!(<li><span class="clickable" role="button" tabindex="0" {onclick}>{t(Text::ShowMore)}</span></li>)
+        });
+
+        html! {
+            <div class="row">
+                <span>{t(Text::CommonWords)}</span>{colon()}
+                <ul class="kanji-words">{for words}{for more}</ul>
+            </div>
+        }
+    }
+}
+
+fn render_component(ctx: &Context<Character>, component: &ids::Component) -> Html {
+    let operator = component
+        .operator
+        .map(|operator| html!(<span class="component-operator">{operator}</span>));
+
+    // Unencoded components like `{01}` can't be searched for.
+    let text = (!component.text.is_empty()).then(|| {
+        if component.text.chars().count() == 1 {
+            let onclick = ctx.link().callback({
+                let text = component.text.clone();
+                move |_: MouseEvent| Msg::Change(text.clone())
+            });
+
+            html!(<span class="component-text clickable" role="button" tabindex="0" {onclick}>{component.text.clone()}</span>)
+        } else {
+            html!(<span class="component-text">{component.text.clone()}</span>)
+        }
+    });
+
+    let parts = (!component.parts.is_empty()).then(|| {
+        let parts = component
+            .parts
+            .iter()
+            .map(|part| render_component(ctx, part));
+        html!(<ul class="components">{for parts}</ul>)
+    });
+
+    html! {
+        <li>
+            {for text}
+            {for operator}
+            {for parts}
+        </li>
+    }
+}
@@ -0,0 +1,351 @@
+use gloo::file::{Blob, ObjectUrl};
+use lib::api;
+use lib::difficulty;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::base;
+use crate::error::Error;
+use crate::i18n::{t, Text};
+use crate::pinned::Pinned;
+use crate::ws;
+use crate::Route;
+
+use super::entry::render_priority;
+
+pub(crate) enum Msg {
+    Entries(api::OwnedEntriesResponse),
+    Unpin(u32),
+    Clear,
+    Export,
+    Exported(Vec<u8>),
+    Error(Error),
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+}
+
+pub(crate) struct Compare {
+    pinned: Pinned,
+    /// Pinned phrases, once they have been loaded.
+    entries: Option<Vec<api::OwnedComparedEntry>>,
+    pending: ws::Request,
+    /// Whether pinned phrases are being exported.
+    exporting: bool,
+    /// The last exported document.
+    export: Option<ObjectUrl>,
+}
+
+impl Component for Compare {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let pinned = Pinned::load();
+
+        let pending = ctx.props().ws.request(
+            api::EntriesRequest {
+                sequences: pinned.sequences.clone(),
+            },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::Entries(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+
+        Self {
+            pinned,
+            entries: None,
+            pending,
+            exporting: false,
+            export: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Entries(response) => {
+                self.pending = ws::Request::empty();
+                self.entries = Some(response.entries);
+            }
+            Msg::Unpin(sequence) => {
+                self.pinned = Pinned::load();
+                self.pinned.toggle(sequence);
+
+                if let Some(entries) = &mut self.entries {
+                    entries.retain(|e| e.entry.sequence as u32 != sequence);
+                }
+            }
+            Msg::Clear => {
+                self.pinned.clear();
+                self.entries = Some(Vec::new());
+                self.export = None;
+            }
+            Msg::Export => {
+                let link = ctx.link().clone();
+                self.exporting = true;
+
+                let request = api::ExportPdfRequest {
+                    sequences: self.pinned.sequences.clone(),
+                    ..api::ExportPdfRequest::default()
+                };
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let msg = match export_pdf(&request).await {
+                        Ok(data) => Msg::Exported(data),
+                        Err(error) => Msg::Error(error),
+                    };
+
+                    link.send_message(msg);
+                });
+            }
+            Msg::Exported(data) => {
+                self.exporting = false;
+                let blob = Blob::new_with_options(data.as_slice(), Some("application/pdf"));
+                self.export = Some(ObjectUrl::from(blob));
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.exporting = false;
+
+                if self.entries.is_none() {
+                    self.entries = Some(Vec::new());
+                }
+            }
+        }
+
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let clear = (self.pinned.len() > 0).then(|| {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::Clear);
+            html!(<button class="btn" {onclick} title="Unpin every phrase">{t(Text::Clear)}</button>)
+        });
+
+        let export = (self.pinned.len() > 0).then(|| {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::Export);
+
+            html! {
+                <button class="btn" {onclick} disabled={self.exporting} title="Export pinned phrases as a printable PDF document">{t(Text::ExportPdf)}</button>
+            }
+        });
+
+        let download = self.export.as_ref().map(|url| {
+            html! {
+                <a class="btn" href={url.to_string()} target="_blank" download="jpv-vocabulary.pdf">{t(Text::OpenPdf)}</a>
+            }
+        });
+
+        html! {
+            <>
+                <div id="window-top">
+                    <div class="container">
+                        <span class="left">
+                            <Link<Route> classes="clickable" to={Route::Prompt}>{"🔍"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Reader}>{"📖"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Quiz}>{"✍"}</Link<Route>>
+                        </span>
+                        <span></span>
+                        <span class="title">{t(Text::Compare)}</span>
+                        <span></span>
+                        <span class="right">{for download}{for export}{for clear}</span>
+                    </div>
+                </div>
+
+                <div id="content" class="container">
+                    {self.view_entries(ctx)}
+                </div>
+            </>
+        }
+    }
+}
+
+impl Compare {
+    fn view_entries(&self, ctx: &Context<Self>) -> Html {
+        let Some(entries) = &self.entries else {
+            return html!(<div class="block row"><div class="spinner">{t(Text::Loading)}</div></div>);
+        };
+
+        if entries.is_empty() {
+            return html! {
+                <div class="block row">
+                    {t(Text::NothingToCompare)}
+                </div>
+            };
+        }
+
+        let senses = entries
+            .iter()
+            .map(|e| e.entry.senses.len())
+            .max()
+            .unwrap_or_default();
+
+        let headings = entries.iter().map(|e| render_heading(ctx, e));
+        let overviews = entries.iter().map(render_overview);
+
+        let senses = (0..senses).map(|index| {
+            let cells = entries.iter().map(move |e| render_sense(e, index));
+            html!(<>{for cells}</>)
+        });
+
+        let style = format!(
+            "grid-template-columns: repeat({}, minmax(0, 1fr));",
+            entries.len()
+        );
+
+        html! {
+            <div class="block block-lg compare" {style}>
+                {for headings}
+                {for overviews}
+                {for senses}
+            </div>
+        }
+    }
+}
+
+/// The phrase being compared, as it's usually written.
+fn render_heading(ctx: &Context<Compare>, e: &api::OwnedComparedEntry) -> Html {
+    let entry = &e.entry;
+    let reading = entry.reading_elements.first().map(|r| r.text.as_str());
+
+    let (text, reading) = match entry.kanji_elements.first() {
+        Some(kanji) => (kanji.text.as_str(), reading),
+        None => (reading.unwrap_or_default(), None),
+    };
+
+    let href = serde_urlencoded::to_string([("q", text)])
+        .ok()
+        .map(|query| format!("/?{query}"));
+
+    let reading = reading.map(|reading| html!(<span class="compare-reading">{reading}</span>));
+
+    let sequence = entry.sequence as u32;
+    let onclick = ctx
+        .link()
+        .callback(move |_: MouseEvent| Msg::Unpin(sequence));
+
+    html! {
+        <div class="compare-cell compare-heading">
+            <a class="text kanji highlight" {href}>{text}</a>
+            {for reading}
+            <span class="clickable compare-unpin" title="Stop comparing this phrase" {onclick}>{t(Text::Unpin)}</span>
+        </div>
+    }
+}
+
+/// Priority tags and difficulty, which indicate how frequently the phrase is
+/// used.
+fn render_overview(e: &api::OwnedComparedEntry) -> Html {
+    let entry = &e.entry;
+
+    let mut priority = entry
+        .kanji_elements
+        .iter()
+        .take(1)
+        .flat_map(|k| k.priority.iter())
+        .chain(
+            entry
+                .reading_elements
+                .iter()
+                .take(1)
+                .flat_map(|r| r.priority.iter()),
+        )
+        .collect::<Vec<_>>();
+
+    priority.dedup();
+
+    let difficulty = e.difficulty.map(|d| {
+        let class = classes!("difficulty", format!("difficulty-{}", d.level));
+        let title = format!("Estimated difficulty {:.0}%", d.score * 100.0);
+        html!(<span {class} {title}>{format!("Difficulty {} / {}", d.level, difficulty::MAX_LEVEL)}</span>)
+    });
+
+    html! {
+        <div class="compare-cell">
+            <span class="bullets">{for priority.into_iter().map(render_priority)}</span>
+            {for difficulty}
+        </div>
+    }
+}
+
+/// The sense with the given index, so that senses line up between phrases.
+fn render_sense(e: &api::OwnedComparedEntry, index: usize) -> Html {
+    let Some(sense) = e.entry.senses.get(index) else {
+        return html!(<div class="compare-cell" />);
+    };
+
+    let pos = sense
+        .pos
+        .iter()
+        .map(|p| html!(<span class="bullet sm pos" title={p.help()}>{p.ident()}</span>));
+    let misc = sense
+        .misc
+        .iter()
+        .map(|m| html!(<span class="bullet sm misc" title={m.help()}>{m.ident()}</span>));
+    let field = sense
+        .field
+        .iter()
+        .map(|f| html!(<span class="bullet sm field" title={f.help()}>{f.ident()}</span>));
+
+    let gloss = sense
+        .gloss
+        .iter()
+        .map(|g| g.text.as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let example = e
+        .sense_examples
+        .iter()
+        .find(|s| s.sense == index)
+        .and_then(|s| s.examples.first())
+        .map(|example| {
+            html! {
+                <div class="compare-example">
+                    <div>{example.text.clone()}</div>
+                    <div class="compare-translation">{example.translation.clone()}</div>
+                </div>
+            }
+        });
+
+    html! {
+        <div class="compare-cell">
+            <div class="compare-gloss">
+                <span class="compare-index">{format!("{}.", index + 1)}</span>
+                {gloss}
+            </div>
+            <div class="bullets">{for pos}{for misc}{for field}</div>
+            {for example}
+        </div>
+    }
+}
+
+/// Export phrases as a PDF document.
+async fn export_pdf(request: &api::ExportPdfRequest) -> Result<Vec<u8>, Error> {
+    let url = base::url("/api/export/pdf").ok_or("Missing window location")?;
+
+    let request = gloo::net::http::Request::post(&url)
+        .header(
+            api::compat::HEADER,
+            &api::compat::PROTOCOL_VERSION.to_string(),
+        )
+        .json(request)
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    let response = request
+        .send()
+        .await
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    if !response.ok() {
+        return Err(Error::from_response(&response).await);
+    }
+
+    response
+        .binary()
+        .await
+        .map_err(|error| anyhow::Error::msg(error.to_string()).into())
+}
@@ -1,16 +1,47 @@
 use std::collections::HashSet;
 
 use lib::api;
-use lib::config::ConfigIndex;
+use lib::config::{
+    AnkiPart, CaptureFilter, ConfigIndex, OcrEngine, Tokenizer, Transcription, Tts,
+    ANKI_CONNECT_URL,
+};
 use yew::prelude::*;
 
+use crate::display::{Display, Metadata};
 use crate::error::Error;
+use crate::i18n::{self, t, Language, Text};
 use crate::{c, ws};
 
 pub(crate) enum Msg {
-    GetConfig(api::GetConfigResult),
+    GetConfig(Box<api::GetConfigResult>),
     Toggle(String),
     ToggleOcr,
+    ToggleOpenBrowser,
+    ToggleCheckUpdates,
+    ToggleMetadata(Metadata),
+    Language(Language),
+    SyncDir(String),
+    TranscriptionModel(String),
+    TogglePlainReadings,
+    ToggleRevealTranslations,
+    ToggleStats,
+    ToggleAnki,
+    AnkiUrl(String),
+    AnkiDeck(String),
+    AnkiModel(String),
+    AnkiField(AnkiPart, String),
+    AnkiTags(String),
+    AddCaptureFilter,
+    RemoveCaptureFilter(usize),
+    ToggleCaptureFilter(usize),
+    ToggleCaptureFilterDrop(usize),
+    CaptureFilterName(usize, String),
+    CaptureFilterPattern(usize, String),
+    CaptureFilterReplace(usize, String),
+    CaptureSample(String),
+    IgnoredSources(String),
+    CapturePreview(api::PreviewCaptureFiltersResponse),
+    StatsRetention(String),
     IndexAdd,
     IndexAddSave(String, ConfigIndex),
     IndexAddCancel,
@@ -49,6 +80,22 @@ pub(crate) struct Config {
     missing_ocr: Option<api::MissingOcr>,
     edit_index: HashSet<String>,
     index_add: bool,
+    display: Display,
+    /// The text-to-speech command line being edited.
+    tts_command: String,
+    /// The tokenizer command line being edited.
+    tokenizer_command: String,
+    /// The OCR engine command line being edited.
+    ocr_command: String,
+    /// The tags added to Anki notes being edited.
+    anki_tags: String,
+    /// The sources whose captures are ignored being edited.
+    ignored_sources: String,
+    /// Text capture filters are tried on.
+    capture_sample: String,
+    /// What the capture filters do to the sample.
+    capture_preview: Option<api::PreviewCaptureFiltersResponse>,
+    preview_request: ws::Request,
     request: ws::Request,
 }
 
@@ -60,7 +107,7 @@ impl Component for Config {
         let request = ctx.props().ws.request(
             api::GetConfig,
             ctx.link().callback(|result| match result {
-                Ok(config) => Msg::GetConfig(config),
+                Ok(config) => Msg::GetConfig(Box::new(config)),
                 Err(error) => Msg::Error(error),
             }),
         );
@@ -72,6 +119,15 @@ impl Component for Config {
             missing_ocr: None,
             edit_index: HashSet::new(),
             index_add: false,
+            display: Display::load(),
+            tts_command: String::new(),
+            tokenizer_command: String::new(),
+            ocr_command: String::new(),
+            anki_tags: String::new(),
+            ignored_sources: String::new(),
+            capture_sample: String::new(),
+            capture_preview: None,
+            preview_request: ws::Request::empty(),
             request,
         }
     }
@@ -79,6 +135,30 @@ impl Component for Config {
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Msg::GetConfig(result) => {
+                self.tts_command = result
+                    .config
+                    .tts
+                    .as_ref()
+                    .map(Tts::command_line)
+                    .unwrap_or_default();
+
+                self.tokenizer_command = result
+                    .config
+                    .tokenizer
+                    .as_ref()
+                    .map(Tokenizer::command_line)
+                    .unwrap_or_default();
+
+                self.ocr_command = result
+                    .config
+                    .ocr_engine
+                    .as_ref()
+                    .map(OcrEngine::command_line)
+                    .unwrap_or_default();
+
+                self.anki_tags = result.config.anki.tags.join(" ");
+                self.ignored_sources = result.config.ignored_sources.join(", ");
+
                 self.state = Some(State {
                     remote: result.config.clone(),
                     local: result.config,
@@ -98,6 +178,159 @@ impl Component for Config {
                     state.local.ocr = !state.local.ocr;
                 }
             }
+            Msg::ToggleOpenBrowser => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.open_browser = !state.local.open_browser;
+                }
+            }
+            Msg::ToggleCheckUpdates => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.check_updates = !state.local.check_updates;
+                }
+            }
+            Msg::ToggleMetadata(metadata) => {
+                self.display.toggle(metadata);
+            }
+            Msg::ToggleRevealTranslations => {
+                self.display.toggle_reveal_translations();
+            }
+            Msg::TogglePlainReadings => {
+                self.display.toggle_plain_readings();
+            }
+            Msg::Language(language) => {
+                i18n::set_language(language);
+            }
+            Msg::SyncDir(dir) => {
+                if let Some(state) = self.state.as_mut() {
+                    let dir = dir.trim();
+                    state.local.sync_dir = (!dir.is_empty()).then(|| dir.to_owned());
+                }
+            }
+            Msg::TranscriptionModel(model) => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.transcription =
+                        (!model.trim().is_empty()).then_some(Transcription { model });
+                }
+            }
+            Msg::ToggleStats => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.stats.enabled = !state.local.stats.enabled;
+                }
+            }
+            Msg::StatsRetention(retention) => {
+                if let (Some(state), Ok(retention)) =
+                    (self.state.as_mut(), retention.trim().parse())
+                {
+                    state.local.stats.retention = retention;
+                }
+            }
+            Msg::ToggleAnki => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.anki.enabled = !state.local.anki.enabled;
+                }
+            }
+            Msg::AnkiUrl(url) => {
+                if let Some(state) = self.state.as_mut() {
+                    let url = url.trim();
+
+                    state.local.anki.url = if url.is_empty() {
+                        ANKI_CONNECT_URL.to_owned()
+                    } else {
+                        url.to_owned()
+                    };
+                }
+            }
+            Msg::AnkiDeck(deck) => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.anki.deck = deck;
+                }
+            }
+            Msg::AnkiModel(model) => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.anki.model = model;
+                }
+            }
+            Msg::AnkiField(part, field) => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.anki.fields.insert(part, field);
+                }
+            }
+            Msg::AnkiTags(tags) => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.anki.tags = tags.split_whitespace().map(str::to_owned).collect();
+                }
+
+                self.anki_tags = tags;
+            }
+            Msg::AddCaptureFilter => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.capture_filters.push(CaptureFilter::default());
+                }
+            }
+            Msg::RemoveCaptureFilter(index) => {
+                if let Some(state) = self.state.as_mut() {
+                    if index < state.local.capture_filters.len() {
+                        state.local.capture_filters.remove(index);
+                    }
+                }
+
+                self.preview_capture_filters(ctx);
+            }
+            Msg::ToggleCaptureFilter(index) => {
+                if let Some(filter) = self.capture_filter(index) {
+                    filter.enabled = !filter.enabled;
+                }
+
+                self.preview_capture_filters(ctx);
+            }
+            Msg::ToggleCaptureFilterDrop(index) => {
+                if let Some(filter) = self.capture_filter(index) {
+                    filter.replace = match filter.replace {
+                        Some(..) => None,
+                        None => Some(String::new()),
+                    };
+                }
+
+                self.preview_capture_filters(ctx);
+            }
+            Msg::CaptureFilterName(index, name) => {
+                if let Some(filter) = self.capture_filter(index) {
+                    filter.name = name;
+                }
+            }
+            Msg::CaptureFilterPattern(index, pattern) => {
+                if let Some(filter) = self.capture_filter(index) {
+                    filter.pattern = pattern;
+                }
+
+                self.preview_capture_filters(ctx);
+            }
+            Msg::CaptureFilterReplace(index, replace) => {
+                if let Some(filter) = self.capture_filter(index) {
+                    filter.replace = Some(replace);
+                }
+
+                self.preview_capture_filters(ctx);
+            }
+            Msg::CaptureSample(text) => {
+                self.capture_sample = text;
+                self.preview_capture_filters(ctx);
+            }
+            Msg::IgnoredSources(sources) => {
+                if let Some(state) = self.state.as_mut() {
+                    state.local.ignored_sources = sources
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|source| !source.is_empty())
+                        .map(str::to_owned)
+                        .collect();
+                }
+
+                self.ignored_sources = sources;
+            }
+            Msg::CapturePreview(preview) => {
+                self.capture_preview = Some(preview);
+            }
             Msg::IndexAdd => {
                 self.index_add = true;
             }
@@ -175,6 +408,15 @@ impl Component for Config {
     fn view(&self, ctx: &Context<Self>) -> Html {
         let mut indexes = Vec::new();
         let mut ocr = None;
+        let mut sync = None;
+        let mut tts = None;
+        let mut tokenizer = None;
+        let mut transcription = None;
+        let mut stats = None;
+        let mut anki = None;
+        let mut capture_filters = None;
+        let mut startup = None;
+        let mut check_updates = None;
 
         if let Some(state) = &self.state {
             for (id, index) in &state.local.indexes {
@@ -237,6 +479,271 @@ impl Component for Config {
                 }
             }
 
+            sync = Some({
+                let oninput = ctx.link().batch_callback(|e: InputEvent| {
+                    let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+                    Some(Msg::SyncDir(input.value()))
+                });
+
+                let value = state.local.sync_dir.clone().unwrap_or_default();
+
+                html! {
+                    <div class="block row row-spaced">
+                        <label for="sync-dir" title="Profiles are synchronized through this directory, like a shared network drive or a folder synchronized between machines">{"Sync directory"}</label>
+                        <input id="sync-dir" class="end" type="text" placeholder="Not synchronized" {value} disabled={self.pending} {oninput} />
+                    </div>
+                }
+            });
+
+            tts = Some({
+                let value = self.tts_command.clone();
+
+                html! {
+                    <div class="block row row-spaced">
+                        <label for="tts-command" title={format!("A program which writes audio to its output, where {} is replaced with the text to read. Programs are only set in the configuration file", Tts::TEXT)}>{"Command"}</label>
+                        <input id="tts-command" class="end" type="text" placeholder="Set in the configuration file" {value} readonly=true />
+                    </div>
+                }
+            });
+
+            tokenizer = Some({
+                let value = self.tokenizer_command.clone();
+
+                html! {
+                    <div class="block row row-spaced">
+                        <label for="tokenizer-command" title="A morphological analyzer like MeCab which reads text from its input and writes words in MeCab's format. Without one, the longest words found in the dictionary are used. Programs are only set in the configuration file">{"Command"}</label>
+                        <input id="tokenizer-command" class="end" type="text" placeholder="Set in the configuration file" {value} readonly=true />
+                    </div>
+                }
+            });
+
+            transcription = Some({
+                let oninput = ctx.link().batch_callback(|e: InputEvent| {
+                    let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+                    Some(Msg::TranscriptionModel(input.value()))
+                });
+
+                let value = state
+                    .local
+                    .transcription
+                    .as_ref()
+                    .map(|transcription| transcription.model.clone())
+                    .unwrap_or_default();
+
+                html! {
+                    <div class="block row row-spaced">
+                        <label for="transcription-model" title="A whisper.cpp model used to transcribe captured audio, which requires the service to be built with the whisper feature">{t(Text::Model)}</label>
+                        <input id="transcription-model" class="end" type="text" placeholder="ggml-small.bin" {value} disabled={self.pending} {oninput} />
+                    </div>
+                }
+            });
+
+            startup = Some({
+                let checked = state.local.open_browser;
+                let onchange = ctx.link().callback(|_| Msg::ToggleOpenBrowser);
+
+                html! {
+                    <div class="block row row-spaced">
+                        <input id="open-browser" type="checkbox" {checked} disabled={self.pending} {onchange} />
+                        <label for="open-browser" title="Can be overridden with --open and --no-open">{t(Text::OpenBrowser)}</label>
+                    </div>
+                }
+            });
+
+            check_updates = Some({
+                let checked = state.local.check_updates;
+                let onchange = ctx.link().callback(|_| Msg::ToggleCheckUpdates);
+
+                html! {
+                    <div class="block row row-spaced">
+                        <input id="check-updates" type="checkbox" {checked} disabled={self.pending} {onchange} />
+                        <label for="check-updates" title="Asks GitHub about new releases, nothing is downloaded">{t(Text::CheckUpdates)}</label>
+                    </div>
+                }
+            });
+
+            stats = Some({
+                let checked = state.local.stats.enabled;
+                let onchange = ctx.link().callback(|_| Msg::ToggleStats);
+
+                let oninput = ctx.link().batch_callback(|e: InputEvent| {
+                    let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+                    Some(Msg::StatsRetention(input.value()))
+                });
+
+                let value = state.local.stats.retention.to_string();
+
+                html! {
+                    <>
+                        <div class="block row row-spaced">
+                            <input id="stats" type="checkbox" {checked} disabled={self.pending} {onchange} />
+                            <label for="stats" title="Count searches, captures, reviews and saved words per day, stored on this machine">{t(Text::CollectStatistics)}</label>
+                        </div>
+
+                        <div class="block row row-spaced">
+                            <label for="stats-retention" title="Days statistics are kept for, where 0 keeps them forever">{t(Text::KeepForDays)}</label>
+                            <input id="stats-retention" class="end" type="number" min="0" {value} disabled={self.pending} {oninput} />
+                        </div>
+                    </>
+                }
+            });
+
+            anki = Some({
+                let config = &state.local.anki;
+                let checked = config.enabled;
+                let onchange = ctx.link().callback(|_| Msg::ToggleAnki);
+
+                let url = text_input(
+                    ctx,
+                    "anki-url",
+                    t(Text::AnkiUrlHelp),
+                    t(Text::AnkiUrl),
+                    ANKI_CONNECT_URL,
+                    &config.url,
+                    self.pending,
+                    Msg::AnkiUrl,
+                );
+
+                let deck = text_input(
+                    ctx,
+                    "anki-deck",
+                    t(Text::AnkiDeckHelp),
+                    t(Text::AnkiDeck),
+                    "Default",
+                    &config.deck,
+                    self.pending,
+                    Msg::AnkiDeck,
+                );
+
+                let model = text_input(
+                    ctx,
+                    "anki-model",
+                    t(Text::AnkiModelHelp),
+                    t(Text::AnkiModel),
+                    "Basic",
+                    &config.model,
+                    self.pending,
+                    Msg::AnkiModel,
+                );
+
+                let fields = AnkiPart::ALL.into_iter().map(|part| {
+                    let value = config
+                        .fields
+                        .get(&part)
+                        .map(String::as_str)
+                        .unwrap_or_default();
+
+                    text_input(
+                        ctx,
+                        &format!("anki-field-{}", part.ident()),
+                        t(Text::AnkiFieldHelp),
+                        part_title(part),
+                        t(Text::AnkiNotWritten),
+                        value,
+                        self.pending,
+                        move |field| Msg::AnkiField(part, field),
+                    )
+                });
+
+                let tags = text_input(
+                    ctx,
+                    "anki-tags",
+                    t(Text::AnkiTagsHelp),
+                    t(Text::AnkiTags),
+                    t(Text::AnkiNoTags),
+                    &self.anki_tags,
+                    self.pending,
+                    Msg::AnkiTags,
+                );
+
+                html! {
+                    <>
+                        <div class="block row row-spaced">
+                            <input id="anki" type="checkbox" {checked} disabled={self.pending} {onchange} />
+                            <label for="anki" title={t(Text::AnkiHelp)}>{t(Text::AddNotesToAnki)}</label>
+                        </div>
+
+                        {url}
+                        {deck}
+                        {model}
+                        {for fields}
+                        {tags}
+                    </>
+                }
+            });
+
+            capture_filters = Some({
+                let errors = self
+                    .capture_preview
+                    .as_ref()
+                    .map(|preview| preview.errors.as_slice())
+                    .unwrap_or_default();
+
+                let filters =
+                    state
+                        .local
+                        .capture_filters
+                        .iter()
+                        .enumerate()
+                        .map(|(index, filter)| {
+                            let error = errors
+                                .iter()
+                                .find(|error| error.index == index)
+                                .map(|error| error.message.as_str());
+
+                            capture_filter(ctx, index, filter, error, self.pending)
+                        });
+
+                let onadd = ctx.link().callback(|_| Msg::AddCaptureFilter);
+
+                let ignored_sources = text_input(
+                    ctx,
+                    "ignored-sources",
+                    t(Text::IgnoredSourcesHelp),
+                    t(Text::IgnoredSources),
+                    "firefox, Google-chrome",
+                    &self.ignored_sources,
+                    self.pending,
+                    Msg::IgnoredSources,
+                );
+
+                let oninput = ctx.link().batch_callback(|e: InputEvent| {
+                    let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+                    Some(Msg::CaptureSample(input.value()))
+                });
+
+                let preview = self
+                    .capture_preview
+                    .as_ref()
+                    .filter(|_| !self.capture_sample.is_empty())
+                    .map(|preview| match &preview.text {
+                        Some(text) => html! {
+                            <div class="block row row-spaced capture-preview">{text.clone()}</div>
+                        },
+                        None => html! {
+                            <div class="block row row-spaced notice">{t(Text::CaptureDropped)}</div>
+                        },
+                    });
+
+                html! {
+                    <>
+                        {ignored_sources}
+                        {for filters}
+
+                        <div class="block row row-spaced">
+                            <button class="btn end primary" disabled={self.pending} onclick={onadd}>{t(Text::AddFilter)}</button>
+                        </div>
+
+                        <div class="block row row-spaced">
+                            <label for="capture-sample" title={t(Text::TryFiltersHelp)}>{t(Text::TryFilters)}</label>
+                            <input id="capture-sample" class="end" type="text" placeholder="【名前】「こんにちは」" value={self.capture_sample.clone()} {oninput} />
+                        </div>
+
+                        {preview}
+                    </>
+                }
+            });
+
             ocr = Some({
                 let checked = state.local.ocr;
 
@@ -268,6 +775,8 @@ impl Component for Config {
                     }
                 });
 
+                let value = self.ocr_command.clone();
+
                 html! {
                     <>
                         <div class="block row row-spaced">
@@ -275,6 +784,11 @@ impl Component for Config {
                             <label for="ocr">{"OCR Support"}</label>
                         </div>
 
+                        <div class="block row row-spaced">
+                            <label for="ocr-command" title="A program used instead of tesseract, like a wrapper around manga-ocr, which reads a PNG image from its input and writes the recognized text. Programs are only set in the configuration file">{t(Text::OcrEngine)}</label>
+                            <input id="ocr-command" class="end" type="text" placeholder="tesseract" {value} readonly=true />
+                        </div>
+
                         {for missing_ocr}
                     </>
                 }
@@ -298,8 +812,8 @@ impl Component for Config {
 
             html! {
                 <div class="block row row-spaced">
-                    <button class="btn end primary" disabled={self.pending} {onclick}>{"New dictionary"}</button>
-                    <button class="btn primary" disabled={self.pending} onclick={onrebuild} title="Install all missing dictionaries">{"Install all"}</button>
+                    <button class="btn end primary" disabled={self.pending} {onclick}>{t(Text::NewDictionary)}</button>
+                    <button class="btn primary" disabled={self.pending} onclick={onrebuild} title="Install all missing dictionaries">{t(Text::InstallAll)}</button>
                 </div>
             }
         };
@@ -311,11 +825,54 @@ impl Component for Config {
             </>
         };
 
+        let metadata = Metadata::ALL.into_iter().map(|metadata| {
+            let id = format!("metadata-{}", metadata.ident());
+            let checked = self.display.is_shown(metadata);
+            let onchange = ctx.link().callback(move |_| Msg::ToggleMetadata(metadata));
+
+            html! {
+                <div class="block row row-spaced">
+                    <input id={id.clone()} type="checkbox" {checked} {onchange} />
+                    <label for={id}>{metadata.title()}</label>
+                </div>
+            }
+        });
+
+        let onlanguage = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+            Some(Msg::Language(Language::from_tag(&select.value())?))
+        });
+
+        let languages = Language::ALL.into_iter().map(|language| {
+            let selected = language == i18n::language();
+
+            html! {
+                <option value={language.tag()} {selected}>{language.title()}</option>
+            }
+        });
+
+        let onplain = ctx.link().callback(|_| Msg::TogglePlainReadings);
+
+        let onreveal = ctx.link().callback(|_| Msg::ToggleRevealTranslations);
+
+        let accessibility = html! {
+            <>
+                <div class="block row row-spaced">
+                    <input id="plain-readings" type="checkbox" checked={self.display.plain_readings} onchange={onplain} />
+                    <label for="plain-readings" title="Show readings in parentheses after kanji instead of above them, which screen readers read more reliably">{t(Text::PlainReadings)}</label>
+                </div>
+                <div class="block row row-spaced">
+                    <input id="reveal-translations" type="checkbox" checked={self.display.reveal_translations} onchange={onreveal} />
+                    <label for="reveal-translations" title="Captured translations are otherwise blurred until hovered, so that they can be peeked at">{t(Text::RevealTranslations)}</label>
+                </div>
+            </>
+        };
+
         let onsave = ctx.link().callback(|_| Msg::Save);
 
         let back = (!ctx.props().embed).then(|| {
             html! {
-                <button class="btn btn-lg" onclick={ctx.props().onback.reform(|_| ())}>{"Back"}</button>
+                <button class="btn btn-lg" onclick={ctx.props().onback.reform(|_| ())}>{t(Text::Back)}</button>
             }
         });
 
@@ -346,7 +903,7 @@ impl Component for Config {
         let pending = self.pending.then(|| {
             html! {
                 <div class="block block-lg row row-spaced">
-                    <div class="spinner">{"Loading"}</div>
+                    <div class="spinner" role="status" aria-live="polite">{t(Text::Loading)}</div>
                 </div>
             }
         });
@@ -355,23 +912,213 @@ impl Component for Config {
             <>
                 <div class="block block-lg row row-spaced">
                     {back}
-                    <button class="btn btn-lg end primary" {disabled} onclick={onsave}>{"Save"}</button>
+                    <button class="btn btn-lg end primary" {disabled} onclick={onsave}>{t(Text::Save)}</button>
                 </div>
 
                 {pending}
 
-                <h5>{"Dictionaries"}</h5>
+                <h5 title="Stored in this browser">{t(Text::Language)}</h5>
+                <div class="block block-lg row row-spaced">
+                    <select id="language" aria-label={t(Text::Language)} onchange={onlanguage}>{for languages}</select>
+                </div>
+
+                <h5>{t(Text::Profile)}</h5>
+                <div class="block block-lg"><c::Profiles ws={ctx.props().ws.clone()} /></div>
+
+                <h5>{t(Text::Sync)}</h5>
+                <div class="block block-lg">{for sync}</div>
+
+                <h5>{t(Text::Dictionaries)}</h5>
                 <div class="block block-lg">{dictionaries}</div>
 
+                <h5 title="Shown for each sense of an entry, stored in this browser">{t(Text::SenseDetails)}</h5>
+                <div class="block block-lg">{for metadata}</div>
+
+                <h5 title="Stored in this browser">{t(Text::Accessibility)}</h5>
+                <div class="block block-lg">{accessibility}</div>
+
+                <h5 title="Used to read words out loud">{t(Text::SpeechSynthesis)}</h5>
+                <div class="block block-lg">{for tts}</div>
+
+                <h5 title="Used to split text into words">{t(Text::WordSegmentation)}</h5>
+                <div class="block block-lg">{for tokenizer}</div>
+
+                <h5 title="Used to transcribe captured audio">{t(Text::Transcription)}</h5>
+                <div class="block block-lg">{for transcription}</div>
+
+                <h5>{t(Text::Statistics)}</h5>
+                <div class="block block-lg">{for stats}</div>
+
+                <h5>{"Anki"}</h5>
+                <div class="block block-lg">{for anki}</div>
+
+                <h5 title="Applied in order to captured text before it's looked up">{t(Text::CaptureFilters)}</h5>
+                <div class="block block-lg">{for capture_filters}</div>
+
+                <h5>{t(Text::Startup)}</h5>
+                <div class="block block-lg">{for startup}{for check_updates}</div>
+
                 <h5>{"OCR"}</h5>
 
                 <div class="block block-lg">
                     {for ocr}
                 </div>
 
-                <h5>{"Log"}</h5>
+                <h5>{t(Text::Log)}</h5>
                 {log}
             </>
         }
     }
 }
+
+impl Config {
+    /// The capture filter being edited at the given index.
+    fn capture_filter(&mut self, index: usize) -> Option<&mut CaptureFilter> {
+        self.state.as_mut()?.local.capture_filters.get_mut(index)
+    }
+
+    /// Preview the capture filters being edited on the sample text.
+    fn preview_capture_filters(&mut self, ctx: &Context<Self>) {
+        let Some(state) = &self.state else {
+            return;
+        };
+
+        if self.capture_sample.is_empty() {
+            self.capture_preview = None;
+            self.preview_request = ws::Request::empty();
+            return;
+        }
+
+        self.preview_request = ctx.props().ws.request(
+            api::PreviewCaptureFiltersRequest {
+                filters: state.local.capture_filters.clone(),
+                text: self.capture_sample.clone(),
+            },
+            ctx.link().callback(|result| match result {
+                Ok(preview) => Msg::CapturePreview(preview),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+}
+
+/// The settings of a single capture filter.
+fn capture_filter(
+    ctx: &Context<Config>,
+    index: usize,
+    filter: &CaptureFilter,
+    error: Option<&str>,
+    disabled: bool,
+) -> Html {
+    let id = format!("capture-filter-{index}");
+
+    let ontoggle = ctx
+        .link()
+        .callback(move |_| Msg::ToggleCaptureFilter(index));
+    let ondrop = ctx
+        .link()
+        .callback(move |_| Msg::ToggleCaptureFilterDrop(index));
+    let onremove = ctx
+        .link()
+        .callback(move |_| Msg::RemoveCaptureFilter(index));
+
+    let name = text_input(
+        ctx,
+        &format!("{id}-name"),
+        t(Text::FilterNameHelp),
+        t(Text::FilterName),
+        t(Text::FilterUnnamed),
+        &filter.name,
+        disabled,
+        move |name| Msg::CaptureFilterName(index, name),
+    );
+
+    let pattern = text_input(
+        ctx,
+        &format!("{id}-pattern"),
+        t(Text::FilterPatternHelp),
+        t(Text::FilterPattern),
+        "^【[^】]*】",
+        &filter.pattern,
+        disabled,
+        move |pattern| Msg::CaptureFilterPattern(index, pattern),
+    );
+
+    let replace = filter.replace.as_deref().map(|replace| {
+        text_input(
+            ctx,
+            &format!("{id}-replace"),
+            t(Text::FilterReplaceHelp),
+            t(Text::FilterReplace),
+            t(Text::FilterRemoved),
+            replace,
+            disabled,
+            move |replace| Msg::CaptureFilterReplace(index, replace),
+        )
+    });
+
+    let error = error.map(|error| {
+        html! {
+            <div class="block row row-spaced danger">{error.to_owned()}</div>
+        }
+    });
+
+    let class = classes! {
+        "block",
+        "capture-filter",
+        filter.enabled.then_some("enabled"),
+    };
+
+    html! {
+        <div {class}>
+            <div class="block row row-spaced">
+                <input id={id.clone()} type="checkbox" checked={filter.enabled} {disabled} onchange={ontoggle} />
+                <label for={id.clone()}>{t(Text::Enabled)}</label>
+                <input id={format!("{id}-drop")} type="checkbox" checked={filter.replace.is_none()} {disabled} onchange={ondrop} />
+                <label for={format!("{id}-drop")} title={t(Text::DropMatchesHelp)}>{t(Text::DropMatches)}</label>
+                <button class="btn end danger" {disabled} onclick={onremove}>{t(Text::Delete)}</button>
+            </div>
+
+            {name}
+            {pattern}
+            {replace}
+            {error}
+        </div>
+    }
+}
+
+/// A labelled text input for a setting.
+#[allow(clippy::too_many_arguments)]
+fn text_input(
+    ctx: &Context<Config>,
+    id: &str,
+    title: &'static str,
+    label: &'static str,
+    placeholder: &'static str,
+    value: &str,
+    disabled: bool,
+    msg: impl Fn(String) -> Msg + 'static,
+) -> Html {
+    let oninput = ctx.link().batch_callback(move |e: InputEvent| {
+        let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+        Some(msg(input.value()))
+    });
+
+    html! {
+        <div class="block row row-spaced">
+            <label for={id.to_owned()} {title}>{label}</label>
+            <input id={id.to_owned()} class="end" type="text" {placeholder} value={value.to_owned()} {disabled} {oninput} />
+        </div>
+    }
+}
+
+/// The title of a part of an Anki note.
+fn part_title(part: AnkiPart) -> &'static str {
+    match part {
+        AnkiPart::Word => t(Text::AnkiWordField),
+        AnkiPart::Reading => t(Text::AnkiReadingField),
+        AnkiPart::Meaning => t(Text::AnkiMeaningField),
+        AnkiPart::Sentence => t(Text::AnkiSentenceField),
+        AnkiPart::Image => t(Text::AnkiImageField),
+    }
+}
@@ -0,0 +1,192 @@
+use lib::api;
+use yew::prelude::*;
+
+use crate::error::Error;
+use crate::i18n::{t, Text};
+use crate::ws;
+
+use super::{colon, spacing};
+
+pub(crate) enum Msg {
+    Load,
+    Counters(api::OwnedCountersResponse),
+    Select(usize),
+    Number(String),
+    Count(api::CountResponse),
+    Error(Error),
+}
+
+#[derive(Properties)]
+pub struct Props {
+    pub ws: ws::Handle,
+    pub sequence: u32,
+}
+
+impl PartialEq for Props {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.sequence == other.sequence
+    }
+}
+
+pub(crate) struct Counters {
+    counters: Option<Vec<api::OwnedCounter>>,
+    selected: usize,
+    number: String,
+    reading: Option<String>,
+    pending: ws::Request,
+    pending_count: ws::Request,
+}
+
+impl Component for Counters {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        Self {
+            counters: None,
+            selected: 0,
+            number: String::new(),
+            reading: None,
+            pending: ws::Request::empty(),
+            pending_count: ws::Request::empty(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Load => {
+                self.pending = ctx.props().ws.request(
+                    api::CountersRequest {
+                        sequence: ctx.props().sequence,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Counters(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::Counters(response) => {
+                self.pending = ws::Request::empty();
+                self.counters = Some(response.counters);
+                self.selected = 0;
+                true
+            }
+            Msg::Select(index) => {
+                self.selected = index;
+                self.request_count(ctx);
+                true
+            }
+            Msg::Number(number) => {
+                self.number = number;
+                self.request_count(ctx);
+                true
+            }
+            Msg::Count(response) => {
+                self.pending_count = ws::Request::empty();
+                self.reading = response.reading;
+                true
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                false
+            }
+        }
+    }
+
+    fn changed(&mut self, _: &Context<Self>, _: &Self::Properties) -> bool {
+        self.counters = None;
+        self.selected = 0;
+        self.number.clear();
+        self.reading = None;
+        self.pending = ws::Request::empty();
+        self.pending_count = ws::Request::empty();
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let Some(counters) = &self.counters else {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::Load);
+            return html!(<div class="block row"><span class="clickable" {onclick}>{t(Text::Counters)}</span></div>);
+        };
+
+        if counters.is_empty() {
+            return html!(<div class="block row"><span>{t(Text::NoCounters)}</span></div>);
+        }
+
+        let chips = counters.iter().enumerate().map(|(index, c)| {
+            let onclick = ctx.link().callback(move |_: MouseEvent| Msg::Select(index));
+
+            let class = classes! {
+                "chip",
+                (index == self.selected).then_some("active"),
+            };
+
+            html!(<button {class} title={c.description.clone()} {onclick}>{format!("{} ({})", c.text, c.reading)}</button>)
+        });
+
+        let selected = counters.get(self.selected).map(|c| {
+            let readings = c.readings.iter().enumerate().map(|(n, reading)| {
+                html!(<span class="counter-reading">{format!("{}{}", n + 1, c.text)}{colon()}{reading}</span>)
+            });
+
+            let oninput = ctx.link().batch_callback(|e: InputEvent| {
+                let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+                Some(Msg::Number(input.value()))
+            });
+
+            let reading = match (self.number.is_empty(), &self.reading) {
+                (true, _) => None,
+                (false, Some(reading)) => Some(html!(<span>{reading}</span>)),
+                (false, None) => Some(html!(<span>{t(Text::NotUsedWithNumber)}</span>)),
+            };
+
+            html! {
+                <>
+                <div class="block row">{c.description.clone()}</div>
+                <div class="block row counter-readings">{for readings}</div>
+                <div class="block row">
+                    <input type="number" min="1" placeholder={t(Text::Number)} value={self.number.clone()} {oninput} />
+                    {spacing()}
+                    <span>{c.text.clone()}</span>
+                    {spacing()}
+                    {for reading}
+                </div>
+                </>
+            }
+        });
+
+        html! {
+            <div class="block counters">
+                <div class="block row chips"><span>{t(Text::Counters)}</span>{colon()}{for chips}</div>
+                {for selected}
+            </div>
+        }
+    }
+}
+
+impl Counters {
+    fn request_count(&mut self, ctx: &Context<Self>) {
+        let counter = self
+            .counters
+            .as_ref()
+            .and_then(|c| c.get(self.selected))
+            .map(|c| c.text.clone());
+
+        let (Some(counter), Ok(number)) = (counter, self.number.parse::<u32>()) else {
+            self.pending_count = ws::Request::empty();
+            self.reading = None;
+            return;
+        };
+
+        self.pending_count = ctx.props().ws.request(
+            api::CountRequest { counter, number },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::Count(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::rc::Rc;
+
+use lib::api;
+use yew::prelude::*;
+
+pub(crate) enum Msg {
+    Toggle,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct Props {
+    pub dictionary: Rc<api::Dictionary>,
+}
+
+/// A badge indicating which dictionary a result is from, which can be
+/// expanded to show attribution for the dictionary.
+pub(crate) struct DictionaryBadge {
+    open: bool,
+}
+
+impl Component for DictionaryBadge {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        Self { open: false }
+    }
+
+    fn update(&mut self, _: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Toggle => {
+                self.open = !self.open;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let dictionary = &ctx.props().dictionary;
+
+        let class = classes! {
+            "dictionary-badge",
+            format!("dictionary-{}", dictionary.format.id()),
+            dictionary.user.then_some("user"),
+            self.open.then_some("active"),
+        };
+
+        let title = if dictionary.user {
+            format!("From user dictionary {}", dictionary.title)
+        } else {
+            format!("From {}", dictionary.title)
+        };
+
+        let onclick = ctx.link().callback(|_| Msg::Toggle);
+
+        let info = self.open.then(|| {
+            let help = dictionary.help.as_ref().map(|help| {
+                html!(<div><a href={help.clone()} target="_blank">{"More information"}</a></div>)
+            });
+
+            let attribution = dictionary
+                .attribution
+                .as_ref()
+                .map(|text| html!(<div class="dictionary-attribution">{text}</div>));
+
+            html! {
+                <div class="block block-sm notice dictionary-info">
+                    <div class="title">{&dictionary.title}</div>
+                    <div>{format!("Installed as `{}`", dictionary.id)}</div>
+                    <div><a href={dictionary.url.clone()} target="_blank">{&dictionary.url}</a></div>
+                    {for help}
+                    {for attribution}
+                </div>
+            }
+        });
+
+        html! {
+            <>
+                <span {class} {title} {onclick}>{dictionary.format.title()}</span>
+                {for info}
+            </>
+        }
+    }
+}
@@ -2,12 +2,15 @@ use lib::config::{ConfigIndex, IndexFormat};
 use url::Url;
 use yew::prelude::*;
 
+use crate::i18n::{t, Text};
+
 pub(crate) enum Msg {
     ChangeId(String),
     ChangeFormat(IndexFormat),
     ChangeDescription(String),
     ChangeUrl(String),
     ChangeHelp(String),
+    ToggleCompressed,
     Save,
 }
 
@@ -42,6 +45,7 @@ pub(crate) struct EditIndex {
     description: String,
     url: String,
     help: String,
+    compressed: bool,
     errors: Errors,
 }
 
@@ -60,6 +64,7 @@ impl Component for EditIndex {
                 .unwrap_or_default(),
             url: index.map(|i| i.url.clone()).unwrap_or_default(),
             help: index.and_then(|i| i.help.clone()).unwrap_or_default(),
+            compressed: index.map(|i| i.compressed).unwrap_or_default(),
             errors: Errors::default(),
         }
     }
@@ -84,6 +89,9 @@ impl Component for EditIndex {
                 self.help = help;
                 self.validate(ctx);
             }
+            Msg::ToggleCompressed => {
+                self.compressed = !self.compressed;
+            }
             Msg::Save => {
                 let id = ctx.props().index.is_none().then(|| self.id.clone());
                 self.validate(ctx);
@@ -99,6 +107,7 @@ impl Component for EditIndex {
                         } else {
                             Some(self.help.clone())
                         },
+                        compressed: self.compressed,
                     };
 
                     ctx.props().onsave.emit((id, index));
@@ -142,6 +151,8 @@ impl Component for EditIndex {
             }
         });
 
+        let onchangecompressed = ctx.link().callback(|_| Msg::ToggleCompressed);
+
         let oncancel = ctx.props().oncancel.reform(|_| ());
 
         let onsave = ctx.link().callback(move |_| Msg::Save);
@@ -172,8 +183,8 @@ impl Component for EditIndex {
 
             html! {
                 <div {class}>
-                    <h6>{"Id"}</h6>
-                    <p class="form-help">{"The unique identifier of the dictionary, must only contain [a-z], [A-Z], and [0-9]."}</p>
+                    <h6>{t(Text::Id)}</h6>
+                    <p class="form-help">{t(Text::IdHelp)}</p>
                     <input type="text" disabled={ctx.props().pending} value={self.id.clone()} {oninput} />
                     <>{error}</>
                 </div>
@@ -184,7 +195,7 @@ impl Component for EditIndex {
             let ondelete = ctx.props().ondelete.reform(|_| ());
 
             html! {
-                <button class="btn end danger" disabled={ctx.props().pending} onclick={ondelete}>{"Delete"}</button>
+                <button class="btn end danger" disabled={ctx.props().pending} onclick={ondelete}>{t(Text::Delete)}</button>
             }
         });
 
@@ -221,32 +232,41 @@ impl Component for EditIndex {
         html! {
             <div {class}>
                 <div class="block form">
-                    <h6>{"Format"}</h6>
+                    <h6>{t(Text::Format)}</h6>
                     <select onchange={onchangeformat}>
                         <option value="jmdict" selected={self.format == IndexFormat::Jmdict}>{"JMDict"}</option>
                         <option value="jmnedict" selected={self.format == IndexFormat::Jmnedict}>{"JMnedict"}</option>
                         <option value="kanjidic2" selected={self.format == IndexFormat::Kanjidic2}>{"Kanjidic2"}</option>
+                        <option value="tanaka" selected={self.format == IndexFormat::Tanaka}>{t(Text::TanakaCorpus)}</option>
+                        <option value="ids" selected={self.format == IndexFormat::Ids}>{"IDS"}</option>
                     </select>
                 </div>
                 {id}
                 <div class={url_class}>
-                    <h6>{"URL"}</h6>
+                    <h6>{t(Text::Url)}</h6>
                     <input type="text" disabled={ctx.props().pending} value={self.url.clone()} onchange={onchangeurl} />
                     <>{url_error}</>
                 </div>
                 <div class="block form">
-                    <h6>{"Description"}</h6>
+                    <h6>{t(Text::Description)}</h6>
                     <input type="text" disabled={ctx.props().pending} value={self.description.clone()} onchange={onchangedescription} />
                 </div>
                 <div class={help_class}>
-                    <h6>{"Help Page"}</h6>
+                    <h6>{t(Text::HelpPage)}</h6>
                     <input type="text" disabled={ctx.props().pending} value={self.help.clone()} onchange={onchangehelp} />
                     <>{help_error}</>
                 </div>
+                <div class="block form">
+                    <div class="row row-spaced">
+                        <input id="compressed" type="checkbox" checked={self.compressed} disabled={ctx.props().pending} onchange={onchangecompressed} />
+                        <label for="compressed">{t(Text::Compressed)}</label>
+                    </div>
+                    <p class="form-help">{t(Text::CompressedHelp)}</p>
+                </div>
                 <div class="block row row-spaced">
-                    <button class="btn" disabled={ctx.props().pending} onclick={oncancel}>{"Cancel"}</button>
+                    <button class="btn" disabled={ctx.props().pending} onclick={oncancel}>{t(Text::Cancel)}</button>
                     {delete}
-                    <button class={save_classes} disabled={ctx.props().pending} onclick={onsave}>{"Save"}</button>
+                    <button class={save_classes} disabled={ctx.props().pending} onclick={onsave}>{t(Text::Save)}</button>
                 </div>
             </div>
         }
@@ -261,14 +281,14 @@ impl EditIndex {
                     .chars()
                     .all(|c| matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9'))
             {
-                self.errors.id = Some("Must be a non-empty sequence of [a-z], [A-Z], and [0-9].");
+                self.errors.id = Some(t(Text::InvalidId));
             } else {
                 self.errors.id = None;
             }
         }
 
         if self.url.is_empty() {
-            self.errors.url = Some("Must be non-empty".to_string());
+            self.errors.url = Some(t(Text::Required).to_owned());
         } else if let Err(error) = Url::parse(&self.url) {
             self.errors.url = Some(error.to_string());
         } else {
@@ -1,19 +1,80 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
 
-use lib::database::Source;
+use lib::api;
+use lib::database::{PhraseIndex, Source};
 use lib::entities::KanjiInfo;
 use lib::jmdict::{
     OwnedExample, OwnedExampleSentence, OwnedKanjiElement, OwnedReadingElement, OwnedSense,
 };
-use lib::{inflection, jmdict, kana, Form, Furigana, Inflection, OwnedInflections, Priority};
+use lib::profile::Annotation;
+use lib::{
+    difficulty, inflection, jmdict, kana, Difficulty, Form, Furigana, Inflection, OwnedInflections,
+    PartOfSpeechGroup, Priority,
+};
+use web_sys::{HtmlAudioElement, HtmlInputElement, HtmlTextAreaElement};
 use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::base;
+use crate::components as c;
+use crate::display::{Display, Metadata};
+use crate::error::Error;
+use crate::i18n::{self, t, Shown, Text};
+use crate::pinned::Pinned;
+use crate::ws;
+
+use super::{colon, comma, icon, iter, romaji, ruby, seq, spacing};
+
+/// Render the sense metadata of the given category as chips, unless the
+/// category is hidden.
+macro_rules! metadata {
+    ($display:expr, $metadata:expr, $base:ident . $name:ident) => {
+        $display
+            .is_shown($metadata)
+            .then(|| {
+                $base.$name.iter().map(|d| {
+                    let class = classes! {
+                        "bullet",
+                        "sm",
+                        stringify!($name),
+                        format!("{}-{}", stringify!($name), d.ident()),
+                    };
+
+                    let title = format!("{}: {}", $metadata.title(), d.help());
+                    html!(<span class={class} {title}>{d.ident()}</span>)
+                })
+            })
+            .into_iter()
+            .flatten()
+    };
+}
 
-use super::{colon, comma, iter, romaji, ruby, seq, spacing};
+/// The number of additional corpus examples loaded at a time.
+const MORE_SENSE_EXAMPLES: usize = 10;
 
 pub(crate) enum Msg {
     ToggleForm(usize, Form),
     ResetForm(usize),
+    ToggleRare,
+    TogglePin,
+    Speak(String),
     Change(String, Option<String>),
+    LoadRelated,
+    Related(api::OwnedRelatedResponse),
+    MoreSenseExamples(usize),
+    SenseExamples(usize, api::OwnedSenseExamplesResponse),
+    EditAnnotation,
+    Note(String),
+    Tags(String),
+    SaveAnnotation,
+    CancelAnnotation,
+    AnnotationSaved(api::SetAnnotationResponse),
+    Mine,
+    Mined(lib::mining::Card),
+    AnkiNotes(Vec<u64>),
+    AddToAnki,
+    Error(Error),
 }
 
 #[derive(Default)]
@@ -49,6 +110,10 @@ impl Combined {
         self.kanji.info.contains(KanjiInfo::SearchOnlyKanji)
     }
 
+    fn is_rarely_used(&self) -> bool {
+        self.kanji.is_rarely_used() || self.reading.is_rarely_used()
+    }
+
     /// Provide furigana iterator for the combined reading.
     fn furigana(&self) -> Furigana<'_, 1, 1> {
         kana::Full::new(&self.kanji.text, &self.reading.text, "").furigana()
@@ -63,22 +128,75 @@ impl Combined {
 pub(crate) struct Entry {
     combined: Vec<Combined>,
     readings: Vec<OwnedReadingElement>,
+    /// Whether rarely used spellings are shown.
+    show_rare: bool,
+    display: Display,
     states: Vec<ExtraState>,
     inflections: Vec<(inflection::Reading, OwnedInflections)>,
+    related: Option<Vec<jmdict::OwnedEntry>>,
+    pending_related: ws::Request,
+    /// Corpus examples loaded for each sense, starting out with the best
+    /// examples provided with the entry.
+    sense_examples: HashMap<usize, api::OwnedSenseExamples>,
+    pending_sense_examples: HashMap<usize, ws::Request>,
+    /// The personal note and tags of the entry.
+    annotation: Option<Annotation>,
+    /// Whether the annotation is being edited.
+    editing: bool,
+    note: String,
+    tags: String,
+    pending_annotation: ws::Request,
+    /// Audio of a reading being read out loud.
+    speech: Option<HtmlAudioElement>,
+    /// Phrases pinned to be compared.
+    pinned: Pinned,
+    /// The card mined for the entry from the current sentence.
+    mined: Option<lib::mining::Card>,
+    mining: bool,
+    pending_mine: ws::Request,
+    /// Notes in Anki for the entry, if they've been looked up.
+    anki_notes: Option<Vec<u64>>,
+    adding_to_anki: bool,
+    pending_anki: ws::Request,
 }
 
 #[derive(Properties)]
 pub struct Props {
+    pub ws: ws::Handle,
     pub embed: bool,
     pub sources: BTreeSet<Source>,
     pub entry: jmdict::OwnedEntry,
+    #[prop_or_default]
+    pub sense_examples: Vec<api::OwnedSenseExamples>,
+    #[prop_or_default]
+    pub difficulty: Option<Difficulty>,
+    #[prop_or_default]
+    pub annotation: Option<Annotation>,
+    /// The dictionary the entry is from.
+    #[prop_or_default]
+    pub dictionary: Option<Rc<api::Dictionary>>,
     pub onchange: Callback<(String, Option<String>), ()>,
+    /// Whether readings can be read out loud.
+    #[prop_or_default]
+    pub tts: bool,
+    /// The sentence the entry was looked up from, which it can be mined
+    /// from.
+    #[prop_or_default]
+    pub sentence: Option<String>,
+    /// Whether entries can be added to Anki.
+    #[prop_or_default]
+    pub anki: bool,
 }
 
 impl PartialEq for Props {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        self.sources == other.sources && self.entry.sequence == other.entry.sequence
+        self.sources == other.sources
+            && self.entry.sequence == other.entry.sequence
+            && self.dictionary == other.dictionary
+            && self.tts == other.tts
+            && self.sentence == other.sentence
+            && self.anki == other.anki
     }
 }
 
@@ -92,6 +210,8 @@ impl Component for Entry {
         let mut this = Self {
             combined: Vec::new(),
             readings: Vec::new(),
+            show_rare: false,
+            display: Display::load(),
             states: ctx
                 .props()
                 .sources
@@ -102,9 +222,27 @@ impl Component for Entry {
                 .into_iter()
                 .map(|(r, i, _)| (r, borrowme::to_owned(i)))
                 .collect(),
+            related: None,
+            pending_related: ws::Request::empty(),
+            sense_examples: sense_examples(ctx),
+            pending_sense_examples: HashMap::new(),
+            annotation: ctx.props().annotation.clone(),
+            editing: false,
+            note: String::new(),
+            tags: String::new(),
+            pending_annotation: ws::Request::empty(),
+            speech: None,
+            pinned: Pinned::load(),
+            mined: None,
+            mining: false,
+            pending_mine: ws::Request::empty(),
+            anki_notes: None,
+            adding_to_anki: false,
+            pending_anki: ws::Request::empty(),
         };
 
         this.refresh_entry(ctx);
+        this.load_anki_notes(ctx);
         this
     }
 
@@ -120,9 +258,185 @@ impl Component for Entry {
                     state.filter = Inflection::default();
                 }
             }
+            Msg::ToggleRare => {
+                self.show_rare = !self.show_rare;
+            }
+            Msg::TogglePin => {
+                self.pinned = Pinned::load();
+                self.pinned.toggle(ctx.props().entry.sequence as u32);
+            }
+            Msg::Speak(text) => {
+                if let Some(speech) = self.speech.take() {
+                    let _ = speech.pause();
+                }
+
+                match speak(&text) {
+                    Ok(speech) => {
+                        self.speech = Some(speech);
+                    }
+                    Err(error) => {
+                        log::error!("{error}");
+                    }
+                }
+            }
             Msg::Change(text, english) => {
                 ctx.props().onchange.emit((text, english));
             }
+            Msg::LoadRelated => {
+                self.pending_related = ctx.props().ws.request(
+                    api::RelatedRequest {
+                        sequence: ctx.props().entry.sequence as u32,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Related(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                return false;
+            }
+            Msg::Related(response) => {
+                self.pending_related = ws::Request::empty();
+                self.related = Some(response.related);
+            }
+            Msg::MoreSenseExamples(sense) => {
+                let offset = self
+                    .sense_examples
+                    .get(&sense)
+                    .map(|e| e.examples.len())
+                    .unwrap_or_default();
+
+                let request = ctx.props().ws.request(
+                    api::SenseExamplesRequest {
+                        sequence: ctx.props().entry.sequence as u32,
+                        sense,
+                        offset,
+                        limit: Some(MORE_SENSE_EXAMPLES),
+                    },
+                    ctx.link().callback(move |result| match result {
+                        Ok(response) => Msg::SenseExamples(sense, response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                self.pending_sense_examples.insert(sense, request);
+                return false;
+            }
+            Msg::SenseExamples(sense, response) => {
+                self.pending_sense_examples.remove(&sense);
+
+                let output =
+                    self.sense_examples
+                        .entry(sense)
+                        .or_insert_with(|| api::OwnedSenseExamples {
+                            sense,
+                            total: 0,
+                            examples: Vec::new(),
+                        });
+
+                output.total = response.total;
+                output.examples.extend(response.examples);
+            }
+            Msg::EditAnnotation => {
+                self.editing = true;
+                self.note = self
+                    .annotation
+                    .as_ref()
+                    .map(|a| a.note.clone())
+                    .unwrap_or_default();
+                self.tags = self
+                    .annotation
+                    .as_ref()
+                    .map(|a| a.tags.join(" "))
+                    .unwrap_or_default();
+            }
+            Msg::Note(note) => {
+                self.note = note;
+            }
+            Msg::Tags(tags) => {
+                self.tags = tags;
+            }
+            Msg::SaveAnnotation => {
+                let tags = self
+                    .tags
+                    .split(|c: char| c.is_whitespace() || c == ',')
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+
+                self.pending_annotation = ctx.props().ws.request(
+                    api::SetAnnotationRequest {
+                        sequence: ctx.props().entry.sequence,
+                        note: self.note.clone(),
+                        tags,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::AnnotationSaved(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                return false;
+            }
+            Msg::CancelAnnotation => {
+                self.editing = false;
+                self.pending_annotation = ws::Request::empty();
+            }
+            Msg::AnnotationSaved(response) => {
+                self.pending_annotation = ws::Request::empty();
+                self.annotation = response.annotation;
+                self.editing = false;
+            }
+            Msg::Mine => {
+                let Some(sentence) = ctx.props().sentence.clone() else {
+                    return false;
+                };
+
+                self.mining = true;
+
+                self.pending_mine = ctx.props().ws.request(
+                    api::MineRequest {
+                        sentence,
+                        sequence: ctx.props().entry.sequence,
+                        screenshot: Display::load().screenshots,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(card) => Msg::Mined(card),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+            }
+            Msg::Mined(card) => {
+                self.pending_mine = ws::Request::empty();
+                self.mining = false;
+                self.mined = Some(card);
+            }
+            Msg::AnkiNotes(notes) => {
+                self.pending_anki = ws::Request::empty();
+                self.adding_to_anki = false;
+                self.anki_notes = Some(notes);
+            }
+            Msg::AddToAnki => {
+                self.adding_to_anki = true;
+
+                self.pending_anki = ctx.props().ws.request(
+                    api::AddAnkiNoteRequest {
+                        sequence: ctx.props().entry.sequence,
+                        allow_duplicate: false,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(api::AnkiNotesResponse { notes }) => Msg::AnkiNotes(notes),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.pending_mine = ws::Request::empty();
+                self.mining = false;
+                self.pending_anki = ws::Request::empty();
+                self.adding_to_anki = false;
+            }
         }
 
         true
@@ -143,7 +457,22 @@ impl Component for Entry {
             .map(|_| ExtraState::default())
             .collect();
 
+        self.related = None;
+        self.pending_related = ws::Request::empty();
+        self.sense_examples = sense_examples(ctx);
+        self.pending_sense_examples.clear();
+        self.annotation = ctx.props().annotation.clone();
+        self.editing = false;
+        self.pending_annotation = ws::Request::empty();
+        self.pinned = Pinned::load();
+        self.mined = None;
+        self.mining = false;
+        self.pending_mine = ws::Request::empty();
+        self.anki_notes = None;
+        self.adding_to_anki = false;
+        self.pending_anki = ws::Request::empty();
         self.refresh_entry(ctx);
+        self.load_anki_notes(ctx);
         true
     }
 
@@ -168,9 +497,19 @@ impl Component for Entry {
                     render_extra(ctx, index, inflection, inflections, state.filter)
                 });
 
+        let skeleton = sources
+            .contains(&Source::Phrase {
+                index: PhraseIndex::Skeleton,
+            })
+            .then(|| html!(<div class="block notice">{t(Text::SkeletonResult)}</div>));
+
+        let show_rare = self.show_rare;
+
         let reading = iter(
             seq(
-                self.readings.iter().filter(|r| !r.is_search_only()),
+                self.readings
+                    .iter()
+                    .filter(|r| !r.is_search_only() && (show_rare || !r.is_rarely_used())),
                 |e, not_last| render_reading(ctx, e, not_last),
             ),
             |iter| html!(<div class="block row entry-readings">{for iter}</div>),
@@ -179,7 +518,7 @@ impl Component for Entry {
         let common = iter(
             seq(
                 self.combined.iter().filter(|c| c.is_common()),
-                |e, not_last| render_combined(ctx, e, not_last),
+                |e, not_last| render_combined(ctx, e, false, not_last),
             ),
             |iter| {
                 html! {
@@ -190,52 +529,197 @@ impl Component for Entry {
 
         let other_kana = iter(
             seq(
-                self.readings.iter().filter(|c| c.is_search_only()),
+                self.readings
+                    .iter()
+                    .filter(|c| show_rare && c.is_search_only()),
                 |e, not_last| render_reading(ctx, e, not_last),
             ),
             |iter| {
                 html! {
-                    html!(<div class="block row"><span>{"Other kana"}</span>{colon()}{spacing()}{for iter}</div>)
+                    html!(<div class="block row"><span>{t(Text::OtherKana)}</span>{colon()}{spacing()}{for iter}</div>)
                 }
             },
         );
 
         let other_kanji = iter(
             seq(
-                self.combined.iter().filter(|c| !c.is_common()),
-                |e, not_last| render_combined(ctx, e, not_last),
+                self.combined
+                    .iter()
+                    .filter(|c| !c.is_common() && (show_rare || !c.is_rarely_used())),
+                |e, not_last| render_combined(ctx, e, true, not_last),
             ),
             |iter| {
                 html! {
-                    html!(<div class="block row"><span>{"Other kanji"}</span>{colon()}{spacing()}{for iter}</div>)
+                    html!(<div class="block row"><span>{t(Text::OtherKanji)}</span>{colon()}{spacing()}{for iter}</div>)
                 }
             },
         );
 
+        let rare = self.readings.iter().filter(|r| r.is_rarely_used()).count()
+            + self
+                .combined
+                .iter()
+                .filter(|c| !c.is_common() && c.is_rarely_used())
+                .count();
+
+        let toggle_rare = (rare > 0).then(|| {
+            let onclick = ctx.link().callback(|_| Msg::ToggleRare);
+
+            let text = if show_rare {
+                t(Text::HideRareSpellings).to_owned()
+            } else {
+                i18n::show_rare_spellings(rare)
+            };
+
+            html! {
+                <div class="block row">
+                    <button class="btn btn-sm" title={t(Text::RareSpellingsHelp)} {onclick}>{text}</button>
+                </div>
+            }
+        });
+
         let senses = iter(
-            entry.senses.iter().map(|s| self.render_sense(ctx, s)),
+            entry
+                .senses
+                .iter()
+                .enumerate()
+                .map(|(index, s)| self.render_sense(ctx, index, s)),
             |iter| html!(<ul class="block block-lg list-numerical">{for iter}</ul>),
         );
 
-        let sequence = (!ctx.props().embed).then(|| html! {
-            <div class="block block row entry-sequence"><a href={format!("/api/entry/{}", entry.sequence)} target="_api">{format!("#{}", entry.sequence)}</a></div>
+        let dictionary = ctx
+            .props()
+            .dictionary
+            .clone()
+            .map(|dictionary| html!(<c::DictionaryBadge {dictionary} />));
+
+        let sequence = (!ctx.props().embed).then(|| {
+            let is_pinned = self.pinned.is_pinned(entry.sequence as u32);
+            let onpin = ctx.link().callback(|_: MouseEvent| Msg::TogglePin);
+
+            let (pin, title) = if is_pinned {
+                (t(Text::Unpin), t(Text::UnpinHelp))
+            } else {
+                (t(Text::Pin), t(Text::PinHelp))
+            };
+
+            let compare = (is_pinned && self.pinned.len() > 1).then(|| html! {
+                <Link<crate::Route> classes="clickable" to={crate::Route::Compare}>{format!("{} {}", t(Text::Compare), self.pinned.len())}</Link<crate::Route>>
+            });
+
+            let mine = ctx.props().sentence.as_ref().map(|sentence| {
+                let title = format!("{} {sentence}", t(Text::MineFrom));
+
+                match &self.mined {
+                    Some(card) => html! {
+                        <Link<crate::Route> classes="clickable" to={crate::Route::Mining}>{format!("{} #{}", t(Text::Mined), card.id)}</Link<crate::Route>>
+                    },
+                    None => {
+                        let onclick = ctx.link().callback(|_: MouseEvent| Msg::Mine);
+                        html!(<button class="btn btn-sm" disabled={self.mining} {title} {onclick}>{t(Text::Mine)}</button>)
+                    }
+                }
+            });
+
+            let anki = ctx.props().anki.then(|| match &self.anki_notes {
+                Some(notes) if !notes.is_empty() => {
+                    let title = format!("{} {}", t(Text::AnkiNotesForPhrase), notes.iter().map(u64::to_string).collect::<Vec<_>>().join(", "));
+                    html!(<span {title}>{format!("✓ {}", t(Text::InAnki))}</span>)
+                }
+                _ => {
+                    let onclick = ctx.link().callback(|_: MouseEvent| Msg::AddToAnki);
+                    let disabled = self.adding_to_anki || self.anki_notes.is_none();
+                    html!(<button class="btn btn-sm" {disabled} {onclick}>{t(Text::AddToAnki)}</button>)
+                }
+            });
+
+            html! {
+                <div class="block block row row-spaced entry-sequence">
+                    <a href={base::href(&format!("/api/entry/{}", entry.sequence))} target="_api">{format!("#{}", entry.sequence)}</a>
+                    <span class="clickable" role="button" tabindex="0" {title} onclick={onpin}>{pin}</span>
+                    {for compare}
+                    {for mine}
+                    {for anki}
+                    <Link<crate::Route> classes="clickable" to={crate::Route::Graph { seq: entry.sequence as u32 }}>{t(Text::RelatedWords)}</Link<crate::Route>>
+                </div>
+            }
         });
 
+        let related = (!ctx.props().embed).then(|| self.render_related(ctx));
+        let annotation = self.render_annotation(ctx);
+
+        let difficulty = ctx.props().difficulty.map(|d| {
+            let class = classes!("difficulty", format!("difficulty-{}", d.level));
+            let title = format!("{} {:.0}%", t(Text::EstimatedDifficulty), d.score * 100.0);
+
+            html! {
+                <div class="block row entry-difficulty">
+                    <span {class} {title}>{format!("{} {} / {}", t(Text::Difficulty), d.level, difficulty::MAX_LEVEL)}</span>
+                </div>
+            }
+        });
+
+        let is_noun = entry
+            .senses
+            .iter()
+            .any(|s| s.pos.iter().any(|p| p.group() == PartOfSpeechGroup::Noun));
+
+        let counters = (!ctx.props().embed && is_noun).then(
+            || html!(<c::Counters ws={ctx.props().ws.clone()} sequence={entry.sequence as u32} />),
+        );
+
+        let recordings = (!ctx.props().embed).then(
+            || html!(<c::Recordings ws={ctx.props().ws.clone()} sequence={entry.sequence} />),
+        );
+
+        // Screen readers announce the entry by how the phrase is usually
+        // written.
+        let label = entry
+            .kanji_elements
+            .first()
+            .map(|k| k.text.clone())
+            .or_else(|| entry.reading_elements.first().map(|r| r.text.clone()));
+
         html! {
-            <div class="block block-lg entry">
+            <div class="block block-lg entry" role="article" aria-label={label}>
+                {for dictionary}
                 {sequence}
+                {for difficulty}
+                {for skeleton}
                 {for extras}
                 {for reading}
                 {for common}
+                {annotation}
                 {for senses}
                 {for other_kana}
                 {for other_kanji}
+                {for toggle_rare}
+                {for related}
+                {for counters}
+                {for recordings}
             </div>
         }
     }
 }
 
 impl Entry {
+    /// Look up the notes in Anki for the entry.
+    fn load_anki_notes(&mut self, ctx: &Context<Self>) {
+        if !ctx.props().anki || ctx.props().embed {
+            return;
+        }
+
+        self.pending_anki = ctx.props().ws.request(
+            api::AnkiNotesRequest {
+                sequence: ctx.props().entry.sequence,
+            },
+            ctx.link().callback(|result| match result {
+                Ok(api::AnkiNotesResponse { notes }) => Msg::AnkiNotes(notes),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
     fn refresh_entry(&mut self, ctx: &Context<Self>) {
         self.combined.clear();
         self.readings.clear();
@@ -275,11 +759,157 @@ impl Entry {
         }
     }
 
-    fn render_sense(&self, ctx: &Context<Self>, s: &OwnedSense) -> Html {
+    fn render_annotation(&self, ctx: &Context<Self>) -> Html {
+        if self.editing {
+            let onnote = ctx.link().batch_callback(|e: InputEvent| {
+                let input = e.target_dyn_into::<HtmlTextAreaElement>()?;
+                Some(Msg::Note(input.value()))
+            });
+
+            let ontags = ctx.link().batch_callback(|e: InputEvent| {
+                let input = e.target_dyn_into::<HtmlInputElement>()?;
+                Some(Msg::Tags(input.value()))
+            });
+
+            let onsave = ctx.link().callback(|_: MouseEvent| Msg::SaveAnnotation);
+            let oncancel = ctx.link().callback(|_: MouseEvent| Msg::CancelAnnotation);
+
+            return html! {
+                <div class="block entry-annotation">
+                    <textarea class="block" placeholder={t(Text::Note)} value={self.note.clone()} oninput={onnote} />
+                    <div class="block row row-spaced">
+                        <input type="text" placeholder={t(Text::TagsHelp)} value={self.tags.clone()} oninput={ontags} />
+                        <button class="btn primary" onclick={onsave}>{t(Text::Save)}</button>
+                        <button class="btn" onclick={oncancel}>{t(Text::Cancel)}</button>
+                    </div>
+                </div>
+            };
+        }
+
+        let onedit = ctx.link().callback(|_: MouseEvent| Msg::EditAnnotation);
+
+        let Some(annotation) = &self.annotation else {
+            if ctx.props().embed {
+                return Html::default();
+            }
+
+            return html!(<div class="block row"><span class="clickable" role="button" tabindex="0" onclick={onedit}>{t(Text::AddNote)}</span></div>);
+        };
+
+        let note = (!annotation.note.is_empty())
+            .then(|| html!(<div class="block entry-note">{annotation.note.clone()}</div>));
+
+        let tags = annotation.tags.iter().map(|tag| {
+            let onclick = ctx.link().callback({
+                let query = format!("tag:{tag}");
+                move |_: MouseEvent| Msg::Change(query.clone(), None)
+            });
+
+            html!(<span class="chip clickable" role="button" tabindex="0" {onclick}>{format!("#{tag}")}</span>)
+        });
+
+        html! {
+            <div class="block entry-annotation">
+                {for note}
+                <div class="block row chips">
+                    {for tags}
+                    <span class="clickable" role="button" tabindex="0" onclick={onedit}>{t(Text::EditNote)}</span>
+                </div>
+            </div>
+        }
+    }
+
+    fn render_related(&self, ctx: &Context<Self>) -> Html {
+        let Some(related) = &self.related else {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::LoadRelated);
+            return html!(<div class="block row"><span class="clickable" role="button" tabindex="0" {onclick}>{t(Text::RelatedWords)}</span></div>);
+        };
+
+        if related.is_empty() {
+            return html!(<div class="block row related"><span>{t(Text::NoRelatedWords)}</span></div>);
+        }
+
+        let words = seq(related.iter(), |entry, not_last| {
+            let text = entry
+                .kanji_elements
+                .first()
+                .map(|k| k.text.as_str())
+                .or_else(|| entry.reading_elements.first().map(|r| r.text.as_str()))
+                .unwrap_or_default()
+                .to_owned();
+
+            let title = entry
+                .senses
+                .first()
+                .and_then(|s| s.gloss.first())
+                .map(|g| g.text.clone());
+
+            let onclick = ctx.link().callback({
+                let text = text.clone();
+                move |_: MouseEvent| Msg::Change(text.clone(), None)
+            });
+
+            html!(<><span class="text clickable" role="button" tabindex="0" {title} {onclick}>{text}</span>{for not_last.then(comma)}</>)
+        });
+
+        html! {
+            <div class="block row related"><span>{t(Text::RelatedWords)}</span>{colon()}{spacing()}{for words}</div>
+        }
+    }
+
+    fn render_sense_examples(&self, ctx: &Context<Self>, index: usize) -> Html {
+        let Some(examples) = self.sense_examples.get(&index) else {
+            return Html::default();
+        };
+
+        let more = (examples.total > examples.examples.len()).then(|| {
+            let onclick = ctx
+                .link()
+                .callback(move |_: MouseEvent| Msg::MoreSenseExamples(index));
+
+            html! {
+                <div class="block row">
+                    <span>{i18n::showing(examples.examples.len(), examples.total, Shown::Examples)}</span>
+                    {spacing()}
+                    <span class="clickable" role="button" tabindex="0" {onclick}>{t(Text::ShowMore)}</span>
+                </div>
+            }
+        });
+
+        let examples = examples.examples.iter().map(|e| {
+            let onclick = ctx.link().callback({
+                let text = e.text.clone();
+                let translation = e.translation.clone();
+                move |_: MouseEvent| Msg::Change(text.clone(), Some(translation.clone()))
+            });
+
+            html! {
+                <div class="block row entry-example">
+                    <span class="clickable" role="button" tabindex="0" {onclick}>{&e.text}</span>
+                    {colon()}
+                    <span>{&e.translation}</span>
+                </div>
+            }
+        });
+
+        html!(<div class="block entry-examples">{for examples}{for more}</div>)
+    }
+
+    fn render_sense(&self, ctx: &Context<Self>, index: usize, s: &OwnedSense) -> Html {
+        let display = &self.display;
+
         let info = s
             .info
             .as_ref()
-            .map(|info| html!(<div class="block row sense-info">{info}</div>));
+            .filter(|_| display.is_shown(Metadata::Info))
+            .map(|info| {
+                html! {
+                    <div class="block row sense-info">
+                        <span class="bullet sm metadata-label">{Metadata::Info.ident()}</span>
+                        <span>{info}</span>
+                    </div>
+                }
+            });
 
         let stags = seq(s.stagr.iter().chain(s.stagk.iter()), |text, not_last| {
             let stag = if let Some(c) = self.combined.iter().find(|c| c.is_kanji(text)) {
@@ -293,20 +923,20 @@ impl Entry {
                 move |_: MouseEvent| Msg::Change(text.clone(), None)
             });
 
-            html!(<><span class="sense-stag clickable" {onclick}>{stag}{for not_last.then(comma)}</span></>)
+            html!(<><span class="sense-stag clickable" role="button" tabindex="0" {onclick}>{stag}{for not_last.then(comma)}</span></>)
         });
 
         let stag = iter(stags, |stags| {
             html! {
-                <div class="block row sense-stags"><span>{"Applies to"}</span>{colon()}{for stags}</div>
+                <div class="block row sense-stags"><span>{t(Text::AppliesTo)}</span>{colon()}{for stags}</div>
             }
         });
 
         let glossary = texts(s.gloss.iter().map(|gloss| &gloss.text), None);
-        let bullets = bullets!(s.pos, "sm")
-            .chain(bullets!(s.misc, "sm"))
-            .chain(bullets!(s.dialect, "sm"))
-            .chain(bullets!(s.field, "sm"));
+        let bullets = metadata!(display, Metadata::PartOfSpeech, s.pos)
+            .chain(metadata!(display, Metadata::Misc, s.misc))
+            .chain(metadata!(display, Metadata::Dialect, s.dialect))
+            .chain(metadata!(display, Metadata::Field, s.field));
 
         let bullets = iter(
             bullets,
@@ -323,12 +953,15 @@ impl Entry {
             |iter| html!(<div class="block entry-examples">{for iter}</div>),
         );
 
+        let sense_examples = (!ctx.props().embed).then(|| self.render_sense_examples(ctx, index));
+
         html! {
             <li class="section entry-sense">
                 {for glossary}
                 {for info}
                 {for stag}
                 {for examples}
+                {for sense_examples}
             </li>
         }
     }
@@ -368,7 +1001,7 @@ impl Entry {
                 html!(<>{text}</>)
             };
 
-            html!(<><span class="text highlight clickable" {onclick}>{text}</span>{for not_last.then(comma)}</>)
+            html!(<><span class="text highlight clickable" role="button" tabindex="0" {onclick}>{text}</span>{for not_last.then(comma)}</>)
         });
 
         let sent = languages(&example.sentences).map(|l: Languages<'_>| {
@@ -380,7 +1013,7 @@ impl Entry {
 
             let eng = l.eng.map(|text| html!(<span>{text}</span>));
 
-            html!(<>{colon()}<span class="clickable" {onclick}>{l.jpn}</span>{for eng}</>)
+            html!(<>{colon()}<span class="clickable" role="button" tabindex="0" {onclick}>{l.jpn}</span>{for eng}</>)
         });
 
         html! {
@@ -396,10 +1029,7 @@ fn find_inflection<'a>(
 ) -> Option<(Inflection, &'a OwnedInflections)> {
     match source {
         Source::Inflection { data } => {
-            let Some((_, inflections)) = inflections.iter().find(|(r, _)| *r == data.reading)
-            else {
-                return None;
-            };
+            let (_, inflections) = inflections.iter().find(|(r, _)| *r == data.reading)?;
 
             Some((data.inflection, inflections))
         }
@@ -422,10 +1052,12 @@ fn render_extra(
 
     let inflection_html = render_inflection(ctx, index, inflection, filter, inflections);
     let tutorials = render_tutorials(inflection, filter);
+    let explanation = i18n::inflection(inflection ^ filter);
 
     Some(html! {
         <div class="block notice">
-            <div class="block block-sm title">{"Result based on inflection:"}</div>
+            <div class="block block-sm title">{t(Text::InflectionResult)}</div>
+            <div class="block block-sm explanation">{explanation}</div>
             <div class="block block-sm row bullets">{for inflection_html}</div>
             {tutorials}
             {for word}
@@ -462,15 +1094,16 @@ fn render_inflection<'a>(
             .link()
             .batch_callback(move |_: MouseEvent| exists.then_some(Msg::ToggleForm(index, f)));
 
-        Some(html!(<span {class} {onclick} title={f.title()}>{f.describe()}</span>))
+        Some(html!(<span {class} role="button" tabindex="0" {onclick} title={f.title()}>{f.describe()}</span>))
     });
 
     let onclick = ctx
         .link()
         .callback(move |_: MouseEvent| Msg::ResetForm(index));
 
-    let reset = (!filter.is_empty())
-        .then(|| html!(<span class="inflection clickable danger" {onclick}>{"Reset"}</span>));
+    let reset = (!filter.is_empty()).then(
+        || html!(<span class="inflection clickable danger" role="button" tabindex="0" {onclick}>{t(Text::Reset)}</span>),
+    );
 
     form.chain(reset)
 }
@@ -497,7 +1130,7 @@ fn render_tutorials(inflection: Inflection, filter: Inflection) -> Html {
         |(f, url), not_last| {
             html! {
                 <>
-                    <a href={url} target="_tutorial" title={format!("Tutorial for {}", f.title())}>{format!("Tutorial for `{}`", f.describe())}</a>
+                    <a href={url} target="_tutorial" title={format!("{} {}", t(Text::TutorialFor), f.title())}>{format!("{} `{}`", t(Text::TutorialFor), f.describe())}</a>
                     {for not_last.then(comma)}
                 </>
             }
@@ -520,9 +1153,12 @@ fn render_reading(ctx: &Context<Entry>, reading: &OwnedReadingElement, not_last:
         move |_: MouseEvent| Msg::Change(text.clone(), None)
     });
 
+    let speak = render_speak(ctx, &reading.text);
+
     html! {
         <>
             <a class="text kanji highlight" {onclick}>{&reading.text}</a>
+            {speak}
             {for bullets}
             {for not_last.then(comma)}
         </>
@@ -532,6 +1168,7 @@ fn render_reading(ctx: &Context<Entry>, reading: &OwnedReadingElement, not_last:
 fn render_combined(
     ctx: &Context<Entry>,
     c @ Combined { kanji, .. }: &Combined,
+    notes: bool,
     not_last: bool,
 ) -> Html {
     let priority = kanji.priority.iter().map(render_priority);
@@ -546,16 +1183,60 @@ fn render_combined(
         move |_: MouseEvent| Msg::Change(text.clone(), None)
     });
 
+    // Spell out why a variant isn't commonly used.
+    let notes = notes
+        .then(|| {
+            let notes = kanji.info.iter().map(|i| i.help()).collect::<Vec<_>>();
+            (!notes.is_empty()).then(
+                || html!(<span class="variant-notes">{format!("({})", notes.join("; "))}</span>),
+            )
+        })
+        .flatten();
+
+    let speak = render_speak(ctx, &c.reading.text);
+
     html! {
         <>
             <a class="text kanji highlight" {onclick} title={romaji(c.furigana())}>{ruby(c.furigana())}</a>
+            {speak}
             {for bullets}
+            {for notes}
             {for not_last.then(comma)}
         </>
     }
 }
 
-fn render_priority(p: &Priority) -> Html {
+/// Button which reads the given reading out loud.
+fn render_speak(ctx: &Context<Entry>, reading: &str) -> Html {
+    if !ctx.props().tts {
+        return Html::default();
+    }
+
+    let onclick = ctx.link().callback({
+        let reading = reading.to_owned();
+        move |_: MouseEvent| Msg::Speak(reading.clone())
+    });
+
+    html!(<span class="clickable speak" role="button" tabindex="0" title={t(Text::ReadOutLoud)} {onclick}>{icon("🔊", t(Text::ReadOutLoud))}</span>)
+}
+
+/// Start reading the given text out loud.
+fn speak(text: &str) -> Result<HtmlAudioElement, Error> {
+    let url = base::url("/api/tts").ok_or("Missing window location")?;
+
+    let query = serde_urlencoded::to_string(api::TtsRequest {
+        text: text.to_owned(),
+    })
+    .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    let audio = HtmlAudioElement::new_with_src(&format!("{url}?{query}"))?;
+    // Failing to play, like when the engine isn't available, is reported
+    // by the browser.
+    let _ = audio.play()?;
+    Ok(audio)
+}
+
+pub(super) fn render_priority(p: &Priority) -> Html {
     html!(<span class={format!("bullet prio-{}", p.category())} title={p.title()}>{p.category()}{p.level()}</span>)
 }
 
@@ -578,3 +1259,12 @@ where
         }
     })
 }
+
+/// Index the best corpus examples provided with the entry by sense.
+fn sense_examples(ctx: &Context<Entry>) -> HashMap<usize, api::OwnedSenseExamples> {
+    ctx.props()
+        .sense_examples
+        .iter()
+        .map(|e| (e.sense, e.clone()))
+        .collect()
+}
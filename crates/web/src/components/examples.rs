@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::rc::Rc;
+
+use lib::{api, kana};
+use yew::prelude::*;
+
+use super::ruby;
+use crate::i18n::{self, t, Shown, Text};
+
+pub enum Msg {
+    ToggleFurigana(usize),
+    Change(String, Option<String>),
+}
+
+#[derive(Properties)]
+pub struct Props {
+    pub embed: bool,
+    pub examples: Rc<[api::OwnedSearchExample]>,
+    pub total: usize,
+    pub onchange: Callback<(String, Option<String>), ()>,
+}
+
+impl PartialEq for Props {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.embed == other.embed
+            && Rc::ptr_eq(&self.examples, &other.examples)
+            && self.total == other.total
+    }
+}
+
+pub struct Examples {
+    /// Examples which have furigana enabled.
+    furigana: HashSet<usize>,
+}
+
+impl Component for Examples {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        Self {
+            furigana: HashSet::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::ToggleFurigana(index) => {
+                if !self.furigana.remove(&index) {
+                    self.furigana.insert(index);
+                }
+
+                true
+            }
+            Msg::Change(text, translation) => {
+                ctx.props().onchange.emit((text, translation));
+                false
+            }
+        }
+    }
+
+    fn changed(&mut self, _: &Context<Self>, _: &Self::Properties) -> bool {
+        self.furigana.clear();
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let examples = &ctx.props().examples;
+
+        let entries = examples.iter().enumerate().map(|(index, e)| {
+            let furigana = self
+                .furigana
+                .contains(&index)
+                .then(|| e.kanji.as_deref().zip(e.reading.as_deref()))
+                .flatten();
+
+            let text = highlighted(&e.text, &e.text_highlights, furigana);
+
+            let translation = e.translation.as_ref().map(|translation| {
+                let translation = highlighted(translation, &e.translation_highlights, None);
+                html!(<div class="block row example-translation">{translation}</div>)
+            });
+
+            let onclick = ctx.link().callback({
+                let text = e.text.clone();
+                let translation = e.translation.clone();
+                move |_: MouseEvent| Msg::Change(text.clone(), translation.clone())
+            });
+
+            let toggle = (e.kanji.is_some() && e.reading.is_some()).then(|| {
+                let onclick = ctx.link().callback(move |_| Msg::ToggleFurigana(index));
+                html!(<button class="btn btn-sm" title={t(Text::ToggleFurigana)} {onclick}>{"ふ"}</button>)
+            });
+
+            html! {
+                <div class="block example">
+                    <div class="block row row-spaced">
+                        <span class="text example-text clickable" {onclick}>{text}</span>
+                        {for toggle}
+                    </div>
+                    {for translation}
+                </div>
+            }
+        });
+
+        let more = (ctx.props().total > examples.len()).then(|| {
+            html! {
+                <div class="block row">
+                    {i18n::showing(examples.len(), ctx.props().total, Shown::Examples)}
+                </div>
+            }
+        });
+
+        let header = (!ctx.props().embed).then(|| html!(<h4>{t(Text::Examples)}</h4>));
+
+        html! {
+            <div class="block block-lg">
+                {header}
+                {for entries}
+                {for more}
+            </div>
+        }
+    }
+}
+
+/// Render text with the given highlighted byte ranges, optionally annotating
+/// occurrences of the given kanji with furigana.
+fn highlighted(text: &str, highlights: &[Range<usize>], furigana: Option<(&str, &str)>) -> Html {
+    let mut output = Vec::new();
+    let mut last = 0;
+
+    for range in highlights {
+        let start = range.start.max(last);
+
+        let (Some(before), Some(highlight)) = (text.get(last..start), text.get(start..range.end))
+        else {
+            continue;
+        };
+
+        if highlight.is_empty() {
+            continue;
+        }
+
+        output.push(annotated(before, furigana));
+        output.push(html!(<span class="highlight-term">{annotated(highlight, furigana)}</span>));
+        last = range.end;
+    }
+
+    output.push(annotated(text.get(last..).unwrap_or_default(), furigana));
+    html!(<>{for output}</>)
+}
+
+/// Annotate occurrences of kanji in the text with furigana.
+fn annotated(text: &str, furigana: Option<(&str, &str)>) -> Html {
+    let Some((kanji, reading)) = furigana else {
+        return html!({ text.to_owned() });
+    };
+
+    let mut output = Vec::new();
+    let mut last = 0;
+
+    for (start, string) in text.match_indices(kanji) {
+        output.push(html!({ text[last..start].to_owned() }));
+        output.push(ruby(kana::Full::new(string, reading, "").furigana()));
+        last = start + string.len();
+    }
+
+    output.push(html!({ text[last..].to_owned() }));
+    html!(<>{for output}</>)
+}
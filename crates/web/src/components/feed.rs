@@ -0,0 +1,559 @@
+//! A feed of captured lines, like the texthooker pages used while reading
+//! visual novels.
+//!
+//! Lines are only ever appended, and any of them can be clicked to look up
+//! the words in it. The feed is stored in session storage so that it survives
+//! navigating to other pages.
+
+use std::rc::Rc;
+use std::str::from_utf8;
+
+use gloo::storage::{SessionStorage, Storage};
+use lib::api;
+use serde::{Deserialize, Serialize};
+use web_sys::window;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::c;
+use crate::display::Display;
+use crate::error::Error;
+use crate::i18n::{self, t, Text};
+use crate::ws;
+use crate::Route;
+
+use super::seq;
+
+/// Key the feed is stored under in session storage.
+const STORAGE_KEY: &str = "jpv-feed";
+/// The maximum number of lines kept in the feed.
+const MAX_LINES: usize = 1000;
+/// The number of phrases shown for a looked up word.
+const PHRASES_LIMIT: usize = 5;
+
+pub(crate) enum Msg {
+    Broadcast(api::OwnedBroadcastKind),
+    SelectLine(usize),
+    TogglePause,
+    ToggleAutoScroll,
+    SelectSource(Option<String>),
+    Clear,
+    Analyze(usize),
+    AnalyzeCycle,
+    AnalyzeResponse(api::OwnedAnalyzeResponse),
+    Search(String),
+    SearchResponse(api::OwnedSearchResponse),
+    Error(Error),
+}
+
+impl From<api::OwnedBroadcastKind> for Msg {
+    #[inline]
+    fn from(broadcast: api::OwnedBroadcastKind) -> Self {
+        Msg::Broadcast(broadcast)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+}
+
+/// A captured line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Line {
+    text: String,
+    /// When the line was captured, in milliseconds since the unix epoch.
+    time: f64,
+    /// The number of characters read in the line.
+    characters: usize,
+    /// The application the line was captured from, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    /// A translation captured along with the line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    translation: Option<String>,
+}
+
+/// The stored feed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Stored {
+    lines: Vec<Line>,
+}
+
+pub(crate) struct Feed {
+    lines: Vec<Line>,
+    /// Lines captured while the feed was paused, which are added once it's
+    /// resumed.
+    queued: Vec<Line>,
+    paused: bool,
+    auto_scroll: bool,
+    /// Set when lines have been added, so that the page is scrolled to them
+    /// once rendered.
+    scroll: bool,
+    /// The selected line.
+    line: Option<usize>,
+    /// Only lines from this source are shown if set.
+    source: Option<String>,
+    query: Rc<str>,
+    analyzed: Rc<[Rc<str>]>,
+    index: usize,
+    analyze_at: Option<usize>,
+    phrases: Vec<api::OwnedSearchPhrase>,
+    pending_search: ws::Request,
+    _listener: ws::Listener,
+}
+
+impl Component for Feed {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let stored = SessionStorage::get::<Stored>(STORAGE_KEY).unwrap_or_default();
+
+        Self {
+            lines: stored.lines,
+            queued: Vec::new(),
+            paused: false,
+            auto_scroll: true,
+            scroll: true,
+            line: None,
+            source: None,
+            query: Rc::from(""),
+            analyzed: Rc::from([]),
+            index: 0,
+            analyze_at: None,
+            phrases: Vec::new(),
+            pending_search: ws::Request::empty(),
+            _listener: ctx.props().ws.listen(ctx),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Broadcast(api::OwnedBroadcastKind::SendClipboardData(clipboard)) => {
+                let Some(text) = clipboard_text(clipboard.ty.as_deref(), &clipboard.data) else {
+                    return false;
+                };
+
+                let last = self.queued.last().or(self.lines.last());
+
+                if last.is_some_and(|line| line.text == text) {
+                    return false;
+                }
+
+                let line = Line {
+                    characters: lib::stats::characters(&text),
+                    text,
+                    time: js_sys::Date::now(),
+                    source: clipboard.source.clone(),
+                    translation: None,
+                };
+
+                self.capture(line);
+            }
+            Msg::Broadcast(api::OwnedBroadcastKind::CapturedLine(captured)) => {
+                let last = self.queued.last().or(self.lines.last());
+
+                if last.is_some_and(|line| line.text == captured.text) {
+                    return false;
+                }
+
+                let line = Line {
+                    characters: lib::stats::characters(&captured.text),
+                    text: captured.text,
+                    time: js_sys::Date::now(),
+                    source: captured.source,
+                    translation: captured.translation,
+                };
+
+                self.capture(line);
+            }
+            Msg::Broadcast(api::OwnedBroadcastKind::RecognizedText(recognized)) => {
+                let last = self.queued.last().or(self.lines.last());
+
+                if last.is_some_and(|line| line.text == recognized.text) {
+                    return false;
+                }
+
+                let line = Line {
+                    characters: lib::stats::characters(&recognized.text),
+                    text: recognized.text,
+                    time: js_sys::Date::now(),
+                    source: recognized.source,
+                    translation: None,
+                };
+
+                self.capture(line);
+            }
+            Msg::Broadcast(..) => {
+                return false;
+            }
+            Msg::SelectLine(n) => {
+                let Some(line) = self.lines.get(n) else {
+                    return false;
+                };
+
+                let query = Rc::from(line.text.as_str());
+                self.clear_lookup();
+                self.line = Some(n);
+                self.query = query;
+            }
+            Msg::TogglePause => {
+                self.paused = !self.paused;
+
+                if !self.paused {
+                    let queued = std::mem::take(&mut self.queued);
+                    self.push(queued);
+                }
+            }
+            Msg::ToggleAutoScroll => {
+                self.auto_scroll = !self.auto_scroll;
+                self.scroll = self.auto_scroll;
+            }
+            Msg::SelectSource(source) => {
+                self.source = source;
+                self.scroll = self.auto_scroll;
+            }
+            Msg::Clear => {
+                self.lines.clear();
+                self.queued.clear();
+                self.line = None;
+                self.clear_lookup();
+                self.save();
+            }
+            Msg::Analyze(i) => {
+                if self.analyze_at != Some(i) {
+                    self.index = 0;
+                }
+
+                self.analyze_at = Some(i);
+
+                self.pending_search = ctx.props().ws.request(
+                    api::AnalyzeRequest {
+                        q: self.query.as_ref().to_owned(),
+                        start: i,
+                        end: None,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::AnalyzeResponse(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+            }
+            Msg::AnalyzeCycle => {
+                if self.analyzed.is_empty() {
+                    return false;
+                }
+
+                self.index = (self.index + 1) % self.analyzed.len();
+                self.search_analyzed(ctx);
+            }
+            Msg::AnalyzeResponse(response) => {
+                self.analyzed = response.data.into_iter().map(|d| d.string.into()).collect();
+                self.index = self.index.min(self.analyzed.len().saturating_sub(1));
+                self.search_analyzed(ctx);
+            }
+            Msg::Search(q) => {
+                self.search(ctx, q);
+                return false;
+            }
+            Msg::SearchResponse(response) => {
+                self.pending_search = ws::Request::empty();
+                self.phrases = response.phrases;
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.pending_search = ws::Request::empty();
+            }
+        }
+
+        true
+    }
+
+    fn rendered(&mut self, _: &Context<Self>, _: bool) {
+        if !std::mem::take(&mut self.scroll) || !self.auto_scroll {
+            return;
+        }
+
+        let Some(window) = window() else {
+            return;
+        };
+
+        if let Some(element) = window.document().and_then(|d| d.document_element()) {
+            window.scroll_to_with_x_and_y(0.0, f64::from(element.scroll_height()));
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                <div id="window-top">
+                    <div class="container">
+                        <span class="left">
+                            <Link<Route> classes="clickable" to={Route::Prompt}>{"🔍"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Reader}>{"📖"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Mining}>{"⛏"}</Link<Route>>
+                        </span>
+                        <span></span>
+                        <span class="title">{t(Text::Feed)}</span>
+                        <span></span>
+                        <span class="right">{self.view_stats()}</span>
+                    </div>
+                </div>
+
+                <div id="content" class="container feed">
+                    {self.view_controls(ctx)}
+                    {self.view_lines(ctx)}
+                </div>
+            </>
+        }
+    }
+}
+
+impl Feed {
+    /// Add a captured line, or queue it while paused.
+    fn capture(&mut self, line: Line) {
+        if self.paused {
+            self.queued.push(line);
+        } else {
+            self.push([line]);
+        }
+    }
+
+    /// Append lines to the feed.
+    fn push(&mut self, lines: impl IntoIterator<Item = Line>) {
+        let before = self.lines.len();
+        self.lines.extend(lines);
+
+        if self.lines.len() == before {
+            return;
+        }
+
+        if let Some(excess) = self.lines.len().checked_sub(MAX_LINES) {
+            self.lines.drain(..excess);
+
+            self.line = match self.line {
+                Some(line) if line >= excess => Some(line - excess),
+                Some(..) => {
+                    self.clear_lookup();
+                    None
+                }
+                None => None,
+            };
+        }
+
+        self.scroll = true;
+        self.save();
+    }
+
+    fn save(&self) {
+        let stored = Stored {
+            lines: self.lines.clone(),
+        };
+
+        if let Err(error) = SessionStorage::set(STORAGE_KEY, &stored) {
+            log::warn!("Failed to store feed: {error}");
+        }
+    }
+
+    fn clear_lookup(&mut self) {
+        self.analyzed = Rc::from([]);
+        self.index = 0;
+        self.analyze_at = None;
+        self.phrases.clear();
+        self.pending_search = ws::Request::empty();
+    }
+
+    fn search_analyzed(&mut self, ctx: &Context<Self>) {
+        if let Some(q) = self.analyzed.get(self.index) {
+            self.search(ctx, q.as_ref().to_owned());
+        } else {
+            self.phrases.clear();
+        }
+    }
+
+    fn search(&mut self, ctx: &Context<Self>, q: String) {
+        self.pending_search = ctx.props().ws.request(
+            api::SearchRequest {
+                q,
+                group: false,
+                chunk: None,
+            },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::SearchResponse(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    fn view_stats(&self) -> Html {
+        let characters = self.lines.iter().map(|line| line.characters).sum::<usize>();
+
+        let elapsed = match (self.lines.first(), self.lines.last()) {
+            (Some(first), Some(last)) => (last.time - first.time) / 1000.0,
+            _ => 0.0,
+        };
+
+        // Speed is only meaningful once a bit of time has passed.
+        let speed = (elapsed >= 60.0).then(|| {
+            let per_hour = characters as f64 / elapsed * 3600.0;
+            html!(<span title={t(Text::FeedSpeedHelp)}>{i18n::per_hour(per_hour)}</span>)
+        });
+
+        html! {
+            <span class="row row-spaced feed-stats">
+                <span title={t(Text::LinesCapturedHelp)}>{i18n::lines(self.lines.len())}</span>
+                <span title={t(Text::CharactersReadHelp)}>{i18n::characters(characters)}</span>
+                {for speed}
+            </span>
+        }
+    }
+
+    fn view_controls(&self, ctx: &Context<Self>) -> Html {
+        let onpause = ctx.link().callback(|_| Msg::TogglePause);
+
+        let pause = if self.paused {
+            let text = if self.queued.is_empty() {
+                t(Text::Resume).to_owned()
+            } else {
+                format!("{} ({})", t(Text::Resume), self.queued.len())
+            };
+
+            html!(<button class="btn primary" onclick={onpause} title={t(Text::ResumeHelp)}>{text}</button>)
+        } else {
+            html!(<button class="btn" onclick={onpause} title={t(Text::PauseHelp)}>{t(Text::Pause)}</button>)
+        };
+
+        let onscroll = ctx.link().callback(|_| Msg::ToggleAutoScroll);
+        let onclear = ctx.link().callback(|_| Msg::Clear);
+
+        let mut sources = self
+            .lines
+            .iter()
+            .filter_map(|line| line.source.as_deref())
+            .collect::<Vec<_>>();
+
+        sources.sort();
+        sources.dedup();
+
+        let sources = (!sources.is_empty()).then(|| {
+            let onchange = ctx.link().batch_callback(|e: Event| {
+                let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+                let value = select.value();
+                Some(Msg::SelectSource((!value.is_empty()).then_some(value)))
+            });
+
+            let options = sources.into_iter().map(|source| {
+                let selected = self.source.as_deref() == Some(source);
+                html!(<option value={source.to_owned()} {selected}>{source}</option>)
+            });
+
+            html! {
+                <select aria-label={t(Text::Sources)} title={t(Text::SourceHelp)} {onchange}>
+                    <option value="" selected={self.source.is_none()}>{t(Text::AllSources)}</option>
+                    {for options}
+                </select>
+            }
+        });
+
+        html! {
+            <div class="block block-lg row row-spaced">
+                {pause}
+                <input id="feed-auto-scroll" type="checkbox" checked={self.auto_scroll} onchange={onscroll} />
+                <label for="feed-auto-scroll">{t(Text::AutoScroll)}</label>
+                {sources}
+                <button class="end btn danger" disabled={self.lines.is_empty()} onclick={onclear}>{t(Text::Clear)}</button>
+            </div>
+        }
+    }
+
+    fn view_lines(&self, ctx: &Context<Self>) -> Html {
+        if self.lines.is_empty() {
+            return html!(<div class="block">{t(Text::NoFeedLines)}</div>);
+        }
+
+        let mut lookup = Some(self.view_lookup(ctx));
+
+        let blur = (!Display::load().reveal_translations).then_some("blurred");
+
+        let lines = self.lines.iter().enumerate();
+
+        let lines = lines.filter(|(_, line)| {
+            self.source.is_none() || line.source.as_deref() == self.source.as_deref()
+        });
+
+        let lines = lines.map(|(n, line)| {
+            let active = self.line == Some(n);
+            let lookup = active.then(|| lookup.take()).flatten();
+            let onclick = ctx.link().callback(move |_| Msg::SelectLine(n));
+
+            let class = classes!(
+                "block",
+                "feed-line",
+                "clickable",
+                active.then_some("active")
+            );
+
+            html! {
+                <>
+                    <div {class} {onclick}>
+                        <span lang="ja">{line.text.clone()}</span>
+                        {for line.source.as_deref().filter(|_| self.source.is_none()).map(|source| html!(<span class="feed-source">{source.to_owned()}</span>))}
+                        {for line.translation.as_deref().map(|translation| html!(<div class={classes!("feed-translation", blur)}>{translation.to_owned()}</div>))}
+                    </div>
+                    {for lookup}
+                </>
+            }
+        });
+
+        html!(<div class="block block-lg">{for lines}</div>)
+    }
+
+    fn view_lookup(&self, ctx: &Context<Self>) -> Html {
+        let on_analyze = ctx.link().callback(Msg::Analyze);
+        let on_analyze_cycle = ctx.link().callback(|_| Msg::AnalyzeCycle);
+
+        let phrases = (!self.phrases.is_empty()).then(|| {
+            let phrases = self.phrases.iter().take(PHRASES_LIMIT).map(|e| {
+                let onchange = ctx.link().callback(|(input, _)| Msg::Search(input));
+                html!(<c::Entry ws={ctx.props().ws.clone()} embed={true} sources={e.key.sources.clone()} entry={e.phrase.clone()} sense_examples={e.sense_examples.clone()} difficulty={e.difficulty} annotation={e.annotation.clone()} {onchange} />)
+            });
+
+            let phrases = seq(phrases, |entry, not_last| {
+                if not_last {
+                    html!(<>{entry}<div class="entry-separator" /></>)
+                } else {
+                    entry
+                }
+            });
+
+            html!(<div class="block block-lg lookup">{for phrases}</div>)
+        });
+
+        html! {
+            <>
+                <div class="block feed-lookup">
+                    <c::AnalyzeToggle query={self.query.clone()} analyzed={self.analyzed.clone()} index={self.index} analyze_at={self.analyze_at} {on_analyze} {on_analyze_cycle} />
+                </div>
+                {phrases}
+            </>
+        }
+    }
+}
+
+/// The text of captured clipboard data, if it's text.
+fn clipboard_text(ty: Option<&str>, data: &[u8]) -> Option<String> {
+    let text = if matches!(ty, Some("application/json")) || data.starts_with(b"{") {
+        match serde_json::from_slice::<api::CaptureJson>(data) {
+            Ok(json) => json.text,
+            Err(..) => from_utf8(data).ok()?.to_owned(),
+        }
+    } else if ty.is_none_or(|ty| ty.starts_with("text/")) {
+        from_utf8(data).ok()?.to_owned()
+    } else {
+        return None;
+    };
+
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_owned())
+}
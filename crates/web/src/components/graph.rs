@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+use lib::api;
+use lib::graph::{self, Graph, Relation};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::error::Error;
+use crate::i18n::{t, Text};
+use crate::ws;
+use crate::Route;
+
+/// Distance between the rings phrases at different depths are placed on.
+const RING: f64 = 160.0;
+/// Margin around the outermost ring.
+const MARGIN: f64 = 100.0;
+/// Radius of the circle representing a phrase.
+const NODE_RADIUS: f64 = 6.0;
+/// The depth a graph is initially built to.
+const DEFAULT_DEPTH: usize = 2;
+
+pub(crate) enum Msg {
+    Depth(usize),
+    Graph(Graph),
+    Error(Error),
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+    pub(crate) seq: u32,
+}
+
+pub(crate) struct GraphView {
+    depth: usize,
+    graph: Option<Graph>,
+    pending: ws::Request,
+}
+
+impl Component for GraphView {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut this = Self {
+            depth: DEFAULT_DEPTH,
+            graph: None,
+            pending: ws::Request::empty(),
+        };
+
+        this.refresh(ctx);
+        this
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Depth(depth) => {
+                self.depth = depth;
+                self.refresh(ctx);
+            }
+            Msg::Graph(graph) => {
+                self.pending = ws::Request::empty();
+                self.graph = Some(graph);
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.graph = Some(Graph::default());
+            }
+        }
+
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _: &Self::Properties) -> bool {
+        self.refresh(ctx);
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let depths = (1..=graph::MAX_DEPTH).map(|depth| {
+            let class = classes! {
+                "chip",
+                "clickable",
+                (depth == self.depth).then_some("active"),
+            };
+
+            let onclick = ctx.link().callback(move |_: MouseEvent| Msg::Depth(depth));
+            html!(<span {class} {onclick}>{depth}</span>)
+        });
+
+        html! {
+            <>
+                <div id="window-top">
+                    <div class="container">
+                        <span class="left">
+                            <Link<Route> classes="clickable" to={Route::Prompt}>{"🔍"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Reader}>{"📖"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Quiz}>{"✍"}</Link<Route>>
+                        </span>
+                        <span></span>
+                        <span class="title">{t(Text::RelatedWords)}</span>
+                        <span></span>
+                        <span class="right"></span>
+                    </div>
+                </div>
+
+                <div id="content" class="container graph">
+                    <div class="block row chips"><span>{t(Text::Depth)}</span>{for depths}</div>
+                    {legend()}
+                    {self.view_graph(ctx)}
+                </div>
+            </>
+        }
+    }
+}
+
+impl GraphView {
+    fn refresh(&mut self, ctx: &Context<Self>) {
+        self.graph = None;
+
+        self.pending = ctx.props().ws.request(
+            api::GraphRequest {
+                seq: ctx.props().seq,
+                depth: self.depth,
+            },
+            ctx.link().callback(|result| match result {
+                Ok(graph) => Msg::Graph(graph),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    fn view_graph(&self, ctx: &Context<Self>) -> Html {
+        let Some(graph) = &self.graph else {
+            return html!(<div class="block row"><div class="spinner">{t(Text::Loading)}</div></div>);
+        };
+
+        let Some(center) = graph.nodes.first() else {
+            return html!(<div class="block row">{t(Text::NoSuchPhrase)}</div>);
+        };
+
+        if graph.edges.is_empty() {
+            return html!(<div class="block row">{format!("{} {}", t(Text::NoRelatedWordsFor), center.text)}</div>);
+        }
+
+        let positions = layout(graph);
+        let size = graph
+            .nodes
+            .iter()
+            .map(|n| n.depth)
+            .max()
+            .unwrap_or_default() as f64
+            * RING
+            + MARGIN;
+
+        let edges = graph.edges.iter().flat_map(|edge| {
+            let (x1, y1) = *positions.get(&edge.from)?;
+            let (x2, y2) = *positions.get(&edge.to)?;
+            let class = classes!("graph-edge", format!("graph-{}", ident(edge.relation)));
+
+            Some(html! {
+                <line {class} x1={x1.to_string()} y1={y1.to_string()} x2={x2.to_string()} y2={y2.to_string()}>
+                    <title>{edge.relation.title()}</title>
+                </line>
+            })
+        });
+
+        let navigator = ctx.link().navigator();
+
+        let nodes = graph.nodes.iter().flat_map(|node| {
+            let (x, y) = *positions.get(&node.sequence)?;
+            let seq = node.sequence;
+
+            let onclick = navigator.clone().map(|navigator| {
+                Callback::from(move |_: MouseEvent| navigator.push(&Route::Graph { seq }))
+            });
+
+            let class = classes!("graph-node", (node.depth == 0).then_some("graph-center"));
+
+            let title = match &node.reading {
+                Some(reading) => format!("{}（{}）: {}", node.text, reading, node.gloss),
+                None => format!("{}: {}", node.text, node.gloss),
+            };
+
+            Some(html! {
+                <g {class} {onclick}>
+                    <title>{title}</title>
+                    <circle cx={x.to_string()} cy={y.to_string()} r={NODE_RADIUS.to_string()} />
+                    <text x={x.to_string()} y={(y - NODE_RADIUS * 2.0).to_string()}>{node.text.clone()}</text>
+                </g>
+            })
+        });
+
+        let view_box = format!("{} {} {} {}", -size, -size, size * 2.0, size * 2.0);
+
+        let href = serde_urlencoded::to_string([("q", center.text.as_str())])
+            .ok()
+            .map(|query| format!("/?{query}"));
+
+        html! {
+            <>
+                <div class="block row"><a {href}>{format!("{} {}", t(Text::LookUp), center.text)}</a></div>
+                <svg class="block block-lg graph-view" viewBox={view_box}>
+                    {for edges}
+                    {for nodes}
+                </svg>
+            </>
+        }
+    }
+}
+
+/// Place phrases on rings around the phrase the graph was built from, where
+/// each ring holds the phrases at one depth.
+fn layout(graph: &Graph) -> HashMap<u32, (f64, f64)> {
+    let mut rings = HashMap::<usize, Vec<u32>>::new();
+
+    for node in &graph.nodes {
+        rings.entry(node.depth).or_default().push(node.sequence);
+    }
+
+    let mut positions = HashMap::new();
+
+    for (depth, sequences) in rings {
+        let radius = depth as f64 * RING;
+        // Rotate each ring a bit so that edges between rings overlap less.
+        let offset = depth as f64 * 0.5;
+
+        for (index, sequence) in sequences.iter().enumerate() {
+            let angle = TAU * index as f64 / sequences.len() as f64 + offset;
+            positions.insert(*sequence, (radius * angle.cos(), radius * angle.sin()));
+        }
+    }
+
+    positions
+}
+
+fn ident(relation: Relation) -> &'static str {
+    match relation {
+        Relation::Reference => "reference",
+        Relation::Antonym => "antonym",
+        Relation::Related => "related",
+    }
+}
+
+fn legend() -> Html {
+    let relations = [Relation::Reference, Relation::Antonym, Relation::Related]
+        .into_iter()
+        .map(|relation| {
+            let class = classes!("graph-legend", format!("graph-{}", ident(relation)));
+            html!(<span {class}>{relation.title()}</span>)
+        });
+
+    html!(<div class="block row row-spaced">{for relations}</div>)
+}
@@ -0,0 +1,308 @@
+//! Sentences mined from captured text, which can be sent to Anki.
+
+use std::collections::HashSet;
+
+use lib::api;
+use lib::mining::Card;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::base;
+use crate::display::Display;
+use crate::error::Error;
+use crate::i18n::{t, Text};
+use crate::ws;
+use crate::Route;
+
+use super::spacing;
+
+pub(crate) enum Msg {
+    Mined(api::MinedResponse),
+    GetConfig(Box<api::GetConfigResult>),
+    Export(Vec<u64>, bool),
+    Exported(api::ExportMinedResponse),
+    Delete(u64),
+    Deleted(u64),
+    ToggleScreenshots,
+    Error(Error),
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+}
+
+pub(crate) struct Mining {
+    cards: Option<Vec<Card>>,
+    /// Cards which weren't sent since their words are already in Anki.
+    duplicates: HashSet<u64>,
+    /// Whether notes can be added to Anki, once the configuration is loaded.
+    anki: Option<bool>,
+    display: Display,
+    exporting: bool,
+    error: Option<Error>,
+    pending: ws::Request,
+    pending_config: ws::Request,
+}
+
+impl Component for Mining {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let pending = ctx.props().ws.request(
+            api::MinedRequest,
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::Mined(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+
+        let pending_config = ctx.props().ws.request(
+            api::GetConfig,
+            ctx.link().callback(|result| match result {
+                Ok(state) => Msg::GetConfig(Box::new(state)),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+
+        Self {
+            cards: None,
+            duplicates: HashSet::new(),
+            anki: None,
+            display: Display::load(),
+            exporting: false,
+            error: None,
+            pending,
+            pending_config,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Mined(response) => {
+                self.pending = ws::Request::empty();
+                self.exporting = false;
+                self.cards = Some(response.cards);
+            }
+            Msg::GetConfig(state) => {
+                self.pending_config = ws::Request::empty();
+                self.anki = Some(state.config.anki.enabled);
+            }
+            Msg::Export(ids, allow_duplicates) => {
+                self.exporting = true;
+                self.error = None;
+
+                for id in &ids {
+                    self.duplicates.remove(id);
+                }
+
+                self.pending = ctx.props().ws.request(
+                    api::ExportMinedRequest {
+                        ids,
+                        allow_duplicates,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Exported(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+            }
+            Msg::Exported(response) => {
+                self.pending = ws::Request::empty();
+                self.exporting = false;
+                self.cards = Some(response.cards);
+                self.duplicates.extend(response.duplicates);
+            }
+            Msg::Delete(id) => {
+                self.pending = ctx.props().ws.request(
+                    api::DeleteMinedRequest { id },
+                    ctx.link().callback(move |result| match result {
+                        Ok(api::Empty) => Msg::Deleted(id),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                return false;
+            }
+            Msg::Deleted(id) => {
+                self.pending = ws::Request::empty();
+
+                if let Some(cards) = &mut self.cards {
+                    cards.retain(|card| card.id != id);
+                }
+            }
+            Msg::ToggleScreenshots => {
+                self.display.toggle_screenshots();
+            }
+            Msg::Error(error) => {
+                self.pending = ws::Request::empty();
+                self.pending_config = ws::Request::empty();
+                self.exporting = false;
+                self.error = Some(error);
+            }
+        }
+
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let unexported = self
+            .cards
+            .iter()
+            .flatten()
+            .filter(|card| card.note.is_none() && !self.duplicates.contains(&card.id))
+            .map(|card| card.id)
+            .collect::<Vec<_>>();
+
+        let export_all = (self.anki == Some(true) && !unexported.is_empty()).then(|| {
+            let onclick = ctx
+                .link()
+                .callback(move |_: MouseEvent| Msg::Export(unexported.clone(), false));
+
+            html!(<button class="btn primary" disabled={self.exporting} {onclick} title={t(Text::SendAllToAnkiHelp)}>{t(Text::SendAllToAnki)}</button>)
+        });
+
+        html! {
+            <>
+                <div id="window-top">
+                    <div class="container">
+                        <span class="left">
+                            <Link<Route> classes="clickable" to={Route::Prompt}>{"🔍"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Reader}>{"📖"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Stats}>{"📊"}</Link<Route>>
+                        </span>
+                        <span></span>
+                        <span class="title">{t(Text::MinedSentences)}</span>
+                        <span></span>
+                        <span class="right">{for export_all}</span>
+                    </div>
+                </div>
+
+                <div id="content" class="container mining">
+                    {self.view_cards(ctx)}
+                </div>
+            </>
+        }
+    }
+}
+
+impl Mining {
+    fn view_cards(&self, ctx: &Context<Self>) -> Html {
+        let error = self
+            .error
+            .as_ref()
+            .map(|error| html!(<div class="block block-sm danger">{error.to_string()}</div>));
+
+        let checked = self.display.screenshots;
+        let onchange = ctx.link().callback(|_| Msg::ToggleScreenshots);
+
+        let screenshots = html! {
+            <div class="block row row-spaced">
+                <input id="mining-screenshots" type="checkbox" {checked} {onchange} />
+                <label for="mining-screenshots">{t(Text::AttachScreenshots)}</label>
+            </div>
+        };
+
+        let disabled = (self.anki == Some(false))
+            .then(|| html!(<div class="block">{t(Text::AnkiDisabled)}</div>));
+
+        let cards = match &self.cards {
+            None => {
+                html!(<div class="block row"><div class="spinner">{t(Text::Loading)}</div></div>)
+            }
+            Some(cards) if cards.is_empty() => {
+                html!(<div class="block">{t(Text::NoMinedSentences)}</div>)
+            }
+            Some(cards) => {
+                let cards = cards.iter().rev().map(|card| self.view_card(ctx, card));
+                html!(<>{for cards}</>)
+            }
+        };
+
+        html! {
+            <>
+                {for error}
+                {for disabled}
+                {screenshots}
+                {cards}
+            </>
+        }
+    }
+
+    fn view_card(&self, ctx: &Context<Self>, card: &Card) -> Html {
+        let sentence = match card.highlight() {
+            Some(range) => html! {
+                <>
+                    {&card.sentence[..range.start]}
+                    <b>{&card.sentence[range.clone()]}</b>
+                    {&card.sentence[range.end..]}
+                </>
+            },
+            None => html!({ card.sentence.clone() }),
+        };
+
+        let reading = card
+            .reading
+            .as_ref()
+            .map(|reading| html!(<span class="mining-reading">{format!("[{reading}]")}</span>));
+
+        let glosses = card
+            .glosses
+            .iter()
+            .map(|gloss| html!(<li>{gloss.clone()}</li>));
+
+        let image = card.image.is_some().then(|| {
+            let src = base::href(&format!("/api/mining/{}/image", card.id));
+            html!(<img class="block mining-image" {src} alt={t(Text::CapturedImage)} />)
+        });
+
+        let id = card.id;
+
+        let export = match card.note {
+            Some(note) => {
+                Some(html!(<span title={format!("{} {note}", t(Text::AnkiNote))}>{"✓ Anki"}</span>))
+            }
+            None if self.anki != Some(true) => None,
+            None if self.duplicates.contains(&id) => {
+                let onclick = ctx
+                    .link()
+                    .callback(move |_: MouseEvent| Msg::Export(vec![id], true));
+
+                Some(html! {
+                    <>
+                        <span>{t(Text::AlreadyInAnki)}</span>
+                        <button class="btn btn-sm" disabled={self.exporting} {onclick}>{t(Text::AddAnyway)}</button>
+                    </>
+                })
+            }
+            None => {
+                let onclick = ctx
+                    .link()
+                    .callback(move |_: MouseEvent| Msg::Export(vec![id], false));
+                Some(
+                    html!(<button class="btn btn-sm" disabled={self.exporting} {onclick}>{t(Text::SendToAnki)}</button>),
+                )
+            }
+        };
+
+        let ondelete = ctx.link().callback(move |_: MouseEvent| Msg::Delete(id));
+
+        html! {
+            <div class="block block-lg mining-card">
+                <div class="block mining-sentence" lang="ja">{sentence}</div>
+                <div class="block row" lang="ja">
+                    <span class="mining-word">{card.word.clone()}</span>
+                    {spacing()}
+                    {for reading}
+                </div>
+                <ol class="block list-numerical">{for glosses}</ol>
+                {for image}
+                <div class="block row row-spaced">
+                    {for export}
+                    <button class="end btn btn-sm danger" onclick={ondelete}>{t(Text::Delete)}</button>
+                </div>
+            </div>
+        }
+    }
+}
@@ -1,5 +1,6 @@
 #[macro_use]
 mod tools;
+pub(crate) use self::tools::icon;
 use self::tools::{colon, comma, iter, romaji, ruby, seq, spacing};
 
 pub(crate) mod entry;
@@ -11,14 +12,59 @@ pub(crate) use self::name::Name;
 pub(crate) mod character;
 pub(crate) use self::character::Character;
 
+pub(crate) mod examples;
+pub(crate) use self::examples::Examples;
+
+pub(crate) mod counters;
+pub(crate) use self::counters::Counters;
+
 pub(crate) mod prompt;
 pub(crate) use self::prompt::Prompt;
 
 pub(crate) mod config;
 pub(crate) use self::config::Config;
 
+pub(crate) mod profiles;
+pub(crate) use self::profiles::Profiles;
+
+pub(crate) mod reader;
+pub(crate) use self::reader::Reader;
+
+pub(crate) mod quiz;
+pub(crate) use self::quiz::Quiz;
+
+pub(crate) mod compare;
+pub(crate) use self::compare::Compare;
+
+pub(crate) mod graph;
+pub(crate) use self::graph::GraphView;
+
+pub(crate) mod stats;
+pub(crate) use self::stats::Stats;
+
+pub(crate) mod recordings;
+pub(crate) use self::recordings::Recordings;
+
+pub(crate) mod mining;
+pub(crate) use self::mining::Mining;
+
+pub(crate) mod feed;
+pub(crate) use self::feed::Feed;
+
+pub(crate) mod share;
+pub(crate) use self::share::Share;
+
+pub(crate) mod onboarding;
+pub(crate) use self::onboarding::Onboarding;
+
 pub(crate) use self::analyze_toggle::AnalyzeToggle;
 mod analyze_toggle;
 
 pub(crate) use self::edit_index::EditIndex;
 mod edit_index;
+
+pub(crate) use self::dictionary_badge::DictionaryBadge;
+mod dictionary_badge;
+
+pub(crate) use self::sections::Sections;
+mod sections;
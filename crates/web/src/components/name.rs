@@ -1,6 +1,10 @@
-use lib::{jmnedict, kana};
+use std::rc::Rc;
+
+use lib::{api, jmnedict, kana};
 use yew::prelude::*;
 
+use crate::components as c;
+
 use super::{comma, romaji, ruby, seq};
 
 pub enum Msg {}
@@ -9,6 +13,9 @@ pub enum Msg {}
 pub struct Props {
     pub embed: bool,
     pub entry: jmnedict::OwnedEntry,
+    /// The dictionary the name is from.
+    #[prop_or_default]
+    pub dictionary: Option<Rc<api::Dictionary>>,
 }
 
 pub struct Name;
@@ -55,10 +62,17 @@ impl Component for Name {
 
         let bullets = bullets!(entry.name_types, "sm");
 
+        let dictionary = ctx
+            .props()
+            .dictionary
+            .clone()
+            .map(|dictionary| html!(<c::DictionaryBadge {dictionary} />));
+
         html! {
             <span class="row">
                 {entries}
                 {for bullets}
+                {for dictionary}
             </span>
         }
     }
@@ -0,0 +1,279 @@
+//! Guided setup shown on the first run, before any dictionaries are
+//! installed.
+
+use lib::api;
+use lib::config::EDRDG_LICENSE_URL;
+use yew::prelude::*;
+
+use crate::error::Error;
+use crate::i18n::{t, Text};
+use crate::ws;
+
+use super::spacing;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    Welcome,
+    Dictionaries,
+    Capture,
+}
+
+pub(crate) enum Msg {
+    GetConfig(Box<api::GetConfigResult>),
+    Step(Step),
+    ToggleLicense,
+    ToggleOcr,
+    /// Continue past the dictionaries, installing them or not.
+    Install(bool),
+    Skip,
+    Finish,
+    Done,
+    Error(Error),
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+    /// Dictionaries which are enabled but not installed.
+    pub(crate) missing: Vec<api::MissingIndex>,
+    /// Called once the setup has been completed or skipped.
+    pub(crate) ondone: Callback<()>,
+}
+
+pub(crate) struct Onboarding {
+    step: Step,
+    accept_license: bool,
+    install: bool,
+    ocr: bool,
+    missing_ocr: Option<api::MissingOcr>,
+    submitting: bool,
+    error: Option<Error>,
+    pending: ws::Request,
+}
+
+impl Component for Onboarding {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let pending = ctx.props().ws.request(
+            api::GetConfig,
+            ctx.link().callback(|result| match result {
+                Ok(config) => Msg::GetConfig(Box::new(config)),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+
+        Self {
+            step: Step::Welcome,
+            accept_license: false,
+            install: true,
+            ocr: true,
+            missing_ocr: None,
+            submitting: false,
+            error: None,
+            pending,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::GetConfig(result) => {
+                self.pending = ws::Request::empty();
+                self.ocr = result.config.ocr;
+                self.missing_ocr = result.missing_ocr;
+                true
+            }
+            Msg::Step(step) => {
+                self.step = step;
+                true
+            }
+            Msg::ToggleLicense => {
+                self.accept_license = !self.accept_license;
+                true
+            }
+            Msg::ToggleOcr => {
+                self.ocr = !self.ocr;
+                true
+            }
+            Msg::Install(install) => {
+                self.install = install;
+                self.step = Step::Capture;
+                true
+            }
+            Msg::Skip => {
+                self.install = false;
+                self.submit(ctx);
+                true
+            }
+            Msg::Finish => {
+                self.submit(ctx);
+                true
+            }
+            Msg::Done => {
+                self.pending = ws::Request::empty();
+                ctx.props().ondone.emit(());
+                false
+            }
+            Msg::Error(error) => {
+                self.pending = ws::Request::empty();
+                self.submitting = false;
+                self.error = Some(error);
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let pending = self.submitting;
+
+        let error = self
+            .error
+            .as_ref()
+            .map(|error| html!(<div class="block block-sm danger">{error.to_string()}</div>));
+
+        let step = match self.step {
+            Step::Welcome => {
+                let onstart = ctx.link().callback(|_| Msg::Step(Step::Dictionaries));
+                let onskip = ctx.link().callback(|_| Msg::Skip);
+
+                html! {
+                    <>
+                        <div class="block block-sm title">{t(Text::Welcome)}</div>
+                        <div class="block block-sm">{t(Text::OnboardingIntro)}</div>
+
+                        <div class="block block-sm row row-spaced">
+                            <button class="btn btn-lg" disabled={pending} onclick={onskip}>{t(Text::Skip)}</button>
+                            <button class="end btn btn-lg primary" onclick={onstart}>{t(Text::GetStarted)}</button>
+                        </div>
+                    </>
+                }
+            }
+            Step::Dictionaries => {
+                let missing = ctx.props().missing.iter().map(|index| {
+                    let description = index.description.clone().unwrap_or_default();
+
+                    html! {
+                        <li>
+                            <span class="title">{index.format.title()}</span>
+                            {spacing()}
+                            <span>{description}</span>
+                        </li>
+                    }
+                });
+
+                let attribution = ctx
+                    .props()
+                    .missing
+                    .iter()
+                    .find_map(|index| index.format.attribution());
+
+                let license = attribution.map(|attribution| {
+                    let checked = self.accept_license;
+                    let onchange = ctx.link().callback(|_| Msg::ToggleLicense);
+
+                    html! {
+                        <>
+                            <div class="block block-sm">{attribution}</div>
+                            <div class="block block-sm row row-spaced">
+                                <input id="onboarding-license" type="checkbox" {checked} {onchange} />
+                                <label for="onboarding-license">{t(Text::AcceptLicense)}</label>
+                                <a href={EDRDG_LICENSE_URL} target="_license">{"EDRDG"}</a>
+                            </div>
+                        </>
+                    }
+                });
+
+                let accepted = attribution.is_none() || self.accept_license;
+
+                let onback = ctx.link().callback(|_| Msg::Step(Step::Welcome));
+                let onlater = ctx.link().callback(|_| Msg::Install(false));
+                let oninstall = ctx.link().callback(|_| Msg::Install(true));
+
+                html! {
+                    <>
+                        <div class="block block-sm title">{t(Text::InstallDictionaries)}</div>
+                        <div class="block block-sm">{t(Text::InstallDictionariesIntro)}</div>
+                        <ul class="block block-sm">{for missing}</ul>
+                        {for license}
+
+                        <div class="block block-sm row row-spaced">
+                            <button class="btn btn-lg" onclick={onback}>{t(Text::Back)}</button>
+                            <button class="end btn btn-lg" onclick={onlater}>{t(Text::NotNow)}</button>
+                            {spacing()}
+                            <button class="btn btn-lg primary" disabled={!accepted} onclick={oninstall}>{t(Text::InstallNow)}</button>
+                        </div>
+                    </>
+                }
+            }
+            Step::Capture => {
+                let checked = self.ocr;
+                let onchange = ctx.link().callback(|_| Msg::ToggleOcr);
+
+                let missing_ocr = self.missing_ocr.as_ref().filter(|_| self.ocr).map(|missing| {
+                    let install = missing.install_url.as_ref().map(|install| {
+                        let href = install.url.clone();
+                        html!(<a {href} class="btn" target="_install_url">{format!("⇓ {}", install.text)}</a>)
+                    });
+
+                    html! {
+                        <div class="block block-sm row row-spaced">
+                            <span>{"OCR support is not installed"}</span>
+                            {for install}
+                        </div>
+                    }
+                });
+
+                let onback = ctx.link().callback(|_| Msg::Step(Step::Dictionaries));
+                let onfinish = ctx.link().callback(|_| Msg::Finish);
+
+                html! {
+                    <>
+                        <div class="block block-sm title">{t(Text::Capture)}</div>
+                        <div class="block block-sm">{t(Text::CaptureIntro)}</div>
+
+                        <div class="block block-sm row row-spaced">
+                            <input id="onboarding-ocr" type="checkbox" {checked} {onchange} />
+                            <label for="onboarding-ocr">{t(Text::RecognizeImages)}</label>
+                        </div>
+
+                        {for missing_ocr}
+
+                        <div class="block block-sm row row-spaced">
+                            <button class="btn btn-lg" onclick={onback}>{t(Text::Back)}</button>
+                            <button class="end btn btn-lg primary" disabled={pending} onclick={onfinish}>{t(Text::Finish)}</button>
+                        </div>
+                    </>
+                }
+            }
+        };
+
+        html! {
+            <div class="block block-lg notice" id="onboarding">
+                {step}
+                {for error}
+            </div>
+        }
+    }
+}
+
+impl Onboarding {
+    fn submit(&mut self, ctx: &Context<Self>) {
+        self.submitting = true;
+        self.error = None;
+
+        let request = api::OnboardRequest {
+            install: self.install,
+            accept_license: self.accept_license,
+            ocr: self.ocr,
+        };
+
+        self.pending = ctx.props().ws.request(
+            request,
+            ctx.link().callback(|result| match result {
+                Ok(api::Empty) => Msg::Done,
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+}
@@ -0,0 +1,497 @@
+use gloo::file::callbacks::FileReader;
+use lib::api;
+use lib::database::NameWeighting;
+use lib::profile::{Density, Readings};
+use yew::prelude::*;
+
+use crate::base;
+use crate::error::Error;
+use crate::i18n::{self, t, Text};
+use crate::ws;
+
+use super::spacing;
+
+/// The number of history entries shown.
+const HISTORY_LIMIT: usize = 20;
+/// The sizes Japanese text can be scaled to, in percent.
+const TEXT_SCALES: [u32; 6] = [75, 100, 125, 150, 200, 250];
+
+pub(crate) enum Msg {
+    Load,
+    Profiles(api::ProfilesResponse),
+    History(api::HistoryResponse),
+    Select(String),
+    Name(String),
+    Create,
+    Delete,
+    Names(NameWeighting),
+    Readings(Readings),
+    Density(Density),
+    TextScale(u32),
+    Sync,
+    Synced,
+    Import(web_sys::File),
+    Imported,
+    ImportKnown(web_sys::File),
+    KnownRead(String),
+    KnownImported(api::ImportKnownWordsResponse),
+    Broadcast(api::OwnedBroadcastKind),
+    Error(Error),
+}
+
+impl From<api::OwnedBroadcastKind> for Msg {
+    #[inline]
+    fn from(broadcast: api::OwnedBroadcastKind) -> Self {
+        Msg::Broadcast(broadcast)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+}
+
+pub(crate) struct Profiles {
+    profiles: Option<api::ProfilesResponse>,
+    history: Vec<lib::profile::HistoryEntry>,
+    name: String,
+    importing: bool,
+    syncing: bool,
+    known: Option<api::ImportKnownWordsResponse>,
+    known_upload: Option<FileReader>,
+    pending: ws::Request,
+    pending_sync: ws::Request,
+    pending_history: ws::Request,
+    pending_known: ws::Request,
+    _listener: ws::Listener,
+}
+
+impl Component for Profiles {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::Load);
+
+        Self {
+            profiles: None,
+            history: Vec::new(),
+            name: String::new(),
+            importing: false,
+            syncing: false,
+            known: None,
+            known_upload: None,
+            pending: ws::Request::empty(),
+            pending_sync: ws::Request::empty(),
+            pending_history: ws::Request::empty(),
+            pending_known: ws::Request::empty(),
+            _listener: ctx.props().ws.listen(ctx),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Load => {
+                self.request(ctx, api::ProfilesRequest);
+                false
+            }
+            Msg::Profiles(response) => {
+                self.pending = ws::Request::empty();
+                self.profiles = Some(response);
+
+                self.pending_history = ctx.props().ws.request(
+                    api::HistoryRequest,
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::History(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                true
+            }
+            Msg::History(response) => {
+                self.pending_history = ws::Request::empty();
+                self.history = response.entries;
+                true
+            }
+            Msg::Select(name) => {
+                self.request(ctx, api::SelectProfileRequest { name });
+                false
+            }
+            Msg::Name(name) => {
+                self.name = name;
+                true
+            }
+            Msg::Create => {
+                let name = std::mem::take(&mut self.name);
+                self.request(ctx, api::SelectProfileRequest { name });
+                true
+            }
+            Msg::Delete => {
+                if let Some(profiles) = &self.profiles {
+                    let name = profiles.current.clone();
+                    self.request(ctx, api::DeleteProfileRequest { name });
+                }
+
+                false
+            }
+            Msg::Names(names) => {
+                self.request(ctx, api::SetNameWeightingRequest { names });
+                false
+            }
+            Msg::Readings(readings) => {
+                self.request(ctx, api::SetReadingsRequest { readings });
+                false
+            }
+            Msg::Density(density) => {
+                if let Some(profiles) = &self.profiles {
+                    let text_scale = profiles.text_scale;
+                    self.request(
+                        ctx,
+                        api::SetDensityRequest {
+                            density,
+                            text_scale,
+                        },
+                    );
+                }
+
+                false
+            }
+            Msg::TextScale(text_scale) => {
+                if let Some(profiles) = &self.profiles {
+                    let density = profiles.density;
+                    self.request(
+                        ctx,
+                        api::SetDensityRequest {
+                            density,
+                            text_scale,
+                        },
+                    );
+                }
+
+                false
+            }
+            Msg::Sync => {
+                self.syncing = true;
+
+                self.pending_sync = ctx.props().ws.request(
+                    api::SyncRequest,
+                    ctx.link().callback(|result| match result {
+                        Ok(api::Empty) => Msg::Synced,
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                true
+            }
+            Msg::Synced => {
+                self.pending_sync = ws::Request::empty();
+                self.syncing = false;
+                true
+            }
+            Msg::Import(file) => {
+                let Some(url) = user_data_url() else {
+                    return false;
+                };
+
+                self.importing = true;
+                let link = ctx.link().clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let msg = match import(&url, file).await {
+                        Ok(()) => Msg::Imported,
+                        Err(error) => Msg::Error(error),
+                    };
+
+                    link.send_message(msg);
+                });
+
+                true
+            }
+            Msg::Imported => {
+                self.importing = false;
+                true
+            }
+            Msg::ImportKnown(file) => {
+                self.importing = true;
+                self.known = None;
+
+                let link = ctx.link().clone();
+                let file = gloo::file::File::from(file);
+
+                self.known_upload =
+                    Some(gloo::file::callbacks::read_as_text(&file, move |result| {
+                        let msg = match result {
+                            Ok(text) => Msg::KnownRead(text),
+                            Err(error) => Msg::Error(anyhow::Error::from(error).into()),
+                        };
+
+                        link.send_message(msg);
+                    }));
+
+                true
+            }
+            Msg::KnownRead(text) => {
+                self.known_upload = None;
+
+                self.pending_known = ctx.props().ws.request(
+                    api::ImportKnownWordsRequest { text },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::KnownImported(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::KnownImported(response) => {
+                self.pending_known = ws::Request::empty();
+                self.importing = false;
+                self.known = Some(response);
+                true
+            }
+            Msg::Broadcast(api::OwnedBroadcastKind::ProfileChanged) => {
+                self.request(ctx, api::ProfilesRequest);
+                false
+            }
+            Msg::Broadcast(..) => false,
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.importing = false;
+                self.syncing = false;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let Some(profiles) = &self.profiles else {
+            return html!(<div class="block row"><div class="spinner">{t(Text::Loading)}</div></div>);
+        };
+
+        let options = profiles.profiles.iter().map(|name| {
+            let selected = *name == profiles.current;
+            html!(<option value={name.clone()} {selected}>{name.clone()}</option>)
+        });
+
+        let onselect = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+            Some(Msg::Select(select.value()))
+        });
+
+        let oninput = ctx.link().batch_callback(|e: InputEvent| {
+            let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+            Some(Msg::Name(input.value()))
+        });
+
+        let oncreate = ctx.link().callback(|_| Msg::Create);
+        let ondelete = ctx.link().callback(|_| Msg::Delete);
+
+        let onimport = ctx.link().batch_callback(|e: Event| {
+            let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+            let file = input.files()?.get(0)?;
+            input.set_value("");
+            Some(Msg::Import(file))
+        });
+
+        let onimportknown = ctx.link().batch_callback(|e: Event| {
+            let input = e.target_dyn_into::<web_sys::HtmlInputElement>()?;
+            let file = input.files()?.get(0)?;
+            input.set_value("");
+            Some(Msg::ImportKnown(file))
+        });
+
+        let known = self.known.as_ref().map(|known| {
+            let text = i18n::imported_known_words(
+                known.added,
+                known.words,
+                known.known,
+                known.missing.len(),
+            );
+
+            html!(<div class="block row">{text}</div>)
+        });
+
+        let export = user_data_url().map(|href| {
+            html!(<a class="btn" {href} download="jpv-user-data.zip" title={t(Text::ExportUserData)}>{t(Text::Export)}</a>)
+        });
+
+        let sync = profiles.sync.then(|| {
+            let onclick = ctx.link().callback(|_| Msg::Sync);
+            html!(<button class="btn" disabled={self.syncing} {onclick} title={t(Text::SyncProfile)}>{t(Text::Sync)}</button>)
+        });
+
+        let names = {
+            let checked = profiles.names == NameWeighting::Context;
+
+            let onchange = ctx.link().callback(move |_| {
+                Msg::Names(if checked {
+                    NameWeighting::Plain
+                } else {
+                    NameWeighting::Context
+                })
+            });
+
+            html! {
+                <div class="block row row-spaced">
+                    <input id="profile-names" type="checkbox" {checked} {onchange} />
+                    <label for="profile-names">{t(Text::PreferWords)}</label>
+                </div>
+            }
+        };
+
+        let readings = {
+            let options = Readings::ALL.iter().map(|&readings| {
+                let selected = profiles.readings == readings;
+                html!(<option value={readings.ident()} {selected}>{t(readings_text(readings))}</option>)
+            });
+
+            let onchange = ctx.link().batch_callback(|e: Event| {
+                let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+                Some(Msg::Readings(Readings::parse(&select.value())?))
+            });
+
+            html! {
+                <div class="block row row-spaced">
+                    <label for="profile-readings">{t(Text::ShowReadings)}</label>
+                    <select id="profile-readings" {onchange}>{for options}</select>
+                </div>
+            }
+        };
+
+        let density = {
+            let options = Density::ALL.iter().map(|&density| {
+                let selected = profiles.density == density;
+                html!(<option value={density.ident()} {selected}>{t(density_text(density))}</option>)
+            });
+
+            let ondensity = ctx.link().batch_callback(|e: Event| {
+                let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+                Some(Msg::Density(Density::parse(&select.value())?))
+            });
+
+            let scales = TEXT_SCALES.iter().map(|&scale| {
+                let selected = profiles.text_scale == scale;
+                html!(<option value={scale.to_string()} {selected}>{format!("{scale}%")}</option>)
+            });
+
+            let onscale = ctx.link().batch_callback(|e: Event| {
+                let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+                Some(Msg::TextScale(select.value().parse().ok()?))
+            });
+
+            html! {
+                <div class="block row row-spaced">
+                    <label for="profile-density">{t(Text::Density)}</label>
+                    <select id="profile-density" title={t(Text::DensityHelp)} onchange={ondensity}>{for options}</select>
+                    <label for="profile-text-scale">{t(Text::JapaneseTextSize)}</label>
+                    <select id="profile-text-scale" onchange={onscale}>{for scales}</select>
+                </div>
+            }
+        };
+
+        let is_default = profiles.current == lib::profile::DEFAULT_PROFILE;
+        let valid = lib::profile::is_valid_name(&self.name);
+
+        let history = (!self.history.is_empty()).then(|| {
+            let entries = self.history.iter().take(HISTORY_LIMIT).map(|entry| {
+                let href = serde_urlencoded::to_string([("q", entry.text.as_str())])
+                    .map(|query| format!("/?{query}"))
+                    .unwrap_or_default();
+
+                html!(<a class="chip" {href}>{entry.text.clone()}</a>)
+            });
+
+            html!(<div class="block row chips profile-history">{for entries}</div>)
+        });
+
+        html! {
+            <>
+                <div class="block row row-spaced">
+                    <select id="profile" title={t(Text::SelectProfile)} onchange={onselect}>{for options}</select>
+                    <button class="btn danger" disabled={is_default} onclick={ondelete} title={t(Text::DeleteProfile)}>{t(Text::Delete)}</button>
+                    <input class="end" type="text" placeholder={t(Text::NewProfile)} value={self.name.clone()} {oninput} />
+                    {spacing()}
+                    <button class="btn primary" disabled={!valid} onclick={oncreate}>{t(Text::Create)}</button>
+                </div>
+
+                {for history}
+
+                {names}
+                {readings}
+                {density}
+
+                <div class="block row row-spaced">
+                    {for sync}
+                    {for export}
+                    <label for="import-user-data" class="btn" title={t(Text::ImportUserData)}>{t(Text::Import)}</label>
+                    <input id="import-user-data" type="file" accept=".zip,application/zip" disabled={self.importing} onchange={onimport} />
+                    <label for="import-known-words" class="btn" title={t(Text::ImportKnownWordsHelp)}>{t(Text::ImportKnownWords)}</label>
+                    <input id="import-known-words" type="file" accept=".txt,.csv,.tsv,text/plain,text/csv" disabled={self.importing} onchange={onimportknown} />
+                </div>
+
+                {for known}
+            </>
+        }
+    }
+}
+
+/// The translated title of a way of showing readings.
+fn readings_text(readings: Readings) -> Text {
+    match readings {
+        Readings::Always => Text::ReadingsAlways,
+        Readings::Hover => Text::ReadingsHover,
+        Readings::Never => Text::ReadingsNever,
+    }
+}
+
+/// The translated title of a density.
+fn density_text(density: Density) -> Text {
+    match density {
+        Density::Normal => Text::DensityNormal,
+        Density::Compact => Text::Compact,
+    }
+}
+
+/// The address user data is exported from and imported to.
+fn user_data_url() -> Option<String> {
+    base::url("/api/user-data")
+}
+
+/// Import user data from the given archive.
+async fn import(url: &str, file: web_sys::File) -> Result<(), Error> {
+    let request = gloo::net::http::Request::post(url)
+        .header(
+            api::compat::HEADER,
+            &api::compat::PROTOCOL_VERSION.to_string(),
+        )
+        .header("Content-Type", "application/zip")
+        .body(file)
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    let response = request
+        .send()
+        .await
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    if !response.ok() {
+        return Err(Error::from_response(&response).await);
+    }
+
+    Ok(())
+}
+
+impl Profiles {
+    fn request<T>(&mut self, ctx: &Context<Self>, request: T)
+    where
+        T: api::Request<Response = api::ProfilesResponse>,
+    {
+        self.pending = ctx.props().ws.request(
+            request,
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::Profiles(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+}
@@ -1,14 +1,24 @@
 use std::cell::Cell;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::mem::replace;
+use std::ops::Range;
 use std::rc::Rc;
 use std::str::from_utf8;
 
+use gloo::timers::callback::Timeout;
 use gloo::utils::format::JsValueSerdeExt;
 use lib::api;
+use lib::config::IndexFormat;
+use lib::detect::{self, Script};
+use lib::jmdict;
 use lib::kanjidic2;
-use lib::romaji;
+use lib::profile::WordStatus;
+use lib::romaji::{self, Transform};
+use lib::PartOfSpeechGroup;
+use lib::Score;
 use serde::Deserialize;
 use serde::Serialize;
 use wasm_bindgen::closure::Closure;
@@ -19,13 +29,45 @@ use yew::prelude::*;
 use yew_router::{prelude::*, AnyRoute};
 
 use crate::c;
+use crate::display::Display;
 use crate::error::Error;
-use crate::query::{Mode, Query, Tab};
+use crate::gesture;
+use crate::i18n::{self, t, Language, Shown, Text};
+use crate::navigation::{self, Navigation};
+use crate::query::{Interaction, KanjiFilter, KanjiSort, Mode, Query, Tab};
 use crate::ws;
 
-use super::{comma, seq, spacing};
+use super::analyze_toggle::set_word_status;
+use super::entry::render_priority;
+use super::sections::Section;
+use super::{comma, icon, iter, seq, spacing};
 
 const DEFAULT_LIMIT: usize = 100;
+/// The number of phrases in each streamed search response.
+const SEARCH_CHUNK: usize = 20;
+/// How long a query has to stay unchanged before it's added to the history,
+/// in milliseconds.
+const HISTORY_DELAY: u32 = 2000;
+
+/// Part of speech groups which can be used to filter phrases.
+const FILTER_GROUPS: &[PartOfSpeechGroup] = &[
+    PartOfSpeechGroup::Verb,
+    PartOfSpeechGroup::Noun,
+    PartOfSpeechGroup::Adjective,
+    PartOfSpeechGroup::Expression,
+];
+
+/// Confidence below which a line recognized in an image is shown as uncertain.
+const LOW_CONFIDENCE: f32 = 0.6;
+
+/// Text recognized in a captured image, which can be corrected.
+struct Recognized {
+    key: u64,
+    text: String,
+    lines: Vec<api::OwnedRecognizedLine>,
+    /// The correction being edited.
+    correction: Option<String>,
+}
 
 // How a history update is performed
 pub(crate) enum History {
@@ -33,21 +75,64 @@ pub(crate) enum History {
     Push,
     /// History is replaced.
     Replace,
+    /// History is replaced in the browser, but pushed to the in-app
+    /// navigation stack. Used for fine-grained states like analysis.
+    Analyze,
 }
 
 pub(crate) enum Msg {
     OpenConfig,
+    Back,
+    Forward,
+    Navigate(usize),
+    Timeline(bool),
+    ClearTimeline,
     Mode(Mode),
     CaptureClipboard(bool),
+    CaptureFocus(bool),
+    CaptureFocusUpdated,
     Tab(Tab),
+    Pos(Option<PartOfSpeechGroup>),
+    KanjiSort(KanjiSort),
+    KanjiFilter(KanjiFilter),
+    Interaction(Interaction),
+    Group(bool),
+    Compact(bool),
+    Hard(bool),
+    Status(bool),
+    Subtitles(bool),
+    SetWordStatus(u64, WordStatus),
+    WordStatusSet,
+    EditOcr,
+    OcrCorrection(String),
+    CorrectOcr,
+    CancelOcr,
+    OcrCorrected,
+    ToggleVariants(u64),
+    ToggleSummary(u64),
     Change(String),
     ForceChange(String, Option<String>),
     Analyze(usize),
+    AnalyzeSpan(Range<usize>),
+    AnalyzeSelect(usize),
     AnalyzeCycle,
+    Swipe(gesture::Direction),
     HistoryChanged(Location),
-    GetConfig(api::GetConfigResult),
+    GetConfig(Box<api::GetConfigResult>),
+    IndexStatus(api::StatusResponse),
+    Update(api::UpdateResponse),
+    Onboarded,
+    Install,
+    Installing,
     SearchResponse(api::OwnedSearchResponse),
+    SummariesResponse(api::OwnedSearchSummariesResponse),
+    EntryResponse(u64, api::OwnedEntryResponse),
+    SearchExamplesResponse(api::OwnedSearchExamplesResponse),
     AnalyzeResponse(api::OwnedAnalyzeResponse),
+    DifficultyResponse(api::DifficultyResponse),
+    WordStatusResponse(api::WordStatusResponse),
+    RecordHistory(Rc<str>),
+    HistoryRecorded,
     MoreEntries,
     MoreCharacters,
     ContentMessage(ContentMessage),
@@ -81,18 +166,72 @@ pub(crate) struct Prompt {
     query: Query,
     phrases: Vec<api::OwnedSearchPhrase>,
     names: Vec<api::OwnedSearchName>,
+    facets: BTreeMap<PartOfSpeechGroup, usize>,
+    /// Dictionaries which results are from, by identifier.
+    dictionaries: HashMap<String, Rc<api::Dictionary>>,
+    /// Sequence numbers of phrases whose variants are expanded.
+    expanded: HashSet<u64>,
+    /// Summaries of matching entries, when results are compact.
+    summaries: Vec<api::OwnedEntrySummary>,
+    /// Full entries loaded for expanded summaries, by sequence number.
+    details: HashMap<u64, jmdict::OwnedEntry>,
     limit_entries: usize,
     characters: Vec<kanjidic2::OwnedCharacter>,
     limit_characters: usize,
+    examples: Rc<[api::OwnedSearchExample]>,
+    examples_total: usize,
+    /// Difficulty of words in the query, when hard words are highlighted.
+    difficulty: Rc<[api::WordDifficulty]>,
+    /// Status of words in the query, when word status is highlighted.
+    word_status: Rc<[api::WordStatusSpan]>,
     pending_search: ws::Request,
+    /// Whether more phrases are being streamed for the current search.
+    searching: bool,
+    pending_entries: HashMap<u64, ws::Request>,
+    pending_examples: ws::Request,
+    pending_difficulty: ws::Request,
+    pending_word_status: ws::Request,
+    pending_set_word_status: ws::Request,
+    pending_correct_ocr: ws::Request,
+    pending_history: ws::Request,
+    pending_capture_focus: ws::Request,
+    capture_focus: api::CaptureFocus,
     log: Vec<api::OwnedLogEntry>,
     tasks: BTreeMap<String, api::OwnedTaskProgress>,
     analysis: Rc<[Rc<str>]>,
+    /// Scores of the analyzed candidates, in the same order as `analysis`.
+    scores: Vec<Score>,
     ocr: bool,
-    missing: BTreeSet<String>,
+    /// The text most recently recognized in a captured image.
+    recognized: Option<Recognized>,
+    /// Whether text-to-speech is configured.
+    tts: bool,
+    /// Whether notes can be added to Anki.
+    anki: bool,
+    /// Dictionaries which are enabled but not installed.
+    missing: Vec<api::MissingIndex>,
+    /// Set while missing dictionaries are being installed.
+    installing: bool,
     missing_ocr: Option<api::MissingOcr>,
+    /// Set when the last search failed since there's nothing to search.
+    index_missing: bool,
+    /// Whether the first-run setup is shown.
+    onboarding: bool,
     get_config: Option<ws::Request>,
+    get_status: Option<ws::Request>,
+    /// A newer release of the service, if checking for them is enabled.
+    update: Option<api::Release>,
+    get_update: Option<ws::Request>,
     is_open: bool,
+    history_timeout: Option<Timeout>,
+    /// In-app navigation stack.
+    navigation: Navigation,
+    /// Whether the navigation timeline is shown.
+    timeline: bool,
+    /// The search input, which is focused when the prompt is first shown.
+    input: NodeRef,
+    /// Swipes over the analyzed text.
+    swipe: gesture::Swipe,
     _callback: Closure<dyn FnMut(MessageEvent)>,
     _location_handle: Option<LocationHandle>,
     _listener: ws::Listener,
@@ -129,6 +268,12 @@ impl Component for Prompt {
 
         let query = decode_query(ctx.link().location());
 
+        let mut navigation = Navigation::load();
+
+        if !query.embed {
+            navigation.visit(navigation::Entry::new(&query));
+        }
+
         let listener = ctx.props().ws.listen(ctx);
         let state_changes = ctx.props().ws.state_changes(ctx);
 
@@ -136,18 +281,55 @@ impl Component for Prompt {
             query,
             phrases: Vec::default(),
             names: Vec::default(),
+            facets: BTreeMap::new(),
+            dictionaries: HashMap::new(),
+            expanded: HashSet::new(),
+            summaries: Vec::new(),
+            details: HashMap::new(),
             limit_entries: DEFAULT_LIMIT,
             characters: Vec::default(),
             limit_characters: DEFAULT_LIMIT,
+            examples: Rc::from([]),
+            examples_total: 0,
+            difficulty: Rc::from([]),
+            word_status: Rc::from([]),
             pending_search: ws::Request::empty(),
+            searching: false,
+            pending_entries: HashMap::new(),
+            pending_examples: ws::Request::empty(),
+            pending_difficulty: ws::Request::empty(),
+            pending_word_status: ws::Request::empty(),
+            pending_set_word_status: ws::Request::empty(),
+            pending_correct_ocr: ws::Request::empty(),
+            pending_history: ws::Request::empty(),
+            pending_capture_focus: ws::Request::empty(),
+            capture_focus: api::CaptureFocus {
+                focused: false,
+                claimed: false,
+            },
             log: Vec::new(),
             tasks: BTreeMap::new(),
             analysis: Rc::from([]),
+            scores: Vec::new(),
             ocr: false,
-            missing: BTreeSet::new(),
+            recognized: None,
+            tts: false,
+            anki: false,
+            missing: Vec::new(),
+            installing: false,
             missing_ocr: None,
+            index_missing: false,
+            onboarding: false,
             get_config: None,
+            get_status: None,
+            update: None,
+            get_update: None,
             is_open: false,
+            history_timeout: None,
+            navigation,
+            timeline: false,
+            input: NodeRef::default(),
+            swipe: gesture::Swipe::default(),
             _callback: callback,
             _location_handle: location_handle,
             _listener: listener,
@@ -155,6 +337,8 @@ impl Component for Prompt {
         };
 
         this.get_config(ctx);
+        this.get_status(ctx);
+        this.get_update(ctx);
         this.reload(ctx);
         this
     }
@@ -166,21 +350,26 @@ impl Component for Prompt {
                 self.save_query(ctx, History::Push);
                 true
             }
+            Msg::Back => {
+                let index = self.navigation.cursor().saturating_sub(1);
+                self.navigate(ctx, index)
+            }
+            Msg::Forward => {
+                let index = self.navigation.cursor() + 1;
+                self.navigate(ctx, index)
+            }
+            Msg::Navigate(index) => self.navigate(ctx, index),
+            Msg::Timeline(timeline) => {
+                self.timeline = timeline;
+                true
+            }
+            Msg::ClearTimeline => {
+                self.navigation.clear();
+                true
+            }
             Msg::GetConfig(state) => {
                 log::trace!("{:?}", state);
 
-                let mut missing = state
-                    .config
-                    .indexes
-                    .into_iter()
-                    .filter(|(_, index)| index.enabled)
-                    .map(|(key, _)| key)
-                    .collect::<BTreeSet<_>>();
-
-                for id in state.installed {
-                    missing.remove(&id);
-                }
-
                 let mut any = false;
 
                 if state.config.ocr != self.ocr {
@@ -188,8 +377,13 @@ impl Component for Prompt {
                     any |= true;
                 }
 
-                if missing != self.missing {
-                    self.missing = missing;
+                if state.config.tts.is_some() != self.tts {
+                    self.tts = state.config.tts.is_some();
+                    any |= true;
+                }
+
+                if state.config.anki.enabled != self.anki {
+                    self.anki = state.config.anki.enabled;
                     any |= true;
                 }
 
@@ -200,18 +394,127 @@ impl Component for Prompt {
 
                 any
             }
+            Msg::IndexStatus(status) => {
+                self.get_status = None;
+                self.installing = false;
+                self.missing = status.missing;
+                self.onboarding = !status.onboarded && status.installed.is_empty();
+                true
+            }
+            Msg::Onboarded => {
+                self.onboarding = false;
+                self.get_status(ctx);
+                true
+            }
+            Msg::Update(update) => {
+                self.get_update = None;
+                self.update = update.available;
+                true
+            }
+            Msg::Install => {
+                self.installing = true;
+
+                self.get_status = Some(ctx.props().ws.request(
+                    api::InstallAllRequest,
+                    ctx.link().callback(|result| match result {
+                        Ok(api::Empty) => Msg::Installing,
+                        Err(error) => Msg::Error(error),
+                    }),
+                ));
+
+                true
+            }
+            Msg::Installing => {
+                // Installation carries on in the background, and the status
+                // is refreshed once it's done.
+                self.get_status = None;
+                false
+            }
             Msg::SearchResponse(response) => {
-                self.phrases = response.phrases;
+                self.searching = response.more;
+                self.index_missing = false;
+
+                // Later responses of a streamed search only add phrases.
+                if response.offset > 0 {
+                    self.phrases.extend(response.phrases);
+                } else {
+                    self.phrases = response.phrases;
+                }
+
+                self.phrases.sort_by_key(|a| a.key.weight);
+
+                if response.offset > 0 {
+                    return true;
+                }
+
                 self.names = response.names;
-                self.phrases.sort_by(|a, b| a.key.weight.cmp(&b.key.weight));
-                self.names.sort_by(|a, b| a.key.weight.cmp(&b.key.weight));
+                self.names.sort_by_key(|a| a.key.weight);
                 self.characters = response.characters;
+                self.facets = response.facets;
+                self.dictionaries = response
+                    .dictionaries
+                    .into_iter()
+                    .map(|d| (d.id.clone(), Rc::new(d)))
+                    .collect();
+                self.summaries.clear();
+                self.expanded.clear();
                 self.limit_entries = DEFAULT_LIMIT;
                 self.limit_characters = DEFAULT_LIMIT;
                 true
             }
+            Msg::SummariesResponse(response) => {
+                self.searching = false;
+                self.summaries = response.entries;
+                self.phrases.clear();
+                self.names.clear();
+                self.facets.clear();
+                self.expanded.clear();
+                self.pending_entries.clear();
+                self.limit_entries = DEFAULT_LIMIT;
+                true
+            }
+            Msg::EntryResponse(sequence, response) => {
+                self.pending_entries.remove(&sequence);
+                self.details.insert(sequence, response.entry);
+                true
+            }
+            Msg::SearchExamplesResponse(response) => {
+                self.examples = response.examples.into();
+                self.examples_total = response.total;
+                true
+            }
+            Msg::RecordHistory(text) => {
+                self.history_timeout = None;
+
+                self.pending_history = ctx.props().ws.request(
+                    api::AddHistoryRequest {
+                        text: text.as_ref().to_owned(),
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(api::Empty) => Msg::HistoryRecorded,
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::HistoryRecorded => {
+                self.pending_history = ws::Request::empty();
+                false
+            }
+            Msg::DifficultyResponse(response) => {
+                self.pending_difficulty = ws::Request::empty();
+                self.difficulty = response.words.into();
+                true
+            }
+            Msg::WordStatusResponse(response) => {
+                self.pending_word_status = ws::Request::empty();
+                self.word_status = response.words.into();
+                true
+            }
             Msg::AnalyzeResponse(response) => {
                 log::trace!("Analyze response");
+                self.scores = response.data.iter().map(|d| d.score).collect();
                 self.analysis = response.data.into_iter().map(|d| d.string.into()).collect();
                 self.search(ctx);
                 false
@@ -220,9 +523,9 @@ impl Component for Prompt {
                 self.query.mode = mode;
 
                 let new_query = match self.query.mode {
-                    Mode::Unfiltered => self.query.text.clone(),
-                    Mode::Hiragana => process_query(&self.query.text, romaji::Segment::hiragana),
-                    Mode::Katakana => process_query(&self.query.text, romaji::Segment::katakana),
+                    Mode::Auto | Mode::Unfiltered => self.query.text.clone(),
+                    Mode::Hiragana => process_query(&self.query.text, Transform::Hiragana),
+                    Mode::Katakana => process_query(&self.query.text, Transform::Katakana),
                 };
 
                 let history = if new_query != self.query.text {
@@ -233,6 +536,8 @@ impl Component for Prompt {
 
                 self.query.text = new_query;
                 self.save_query(ctx, history);
+                // How the query is searched for depends on the mode.
+                self.search(ctx);
                 true
             }
             Msg::CaptureClipboard(capture_clipboard) => {
@@ -240,24 +545,206 @@ impl Component for Prompt {
                 self.save_query(ctx, History::Replace);
                 true
             }
+            Msg::CaptureFocus(claim) => {
+                self.pending_capture_focus = ctx.props().ws.request(
+                    api::CaptureFocusRequest { claim },
+                    ctx.link().callback(|result| match result {
+                        Ok(api::Empty) => Msg::CaptureFocusUpdated,
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::CaptureFocusUpdated => {
+                self.pending_capture_focus = ws::Request::empty();
+                false
+            }
             Msg::Tab(tab) => {
                 self.query.tab = tab;
                 self.save_query(ctx, History::Replace);
                 true
             }
+            Msg::Pos(pos) => {
+                self.query.pos = pos;
+                self.limit_entries = DEFAULT_LIMIT;
+                self.save_query(ctx, History::Replace);
+                true
+            }
+            Msg::KanjiSort(sort) => {
+                self.query.kanji_sort = sort;
+                self.limit_characters = DEFAULT_LIMIT;
+                self.save_query(ctx, History::Replace);
+                true
+            }
+            Msg::KanjiFilter(filter) => {
+                self.query.kanji_filter = filter;
+                self.limit_characters = DEFAULT_LIMIT;
+                self.save_query(ctx, History::Replace);
+                true
+            }
+            Msg::Interaction(interaction) => {
+                self.query.interaction = interaction;
+                self.save_query(ctx, History::Replace);
+                true
+            }
+            Msg::Group(group) => {
+                self.query.group = group;
+                self.save_query(ctx, History::Replace);
+                self.search(ctx);
+                true
+            }
+            Msg::Compact(compact) => {
+                self.query.compact = compact;
+                self.save_query(ctx, History::Replace);
+                self.search(ctx);
+                true
+            }
+            Msg::Hard(hard) => {
+                self.query.hard = hard;
+                self.save_query(ctx, History::Replace);
+                self.request_difficulty(ctx);
+                true
+            }
+            Msg::Status(status) => {
+                self.query.status = status;
+                self.save_query(ctx, History::Replace);
+                self.request_word_status(ctx);
+                true
+            }
+            Msg::Subtitles(subtitles) => {
+                self.query.subtitles = subtitles;
+                self.save_query(ctx, History::Replace);
+                true
+            }
+            Msg::SetWordStatus(sequence, status) => {
+                self.word_status = set_word_status(&self.word_status, sequence, status);
+
+                self.pending_set_word_status = ctx.props().ws.request(
+                    api::SetWordStatusRequest { sequence, status },
+                    ctx.link().callback(|result| match result {
+                        Ok(api::Empty) => Msg::WordStatusSet,
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                true
+            }
+            Msg::WordStatusSet => {
+                self.pending_set_word_status = ws::Request::empty();
+                false
+            }
+            Msg::EditOcr => {
+                if let Some(recognized) = &mut self.recognized {
+                    recognized.correction = Some(recognized.text.clone());
+                }
+
+                true
+            }
+            Msg::OcrCorrection(text) => {
+                if let Some(recognized) = &mut self.recognized {
+                    recognized.correction = Some(text);
+                }
+
+                true
+            }
+            Msg::CorrectOcr => {
+                let Some(recognized) = &mut self.recognized else {
+                    return false;
+                };
+
+                let Some(text) = recognized.correction.take() else {
+                    return false;
+                };
+
+                let text = text.trim().to_owned();
+
+                if text.is_empty() || text == recognized.text {
+                    return true;
+                }
+
+                self.pending_correct_ocr = ctx.props().ws.request(
+                    api::CorrectOcrRequest {
+                        key: recognized.key,
+                        text: text.clone(),
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(api::Empty) => Msg::OcrCorrected,
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                // The correction is trusted fully, just like it is once it's
+                // cached by the service.
+                recognized.lines = vec![api::OwnedRecognizedLine {
+                    text: text.clone(),
+                    confidence: Some(1.0),
+                }];
+
+                recognized.text = text.clone();
+
+                self.query.set(text.into(), None);
+                self.analysis = Rc::from([]);
+                self.save_query(ctx, History::Push);
+                self.request_highlights(ctx);
+                self.search(ctx);
+                true
+            }
+            Msg::CancelOcr => {
+                if let Some(recognized) = &mut self.recognized {
+                    recognized.correction = None;
+                }
+
+                true
+            }
+            Msg::OcrCorrected => {
+                self.pending_correct_ocr = ws::Request::empty();
+                false
+            }
+            Msg::ToggleVariants(sequence) => {
+                if !self.expanded.remove(&sequence) {
+                    self.expanded.insert(sequence);
+                }
+
+                true
+            }
+            Msg::ToggleSummary(sequence) => {
+                if self.expanded.remove(&sequence) {
+                    return true;
+                }
+
+                self.expanded.insert(sequence);
+
+                if !self.details.contains_key(&sequence) {
+                    let request = ctx.props().ws.request(
+                        api::EntryRequest {
+                            sequence: sequence as u32,
+                        },
+                        ctx.link().callback(move |result| match result {
+                            Ok(response) => Msg::EntryResponse(sequence, response),
+                            Err(error) => Msg::Error(error),
+                        }),
+                    );
+
+                    self.pending_entries.insert(sequence, request);
+                }
+
+                true
+            }
             Msg::Change(input) => {
                 log::trace!("{:?}", input);
 
                 let input = match self.query.mode {
-                    Mode::Unfiltered => Rc::from(input),
-                    Mode::Hiragana => process_query(&input, romaji::Segment::hiragana),
-                    Mode::Katakana => process_query(&input, romaji::Segment::katakana),
+                    Mode::Auto | Mode::Unfiltered => Rc::from(input),
+                    Mode::Hiragana => process_query(&input, Transform::Hiragana),
+                    Mode::Katakana => process_query(&input, Transform::Katakana),
                 };
 
                 if self.query.text != input {
                     self.query.set(input, None);
                     self.analysis = Rc::from([]);
                     self.save_query(ctx, History::Replace);
+                    self.request_highlights(ctx);
                     self.search(ctx);
                 }
 
@@ -265,38 +752,88 @@ impl Component for Prompt {
             }
             Msg::ForceChange(input, translation) => {
                 let input = match self.query.mode {
-                    Mode::Unfiltered => Rc::from(input),
-                    Mode::Hiragana => process_query(&input, romaji::Segment::hiragana),
-                    Mode::Katakana => process_query(&input, romaji::Segment::katakana),
+                    Mode::Auto | Mode::Unfiltered => Rc::from(input),
+                    Mode::Hiragana => process_query(&input, Transform::Hiragana),
+                    Mode::Katakana => process_query(&input, Transform::Katakana),
                 };
 
                 self.query.set(input, translation);
                 self.analysis = Rc::from([]);
                 self.save_query(ctx, History::Push);
+                self.request_highlights(ctx);
                 self.search(ctx);
                 true
             }
             Msg::Analyze(i) => {
-                if self.query.analyze_at != Some(i) {
+                if self.query.analyze_at != Some(i) || self.query.analyze_end.is_some() {
                     self.query.index = 0;
                 }
 
                 self.query.analyze_at = Some(i);
-                self.save_query(ctx, History::Push);
+                self.query.analyze_end = None;
+                self.save_query(ctx, History::Analyze);
+                self.analyze(ctx);
+                true
+            }
+            Msg::AnalyzeSpan(range) => {
+                self.query.index = 0;
+                self.query.analyze_at = Some(range.start);
+                self.query.analyze_end = Some(range.end);
+                self.save_query(ctx, History::Analyze);
                 self.analyze(ctx);
                 true
             }
+            Msg::AnalyzeSelect(index) => {
+                if index >= self.analysis.len() || index == self.query.index {
+                    return false;
+                }
+
+                self.query.index = index;
+                self.save_query(ctx, History::Analyze);
+                self.search(ctx);
+                true
+            }
             Msg::AnalyzeCycle => {
                 if !self.analysis.is_empty() {
                     self.query.index += 1;
                     self.query.index %= self.analysis.len();
-                    self.save_query(ctx, History::Push);
+                    self.save_query(ctx, History::Analyze);
                     self.search(ctx);
                     true
                 } else {
                     false
                 }
             }
+            Msg::Swipe(direction) => match direction {
+                gesture::Direction::Left => {
+                    ctx.link().send_message(Msg::AnalyzeCycle);
+                    false
+                }
+                gesture::Direction::Right => {
+                    let len = self.analysis.len();
+
+                    if len > 0 {
+                        ctx.link()
+                            .send_message(Msg::AnalyzeSelect((self.query.index + len - 1) % len));
+                    }
+
+                    false
+                }
+                gesture::Direction::Down => {
+                    if self.query.analyze_at.is_none() {
+                        return false;
+                    }
+
+                    self.query.analyze_at = None;
+                    self.query.analyze_end = None;
+                    self.query.index = 0;
+                    self.analysis = Rc::from([]);
+                    self.save_query(ctx, History::Analyze);
+                    self.search(ctx);
+                    true
+                }
+                gesture::Direction::Up => false,
+            },
             Msg::HistoryChanged(location) => {
                 // Prevents internal history changes from firing.
                 if location.state::<IsInternal>().filter(|s| s.set()).is_some() {
@@ -304,19 +841,13 @@ impl Component for Prompt {
                 }
 
                 let query = decode_query(Some(location));
-                let old = replace(&mut self.query, query);
-
-                log::trace!("History change");
-                log::trace!("From: {:?}", old);
-                log::trace!("To: {:?}", self.query);
 
-                if self.query.analyze_at != old.analyze_at || self.query.text != old.text {
-                    self.analysis = Rc::from([]);
-                    self.reload(ctx);
-                } else if self.query.index != old.index {
-                    self.search(ctx);
+                if !query.embed {
+                    self.navigation.visit(navigation::Entry::new(&query));
                 }
 
+                log::trace!("History change");
+                self.apply_query(ctx, query);
                 true
             }
             Msg::MoreEntries => {
@@ -329,14 +860,12 @@ impl Component for Prompt {
             }
             Msg::ContentMessage(message) => {
                 match message {
-                    ContentMessage::Ping(payload) => {
-                        if self.is_open {
-                            if let Err(error) = post_parent_message(&ContentMessage::Pong(payload))
-                            {
-                                log::warn!("Failed to post message: {error}");
-                            }
+                    ContentMessage::Ping(payload) if self.is_open => {
+                        if let Err(error) = post_parent_message(&ContentMessage::Pong(payload)) {
+                            log::warn!("Failed to post message: {error}");
                         }
                     }
+                    ContentMessage::Ping(..) => {}
                     ContentMessage::Open => {}
                     ContentMessage::Update(message) => {
                         self.query.set(message.text.into(), None);
@@ -347,6 +876,7 @@ impl Component for Prompt {
 
                         self.analysis = Rc::from([]);
                         self.save_query(ctx, History::Push);
+                        self.request_highlights(ctx);
                         self.analyze(ctx);
                     }
                     _ => {}
@@ -365,6 +895,12 @@ impl Component for Prompt {
                             ctx.link().send_message(error);
                         }
                     }
+                    api::OwnedBroadcastKind::CapturedLine(line) => {
+                        self.update_from_captured_line(ctx, line.text, line.translation);
+                    }
+                    api::OwnedBroadcastKind::RecognizedText(recognized) => {
+                        self.update_from_recognized(ctx, recognized);
+                    }
                     api::OwnedBroadcastKind::LogBackFill(log) => {
                         self.log.extend(log.log);
                     }
@@ -377,8 +913,14 @@ impl Component for Prompt {
                     api::OwnedBroadcastKind::TaskCompleted(task) => {
                         self.tasks.remove(&task.name);
                     }
+                    api::OwnedBroadcastKind::CaptureFocus(capture_focus) => {
+                        self.capture_focus = capture_focus;
+                    }
+                    api::OwnedBroadcastKind::ProfileChanged => {}
                     api::OwnedBroadcastKind::Refresh => {
                         self.get_config(ctx);
+                        self.get_status(ctx);
+                        self.get_update(ctx);
                         self.reload(ctx);
                     }
                 }
@@ -395,12 +937,28 @@ impl Component for Prompt {
                 true
             }
             Msg::Error(error) => {
+                if error.kind() == Some(api::ErrorKind::IndexMissing) {
+                    self.searching = false;
+                    self.index_missing = true;
+                    return true;
+                }
+
                 log::error!("{error}");
                 false
             }
         }
     }
 
+    fn rendered(&mut self, _: &Context<Self>, first_render: bool) {
+        // Focus the search input so that it can be typed into immediately,
+        // unless the prompt is embedded in another page.
+        if first_render && !self.query.embed {
+            if let Some(input) = self.input.cast::<HtmlInputElement>() {
+                let _ = input.focus();
+            }
+        }
+    }
+
     fn view(&self, ctx: &Context<Self>) -> Html {
         let oninput = ctx.link().batch_callback(|e: InputEvent| {
             let input: HtmlInputElement = e.target_dyn_into()?;
@@ -408,6 +966,10 @@ impl Component for Prompt {
             Some(Msg::Change(value))
         });
 
+        let onauto = ctx
+            .link()
+            .batch_callback(|_: Event| Some(Msg::Mode(Mode::Auto)));
+
         let onromanize = ctx
             .link()
             .batch_callback(|_: Event| Some(Msg::Mode(Mode::Unfiltered)));
@@ -425,39 +987,184 @@ impl Component for Prompt {
             move |_: Event| Some(Msg::CaptureClipboard(!capture_clipboard))
         });
 
+        let ongroup = ctx.link().batch_callback({
+            let group = self.query.group;
+            move |_: Event| Some(Msg::Group(!group))
+        });
+
+        let oncompact = ctx.link().batch_callback({
+            let compact = self.query.compact;
+            move |_: Event| Some(Msg::Compact(!compact))
+        });
+
+        let onhard = ctx.link().batch_callback({
+            let hard = self.query.hard;
+            move |_: Event| Some(Msg::Hard(!hard))
+        });
+
+        let onstatus = ctx.link().batch_callback({
+            let status = self.query.status;
+            move |_: Event| Some(Msg::Status(!status))
+        });
+
+        let onsubtitles = ctx.link().batch_callback({
+            let subtitles = self.query.subtitles;
+            move |_: Event| Some(Msg::Subtitles(!subtitles))
+        });
+
+        let oninteraction = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+            Some(Msg::Interaction(Interaction::parse(&select.value())?))
+        });
+
+        let interactions = Interaction::VALUES.iter().map(|&interaction| {
+            let selected = self.query.interaction == interaction;
+            html!(<option value={interaction.ident()} {selected}>{interaction.title()}</option>)
+        });
+
+        let oncapturefocus = ctx.link().batch_callback({
+            let focused = self.capture_focus.focused;
+            move |_: Event| Some(Msg::CaptureFocus(!focused))
+        });
+
+        let capture_focus_title = if self.capture_focus.focused {
+            t(Text::CaptureFocused)
+        } else if self.capture_focus.claimed {
+            t(Text::CaptureFocusClaimed)
+        } else {
+            t(Text::ClaimCaptureFocus)
+        };
+
         let analyze = if self.query.text.is_empty() {
             let text = if self.query.embed {
-                "Nothing to analyze"
+                t(Text::NothingToAnalyze)
             } else {
-                "Type something in the prompt"
+                t(Text::TypeSomething)
             };
 
             html!(<div id="analyze" class="block row analyze-text empty">{text}</div>)
         } else {
             let on_analyze = ctx.link().callback(Msg::Analyze);
             let on_analyze_cycle = ctx.link().callback(|_| Msg::AnalyzeCycle);
-            html!(<c::AnalyzeToggle query={self.query.text.clone()} analyzed={self.analysis.clone()} index={self.query.index} analyze_at={self.query.analyze_at} {on_analyze} {on_analyze_cycle} />)
+            let on_analyze_span = ctx.link().callback(Msg::AnalyzeSpan);
+            let on_word_status = ctx
+                .link()
+                .callback(|(sequence, status)| Msg::SetWordStatus(sequence, status));
+            let toggle = html!(<c::AnalyzeToggle query={self.query.text.clone()} analyzed={self.analysis.clone()} index={self.query.index} analyze_at={self.query.analyze_at} difficulty={self.difficulty.clone()} word_status={self.word_status.clone()} interaction={self.query.interaction} {on_analyze} {on_analyze_cycle} {on_analyze_span} {on_word_status} />);
+
+            // Dragging over the text selects it instead when selecting.
+            if self.query.interaction == Interaction::Select {
+                toggle
+            } else {
+                let gesture::Handlers {
+                    onpointerdown,
+                    onpointerup,
+                    onpointercancel,
+                } = self.swipe.handlers(ctx.link().callback(Msg::Swipe));
+
+                html!(<div class="swipe" {onpointerdown} {onpointerup} {onpointercancel}>{toggle}</div>)
+            }
         };
 
-        let translation = self.query.translation.as_ref().map(|text| {
+        let mut translation = self.query.translation.as_ref().map(|text| {
             html! {
                 <div class="block row" id="translation">
-                    <span class="translation-title">{"Translation:"}</span>
+                    <span class="translation-title">{t(Text::Translation)}</span>
                     {spacing()}
                     <span>{text}</span>
                 </div>
             }
         });
 
+        // Dual subtitles stack the translation under the analyzed text, where
+        // it's blurred until hovered unless configured otherwise.
+        let analyze = match &self.query.translation {
+            Some(text) if self.query.subtitles && !self.query.text.is_empty() => {
+                translation = None;
+
+                let class = classes!(
+                    "subtitle-translation",
+                    (!Display::load().reveal_translations).then_some("blurred")
+                );
+
+                html! {
+                    <div class="subtitles">
+                        {analyze}
+                        <div {class} title={t(Text::Translation)}>{text}</div>
+                    </div>
+                }
+            }
+            _ => analyze,
+        };
+
+        let filtered = self
+            .phrases
+            .iter()
+            .filter(|e| match self.query.pos {
+                Some(group) => has_group(e, group),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
+        // Entries looked up by analyzing text can be mined from the sentence
+        // being analyzed.
+        let sentence = self
+            .query
+            .analyze_at
+            .map(|at| lib::mining::sentence_at(self.query.text.as_ref(), at).to_owned())
+            .filter(|sentence| !sentence.is_empty());
+
         let phrases = (!self.phrases.is_empty()).then(|| {
-            let phrases = self.phrases.iter().take(self.limit_entries).map(|e| {
+            let phrases = filtered.iter().take(self.limit_entries).map(|e| {
                 let entry = e.phrase.clone();
 
                 let change = ctx.link().callback(|(input, translation)| {
                     Msg::ForceChange(input, translation)
                 });
 
-                html!(<c::Entry embed={self.query.embed} sources={e.key.sources.clone()} entry={entry} onchange={change} />)
+                let variants = (!e.variants.is_empty()).then(|| {
+                    let sequence = e.phrase.sequence;
+                    let expanded = self.expanded.contains(&sequence);
+                    let onclick = ctx.link().callback(move |_| Msg::ToggleVariants(sequence));
+
+                    let title = i18n::variants(e.variants.len(), expanded);
+
+                    let entries = expanded.then(|| {
+                        let entries = e.variants.iter().map(|v| {
+                            let change = ctx.link().callback(|(input, translation)| {
+                                Msg::ForceChange(input, translation)
+                            });
+
+                            html!(<c::Entry ws={ctx.props().ws.clone()} embed={self.query.embed} tts={self.tts} anki={self.anki} sources={v.key.sources.clone()} entry={v.phrase.clone()} sense_examples={v.sense_examples.clone()} difficulty={v.difficulty} annotation={v.annotation.clone()} dictionary={self.dictionary(&v.dictionary)} sentence={sentence.clone()} onchange={change} />)
+                        });
+
+                        let entries = seq(entries, |entry, not_last| {
+                            if not_last {
+                                html!(<>{entry}<div class="entry-separator" /></>)
+                            } else {
+                                entry
+                            }
+                        });
+
+                        html!(<div class="block variants">{for entries}</div>)
+                    });
+
+                    html! {
+                        <>
+                        <div class="block row">
+                            <button class="btn btn-sm" {onclick}>{title}</button>
+                        </div>
+                        {for entries}
+                        </>
+                    }
+                });
+
+                html! {
+                    <>
+                    <c::Entry ws={ctx.props().ws.clone()} embed={self.query.embed} tts={self.tts} anki={self.anki} sources={e.key.sources.clone()} entry={entry} sense_examples={e.sense_examples.clone()} difficulty={e.difficulty} annotation={e.annotation.clone()} dictionary={self.dictionary(&e.dictionary)} sentence={sentence.clone()} onchange={change} />
+                    {for variants}
+                    </>
+                }
             });
 
             let phrases = seq(phrases, |entry, not_last| {
@@ -468,40 +1175,74 @@ impl Component for Prompt {
                 }
             });
 
-            let more = (self.phrases.len() > self.limit_entries).then(|| {
+            let more = (filtered.len() > self.limit_entries).then(|| {
                 html! {
                     <div class="block block-lg">
                         <div class="block row">
-                            {format!("Showing {} out of {} phrases", self.limit_entries, self.phrases.len())}
+                            {i18n::showing(self.limit_entries, filtered.len(), Shown::Phrases)}
                         </div>
 
                         <div class="block row">
-                            <button class="btn" onclick={ctx.link().callback(|_| Msg::MoreEntries)}>{"Show more"}</button>
+                            <button class="btn" onclick={ctx.link().callback(|_| Msg::MoreEntries)}>{t(Text::ShowMore)}</button>
                         </div>
                     </div>
                 }
             });
 
             let header = (!self.query.embed).then(|| {
-                html!(<h4>{"Phrases"}</h4>)
+                html!(<h4>{t(Text::Phrases)}</h4>)
+            });
+
+            let searching = self.searching.then(|| {
+                html!(<div class="block row searching" role="status" aria-live="polite">{t(Text::LoadingMore)}</div>)
+            });
+
+            let chips = FILTER_GROUPS.iter().flat_map(|&group| {
+                let count = self.facets.get(&group).copied().unwrap_or_default();
+                let active = self.query.pos == Some(group);
+
+                if count == 0 && !active {
+                    return None;
+                }
+
+                let pos = (!active).then_some(group);
+                let onclick = ctx.link().callback(move |_| Msg::Pos(pos));
+
+                Some(html! {
+                    <span class={classes!("chip", "clickable", active.then_some("active"))} {onclick}>
+                        {format!("{} ({count})", group.title())}
+                    </span>
+                })
+            }).collect::<Vec<_>>();
+
+            let chips = (!chips.is_empty()).then(|| {
+                html!(<div class="block row chips">{for chips}</div>)
             });
 
             html! {
                 <div class="block block-lg">
                     {header}
+                    {chips}
                     {for phrases}
+                    {for searching}
                     {for more}
                 </div>
             }
         });
 
+        let phrases = if self.query.compact {
+            self.render_summaries(ctx)
+        } else {
+            phrases
+        };
+
         let names = (!self.names.is_empty()).then(|| {
             let names = self
                 .names
                 .iter()
-                .map(|e| html!(<c::Name embed={self.query.embed} entry={e.name.clone()} />));
+                .map(|e| html!(<c::Name embed={self.query.embed} entry={e.name.clone()} dictionary={self.dictionary(&e.dictionary)} />));
 
-            let header = (!self.query.embed).then(|| html!(<h4>{"Names"}</h4>));
+            let header = (!self.query.embed).then(|| html!(<h4>{t(Text::Names)}</h4>));
 
             html! {
                 <>
@@ -512,44 +1253,66 @@ impl Component for Prompt {
         });
 
         let kanjis = (!self.characters.is_empty()).then(|| {
-            let iter = seq(self.characters.iter().take(self.limit_characters), |c, not_last| {
+            let characters = self.sorted_characters();
+
+            let iter = seq(characters.iter().take(self.limit_characters), |c, not_last| {
                 let separator = not_last.then(|| html!(<div class="character-separator" />));
 
-                html! {
-                    <>
-                        <c::Character embed={self.query.embed} character={c.clone()} />
-                        {for separator}
+                let change = ctx.link().callback(|(input, translation)| {
+                    Msg::ForceChange(input, translation)
+                });
+
+                html! {
+                    <>
+                        <c::Character ws={ctx.props().ws.clone()} embed={self.query.embed} character={(*c).clone()} onchange={change} />
+                        {for separator}
                     </>
                 }
             });
 
-            let more = (self.characters.len() > self.limit_characters).then(|| {
+            let more = (characters.len() > self.limit_characters).then(|| {
                 html! {
                     <div class="block block-lg">
                         <div class="block row">
-                            {format!("Showing {} out of {} characters", self.limit_characters, self.characters.len())}
+                            {i18n::showing(self.limit_characters, characters.len(), Shown::Characters)}
                         </div>
 
                         <div class="block row">
-                            <button class="btn" onclick={ctx.link().callback(|_| Msg::MoreCharacters)}>{"Show more"}</button>
+                            <button class="btn" onclick={ctx.link().callback(|_| Msg::MoreCharacters)}>{t(Text::ShowMore)}</button>
                         </div>
                     </div>
                 }
             });
 
             let header = (!self.query.embed).then(|| {
-                html!(<h4>{"Kanji"}</h4>)
+                html!(<h4>{t(Text::Kanji)}</h4>)
+            });
+
+            let controls = (!self.query.embed).then(|| self.render_kanji_controls(ctx));
+
+            let empty = characters.is_empty().then(|| {
+                html!(<div class="block row">{t(Text::NoKanjiMatch)}</div>)
             });
 
             html! {
                 <div class="block block-lg">
                     {header}
+                    {controls}
                     {for iter}
+                    {for empty}
                     {for more}
                 </div>
             }
         });
 
+        let examples = (!self.examples.is_empty()).then(|| {
+            let onchange = ctx.link().callback(|(input, translation)| {
+                Msg::ForceChange(input, translation)
+            });
+
+            html!(<c::Examples embed={self.query.embed} examples={self.examples.clone()} total={self.examples_total} {onchange} />)
+        });
+
         let page = if self.query.embed {
             let tab = |title: &str, len: usize, tab: Tab| {
                 let is_tab = self.query.tab == tab;
@@ -569,9 +1332,10 @@ impl Component for Prompt {
             };
 
             let tabs = [
-                tab("Phrases", self.phrases.len(), Tab::Phrases),
-                tab("Names", self.names.len(), Tab::Names),
-                tab("Kanji", self.characters.len(), Tab::Kanji),
+                tab(t(Text::Phrases), self.phrases.len(), Tab::Phrases),
+                tab(t(Text::Names), self.names.len(), Tab::Names),
+                tab(t(Text::Kanji), self.characters.len(), Tab::Kanji),
+                tab(t(Text::Examples), self.examples_total, Tab::Examples),
             ];
 
             let content = match self.query.tab {
@@ -584,6 +1348,9 @@ impl Component for Prompt {
                 Tab::Kanji => {
                     html!(<div class="block block-lg kanjis">{kanjis}</div>)
                 }
+                Tab::Examples => {
+                    html!(<div class="block block-lg">{examples}</div>)
+                }
                 Tab::Settings => {
                     let onback = ctx.link().callback(|_| Msg::Tab(Tab::Phrases));
                     html!(<div class="block block-lg"><c::Config embed={self.query.embed} log={self.log.clone()} ws={ctx.props().ws.clone()} {onback} /></div>)
@@ -593,6 +1360,7 @@ impl Component for Prompt {
             html! {
                 <>
                     <div class="block block-lg">{analyze}</div>
+                    {self.render_recognized(ctx)}
                     {for translation}
                     <div class="tabs">{for tabs}</div>
                     {content}
@@ -606,44 +1374,103 @@ impl Component for Prompt {
                 }
                 _ => {
                     let onclick = ctx.link().callback(|_| Msg::OpenConfig);
+                    let timeline = self.timeline.then(|| self.render_timeline(ctx));
 
                     let prompt = html! {
                         <>
-                        <div class="block block row" id="prompt">
-                            <input value={self.query.text.clone()} type="text" oninput={oninput} />
+                        <div class="block block row" id="prompt" role="search">
+                            <input ref={self.input.clone()} value={self.query.text.clone()} type="text" aria-label={t(Text::Search)} oninput={oninput} />
                         </div>
 
+                        {self.render_detected(ctx)}
+
                         <div class="block block-lg row row-spaced">
-                            <label for="romanize" title="Do not process input at all">
+                            {self.render_navigation(ctx)}
+
+                            <label for="auto" title={t(Text::AutoHelp)}>
+                                <input type="checkbox" id="auto" checked={self.query.mode == Mode::Auto} onchange={onauto} />
+                                {t(Text::Auto)}
+                            </label>
+
+                            <label for="romanize" title={t(Text::UnfilteredHelp)}>
                                 <input type="checkbox" id="romanize" checked={self.query.mode == Mode::Unfiltered} onchange={onromanize} />
-                                {"Default"}
+                                {t(Text::Unfiltered)}
                             </label>
 
-                            <label for="hiragana" title="Process input as Hiragana">
+                            <label for="hiragana" title={t(Text::HiraganaHelp)}>
                                 <input type="checkbox" id="hiragana" checked={self.query.mode == Mode::Hiragana} onchange={onhiragana} />
                                 {"ひらがな"}
                             </label>
 
-                            <label for="katakana" title="Treat input as Katakana">
+                            <label for="katakana" title={t(Text::KatakanaHelp)}>
                                 <input type="checkbox" id="katakana" checked={self.query.mode == Mode::Katakana} onchange={onkatakana} />
                                 {"カタカナ"}
                             </label>
 
-                            <label for="clipboard" title="Capture clipboard">
+                            <label for="clipboard" title={t(Text::CaptureClipboard)}>
                                 <input type="checkbox" id="clipboard" checked={self.query.capture_clipboard} onchange={oncaptureclipboard} />
                                 {"📋"}
                             </label>
 
-                            <span class="end clickable" {onclick}>{"⚙ Config"}</span>
+                            <label for="group" title={t(Text::GroupHelp)}>
+                                <input type="checkbox" id="group" checked={self.query.group} onchange={ongroup} />
+                                {t(Text::Group)}
+                            </label>
+
+                            <label for="compact" title={t(Text::CompactHelp)}>
+                                <input type="checkbox" id="compact" checked={self.query.compact} onchange={oncompact} />
+                                {t(Text::Compact)}
+                            </label>
+
+                            <label for="hard" title={t(Text::HardWordsHelp)}>
+                                <input type="checkbox" id="hard" checked={self.query.hard} onchange={onhard} />
+                                {t(Text::HardWords)}
+                            </label>
+
+                            <label for="status" title={t(Text::WordStatusHelp)}>
+                                <input type="checkbox" id="status" checked={self.query.status} onchange={onstatus} />
+                                {t(Text::WordStatus)}
+                            </label>
+
+                            <label for="subtitles" title={t(Text::SubtitlesHelp)}>
+                                <input type="checkbox" id="subtitles" checked={self.query.subtitles} onchange={onsubtitles} />
+                                {t(Text::Subtitles)}
+                            </label>
+
+                            <select id="interaction" title={t(Text::InteractionHelp)} onchange={oninteraction}>{for interactions}</select>
+
+                            <label for="capture-focus" title={capture_focus_title}>
+                                <input type="checkbox" id="capture-focus" checked={self.capture_focus.focused} onchange={oncapturefocus} />
+                                {icon("🎯", t(Text::CaptureFocus))}
+                            </label>
+
+                            <button class="end btn" {onclick}>{format!("⚙ {}", t(Text::Config))}</button>
                         </div>
+
+                        {for timeline}
                         </>
                     };
 
-                    let kanjis = kanjis.map(|kanjis| {
-                        html! {
-                            <div class="column characters">{kanjis}</div>
-                        }
-                    });
+                    let sections = vec![
+                        Section {
+                            title: t(Text::Entries),
+                            len: self.phrases.len() + self.names.len(),
+                            side: false,
+                            content: html!(<>{phrases}{names}</>),
+                        },
+                        Section {
+                            title: t(Text::Kanji),
+                            len: self.characters.len(),
+                            side: true,
+                            content: html!(<>{for kanjis}</>),
+                        },
+                        Section {
+                            title: t(Text::Examples),
+                            len: self.examples_total,
+                            side: false,
+                            content: html!(<>{for examples}</>),
+                        },
+                    ];
 
                     html! {
                         <>
@@ -651,12 +1478,11 @@ impl Component for Prompt {
 
                             <>
                                 <div class="block block-xl">{analyze}</div>
+                                {self.render_candidates(ctx)}
+                                {self.render_recognized(ctx)}
                                 {for translation}
 
-                                <div class="columns">
-                                    <div class="column">{phrases}{names}</div>
-                                    {for kanjis}
-                                </div>
+                                <c::Sections {sections} />
                             </>
                         </>
                     }
@@ -713,24 +1539,31 @@ impl Component for Prompt {
             }
         });
 
-        let missing = (self.query.tab != Tab::Settings && !self.missing.is_empty()).then(|| {
-            let missing = seq(self.missing.iter(), |id, not_last| {
-                html! {
-                    <>
-                        <span>{id}</span>
-                        {not_last.then(comma)}
-                    </>
-                }
-            });
+        let onboarding = (self.onboarding && self.query.tab != Tab::Settings).then(|| {
+            let ondone = ctx.link().callback(|()| Msg::Onboarded);
 
+            html! {
+                <c::Onboarding ws={ctx.props().ws.clone()} missing={self.missing.clone()} {ondone} />
+            }
+        });
+
+        let missing =
+            (!self.onboarding && self.query.tab != Tab::Settings).then(|| self.view_missing(ctx));
+
+        // Dictionaries which are configured but missing are listed above, so
+        // this only covers when none are installed or enabled at all.
+        let index_missing = (self.query.tab != Tab::Settings
+            && !self.onboarding
+            && self.index_missing
+            && self.missing.is_empty())
+        .then(|| {
             let onclick = ctx.link().callback(|_| Msg::Tab(Tab::Settings));
 
             html! {
                 <div class="block block-lg danger">
                     <div class="block block-sm row row-spaced">
-                        <span class="title">{"Dictionaries missing:"}</span>
-                        <span>{for missing}</span>
-                        <button class="end btn btn-lg" {onclick}>{"⚙ Fix in Settings"}</button>
+                        <span class="title">{t(Text::NoDictionaries)}</span>
+                        <button class="end btn btn-lg" {onclick}>{format!("⚙ {}", t(Text::FixInSettings))}</button>
                     </div>
                 </div>
             }
@@ -759,58 +1592,101 @@ impl Component for Prompt {
             html! {
                 <div class="block block-lg danger">
                     <div class="block block-sm row row-spaced">
-                        <span class="title">{"OCR support is enabled but not installed"}</span>
+                        <span class="title">{t(Text::OcrNotInstalled)}</span>
                     </div>
 
                     <div class="block block-sm row row-spaced">
                         {for install_url}
-                        <button class="end btn btn-lg" {onclick}>{"⚙ Disable"}</button>
+                        <button class="end btn btn-lg" {onclick}>{format!("⚙ {}", t(Text::Disable))}</button>
                     </div>
                 </div>
             }
         });
 
+        let update = self
+            .update
+            .as_ref()
+            .filter(|_| !self.query.embed)
+            .map(|release| {
+                let href = release.url.clone();
+
+                html! {
+                    <div class="block block-lg notice">
+                        <div class="block block-sm row row-spaced">
+                            <span class="title">{t(Text::UpdateAvailable)}</span>
+                            <span>{&release.version}</span>
+                            <a {href} class="end btn btn-lg" target="_release">{t(Text::ShowRelease)}</a>
+                        </div>
+                    </div>
+                }
+            });
+
         let window_top = {
             let onclick = ctx.link().callback(|_| Msg::Tab(Tab::Phrases));
 
             let search = html! {
-                <a class="search clickable" title="Search" {onclick}>{"🔍"}</a>
+                <a class="search clickable" title={t(Text::Search)} role="button" tabindex="0" {onclick}>{icon("🔍", t(Text::Search))}</a>
             };
 
             let onclick = ctx.link().callback(|_| Msg::Tab(Tab::Settings));
 
             let config = html! {
-                <a class="config clickable" {onclick} title="Configure">{"⚙"}</a>
+                <a class="config clickable" {onclick} title={t(Text::Configure)} role="button" tabindex="0">{icon("⚙", t(Text::Configure))}</a>
             };
 
+            let reader = (!self.query.embed).then(|| {
+                html! {
+                    <>
+                        <Link<crate::Route> classes="reader clickable" to={crate::Route::Reader}>{icon("📖", t(Text::Reader))}</Link<crate::Route>>
+                        <Link<crate::Route> classes="quiz clickable" to={crate::Route::Quiz}>{icon("✍", t(Text::Quiz))}</Link<crate::Route>>
+                        <Link<crate::Route> classes="compare clickable" to={crate::Route::Compare}>{icon("⚖", t(Text::Compare))}</Link<crate::Route>>
+                        <Link<crate::Route> classes="stats clickable" to={crate::Route::Stats}>{icon("📊", t(Text::Statistics))}</Link<crate::Route>>
+                        <Link<crate::Route> classes="mining clickable" to={crate::Route::Mining}>{icon("⛏", t(Text::MinedSentences))}</Link<crate::Route>>
+                        <Link<crate::Route> classes="feed clickable" to={crate::Route::Feed}>{icon("📜", t(Text::Feed))}</Link<crate::Route>>
+                    </>
+                }
+            });
+
             let maximize = if self.query.embed {
                 self.query.to_href(true).map(|href| {
                     html! {
-                        <a class="maximize clickable" {href} target="_window" title="Open in big window">{"🗖"}</a>
+                        <a class="maximize clickable" {href} target="_window" title={t(Text::OpenInBigWindow)}>{icon("🗖", t(Text::OpenInBigWindow))}</a>
                     }
                 })
             } else {
                 None
             };
 
+            // Links are copied from here, opening them shows the same query.
+            let short_link = (!self.query.embed && !self.query.text.is_empty())
+                .then(|| self.query.to_short_href())
+                .flatten()
+                .map(|href| {
+                    html! {
+                        <a class="short-link clickable" {href} title={t(Text::ShortLink)}>{icon("🔗", t(Text::ShortLink))}</a>
+                    }
+                });
+
             html! {
-                <div id="window-top">
+                <div id="window-top" role="banner">
                     <div class="container">
-                        <span class="left">
+                        <span class="left" role="navigation" aria-label={t(Text::Pages)}>
                             {search}
                             {config}
+                            {reader}
                         </span>
                         <span></span>
                         <span class="title">
-                            <a href="https://github.com/udoprog/jpv">{"Japanese Dictionary"}</a>
+                            <a href="https://github.com/udoprog/jpv">{t(Text::AppName)}</a>
                             <span class="sub-title">
-                                <span>{"by "}</span>
+                                <span>{t(Text::By)}</span>
                                 <a href="https://udoprog.github.io">{"John-John Tedro"}</a>
                             </span>
                         </span>
                         <span></span>
                         <span class="right">
                             {maximize}
+                            {short_link}
                         </span>
                     </div>
                 </div>
@@ -826,8 +1702,11 @@ impl Component for Prompt {
             <>
                 {window_top}
 
-                <div id="content" {class}>
+                <div id="content" {class} role="main">
+                    {update}
+                    {onboarding}
                     {missing}
+                    {index_missing}
                     {missing_ocr}
                     {tasks}
                     {page}
@@ -850,17 +1729,17 @@ impl Prompt {
     }
 }
 
-fn process_query<'a, F>(input: &'a str, segment: F) -> Rc<str>
-where
-    F: Copy + FnOnce(&romaji::Segment<'a>) -> &'a str,
-{
-    let mut out = String::new();
-
-    for s in romaji::analyze(input) {
-        out.push_str(segment(&s));
-    }
+fn process_query(input: &str, transform: Transform) -> Rc<str> {
+    Rc::from(romaji::transform(input, transform))
+}
 
-    Rc::from(out)
+/// Test if the given phrase has a sense in the given part of speech group.
+fn has_group(phrase: &api::OwnedSearchPhrase, group: PartOfSpeechGroup) -> bool {
+    phrase
+        .phrase
+        .senses
+        .iter()
+        .any(|sense| sense.pos.iter().any(|pos| pos.group() == group))
 }
 
 fn decode_query(location: Option<Location>) -> Query {
@@ -869,8 +1748,12 @@ fn decode_query(location: Option<Location>) -> Query {
         None => None,
     };
 
-    let query = query.unwrap_or_default();
-    let (mut query, analyze_at_char) = Query::deserialize(query);
+    decode_raw(query.unwrap_or_default())
+}
+
+/// Decode a query from its serialized form.
+fn decode_raw(raw: Vec<(String, String)>) -> Query {
+    let (mut query, analyze_at_char) = Query::deserialize(raw);
 
     if let Some(analyze_at_char) = analyze_at_char {
         query.update_analyze_at_char(analyze_at_char);
@@ -880,40 +1763,306 @@ fn decode_query(location: Option<Location>) -> Query {
 }
 
 fn copyright() -> Html {
-    html! {
-        <>
-            <div class="block inline">
-                <span>{"Made with ❤️ by "}</span>
-                <a href="https://udoprog.github.io">{"John-John Tedro"}</a>
-                <span>{", freely available forever under the "}</span>
-                <a href="https://github.com/udoprog/jpv/blob/main/LICENSE-MIT">{"MIT"}</a>
-                <span>{" or "}</span>
-                <a href="https://github.com/udoprog/jpv/blob/main/LICENSE-APACHE">{"Apache 2.0 license"}</a>
-            </div>
+    match i18n::language() {
+        Language::English => html! {
+            <>
+                <div class="block inline">
+                    <span>{"Made with ❤️ by "}</span>
+                    <a href="https://udoprog.github.io">{"John-John Tedro"}</a>
+                    <span>{", freely available forever under the "}</span>
+                    <a href="https://github.com/udoprog/jpv/blob/main/LICENSE-MIT">{"MIT"}</a>
+                    <span>{" or "}</span>
+                    <a href="https://github.com/udoprog/jpv/blob/main/LICENSE-APACHE">{"Apache 2.0 license"}</a>
+                </div>
 
-            <div class="block inline">
-                <span>{"This application uses "}</span>
-                <a href="https://www.edrdg.org/wiki/index.php/JMdict-EDICT_Dictionary_Project">{"JMDICT"}</a>
-                <span>{", "}</span>
-                <a href="https://www.edrdg.org/wiki/index.php/KANJIDIC_Project">{"KANJIDIC2"}</a>
-                <span>{", and "}</span>
-                <a href="http://edrdg.org/enamdict/enamdict_doc.html">{"ENAMDICT"}</a>
-                <span>{" which is the property of the "}</span>
-                <a href="https://www.edrdg.org">{"EDRDG"}</a>
-                <span>{" and is used in conformance with its "}</span>
-                <a href="https://www.edrdg.org/edrdg/licence.html">{"licence"}</a>
-                <span>{"."}</span>
-            </div>
-        </>
+                <div class="block inline">
+                    <span>{"This application uses "}</span>
+                    <a href="https://www.edrdg.org/wiki/index.php/JMdict-EDICT_Dictionary_Project">{"JMDICT"}</a>
+                    <span>{", "}</span>
+                    <a href="https://www.edrdg.org/wiki/index.php/KANJIDIC_Project">{"KANJIDIC2"}</a>
+                    <span>{", and "}</span>
+                    <a href="http://edrdg.org/enamdict/enamdict_doc.html">{"ENAMDICT"}</a>
+                    <span>{" which is the property of the "}</span>
+                    <a href="https://www.edrdg.org">{"EDRDG"}</a>
+                    <span>{" and is used in conformance with its "}</span>
+                    <a href="https://www.edrdg.org/edrdg/licence.html">{"licence"}</a>
+                    <span>{"."}</span>
+                </div>
+            </>
+        },
+        // The order of the sentences differs, so links can't be put between
+        // translated texts.
+        Language::Japanese => html! {
+            <>
+                <div class="block inline">
+                    <a href="https://udoprog.github.io">{"John-John Tedro"}</a>
+                    <span>{"が❤️を込めて作りました。"}</span>
+                    <a href="https://github.com/udoprog/jpv/blob/main/LICENSE-MIT">{"MIT"}</a>
+                    <span>{"または"}</span>
+                    <a href="https://github.com/udoprog/jpv/blob/main/LICENSE-APACHE">{"Apache 2.0ライセンス"}</a>
+                    <span>{"のもとで、いつまでも無料で利用できます。"}</span>
+                </div>
+
+                <div class="block inline">
+                    <span>{"このアプリケーションは"}</span>
+                    <a href="https://www.edrdg.org/wiki/index.php/JMdict-EDICT_Dictionary_Project">{"JMDICT"}</a>
+                    <span>{"、"}</span>
+                    <a href="https://www.edrdg.org/wiki/index.php/KANJIDIC_Project">{"KANJIDIC2"}</a>
+                    <span>{"、"}</span>
+                    <a href="http://edrdg.org/enamdict/enamdict_doc.html">{"ENAMDICT"}</a>
+                    <span>{"を使用しています。これらは"}</span>
+                    <a href="https://www.edrdg.org">{"EDRDG"}</a>
+                    <span>{"の所有物であり、その"}</span>
+                    <a href="https://www.edrdg.org/edrdg/licence.html">{"ライセンス"}</a>
+                    <span>{"に従って使用されています。"}</span>
+                </div>
+            </>
+        },
     }
 }
 
 impl Prompt {
+    /// Render compact summaries of results, with full entries for the ones
+    /// which are expanded.
+    fn render_summaries(&self, ctx: &Context<Self>) -> Option<Html> {
+        if self.summaries.is_empty() {
+            return None;
+        }
+
+        let summaries = self.summaries.iter().take(self.limit_entries).map(|s| {
+            let is_phrase = s.kind == api::SummaryKind::Phrase;
+            let expanded = is_phrase && self.expanded.contains(&s.sequence);
+
+            let reading = s
+                .reading
+                .as_ref()
+                .map(|reading| html!(<span class="summary-reading">{format!("【{reading}】")}</span>));
+
+            let gloss = s
+                .gloss
+                .as_ref()
+                .map(|gloss| html!(<span class="summary-gloss">{gloss}</span>));
+
+            let kind = (!is_phrase).then(|| html!(<span class="bullet sm">{"name"}</span>));
+
+            let priority = iter(s.priority.iter().map(render_priority), |iter| {
+                html!(<span class="bullets">{for iter}</span>)
+            });
+
+            let onclick = is_phrase.then(|| {
+                let sequence = s.sequence;
+                ctx.link().callback(move |_| Msg::ToggleSummary(sequence))
+            });
+
+            let class = classes!(
+                "block",
+                "row",
+                "row-spaced",
+                "summary",
+                is_phrase.then_some("clickable"),
+                expanded.then_some("active")
+            );
+
+            let detail = expanded.then(|| match self.details.get(&s.sequence) {
+                Some(entry) => {
+                    let change = ctx.link().callback(|(input, translation)| {
+                        Msg::ForceChange(input, translation)
+                    });
+
+                    html!(<c::Entry ws={ctx.props().ws.clone()} embed={self.query.embed} tts={self.tts} sources={BTreeSet::new()} entry={entry.clone()} onchange={change} />)
+                }
+                None => html!(<div class="block summary-loading">{t(Text::Loading)}</div>),
+            });
+
+            html! {
+                <>
+                    <div {class} {onclick}>
+                        <span class="summary-term">{&s.term}</span>
+                        {for reading}
+                        {for kind}
+                        {for priority}
+                        {for gloss}
+                    </div>
+                    {for detail}
+                </>
+            }
+        });
+
+        let more = (self.summaries.len() > self.limit_entries).then(|| {
+            html! {
+                <div class="block row">
+                    <button class="btn" onclick={ctx.link().callback(|_| Msg::MoreEntries)}>{t(Text::ShowMore)}</button>
+                </div>
+            }
+        });
+
+        let header = (!self.query.embed).then(|| html!(<h4>{t(Text::Results)}</h4>));
+
+        Some(html! {
+            <div class="block block-lg summaries">
+                {header}
+                {for summaries}
+                {for more}
+            </div>
+        })
+    }
+
+    /// Look up the dictionary with the given identifier.
+    fn dictionary(&self, id: &str) -> Option<Rc<api::Dictionary>> {
+        self.dictionaries.get(id).cloned()
+    }
+
+    /// Characters matching the kanji filter in the selected order.
+    fn sorted_characters(&self) -> Vec<&kanjidic2::OwnedCharacter> {
+        let mut characters = self
+            .characters
+            .iter()
+            .filter(|c| match self.query.kanji_filter {
+                KanjiFilter::All => true,
+                KanjiFilter::Kyouiku => matches!(c.misc.grade, Some(1..=6)),
+                KanjiFilter::Joyo => matches!(c.misc.grade, Some(1..=8)),
+                KanjiFilter::Jlpt => c.misc.jlpt.is_some(),
+            })
+            .collect::<Vec<_>>();
+
+        let key = |c: &kanjidic2::OwnedCharacter| match self.query.kanji_sort {
+            KanjiSort::Relevance => None,
+            KanjiSort::Grade => c.misc.grade.map(u32::from),
+            // Old JLPT levels go from 4 (easiest) to 1 (hardest).
+            KanjiSort::Jlpt => c.misc.jlpt.map(|level| 4u32.saturating_sub(level.into())),
+            KanjiSort::Frequency => c.misc.freq,
+            KanjiSort::Strokes => c.misc.stroke_count.map(u32::from),
+        };
+
+        // Stable sort, so that relevance breaks ties and characters without
+        // the field are last.
+        if self.query.kanji_sort != KanjiSort::Relevance {
+            characters.sort_by_key(|c| key(c).unwrap_or(u32::MAX));
+        }
+
+        characters
+    }
+
+    fn render_kanji_controls(&self, ctx: &Context<Self>) -> Html {
+        let onsort = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+            Some(Msg::KanjiSort(KanjiSort::parse(&select.value())?))
+        });
+
+        let onfilter = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_dyn_into::<web_sys::HtmlSelectElement>()?;
+            Some(Msg::KanjiFilter(KanjiFilter::parse(&select.value())?))
+        });
+
+        let sorts = KanjiSort::VALUES.iter().map(|&sort| {
+            let selected = self.query.kanji_sort == sort;
+            html!(<option value={sort.ident()} {selected}>{sort.title()}</option>)
+        });
+
+        let filters = KanjiFilter::VALUES.iter().map(|&filter| {
+            let selected = self.query.kanji_filter == filter;
+            html!(<option value={filter.ident()} {selected}>{filter.title()}</option>)
+        });
+
+        html! {
+            <div class="block row">
+                <label for="kanji-sort">{t(Text::SortBy)}</label>
+                {spacing()}
+                <select id="kanji-sort" onchange={onsort}>{for sorts}</select>
+                {spacing()}
+                <select id="kanji-filter" title={t(Text::FilterKanji)} onchange={onfilter}>{for filters}</select>
+            </div>
+        }
+    }
+
     fn get_config(&mut self, ctx: &Context<Self>) {
         self.get_config = Some(ctx.props().ws.request(
             api::GetConfig,
             ctx.link().callback(|result| match result {
-                Ok(state) => Msg::GetConfig(state),
+                Ok(state) => Msg::GetConfig(Box::new(state)),
+                Err(error) => Msg::Error(error),
+            }),
+        ));
+    }
+
+    /// Banners for dictionaries which are enabled but not installed, where
+    /// missing kanji data is called out since searches silently lack
+    /// characters without it.
+    fn view_missing(&self, ctx: &Context<Self>) -> Html {
+        let install = if self.installing {
+            html!(<button class="btn btn-lg" disabled=true>{t(Text::Installing)}</button>)
+        } else {
+            let onclick = ctx.link().callback(|_| Msg::Install);
+            html!(<button class="btn btn-lg" {onclick}>{t(Text::InstallNow)}</button>)
+        };
+
+        let kanji = self
+            .missing
+            .iter()
+            .any(|index| index.format == IndexFormat::Kanjidic2);
+
+        let kanji = kanji.then(|| {
+            html! {
+                <div class="block block-lg danger" role="alert">
+                    <div class="block block-sm row row-spaced">
+                        <span class="title">{t(Text::KanjiMissing)}</span>
+                        <span class="end">{install.clone()}</span>
+                    </div>
+                </div>
+            }
+        });
+
+        let others = self
+            .missing
+            .iter()
+            .filter(|index| index.format != IndexFormat::Kanjidic2)
+            .collect::<Vec<_>>();
+
+        let others = (!others.is_empty()).then(|| {
+            let names = seq(others.iter(), |index, not_last| {
+                html! {
+                    <>
+                        <span title={index.description.clone()}>{&index.id}</span>
+                        {not_last.then(comma)}
+                    </>
+                }
+            });
+
+            let onclick = ctx.link().callback(|_| Msg::Tab(Tab::Settings));
+
+            html! {
+                <div class="block block-lg danger" role="alert">
+                    <div class="block block-sm row row-spaced">
+                        <span class="title">{t(Text::DictionariesMissing)}</span>
+                        <span>{for names}</span>
+                        <span class="end">
+                            {install.clone()}
+                            {spacing()}
+                            <button class="btn btn-lg" {onclick}>{format!("⚙ {}", t(Text::FixInSettings))}</button>
+                        </span>
+                    </div>
+                </div>
+            }
+        });
+
+        html!(<>{kanji}{others}</>)
+    }
+
+    fn get_status(&mut self, ctx: &Context<Self>) {
+        self.get_status = Some(ctx.props().ws.request(
+            api::StatusRequest,
+            ctx.link().callback(|result| match result {
+                Ok(status) => Msg::IndexStatus(status),
+                Err(error) => Msg::Error(error),
+            }),
+        ));
+    }
+
+    fn get_update(&mut self, ctx: &Context<Self>) {
+        self.get_update = Some(ctx.props().ws.request(
+            api::UpdateRequest,
+            ctx.link().callback(|result| match result {
+                Ok(update) => Msg::Update(update),
                 Err(error) => Msg::Error(error),
             }),
         ));
@@ -922,6 +2071,8 @@ impl Prompt {
     fn reload(&mut self, ctx: &Context<Self>) {
         log::trace!("Reload");
 
+        self.request_highlights(ctx);
+
         if self.analyze(ctx) {
             return;
         }
@@ -929,19 +2080,105 @@ impl Prompt {
         self.search(ctx);
     }
 
+    /// Request everything used to highlight words in the query.
+    fn request_highlights(&mut self, ctx: &Context<Self>) {
+        self.request_difficulty(ctx);
+        self.request_word_status(ctx);
+    }
+
+    fn request_word_status(&mut self, ctx: &Context<Self>) {
+        if !self.query.status || self.query.text.is_empty() {
+            self.pending_word_status = ws::Request::empty();
+            self.word_status = Rc::from([]);
+            return;
+        }
+
+        self.pending_word_status = ctx.props().ws.request(
+            api::WordStatusRequest {
+                q: self.query.text.as_ref().to_owned(),
+            },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::WordStatusResponse(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    fn request_difficulty(&mut self, ctx: &Context<Self>) {
+        if !self.query.hard || self.query.text.is_empty() {
+            self.pending_difficulty = ws::Request::empty();
+            self.difficulty = Rc::from([]);
+            return;
+        }
+
+        self.pending_difficulty = ctx.props().ws.request(
+            api::DifficultyRequest {
+                q: self.query.text.as_ref().to_owned(),
+            },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::DifficultyResponse(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    /// The text to search for, which is either the selected analysis or the
+    /// query routed according to its detected script.
+    fn search_text(&self) -> Rc<str> {
+        if let Some(input) = self.analysis.get(self.query.index) {
+            return input.clone();
+        }
+
+        if self.query.mode == Mode::Auto && detect::detect(&self.query.text) == Script::Romaji {
+            return detect::to_hiragana(&self.query.text).into();
+        }
+
+        self.query.text.clone()
+    }
+
     fn search(&mut self, ctx: &Context<Self>) {
-        let text = if let Some(input) = self.analysis.get(self.query.index) {
-            input.clone()
-        } else {
-            self.query.text.clone()
-        };
+        let text = self.search_text();
 
         log::trace!("Search `{text}`");
 
-        let text = text.to_lowercase();
+        self.history_timeout = (!self.query.text.is_empty()).then(|| {
+            let link = ctx.link().clone();
+            let text = self.query.text.clone();
+            Timeout::new(HISTORY_DELAY, move || {
+                link.send_message(Msg::RecordHistory(text))
+            })
+        });
+
+        self.pending_examples = ctx.props().ws.request(
+            api::SearchExamplesRequest {
+                q: text.as_ref().to_owned(),
+            },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::SearchExamplesResponse(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+
+        let text = lib::query::to_lowercase(&text);
+
+        if self.query.compact {
+            self.pending_search = ctx.props().ws.request(
+                api::SearchSummariesRequest { q: text },
+                ctx.link().callback(|result| match result {
+                    Ok(response) => Msg::SummariesResponse(response),
+                    Err(error) => Msg::Error(error),
+                }),
+            );
+
+            return;
+        }
 
         self.pending_search = ctx.props().ws.request(
-            api::SearchRequest { q: text },
+            api::SearchRequest {
+                q: text,
+                group: self.query.group,
+                chunk: Some(SEARCH_CHUNK),
+            },
             ctx.link().callback(|result| match result {
                 Ok(response) => Msg::SearchResponse(response),
                 Err(error) => Msg::Error(error),
@@ -962,6 +2199,7 @@ impl Prompt {
             api::AnalyzeRequest {
                 q: input,
                 start: analyze,
+                end: self.query.analyze_end,
             },
             ctx.link().callback(|result| match result {
                 Ok(response) => Msg::AnalyzeResponse(response),
@@ -972,20 +2210,303 @@ impl Prompt {
         true
     }
 
+    /// Render which script the query was detected as, with a way to override
+    /// it.
+    fn render_detected(&self, ctx: &Context<Self>) -> Html {
+        if self.query.mode != Mode::Auto || !self.analysis.is_empty() {
+            return Html::default();
+        }
+
+        let (text, button, mode) = match detect::detect(&self.query.text) {
+            Script::Romaji => (
+                format!(
+                    "{} {}",
+                    t(Text::SearchingRomajiAs),
+                    detect::to_hiragana(&self.query.text)
+                ),
+                t(Text::SearchAsEnglish),
+                Mode::Unfiltered,
+            ),
+            Script::English => (
+                String::from(t(Text::SearchingAsEnglish)),
+                t(Text::SearchAsRomaji),
+                Mode::Hiragana,
+            ),
+            _ => return Html::default(),
+        };
+
+        let onclick = ctx.link().callback(move |_| Msg::Mode(mode));
+
+        html! {
+            <div class="block row row-spaced detected-script">
+                <span>{text}</span>
+                <button class="btn btn-sm" {onclick}>{button}</button>
+            </div>
+        }
+    }
+
+    /// Render the analyzed candidates in the order they are cycled through,
+    /// along with why they were ranked like they were.
+    fn render_candidates(&self, ctx: &Context<Self>) -> Html {
+        if self.analysis.len() <= 1 {
+            return Html::default();
+        }
+
+        let candidates =
+            self.analysis
+                .iter()
+                .zip(&self.scores)
+                .enumerate()
+                .map(|(index, (string, score))| {
+                    let title = i18n::candidate_score(
+                        score.total,
+                        score.frequency,
+                        score.length,
+                        score.pos,
+                    );
+
+                    let class = classes!(
+                        "chip",
+                        "clickable",
+                        (index == self.query.index).then_some("active")
+                    );
+                    let onclick = ctx.link().callback(move |_| Msg::AnalyzeSelect(index));
+                    html!(<span {class} {title} {onclick}>{string.clone()}</span>)
+                });
+
+        html! {
+            <div class="block row bullets analyze-candidates">{for candidates}</div>
+        }
+    }
+
+    /// Render the lines recognized in a captured image, with how confident
+    /// the OCR engine is in them and a way to correct them.
+    fn render_recognized(&self, ctx: &Context<Self>) -> Html {
+        let Some(recognized) = &self.recognized else {
+            return Html::default();
+        };
+
+        // Only shown while the recognized text is what's being analyzed.
+        if self.query.text.as_ref() != recognized.text {
+            return Html::default();
+        }
+
+        let lines = recognized.lines.iter().map(|line| {
+            let uncertain = line
+                .confidence
+                .is_some_and(|confidence| confidence < LOW_CONFIDENCE);
+
+            let class = classes!("ocr-line", uncertain.then_some("uncertain"));
+
+            let confidence = line.confidence.map(|confidence| {
+                html! {
+                    <span class="ocr-confidence" title={t(Text::Confidence)}>
+                        {format!("{:.0}%", confidence * 100.0)}
+                    </span>
+                }
+            });
+
+            html! {
+                <div {class}>
+                    <span>{line.text.clone()}</span>
+                    {for confidence}
+                </div>
+            }
+        });
+
+        let edit = match &recognized.correction {
+            Some(correction) => {
+                let oninput = ctx.link().batch_callback(|e: InputEvent| {
+                    let input = e.target_dyn_into::<HtmlInputElement>()?;
+                    Some(Msg::OcrCorrection(input.value()))
+                });
+
+                let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+                    e.prevent_default();
+                    Msg::CorrectOcr
+                });
+
+                let oncancel = ctx.link().callback(|_| Msg::CancelOcr);
+
+                html! {
+                    <form class="block row row-spaced ocr-correction" {onsubmit}>
+                        <input type="text" value={correction.clone()} {oninput} />
+                        <button class="btn primary" type="submit">{t(Text::Save)}</button>
+                        <button class="btn" type="button" onclick={oncancel}>{t(Text::Cancel)}</button>
+                    </form>
+                }
+            }
+            None => {
+                let onclick = ctx.link().callback(|_| Msg::EditOcr);
+
+                html! {
+                    <div class="block row">
+                        <button class="btn btn-sm" {onclick}>{t(Text::CorrectOcr)}</button>
+                    </div>
+                }
+            }
+        };
+
+        html! {
+            <div class="block" id="ocr">
+                <div class="block ocr-lines">{for lines}</div>
+                {edit}
+            </div>
+        }
+    }
+
+    fn render_navigation(&self, ctx: &Context<Self>) -> Html {
+        let onback = ctx.link().callback(|_| Msg::Back);
+        let onforward = ctx.link().callback(|_| Msg::Forward);
+
+        let ontimeline = ctx.link().batch_callback({
+            let timeline = self.timeline;
+            move |_: Event| Some(Msg::Timeline(!timeline))
+        });
+
+        html! {
+            <>
+                <button class="btn" title={t(Text::GoBack)} disabled={!self.navigation.can_back()} onclick={onback}>{"◀"}</button>
+                <button class="btn" title={t(Text::GoForward)} disabled={!self.navigation.can_forward()} onclick={onforward}>{"▶"}</button>
+
+                <label for="timeline" title={t(Text::TimelineHelp)}>
+                    <input type="checkbox" id="timeline" checked={self.timeline} onchange={ontimeline} />
+                    {t(Text::Timeline)}
+                </label>
+            </>
+        }
+    }
+
+    fn render_timeline(&self, ctx: &Context<Self>) -> Html {
+        let cursor = self.navigation.cursor();
+
+        let entries = self
+            .navigation
+            .entries()
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, entry)| {
+                let text = if entry.text.is_empty() {
+                    html!(<span class="timeline-empty">{t(Text::EmptyPrompt)}</span>)
+                } else {
+                    match entry
+                        .analyze_at
+                        .and_then(|at| Some((entry.text.get(..at)?, entry.text.get(at..)?)))
+                    {
+                        Some((head, tail)) => {
+                            html!(<>{head}<span class="timeline-analyzed">{tail}</span></>)
+                        }
+                        None => html!({ entry.text.clone() }),
+                    }
+                };
+
+                let onclick =
+                    (index != cursor).then(|| ctx.link().callback(move |_| Msg::Navigate(index)));
+
+                let class = classes!(
+                    "timeline-entry",
+                    (index == cursor).then_some("active"),
+                    onclick.is_some().then_some("clickable")
+                );
+
+                html!(<li {class} {onclick}>{text}</li>)
+            });
+
+        let onclear = ctx.link().callback(|_| Msg::ClearTimeline);
+
+        html! {
+            <div class="block block-lg timeline">
+                <div class="block row row-spaced">
+                    <span class="title">{t(Text::Timeline)}</span>
+                    <button class="btn end" disabled={self.navigation.entries().len() <= 1} onclick={onclear}>{t(Text::Clear)}</button>
+                </div>
+
+                <ul class="block">{for entries}</ul>
+            </div>
+        }
+    }
+
+    /// Navigate to the given entry in the navigation stack.
+    fn navigate(&mut self, ctx: &Context<Self>, index: usize) -> bool {
+        if index == self.navigation.cursor() {
+            return false;
+        }
+
+        let Some(entry) = self.navigation.go(index) else {
+            return false;
+        };
+
+        let query = decode_raw(entry.query.clone());
+        self.apply_query(ctx, query);
+        self.save_query(ctx, History::Replace);
+        true
+    }
+
+    /// Apply a query which was navigated to, reloading what has changed.
+    fn apply_query(&mut self, ctx: &Context<Self>, query: Query) {
+        let old = replace(&mut self.query, query);
+
+        log::trace!("From: {:?}", old);
+        log::trace!("To: {:?}", self.query);
+
+        if self.query.analyze_at != old.analyze_at
+            || self.query.analyze_end != old.analyze_end
+            || self.query.text != old.text
+        {
+            self.analysis = Rc::from([]);
+            self.reload(ctx);
+        } else if self.query.index != old.index
+            || self.query.group != old.group
+            || self.query.compact != old.compact
+        {
+            self.search(ctx);
+        }
+
+        if self.query.hard != old.hard {
+            self.request_difficulty(ctx);
+        }
+
+        if self.query.status != old.status {
+            self.request_word_status(ctx);
+        }
+    }
+
     fn save_query(&mut self, ctx: &Context<Prompt>, history: History) {
         let (Some(location), Some(navigator)) = (ctx.link().location(), ctx.link().navigator())
         else {
             return;
         };
 
+        // The navigator prefixes paths with the base path, so it has to be
+        // stripped from the current one.
         let path = location.path();
+
+        let path = match navigator
+            .basename()
+            .and_then(|base| path.strip_prefix(base))
+        {
+            Some("") => "/",
+            Some(path) => path,
+            None => path,
+        };
+
         let path = AnyRoute::new(path);
 
+        if !self.query.embed {
+            let entry = navigation::Entry::new(&self.query);
+
+            match history {
+                History::Push | History::Analyze => self.navigation.push(entry),
+                History::Replace => self.navigation.replace(entry),
+            }
+        }
+
         let query = self.query.serialize(false);
 
         let result = match history {
             History::Push => navigator.push_with_query_and_state(&path, &query, IsInternal::new()),
-            History::Replace => {
+            History::Replace | History::Analyze => {
                 navigator.replace_with_query_and_state(&path, &query, IsInternal::new())
             }
         };
@@ -995,23 +2516,42 @@ impl Prompt {
         }
     }
 
-    /// Update from what looks like JSON in a clipboard.
-    fn update_from_clipboard_json(
+    /// Update from a captured line and its translation.
+    fn update_from_captured_line(
         &mut self,
         ctx: &Context<Self>,
-        json: &lib::api::SendClipboardJson,
-    ) -> Result<(), Error> {
-        if self.query.capture_clipboard && self.query.text.as_ref() != json.primary.as_str() {
-            self.query.set(
-                json.primary.clone().into(),
-                json.secondary.as_ref().filter(|s| !s.is_empty()).cloned(),
-            );
+        text: String,
+        translation: Option<String>,
+    ) {
+        if self.query.capture_clipboard && self.query.text.as_ref() != text.as_str() {
+            self.query
+                .set(text.into(), translation.filter(|s| !s.is_empty()));
             self.analysis = Rc::from([]);
             self.save_query(ctx, History::Push);
+            self.request_highlights(ctx);
             self.search(ctx);
         }
+    }
 
-        Ok(())
+    /// Update from text recognized in a captured image, which is kept so that
+    /// it can be corrected.
+    fn update_from_recognized(
+        &mut self,
+        ctx: &Context<Self>,
+        recognized: api::OwnedRecognizedText,
+    ) {
+        if !self.query.capture_clipboard {
+            return;
+        }
+
+        self.recognized = Some(Recognized {
+            key: recognized.key,
+            text: recognized.text.clone(),
+            lines: recognized.lines,
+            correction: None,
+        });
+
+        self.update_from_captured_line(ctx, recognized.text, None);
     }
 
     /// Update from clipboard.
@@ -1022,15 +2562,15 @@ impl Prompt {
         data: &[u8],
     ) -> Result<(), Error> {
         if matches!(ty, Some("application/json")) {
-            let json = serde_json::from_slice::<lib::api::SendClipboardJson>(data)?;
-            self.update_from_clipboard_json(ctx, &json)?;
+            let json = serde_json::from_slice::<lib::api::CaptureJson>(data)?;
+            self.update_from_captured_line(ctx, json.text, json.translation);
             return Ok(());
         }
 
         // Heuristics.
-        if data.starts_with(&[b'{']) {
-            if let Ok(json) = serde_json::from_slice::<lib::api::SendClipboardJson>(data) {
-                self.update_from_clipboard_json(ctx, &json)?;
+        if data.starts_with(b"{") {
+            if let Ok(json) = serde_json::from_slice::<lib::api::CaptureJson>(data) {
+                self.update_from_captured_line(ctx, json.text, json.translation);
                 return Ok(());
             }
         }
@@ -1041,6 +2581,7 @@ impl Prompt {
             self.query.set(data.into(), None);
             self.analysis = Rc::from([]);
             self.save_query(ctx, History::Push);
+            self.request_highlights(ctx);
             self.search(ctx);
         }
 
@@ -0,0 +1,239 @@
+use lib::api;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::error::Error;
+use crate::i18n::{t, Text};
+use crate::ws;
+use crate::Route;
+
+use super::spacing;
+
+pub(crate) enum Msg {
+    Next,
+    Question(api::QuizResponse),
+    Answer(String),
+    Submit,
+    Checked(api::QuizAnswerResponse),
+    Error(Error),
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+}
+
+pub(crate) struct Quiz {
+    /// The question being asked, `None` once it has been established that
+    /// there are no words to practice.
+    question: Option<api::QuizQuestion>,
+    loading: bool,
+    answer: String,
+    /// The result of answering the current question.
+    result: Option<api::QuizAnswerResponse>,
+    correct: usize,
+    answered: usize,
+    pending: ws::Request,
+}
+
+impl Component for Quiz {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::Next);
+
+        Self {
+            question: None,
+            loading: true,
+            answer: String::new(),
+            result: None,
+            correct: 0,
+            answered: 0,
+            pending: ws::Request::empty(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Next => {
+                self.loading = true;
+
+                self.pending = ctx.props().ws.request(
+                    api::QuizRequest::default(),
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Question(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                true
+            }
+            Msg::Question(response) => {
+                self.pending = ws::Request::empty();
+                self.loading = false;
+                self.question = response.question;
+                self.answer.clear();
+                self.result = None;
+                true
+            }
+            Msg::Answer(answer) => {
+                self.answer = answer;
+                true
+            }
+            Msg::Submit => {
+                if self.result.is_some() {
+                    ctx.link().send_message(Msg::Next);
+                    return false;
+                }
+
+                let Some(question) = &self.question else {
+                    return false;
+                };
+
+                if self.answer.trim().is_empty() {
+                    return false;
+                }
+
+                self.pending = ctx.props().ws.request(
+                    api::QuizAnswerRequest {
+                        sequence: question.sequence,
+                        text: question.text.clone(),
+                        inflection: question.inflection,
+                        answer: self.answer.clone(),
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Checked(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::Checked(response) => {
+                self.pending = ws::Request::empty();
+                self.answered += 1;
+
+                if response.correct {
+                    self.correct += 1;
+                }
+
+                self.result = Some(response);
+                true
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.loading = false;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <>
+                <div id="window-top">
+                    <div class="container">
+                        <span class="left">
+                            <Link<Route> classes="clickable" to={Route::Prompt}>{"🔍"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Reader}>{"📖"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Quiz}>{"✍"}</Link<Route>>
+                        </span>
+                        <span></span>
+                        <span class="title">{t(Text::Conjugation)}</span>
+                        <span></span>
+                        <span class="right"></span>
+                    </div>
+                </div>
+
+                <div id="content" class="container quiz">
+                    {self.view_question(ctx)}
+                </div>
+            </>
+        }
+    }
+}
+
+impl Quiz {
+    fn view_question(&self, ctx: &Context<Self>) -> Html {
+        if self.loading {
+            return html!(<div class="block row"><div class="spinner">{t(Text::Loading)}</div></div>);
+        }
+
+        let Some(question) = &self.question else {
+            return html! {
+                <div class="block row">
+                    {t(Text::NothingToPractice)}
+                </div>
+            };
+        };
+
+        let reading = (question.reading != question.text).then(
+            || html!(<span class="quiz-reading">{format!("（{}）", question.reading)}</span>),
+        );
+
+        let gloss = (!question.gloss.is_empty())
+            .then(|| html!(<div class="block row quiz-gloss">{question.gloss.clone()}</div>));
+
+        let oninput = ctx.link().batch_callback(|e: InputEvent| {
+            let input = e.target_dyn_into::<HtmlInputElement>()?;
+            Some(Msg::Answer(input.value()))
+        });
+
+        let onsubmit = ctx.link().callback(|e: SubmitEvent| {
+            e.prevent_default();
+            Msg::Submit
+        });
+
+        let result = self.result.as_ref().map(|result| {
+            let (class, title) = if result.correct {
+                ("quiz-correct", "Correct")
+            } else {
+                (
+                    "quiz-incorrect",
+                    "Incorrect, the word has been scheduled for review",
+                )
+            };
+
+            html! {
+                <div class={classes!("block", "row", class)}>
+                    <span>{title}</span>
+                    {spacing()}
+                    <span class="quiz-expected">{result.expected.join(" / ")}</span>
+                </div>
+            }
+        });
+
+        let next = if self.result.is_some() {
+            "Next"
+        } else {
+            "Check"
+        };
+
+        let score = (self.answered > 0).then(|| {
+            html!(<div class="block row">{format!("{} of {} correct", self.correct, self.answered)}</div>)
+        });
+
+        html! {
+            <>
+                <div class="block block-lg">
+                    <div class="block row quiz-word">
+                        <span>{question.text.clone()}</span>
+                        {reading}
+                    </div>
+                    {gloss}
+                    <div class="block row quiz-inflection">{question.explanation.clone()}</div>
+                </div>
+
+                <form class="block block-lg row row-spaced" {onsubmit}>
+                    <input type="text" placeholder="Conjugated form" value={self.answer.clone()} disabled={self.result.is_some()} {oninput} />
+                    <button class="btn primary" type="submit">{next}</button>
+                </form>
+
+                {result}
+                {score}
+            </>
+        }
+    }
+}
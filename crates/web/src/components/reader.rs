@@ -0,0 +1,841 @@
+use std::rc::Rc;
+
+use gloo::file::callbacks::FileReader;
+use lib::api;
+use lib::profile::WordStatus;
+use lib::reader::epub::Ruby;
+use lib::reader::{subtitles, DocumentInfo};
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::base;
+use crate::c;
+use crate::error::Error;
+use crate::i18n::{self, t, Text};
+use crate::ws;
+use crate::Route;
+
+use super::analyze_toggle::set_word_status;
+use super::{seq, spacing};
+
+/// The number of phrases shown for a looked up word.
+const PHRASES_LIMIT: usize = 5;
+
+pub(crate) enum Msg {
+    Documents(api::DocumentsResponse),
+    Title(String),
+    Text(String),
+    Ruby(Ruby),
+    Add,
+    Added(DocumentInfo),
+    Upload(web_sys::File),
+    Uploaded(String, String),
+    Delete(u64),
+    Page(Option<usize>),
+    Seek(u64),
+    PageResponse(api::PageResponse),
+    SelectLine(usize),
+    WordStatusResponse(api::WordStatusResponse),
+    SetWordStatus(u64, WordStatus),
+    WordStatusSet,
+    Time(String),
+    JumpTime,
+    LinesQuery(String),
+    SearchLines,
+    LinesResponse(api::SearchLinesResponse),
+    Stats(api::CoverageResponse),
+    Analyze(usize),
+    AnalyzeCycle,
+    AnalyzeResponse(api::OwnedAnalyzeResponse),
+    Search(String),
+    SearchResponse(api::OwnedSearchResponse),
+    Error(Error),
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+    /// The document being read, or `None` to list documents.
+    #[prop_or_default]
+    pub(crate) id: Option<u64>,
+}
+
+pub(crate) struct Reader {
+    documents: Option<Vec<DocumentInfo>>,
+    title: String,
+    text: String,
+    ruby: Ruby,
+    adding: bool,
+    page: Option<api::PageResponse>,
+    stats: Option<api::CoverageResponse>,
+    query: Rc<str>,
+    analyzed: Rc<[Rc<str>]>,
+    index: usize,
+    analyze_at: Option<usize>,
+    phrases: Vec<api::OwnedSearchPhrase>,
+    /// Status of words in the text being read.
+    word_status: Rc<[api::WordStatusSpan]>,
+    /// The selected timed line on the page.
+    line: Option<usize>,
+    time: String,
+    lines_query: String,
+    line_matches: Vec<api::LineMatch>,
+    upload: Option<FileReader>,
+    pending: ws::Request,
+    pending_page: ws::Request,
+    pending_stats: ws::Request,
+    pending_search: ws::Request,
+    pending_lines: ws::Request,
+    pending_word_status: ws::Request,
+    pending_set_word_status: ws::Request,
+}
+
+impl Component for Reader {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut this = Self {
+            documents: None,
+            title: String::new(),
+            text: String::new(),
+            ruby: Ruby::default(),
+            adding: false,
+            page: None,
+            stats: None,
+            query: Rc::from(""),
+            analyzed: Rc::from([]),
+            index: 0,
+            analyze_at: None,
+            phrases: Vec::new(),
+            word_status: Rc::from([]),
+            line: None,
+            time: String::new(),
+            lines_query: String::new(),
+            line_matches: Vec::new(),
+            upload: None,
+            pending: ws::Request::empty(),
+            pending_page: ws::Request::empty(),
+            pending_stats: ws::Request::empty(),
+            pending_search: ws::Request::empty(),
+            pending_lines: ws::Request::empty(),
+            pending_word_status: ws::Request::empty(),
+            pending_set_word_status: ws::Request::empty(),
+        };
+
+        this.load(ctx);
+        this
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _: &Self::Properties) -> bool {
+        self.page = None;
+        self.stats = None;
+        self.line_matches.clear();
+        self.clear_lookup();
+        self.load(ctx);
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Documents(response) => {
+                self.pending = ws::Request::empty();
+                self.documents = Some(response.documents);
+                true
+            }
+            Msg::Title(title) => {
+                self.title = title;
+                true
+            }
+            Msg::Text(text) => {
+                self.text = text;
+                true
+            }
+            Msg::Ruby(ruby) => {
+                self.ruby = ruby;
+                true
+            }
+            Msg::Add => {
+                let request = api::AddDocumentRequest {
+                    title: std::mem::take(&mut self.title),
+                    text: std::mem::take(&mut self.text),
+                };
+
+                self.add(ctx, request);
+                true
+            }
+            Msg::Added(document) => {
+                self.pending = ws::Request::empty();
+                self.adding = false;
+
+                if let Some(navigator) = ctx.link().navigator() {
+                    navigator.push(&Route::Document { id: document.id });
+                }
+
+                true
+            }
+            Msg::Upload(file) => {
+                let link = ctx.link().clone();
+                self.adding = true;
+
+                if file.name().to_lowercase().ends_with(".epub") {
+                    let ruby = self.ruby;
+
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let msg = match upload_epub(file, ruby).await {
+                            Ok(document) => Msg::Added(document),
+                            Err(error) => Msg::Error(error),
+                        };
+
+                        link.send_message(msg);
+                    });
+
+                    return true;
+                }
+
+                let file = gloo::file::File::from(file);
+                let title = file.name();
+
+                self.upload = Some(gloo::file::callbacks::read_as_text(&file, move |result| {
+                    let msg = match result {
+                        Ok(text) => Msg::Uploaded(title, text),
+                        Err(error) => Msg::Error(anyhow::Error::from(error).into()),
+                    };
+
+                    link.send_message(msg);
+                }));
+
+                true
+            }
+            Msg::Uploaded(name, text) => {
+                self.upload = None;
+
+                let (title, extension) = match name.rsplit_once('.') {
+                    Some((title, extension)) => (title.to_owned(), extension.to_lowercase()),
+                    None => (name.clone(), String::new()),
+                };
+
+                if matches!(extension.as_str(), "srt" | "ass" | "ssa") {
+                    self.add(ctx, api::AddSubtitlesRequest { name, text });
+                } else {
+                    self.add(ctx, api::AddDocumentRequest { title, text });
+                }
+
+                false
+            }
+            Msg::Delete(id) => {
+                self.pending = ctx.props().ws.request(
+                    api::DeleteDocumentRequest { id },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Documents(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::Page(page) => {
+                self.request_page(ctx, page, None);
+                false
+            }
+            Msg::Seek(time) => {
+                self.request_page(ctx, None, Some(time));
+                false
+            }
+            Msg::PageResponse(response) => {
+                self.pending_page = ws::Request::empty();
+                self.clear_lookup();
+
+                if response.lines.is_empty() {
+                    self.query = Rc::from(response.text.as_str());
+                    self.line = None;
+                } else {
+                    self.line = response.line;
+                    self.query = Rc::from(line_text(&response, response.line));
+                }
+
+                self.page = Some(response);
+                self.request_word_status(ctx);
+                true
+            }
+            Msg::SelectLine(line) => {
+                let Some(page) = &self.page else {
+                    return false;
+                };
+
+                let query = Rc::from(line_text(page, Some(line)));
+                self.clear_lookup();
+                self.line = Some(line);
+                self.query = query;
+                self.request_word_status(ctx);
+                true
+            }
+            Msg::WordStatusResponse(response) => {
+                self.pending_word_status = ws::Request::empty();
+                self.word_status = response.words.into();
+                true
+            }
+            Msg::SetWordStatus(sequence, status) => {
+                self.word_status = set_word_status(&self.word_status, sequence, status);
+
+                self.pending_set_word_status = ctx.props().ws.request(
+                    api::SetWordStatusRequest { sequence, status },
+                    ctx.link().callback(|result| match result {
+                        Ok(api::Empty) => Msg::WordStatusSet,
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                true
+            }
+            Msg::WordStatusSet => {
+                self.pending_set_word_status = ws::Request::empty();
+                false
+            }
+            Msg::Time(time) => {
+                self.time = time;
+                true
+            }
+            Msg::JumpTime => match subtitles::parse_time(self.time.trim()) {
+                Ok(time) => {
+                    self.request_page(ctx, None, Some(time));
+                    false
+                }
+                Err(error) => {
+                    log::error!("{error}");
+                    false
+                }
+            },
+            Msg::LinesQuery(q) => {
+                self.lines_query = q;
+                true
+            }
+            Msg::SearchLines => {
+                let Some(id) = ctx.props().id else {
+                    return false;
+                };
+
+                self.pending_lines = ctx.props().ws.request(
+                    api::SearchLinesRequest {
+                        id,
+                        q: self.lines_query.clone(),
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::LinesResponse(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::LinesResponse(response) => {
+                self.pending_lines = ws::Request::empty();
+                self.line_matches = response.lines;
+                true
+            }
+            Msg::Stats(response) => {
+                self.pending_stats = ws::Request::empty();
+                self.stats = Some(response);
+                true
+            }
+            Msg::Analyze(i) => {
+                if self.analyze_at != Some(i) {
+                    self.index = 0;
+                }
+
+                self.analyze_at = Some(i);
+
+                self.pending_search = ctx.props().ws.request(
+                    api::AnalyzeRequest {
+                        q: self.query.as_ref().to_owned(),
+                        start: i,
+                        end: None,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::AnalyzeResponse(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                true
+            }
+            Msg::AnalyzeCycle => {
+                if self.analyzed.is_empty() {
+                    return false;
+                }
+
+                self.index = (self.index + 1) % self.analyzed.len();
+                self.search_analyzed(ctx);
+                true
+            }
+            Msg::AnalyzeResponse(response) => {
+                self.analyzed = response.data.into_iter().map(|d| d.string.into()).collect();
+                self.index = self.index.min(self.analyzed.len().saturating_sub(1));
+                self.search_analyzed(ctx);
+                true
+            }
+            Msg::Search(q) => {
+                self.search(ctx, q);
+                false
+            }
+            Msg::SearchResponse(response) => {
+                self.pending_search = ws::Request::empty();
+                self.phrases = response.phrases;
+                true
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.adding = false;
+                self.upload = None;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let content = match ctx.props().id {
+            Some(..) => self.view_document(ctx),
+            None => self.view_documents(ctx),
+        };
+
+        html! {
+            <>
+                <div id="window-top">
+                    <div class="container">
+                        <span class="left">
+                            <Link<Route> classes="clickable" to={Route::Prompt}>{"🔍"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Reader}>{"📖"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Quiz}>{"✍"}</Link<Route>>
+                        </span>
+                        <span></span>
+                        <span class="title">{t(Text::Reader)}</span>
+                        <span></span>
+                        <span class="right"></span>
+                    </div>
+                </div>
+
+                <div id="content" class="container reader">
+                    {content}
+                </div>
+            </>
+        }
+    }
+}
+
+impl Reader {
+    fn load(&mut self, ctx: &Context<Self>) {
+        match ctx.props().id {
+            Some(id) => {
+                ctx.link().send_message(Msg::Page(None));
+
+                self.pending_stats = ctx.props().ws.request(
+                    api::DocumentStatsRequest { id },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Stats(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+            }
+            None => {
+                self.pending = ctx.props().ws.request(
+                    api::DocumentsRequest,
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Documents(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+            }
+        }
+    }
+
+    fn add<T>(&mut self, ctx: &Context<Self>, request: T)
+    where
+        T: api::Request<Response = DocumentInfo>,
+    {
+        self.adding = true;
+
+        self.pending = ctx.props().ws.request(
+            request,
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::Added(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    fn request_page(&mut self, ctx: &Context<Self>, page: Option<usize>, time: Option<u64>) {
+        let Some(id) = ctx.props().id else {
+            return;
+        };
+
+        self.pending_page = ctx.props().ws.request(
+            api::PageRequest { id, page, time },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::PageResponse(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    fn request_word_status(&mut self, ctx: &Context<Self>) {
+        self.word_status = Rc::from([]);
+
+        if self.query.is_empty() {
+            self.pending_word_status = ws::Request::empty();
+            return;
+        }
+
+        self.pending_word_status = ctx.props().ws.request(
+            api::WordStatusRequest {
+                q: self.query.as_ref().to_owned(),
+            },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::WordStatusResponse(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    fn clear_lookup(&mut self) {
+        self.analyzed = Rc::from([]);
+        self.index = 0;
+        self.analyze_at = None;
+        self.phrases.clear();
+        self.pending_search = ws::Request::empty();
+    }
+
+    fn search_analyzed(&mut self, ctx: &Context<Self>) {
+        if let Some(q) = self.analyzed.get(self.index) {
+            self.search(ctx, q.as_ref().to_owned());
+        } else {
+            self.phrases.clear();
+        }
+    }
+
+    fn search(&mut self, ctx: &Context<Self>, q: String) {
+        self.pending_search = ctx.props().ws.request(
+            api::SearchRequest {
+                q,
+                group: false,
+                chunk: None,
+            },
+            ctx.link().callback(|result| match result {
+                Ok(response) => Msg::SearchResponse(response),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    fn view_documents(&self, ctx: &Context<Self>) -> Html {
+        let documents = match &self.documents {
+            Some(documents) if documents.is_empty() => {
+                html!(<div class="block row">{t(Text::NoDocuments)}</div>)
+            }
+            Some(documents) => {
+                let documents = documents.iter().map(|d| {
+                    let id = d.id;
+                    let ondelete = ctx.link().callback(move |_| Msg::Delete(id));
+
+                    html! {
+                        <div class="block row row-spaced document">
+                            <Link<Route> classes="document-title" to={Route::Document { id }}>{d.title.clone()}</Link<Route>>
+                            <span class="end">{i18n::page(d.position + 1, d.pages)}</span>
+                            <button class="btn btn-sm danger" onclick={ondelete} title={t(Text::DeleteDocument)}>{t(Text::Delete)}</button>
+                        </div>
+                    }
+                });
+
+                html!(<>{for documents}</>)
+            }
+            None => {
+                html!(<div class="block row"><div class="spinner">{t(Text::Loading)}</div></div>)
+            }
+        };
+
+        let ontitle = ctx.link().batch_callback(|e: InputEvent| {
+            let input = e.target_dyn_into::<HtmlInputElement>()?;
+            Some(Msg::Title(input.value()))
+        });
+
+        let ontext = ctx.link().batch_callback(|e: InputEvent| {
+            let input = e.target_dyn_into::<HtmlTextAreaElement>()?;
+            Some(Msg::Text(input.value()))
+        });
+
+        let onadd = ctx.link().callback(|_| Msg::Add);
+
+        let onruby = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_dyn_into::<HtmlSelectElement>()?;
+
+            match select.value().as_str() {
+                "preserve" => Some(Msg::Ruby(Ruby::Preserve)),
+                _ => Some(Msg::Ruby(Ruby::Strip)),
+            }
+        });
+
+        let onupload = ctx.link().batch_callback(|e: Event| {
+            let input = e.target_dyn_into::<HtmlInputElement>()?;
+            let file = input.files()?.get(0)?;
+            input.set_value("");
+            Some(Msg::Upload(file))
+        });
+
+        let empty = self.text.trim().is_empty();
+
+        html! {
+            <>
+                <div class="block block-lg">
+                    <h4>{t(Text::Documents)}</h4>
+                    {documents}
+                </div>
+
+                <div class="block block-lg form">
+                    <h4>{t(Text::AddDocument)}</h4>
+                    <input type="text" placeholder={t(Text::Title)} value={self.title.clone()} oninput={ontitle} />
+                    <textarea class="document-text" placeholder={t(Text::DocumentPlaceholder)} value={self.text.clone()} oninput={ontext} />
+
+                    <div class="row row-spaced">
+                        <button class="btn primary" disabled={empty || self.adding} onclick={onadd}>{t(Text::Add)}</button>
+                        <label for="upload-document" class="btn" title={t(Text::UploadHelp)}>{t(Text::Upload)}</label>
+                        <input id="upload-document" type="file" accept=".txt,text/plain,.epub,application/epub+zip,.srt,.ass,.ssa" disabled={self.adding} onchange={onupload} />
+                        <select title={t(Text::FuriganaHelp)} onchange={onruby}>
+                            <option value="strip" selected={self.ruby == Ruby::Strip}>{t(Text::RemoveFurigana)}</option>
+                            <option value="preserve" selected={self.ruby == Ruby::Preserve}>{t(Text::KeepFurigana)}</option>
+                        </select>
+                    </div>
+                </div>
+            </>
+        }
+    }
+
+    fn view_document(&self, ctx: &Context<Self>) -> Html {
+        let Some(page) = &self.page else {
+            return html!(<div class="block row"><div class="spinner">{t(Text::Loading)}</div></div>);
+        };
+
+        let current = page.page;
+        let pages = page.document.pages;
+
+        let onprev = ctx
+            .link()
+            .callback(move |_| Msg::Page(Some(current.saturating_sub(1))));
+        let onnext = ctx.link().callback(move |_| Msg::Page(Some(current + 1)));
+
+        let navigation = html! {
+            <div class="block row row-spaced">
+                <button class="btn" disabled={current == 0} onclick={onprev}>{format!("← {}", t(Text::Previous))}</button>
+                <span>{i18n::page(current + 1, pages)}</span>
+                <button class="btn" disabled={current + 1 >= pages} onclick={onnext}>{format!("{} →", t(Text::Next))}</button>
+            </div>
+        };
+
+        let on_analyze = ctx.link().callback(Msg::Analyze);
+        let on_analyze_cycle = ctx.link().callback(|_| Msg::AnalyzeCycle);
+
+        let phrases = (!self.phrases.is_empty()).then(|| {
+            let phrases = self.phrases.iter().take(PHRASES_LIMIT).map(|e| {
+                let onchange = ctx.link().callback(|(input, _)| Msg::Search(input));
+                html!(<c::Entry ws={ctx.props().ws.clone()} embed={true} sources={e.key.sources.clone()} entry={e.phrase.clone()} sense_examples={e.sense_examples.clone()} difficulty={e.difficulty} annotation={e.annotation.clone()} {onchange} />)
+            });
+
+            let phrases = seq(phrases, |entry, not_last| {
+                if not_last {
+                    html!(<>{entry}<div class="entry-separator" /></>)
+                } else {
+                    entry
+                }
+            });
+
+            html!(<div class="block block-lg lookup">{for phrases}</div>)
+        });
+
+        let stats = self.stats.as_ref().map(|stats| {
+            let words = stats.unknown_words.iter().map(|w| {
+                let q = w.text.clone();
+                let onclick = ctx.link().callback(move |_| Msg::Search(q.clone()));
+                html!(<span class="chip clickable" {onclick}>{format!("{} ({})", w.text, w.count)}</span>)
+            });
+
+            html! {
+                <div class="block block-lg">
+                    <h4>{t(Text::UnknownWords)}</h4>
+                    <div class="block row">
+                        {i18n::document_words(stats.words, stats.unique, stats.unknown)}
+                        {spacing()}
+                        {i18n::known_words(stats.coverage)}
+                    </div>
+                    <div class="block row chips document-words">{for words}</div>
+                </div>
+            }
+        });
+
+        let toc = (!page.toc.is_empty()).then(|| {
+            let entries = page.toc.iter().enumerate().map(|(n, entry)| {
+                let next = page.toc.get(n + 1).map_or(pages, |e| e.page);
+                let active = (entry.page..next.max(entry.page + 1)).contains(&current);
+                let target = entry.page;
+                let onclick = ctx.link().callback(move |_| Msg::Page(Some(target)));
+
+                html! {
+                    <li class={classes!("clickable", active.then_some("active"))} {onclick}>{entry.title.clone()}</li>
+                }
+            });
+
+            html! {
+                <div class="column toc">
+                    <h4>{t(Text::Contents)}</h4>
+                    <ul>{for entries}</ul>
+                </div>
+            }
+        });
+
+        let on_word_status = ctx
+            .link()
+            .callback(|(sequence, status)| Msg::SetWordStatus(sequence, status));
+
+        let analyze = html! {
+            <c::AnalyzeToggle query={self.query.clone()} analyzed={self.analyzed.clone()} index={self.index} analyze_at={self.analyze_at} word_status={self.word_status.clone()} {on_analyze} {on_analyze_cycle} {on_word_status} />
+        };
+
+        let content = if page.lines.is_empty() {
+            html! {
+                <>
+                    <div class="block block-lg document-page">{analyze}</div>
+                    {phrases}
+                </>
+            }
+        } else {
+            let mut analyze = Some(analyze);
+            let mut phrases = phrases;
+
+            let lines = page.lines.iter().enumerate().map(|(n, line)| {
+                let text = page.text.get(line.range.clone()).unwrap_or_default();
+                let active = self.line == Some(n);
+
+                let lookup = active.then(|| {
+                    html! {
+                        <>
+                            <div class="block document-page">{analyze.take()}</div>
+                            {phrases.take()}
+                        </>
+                    }
+                });
+
+                let onclick = ctx.link().callback(move |_| Msg::SelectLine(n));
+
+                html! {
+                    <>
+                        <div class={classes!("block", "row", "row-spaced", "subtitle-line", "clickable", active.then_some("active"))} {onclick}>
+                            <span class="subtitle-time">{subtitles::format_time(line.start)}</span>
+                            <span>{text.to_owned()}</span>
+                        </div>
+                        {lookup}
+                    </>
+                }
+            }).collect::<Vec<_>>();
+
+            let ontime = ctx.link().batch_callback(|e: InputEvent| {
+                let input = e.target_dyn_into::<HtmlInputElement>()?;
+                Some(Msg::Time(input.value()))
+            });
+
+            let onjump = ctx.link().callback(|_| Msg::JumpTime);
+
+            let onlinesquery = ctx.link().batch_callback(|e: InputEvent| {
+                let input = e.target_dyn_into::<HtmlInputElement>()?;
+                Some(Msg::LinesQuery(input.value()))
+            });
+
+            let onsearchlines = ctx.link().callback(|_| Msg::SearchLines);
+
+            let matches = (!self.line_matches.is_empty()).then(|| {
+                let matches = self.line_matches.iter().map(|m| {
+                    let time = m.start;
+                    let onclick = ctx.link().callback(move |_| Msg::Seek(time));
+
+                    html! {
+                        <div class="block row row-spaced subtitle-line clickable" {onclick}>
+                            <span class="subtitle-time">{subtitles::format_time(m.start)}</span>
+                            <span>{m.text.clone()}</span>
+                        </div>
+                    }
+                });
+
+                html!(<div class="block block-lg">{for matches}</div>)
+            });
+
+            html! {
+                <>
+                    <div class="block row row-spaced">
+                        <input type="text" placeholder="mm:ss" value={self.time.clone()} oninput={ontime} />
+                        <button class="btn" onclick={onjump}>{t(Text::JumpToTime)}</button>
+                        <input type="text" placeholder={t(Text::SearchLines)} value={self.lines_query.clone()} oninput={onlinesquery} />
+                        <button class="btn" onclick={onsearchlines}>{t(Text::Search)}</button>
+                    </div>
+
+                    {for matches}
+
+                    <div class="block block-lg">{lines}</div>
+                </>
+            }
+        };
+
+        html! {
+            <>
+                <h4>{page.document.title.clone()}</h4>
+
+                <div class="columns">
+                    {for toc}
+
+                    <div class="column">
+                        {navigation.clone()}
+                        {content}
+                        {navigation}
+                        {stats}
+                    </div>
+                </div>
+            </>
+        }
+    }
+}
+
+/// The text of a timed line on a page.
+fn line_text(page: &api::PageResponse, line: Option<usize>) -> &str {
+    line.and_then(|n| page.lines.get(n))
+        .and_then(|l| page.text.get(l.range.clone()))
+        .unwrap_or_default()
+}
+
+/// Upload an EPUB book to the reader.
+async fn upload_epub(file: web_sys::File, ruby: Ruby) -> Result<DocumentInfo, Error> {
+    let url = base::url("/api/documents/epub").ok_or("Missing window location")?;
+
+    let query = serde_urlencoded::to_string(api::AddEpubRequest { ruby })
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    let url = format!("{url}?{query}");
+
+    let request = gloo::net::http::Request::post(&url)
+        .header(
+            api::compat::HEADER,
+            &api::compat::PROTOCOL_VERSION.to_string(),
+        )
+        .header("Content-Type", "application/epub+zip")
+        .body(file)
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    let response = request
+        .send()
+        .await
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    if !response.ok() {
+        return Err(Error::from_response(&response).await);
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|error| anyhow::Error::msg(error.to_string()).into())
+}
@@ -0,0 +1,352 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use lib::api;
+use lib::recordings::Recording;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::js_sys::Array;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    window, Blob, BlobEvent, BlobPropertyBag, MediaRecorder, MediaStream, MediaStreamConstraints,
+    MediaStreamTrack,
+};
+use yew::prelude::*;
+
+use crate::base;
+use crate::error::Error;
+use crate::i18n::{t, Text};
+use crate::ws;
+
+use super::colon;
+
+pub(crate) enum Msg {
+    Load,
+    Recordings(api::RecordingsResponse),
+    Record,
+    Started(MediaStream),
+    Stop,
+    Stopped,
+    Added(Recording),
+    Delete(u64),
+    Deleted(u64),
+    Error(Error),
+}
+
+#[derive(Properties)]
+pub struct Props {
+    pub ws: ws::Handle,
+    pub sequence: u64,
+}
+
+impl PartialEq for Props {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.sequence == other.sequence
+    }
+}
+
+/// An ongoing recording.
+struct Recorder {
+    recorder: MediaRecorder,
+    stream: MediaStream,
+    chunks: Rc<RefCell<Vec<Blob>>>,
+    _on_data: Closure<dyn FnMut(BlobEvent)>,
+    _on_stop: Closure<dyn FnMut()>,
+}
+
+impl Recorder {
+    /// Construct an audio blob out of everything recorded so far.
+    fn finish(&self) -> Result<Blob, Error> {
+        for track in self.stream.get_tracks() {
+            track.unchecked_into::<MediaStreamTrack>().stop();
+        }
+
+        let chunks = self.chunks.borrow().iter().collect::<Array>();
+        let mut options = BlobPropertyBag::new();
+        options.type_(&self.recorder.mime_type());
+        Ok(Blob::new_with_blob_sequence_and_options(&chunks, &options)?)
+    }
+}
+
+pub(crate) struct Recordings {
+    recordings: Option<Vec<Recording>>,
+    recorder: Option<Recorder>,
+    /// Whether a recording is being uploaded.
+    uploading: bool,
+    error: Option<String>,
+    pending: ws::Request,
+    pending_delete: ws::Request,
+}
+
+impl Component for Recordings {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(_: &Context<Self>) -> Self {
+        Self {
+            recordings: None,
+            recorder: None,
+            uploading: false,
+            error: None,
+            pending: ws::Request::empty(),
+            pending_delete: ws::Request::empty(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Load => {
+                self.pending = ctx.props().ws.request(
+                    api::RecordingsRequest {
+                        sequence: ctx.props().sequence,
+                    },
+                    ctx.link().callback(|result| match result {
+                        Ok(response) => Msg::Recordings(response),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::Recordings(response) => {
+                self.pending = ws::Request::empty();
+                self.recordings = Some(response.recordings);
+                true
+            }
+            Msg::Record => {
+                self.error = None;
+                let link = ctx.link().clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let msg = match user_media().await {
+                        Ok(stream) => Msg::Started(stream),
+                        Err(error) => Msg::Error(error),
+                    };
+
+                    link.send_message(msg);
+                });
+
+                true
+            }
+            Msg::Started(stream) => {
+                match start(ctx, stream) {
+                    Ok(recorder) => {
+                        self.recorder = Some(recorder);
+                    }
+                    Err(error) => {
+                        ctx.link().send_message(Msg::Error(error));
+                    }
+                }
+
+                true
+            }
+            Msg::Stop => {
+                if let Some(recorder) = &self.recorder {
+                    if let Err(error) = recorder.recorder.stop() {
+                        ctx.link().send_message(Msg::Error(error.into()));
+                    }
+                }
+
+                false
+            }
+            Msg::Stopped => {
+                let Some(recorder) = self.recorder.take() else {
+                    return false;
+                };
+
+                let blob = match recorder.finish() {
+                    Ok(blob) => blob,
+                    Err(error) => {
+                        ctx.link().send_message(Msg::Error(error));
+                        return true;
+                    }
+                };
+
+                let Some(url) = user_audio_url() else {
+                    return true;
+                };
+
+                let url = format!("{url}?sequence={}", ctx.props().sequence);
+                self.uploading = true;
+                let link = ctx.link().clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let msg = match upload(&url, blob).await {
+                        Ok(recording) => Msg::Added(recording),
+                        Err(error) => Msg::Error(error),
+                    };
+
+                    link.send_message(msg);
+                });
+
+                true
+            }
+            Msg::Added(recording) => {
+                self.uploading = false;
+                self.recordings.get_or_insert_with(Vec::new).push(recording);
+                true
+            }
+            Msg::Delete(id) => {
+                self.pending_delete = ctx.props().ws.request(
+                    api::DeleteRecordingRequest { id },
+                    ctx.link().callback(move |result| match result {
+                        Ok(api::Empty) => Msg::Deleted(id),
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+
+                false
+            }
+            Msg::Deleted(id) => {
+                self.pending_delete = ws::Request::empty();
+
+                if let Some(recordings) = &mut self.recordings {
+                    recordings.retain(|r| r.id != id);
+                }
+
+                true
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.uploading = false;
+                self.error = Some(error.to_string());
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _: &Context<Self>, _: &Self::Properties) -> bool {
+        self.recordings = None;
+        self.recorder = None;
+        self.uploading = false;
+        self.error = None;
+        self.pending = ws::Request::empty();
+        self.pending_delete = ws::Request::empty();
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let Some(recordings) = &self.recordings else {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::Load);
+            return html!(<div class="block row"><span class="clickable" {onclick}>{t(Text::Recordings)}</span></div>);
+        };
+
+        let url = user_audio_url();
+
+        let recordings = recordings.iter().map(|recording| {
+            let src = url.as_ref().map(|url| format!("{url}/{}", recording.id));
+            let id = recording.id;
+            let ondelete = ctx.link().callback(move |_: MouseEvent| Msg::Delete(id));
+
+            html! {
+                <div class="block row row-spaced recording">
+                    <audio controls=true {src} />
+                    <button class="btn" onclick={ondelete} title={t(Text::DeleteRecording)}>{t(Text::Delete)}</button>
+                </div>
+            }
+        });
+
+        let button = if self.recorder.is_some() {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::Stop);
+            html!(<button class="btn primary recording-active" {onclick}>{t(Text::Stop)}</button>)
+        } else {
+            let onclick = ctx.link().callback(|_: MouseEvent| Msg::Record);
+            html!(<button class="btn" disabled={self.uploading} {onclick} title={t(Text::RecordHelp)}>{t(Text::Record)}</button>)
+        };
+
+        let error = self
+            .error
+            .as_ref()
+            .map(|error| html!(<div class="block row recording-error">{error.clone()}</div>));
+
+        html! {
+            <div class="block recordings">
+                <div class="block row"><span>{t(Text::Recordings)}</span>{colon()}{button}</div>
+                {for recordings}
+                {for error}
+            </div>
+        }
+    }
+}
+
+/// The address recordings are uploaded to and served from.
+fn user_audio_url() -> Option<String> {
+    base::url("/api/user-audio")
+}
+
+/// Ask for access to the microphone.
+async fn user_media() -> Result<MediaStream, Error> {
+    let Some(window) = window() else {
+        return Err(Error::from("Missing window"));
+    };
+
+    let mut constraints = MediaStreamConstraints::new();
+    constraints.audio(&JsValue::TRUE);
+
+    let promise = window
+        .navigator()
+        .media_devices()?
+        .get_user_media_with_constraints(&constraints)?;
+
+    Ok(JsFuture::from(promise).await?.unchecked_into())
+}
+
+/// Start recording the given stream.
+fn start(ctx: &Context<Recordings>, stream: MediaStream) -> Result<Recorder, Error> {
+    let recorder = MediaRecorder::new_with_media_stream(&stream)?;
+    let chunks = Rc::new(RefCell::new(Vec::new()));
+
+    let on_data = Closure::wrap({
+        let chunks = chunks.clone();
+
+        Box::new(move |e: BlobEvent| {
+            if let Some(data) = e.data() {
+                chunks.borrow_mut().push(data);
+            }
+        }) as Box<dyn FnMut(BlobEvent)>
+    });
+
+    let on_stop = Closure::wrap({
+        let link = ctx.link().clone();
+        Box::new(move || link.send_message(Msg::Stopped)) as Box<dyn FnMut()>
+    });
+
+    recorder.set_ondataavailable(Some(on_data.as_ref().unchecked_ref()));
+    recorder.set_onstop(Some(on_stop.as_ref().unchecked_ref()));
+    recorder.start()?;
+
+    Ok(Recorder {
+        recorder,
+        stream,
+        chunks,
+        _on_data: on_data,
+        _on_stop: on_stop,
+    })
+}
+
+/// Upload a recording.
+async fn upload(url: &str, blob: Blob) -> Result<Recording, Error> {
+    let request = gloo::net::http::Request::post(url)
+        .header(
+            api::compat::HEADER,
+            &api::compat::PROTOCOL_VERSION.to_string(),
+        )
+        .header("Content-Type", &blob.type_())
+        .body(blob)
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    let response = request
+        .send()
+        .await
+        .map_err(|error| anyhow::Error::msg(error.to_string()))?;
+
+    if !response.ok() {
+        return Err(Error::from_response(&response).await);
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|error| anyhow::Error::msg(error.to_string()).into())
+}
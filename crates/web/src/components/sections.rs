@@ -0,0 +1,130 @@
+//! Sections of results, shown side by side in columns on wide screens and as
+//! tabs on narrow screens where there's no room for columns.
+
+use gloo::events::EventListener;
+use yew::prelude::*;
+
+/// Screens narrower than this, in CSS pixels, show sections as tabs.
+const BREAKPOINT: f64 = 768.0;
+
+/// A section of results.
+#[derive(Clone, PartialEq)]
+pub(crate) struct Section {
+    pub(crate) title: &'static str,
+    /// The number of results in the section, which is empty if it's zero.
+    pub(crate) len: usize,
+    /// Whether the section is shown in the side column on wide screens.
+    pub(crate) side: bool,
+    pub(crate) content: Html,
+}
+
+pub(crate) enum Msg {
+    Resize,
+    Select(usize),
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    /// Sections in the order their tabs are shown.
+    pub(crate) sections: Vec<Section>,
+}
+
+pub(crate) struct Sections {
+    narrow: bool,
+    selected: usize,
+    _resize: EventListener,
+}
+
+impl Component for Sections {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let resize = EventListener::new(&gloo::utils::window(), "resize", {
+            let link = ctx.link().clone();
+            move |_| link.send_message(Msg::Resize)
+        });
+
+        Self {
+            narrow: is_narrow(),
+            selected: 0,
+            _resize: resize,
+        }
+    }
+
+    fn update(&mut self, _: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Resize => {
+                let narrow = is_narrow();
+                let changed = self.narrow != narrow;
+                self.narrow = narrow;
+                changed
+            }
+            Msg::Select(index) => {
+                self.selected = index;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let sections = &ctx.props().sections;
+
+        if !self.narrow {
+            let main = sections
+                .iter()
+                .filter(|s| !s.side)
+                .map(|s| s.content.clone());
+            let side = sections.iter().filter(|s| s.side && s.len > 0);
+
+            let side = side.map(|s| html!(<div class="column side">{s.content.clone()}</div>));
+
+            return html! {
+                <div class="columns">
+                    <div class="column">{for main}</div>
+                    {for side}
+                </div>
+            };
+        }
+
+        // Keep showing the selected section unless it's empty, in which case
+        // the first one with results is shown.
+        let selected = match sections.get(self.selected) {
+            Some(section) if section.len > 0 => self.selected,
+            _ => sections.iter().position(|s| s.len > 0).unwrap_or_default(),
+        };
+
+        let tabs = sections.iter().enumerate().map(|(index, section)| {
+            let active = index == selected;
+
+            let class = classes!(
+                "tab",
+                active.then_some("active"),
+                (section.len == 0).then_some("disabled")
+            );
+
+            let onclick = (!active && section.len > 0)
+                .then(|| ctx.link().callback(move |_| Msg::Select(index)));
+
+            html!(<a {class} {onclick}>{format!("{} ({})", section.title, section.len)}</a>)
+        });
+
+        let content = sections.get(selected).map(|s| s.content.clone());
+
+        html! {
+            <>
+                <div class="tabs">{for tabs}</div>
+                <div class="block block-lg">{for content}</div>
+            </>
+        }
+    }
+}
+
+/// Test if the window is narrower than the breakpoint.
+fn is_narrow() -> bool {
+    gloo::utils::window()
+        .inner_width()
+        .ok()
+        .and_then(|width| width.as_f64())
+        .is_some_and(|width| width < BREAKPOINT)
+}
@@ -0,0 +1,70 @@
+use serde::Deserialize;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::Route;
+
+/// Data shared with the application, as described by the share target in the
+/// web app manifest.
+#[derive(Default, Deserialize)]
+struct Shared {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+impl Shared {
+    /// The text to look up, preferring the shared text over its title or
+    /// address since not every application provides it.
+    fn into_text(self) -> Option<String> {
+        [self.text, self.title, self.url]
+            .into_iter()
+            .flatten()
+            .map(|text| text.trim().to_owned())
+            .find(|text| !text.is_empty())
+    }
+}
+
+/// Receives text shared from other applications and opens it in the prompt.
+pub(crate) struct Share;
+
+impl Component for Share {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if !first_render {
+            return;
+        }
+
+        let (Some(navigator), Some(location)) = (ctx.link().navigator(), ctx.link().location())
+        else {
+            return;
+        };
+
+        let shared = location.query::<Shared>().unwrap_or_default();
+
+        let result = match shared.into_text() {
+            Some(text) => navigator.replace_with_query(&Route::Prompt, &[("q", text)]),
+            None => {
+                navigator.replace(&Route::Prompt);
+                Ok(())
+            }
+        };
+
+        if let Err(error) = result {
+            log::error!("Failed to open shared text: {error}");
+        }
+    }
+
+    fn view(&self, _: &Context<Self>) -> Html {
+        html!(<div id="content" class="container">{"Opening shared text"}</div>)
+    }
+}
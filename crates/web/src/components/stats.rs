@@ -0,0 +1,322 @@
+use lib::api;
+use lib::stats;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::base;
+use crate::error::Error;
+use crate::i18n::{self, t, Text};
+use crate::ws;
+use crate::Route;
+
+/// Ranges of days which can be shown.
+const RANGES: [u32; 4] = [7, 30, 90, 365];
+/// The range of days initially shown.
+const DEFAULT_DAYS: u32 = 30;
+
+/// A counter which is charted.
+#[derive(Clone, Copy)]
+enum Counter {
+    Searches,
+    Captures,
+    Reviews,
+    Saved,
+}
+
+impl Counter {
+    const ALL: [Counter; 4] = [
+        Counter::Searches,
+        Counter::Captures,
+        Counter::Reviews,
+        Counter::Saved,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Counter::Searches => t(Text::Searches),
+            Counter::Captures => t(Text::Captures),
+            Counter::Reviews => t(Text::Reviews),
+            Counter::Saved => t(Text::WordsSaved),
+        }
+    }
+
+    fn ident(self) -> &'static str {
+        match self {
+            Counter::Searches => "searches",
+            Counter::Captures => "captures",
+            Counter::Reviews => "reviews",
+            Counter::Saved => "saved",
+        }
+    }
+
+    fn get(self, day: &stats::Day) -> u32 {
+        match self {
+            Counter::Searches => day.searches,
+            Counter::Captures => day.captures,
+            Counter::Reviews => day.reviews,
+            Counter::Saved => day.saved,
+        }
+    }
+}
+
+pub(crate) enum Msg {
+    Days(u32),
+    Stats(api::StatsResponse),
+    Clear,
+    Cleared,
+    Error(Error),
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct Props {
+    pub(crate) ws: ws::Handle,
+}
+
+pub(crate) struct Stats {
+    days: u32,
+    stats: Option<api::StatsResponse>,
+    pending: ws::Request,
+}
+
+impl Component for Stats {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut this = Self {
+            days: DEFAULT_DAYS,
+            stats: None,
+            pending: ws::Request::empty(),
+        };
+
+        this.refresh(ctx);
+        this
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::Days(days) => {
+                self.days = days;
+                self.refresh(ctx);
+            }
+            Msg::Stats(stats) => {
+                self.pending = ws::Request::empty();
+                self.stats = Some(stats);
+            }
+            Msg::Clear => {
+                self.pending = ctx.props().ws.request(
+                    api::ClearStatsRequest,
+                    ctx.link().callback(|result| match result {
+                        Ok(api::Empty) => Msg::Cleared,
+                        Err(error) => Msg::Error(error),
+                    }),
+                );
+            }
+            Msg::Cleared => {
+                self.refresh(ctx);
+            }
+            Msg::Error(error) => {
+                log::error!("{error}");
+                self.pending = ws::Request::empty();
+            }
+        }
+
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.link().callback(|_: MouseEvent| Msg::Clear);
+
+        html! {
+            <>
+                <div id="window-top">
+                    <div class="container">
+                        <span class="left">
+                            <Link<Route> classes="clickable" to={Route::Prompt}>{"🔍"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Reader}>{"📖"}</Link<Route>>
+                            <Link<Route> classes="clickable" to={Route::Quiz}>{"✍"}</Link<Route>>
+                        </span>
+                        <span></span>
+                        <span class="title">{t(Text::Statistics)}</span>
+                        <span></span>
+                        <span class="right">
+                            <button class="btn" {onclick} title="Remove all collected statistics">{t(Text::Clear)}</button>
+                        </span>
+                    </div>
+                </div>
+
+                <div id="content" class="container stats">
+                    {self.view_stats(ctx)}
+                </div>
+            </>
+        }
+    }
+}
+
+impl Stats {
+    fn refresh(&mut self, ctx: &Context<Self>) {
+        self.pending = ctx.props().ws.request(
+            api::StatsRequest { days: self.days },
+            ctx.link().callback(|result| match result {
+                Ok(stats) => Msg::Stats(stats),
+                Err(error) => Msg::Error(error),
+            }),
+        );
+    }
+
+    fn view_stats(&self, ctx: &Context<Self>) -> Html {
+        let Some(stats) = &self.stats else {
+            return html!(<div class="block row"><div class="spinner">{t(Text::Loading)}</div></div>);
+        };
+
+        let ranges = RANGES.into_iter().map(|days| {
+            let class = classes! {
+                "chip",
+                "clickable",
+                (days == self.days).then_some("active"),
+            };
+
+            let onclick = ctx.link().callback(move |_: MouseEvent| Msg::Days(days));
+            html!(<span {class} {onclick}>{i18n::days(days)}</span>)
+        });
+
+        let totals = Counter::ALL.into_iter().map(|counter| {
+            html! {
+                <div class="stats-total">
+                    <span class="stats-value">{i18n::number(counter.get(&stats.total))}</span>
+                    <span class="stats-label">{counter.title()}</span>
+                </div>
+            }
+        });
+
+        let charts = Counter::ALL.into_iter().map(|counter| {
+            render_chart(counter.title(), counter.ident(), &stats.days, |d| {
+                f64::from(counter.get(&d.activity))
+            })
+        });
+
+        let characters = render_chart(t(Text::CharactersRead), "characters", &stats.days, |d| {
+            d.immersion.characters as f64
+        });
+
+        let sources = (stats.sources.len() > 1).then(|| {
+            let sources = stats.sources.iter().map(|s| {
+                html! {
+                    <div class="block row row-spaced stats-source">
+                        <span class="stats-source-name">{s.source.clone()}</span>
+                        <span class="end">{format!("{} {}", i18n::number(s.immersion.characters as f64), t(Text::CharactersRead))}</span>
+                        <span>{i18n::duration(s.immersion.seconds)}</span>
+                    </div>
+                }
+            });
+
+            html! {
+                <>
+                    <h5>{t(Text::Sources)}</h5>
+                    <div class="block block-lg">{for sources}</div>
+                </>
+            }
+        });
+
+        html! {
+            <>
+                <div class="block block-lg row row-spaced stats-streaks">
+                    <div class="stats-total">
+                        <span class="stats-value">{i18n::days(stats.streak)}</span>
+                        <span class="stats-label">{t(Text::CurrentStreak)}</span>
+                    </div>
+                    <div class="stats-total">
+                        <span class="stats-value">{i18n::days(stats.longest_streak)}</span>
+                        <span class="stats-label">{t(Text::LongestStreak)}</span>
+                    </div>
+                </div>
+
+                <h5 title="Across every day statistics are kept for">{t(Text::Totals)}</h5>
+                <div class="block block-lg row row-spaced">{for totals}</div>
+
+                <div class="block row row-spaced">
+                    <h5 title="Text captured from other applications, across every day statistics are kept for">{t(Text::Immersion)}</h5>
+                    <a class="end btn btn-sm" href={base::href("/api/stats/captures.csv")} download="jpv-captures.csv" title="Download every capture with its time, source and number of characters">{t(Text::ExportCsv)}</a>
+                </div>
+                <div class="block block-lg row row-spaced">{render_immersion(&stats.immersion)}</div>
+                {sources}
+
+                <div class="block row chips">{for ranges}</div>
+                {characters}
+                {for charts}
+            </>
+        }
+    }
+}
+
+/// Totals of text read through captures.
+fn render_immersion(immersion: &stats::Immersion) -> Html {
+    let speed = immersion.speed().map(|speed| {
+        html! {
+            <div class="stats-total" title="Estimated from the time between captures">
+                <span class="stats-value">{i18n::number(speed as f64)}</span>
+                <span class="stats-label">{t(Text::ReadingSpeed)}</span>
+            </div>
+        }
+    });
+
+    html! {
+        <>
+            <div class="stats-total">
+                <span class="stats-value">{i18n::number(immersion.characters as f64)}</span>
+                <span class="stats-label">{t(Text::CharactersRead)}</span>
+            </div>
+            <div class="stats-total">
+                <span class="stats-value">{i18n::number(immersion.lines)}</span>
+                <span class="stats-label">{t(Text::Lines)}</span>
+            </div>
+            <div class="stats-total" title="Estimated from the time between captures">
+                <span class="stats-value">{i18n::duration(immersion.seconds)}</span>
+                <span class="stats-label">{t(Text::ReadingTime)}</span>
+            </div>
+            {for speed}
+        </>
+    }
+}
+
+/// A bar chart of a value, with one bar per day.
+fn render_chart(
+    title: &str,
+    ident: &str,
+    days: &[api::StatsDay],
+    value: impl Fn(&api::StatsDay) -> f64,
+) -> Html {
+    let max = days.iter().map(&value).fold(0.0, f64::max);
+    let total = days.iter().map(&value).sum::<f64>();
+
+    let bars = days.iter().map(|d| {
+        let count = value(d);
+
+        let height = if max == 0.0 { 0.0 } else { count * 100.0 / max };
+
+        let style = format!("height: {height:.1}%;");
+        let title = format!("{}: {}", i18n::date(&d.date), i18n::number(count));
+
+        html!(<div class="stats-bar" {title}><div class="stats-fill" {style} /></div>)
+    });
+
+    let class = classes!("stats-chart", format!("stats-{ident}"));
+
+    let range = match (days.first(), days.last()) {
+        (Some(first), Some(last)) => html! {
+            <div class="row row-spaced stats-range">
+                <span>{i18n::date(&first.date)}</span>
+                <span class="end">{i18n::date(&last.date)}</span>
+            </div>
+        },
+        _ => html!(),
+    };
+
+    html! {
+        <div class="block block-lg">
+            <h5>{format!("{title} ({})", i18n::number(total))}</h5>
+            <div {class}>{for bars}</div>
+            {range}
+        </div>
+    }
+}
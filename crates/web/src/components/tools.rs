@@ -3,6 +3,8 @@ use std::iter;
 
 use yew::prelude::*;
 
+use crate::display;
+
 macro_rules! bullets {
     ($base:ident . $name:ident $(, $($tt:tt)*)?) => {
         $base.$name.iter().map(|d| {
@@ -61,6 +63,12 @@ where
     first.map(move |first| render([first].into_iter().chain(iter)))
 }
 
+/// An emoji used as an icon, with a label read by screen readers instead of
+/// the emoji itself.
+pub(crate) fn icon(symbol: &'static str, label: &'static str) -> Html {
+    html!(<span role="img" aria-label={label}>{symbol}</span>)
+}
+
 pub(super) fn romaji<const N: usize, const S: usize>(furigana: lib::Furigana<N, S>) -> String {
     let mut romaji = String::new();
 
@@ -73,10 +81,17 @@ pub(super) fn romaji<const N: usize, const S: usize>(furigana: lib::Furigana<N,
     romaji
 }
 
+/// Render text with readings above its kanji, or in parentheses after them if
+/// plain readings are enabled.
 pub(super) fn ruby<const N: usize, const S: usize>(furigana: lib::Furigana<N, S>) -> Html {
+    let plain = display::plain_readings();
+
     let elements = furigana.iter().map(|group| match group {
+        lib::FuriganaGroup::Kanji(kanji, kana) if plain => {
+            html!(<>{kanji}<span class="plain-reading">{format!("（{kana}）")}</span></>)
+        }
         lib::FuriganaGroup::Kanji(kanji, kana) => {
-            html!(<ruby>{kanji}<rt>{kana}</rt></ruby>)
+            html!(<ruby>{kanji}<rp>{"（"}</rp><rt>{kana}</rt><rp>{"）"}</rp></ruby>)
         }
         lib::FuriganaGroup::Kana(kana) => {
             html!({ kana })
@@ -0,0 +1,170 @@
+//! Display settings of the user interface.
+//!
+//! These only affect how things are shown in this browser, so they're stored
+//! in local storage rather than in the configuration of the service. Display
+//! settings which follow the learner are stored in their profile instead, see
+//! [`apply_profile`].
+
+use std::cell::Cell;
+use std::collections::BTreeSet;
+
+use gloo::storage::{LocalStorage, Storage};
+use lib::api;
+use serde::{Deserialize, Serialize};
+
+/// Key the display settings are stored under in local storage.
+const STORAGE_KEY: &str = "jpv-display";
+
+thread_local! {
+    static PLAIN_READINGS: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Whether readings are shown in parentheses after kanji rather than above
+/// them, which screen readers read more reliably than ruby annotations.
+pub(crate) fn plain_readings() -> bool {
+    PLAIN_READINGS.with(|cell| {
+        if let Some(plain) = cell.get() {
+            return plain;
+        }
+
+        let plain = Display::load().plain_readings;
+        cell.set(Some(plain));
+        plain
+    })
+}
+
+/// Apply the display settings of the current profile to the whole document,
+/// where they're picked up by the stylesheet. This way every view follows
+/// them, without having to be rendered again when they change.
+pub(crate) fn apply_profile(profiles: &api::ProfilesResponse) {
+    let Some(element) = gloo::utils::document().document_element() else {
+        return;
+    };
+
+    let text_scale = format!("--text-scale: {}", f64::from(profiles.text_scale) / 100.0);
+
+    let attributes = [
+        ("data-readings", profiles.readings.ident()),
+        ("data-density", profiles.density.ident()),
+        ("style", text_scale.as_str()),
+    ];
+
+    for (name, value) in attributes {
+        if let Err(error) = element.set_attribute(name, value) {
+            log::warn!("Failed to apply display settings: {error:?}");
+        }
+    }
+}
+
+/// A category of metadata attached to a sense.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Metadata {
+    PartOfSpeech,
+    Misc,
+    Dialect,
+    Field,
+    Info,
+}
+
+impl Metadata {
+    /// All metadata categories, in the order they are shown.
+    pub(crate) const ALL: [Metadata; 5] = [
+        Metadata::PartOfSpeech,
+        Metadata::Misc,
+        Metadata::Dialect,
+        Metadata::Field,
+        Metadata::Info,
+    ];
+
+    /// A stable identifier for the category.
+    pub(crate) fn ident(&self) -> &'static str {
+        match self {
+            Metadata::PartOfSpeech => "pos",
+            Metadata::Misc => "misc",
+            Metadata::Dialect => "dialect",
+            Metadata::Field => "field",
+            Metadata::Info => "info",
+        }
+    }
+
+    /// A human readable title for the category.
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            Metadata::PartOfSpeech => "Part of speech",
+            Metadata::Misc => "Usage",
+            Metadata::Dialect => "Dialect",
+            Metadata::Field => "Field",
+            Metadata::Info => "Notes",
+        }
+    }
+}
+
+/// Display settings.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Display {
+    /// Metadata categories which are hidden from senses.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub(crate) hidden: BTreeSet<Metadata>,
+    /// Show readings in parentheses instead of as ruby.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(crate) plain_readings: bool,
+    /// Attach the most recently captured image to mined cards.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(crate) screenshots: bool,
+    /// Show captured translations as is, instead of blurred until hovered.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(crate) reveal_translations: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl Display {
+    /// Load display settings from local storage.
+    pub(crate) fn load() -> Self {
+        LocalStorage::get::<Self>(STORAGE_KEY).unwrap_or_default()
+    }
+
+    /// Test if the given metadata category is shown.
+    pub(crate) fn is_shown(&self, metadata: Metadata) -> bool {
+        !self.hidden.contains(&metadata)
+    }
+
+    /// Toggle if the given metadata category is shown and save the settings.
+    pub(crate) fn toggle(&mut self, metadata: Metadata) {
+        if !self.hidden.remove(&metadata) {
+            self.hidden.insert(metadata);
+        }
+
+        self.save();
+    }
+
+    /// Toggle if readings are shown in parentheses and save the settings.
+    pub(crate) fn toggle_plain_readings(&mut self) {
+        self.plain_readings = !self.plain_readings;
+        PLAIN_READINGS.with(|cell| cell.set(Some(self.plain_readings)));
+        self.save();
+    }
+
+    /// Toggle if captured images are attached to mined cards and save the
+    /// settings.
+    pub(crate) fn toggle_screenshots(&mut self) {
+        self.screenshots = !self.screenshots;
+        self.save();
+    }
+
+    /// Toggle if captured translations are blurred until hovered and save the
+    /// settings.
+    pub(crate) fn toggle_reveal_translations(&mut self) {
+        self.reveal_translations = !self.reveal_translations;
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Err(error) = LocalStorage::set(STORAGE_KEY, self) {
+            log::warn!("Failed to store display settings: {error}");
+        }
+    }
+}
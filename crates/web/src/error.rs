@@ -1,6 +1,9 @@
 use std::fmt;
 use std::str::Utf8Error;
 
+use gloo::net::http::Response;
+use lib::api;
+
 /// Result type in use.
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
@@ -9,10 +12,42 @@ pub struct Error {
     error: anyhow::Error,
 }
 
+impl Error {
+    /// The kind of the error if it was returned by the service.
+    pub(crate) fn kind(&self) -> Option<api::ErrorKind> {
+        self.error
+            .downcast_ref::<api::ApiError>()
+            .map(|error| error.kind)
+    }
+
+    /// Construct an error from a failed HTTP response, using the structured
+    /// error in its body if there is one.
+    pub(crate) async fn from_response(response: &Response) -> Self {
+        match response.json::<api::ApiError>().await {
+            Ok(error) => Self::from(error),
+            Err(..) => Self {
+                error: anyhow::anyhow!("Request failed with status {}", response.status()),
+            },
+        }
+    }
+}
+
 impl fmt::Display for Error {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.error)
+        write!(f, "{}", self.error)?;
+
+        // Makes it possible to find the failed request in the logs of the
+        // service.
+        if let Some(id) = self
+            .error
+            .downcast_ref::<api::ApiError>()
+            .and_then(|error| error.request_id.as_deref())
+        {
+            write!(f, " (request {id})")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -23,6 +58,15 @@ impl From<anyhow::Error> for Error {
     }
 }
 
+impl From<api::ApiError> for Error {
+    #[inline]
+    fn from(error: api::ApiError) -> Self {
+        Self {
+            error: anyhow::Error::new(error),
+        }
+    }
+}
+
 impl From<&'static str> for Error {
     #[inline]
     fn from(value: &'static str) -> Self {
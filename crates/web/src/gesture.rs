@@ -0,0 +1,110 @@
+//! Touch gestures.
+//!
+//! A [`Swipe`] is kept in the state of the component using it so that a
+//! gesture survives the component being rendered again while it's ongoing,
+//! and [`Swipe::handlers`] provides the pointer event handlers to put on the
+//! element which is swiped.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use web_sys::PointerEvent;
+use yew::Callback;
+
+/// How far a touch has to move, in CSS pixels, to count as a swipe.
+const DISTANCE: i32 = 48;
+
+/// The direction of a swipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Handlers to put on the element which is swiped.
+pub(crate) struct Handlers {
+    pub(crate) onpointerdown: Callback<PointerEvent>,
+    pub(crate) onpointerup: Callback<PointerEvent>,
+    pub(crate) onpointercancel: Callback<PointerEvent>,
+}
+
+/// Tracks swipes made by touch.
+///
+/// Elements which are swiped vertically should have `touch-action` set so
+/// that the browser doesn't scroll instead, which cancels the gesture.
+#[derive(Default)]
+pub(crate) struct Swipe {
+    /// The pointer and where it was pressed.
+    start: Rc<Cell<Option<(i32, i32, i32)>>>,
+}
+
+impl Swipe {
+    /// Construct handlers which call `on_swipe` when the element is swiped.
+    pub(crate) fn handlers(&self, on_swipe: Callback<Direction>) -> Handlers {
+        let onpointerdown = Callback::from({
+            let start = self.start.clone();
+
+            move |e: PointerEvent| {
+                // Only touches are swiped, mice and pens select instead.
+                if e.pointer_type() == "touch" && e.is_primary() {
+                    start.set(Some((e.pointer_id(), e.client_x(), e.client_y())));
+                }
+            }
+        });
+
+        let onpointerup = Callback::from({
+            let start = self.start.clone();
+
+            move |e: PointerEvent| {
+                let Some((id, x, y)) = start.take() else {
+                    return;
+                };
+
+                if id != e.pointer_id() {
+                    return;
+                }
+
+                if let Some(direction) = direction(e.client_x() - x, e.client_y() - y) {
+                    on_swipe.emit(direction);
+                }
+            }
+        });
+
+        let onpointercancel = Callback::from({
+            let start = self.start.clone();
+            move |_: PointerEvent| start.set(None)
+        });
+
+        Handlers {
+            onpointerdown,
+            onpointerup,
+            onpointercancel,
+        }
+    }
+}
+
+/// The direction of a movement, if it's long enough to be a swipe and mostly
+/// along one axis.
+fn direction(dx: i32, dy: i32) -> Option<Direction> {
+    let (ax, ay) = (dx.abs(), dy.abs());
+
+    if ax >= DISTANCE && ax > ay * 2 {
+        return Some(if dx < 0 {
+            Direction::Left
+        } else {
+            Direction::Right
+        });
+    }
+
+    if ay >= DISTANCE && ay > ax * 2 {
+        return Some(if dy < 0 {
+            Direction::Up
+        } else {
+            Direction::Down
+        });
+    }
+
+    None
+}
@@ -0,0 +1,722 @@
+//! Translations of the user interface.
+//!
+//! Texts are compiled into the application as a catalog with one
+//! translation per language. The selected language is stored in local
+//! storage, and defaults to the language of the browser.
+
+use std::cell::Cell;
+
+use gloo::storage::{LocalStorage, Storage};
+use lib::{Form, Inflection};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+/// Key the selected language is stored under in local storage.
+const STORAGE_KEY: &str = "jpv-language";
+
+thread_local! {
+    static LANGUAGE: Cell<Option<Language>> = const { Cell::new(None) };
+}
+
+/// A language the user interface is translated to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Language {
+    #[default]
+    English,
+    Japanese,
+}
+
+impl Language {
+    /// All supported languages, in the order they are listed.
+    pub(crate) const ALL: [Language; 2] = [Language::English, Language::Japanese];
+
+    /// The language tag, as used by `lang` attributes and for formatting.
+    pub(crate) fn tag(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Japanese => "ja",
+        }
+    }
+
+    /// The name of the language in the language itself.
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Japanese => "日本語",
+        }
+    }
+
+    /// Parse a language from its tag.
+    pub(crate) fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.tag() == tag)
+    }
+
+    /// The language preferred by the browser, if it's supported.
+    fn preferred() -> Option<Self> {
+        let tag = gloo::utils::window().navigator().language()?;
+        let primary = tag.split('-').next()?;
+        Self::from_tag(primary)
+    }
+}
+
+/// The language the user interface is shown in.
+pub(crate) fn language() -> Language {
+    LANGUAGE.with(|cell| {
+        if let Some(language) = cell.get() {
+            return language;
+        }
+
+        let language = LocalStorage::get::<Language>(STORAGE_KEY)
+            .ok()
+            .or_else(Language::preferred)
+            .unwrap_or_default();
+
+        cell.set(Some(language));
+        language
+    })
+}
+
+/// Select the language the user interface is shown in.
+///
+/// The application is reloaded, so that every component is shown in the new
+/// language.
+pub(crate) fn set_language(language: Language) {
+    if let Err(error) = LocalStorage::set(STORAGE_KEY, language) {
+        log::warn!("Failed to store language: {error}");
+    }
+
+    LANGUAGE.with(|cell| cell.set(Some(language)));
+
+    if let Err(error) = gloo::utils::window().location().reload() {
+        log::warn!("Failed to reload: {error:?}");
+    }
+}
+
+/// Mark the document with the selected language, so that the browser picks
+/// appropriate fonts and speech for it.
+pub(crate) fn apply() {
+    let Some(element) = gloo::utils::document().document_element() else {
+        return;
+    };
+
+    if let Err(error) = element.set_attribute("lang", language().tag()) {
+        log::warn!("Failed to set language: {error:?}");
+    }
+}
+
+/// Translate a text to the selected language.
+pub(crate) fn t(text: Text) -> &'static str {
+    text.text(language())
+}
+
+/// Format a number using the conventions of the selected language.
+pub(crate) fn number<T>(value: T) -> String
+where
+    f64: From<T>,
+{
+    js_sys::Number::from(f64::from(value))
+        .to_locale_string(language().tag())
+        .into()
+}
+
+/// Format a `YYYY-MM-DD` date using the conventions of the selected
+/// language, falling back to the date as it is if it can't be parsed.
+pub(crate) fn date(date: &str) -> String {
+    let parsed = js_sys::Date::new(&JsValue::from_str(date));
+
+    if parsed.get_time().is_nan() {
+        return date.to_owned();
+    }
+
+    // Dates without a time are parsed as midnight UTC, so they have to be
+    // formatted in UTC to not be shifted by the local time zone.
+    let options = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&options, &"timeZone".into(), &"UTC".into());
+    let _ = js_sys::Reflect::set(&options, &"month".into(), &"short".into());
+    let _ = js_sys::Reflect::set(&options, &"day".into(), &"numeric".into());
+
+    parsed
+        .to_locale_date_string(language().tag(), &options)
+        .into()
+}
+
+/// A count of days, like "3 days".
+pub(crate) fn days(count: u32) -> String {
+    match language() {
+        Language::English if count == 1 => format!("{count} day"),
+        Language::English => format!("{} days", number(count)),
+        Language::Japanese => format!("{}日", number(count)),
+    }
+}
+
+/// A count of strokes of a character, like "4 strokes".
+pub(crate) fn strokes(count: u32) -> String {
+    match language() {
+        Language::English if count == 1 => format!("{count} stroke"),
+        Language::English => format!("{count} strokes"),
+        Language::Japanese => format!("{count}画"),
+    }
+}
+
+/// The outcome of importing known words.
+pub(crate) fn imported_known_words(
+    added: usize,
+    words: usize,
+    known: usize,
+    missing: usize,
+) -> String {
+    match language() {
+        Language::English if missing == 0 => {
+            format!("Imported {added} of {words} words, {known} known words in total")
+        }
+        Language::English => format!(
+            "Imported {added} of {words} words, {known} known words in total, {missing} not found in the dictionary"
+        ),
+        Language::Japanese if missing == 0 => {
+            format!("{words}語中{added}語をインポートしました。既知語は合計{known}語です")
+        }
+        Language::Japanese => format!(
+            "{words}語中{added}語をインポートしました。既知語は合計{known}語です。{missing}語は辞書に見つかりませんでした"
+        ),
+    }
+}
+
+/// Format a duration given in seconds as hours and minutes.
+pub(crate) fn duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = seconds % 3600 / 60;
+
+    match language() {
+        Language::English if hours == 0 => format!("{minutes} min"),
+        Language::English => format!("{hours} h {minutes} min"),
+        Language::Japanese if hours == 0 => format!("{minutes}分"),
+        Language::Japanese => format!("{hours}時間{minutes}分"),
+    }
+}
+
+/// What results a count of shown results is of.
+#[derive(Clone, Copy)]
+pub(crate) enum Shown {
+    Phrases,
+    Characters,
+    Examples,
+}
+
+/// How many results are shown out of the total, like "Showing 10 out of 20
+/// phrases".
+pub(crate) fn showing(shown: usize, total: usize, what: Shown) -> String {
+    match language() {
+        Language::English => {
+            let what = match what {
+                Shown::Phrases => "phrases",
+                Shown::Characters => "characters",
+                Shown::Examples => "examples",
+            };
+
+            format!("Showing {shown} out of {total} {what}")
+        }
+        Language::Japanese => {
+            let what = match what {
+                Shown::Phrases => "語句",
+                Shown::Characters => "文字",
+                Shown::Examples => "例文",
+            };
+
+            format!("{total}件の{what}のうち{shown}件を表示")
+        }
+    }
+}
+
+/// A page out of a number of pages, like "Page 2 / 10".
+pub(crate) fn page(page: usize, pages: usize) -> String {
+    match language() {
+        Language::English => format!("Page {page} / {pages}"),
+        Language::Japanese => format!("{page} / {pages}ページ"),
+    }
+}
+
+/// A button showing the rare spellings of a phrase.
+pub(crate) fn show_rare_spellings(count: usize) -> String {
+    match language() {
+        Language::English if count == 1 => format!("Show {count} rare spelling"),
+        Language::English => format!("Show {count} rare spellings"),
+        Language::Japanese => format!("まれな表記を{count}件表示"),
+    }
+}
+
+/// A button toggling the variants of a phrase.
+pub(crate) fn variants(count: usize, expanded: bool) -> String {
+    match language() {
+        Language::English if expanded => format!("Hide {count} variants"),
+        Language::English => format!("Show {count} variants"),
+        Language::Japanese if expanded => format!("{count}件の異形を隠す"),
+        Language::Japanese => format!("{count}件の異形を表示"),
+    }
+}
+
+/// The number of words in a document.
+pub(crate) fn document_words(words: usize, unique: usize, unknown: usize) -> String {
+    match language() {
+        Language::English => format!("{words} words, {unique} unique, {unknown} unknown"),
+        Language::Japanese => format!("{words}語、異なり{unique}語、未知{unknown}語"),
+    }
+}
+
+/// The share of words in a document which are known.
+pub(crate) fn known_words(coverage: f32) -> String {
+    match language() {
+        Language::English => format!("({coverage:.1}% of words known)"),
+        Language::Japanese => format!("（既知語{coverage:.1}%）"),
+    }
+}
+
+/// Why an analyzed candidate was ranked like it was.
+pub(crate) fn candidate_score(total: f32, frequency: f32, length: f32, pos: f32) -> String {
+    match language() {
+        Language::English => format!(
+            "Score {total:.1}: frequency ×{frequency:.1}, length ×{length:.0}, part of speech ×{pos:.1}"
+        ),
+        Language::Japanese => format!(
+            "スコア{total:.1}：頻度 ×{frequency:.1}、長さ ×{length:.0}、品詞 ×{pos:.1}"
+        ),
+    }
+}
+
+/// A count of captured lines, like "3 lines".
+pub(crate) fn lines(count: usize) -> String {
+    match language() {
+        Language::English if count == 1 => format!("{count} line"),
+        Language::English => format!("{} lines", number(count as f64)),
+        Language::Japanese => format!("{}行", number(count as f64)),
+    }
+}
+
+/// A count of characters read, like "300 characters".
+pub(crate) fn characters(count: usize) -> String {
+    match language() {
+        Language::English if count == 1 => format!("{count} character"),
+        Language::English => format!("{} characters", number(count as f64)),
+        Language::Japanese => format!("{}文字", number(count as f64)),
+    }
+}
+
+/// A count of characters read per hour, like "300 / h".
+pub(crate) fn per_hour(count: f64) -> String {
+    match language() {
+        Language::English => format!("{} / h", number(count.round())),
+        Language::Japanese => format!("{}文字／時", number(count.round())),
+    }
+}
+
+/// Explain how an inflection is formed, like "passive → negative → past".
+pub(crate) fn inflection(inflection: Inflection) -> String {
+    let mut out = String::new();
+
+    for (n, form) in inflection.explain().enumerate() {
+        if n > 0 {
+            out.push_str(" → ");
+        }
+
+        out.push_str(form_name(form));
+    }
+
+    out
+}
+
+/// The plain name of an inflected form.
+fn form_name(form: Form) -> &'static str {
+    let Language::Japanese = language() else {
+        return form.name();
+    };
+
+    match form {
+        Form::Stem => "連用形",
+        Form::Honorific => "丁寧",
+        Form::Negative => "否定",
+        Form::Te => "て形",
+        Form::TeAru => "てある",
+        Form::TeIru => "ている",
+        Form::TeIku => "ていく",
+        Form::TeKuru => "てくる",
+        Form::TeOku => "ておく",
+        Form::TeShimau => "てしまう",
+        Form::Tai => "たい",
+        Form::EasyTo => "やすい",
+        Form::HardTo => "にくい",
+        Form::TaGaRu => "たがる",
+        Form::Causative => "使役",
+        Form::Chau => "ちゃう",
+        Form::Command => "命令",
+        Form::CommandTeKudasai => "てください",
+        Form::CommandYo => "よ",
+        Form::Conditional => "たら",
+        Form::Darou => "だろう",
+        Form::Hypothetical => "仮定",
+        Form::Kya => "きゃ",
+        Form::Passive => "受身",
+        Form::Past => "過去",
+        Form::Potential => "可能",
+        Form::Simultaneous => "ながら",
+        Form::Volitional => "意志",
+        Form::LooksLike => "そう",
+        Form::Short => "短縮",
+        Form::Conversation => "口語",
+        Form::Zu => "ず",
+        Form::Attributive => "連体形",
+        Form::To => "と",
+    }
+}
+
+macro_rules! texts {
+    ($($name:ident => $en:literal, $ja:literal;)*) => {
+        /// A translated text.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub(crate) enum Text {
+            $($name,)*
+        }
+
+        impl Text {
+            /// The text in the given language.
+            pub(crate) fn text(self, language: Language) -> &'static str {
+                match (self, language) {
+                    $(
+                        (Text::$name, Language::English) => $en,
+                        (Text::$name, Language::Japanese) => $ja,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+texts! {
+    // Common.
+    Loading => "Loading", "読み込み中";
+    LoadingMore => "Loading more phrases...", "さらに読み込み中…";
+    ShowMore => "Show more", "もっと見る";
+    Save => "Save", "保存";
+    Cancel => "Cancel", "キャンセル";
+    Back => "Back", "戻る";
+    Clear => "Clear", "クリア";
+    Delete => "Delete", "削除";
+    Create => "Create", "作成";
+    Import => "Import", "インポート";
+    Export => "Export", "エクスポート";
+    Stop => "Stop", "停止";
+    Add => "Add", "追加";
+    Search => "Search", "検索";
+    LookUp => "Look up", "調べる";
+    ReadOutLoud => "Read out loud", "読み上げる";
+    Disable => "Disable", "無効にする";
+
+    // Search.
+    Phrases => "Phrases", "語句";
+    Names => "Names", "名前";
+    Kanji => "Kanji", "漢字";
+    Entries => "Entries", "見出し語";
+    Examples => "Examples", "例文";
+    Results => "Results", "結果";
+    NoKanjiMatch => "No kanji match the filter", "条件に一致する漢字はありません";
+    Translation => "Translation:", "翻訳：";
+    DictionariesMissing => "Dictionaries missing:", "辞書が見つかりません：";
+    KanjiMissing => "Kanji data not installed", "漢字データがインストールされていません";
+    InstallNow => "Install now", "今すぐインストール";
+    Installing => "Installing…", "インストール中…";
+    NoDictionaries => "No dictionaries are installed or enabled", "インストール済みまたは有効な辞書がありません";
+    Auto => "Auto", "自動";
+    Unfiltered => "None", "なし";
+    Group => "Group", "グループ";
+    Compact => "Compact", "コンパクト";
+    HardWords => "Hard words", "難しい語";
+    WordStatus => "Word status", "学習状況";
+    Subtitles => "Subtitles", "字幕";
+    Confidence => "How confident OCR is in the line", "OCRの認識の確からしさ";
+    CorrectOcr => "Correct text", "テキストを修正";
+    SortBy => "Sort by", "並べ替え";
+    Timeline => "Timeline", "履歴";
+    EmptyPrompt => "Empty prompt", "空の入力";
+    AppName => "Japanese Dictionary", "日本語辞書";
+    By => "by ", "作者：";
+    Pages => "Pages", "ページ";
+    Configure => "Configure", "設定";
+    Config => "Config", "設定";
+    FixInSettings => "Fix in Settings", "設定で修正";
+    OcrNotInstalled => "OCR support is enabled but not installed", "OCRは有効ですがインストールされていません";
+    OpenInBigWindow => "Open in big window", "大きなウィンドウで開く";
+    ShortLink => "Short link to this query", "この検索の短いリンク";
+    GoBack => "Go back", "戻る";
+    GoForward => "Go forward", "進む";
+    TimelineHelp => "Show the states visited in this session", "このセッションで表示した状態を表示";
+    NothingToAnalyze => "Nothing to analyze", "解析するものがありません";
+    TypeSomething => "Type something in the prompt", "入力欄に何か入力してください";
+    AutoHelp => "Detect whether input is romaji, kana, kanji or English", "入力がローマ字、仮名、漢字、英語のどれかを判別する";
+    UnfilteredHelp => "Do not process input at all", "入力をそのまま使う";
+    HiraganaHelp => "Process input as Hiragana", "入力をひらがなとして扱う";
+    KatakanaHelp => "Treat input as Katakana", "入力をカタカナとして扱う";
+    CaptureClipboard => "Capture clipboard", "クリップボードをキャプチャ";
+    GroupHelp => "Group phrases which share kanji", "漢字が共通する語句をまとめる";
+    CompactHelp => "List results compactly, loading full entries when expanded", "結果をコンパクトに表示し、展開時にすべて読み込む";
+    HardWordsHelp => "Highlight hard words in the analyzed text", "解析したテキストの難しい語を強調";
+    WordStatusHelp => "Highlight words by how well they are known", "語をどれだけ知っているかで強調";
+    SubtitlesHelp => "Show captured lines stacked with their translation", "キャプチャした行を翻訳と重ねて表示";
+    InteractionHelp => "How characters are selected for analysis", "解析する文字の選び方";
+    ClickToAnalyze => "Click to analyze", "クリックで解析";
+    LongPressToAnalyze => "Long press to analyze", "長押しで解析";
+    DragToSelect => "Drag to select", "ドラッグで選択";
+    CaptureFocus => "Capture focus", "キャプチャの受け取り";
+    CaptureFocused => "Only this tab reacts to captures", "このタブだけがキャプチャに反応します";
+    CaptureFocusClaimed => "Another tab has capture focus, click to claim it", "別のタブがキャプチャを受け取っています。クリックで切り替えます";
+    ClaimCaptureFocus => "Claim capture focus, so that only this tab reacts to captures", "キャプチャを受け取り、このタブだけが反応するようにする";
+    SearchAsEnglish => "Search as English", "英語として検索";
+    SearchingAsEnglish => "Searching as English", "英語として検索中";
+    SearchAsRomaji => "Search as romaji", "ローマ字として検索";
+    SearchingRomajiAs => "Searching romaji as", "ローマ字を次として検索中：";
+    Hint => "Hint:", "ヒント：";
+    ClickHint => "Click character for substring search", "文字をクリックして部分文字列を検索";
+    LongPressHint => "Press and hold character for substring search", "文字を長押しして部分文字列を検索";
+    SelectHint => "Drag over characters to analyze exactly them", "文字をドラッグしてその部分だけを解析";
+    CycleHint => "(click character to cycle)", "（文字をクリックして切り替え）";
+    FilterKanji => "Filter kanji", "漢字を絞り込む";
+    Relevance => "Relevance", "関連度";
+    JlptLevel => "JLPT level", "JLPTレベル";
+    StrokeCount => "Stroke count", "画数";
+    AllKanji => "All kanji", "すべての漢字";
+    KyouikuKanji => "Kyōiku kanji", "教育漢字";
+    JoyoKanji => "Jōyō kanji", "常用漢字";
+    JlptKanji => "JLPT kanji", "JLPT漢字";
+    ToggleFurigana => "Toggle furigana", "振り仮名の表示を切り替え";
+
+    // Entries.
+    OtherKana => "Other kana", "他の仮名";
+    OtherKanji => "Other kanji", "他の漢字";
+    RelatedWords => "Related words", "関連語";
+    NoRelatedWords => "No related words", "関連語はありません";
+    AddNote => "Add note", "メモを追加";
+    EditNote => "Edit note", "メモを編集";
+    AppliesTo => "Applies to", "適用対象";
+    InflectionResult => "Result based on inflection:", "活用形による結果：";
+    SkeletonResult => "Result with the okurigana left out of the search", "送り仮名を省いた検索による結果";
+    Reset => "Reset", "リセット";
+    Recordings => "Recordings", "録音";
+    Record => "Record", "録音する";
+    RecordHelp => "Record your own pronunciation", "自分の発音を録音";
+    DeleteRecording => "Delete recording", "録音を削除";
+    Counters => "Counters", "助数詞";
+    NoCounters => "No counters", "助数詞はありません";
+    Number => "Number", "数";
+    NotUsedWithNumber => "Not used with this number", "この数には使われません";
+    HideRareSpellings => "Hide rare spellings", "まれな表記を隠す";
+    RareSpellingsHelp => "Rarely-used, outdated and search-only spellings", "まれな表記、古い表記、検索専用の表記";
+    Pin => "Pin", "ピン留め";
+    PinHelp => "Pin this phrase to compare it with other phrases", "この語句をピン留めして他の語句と比較する";
+    UnpinHelp => "Stop comparing this phrase", "この語句の比較をやめる";
+    Mine => "Mine", "収集";
+    Mined => "Mined", "収集済み";
+    MineFrom => "Mine a card for this phrase from:", "この語句のカードを次の文から作成：";
+    AnkiNotesForPhrase => "Notes in Anki for this phrase:", "この語句のAnkiのノート：";
+    Difficulty => "Difficulty", "難易度";
+    EstimatedDifficulty => "Estimated difficulty", "推定難易度";
+    Note => "Note", "メモ";
+    TagsHelp => "Tags, separated by spaces", "タグ（スペース区切り）";
+    TutorialFor => "Tutorial for", "解説：";
+
+    // Characters.
+    Components => "Components", "部品";
+    NoComponents => "No known components", "既知の部品はありません";
+    Grade => "Grade", "学年";
+    OldJlpt => "(old)", "（旧）";
+    Frequency => "Frequency", "頻度";
+    CommonWords => "Common words using this kanji", "この漢字を使う一般的な語";
+    NoCommonWords => "No words using this kanji", "この漢字を使う語はありません";
+
+    // Pages.
+    Conjugation => "Conjugation practice", "活用練習";
+    Reader => "Reader", "リーダー";
+    Compare => "Compare", "比較";
+    Statistics => "Statistics", "統計";
+    Quiz => "Quiz", "クイズ";
+
+    // Quiz.
+    NothingToPractice => "There are no words to practice, mark some words as being learned or add them to a word list first", "練習する語がありません。まず語を学習中にするか、単語リストに追加してください";
+
+    // Reader.
+    Documents => "Documents", "文書";
+    AddDocument => "Add document", "文書を追加";
+    NoDocuments => "No saved documents", "保存された文書はありません";
+    Upload => "Upload", "アップロード";
+    UnknownWords => "Unknown words", "未知語";
+    Contents => "Contents", "目次";
+    Title => "Title", "タイトル";
+    DocumentPlaceholder => "Paste text to read", "読むテキストを貼り付け";
+    UploadHelp => "Add a text file, an EPUB book or subtitles", "テキストファイル、EPUBの本、字幕を追加";
+    DeleteDocument => "Delete the document", "文書を削除";
+    FuriganaHelp => "How furigana in EPUB books is handled", "EPUBの本の振り仮名の扱い";
+    RemoveFurigana => "Remove furigana", "振り仮名を削除";
+    KeepFurigana => "Keep furigana in parenthesis", "振り仮名を括弧内に残す";
+    Previous => "Previous", "前へ";
+    Next => "Next", "次へ";
+    JumpToTime => "Jump to time", "時間へ移動";
+    SearchLines => "Search lines", "行を検索";
+
+    // Compare.
+    NothingToCompare => "Nothing to compare, pin phrases from their entries to compare them side by side", "比較する語句がありません。語句をピン留めすると並べて比較できます";
+    Unpin => "Unpin", "ピン解除";
+    ExportPdf => "Export PDF", "PDFに書き出す";
+    OpenPdf => "Open PDF", "PDFを開く";
+
+    // Graph.
+    Depth => "Depth", "深さ";
+    NoSuchPhrase => "There is no such phrase", "該当する語句はありません";
+    NoRelatedWordsFor => "No related words were found for", "関連語が見つかりませんでした：";
+
+    // Statistics.
+    CurrentStreak => "Current streak", "現在の連続日数";
+    LongestStreak => "Longest streak", "最長の連続日数";
+    Totals => "Totals", "合計";
+    Searches => "Searches", "検索";
+    Captures => "Captures", "キャプチャ";
+    Reviews => "Reviews", "復習";
+    WordsSaved => "Words saved", "保存した語";
+    Immersion => "Immersion", "多読";
+    CharactersRead => "Characters read", "読んだ文字数";
+    ReadingTime => "Time reading", "読書時間";
+    ReadingSpeed => "Characters per hour", "1時間あたりの文字数";
+    Lines => "Lines", "行数";
+    Sources => "Sources", "ソース";
+    ExportCsv => "Export CSV", "CSVをエクスポート";
+
+    // Mining.
+    MinedSentences => "Mined sentences", "集めた文";
+    NoMinedSentences => "Nothing has been mined yet, mine sentences from analyzed text.", "まだ文がありません。解析したテキストから文を集められます。";
+    SendToAnki => "Send to Anki", "Ankiに送る";
+    SendAllToAnki => "Send all to Anki", "すべてAnkiに送る";
+    AttachScreenshots => "Attach the last captured image to mined sentences", "最後にキャプチャした画像を文に添付する";
+    AddNotesToAnki => "Add notes to Anki", "Ankiにノートを追加する";
+    AddToAnki => "Add to Anki", "Ankiに追加";
+    InAnki => "In Anki", "Ankiにあり";
+    AlreadyInAnki => "Already in Anki", "すでにAnkiにあります";
+    AddAnyway => "Add anyway", "それでも追加";
+    Feed => "Feed", "フィード";
+    NoFeedLines => "Captured lines show up here as they arrive, click a line to look it up.", "キャプチャした行がここに表示されます。行をクリックすると調べられます。";
+    Pause => "Pause", "一時停止";
+    Resume => "Resume", "再開";
+    AutoScroll => "Scroll to new lines", "新しい行へスクロール";
+    AllSources => "All sources", "すべてのソース";
+    LinesCapturedHelp => "Lines captured", "キャプチャした行数";
+    CharactersReadHelp => "Characters read, not counting whitespace and punctuation", "読んだ文字数（空白と句読点を除く）";
+    FeedSpeedHelp => "Characters read per hour since the first line", "最初の行からの1時間あたりの文字数";
+    ResumeHelp => "Add the lines captured while paused", "一時停止中にキャプチャした行を追加します";
+    PauseHelp => "Stop adding captured lines until resumed", "再開するまでキャプチャした行を追加しません";
+    SourceHelp => "Only show lines captured from this application", "このアプリケーションからキャプチャした行だけを表示します";
+    AnkiDisabled => "Enable Anki in the settings to send mined sentences to it", "マイニングした文をAnkiに送るには設定でAnkiを有効にしてください";
+    SendAllToAnkiHelp => "Add notes to a running Anki through AnkiConnect, skipping words which are already in it", "AnkiConnect経由で起動中のAnkiにノートを追加します。すでにある語は飛ばします";
+    CapturedImage => "Captured image", "キャプチャした画像";
+    AnkiNote => "Anki note", "Ankiのノート";
+
+    // Configuration.
+    Profile => "Profile", "プロフィール";
+    Sync => "Sync", "同期";
+    Dictionaries => "Dictionaries", "辞書";
+    SenseDetails => "Sense details", "語義の詳細";
+    SpeechSynthesis => "Text-to-speech", "音声合成";
+    WordSegmentation => "Word segmentation", "単語分割";
+    Transcription => "Transcription", "文字起こし";
+    OcrEngine => "Engine", "エンジン";
+    Model => "Model", "モデル";
+    Log => "Log", "ログ";
+    Language => "Language", "言語";
+    CollectStatistics => "Collect statistics", "統計を収集";
+    KeepForDays => "Keep for days", "保存日数";
+    Startup => "Startup", "起動";
+    OpenBrowser => "Open in the browser when started", "起動時にブラウザで開く";
+    CheckUpdates => "Check for new releases", "新しいリリースを確認する";
+    UpdateAvailable => "A new release is available", "新しいリリースがあります";
+    ShowRelease => "Show release", "リリースを表示";
+    NewDictionary => "New dictionary", "辞書を追加";
+    InstallAll => "Install all", "すべてインストール";
+    Accessibility => "Accessibility", "アクセシビリティ";
+    PlainReadings => "Show readings in parentheses", "読みを括弧内に表示";
+    RevealTranslations => "Show translations without hovering", "翻訳をぼかさずに表示";
+    AnkiHelp => "Add entries and mined sentences to a running Anki through AnkiConnect", "AnkiConnect経由で起動中のAnkiに見出しとマイニングした文を追加します";
+    AnkiUrl => "Address", "アドレス";
+    AnkiUrlHelp => "Where AnkiConnect listens, which is an add-on for Anki", "Ankiのアドオンである AnkiConnect の待ち受けアドレス";
+    AnkiDeck => "Deck", "デッキ";
+    AnkiDeckHelp => "The deck notes are added to, which must already exist", "ノートを追加するデッキ。あらかじめ作成しておく必要があります";
+    AnkiModel => "Note type", "ノートタイプ";
+    AnkiModelHelp => "The note type of added notes, whose fields are named below", "追加するノートのノートタイプ。フィールド名は下で指定します";
+    AnkiFieldHelp => "The field of the note type this is written to, which can be shared with other parts. Left empty it's not written", "書き込むノートタイプのフィールド。ほかの項目と共有できます。空欄にすると書き込みません";
+    AnkiNotWritten => "Not written", "書き込まない";
+    AnkiWordField => "Word field", "単語のフィールド";
+    AnkiReadingField => "Reading field", "読みのフィールド";
+    AnkiMeaningField => "Meaning field", "意味のフィールド";
+    AnkiSentenceField => "Sentence field", "文のフィールド";
+    AnkiImageField => "Image field", "画像のフィールド";
+    AnkiTags => "Tags", "タグ";
+    AnkiTagsHelp => "Tags added to every note, separated by spaces", "すべてのノートに追加するタグ（スペース区切り）";
+    AnkiNoTags => "No tags", "タグなし";
+    CaptureFilters => "Capture filters", "キャプチャフィルター";
+    IgnoredSources => "Ignored sources", "無視するソース";
+    AddFilter => "Add filter", "フィルターを追加";
+    Enabled => "Enabled", "有効";
+    DropMatches => "Drop matching captures", "一致したキャプチャを無視する";
+    TryFilters => "Try on text", "テキストで試す";
+    CaptureDropped => "This capture would be dropped", "このキャプチャは無視されます";
+    IgnoredSourcesHelp => "Applications whose captures are ignored, separated by commas, like the browser the dictionary is open in. Captures are labelled with the application they come from when it's known", "キャプチャを無視するアプリケーション（カンマ区切り）。辞書を開いているブラウザなど。わかる場合はキャプチャ元のアプリケーションが記録されます";
+    TryFiltersHelp => "Captured text to try the filters on, nothing is saved", "フィルターを試すキャプチャテキスト。保存はされません";
+    DropMatchesHelp => "Captures which match the pattern are ignored instead of having the match replaced", "パターンに一致したキャプチャは、一致部分を置換せずに無視します";
+    FilterName => "Name", "名前";
+    FilterNameHelp => "Only used to tell filters apart", "フィルターを区別するためだけに使います";
+    FilterUnnamed => "Unnamed", "名前なし";
+    FilterPattern => "Pattern", "パターン";
+    FilterPatternHelp => "A regular expression matched against captured text", "キャプチャしたテキストに照合する正規表現";
+    FilterReplace => "Replace with", "置換後";
+    FilterReplaceHelp => "What matches are replaced with, where $1 or $name refers to a group in the pattern. Left empty matches are removed", "一致部分の置換後の文字列。$1や$nameでパターンのグループを参照できます。空欄にすると一致部分を削除します";
+    FilterRemoved => "Removed", "削除";
+
+    // Profiles.
+    SelectProfile => "Select profile", "プロフィールを選択";
+    DeleteProfile => "Delete the selected profile", "選択したプロフィールを削除";
+    NewProfile => "New profile", "新しいプロフィール";
+    SyncProfile => "Synchronize this profile with other installs", "このプロフィールを他のインストールと同期";
+    ExportUserData => "Export profiles and configuration", "プロフィールと設定をエクスポート";
+    ImportUserData => "Import profiles and configuration from an exported archive", "エクスポートしたアーカイブからプロフィールと設定をインポート";
+    ImportKnownWords => "Import known words", "既知語をインポート";
+    ImportKnownWordsHelp => "Mark words as known from an Anki plain text export or a CSV file", "AnkiのテキストエクスポートまたはCSVファイルの語を既知にする";
+    PreferWords => "Prefer common words over names unless the context suggests a name", "文脈から名前と判断される場合を除き、名前より一般的な語を優先する";
+    ShowReadings => "Show readings above kanji", "漢字の上に読みを表示";
+    ReadingsAlways => "Always", "常に";
+    ReadingsHover => "On hover", "ホバー時";
+    ReadingsNever => "Never", "表示しない";
+    Density => "Density", "表示密度";
+    DensityHelp => "Compact only shows the first gloss of each sense", "コンパクトでは各語義の最初の訳語のみを表示";
+    DensityNormal => "Normal", "標準";
+    JapaneseTextSize => "Japanese text size", "日本語の文字サイズ";
+
+    // Dictionary editing.
+    Id => "Id", "ID";
+    IdHelp => "The unique identifier of the dictionary, must only contain [a-z], [A-Z], and [0-9].", "辞書の一意な識別子です。[a-z]、[A-Z]、[0-9]のみ使用できます。";
+    InvalidId => "Must be a non-empty sequence of [a-z], [A-Z], and [0-9].", "[a-z]、[A-Z]、[0-9]からなる空でない文字列にしてください。";
+    Required => "Must be non-empty", "入力してください";
+    Format => "Format", "形式";
+    TanakaCorpus => "Tanaka corpus", "田中コーパス";
+    Url => "URL", "URL";
+    Description => "Description", "説明";
+    HelpPage => "Help Page", "ヘルプページ";
+    Compressed => "Compressed", "圧縮";
+    CompressedHelp => "Store entries compressed, which makes the dictionary smaller but slightly slower to load entries from. Takes effect when the dictionary is rebuilt.", "見出し語を圧縮して保存します。辞書は小さくなりますが、見出し語の読み込みが少し遅くなります。辞書の再構築時に反映されます。";
+
+    // First-run setup.
+    Welcome => "Welcome to the Japanese Dictionary", "日本語辞書へようこそ";
+    OnboardingIntro => "No dictionaries are installed yet, a few steps get everything set up.", "辞書はまだインストールされていません。いくつかの手順で設定を完了できます。";
+    GetStarted => "Get started", "始める";
+    Skip => "Skip", "スキップ";
+    Finish => "Finish", "完了";
+    NotNow => "Not now", "今はしない";
+    InstallDictionaries => "Install dictionaries", "辞書のインストール";
+    InstallDictionariesIntro => "These dictionaries are downloaded and built in the background:", "以下の辞書がバックグラウンドでダウンロード・構築されます：";
+    AcceptLicense => "I accept the license of the dictionary files", "辞書ファイルのライセンスに同意します";
+    Capture => "Capture", "キャプチャ";
+    CaptureIntro => "Text copied to the clipboard is looked up when it's sent to the dictionary, like through the GNOME extension or jpv send-clipboard.", "クリップボードにコピーしたテキストは、GNOME拡張機能やjpv send-clipboardで辞書に送られると検索されます。";
+    RecognizeImages => "Recognize text in copied images (OCR)", "コピーした画像の文字を認識する（OCR）";
+}
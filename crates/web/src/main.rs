@@ -1,8 +1,16 @@
+mod accessibility;
+mod base;
 mod components;
+mod display;
 mod error;
+mod gesture;
+mod i18n;
+mod navigation;
+mod pinned;
 mod query;
 mod ws;
 
+use lib::api;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
@@ -12,6 +20,25 @@ use self::components as c;
 enum Route {
     #[at("/")]
     Prompt,
+    #[at("/reader")]
+    Reader,
+    #[at("/reader/:id")]
+    Document { id: u64 },
+    #[at("/quiz")]
+    Quiz,
+    #[at("/compare")]
+    Compare,
+    #[at("/graph/:seq")]
+    Graph { seq: u32 },
+    #[at("/stats")]
+    Stats,
+    #[at("/mining")]
+    Mining,
+    #[at("/feed")]
+    Feed,
+    /// Target for text shared from other applications.
+    #[at("/share")]
+    Share,
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -19,9 +46,18 @@ enum Route {
 
 enum Msg {
     WebSocket(ws::Msg),
+    Profiles(api::ProfilesResponse),
+    Broadcast(api::OwnedBroadcastKind),
     Error(error::Error),
 }
 
+impl From<api::OwnedBroadcastKind> for Msg {
+    #[inline]
+    fn from(broadcast: api::OwnedBroadcastKind) -> Self {
+        Msg::Broadcast(broadcast)
+    }
+}
+
 impl From<ws::Msg> for Msg {
     #[inline]
     fn from(msg: ws::Msg) -> Self {
@@ -39,6 +75,8 @@ impl From<error::Error> for Msg {
 struct App {
     ws: ws::Service<Self>,
     handle: ws::Handle,
+    pending_profiles: ws::Request,
+    _listener: ws::Listener,
 }
 
 impl Component for App {
@@ -48,12 +86,20 @@ impl Component for App {
     fn create(ctx: &Context<Self>) -> Self {
         let (ws, handle) = ws::Service::new(ctx);
 
-        let mut this = Self { ws, handle };
+        let _listener = handle.listen(ctx);
+
+        let mut this = Self {
+            ws,
+            handle,
+            pending_profiles: ws::Request::empty(),
+            _listener,
+        };
 
         if let Err(error) = this.ws.connect(ctx) {
             ctx.link().send_message(error);
         }
 
+        this.load_profiles(ctx);
         this
     }
 
@@ -63,6 +109,16 @@ impl Component for App {
                 self.ws.update(ctx, msg);
                 false
             }
+            Msg::Profiles(profiles) => {
+                self.pending_profiles = ws::Request::empty();
+                display::apply_profile(&profiles);
+                false
+            }
+            Msg::Broadcast(api::OwnedBroadcastKind::ProfileChanged) => {
+                self.load_profiles(ctx);
+                false
+            }
+            Msg::Broadcast(..) => false,
             Msg::Error(error) => {
                 log::error!("Failed to fetch: {error}");
                 false
@@ -81,11 +137,55 @@ impl Component for App {
     }
 }
 
+impl App {
+    /// Load the current profile, whose display settings apply to all views.
+    fn load_profiles(&mut self, ctx: &Context<Self>) {
+        self.pending_profiles = self.handle.request(
+            api::ProfilesRequest,
+            ctx.link().batch_callback(|result| match result {
+                Ok(profiles) => Some(Msg::Profiles(profiles)),
+                Err(error) => {
+                    // Profiles aren't available in a shared dictionary.
+                    log::debug!("Failed to load profile: {error}");
+                    None
+                }
+            }),
+        );
+    }
+}
+
 fn switch(routes: Route, ws: &ws::Handle) -> Html {
     match routes {
         Route::Prompt => html! {
             <c::Prompt ws={ws.clone()} />
         },
+        Route::Reader => html! {
+            <c::Reader ws={ws.clone()} />
+        },
+        Route::Document { id } => html! {
+            <c::Reader ws={ws.clone()} id={Some(id)} />
+        },
+        Route::Quiz => html! {
+            <c::Quiz ws={ws.clone()} />
+        },
+        Route::Compare => html! {
+            <c::Compare ws={ws.clone()} />
+        },
+        Route::Graph { seq } => html! {
+            <c::GraphView ws={ws.clone()} {seq} />
+        },
+        Route::Stats => html! {
+            <c::Stats ws={ws.clone()} />
+        },
+        Route::Mining => html! {
+            <c::Mining ws={ws.clone()} />
+        },
+        Route::Feed => html! {
+            <c::Feed ws={ws.clone()} />
+        },
+        Route::Share => html! {
+            <c::Share />
+        },
         Route::NotFound => {
             html! {
                 <div id="content" class="container">{"There is nothing here"}</div>
@@ -97,6 +197,8 @@ fn switch(routes: Route, ws: &ws::Handle) -> Html {
 fn main() -> anyhow::Result<()> {
     wasm_logger::init(wasm_logger::Config::default());
     log::trace!("Started up");
+    i18n::apply();
+    accessibility::install();
     yew::Renderer::<App>::new().render();
     Ok(())
 }
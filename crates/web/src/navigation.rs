@@ -0,0 +1,178 @@
+//! In-app navigation stack for the prompt.
+//!
+//! The stack is kept separately from the browser history, since states like
+//! cycling through analyzed words are too fine-grained to push to the
+//! browser. It is stored in session storage so that it survives reloads of
+//! the page.
+
+use gloo::storage::{SessionStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use crate::query::Query;
+
+/// Key the navigation stack is stored under in session storage.
+const STORAGE_KEY: &str = "jpv-navigation";
+/// The maximum number of entries kept in the stack.
+const MAX_ENTRIES: usize = 100;
+
+/// A state in the navigation stack.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    /// The serialized query.
+    pub(crate) query: Vec<(String, String)>,
+    /// The text of the query.
+    pub(crate) text: String,
+    /// The byte offset in the text being analyzed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) analyze_at: Option<usize>,
+}
+
+impl Entry {
+    /// Construct an entry from a query.
+    pub(crate) fn new(query: &Query) -> Self {
+        Self {
+            query: query
+                .serialize(false)
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value.into_owned()))
+                .collect(),
+            text: query.text.as_ref().to_owned(),
+            analyze_at: query.analyze_at,
+        }
+    }
+
+    /// Test if the other entry was produced by continuing to type in this
+    /// entry, in which case it shouldn't be given its own entry.
+    fn is_continued_by(&self, other: &Entry) -> bool {
+        self.analyze_at.is_none()
+            && other.analyze_at.is_none()
+            && (other.text.starts_with(&self.text) || self.text.starts_with(&other.text))
+    }
+}
+
+/// The navigation stack.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Navigation {
+    entries: Vec<Entry>,
+    cursor: usize,
+}
+
+impl Navigation {
+    /// Load the navigation stack from session storage.
+    pub(crate) fn load() -> Self {
+        let mut this = SessionStorage::get::<Self>(STORAGE_KEY).unwrap_or_default();
+        this.cursor = this.cursor.min(this.entries.len().saturating_sub(1));
+        this
+    }
+
+    /// The entries in the stack, oldest first.
+    pub(crate) fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The index of the current entry.
+    pub(crate) fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub(crate) fn can_back(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub(crate) fn can_forward(&self) -> bool {
+        self.cursor + 1 < self.entries.len()
+    }
+
+    /// Push a new state, discarding any states ahead of the current one.
+    pub(crate) fn push(&mut self, entry: Entry) {
+        match self.entries.get(self.cursor) {
+            Some(current) if *current == entry => {
+                return;
+            }
+            Some(current) if current.is_continued_by(&entry) => {
+                self.entries.truncate(self.cursor);
+            }
+            Some(..) => {
+                self.entries.truncate(self.cursor + 1);
+            }
+            None => {}
+        }
+
+        self.entries.push(entry);
+
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.drain(..self.entries.len() - MAX_ENTRIES);
+        }
+
+        self.cursor = self.entries.len() - 1;
+        self.save();
+    }
+
+    /// Replace the current state.
+    pub(crate) fn replace(&mut self, entry: Entry) {
+        match self.entries.get_mut(self.cursor) {
+            Some(current) => {
+                if *current == entry {
+                    return;
+                }
+
+                *current = entry;
+                self.save();
+            }
+            None => {
+                self.push(entry);
+            }
+        }
+    }
+
+    /// Synchronize with a state the browser navigated to, moving to it if it
+    /// is adjacent to the current state and pushing it otherwise.
+    pub(crate) fn visit(&mut self, entry: Entry) {
+        if self.entries.get(self.cursor) == Some(&entry) {
+            return;
+        }
+
+        let adjacent = [self.cursor.checked_sub(1), Some(self.cursor + 1)]
+            .into_iter()
+            .flatten()
+            .find(|&index| self.entries.get(index) == Some(&entry));
+
+        match adjacent {
+            Some(index) => {
+                self.cursor = index;
+                self.save();
+            }
+            None => {
+                self.push(entry);
+            }
+        }
+    }
+
+    /// Move to the entry at the given index, returning it.
+    pub(crate) fn go(&mut self, index: usize) -> Option<&Entry> {
+        if index >= self.entries.len() {
+            return None;
+        }
+
+        self.cursor = index;
+        self.save();
+        self.entries.get(index)
+    }
+
+    /// Clear all but the current entry.
+    pub(crate) fn clear(&mut self) {
+        if self.cursor < self.entries.len() {
+            self.entries.drain(self.cursor + 1..);
+            self.entries.drain(..self.cursor);
+        }
+
+        self.cursor = 0;
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Err(error) = SessionStorage::set(STORAGE_KEY, self) {
+            log::warn!("Failed to store navigation: {error}");
+        }
+    }
+}
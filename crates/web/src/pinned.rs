@@ -0,0 +1,68 @@
+//! Phrases pinned to be compared side by side.
+//!
+//! Pins are stored in local storage, so they're kept while browsing between
+//! searches.
+
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+/// Key pinned phrases are stored under in local storage.
+const STORAGE_KEY: &str = "jpv-pinned";
+
+/// The maximum number of phrases which can be pinned at once.
+pub(crate) const MAX_PINNED: usize = 4;
+
+/// Pinned phrases.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Pinned {
+    /// Sequences of pinned phrases, in the order they were pinned.
+    #[serde(default)]
+    pub(crate) sequences: Vec<u32>,
+}
+
+impl Pinned {
+    /// Load pinned phrases from local storage.
+    pub(crate) fn load() -> Self {
+        LocalStorage::get::<Self>(STORAGE_KEY).unwrap_or_default()
+    }
+
+    /// Test if the phrase with the given sequence is pinned.
+    pub(crate) fn is_pinned(&self, sequence: u32) -> bool {
+        self.sequences.contains(&sequence)
+    }
+
+    /// The number of pinned phrases.
+    pub(crate) fn len(&self) -> usize {
+        self.sequences.len()
+    }
+
+    /// Toggle if the given phrase is pinned and save the pins.
+    ///
+    /// If [`MAX_PINNED`] phrases are already pinned, the one which was
+    /// pinned first is unpinned to make room.
+    pub(crate) fn toggle(&mut self, sequence: u32) {
+        if self.is_pinned(sequence) {
+            self.sequences.retain(|s| *s != sequence);
+        } else {
+            if self.sequences.len() >= MAX_PINNED {
+                self.sequences.remove(0);
+            }
+
+            self.sequences.push(sequence);
+        }
+
+        self.save();
+    }
+
+    /// Unpin every phrase.
+    pub(crate) fn clear(&mut self) {
+        self.sequences.clear();
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Err(error) = LocalStorage::set(STORAGE_KEY, self) {
+            log::warn!("Failed to store pinned phrases: {error}");
+        }
+    }
+}
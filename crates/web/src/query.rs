@@ -1,15 +1,154 @@
 use std::{borrow::Cow, rc::Rc};
 
+use lib::{link, PartOfSpeechGroup};
 use web_sys::{window, Url};
 
+use crate::base;
+use crate::i18n::{t, Text};
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum Mode {
+    /// Detect the script of the input, and search romaji as kana.
     #[default]
+    Auto,
     Unfiltered,
     Hiragana,
     Katakana,
 }
 
+/// How kanji results are sorted.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KanjiSort {
+    #[default]
+    Relevance,
+    /// Lowest school grade first.
+    Grade,
+    /// Easiest JLPT level first.
+    Jlpt,
+    /// Most frequently used first.
+    Frequency,
+    /// Fewest strokes first.
+    Strokes,
+}
+
+impl KanjiSort {
+    pub(crate) const VALUES: &'static [KanjiSort] = &[
+        KanjiSort::Relevance,
+        KanjiSort::Grade,
+        KanjiSort::Jlpt,
+        KanjiSort::Frequency,
+        KanjiSort::Strokes,
+    ];
+
+    pub(crate) fn ident(&self) -> &'static str {
+        match self {
+            KanjiSort::Relevance => "relevance",
+            KanjiSort::Grade => "grade",
+            KanjiSort::Jlpt => "jlpt",
+            KanjiSort::Frequency => "frequency",
+            KanjiSort::Strokes => "strokes",
+        }
+    }
+
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            KanjiSort::Relevance => t(Text::Relevance),
+            KanjiSort::Grade => t(Text::Grade),
+            KanjiSort::Jlpt => t(Text::JlptLevel),
+            KanjiSort::Frequency => t(Text::Frequency),
+            KanjiSort::Strokes => t(Text::StrokeCount),
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Self::VALUES.iter().copied().find(|s| s.ident() == value)
+    }
+}
+
+/// Which kanji results are shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum KanjiFilter {
+    #[default]
+    All,
+    /// Kanji taught in elementary school.
+    Kyouiku,
+    /// Kanji in the jōyō list.
+    Joyo,
+    /// Kanji which are part of the JLPT.
+    Jlpt,
+}
+
+impl KanjiFilter {
+    pub(crate) const VALUES: &'static [KanjiFilter] = &[
+        KanjiFilter::All,
+        KanjiFilter::Kyouiku,
+        KanjiFilter::Joyo,
+        KanjiFilter::Jlpt,
+    ];
+
+    pub(crate) fn ident(&self) -> &'static str {
+        match self {
+            KanjiFilter::All => "all",
+            KanjiFilter::Kyouiku => "kyouiku",
+            KanjiFilter::Joyo => "joyo",
+            KanjiFilter::Jlpt => "jlpt",
+        }
+    }
+
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            KanjiFilter::All => t(Text::AllKanji),
+            KanjiFilter::Kyouiku => t(Text::KyouikuKanji),
+            KanjiFilter::Joyo => t(Text::JoyoKanji),
+            KanjiFilter::Jlpt => t(Text::JlptKanji),
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Self::VALUES.iter().copied().find(|s| s.ident() == value)
+    }
+}
+
+/// How characters in the analyzed text are interacted with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Interaction {
+    /// Clicking a character analyzes from it.
+    #[default]
+    Click,
+    /// Pressing and holding a character analyzes from it.
+    LongPress,
+    /// Dragging over characters analyzes exactly the selected range.
+    Select,
+}
+
+impl Interaction {
+    pub(crate) const VALUES: &'static [Interaction] = &[
+        Interaction::Click,
+        Interaction::LongPress,
+        Interaction::Select,
+    ];
+
+    pub(crate) fn ident(&self) -> &'static str {
+        match self {
+            Interaction::Click => "click",
+            Interaction::LongPress => "long-press",
+            Interaction::Select => "select",
+        }
+    }
+
+    pub(crate) fn title(&self) -> &'static str {
+        match self {
+            Interaction::Click => t(Text::ClickToAnalyze),
+            Interaction::LongPress => t(Text::LongPressToAnalyze),
+            Interaction::Select => t(Text::DragToSelect),
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        Self::VALUES.iter().copied().find(|s| s.ident() == value)
+    }
+}
+
 /// The current tab.
 #[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
 pub enum Tab {
@@ -17,6 +156,7 @@ pub enum Tab {
     Phrases,
     Names,
     Kanji,
+    Examples,
     Settings,
 }
 
@@ -25,11 +165,32 @@ pub(crate) struct Query {
     pub(crate) text: Rc<str>,
     pub(crate) translation: Option<String>,
     pub(crate) analyze_at: Option<usize>,
+    /// Byte offset where analysis stops, when an explicit range is analyzed.
+    pub(crate) analyze_end: Option<usize>,
     pub(crate) index: usize,
     pub(crate) mode: Mode,
     pub(crate) capture_clipboard: bool,
     pub(crate) embed: bool,
     pub(crate) tab: Tab,
+    /// Only show phrases belonging to the given part of speech group.
+    pub(crate) pos: Option<PartOfSpeechGroup>,
+    /// Group phrases which share kanji.
+    pub(crate) group: bool,
+    /// List results as compact summaries, loading full entries on demand.
+    pub(crate) compact: bool,
+    /// Highlight hard words in the analyzed text.
+    pub(crate) hard: bool,
+    /// Highlight words in the analyzed text by how well they are known.
+    pub(crate) status: bool,
+    /// Show the analyzed text stacked with its translation, like dual
+    /// subtitles.
+    pub(crate) subtitles: bool,
+    /// How kanji are sorted.
+    pub(crate) kanji_sort: KanjiSort,
+    /// Which kanji are shown.
+    pub(crate) kanji_filter: KanjiFilter,
+    /// How the analyzed text is interacted with.
+    pub(crate) interaction: Interaction,
 }
 
 impl Query {
@@ -38,14 +199,24 @@ impl Query {
         self.text = text;
         self.translation = translation;
         self.analyze_at = None;
+        self.analyze_end = None;
         self.index = 0;
+        self.pos = None;
+    }
+
+    /// A short link to the query, which the service expands into the full
+    /// query when it's opened.
+    pub(crate) fn to_short_href(&self) -> Option<String> {
+        let query = self.serialize(true);
+        let token = link::encode(query.iter().map(|(key, value)| (*key, value.as_ref())));
+        base::url(&format!("/s/{token}"))
     }
 
     pub(crate) fn to_href(&self, no_embed: bool) -> Option<String> {
         let href = window()?.location().href().ok()?;
         let query = self.serialize(no_embed);
         let query = serde_urlencoded::to_string(query).ok()?;
-        let url = Url::new_with_base("/", &href).ok()?;
+        let url = Url::new_with_base(&base::href("/"), &href).ok()?;
         url.set_search(&query);
         Some(url.href())
     }
@@ -59,10 +230,12 @@ impl Query {
         }
 
         self.analyze_at = Some(len);
+        self.analyze_end = None;
     }
 
     pub(crate) fn deserialize(raw: Vec<(String, String)>) -> (Self, Option<usize>) {
         let mut analyze_at = None;
+        let mut analyze_end = None;
         let mut analyze_at_char = None;
         let mut text = String::new();
         let mut translation = None;
@@ -70,8 +243,27 @@ impl Query {
         let mut capture_clipboard = false;
         let mut embed = false;
         let mut tab = Tab::default();
+        let mut pos = None;
+        let mut group = false;
+        let mut compact = false;
+        let mut hard = false;
+        let mut status = false;
+        let mut subtitles = false;
+        let mut kanji_sort = KanjiSort::default();
+        let mut kanji_filter = KanjiFilter::default();
+        let mut interaction = Interaction::default();
         let mut index = 0;
 
+        // A compact token expands into the parameters it was encoded from,
+        // which are applied where it appears among the other parameters.
+        let raw = raw.into_iter().flat_map(|(key, value)| match key.as_str() {
+            "s" => link::decode(&value).unwrap_or_else(|error| {
+                log::warn!("Invalid link: {error}");
+                Vec::new()
+            }),
+            _ => vec![(key, value)],
+        });
+
         for (key, value) in raw {
             match key.as_str() {
                 "q" => {
@@ -82,9 +274,10 @@ impl Query {
                 }
                 "mode" => {
                     mode = match value.as_str() {
+                        "none" => Mode::Unfiltered,
                         "hiragana" => Mode::Hiragana,
                         "katakana" => Mode::Katakana,
-                        _ => Mode::Unfiltered,
+                        _ => Mode::Auto,
                     };
                 }
                 "cb" => {
@@ -98,15 +291,48 @@ impl Query {
                         "phrases" => Tab::Phrases,
                         "names" => Tab::Names,
                         "kanji" => Tab::Kanji,
+                        "examples" => Tab::Examples,
                         "settings" => Tab::Settings,
                         _ => Tab::default(),
                     };
                 }
+                "pos" => {
+                    pos = PartOfSpeechGroup::parse_keyword(&value);
+                }
+                "group" => {
+                    group = value == "yes";
+                }
+                "compact" => {
+                    compact = value == "yes";
+                }
+                "hard" => {
+                    hard = value == "yes";
+                }
+                "status" => {
+                    status = value == "yes";
+                }
+                "subs" => {
+                    subtitles = value == "yes";
+                }
+                "kanji-sort" => {
+                    kanji_sort = KanjiSort::parse(&value).unwrap_or_default();
+                }
+                "kanji-filter" => {
+                    kanji_filter = KanjiFilter::parse(&value).unwrap_or_default();
+                }
+                "interaction" => {
+                    interaction = Interaction::parse(&value).unwrap_or_default();
+                }
                 "at" => {
                     if let Ok(i) = value.parse() {
                         analyze_at = Some(i);
                     }
                 }
+                "end" => {
+                    if let Ok(i) = value.parse() {
+                        analyze_end = Some(i);
+                    }
+                }
                 "analyze_at_char" => {
                     if let Ok(i) = value.parse() {
                         analyze_at_char = Some(i);
@@ -128,7 +354,17 @@ impl Query {
             capture_clipboard,
             embed,
             tab,
+            pos,
+            group,
+            compact,
+            hard,
+            status,
+            subtitles,
+            kanji_sort,
+            kanji_filter,
+            interaction,
             analyze_at,
+            analyze_end,
             index,
         };
 
@@ -147,7 +383,10 @@ impl Query {
         }
 
         match self.mode {
-            Mode::Unfiltered => {}
+            Mode::Auto => {}
+            Mode::Unfiltered => {
+                out.push(("mode", Cow::Borrowed("none")));
+            }
             Mode::Hiragana => {
                 out.push(("mode", Cow::Borrowed("hiragana")));
             }
@@ -168,6 +407,10 @@ impl Query {
             out.push(("at", Cow::Owned(analyze_at.to_string())));
         }
 
+        if let Some(analyze_end) = self.analyze_end {
+            out.push(("end", Cow::Owned(analyze_end.to_string())));
+        }
+
         match self.tab {
             Tab::Phrases => {}
             Tab::Names => {
@@ -176,11 +419,50 @@ impl Query {
             Tab::Kanji => {
                 out.push(("tab", Cow::Borrowed("kanji")));
             }
+            Tab::Examples => {
+                out.push(("tab", Cow::Borrowed("examples")));
+            }
             Tab::Settings => {
                 out.push(("tab", Cow::Borrowed("settings")));
             }
         }
 
+        if self.group {
+            out.push(("group", Cow::Borrowed("yes")));
+        }
+
+        if self.compact {
+            out.push(("compact", Cow::Borrowed("yes")));
+        }
+
+        if self.hard {
+            out.push(("hard", Cow::Borrowed("yes")));
+        }
+
+        if self.status {
+            out.push(("status", Cow::Borrowed("yes")));
+        }
+
+        if self.subtitles {
+            out.push(("subs", Cow::Borrowed("yes")));
+        }
+
+        if let Some(pos) = self.pos {
+            out.push(("pos", Cow::Borrowed(pos.ident())));
+        }
+
+        if self.kanji_sort != KanjiSort::default() {
+            out.push(("kanji-sort", Cow::Borrowed(self.kanji_sort.ident())));
+        }
+
+        if self.kanji_filter != KanjiFilter::default() {
+            out.push(("kanji-filter", Cow::Borrowed(self.kanji_filter.ident())));
+        }
+
+        if self.interaction != Interaction::default() {
+            out.push(("interaction", Cow::Borrowed(self.interaction.ident())));
+        }
+
         if self.index > 0 {
             out.push(("index", Cow::Owned(self.index.to_string())));
         }
@@ -13,6 +13,7 @@ use web_sys::js_sys::{ArrayBuffer, Uint8Array};
 use web_sys::{window, BinaryType, CloseEvent, ErrorEvent, MessageEvent, WebSocket};
 use yew::{Callback, Component, Context};
 
+use crate::base;
 use crate::error::{Error, Result};
 
 const INITIAL_TIMEOUT: u32 = 250;
@@ -260,9 +261,7 @@ where
 
                         if pending.serial == response.serial {
                             if let Some(error) = response.error {
-                                pending
-                                    .callback
-                                    .emit(Err(Error::from(anyhow!("{}", error))));
+                                pending.callback.emit(Err(Error::from(error)));
                             } else {
                                 pending.callback.emit(Ok(response.body));
                             }
@@ -306,9 +305,9 @@ where
 
     /// Attempt to establish a connection.
     pub(crate) fn connect(&mut self, ctx: &Context<C>) -> Result<()> {
-        let window = window().ok_or("no window")?;
-        let port = window.location().port()?;
-        let url = format!("ws://127.0.0.1:{port}/ws");
+        // Connect to the host the page was served from, so that the
+        // dictionary can be used from other machines when it's shared.
+        let url = base::ws().ok_or("no window")?;
 
         let ws = match WebSocket::new(&url) {
             Ok(ws) => ws,
@@ -461,6 +460,7 @@ impl Handle {
         let index = requests.insert(pending);
 
         self.shared.onmessage.emit(api::ClientRequestEnvelope {
+            version: api::compat::PROTOCOL_VERSION,
             kind: T::KIND.to_string(),
             index,
             serial,
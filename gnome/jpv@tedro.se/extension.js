@@ -10,8 +10,10 @@ import * as PopupMenu from 'resource:///org/gnome/shell/ui/popupMenu.js';
 
 import * as Main from 'resource:///org/gnome/shell/ui/main.js';
 
-// List of atoms that we care about.
+// List of atoms that we care about, in order of preference. HTML is
+// preferred since readings in ruby annotations can be told apart in it.
 const ATOMS = [
+    'text/html',
     "UTF8_STRING",
     "STRING",
     'text/plain;charset=utf-8',
@@ -36,6 +38,11 @@ const JapaneseDictionaryInterface = `
             <arg type="s" direction="in" name="mimetype" />
             <arg type="ay" direction="in" name="data" />
         </method>
+        <method name="SendClipboardDataFrom">
+            <arg type="s" direction="in" name="mimetype" />
+            <arg type="ay" direction="in" name="data" />
+            <arg type="s" direction="in" name="source" />
+        </method>
     </interface>
 </node>
 `;
@@ -43,6 +50,18 @@ const JapaneseDictionaryProxy = Gio.DBusProxy.makeProxyWrapper(JapaneseDictionar
 
 const CLIPBOARD_TYPE = St.ClipboardType.CLIPBOARD;
 
+// The class of the focused window, which is the application that the
+// clipboard was most likely copied from.
+function focusedApplication() {
+    const window = Shell.Global.get().get_display().get_focus_window();
+
+    if (!window) {
+        return '';
+    }
+
+    return window.get_wm_class() || '';
+}
+
 class ClipboardEntry {
     constructor (mimeType, data) {
         this.mimeType = mimeType;
@@ -161,7 +180,9 @@ class Indicator extends PanelMenu.Button {
             const result = await this.#getClipboardContent();
 
             if (result) {
-                await new Promise((resolve, reject) => this.extension.proxy.SendClipboardDataRemote(result.mimeType, result.data, (_response, error) => {
+                const source = focusedApplication();
+
+                await new Promise((resolve, reject) => this.extension.proxy.SendClipboardDataFromRemote(result.mimeType, result.data, source, (_response, error) => {
                     if (error) {
                         reject(error);
                     } else {